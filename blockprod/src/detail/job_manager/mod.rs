@@ -257,6 +257,7 @@ impl JobManager {
                                         "Chainstate subscriber failed to send new tip",
                                     );
                                 }
+                                ChainstateEvent::Reorg { .. } => {}
                             },
                         );
 
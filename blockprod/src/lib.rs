@@ -203,8 +203,10 @@ mod tests {
 
         let mempool = mempool::make_mempool(
             Arc::clone(&chain_config),
+            Default::default(),
             subsystem::Handle::clone(&chainstate),
             Default::default(),
+            None,
         );
         let mempool = manager.add_subsystem_with_custom_eventloop("mempool", {
             move |call, shutdn| mempool.run(call, shutdn)
@@ -220,6 +222,7 @@ mod tests {
             mempool.clone(),
             Default::default(),
             PeerDbStorageImpl::new(InMemory::new()).unwrap(),
+            p2p::node_key::NodeKeypair::generate(),
         )
         .expect("P2p initialization was successful");
 
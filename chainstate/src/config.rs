@@ -31,6 +31,7 @@ make_config_setting!(
     )
 );
 make_config_setting!(TxIndexEnabled, bool, false);
+make_config_setting!(AddressIndexEnabled, bool, false);
 make_config_setting!(MaxTipAge, Duration, Duration::from_secs(60 * 60 * 24));
 
 /// The chainstate subsystem configuration.
@@ -44,6 +45,8 @@ pub struct ChainstateConfig {
     /// (see bootstrap import function for more information)
     pub min_max_bootstrap_import_buffer_sizes: MinMaxBootstrapImportBufferSizes,
     pub tx_index_enabled: TxIndexEnabled,
+    /// Maintain the address and the spent-by indexes.
+    pub address_index_enabled: AddressIndexEnabled,
     /// The initial block download is finished if the difference between the current time and the
     /// tip time is less than this value.
     pub max_tip_age: MaxTipAge,
@@ -77,4 +80,9 @@ impl ChainstateConfig {
         self.tx_index_enabled = tx_index_enabled.into();
         self
     }
+
+    pub fn with_whether_address_index_enabled(mut self, address_index_enabled: bool) -> Self {
+        self.address_index_enabled = address_index_enabled.into();
+        self
+    }
 }
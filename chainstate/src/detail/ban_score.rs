@@ -31,6 +31,7 @@ use super::{
     BlockSizeError, CheckBlockError, CheckBlockTransactionsError, OrphanCheckError, TxIndexError,
 };
 use crate::{BlockError, ChainstateError};
+use chainstate_storage::token_stats::TokenStatsError;
 use chainstate_types::GetAncestorError;
 
 // TODO: use a ban_score macro in a form similar to thiserror::Error in order to define the ban score
@@ -58,9 +59,11 @@ impl BanScore for BlockError {
             BlockError::BlockProofCalculationError(_) => 100,
             BlockError::TransactionVerifierError(err) => err.ban_score(),
             BlockError::TxIndexConfigError => 0,
+            BlockError::AddressIndexConfigError => 0,
             BlockError::TxIndexConstructionError(_) => 100,
             BlockError::PoSAccountingError(err) => err.ban_score(),
             BlockError::EpochSealError(err) => err.ban_score(),
+            BlockError::TokenStatsError(err) => err.ban_score(),
             BlockError::BlockHeightTooBig(_) => 0,
 
             BlockError::BestBlockIdQueryError(_) => 0,
@@ -106,6 +109,7 @@ impl BanScore for ConnectTransactionError {
             // Even though this is an invariant error, it stems from a block reward that doesn't exist
             ConnectTransactionError::InvariantErrorHeaderCouldNotBeLoaded(_) => 100,
             ConnectTransactionError::FailedToAddAllFeesOfBlock(_) => 100,
+            ConnectTransactionError::TooManySigOpsInBlock(_, _, _) => 100,
             ConnectTransactionError::RewardAdditionError(_) => 100,
             ConnectTransactionError::TimeLockViolation(_) => 100,
             ConnectTransactionError::MissingBlockUndo(_) => 0,
@@ -123,6 +127,9 @@ impl BanScore for ConnectTransactionError {
             ConnectTransactionError::BurnAmountSumError(_) => 100,
             ConnectTransactionError::AttemptToSpendBurnedAmount => 100,
             ConnectTransactionError::MissingPoSAccountingUndo(_) => 0,
+            ConnectTransactionError::MissingTokensUndo(_) => 0,
+            ConnectTransactionError::KeyKindNotActivated(_) => 100,
+            ConnectTransactionError::TokensBlockUndoError(_) => 100,
             ConnectTransactionError::PoSAccountingError(err) => err.ban_score(),
             ConnectTransactionError::AccountingBlockUndoError(_) => 100,
             ConnectTransactionError::SpendStakeError(_) => 100,
@@ -196,6 +203,7 @@ impl BanScore for TransactionVerifierStorageError {
             TransactionVerifierStorageError::TransactionIndexDisabled => 0,
             TransactionVerifierStorageError::PoSAccountingError(err) => err.ban_score(),
             TransactionVerifierStorageError::AccountingBlockUndoError(_) => 100,
+            TransactionVerifierStorageError::TokensBlockUndoError(_) => 100,
         }
     }
 }
@@ -281,6 +289,16 @@ impl BanScore for TokensError {
             TokensError::TokensInBlockReward => 100,
             TokensError::InvariantBrokenUndoIssuanceOnNonexistentToken(_) => 100,
             TokensError::InvariantBrokenRegisterIssuanceWithDuplicateId(_) => 100,
+            TokensError::TokenNotFound(_) => 100,
+            TokensError::TokenSupplyNotControlled(_) => 100,
+            TokensError::TokenSupplyLocked(_) => 100,
+            TokensError::TokenFrozen(_) => 100,
+            TokensError::TokenNotFrozen(_) => 100,
+            TokensError::TokenAuthorityNotProven(_, _) => 100,
+            TokensError::TokenSupplyOverflow(_) => 100,
+            TokensError::InvariantBrokenUndoSupplyChange(_) => 100,
+            TokensError::InvariantBrokenTokensUndoNotUsedUp(_) => 100,
+            TokensError::TokenDataNotActivated(_, _) => 100,
         }
     }
 }
@@ -383,6 +401,7 @@ impl BanScore for BlockSignatureError {
             BlockSignatureError::WrongOutputType(_) => 100,
             BlockSignatureError::WrongDestination(_) => 100,
             BlockSignatureError::BadSignature(_) => 100,
+            BlockSignatureError::KeyKindNotActivated(_) => 100,
         }
     }
 }
@@ -485,4 +504,18 @@ impl BanScore for EpochSealError {
     }
 }
 
+impl BanScore for TokenStatsError {
+    fn ban_score(&self) -> u32 {
+        match self {
+            TokenStatsError::StorageError(_) => 0,
+            TokenStatsError::BlockUndoNotFound(_) => 0,
+            TokenStatsError::TxUndoNotFound(_) => 0,
+            TokenStatsError::IssuanceTokenIdNotFound(_) => 0,
+            TokenStatsError::StatsNotFound(_) => 0,
+            // The block moves more tokens than exist or overflows the amounts
+            TokenStatsError::InconsistentAmounts(_) => 100,
+        }
+    }
+}
+
 // TODO: tests in which we simulate every possible case and test the score
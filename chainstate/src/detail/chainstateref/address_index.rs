@@ -0,0 +1,153 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use chainstate_storage::BlockchainStorageWrite;
+use common::{
+    chain::{
+        signature::Signable, Block, Destination, GenBlock, OutPointSourceId, Spender, TxInput,
+        TxOutput, UtxoOutPoint,
+    },
+    primitives::{id::WithId, BlockHeight, Id, Idable},
+};
+use utils::tap_error_log::LogError;
+
+use crate::BlockError;
+
+/// The destination an output is indexed under, if any
+fn output_destination(output: &TxOutput) -> Option<&Destination> {
+    match output {
+        TxOutput::Transfer(_, destination)
+        | TxOutput::LockThenTransfer(_, destination, _)
+        | TxOutput::ProduceBlockFromStake(destination, _)
+        | TxOutput::CreateDelegationId(destination, _) => Some(destination),
+        TxOutput::CreateStakePool(_, data) => Some(data.decommission_key()),
+        TxOutput::Burn(_) | TxOutput::DelegateStaking(_, _) => None,
+    }
+}
+
+fn index_outputs<S: BlockchainStorageWrite>(
+    db_tx: &mut S,
+    source_id: OutPointSourceId,
+    outputs: &[TxOutput],
+    height: BlockHeight,
+) -> Result<(), BlockError> {
+    for (index, output) in outputs.iter().enumerate() {
+        if let Some(destination) = output_destination(output) {
+            let outpoint = UtxoOutPoint::new(source_id.clone(), index as u32);
+            db_tx.set_address_outpoint(destination, &outpoint, height).log_err()?;
+        }
+    }
+    Ok(())
+}
+
+fn unindex_outputs<S: BlockchainStorageWrite>(
+    db_tx: &mut S,
+    source_id: OutPointSourceId,
+    outputs: &[TxOutput],
+) -> Result<(), BlockError> {
+    for (index, output) in outputs.iter().enumerate() {
+        if let Some(destination) = output_destination(output) {
+            let outpoint = UtxoOutPoint::new(source_id.clone(), index as u32);
+            db_tx.del_address_outpoint(destination, &outpoint).log_err()?;
+        }
+    }
+    Ok(())
+}
+
+fn index_inputs<S: BlockchainStorageWrite>(
+    db_tx: &mut S,
+    inputs: &[TxInput],
+    spender: Spender,
+) -> Result<(), BlockError> {
+    for input in inputs {
+        match input {
+            TxInput::Utxo(outpoint) => db_tx.set_spent_by(outpoint, &spender).log_err()?,
+            TxInput::Account(_) => {}
+        }
+    }
+    Ok(())
+}
+
+fn unindex_inputs<S: BlockchainStorageWrite>(
+    db_tx: &mut S,
+    inputs: &[TxInput],
+) -> Result<(), BlockError> {
+    for input in inputs {
+        match input {
+            TxInput::Utxo(outpoint) => db_tx.del_spent_by(outpoint).log_err()?,
+            TxInput::Account(_) => {}
+        }
+    }
+    Ok(())
+}
+
+/// Record the outputs of the genesis block in the address index
+pub fn connect_genesis<S: BlockchainStorageWrite>(
+    db_tx: &mut S,
+    genesis_id: Id<GenBlock>,
+    outputs: &[TxOutput],
+) -> Result<(), BlockError> {
+    index_outputs(db_tx, genesis_id.into(), outputs, BlockHeight::zero())
+}
+
+/// Record the outputs created and the outputs spent by a block that became part of the mainchain
+pub fn connect_block<S: BlockchainStorageWrite>(
+    db_tx: &mut S,
+    block: &WithId<Block>,
+    height: BlockHeight,
+) -> Result<(), BlockError> {
+    let block_id = block.get_id();
+
+    let reward = block.block_reward_transactable();
+    if let Some(inputs) = reward.inputs() {
+        index_inputs(db_tx, inputs, block_id.into())?;
+    }
+    index_outputs(
+        db_tx,
+        block_id.into(),
+        block.block_reward().outputs(),
+        height,
+    )?;
+
+    for tx in block.transactions() {
+        let tx_id = tx.transaction().get_id();
+        index_inputs(db_tx, tx.inputs(), tx_id.into())?;
+        index_outputs(db_tx, tx_id.into(), tx.outputs(), height)?;
+    }
+
+    Ok(())
+}
+
+/// Revert the changes made by `connect_block` for a block that is removed from the mainchain
+pub fn disconnect_block<S: BlockchainStorageWrite>(
+    db_tx: &mut S,
+    block: &WithId<Block>,
+) -> Result<(), BlockError> {
+    let block_id = block.get_id();
+
+    for tx in block.transactions().iter().rev() {
+        let tx_id = tx.transaction().get_id();
+        unindex_outputs(db_tx, tx_id.into(), tx.outputs())?;
+        unindex_inputs(db_tx, tx.inputs())?;
+    }
+
+    let reward = block.block_reward_transactable();
+    unindex_outputs(db_tx, block_id.into(), block.block_reward().outputs())?;
+    if let Some(inputs) = reward.inputs() {
+        unindex_inputs(db_tx, inputs)?;
+    }
+
+    Ok(())
+}
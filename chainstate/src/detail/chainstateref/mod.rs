@@ -13,11 +13,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use thiserror::Error;
 
 use chainstate_storage::{
-    BlockchainStorageRead, BlockchainStorageWrite, TipStorageTag, TransactionRw,
+    token_stats, BlockchainStorageRead, BlockchainStorageWrite, TipStorageTag, TransactionRw,
 };
 use chainstate_types::{
     block_index_ancestor_getter, get_skip_height, BlockIndex, BlockIndexHandle, BlockStatus,
@@ -32,9 +32,9 @@ use common::{
         },
         config::EpochIndex,
         tokens::TokenAuxiliaryData,
-        tokens::{get_tokens_issuance_count, TokenId},
-        AccountNonce, AccountType, Block, ChainConfig, GenBlock, GenBlockId, OutPointSourceId,
-        Transaction, TxOutput, UtxoOutPoint,
+        tokens::{get_tokens_issuance_count, TokenId, TokenStats},
+        AccountNonce, AccountType, Block, ChainConfig, Destination, GenBlock, GenBlockId,
+        OutPointSourceId, SpendablePosition, Spender, Transaction, TxOutput, UtxoOutPoint,
     },
     primitives::{id::WithId, BlockDistance, BlockHeight, Id, Idable},
     time_getter::TimeGetter,
@@ -58,6 +58,7 @@ use super::{
     BlockSizeError, CheckBlockError, CheckBlockTransactionsError,
 };
 
+pub mod address_index;
 mod epoch_seal;
 pub use epoch_seal::EpochSealError;
 mod in_memory_reorg;
@@ -193,6 +194,49 @@ impl<'a, S: BlockchainStorageRead, V: TransactionVerificationStrategy> Chainstat
         self.db_tx.get_mainchain_tx_index(tx_id).map_err(PropertyQueryError::from)
     }
 
+    pub fn get_mainchain_tx(
+        &self,
+        tx_id: &Id<Transaction>,
+    ) -> Result<Option<Transaction>, PropertyQueryError> {
+        ensure!(
+            *self.chainstate_config.tx_index_enabled,
+            PropertyQueryError::TxIndexDisabled
+        );
+        let tx_index = match self.db_tx.get_mainchain_tx_index(&(*tx_id).into())? {
+            Some(tx_index) => tx_index,
+            None => return Ok(None),
+        };
+        match tx_index.position() {
+            SpendablePosition::Transaction(position) => self
+                .db_tx
+                .get_mainchain_tx_by_position(position)
+                .map_err(PropertyQueryError::from),
+            SpendablePosition::BlockReward(_) => Ok(None),
+        }
+    }
+
+    pub fn get_address_outpoints(
+        &self,
+        destination: &Destination,
+    ) -> Result<BTreeMap<UtxoOutPoint, BlockHeight>, PropertyQueryError> {
+        ensure!(
+            *self.chainstate_config.address_index_enabled,
+            PropertyQueryError::AddressIndexDisabled
+        );
+        self.db_tx.get_address_outpoints(destination).map_err(PropertyQueryError::from)
+    }
+
+    pub fn get_spent_by(
+        &self,
+        outpoint: &UtxoOutPoint,
+    ) -> Result<Option<Spender>, PropertyQueryError> {
+        ensure!(
+            *self.chainstate_config.address_index_enabled,
+            PropertyQueryError::AddressIndexDisabled
+        );
+        self.db_tx.get_spent_by(outpoint).map_err(PropertyQueryError::from)
+    }
+
     pub fn get_block_id_by_height(
         &self,
         height: &BlockHeight,
@@ -278,6 +322,32 @@ impl<'a, S: BlockchainStorageRead, V: TransactionVerificationStrategy> Chainstat
         }
     }
 
+    /// Collect the ids of the blocks from `block_index` (inclusive) down to its ancestor
+    /// `ancestor_id` (exclusive), ordered from the highest block to the lowest one.
+    pub fn get_block_ids_down_to_ancestor(
+        &self,
+        block_index: &GenBlockIndex,
+        ancestor_id: &Id<GenBlock>,
+    ) -> Result<Vec<Id<Block>>, PropertyQueryError> {
+        let mut result = Vec::new();
+        let mut current = block_index.clone();
+        while current.block_id() != *ancestor_id {
+            match current {
+                GenBlockIndex::Block(block_index) => {
+                    result.push(*block_index.block_id());
+                    current = self.get_previous_block_index(&block_index).log_err()?;
+                }
+                GenBlockIndex::Genesis(_) => {
+                    return Err(PropertyQueryError::NotAnAncestor(
+                        *ancestor_id,
+                        block_index.block_id(),
+                    ))
+                }
+            }
+        }
+        Ok(result)
+    }
+
     fn last_common_ancestor_in_main_chain(
         &self,
         block_index: &GenBlockIndex,
@@ -305,6 +375,21 @@ impl<'a, S: BlockchainStorageRead, V: TransactionVerificationStrategy> Chainstat
         self.db_tx.get_token_id(tx_id).map_err(PropertyQueryError::from)
     }
 
+    pub fn get_token_stats(
+        &self,
+        token_id: &TokenId,
+    ) -> Result<Option<TokenStats>, PropertyQueryError> {
+        self.db_tx.get_token_stats(token_id).map_err(PropertyQueryError::from)
+    }
+
+    pub fn get_token_ids(
+        &self,
+        start_after: Option<TokenId>,
+        count: usize,
+    ) -> Result<Vec<TokenId>, PropertyQueryError> {
+        self.db_tx.get_token_ids(start_after, count).map_err(PropertyQueryError::from)
+    }
+
     pub fn get_header_from_height(
         &self,
         height: &BlockHeight,
@@ -1036,6 +1121,17 @@ impl<'a, S: BlockchainStorageWrite, V: TransactionVerificationStrategy> Chainsta
 
         self.connect_transactions(new_tip_block_index, new_tip).log_err()?;
 
+        if *self.chainstate_config.address_index_enabled {
+            address_index::connect_block(
+                &mut self.db_tx,
+                new_tip,
+                new_tip_block_index.block_height(),
+            )?;
+        }
+
+        token_stats::connect_block(&mut self.db_tx, new_tip, new_tip_block_index.block_height())
+            .log_err()?;
+
         self.db_tx
             .set_block_id_at_height(
                 &new_tip_block_index.block_height(),
@@ -1079,9 +1175,18 @@ impl<'a, S: BlockchainStorageWrite, V: TransactionVerificationStrategy> Chainsta
             .get_block_index(&best_block_id)
             .expect("Database error on retrieving current best block index")
             .expect("Best block index not present in the database");
-        let block = self.get_block_from_index(&block_index).log_err()?.expect("Inconsistent DB");
+        let block: WithId<Block> = self
+            .get_block_from_index(&block_index)
+            .log_err()?
+            .expect("Inconsistent DB")
+            .into();
+        // Token stats are reverted with the help of the undo data, which is removed with the transactions
+        token_stats::disconnect_block(&mut self.db_tx, &block).log_err()?;
         // Disconnect transactions
-        self.disconnect_transactions(&block.into()).log_err()?;
+        self.disconnect_transactions(&block).log_err()?;
+        if *self.chainstate_config.address_index_enabled {
+            address_index::disconnect_block(&mut self.db_tx, &block)?;
+        }
         self.db_tx.set_best_block_id(block_index.prev_block_id()).log_err()?;
         // Disconnect block
         self.db_tx.del_block_id_at_height(&block_index.block_height()).log_err()?;
@@ -27,7 +27,7 @@ use chainstate_storage::{BlockchainStorageRead, BlockchainStorageWrite, TipStora
 use chainstate_types::{storage_result, GenBlockIndex};
 use common::{
     chain::{
-        tokens::{TokenAuxiliaryData, TokenId},
+        tokens::{TokenAuxiliaryData, TokenId, TokensBlockUndo},
         AccountNonce, AccountType, Block, ChainConfig, DelegationId, GenBlock, GenBlockId,
         OutPointSourceId, PoolId, Transaction,
     },
@@ -86,6 +86,13 @@ impl<'a, S: BlockchainStorageRead, V: TransactionVerificationStrategy> Transacti
             .map_err(TransactionVerifierStorageError::from)
     }
 
+    fn get_tokens_undo(
+        &self,
+        id: Id<Block>,
+    ) -> Result<Option<TokensBlockUndo>, TransactionVerifierStorageError> {
+        self.db_tx.get_tokens_undo(id).map_err(TransactionVerifierStorageError::from)
+    }
+
     fn get_account_nonce_count(
         &self,
         account: AccountType,
@@ -271,6 +278,39 @@ impl<'a, S: BlockchainStorageWrite, V: TransactionVerificationStrategy>
         }
     }
 
+    fn set_tokens_undo_data(
+        &mut self,
+        tx_source: TransactionSource,
+        undo: &TokensBlockUndo,
+    ) -> Result<(), TransactionVerifierStorageError> {
+        // TODO: check tx_source at compile-time (mintlayer/mintlayer-core#633)
+        match tx_source {
+            TransactionSource::Chain(id) => self
+                .db_tx
+                .set_tokens_undo_data(id, undo)
+                .map_err(TransactionVerifierStorageError::from),
+            TransactionSource::Mempool => {
+                panic!("Flushing mempool info into the storage is forbidden")
+            }
+        }
+    }
+
+    fn del_tokens_undo_data(
+        &mut self,
+        tx_source: TransactionSource,
+    ) -> Result<(), TransactionVerifierStorageError> {
+        // TODO: check tx_source at compile-time (mintlayer/mintlayer-core#633)
+        match tx_source {
+            TransactionSource::Chain(id) => self
+                .db_tx
+                .del_tokens_undo_data(id)
+                .map_err(TransactionVerifierStorageError::from),
+            TransactionSource::Mempool => {
+                panic!("Flushing mempool info into the storage is forbidden")
+            }
+        }
+    }
+
     fn apply_accounting_delta(
         &mut self,
         tx_source: TransactionSource,
@@ -21,6 +21,7 @@ use super::{
         storage::TransactionVerifierStorageError,
     },
 };
+use chainstate_storage::token_stats::TokenStatsError;
 use chainstate_types::{GetAncestorError, PropertyQueryError};
 use common::{
     chain::{
@@ -63,12 +64,16 @@ pub enum BlockError {
     TransactionVerifierError(#[from] TransactionVerifierStorageError),
     #[error("Changing tx index state is not implemented for existing DB")]
     TxIndexConfigError,
+    #[error("Changing address index state is not implemented for existing DB")]
+    AddressIndexConfigError,
     #[error("Transaction index construction error: {0}")]
     TxIndexConstructionError(#[from] TxIndexError),
     #[error("PoS accounting error: {0}")]
     PoSAccountingError(#[from] pos_accounting::Error),
     #[error("Error during sealing an epoch: {0}")]
     EpochSealError(#[from] EpochSealError),
+    #[error("Error during updating token stats: {0}")]
+    TokenStatsError(#[from] TokenStatsError),
     #[error("The block height {0} is too big")]
     BlockHeightTooBig(BlockHeight),
 
@@ -16,9 +16,10 @@
 use serde::{Deserialize, Serialize};
 
 use common::{
-    chain::{block::timestamp::BlockTimestamp, GenBlock},
+    chain::{block::timestamp::BlockTimestamp, GenBlock, Spender, UtxoOutPoint},
     primitives::{BlockHeight, Id},
 };
+use serialization::{Decode, Encode};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ChainInfo {
@@ -28,3 +29,11 @@ pub struct ChainInfo {
     pub median_time: BlockTimestamp,
     pub is_initial_block_download: bool,
 }
+
+/// An output created for an address in the mainchain, and its spender if it was spent
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct AddressHistoryEntry {
+    pub outpoint: UtxoOutPoint,
+    pub block_height: BlockHeight,
+    pub spent_by: Option<Spender>,
+}
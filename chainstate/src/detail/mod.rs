@@ -27,9 +27,12 @@ mod orphan_blocks;
 
 pub use self::{
     error::*,
-    info::ChainInfo,
+    info::{AddressHistoryEntry, ChainInfo},
     median_time::calculate_median_time_past,
-    tokens::{check_nft_issuance_data, check_tokens_issuance_data, is_rfc3986_valid_symbol},
+    tokens::{
+        check_nft_issuance_data, check_tokens_issuance_data, check_tokens_issuance_v1_data,
+        is_rfc3986_valid_symbol,
+    },
 };
 pub use chainstate_types::Locator;
 pub use error::{
@@ -60,21 +63,21 @@ use chainstate_storage::{
 };
 use chainstate_types::{
     pos_randomness::PoSRandomness, BlockIndex, BlockStatus, BlockValidationStage, EpochData,
-    EpochStorageWrite, PropertyQueryError,
+    EpochStorageWrite, GenBlockIndex, PropertyQueryError,
 };
 use chainstateref::ReorgError;
 use common::{
     chain::{
         block::{signed_block_header::SignedBlockHeader, timestamp::BlockTimestamp},
         config::ChainConfig,
-        Block, TxOutput,
+        Block, GenBlock, TxOutput,
     },
     primitives::{id::WithId, BlockHeight, Id, Idable},
     time_getter::TimeGetter,
 };
 use logging::log;
 use utils::{
-    eventhandler::{EventHandler, EventsController},
+    eventhandler::{EventHandler, EventsController, WeakEventHandler},
     tap_error_log::LogError,
 };
 use utxo::UtxosDB;
@@ -151,6 +154,10 @@ impl<S: BlockchainStorage, V: TransactionVerificationStrategy> Chainstate<S, V>
         self.events_controller.subscribe_to_events(handler);
     }
 
+    pub fn subscribe_to_events_weak(&mut self, handler: WeakEventHandler<ChainstateEvent>) {
+        self.events_controller.subscribe_to_events_weak(handler);
+    }
+
     pub fn new(
         chain_config: Arc<ChainConfig>,
         chainstate_config: ChainstateConfig,
@@ -178,6 +185,9 @@ impl<S: BlockchainStorage, V: TransactionVerificationStrategy> Chainstate<S, V>
         chainstate
             .process_tx_index_enabled_flag()
             .map_err(crate::ChainstateError::from)?;
+        chainstate
+            .process_address_index_enabled_flag()
+            .map_err(crate::ChainstateError::from)?;
 
         if best_block_id.is_none() {
             chainstate
@@ -273,6 +283,86 @@ impl<S: BlockchainStorage, V: TransactionVerificationStrategy> Chainstate<S, V>
         Ok(())
     }
 
+    /// Check that address index state is consistent between DB and config.
+    fn process_address_index_enabled_flag(&mut self) -> Result<(), BlockError> {
+        let mut db_tx = self
+            .chainstate_storage
+            .transaction_rw(None)
+            .map_err(BlockError::from)
+            .log_err()?;
+
+        let address_index_enabled = db_tx
+            .get_is_address_index_enabled()
+            .map_err(BlockError::StorageError)
+            .log_err()?;
+
+        if let Some(address_index_enabled) = address_index_enabled {
+            // TODO: Allow changing state (creating new or deleting existing index).
+            utils::ensure!(
+                *self.chainstate_config.address_index_enabled == address_index_enabled,
+                BlockError::AddressIndexConfigError
+            );
+        } else {
+            db_tx
+                .set_is_address_index_enabled(*self.chainstate_config.address_index_enabled)
+                .map_err(BlockError::StorageError)
+                .log_err()?;
+        }
+
+        db_tx.commit().expect("Set address indexing failed");
+
+        Ok(())
+    }
+
+    /// If the new tip doesn't extend `old_tip_id`, collect the blocks that were disconnected
+    /// from the mainchain and the ones that replaced them.
+    fn make_reorg_event(
+        &self,
+        old_tip_id: &Id<GenBlock>,
+        new_block_index: &BlockIndex,
+    ) -> Result<Option<ChainstateEvent>, PropertyQueryError> {
+        if new_block_index.prev_block_id() == old_tip_id {
+            return Ok(None);
+        }
+
+        let chainstate_ref = self.make_db_tx_ro()?;
+        let old_tip_index = chainstate_ref
+            .get_gen_block_index(old_tip_id)?
+            .ok_or(PropertyQueryError::BestBlockIndexNotFound)?;
+        let new_tip_index = GenBlockIndex::Block(new_block_index.clone());
+        let common_ancestor_id =
+            chainstate_ref.last_common_ancestor(&old_tip_index, &new_tip_index)?.block_id();
+
+        if common_ancestor_id == *old_tip_id {
+            return Ok(None);
+        }
+
+        let disconnected =
+            chainstate_ref.get_block_ids_down_to_ancestor(&old_tip_index, &common_ancestor_id)?;
+        let mut connected =
+            chainstate_ref.get_block_ids_down_to_ancestor(&new_tip_index, &common_ancestor_id)?;
+        connected.reverse();
+
+        Ok(Some(ChainstateEvent::Reorg {
+            disconnected,
+            connected,
+        }))
+    }
+
+    fn broadcast_reorg_event(
+        &self,
+        old_tip_id: &Id<GenBlock>,
+        new_block_index: &Option<BlockIndex>,
+    ) {
+        if let Some(new_block_index) = new_block_index {
+            match self.make_reorg_event(old_tip_id, new_block_index) {
+                Ok(Some(event)) => self.events_controller.broadcast(event),
+                Ok(None) => {}
+                Err(e) => log::error!("Failed to collect reorg event data: {e}"),
+            }
+        }
+    }
+
     fn broadcast_new_tip_event(&self, new_block_index: &Option<BlockIndex>) {
         match new_block_index {
             Some(ref new_block_index) => {
@@ -601,6 +691,12 @@ impl<S: BlockchainStorage, V: TransactionVerificationStrategy> Chainstate<S, V>
     ) -> Result<Option<BlockIndex>, BlockError> {
         let block_id = block.get_id();
 
+        let old_tip_id = self
+            .make_db_tx_ro()
+            .map_err(BlockError::from)?
+            .get_best_block_id()
+            .map_err(BlockError::BestBlockIdQueryError)?;
+
         let result = self.attempt_to_process_block(block, block_source)?;
 
         let new_block_index_after_orphans = self.process_orphans_of(&block_id)?;
@@ -610,6 +706,7 @@ impl<S: BlockchainStorage, V: TransactionVerificationStrategy> Chainstate<S, V>
             None => result,
         };
 
+        self.broadcast_reorg_event(&old_tip_id, &result);
         self.broadcast_new_tip_event(&result);
 
         if let Some(ref bi) = result {
@@ -666,6 +763,10 @@ impl<S: BlockchainStorage, V: TransactionVerificationStrategy> Chainstate<S, V>
                 .log_err()?;
         }
 
+        if *self.chainstate_config.address_index_enabled {
+            chainstateref::address_index::connect_genesis(&mut db_tx, genesis_id, genesis.utxos())?;
+        }
+
         db_tx
             .set_epoch_data(
                 0,
@@ -13,6 +13,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeMap;
+
 use chainstate_storage::BlockchainStorageRead;
 use chainstate_types::{BlockIndex, GenBlockIndex, Locator, PropertyQueryError};
 use common::{
@@ -20,14 +22,19 @@ use common::{
         block::{signed_block_header::SignedBlockHeader, BlockReward},
         tokens::{
             RPCFungibleTokenInfo, RPCNonFungibleTokenInfo, RPCTokenInfo, TokenAuxiliaryData,
-            TokenData, TokenId,
+            TokenData, TokenId, TokenStats,
         },
-        Block, GenBlock, OutPointSourceId, Transaction, TxMainChainIndex, TxOutput,
+        Block, Destination, GenBlock, OutPointSourceId, Spender, Transaction, TxMainChainIndex,
+        TxOutput, UtxoOutPoint,
     },
     primitives::{BlockDistance, BlockHeight, Id, Idable},
 };
+use utxo::{Utxo, UtxosView};
 
-use super::{chainstateref, tx_verification_strategy::TransactionVerificationStrategy};
+use super::{
+    chainstateref, info::AddressHistoryEntry,
+    tx_verification_strategy::TransactionVerificationStrategy,
+};
 
 pub fn locator_tip_distances() -> impl Iterator<Item = BlockDistance> {
     itertools::iterate(0, |&i| std::cmp::max(1, i * 2)).map(BlockDistance::new)
@@ -213,6 +220,56 @@ impl<'a, S: BlockchainStorageRead, V: TransactionVerificationStrategy> Chainstat
         self.chainstate_ref.get_mainchain_tx_index(tx_id)
     }
 
+    pub fn get_mainchain_tx(
+        &self,
+        tx_id: &Id<Transaction>,
+    ) -> Result<Option<Transaction>, PropertyQueryError> {
+        self.chainstate_ref.get_mainchain_tx(tx_id)
+    }
+
+    pub fn get_address_utxos(
+        &self,
+        destination: &Destination,
+    ) -> Result<BTreeMap<UtxoOutPoint, Utxo>, PropertyQueryError> {
+        let utxo_view = self.chainstate_ref.make_utxo_view();
+        let mut result = BTreeMap::new();
+        for outpoint in self.chainstate_ref.get_address_outpoints(destination)?.into_keys() {
+            let utxo = utxo_view.utxo(&outpoint).map_err(PropertyQueryError::from)?;
+            if let Some(utxo) = utxo {
+                result.insert(outpoint, utxo);
+            }
+        }
+        Ok(result)
+    }
+
+    pub fn get_address_history(
+        &self,
+        destination: &Destination,
+    ) -> Result<Vec<AddressHistoryEntry>, PropertyQueryError> {
+        let mut entries = self
+            .chainstate_ref
+            .get_address_outpoints(destination)?
+            .into_iter()
+            .map(|(outpoint, block_height)| {
+                let spent_by = self.chainstate_ref.get_spent_by(&outpoint)?;
+                Ok(AddressHistoryEntry {
+                    outpoint,
+                    block_height,
+                    spent_by,
+                })
+            })
+            .collect::<Result<Vec<_>, PropertyQueryError>>()?;
+        entries.sort_by_key(|entry| entry.block_height);
+        Ok(entries)
+    }
+
+    pub fn get_spending_tx(
+        &self,
+        outpoint: &UtxoOutPoint,
+    ) -> Result<Option<Spender>, PropertyQueryError> {
+        self.chainstate_ref.get_spent_by(outpoint)
+    }
+
     pub fn get_token_info_for_rpc(
         &self,
         token_id: TokenId,
@@ -258,7 +315,26 @@ impl<'a, S: BlockchainStorageRead, V: TransactionVerificationStrategy> Chainstat
                         &nft.metadata,
                     )))
                 }
-                TokenData::TokenTransfer(_) => None,
+                TokenData::TokenIssuanceV1(issuance) => {
+                    let total_issued = token_aux_data
+                        .supply()
+                        .map_or(issuance.amount_to_issue, |supply| supply.total_issued());
+                    Some(RPCTokenInfo::new_fungible(RPCFungibleTokenInfo::new(
+                        token_id,
+                        token_aux_data.issuance_tx().get_id(),
+                        token_aux_data.issuance_block_id(),
+                        issuance.token_ticker.clone(),
+                        total_issued,
+                        issuance.number_of_decimals,
+                        issuance.metadata_uri.clone(),
+                    )))
+                }
+                TokenData::TokenTransfer(_)
+                | TokenData::TokenReissueV1 { .. }
+                | TokenData::LockTokenSupplyV1 { .. }
+                | TokenData::ChangeTokenAuthorityV1 { .. }
+                | TokenData::FreezeTokenV1 { .. }
+                | TokenData::UnfreezeTokenV1 { .. } => None,
             }))
     }
 
@@ -276,6 +352,21 @@ impl<'a, S: BlockchainStorageRead, V: TransactionVerificationStrategy> Chainstat
         self.chainstate_ref.get_token_id(tx_id)
     }
 
+    pub fn get_token_stats(
+        &self,
+        token_id: &TokenId,
+    ) -> Result<Option<TokenStats>, PropertyQueryError> {
+        self.chainstate_ref.get_token_stats(token_id)
+    }
+
+    pub fn get_token_ids(
+        &self,
+        start_after: Option<TokenId>,
+        count: usize,
+    ) -> Result<Vec<TokenId>, PropertyQueryError> {
+        self.chainstate_ref.get_token_ids(start_after, count)
+    }
+
     pub fn get_mainchain_blocks_list(&self) -> Result<Vec<Id<Block>>, PropertyQueryError> {
         self.chainstate_ref.get_mainchain_blocks_list()
     }
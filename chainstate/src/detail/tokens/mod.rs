@@ -103,14 +103,24 @@ pub fn check_tokens_issuance_data(
     number_of_decimals: &u8,
     metadata_uri: &[u8],
 ) -> Result<(), TokenIssuanceError> {
-    // Check token ticker
-    check_token_ticker(chain_config, token_ticker)?;
-
     // Check amount
     if amount_to_issue == &Amount::from_atoms(0) {
         return Err(TokenIssuanceError::IssueAmountIsZero);
     }
 
+    check_tokens_issuance_v1_data(chain_config, token_ticker, number_of_decimals, metadata_uri)
+}
+
+/// Tokens issued with an authority may have zero initial supply, so the amount isn't checked
+pub fn check_tokens_issuance_v1_data(
+    chain_config: &ChainConfig,
+    token_ticker: &[u8],
+    number_of_decimals: &u8,
+    metadata_uri: &[u8],
+) -> Result<(), TokenIssuanceError> {
+    // Check token ticker
+    check_token_ticker(chain_config, token_ticker)?;
+
     // Check decimals
     if number_of_decimals > &chain_config.token_max_dec_count() {
         return Err(TokenIssuanceError::IssueErrorTooManyDecimals);
@@ -149,5 +159,33 @@ pub fn check_tokens_data(
         .map_err(|err| TokensError::IssueError(err, tx.get_id(), source_block_id)),
         TokenData::NftIssuance(issuance) => check_nft_issuance_data(chain_config, issuance)
             .map_err(|err| TokensError::IssueError(err, tx.get_id(), source_block_id)),
+        TokenData::TokenIssuanceV1(issuance) => check_tokens_issuance_v1_data(
+            chain_config,
+            &issuance.token_ticker,
+            &issuance.number_of_decimals,
+            &issuance.metadata_uri,
+        )
+        .map_err(|err| TokensError::IssueError(err, tx.get_id(), source_block_id)),
+        TokenData::TokenReissueV1 {
+            token_id: _,
+            amount_to_issue,
+        } => {
+            ensure!(
+                amount_to_issue > &Amount::from_atoms(0),
+                TokensError::IssueError(
+                    TokenIssuanceError::IssueAmountIsZero,
+                    tx.get_id(),
+                    source_block_id
+                )
+            );
+            Ok(())
+        }
+        TokenData::LockTokenSupplyV1 { token_id: _ }
+        | TokenData::ChangeTokenAuthorityV1 {
+            token_id: _,
+            new_authority: _,
+        }
+        | TokenData::FreezeTokenV1 { token_id: _ }
+        | TokenData::UnfreezeTokenV1 { token_id: _ } => Ok(()),
     }
 }
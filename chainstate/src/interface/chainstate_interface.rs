@@ -17,7 +17,7 @@ use std::collections::BTreeMap;
 use std::sync::Arc;
 
 use crate::detail::BlockSource;
-use crate::{ChainInfo, ChainstateConfig, ChainstateError, ChainstateEvent};
+use crate::{AddressHistoryEntry, ChainInfo, ChainstateConfig, ChainstateError, ChainstateEvent};
 
 use chainstate_types::{BlockIndex, EpochData, GenBlockIndex, Locator};
 
@@ -26,19 +26,21 @@ use common::chain::{AccountNonce, AccountType};
 use common::{
     chain::{
         block::{timestamp::BlockTimestamp, Block, BlockReward, GenBlock},
-        tokens::{RPCTokenInfo, TokenAuxiliaryData, TokenId},
-        ChainConfig, DelegationId, OutPointSourceId, PoolId, Transaction, TxInput,
-        TxMainChainIndex, UtxoOutPoint,
+        tokens::{RPCTokenInfo, TokenAuxiliaryData, TokenId, TokenStats},
+        ChainConfig, DelegationId, Destination, OutPointSourceId, PoolId, Spender, Transaction,
+        TxInput, TxMainChainIndex, UtxoOutPoint,
     },
     primitives::{Amount, BlockHeight, Id},
 };
 use pos_accounting::{DelegationData, PoolData};
-use utils::eventhandler::EventHandler;
+use utils::eventhandler::{EventHandler, WeakEventHandler};
 
 use utxo::Utxo;
 
 pub trait ChainstateInterface: Send {
     fn subscribe_to_events(&mut self, handler: Arc<dyn Fn(ChainstateEvent) + Send + Sync>);
+    /// Subscribe to events until the handler is dropped by the caller
+    fn subscribe_to_events_weak(&mut self, handler: WeakEventHandler<ChainstateEvent>);
     fn process_block(
         &mut self,
         block: Block,
@@ -152,6 +154,17 @@ pub trait ChainstateInterface: Send {
         tx_id: &Id<Transaction>,
     ) -> Result<Option<TokenId>, ChainstateError>;
 
+    /// Returns the mainchain supply aggregates of the token
+    fn get_token_stats(&self, token_id: TokenId) -> Result<Option<TokenStats>, ChainstateError>;
+
+    /// Returns up to `count` ids of issued tokens and NFTs in ascending order,
+    /// starting after `start_after` if given.
+    fn get_token_ids(
+        &self,
+        start_after: Option<TokenId>,
+        count: usize,
+    ) -> Result<Vec<TokenId>, ChainstateError>;
+
     /// Returns the coin amounts of the outpoints spent by a transaction.
     /// If a utxo for an input was not found or contains tokens the result is `None`.
     fn get_inputs_outpoints_coin_amount(
@@ -233,4 +246,27 @@ pub trait ChainstateInterface: Send {
         &self,
         account: AccountType,
     ) -> Result<Option<AccountNonce>, ChainstateError>;
+
+    /// Returns the mainchain transaction with given id. Requires the transaction index.
+    fn get_transaction(
+        &self,
+        tx_id: &Id<Transaction>,
+    ) -> Result<Option<Transaction>, ChainstateError>;
+
+    /// Returns the unspent outputs of given destination. Requires the address index.
+    fn get_address_utxos(
+        &self,
+        destination: &Destination,
+    ) -> Result<BTreeMap<UtxoOutPoint, Utxo>, ChainstateError>;
+
+    /// Returns all the mainchain outputs ever created for given destination, ordered by height.
+    /// Requires the address index.
+    fn get_address_history(
+        &self,
+        destination: &Destination,
+    ) -> Result<Vec<AddressHistoryEntry>, ChainstateError>;
+
+    /// Returns the spender of given output, if it was spent in the mainchain.
+    /// Requires the address index.
+    fn get_spending_tx(&self, outpoint: &UtxoOutPoint) -> Result<Option<Spender>, ChainstateError>;
 }
@@ -23,7 +23,8 @@ use crate::{
         tx_verification_strategy::TransactionVerificationStrategy,
         BlockSource, OrphanBlocksRef,
     },
-    ChainInfo, ChainstateConfig, ChainstateError, ChainstateEvent, ChainstateInterface, Locator,
+    AddressHistoryEntry, ChainInfo, ChainstateConfig, ChainstateError, ChainstateEvent,
+    ChainstateInterface, Locator,
 };
 use chainstate_storage::BlockchainStorage;
 use chainstate_types::{BlockIndex, EpochData, GenBlockIndex, PropertyQueryError};
@@ -31,14 +32,14 @@ use common::{
     chain::{
         block::{signed_block_header::SignedBlockHeader, Block, BlockReward, GenBlock},
         config::ChainConfig,
-        tokens::{RPCTokenInfo, TokenAuxiliaryData, TokenId},
-        AccountNonce, AccountType, DelegationId, OutPointSourceId, PoolId, Transaction, TxInput,
-        TxMainChainIndex, TxOutput, UtxoOutPoint,
+        tokens::{RPCTokenInfo, TokenAuxiliaryData, TokenId, TokenStats},
+        AccountNonce, AccountType, DelegationId, Destination, OutPointSourceId, PoolId, Spender,
+        Transaction, TxInput, TxMainChainIndex, TxOutput, UtxoOutPoint,
     },
     primitives::{id::WithId, Amount, BlockHeight, Id},
 };
 use pos_accounting::{DelegationData, PoSAccountingView, PoolData};
-use utils::eventhandler::EventHandler;
+use utils::eventhandler::{EventHandler, WeakEventHandler};
 use utxo::{Utxo, UtxosView};
 
 pub struct ChainstateInterfaceImpl<S, V> {
@@ -58,6 +59,10 @@ impl<S: BlockchainStorage, V: TransactionVerificationStrategy> ChainstateInterfa
         self.chainstate.subscribe_to_events(handler)
     }
 
+    fn subscribe_to_events_weak(&mut self, handler: WeakEventHandler<ChainstateEvent>) {
+        self.chainstate.subscribe_to_events_weak(handler)
+    }
+
     fn process_block(
         &mut self,
         block: Block,
@@ -370,6 +375,26 @@ impl<S: BlockchainStorage, V: TransactionVerificationStrategy> ChainstateInterfa
             .map_err(ChainstateError::FailedToReadProperty)
     }
 
+    fn get_token_stats(&self, token_id: TokenId) -> Result<Option<TokenStats>, ChainstateError> {
+        self.chainstate
+            .query()
+            .map_err(ChainstateError::from)?
+            .get_token_stats(&token_id)
+            .map_err(ChainstateError::FailedToReadProperty)
+    }
+
+    fn get_token_ids(
+        &self,
+        start_after: Option<TokenId>,
+        count: usize,
+    ) -> Result<Vec<TokenId>, ChainstateError> {
+        self.chainstate
+            .query()
+            .map_err(ChainstateError::from)?
+            .get_token_ids(start_after, count)
+            .map_err(ChainstateError::FailedToReadProperty)
+    }
+
     fn get_inputs_outpoints_coin_amount(
         &self,
         inputs: &[TxInput],
@@ -567,6 +592,47 @@ impl<S: BlockchainStorage, V: TransactionVerificationStrategy> ChainstateInterfa
             .get_account_nonce_count(account)
             .map_err(ChainstateError::FailedToReadProperty)
     }
+
+    fn get_transaction(
+        &self,
+        tx_id: &Id<Transaction>,
+    ) -> Result<Option<Transaction>, ChainstateError> {
+        self.chainstate
+            .query()
+            .map_err(ChainstateError::from)?
+            .get_mainchain_tx(tx_id)
+            .map_err(ChainstateError::FailedToReadProperty)
+    }
+
+    fn get_address_utxos(
+        &self,
+        destination: &Destination,
+    ) -> Result<BTreeMap<UtxoOutPoint, Utxo>, ChainstateError> {
+        self.chainstate
+            .query()
+            .map_err(ChainstateError::from)?
+            .get_address_utxos(destination)
+            .map_err(ChainstateError::FailedToReadProperty)
+    }
+
+    fn get_address_history(
+        &self,
+        destination: &Destination,
+    ) -> Result<Vec<AddressHistoryEntry>, ChainstateError> {
+        self.chainstate
+            .query()
+            .map_err(ChainstateError::from)?
+            .get_address_history(destination)
+            .map_err(ChainstateError::FailedToReadProperty)
+    }
+
+    fn get_spending_tx(&self, outpoint: &UtxoOutPoint) -> Result<Option<Spender>, ChainstateError> {
+        self.chainstate
+            .query()
+            .map_err(ChainstateError::from)?
+            .get_spending_tx(outpoint)
+            .map_err(ChainstateError::FailedToReadProperty)
+    }
 }
 
 // TODO: remove this function. The value of an output cannot be generalized and exposed from ChainstateInterface in such way
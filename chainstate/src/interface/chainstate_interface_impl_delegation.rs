@@ -24,10 +24,10 @@ use chainstate_types::{BlockIndex, EpochData, GenBlockIndex};
 use common::chain::{
     block::{signed_block_header::SignedBlockHeader, timestamp::BlockTimestamp, BlockReward},
     config::ChainConfig,
-    tokens::TokenAuxiliaryData,
+    tokens::{TokenAuxiliaryData, TokenStats},
     AccountNonce, AccountType, OutPointSourceId, TxMainChainIndex,
 };
-use common::chain::{Transaction, UtxoOutPoint};
+use common::chain::{Destination, Spender, Transaction, UtxoOutPoint};
 use common::{
     chain::{
         tokens::{RPCTokenInfo, TokenId},
@@ -40,12 +40,12 @@ use common::{
     primitives::Amount,
 };
 use pos_accounting::{DelegationData, PoolData};
-use utils::eventhandler::EventHandler;
+use utils::eventhandler::{EventHandler, WeakEventHandler};
 use utxo::Utxo;
 
 use crate::{
-    chainstate_interface::ChainstateInterface, BlockSource, ChainInfo, ChainstateConfig,
-    ChainstateError, ChainstateEvent,
+    chainstate_interface::ChainstateInterface, AddressHistoryEntry, BlockSource, ChainInfo,
+    ChainstateConfig, ChainstateError, ChainstateEvent,
 };
 
 impl<T: Deref + DerefMut + Send> ChainstateInterface for T
@@ -56,6 +56,10 @@ where
         self.deref_mut().subscribe_to_events(handler)
     }
 
+    fn subscribe_to_events_weak(&mut self, handler: WeakEventHandler<ChainstateEvent>) {
+        self.deref_mut().subscribe_to_events_weak(handler)
+    }
+
     fn process_block(
         &mut self,
         block: Block,
@@ -238,6 +242,18 @@ where
         self.deref().get_token_id_from_issuance_tx(tx_id)
     }
 
+    fn get_token_stats(&self, token_id: TokenId) -> Result<Option<TokenStats>, ChainstateError> {
+        self.deref().get_token_stats(token_id)
+    }
+
+    fn get_token_ids(
+        &self,
+        start_after: Option<TokenId>,
+        count: usize,
+    ) -> Result<Vec<TokenId>, ChainstateError> {
+        self.deref().get_token_ids(start_after, count)
+    }
+
     fn get_inputs_outpoints_coin_amount(
         &self,
         inputs: &[TxInput],
@@ -334,6 +350,31 @@ where
     ) -> Result<Option<AccountNonce>, ChainstateError> {
         self.deref().get_account_nonce_count(account)
     }
+
+    fn get_transaction(
+        &self,
+        tx_id: &Id<Transaction>,
+    ) -> Result<Option<Transaction>, ChainstateError> {
+        self.deref().get_transaction(tx_id)
+    }
+
+    fn get_address_utxos(
+        &self,
+        destination: &Destination,
+    ) -> Result<BTreeMap<UtxoOutPoint, Utxo>, ChainstateError> {
+        self.deref().get_address_utxos(destination)
+    }
+
+    fn get_address_history(
+        &self,
+        destination: &Destination,
+    ) -> Result<Vec<AddressHistoryEntry>, ChainstateError> {
+        self.deref().get_address_history(destination)
+    }
+
+    fn get_spending_tx(&self, outpoint: &UtxoOutPoint) -> Result<Option<Spender>, ChainstateError> {
+        self.deref().get_spending_tx(outpoint)
+    }
 }
 
 #[cfg(test)]
@@ -384,6 +425,7 @@ mod tests {
                 max_orphan_blocks: 0.into(),
                 min_max_bootstrap_import_buffer_sizes: Default::default(),
                 tx_index_enabled: Default::default(),
+                address_index_enabled: Default::default(),
                 max_tip_age: Default::default(),
             };
             let chainstate_storage = Store::new_empty().unwrap();
@@ -27,10 +27,10 @@ pub use crate::{
     config::ChainstateConfig,
     detail::{
         ban_score, calculate_median_time_past, check_nft_issuance_data, check_tokens_issuance_data,
-        is_rfc3986_valid_symbol, BlockError, BlockSource, ChainInfo, CheckBlockError,
-        CheckBlockTransactionsError, ConnectTransactionError, InitializationError, Locator,
-        OrphanCheckError, SpendStakeError, TokenIssuanceError, TokensError,
-        TransactionVerifierStorageError, TxIndexError,
+        check_tokens_issuance_v1_data, is_rfc3986_valid_symbol, AddressHistoryEntry, BlockError,
+        BlockSource, ChainInfo, CheckBlockError, CheckBlockTransactionsError,
+        ConnectTransactionError, InitializationError, Locator, OrphanCheckError, SpendStakeError,
+        TokenIssuanceError, TokensError, TransactionVerifierStorageError, TxIndexError,
     },
 };
 
@@ -54,6 +54,13 @@ use detail::Chainstate;
 #[derive(Debug, Clone)]
 pub enum ChainstateEvent {
     NewTip(Id<Block>, BlockHeight),
+    /// The mainchain was reorganized. Emitted before the corresponding `NewTip` event.
+    /// `disconnected` is ordered from the old tip downwards, `connected` from the fork
+    /// point upwards to the new tip.
+    Reorg {
+        disconnected: Vec<Id<Block>>,
+        connected: Vec<Id<Block>>,
+    },
 }
 
 #[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
@@ -15,18 +15,62 @@
 
 //! Chainstate subsystem RPC handler
 
-use std::io::{Read, Write};
+use std::{
+    collections::BTreeMap,
+    io::{Read, Write},
+    sync::Arc,
+};
 
-use crate::{Block, BlockSource, ChainInfo, GenBlock};
+use crate::{
+    AddressHistoryEntry, Block, BlockSource, ChainInfo, ChainstateError, ChainstateEvent, GenBlock,
+};
 use common::{
+    address::Address,
     chain::{
-        tokens::{RPCTokenInfo, TokenId},
-        PoolId,
+        tokens::{RPCTokenInfo, RPCTokenStats, TokenId},
+        AccountNonce, AccountType, DelegationId, PoolId, Spender, Transaction, TxOutput,
+        UtxoOutPoint,
     },
     primitives::{Amount, BlockHeight, Id},
 };
-use rpc::Result as RpcResult;
+use rpc::{
+    subscription::{PendingSubscriptionSink, SubscriptionResult},
+    Result as RpcResult,
+};
 use serialization::hex_encoded::HexEncoded;
+use utxo::Utxo;
+
+/// The maximum number of tokens returned by a single `token_list` call
+pub const MAX_TOKEN_LIST_COUNT: usize = 1000;
+
+/// Chainstate event as it is sent to RPC subscribers
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type")]
+pub enum RpcChainstateEvent {
+    NewTip {
+        id: Id<Block>,
+        height: BlockHeight,
+    },
+    Reorg {
+        disconnected: Vec<Id<Block>>,
+        connected: Vec<Id<Block>>,
+    },
+}
+
+impl From<ChainstateEvent> for RpcChainstateEvent {
+    fn from(event: ChainstateEvent) -> Self {
+        match event {
+            ChainstateEvent::NewTip(id, height) => Self::NewTip { id, height },
+            ChainstateEvent::Reorg {
+                disconnected,
+                connected,
+            } => Self::Reorg {
+                disconnected,
+                connected,
+            },
+        }
+    }
+}
 
 #[rpc::rpc(server, client, namespace = "chainstate")]
 trait ChainstateRpc {
@@ -69,10 +113,73 @@ trait ChainstateRpc {
     #[method(name = "stake_pool_balance")]
     async fn stake_pool_balance(&self, pool_id: PoolId) -> RpcResult<Option<Amount>>;
 
+    /// Get the pledge amount of the given pool, this is the amount available to the pool owner
+    /// when the pool is decommissioned
+    #[method(name = "stake_pool_pledge")]
+    async fn stake_pool_pledge(&self, pool_id: PoolId) -> RpcResult<Option<Amount>>;
+
+    /// Get the balance of the given delegation
+    #[method(name = "stake_delegation_balance")]
+    async fn stake_delegation_balance(
+        &self,
+        delegation_id: DelegationId,
+    ) -> RpcResult<Option<Amount>>;
+
+    /// Get the last nonce used to spend from the given account.
+    /// Returns None if nothing has been spent from the account yet.
+    #[method(name = "account_nonce_count")]
+    async fn account_nonce_count(&self, account: AccountType) -> RpcResult<Option<AccountNonce>>;
+
     /// Get token information
     #[method(name = "token_info")]
     async fn token_info(&self, token_id: TokenId) -> RpcResult<Option<RPCTokenInfo>>;
 
+    /// Get the circulating supply, the burned amount and the issuance height of a token
+    #[method(name = "token_stats")]
+    async fn token_stats(&self, token_id: TokenId) -> RpcResult<Option<RPCTokenStats>>;
+
+    /// List issued tokens and NFTs ordered by id, starting after `start_after` if given.
+    /// At most `MAX_TOKEN_LIST_COUNT` entries are returned at once.
+    #[method(name = "token_list")]
+    async fn token_list(
+        &self,
+        start_after: Option<TokenId>,
+        count: usize,
+    ) -> RpcResult<Vec<RPCTokenInfo>>;
+
+    /// Returns a hex-encoded mainchain transaction with the given id.
+    /// Requires the transaction index to be enabled.
+    #[method(name = "get_transaction")]
+    async fn get_transaction(
+        &self,
+        tx_id: Id<Transaction>,
+    ) -> RpcResult<Option<HexEncoded<Transaction>>>;
+
+    /// Returns the hex-encoded unspent outputs of the given address.
+    /// Requires the address index to be enabled.
+    #[method(name = "get_address_utxos")]
+    async fn get_address_utxos(
+        &self,
+        address: String,
+    ) -> RpcResult<Vec<(HexEncoded<UtxoOutPoint>, HexEncoded<TxOutput>)>>;
+
+    /// Returns all the mainchain outputs ever created for the given address, along with
+    /// their spenders, ordered by block height.
+    /// Requires the address index to be enabled.
+    #[method(name = "get_address_history")]
+    async fn get_address_history(
+        &self,
+        address: String,
+    ) -> RpcResult<Vec<HexEncoded<AddressHistoryEntry>>>;
+
+    /// Returns the transaction or block that spent the given hex-encoded outpoint.
+    /// Requires the address index to be enabled.
+    #[method(name = "get_spending_tx")]
+    async fn get_spending_tx(
+        &self,
+        outpoint: HexEncoded<UtxoOutPoint>,
+    ) -> RpcResult<Option<HexEncoded<Spender>>>;
+
     /// Write blocks to disk
     #[method(name = "export_bootstrap_file")]
     async fn export_bootstrap_file(
@@ -88,6 +195,15 @@ trait ChainstateRpc {
     /// Return information about the chain.
     #[method(name = "info")]
     async fn info(&self) -> RpcResult<ChainInfo>;
+
+    /// Subscribe to new tip and reorg notifications (WebSocket only).
+    /// A reorg is reported before the new tip it results in.
+    #[subscription(
+        name = "subscribe_events",
+        unsubscribe = "unsubscribe_events",
+        item = RpcChainstateEvent
+    )]
+    async fn subscribe_events(&self) -> SubscriptionResult;
 }
 
 #[async_trait::async_trait]
@@ -143,10 +259,112 @@ impl ChainstateRpcServer for super::ChainstateHandle {
         rpc::handle_result(self.call(move |this| this.get_stake_pool_balance(pool_id)).await)
     }
 
+    async fn stake_pool_pledge(&self, pool_id: PoolId) -> RpcResult<Option<Amount>> {
+        rpc::handle_result(
+            self.call(move |this| {
+                this.get_stake_pool_data(pool_id)
+                    .map(|pool_data| pool_data.map(|pool_data| pool_data.pledge_amount()))
+            })
+            .await,
+        )
+    }
+
+    async fn stake_delegation_balance(
+        &self,
+        delegation_id: DelegationId,
+    ) -> RpcResult<Option<Amount>> {
+        rpc::handle_result(
+            self.call(move |this| this.get_stake_delegation_balance(delegation_id)).await,
+        )
+    }
+
+    async fn account_nonce_count(&self, account: AccountType) -> RpcResult<Option<AccountNonce>> {
+        rpc::handle_result(self.call(move |this| this.get_account_nonce_count(account)).await)
+    }
+
     async fn token_info(&self, token_id: TokenId) -> RpcResult<Option<RPCTokenInfo>> {
         rpc::handle_result(self.call(move |this| this.get_token_info_for_rpc(token_id)).await)
     }
 
+    async fn token_stats(&self, token_id: TokenId) -> RpcResult<Option<RPCTokenStats>> {
+        rpc::handle_result(
+            self.call(move |this| {
+                this.get_token_stats(token_id)
+                    .map(|stats| stats.map(|stats| RPCTokenStats::new(token_id, &stats)))
+            })
+            .await,
+        )
+    }
+
+    async fn token_list(
+        &self,
+        start_after: Option<TokenId>,
+        count: usize,
+    ) -> RpcResult<Vec<RPCTokenInfo>> {
+        rpc::handle_result(
+            self.call(move |this| {
+                let count = std::cmp::min(count, MAX_TOKEN_LIST_COUNT);
+                this.get_token_ids(start_after, count)?
+                    .into_iter()
+                    .filter_map(|token_id| this.get_token_info_for_rpc(token_id).transpose())
+                    .collect::<Result<Vec<_>, ChainstateError>>()
+            })
+            .await,
+        )
+    }
+
+    async fn get_transaction(
+        &self,
+        tx_id: Id<Transaction>,
+    ) -> RpcResult<Option<HexEncoded<Transaction>>> {
+        let tx: Option<Transaction> =
+            rpc::handle_result(self.call(move |this| this.get_transaction(&tx_id)).await)?;
+        Ok(tx.map(HexEncoded::new))
+    }
+
+    async fn get_address_utxos(
+        &self,
+        address: String,
+    ) -> RpcResult<Vec<(HexEncoded<UtxoOutPoint>, HexEncoded<TxOutput>)>> {
+        let utxos: BTreeMap<UtxoOutPoint, Utxo> = rpc::handle_result(
+            self.call(move |this| {
+                Address::from_str(this.get_chain_config(), &address)
+                    .and_then(|address| address.destination(this.get_chain_config()))
+                    .map(|destination| this.get_address_utxos(&destination))
+            })
+            .await,
+        )?;
+        Ok(utxos
+            .into_iter()
+            .map(|(outpoint, utxo)| (outpoint.into(), utxo.take_output().into()))
+            .collect())
+    }
+
+    async fn get_address_history(
+        &self,
+        address: String,
+    ) -> RpcResult<Vec<HexEncoded<AddressHistoryEntry>>> {
+        let history: Vec<AddressHistoryEntry> = rpc::handle_result(
+            self.call(move |this| {
+                Address::from_str(this.get_chain_config(), &address)
+                    .and_then(|address| address.destination(this.get_chain_config()))
+                    .map(|destination| this.get_address_history(&destination))
+            })
+            .await,
+        )?;
+        Ok(history.into_iter().map(HexEncoded::new).collect())
+    }
+
+    async fn get_spending_tx(
+        &self,
+        outpoint: HexEncoded<UtxoOutPoint>,
+    ) -> RpcResult<Option<HexEncoded<Spender>>> {
+        let spender: Option<Spender> = rpc::handle_result(
+            self.call(move |this| this.get_spending_tx(outpoint.as_ref())).await,
+        )?;
+        Ok(spender.map(HexEncoded::new))
+    }
+
     async fn export_bootstrap_file(
         &self,
         file_path: &std::path::Path,
@@ -175,6 +393,23 @@ impl ChainstateRpcServer for super::ChainstateHandle {
     async fn info(&self) -> RpcResult<ChainInfo> {
         rpc::handle_result(self.call(move |this| this.info()).await)
     }
+
+    async fn subscribe_events(&self, pending: PendingSubscriptionSink) -> SubscriptionResult {
+        let (sender, receiver) = rpc::subscription::event_channel();
+
+        // Chainstate only keeps a weak reference, so the handler is unsubscribed
+        // when it's dropped at the end of the subscription
+        let handler: Arc<dyn Fn(ChainstateEvent) + Send + Sync> =
+            Arc::new(move |event: ChainstateEvent| {
+                sender.send(RpcChainstateEvent::from(event));
+            });
+        let weak_handler = Arc::downgrade(&handler);
+        self.call_mut(move |this| this.subscribe_to_events_weak(weak_handler)).await?;
+
+        let result = rpc::subscription::forward_events(pending, receiver).await;
+        drop(handler);
+        result
+    }
 }
 
 #[cfg(test)]
@@ -20,21 +20,23 @@ use common::{
     chain::{
         block::{signed_block_header::SignedBlockHeader, BlockReward},
         config::EpochIndex,
-        tokens::{TokenAuxiliaryData, TokenId},
-        transaction::{Transaction, TxMainChainIndex, TxMainChainPosition},
-        AccountNonce, AccountType, Block, DelegationId, GenBlock, OutPointSourceId, PoolId,
-        UtxoOutPoint,
+        tokens::{TokenAuxiliaryData, TokenId, TokenStats, TokensBlockUndo},
+        transaction::{Spender, Transaction, TxMainChainIndex, TxMainChainPosition},
+        AccountNonce, AccountType, Block, DelegationId, Destination, GenBlock, OutPointSourceId,
+        PoolId, UtxoOutPoint,
     },
-    primitives::{Amount, BlockHeight, Id},
+    primitives::{id::WithId, Amount, BlockHeight, Id},
 };
 use pos_accounting::{
     AccountingBlockUndo, DelegationData, DeltaMergeUndo, PoSAccountingDeltaData,
     PoSAccountingStorageRead, PoSAccountingStorageWrite, PoolData,
 };
+use serialization::{Decode, DecodeAll, Encode};
 use utxo::{Utxo, UtxosBlockUndo, UtxosStorageRead, UtxosStorageWrite};
 
 use crate::{
     schema::{self as db, Schema},
+    token_stats::{self, TokenStatsError},
     BlockchainStorage, BlockchainStorageRead, BlockchainStorageWrite, SealedStorageTag,
     TipStorageTag, TransactionRw, Transactional,
 };
@@ -42,17 +44,95 @@ use crate::{
 mod store_tx;
 pub use store_tx::{StoreTxRo, StoreTxRw};
 
+/// Version of the storage layout written by this code
+pub const CURRENT_STORAGE_VERSION: u32 = 4;
+
+/// Token auxiliary data as stored before tokens got a supply state (storage version 1)
+#[derive(Encode, Decode)]
+struct TokenAuxiliaryDataV1 {
+    issuance_tx: Transaction,
+    issuance_block_id: Id<Block>,
+}
+
 /// Store for blockchain data, parametrized over the backend B
 pub struct Store<B: storage::Backend>(storage::Storage<B, Schema>);
 
 impl<B: storage::Backend> Store<B> {
     /// Create a new chainstate storage
     pub fn new(backend: B) -> crate::Result<Self> {
-        let mut storage = Self(storage::Storage::new(backend).map_err(crate::Error::from)?);
-        storage.set_storage_version(1)?;
+        let storage = Self(storage::Storage::new(backend).map_err(crate::Error::from)?);
+        storage.migrate()?;
         Ok(storage)
     }
 
+    /// Bring the data stored by an older version of the node to the current layout
+    fn migrate(&self) -> crate::Result<()> {
+        let mut db_tx = self.transaction_rw(None)?;
+        let version = db_tx.get_storage_version()?;
+
+        if version > CURRENT_STORAGE_VERSION {
+            db_tx.abort();
+            return Err(storage::error::Recoverable::DbInit.into());
+        }
+
+        if version < 2 {
+            // Token auxiliary data has got the supply state of tokens issued with an authority
+            let legacy_aux_data = db_tx
+                .0
+                .get::<db::DBTokensAuxData, _>()
+                .prefix_iter(&())?
+                .map(|(token_id, aux_data)| {
+                    let aux_data = TokenAuxiliaryDataV1::decode_all(&mut aux_data.bytes())
+                        .expect("Token auxiliary data of storage version 1 to be valid");
+                    (token_id, aux_data)
+                })
+                .collect::<Vec<_>>();
+
+            for (token_id, aux_data) in legacy_aux_data {
+                let aux_data =
+                    TokenAuxiliaryData::new(aux_data.issuance_tx, aux_data.issuance_block_id);
+                db_tx.set_token_aux_data(&token_id, &aux_data)?;
+            }
+        }
+
+        if version < 3 {
+            // Token stats weren't tracked before, replay the mainchain to compute them
+            let mut height = BlockHeight::new(1);
+            while let Some(block_id) = db_tx.get_block_id_by_height(&height)? {
+                let block_id = Id::<Block>::new(block_id.get());
+                let block = db_tx.get_block(block_id)?.expect("Mainchain block to be stored");
+                token_stats::connect_block(&mut db_tx, &WithId::new(block), height).map_err(
+                    |e| match e {
+                        TokenStatsError::StorageError(e) => e,
+                        TokenStatsError::BlockUndoNotFound(_)
+                        | TokenStatsError::TxUndoNotFound(_)
+                        | TokenStatsError::IssuanceTokenIdNotFound(_)
+                        | TokenStatsError::StatsNotFound(_)
+                        | TokenStatsError::InconsistentAmounts(_) => {
+                            panic!(
+                                "Failed to compute token stats of mainchain block {block_id}: {e}"
+                            )
+                        }
+                    },
+                )?;
+                height = height.next_height();
+            }
+        }
+
+        if version < 4 {
+            // The address index wasn't maintained for the blocks stored before, so it can't be
+            // enabled for an existing chain without reindexing
+            if db_tx.get_is_address_index_enabled()?.is_none()
+                && db_tx.get_best_block_id()?.is_some()
+            {
+                db_tx.set_is_address_index_enabled(false)?;
+            }
+        }
+
+        db_tx.set_storage_version(CURRENT_STORAGE_VERSION)?;
+        db_tx.commit()
+    }
+
     /// Dump raw database contents
     pub fn dump_raw(&self) -> crate::Result<storage::raw::StorageContents<Schema>> {
         self.0.dump_raw().map_err(crate::Error::from)
@@ -220,6 +300,13 @@ impl<B: storage::Backend> BlockchainStorageRead for Store<B> {
             tx_index: &TxMainChainPosition,
         ) -> crate::Result<Option<Transaction>>;
 
+        fn get_is_address_index_enabled(&self) -> crate::Result<Option<bool>>;
+        fn get_address_outpoints(
+            &self,
+            destination: &Destination,
+        ) -> crate::Result<BTreeMap<UtxoOutPoint, BlockHeight>>;
+        fn get_spent_by(&self, outpoint: &UtxoOutPoint) -> crate::Result<Option<Spender>>;
+
         fn get_block_id_by_height(
             &self,
             height: &BlockHeight,
@@ -229,11 +316,21 @@ impl<B: storage::Backend> BlockchainStorageRead for Store<B> {
 
         fn get_token_id(&self, tx_id: &Id<Transaction>) -> crate::Result<Option<TokenId>>;
 
+        fn get_token_stats(&self, token_id: &TokenId) -> crate::Result<Option<TokenStats>>;
+
+        fn get_token_ids(
+            &self,
+            start_after: Option<TokenId>,
+            count: usize,
+        ) -> crate::Result<Vec<TokenId>>;
+
         fn get_block_tree_by_height(
             &self,
             start_from: BlockHeight,
         ) -> crate::Result<BTreeMap<BlockHeight, Vec<Id<Block>>>>;
 
+        fn get_tokens_undo(&self, id: Id<Block>) -> crate::Result<Option<TokensBlockUndo>>;
+
         fn get_accounting_undo(
             &self,
             id: Id<Block>,
@@ -369,6 +466,21 @@ impl<B: storage::Backend> BlockchainStorageWrite for Store<B> {
         ) -> crate::Result<()>;
         fn del_mainchain_tx_index(&mut self, tx_id: &OutPointSourceId) -> crate::Result<()>;
 
+        fn set_is_address_index_enabled(&mut self, enabled: bool) -> crate::Result<()>;
+        fn set_address_outpoint(
+            &mut self,
+            destination: &Destination,
+            outpoint: &UtxoOutPoint,
+            height: BlockHeight,
+        ) -> crate::Result<()>;
+        fn del_address_outpoint(
+            &mut self,
+            destination: &Destination,
+            outpoint: &UtxoOutPoint,
+        ) -> crate::Result<()>;
+        fn set_spent_by(&mut self, outpoint: &UtxoOutPoint, spender: &Spender) -> crate::Result<()>;
+        fn del_spent_by(&mut self, outpoint: &UtxoOutPoint) -> crate::Result<()>;
+
         fn set_block_id_at_height(
             &mut self,
             height: &BlockHeight,
@@ -385,6 +497,13 @@ impl<B: storage::Backend> BlockchainStorageWrite for Store<B> {
 
         fn del_token_id(&mut self, issuance_tx_id: &Id<Transaction>) -> crate::Result<()>;
 
+        fn set_token_stats(&mut self, token_id: &TokenId, stats: &TokenStats) -> crate::Result<()>;
+
+        fn del_token_stats(&mut self, token_id: &TokenId) -> crate::Result<()>;
+
+        fn set_tokens_undo_data(&mut self, id: Id<Block>, undo: &TokensBlockUndo) -> crate::Result<()>;
+        fn del_tokens_undo_data(&mut self, id: Id<Block>) -> crate::Result<()>;
+
         fn set_accounting_undo_data(
             &mut self,
             id: Id<Block>,
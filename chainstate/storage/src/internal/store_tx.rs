@@ -19,10 +19,10 @@ use common::{
     chain::{
         block::BlockReward,
         config::EpochIndex,
-        tokens::{TokenAuxiliaryData, TokenId},
-        transaction::{Transaction, TxMainChainIndex, TxMainChainPosition},
-        AccountNonce, AccountType, Block, DelegationId, GenBlock, OutPointSourceId, PoolId,
-        UtxoOutPoint,
+        tokens::{TokenAuxiliaryData, TokenId, TokenStats, TokensBlockUndo},
+        transaction::{Spender, Transaction, TxMainChainIndex, TxMainChainPosition},
+        AccountNonce, AccountType, Block, DelegationId, Destination, GenBlock, OutPointSourceId,
+        PoolId, UtxoOutPoint,
     },
     primitives::{Amount, BlockHeight, Id, Idable, H256},
 };
@@ -65,6 +65,7 @@ mod well_known {
     declare_entry!(BestBlockId: Id<GenBlock>);
     declare_entry!(UtxosBestBlockId: Id<GenBlock>);
     declare_entry!(TxIndexEnabled: bool);
+    declare_entry!(AddressIndexEnabled: bool);
 }
 
 /// Read-only chainstate storage transaction
@@ -149,6 +150,23 @@ macro_rules! impl_read_ops {
                 }
             }
 
+            fn get_is_address_index_enabled(&self) -> crate::Result<Option<bool>> {
+                self.read_value::<well_known::AddressIndexEnabled>()
+            }
+
+            fn get_address_outpoints(
+                &self,
+                destination: &Destination,
+            ) -> crate::Result<BTreeMap<UtxoOutPoint, BlockHeight>> {
+                let map = self.0.get::<db::DBAddressIndex, _>();
+                let items = map.prefix_iter_decoded(&(destination.clone(),))?;
+                Ok(items.map(|((_destination, outpoint), height)| (outpoint, height)).collect())
+            }
+
+            fn get_spent_by(&self, outpoint: &UtxoOutPoint) -> crate::Result<Option<Spender>> {
+                self.read::<db::DBSpentByIndex, _, _>(outpoint)
+            }
+
             fn get_block_id_by_height(
                 &self,
                 height: &BlockHeight,
@@ -170,6 +188,25 @@ macro_rules! impl_read_ops {
                 self.read::<db::DBIssuanceTxVsTokenId, _, _>(&issuance_tx_id)
             }
 
+            fn get_token_stats(&self, token_id: &TokenId) -> crate::Result<Option<TokenStats>> {
+                self.read::<db::DBTokenStats, _, _>(token_id)
+            }
+
+            fn get_token_ids(
+                &self,
+                start_after: Option<TokenId>,
+                count: usize,
+            ) -> crate::Result<Vec<TokenId>> {
+                // Only the keys are needed, the aux data values are left undecoded
+                let map = self.0.get::<db::DBTokensAuxData, _>();
+                let items = map.prefix_iter(&())?;
+                Ok(items
+                    .map(|(token_id, _encoded_aux_data)| token_id)
+                    .skip_while(|token_id| start_after.map_or(false, |start| *token_id <= start))
+                    .take(count)
+                    .collect())
+            }
+
             fn get_block_tree_by_height(
                 &self,
                 start_from: BlockHeight,
@@ -187,6 +224,10 @@ macro_rules! impl_read_ops {
                 Ok(result)
             }
 
+            fn get_tokens_undo(&self, id: Id<Block>) -> crate::Result<Option<TokensBlockUndo>> {
+                self.read::<db::DBTokensBlockUndo, _, _>(id)
+            }
+
             fn get_accounting_undo(
                 &self,
                 id: Id<Block>,
@@ -420,6 +461,38 @@ impl<'st, B: storage::Backend> BlockchainStorageWrite for StoreTxRw<'st, B> {
         self.0.get_mut::<db::DBTxIndex, _>().del(tx_id).map_err(Into::into)
     }
 
+    fn set_is_address_index_enabled(&mut self, enabled: bool) -> crate::Result<()> {
+        self.write_value::<well_known::AddressIndexEnabled>(&enabled)
+    }
+
+    fn set_address_outpoint(
+        &mut self,
+        destination: &Destination,
+        outpoint: &UtxoOutPoint,
+        height: BlockHeight,
+    ) -> crate::Result<()> {
+        self.write::<db::DBAddressIndex, _, _, _>((destination, outpoint), height)
+    }
+
+    fn del_address_outpoint(
+        &mut self,
+        destination: &Destination,
+        outpoint: &UtxoOutPoint,
+    ) -> crate::Result<()> {
+        self.0
+            .get_mut::<db::DBAddressIndex, _>()
+            .del((destination, outpoint))
+            .map_err(Into::into)
+    }
+
+    fn set_spent_by(&mut self, outpoint: &UtxoOutPoint, spender: &Spender) -> crate::Result<()> {
+        self.write::<db::DBSpentByIndex, _, _, _>(outpoint, spender)
+    }
+
+    fn del_spent_by(&mut self, outpoint: &UtxoOutPoint) -> crate::Result<()> {
+        self.0.get_mut::<db::DBSpentByIndex, _>().del(outpoint).map_err(Into::into)
+    }
+
     fn set_block_id_at_height(
         &mut self,
         height: &BlockHeight,
@@ -459,6 +532,22 @@ impl<'st, B: storage::Backend> BlockchainStorageWrite for StoreTxRw<'st, B> {
             .map_err(Into::into)
     }
 
+    fn set_token_stats(&mut self, token_id: &TokenId, stats: &TokenStats) -> crate::Result<()> {
+        self.write::<db::DBTokenStats, _, _, _>(token_id, stats)
+    }
+
+    fn del_token_stats(&mut self, token_id: &TokenId) -> crate::Result<()> {
+        self.0.get_mut::<db::DBTokenStats, _>().del(token_id).map_err(Into::into)
+    }
+
+    fn set_tokens_undo_data(&mut self, id: Id<Block>, undo: &TokensBlockUndo) -> crate::Result<()> {
+        self.write::<db::DBTokensBlockUndo, _, _, _>(id, undo)
+    }
+
+    fn del_tokens_undo_data(&mut self, id: Id<Block>) -> crate::Result<()> {
+        self.0.get_mut::<db::DBTokensBlockUndo, _>().del(id).map_err(Into::into)
+    }
+
     fn set_accounting_undo_data(
         &mut self,
         id: Id<Block>,
@@ -33,7 +33,10 @@ fn test_storage_get_default_version_in_tx() {
         let store = TestStore::new_empty().unwrap();
         let vtx = store.transaction_ro().unwrap().get_storage_version().unwrap();
         let vst = store.get_storage_version().unwrap();
-        assert_eq!(vtx, 1, "Default storage version wrong");
+        assert_eq!(
+            vtx, CURRENT_STORAGE_VERSION,
+            "Default storage version wrong"
+        );
         assert_eq!(vtx, vst, "Transaction and non-transaction inconsistency");
     })
 }
@@ -73,9 +76,12 @@ fn test_storage_manipulation() {
     let mut store = TestStore::new_empty().unwrap();
 
     // Storage version manipulation
-    assert_eq!(store.get_storage_version(), Ok(1));
-    assert_eq!(store.set_storage_version(2), Ok(()));
-    assert_eq!(store.get_storage_version(), Ok(2));
+    assert_eq!(store.get_storage_version(), Ok(CURRENT_STORAGE_VERSION));
+    assert_eq!(
+        store.set_storage_version(CURRENT_STORAGE_VERSION + 1),
+        Ok(())
+    );
+    assert_eq!(store.get_storage_version(), Ok(CURRENT_STORAGE_VERSION + 1));
 
     // Store is now empty, the block is not there
     assert_eq!(store.get_block(block0.get_id()), Ok(None));
@@ -383,3 +389,200 @@ fn utxo_db_impl_test(#[case] seed: Seed) {
     assert!(db_interface.del_undo_data(block_id).is_ok());
     assert_eq!(db_interface.get_undo_data(block_id), Ok(None));
 }
+
+#[cfg(not(loom))]
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn address_index_test(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let mut store = TestStore::new_empty().unwrap();
+
+    let (_, pub_key0) = PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+    let (_, pub_key1) = PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+    let dest0 = Destination::PublicKey(pub_key0);
+    let dest1 = Destination::PublicKey(pub_key1);
+
+    let (_, outpoint0) = create_rand_utxo(&mut rng, 1);
+    let (_, outpoint1) = create_rand_utxo(&mut rng, 2);
+    let (_, outpoint2) = create_rand_utxo(&mut rng, 3);
+
+    assert_eq!(store.get_address_outpoints(&dest0), Ok(BTreeMap::new()));
+
+    store.set_address_outpoint(&dest0, &outpoint0, BlockHeight::new(1)).unwrap();
+    store.set_address_outpoint(&dest0, &outpoint1, BlockHeight::new(2)).unwrap();
+    store.set_address_outpoint(&dest1, &outpoint2, BlockHeight::new(3)).unwrap();
+
+    assert_eq!(
+        store.get_address_outpoints(&dest0),
+        Ok(BTreeMap::from([
+            (outpoint0.clone(), BlockHeight::new(1)),
+            (outpoint1.clone(), BlockHeight::new(2)),
+        ]))
+    );
+    assert_eq!(
+        store.get_address_outpoints(&dest1),
+        Ok(BTreeMap::from([(outpoint2.clone(), BlockHeight::new(3))]))
+    );
+
+    store.del_address_outpoint(&dest0, &outpoint0).unwrap();
+    assert_eq!(
+        store.get_address_outpoints(&dest0),
+        Ok(BTreeMap::from([(outpoint1.clone(), BlockHeight::new(2))]))
+    );
+
+    let spender = Spender::RegularInput(Id::new(H256::random_using(&mut rng)));
+    assert_eq!(store.get_spent_by(&outpoint1), Ok(None));
+    store.set_spent_by(&outpoint1, &spender).unwrap();
+    assert_eq!(store.get_spent_by(&outpoint1), Ok(Some(spender)));
+    store.del_spent_by(&outpoint1).unwrap();
+    assert_eq!(store.get_spent_by(&outpoint1), Ok(None));
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn migrate_token_aux_data_from_v1(#[case] seed: Seed) {
+    impl serialization::EncodeLike<TokenAuxiliaryData> for TokenAuxiliaryDataV1 {}
+
+    let mut rng = make_seedable_rng(seed);
+    let mut store = TestStore::new_empty().unwrap();
+
+    let issuance_tx = Transaction::new(rng.gen(), vec![], vec![]).unwrap();
+    let issuance_block_id = Id::<Block>::new(H256::random_using(&mut rng));
+    let token_id = TokenId::random_using(&mut rng);
+
+    // Write the data the way storage version 1 did
+    let mut db_tx = store.transaction_rw(None).unwrap();
+    db_tx
+        .0
+        .get_mut::<db::DBTokensAuxData, _>()
+        .put(
+            token_id,
+            TokenAuxiliaryDataV1 {
+                issuance_tx: issuance_tx.clone(),
+                issuance_block_id,
+            },
+        )
+        .unwrap();
+    db_tx.set_storage_version(1).unwrap();
+    db_tx.commit().unwrap();
+
+    store.migrate().unwrap();
+
+    assert_eq!(store.get_storage_version(), Ok(CURRENT_STORAGE_VERSION));
+    assert_eq!(
+        store.get_token_aux_data(&token_id),
+        Ok(Some(TokenAuxiliaryData::new(
+            issuance_tx,
+            issuance_block_id
+        )))
+    );
+
+    // Data written by a newer version of the node is not touched
+    store.set_storage_version(CURRENT_STORAGE_VERSION + 1).unwrap();
+    assert_eq!(
+        store.migrate(),
+        Err(crate::Error::Storage(storage::error::Recoverable::DbInit))
+    );
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn migrate_token_stats_from_v2(#[case] seed: Seed) {
+    use common::chain::{
+        block::{timestamp::BlockTimestamp, BlockReward, ConsensusData},
+        signature::inputsig::InputWitness,
+        tokens::{token_id, TokenIssuance},
+        TxInput,
+    };
+    use utxo::UtxosTxUndoWithSources;
+
+    let mut rng = make_seedable_rng(seed);
+    let mut store = TestStore::new_empty().unwrap();
+
+    // A mainchain block issuing a token, stored without stats the way storage version 2 did
+    let amount_to_issue = Amount::from_atoms(rng.gen_range(1..u128::MAX));
+    let issuance_tx = Transaction::new(
+        0,
+        vec![TxInput::from_utxo(
+            Id::<Transaction>::new(H256::random_using(&mut rng)).into(),
+            0,
+        )],
+        vec![TxOutput::Transfer(
+            TokenIssuance {
+                token_ticker: b"XXXX".to_vec(),
+                amount_to_issue,
+                number_of_decimals: 0,
+                metadata_uri: Vec::new(),
+            }
+            .into(),
+            Destination::AnyoneCanSpend,
+        )],
+    )
+    .unwrap();
+    let token_id = token_id(&issuance_tx).unwrap();
+    let issuance_tx_id = issuance_tx.get_id();
+    let block = Block::new(
+        vec![SignedTransaction::new(issuance_tx, vec![InputWitness::NoSignature(None)]).unwrap()],
+        Id::new(H256::random_using(&mut rng)),
+        BlockTimestamp::from_int_seconds(1),
+        ConsensusData::None,
+        BlockReward::new(Vec::new()),
+    )
+    .unwrap();
+    let block_id = block.get_id();
+    let block_undo = UtxosBlockUndo::new(
+        None,
+        BTreeMap::from([(
+            issuance_tx_id,
+            UtxosTxUndoWithSources::new(vec![None], vec![]),
+        )]),
+    )
+    .unwrap();
+
+    store.add_block(&block).unwrap();
+    store.set_block_id_at_height(&BlockHeight::new(1), &block_id.into()).unwrap();
+    store.set_undo_data(block_id, &block_undo).unwrap();
+    store.set_token_id(&issuance_tx_id, &token_id).unwrap();
+    store.set_storage_version(2).unwrap();
+
+    store.migrate().unwrap();
+
+    let mut expected_stats = TokenStats::new(BlockHeight::new(1));
+    expected_stats
+        .apply_transaction(Amount::ZERO, amount_to_issue, Amount::ZERO)
+        .unwrap();
+    assert_eq!(store.get_storage_version(), Ok(CURRENT_STORAGE_VERSION));
+    assert_eq!(store.get_token_stats(&token_id), Ok(Some(expected_stats)));
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn migrate_address_index_flag_from_v3(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+
+    // The flag isn't set for an empty database, so it's taken from the config on the first start
+    let mut store = TestStore::new_empty().unwrap();
+    store.set_storage_version(3).unwrap();
+    store.migrate().unwrap();
+    assert_eq!(store.get_storage_version(), Ok(CURRENT_STORAGE_VERSION));
+    assert_eq!(store.get_is_address_index_enabled(), Ok(None));
+
+    // The chain stored before the address index existed is not indexed
+    let mut store = TestStore::new_empty().unwrap();
+    store.set_best_block_id(&Id::new(H256::random_using(&mut rng))).unwrap();
+    store.set_storage_version(3).unwrap();
+    store.migrate().unwrap();
+    assert_eq!(store.get_is_address_index_enabled(), Ok(Some(false)));
+
+    // The flag stored by a node that already had the address index is kept
+    let mut store = TestStore::new_empty().unwrap();
+    store.set_best_block_id(&Id::new(H256::random_using(&mut rng))).unwrap();
+    store.set_is_address_index_enabled(true).unwrap();
+    store.set_storage_version(3).unwrap();
+    store.migrate().unwrap();
+    assert_eq!(store.get_is_address_index_enabled(), Ok(Some(true)));
+}
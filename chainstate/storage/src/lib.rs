@@ -20,6 +20,7 @@ mod is_transaction_seal;
 #[cfg(any(test, feature = "mock"))]
 pub mod mock;
 pub mod schema;
+pub mod token_stats;
 
 use std::collections::BTreeMap;
 
@@ -29,9 +30,11 @@ pub use internal::Store;
 use chainstate_types::{BlockIndex, EpochStorageRead, EpochStorageWrite};
 use common::chain::block::BlockReward;
 use common::chain::config::EpochIndex;
-use common::chain::tokens::{TokenAuxiliaryData, TokenId};
-use common::chain::transaction::{Transaction, TxMainChainIndex, TxMainChainPosition};
-use common::chain::{AccountNonce, AccountType, Block, GenBlock, OutPointSourceId};
+use common::chain::tokens::{TokenAuxiliaryData, TokenId, TokenStats, TokensBlockUndo};
+use common::chain::transaction::{
+    Spender, Transaction, TxMainChainIndex, TxMainChainPosition, UtxoOutPoint,
+};
+use common::chain::{AccountNonce, AccountType, Block, Destination, GenBlock, OutPointSourceId};
 use common::primitives::{BlockHeight, Id};
 use pos_accounting::{
     AccountingBlockUndo, DeltaMergeUndo, PoSAccountingDeltaData, PoSAccountingStorageRead,
@@ -89,6 +92,18 @@ pub trait BlockchainStorageRead:
         tx_index: &TxMainChainPosition,
     ) -> crate::Result<Option<Transaction>>;
 
+    fn get_is_address_index_enabled(&self) -> crate::Result<Option<bool>>;
+
+    /// Get all the mainchain outputs ever created for given destination,
+    /// along with the height of the block that created them
+    fn get_address_outpoints(
+        &self,
+        destination: &Destination,
+    ) -> crate::Result<BTreeMap<UtxoOutPoint, BlockHeight>>;
+
+    /// Get the mainchain spender of given output
+    fn get_spent_by(&self, outpoint: &UtxoOutPoint) -> crate::Result<Option<Spender>>;
+
     /// Get mainchain block by its height
     fn get_block_id_by_height(&self, height: &BlockHeight) -> crate::Result<Option<Id<GenBlock>>>;
 
@@ -98,12 +113,25 @@ pub trait BlockchainStorageRead:
     /// Get token id by id of the creation tx
     fn get_token_id(&self, tx_id: &Id<Transaction>) -> crate::Result<Option<TokenId>>;
 
+    /// Get token amounts aggregated over the mainchain
+    fn get_token_stats(&self, token_id: &TokenId) -> crate::Result<Option<TokenStats>>;
+
+    /// Get up to `count` ids of issued tokens in ascending order, starting after given id
+    fn get_token_ids(
+        &self,
+        start_after: Option<TokenId>,
+        count: usize,
+    ) -> crate::Result<Vec<TokenId>>;
+
     /// Get block tree as height vs ids
     fn get_block_tree_by_height(
         &self,
         start_from: BlockHeight,
     ) -> crate::Result<BTreeMap<BlockHeight, Vec<Id<Block>>>>;
 
+    /// Get tokens undo for specific block
+    fn get_tokens_undo(&self, id: Id<Block>) -> crate::Result<Option<TokensBlockUndo>>;
+
     /// Get accounting undo for specific block
     fn get_accounting_undo(&self, id: Id<Block>) -> crate::Result<Option<AccountingBlockUndo>>;
 
@@ -159,6 +187,30 @@ pub trait BlockchainStorageWrite:
     /// Delete outputs state index associated with given transaction
     fn del_mainchain_tx_index(&mut self, tx_id: &OutPointSourceId) -> Result<()>;
 
+    /// Change address indexing state flag
+    fn set_is_address_index_enabled(&mut self, enabled: bool) -> Result<()>;
+
+    /// Record an output created for given destination at given height
+    fn set_address_outpoint(
+        &mut self,
+        destination: &Destination,
+        outpoint: &UtxoOutPoint,
+        height: BlockHeight,
+    ) -> Result<()>;
+
+    /// Remove an output record of given destination
+    fn del_address_outpoint(
+        &mut self,
+        destination: &Destination,
+        outpoint: &UtxoOutPoint,
+    ) -> Result<()>;
+
+    /// Set the spender of given output
+    fn set_spent_by(&mut self, outpoint: &UtxoOutPoint, spender: &Spender) -> Result<()>;
+
+    /// Remove the spender of given output
+    fn del_spent_by(&mut self, outpoint: &UtxoOutPoint) -> Result<()>;
+
     /// Set the mainchain block at given height to be given block.
     fn set_block_id_at_height(
         &mut self,
@@ -181,6 +233,18 @@ pub trait BlockchainStorageWrite:
     // Remove token id
     fn del_token_id(&mut self, issuance_tx_id: &Id<Transaction>) -> Result<()>;
 
+    /// Set token amounts aggregated over the mainchain
+    fn set_token_stats(&mut self, token_id: &TokenId, stats: &TokenStats) -> Result<()>;
+
+    /// Remove token amounts aggregated over the mainchain
+    fn del_token_stats(&mut self, token_id: &TokenId) -> Result<()>;
+
+    // Set tokens block undo data for specific block
+    fn set_tokens_undo_data(&mut self, id: Id<Block>, undo: &TokensBlockUndo) -> Result<()>;
+
+    // Remove tokens block undo data for specific block
+    fn del_tokens_undo_data(&mut self, id: Id<Block>) -> Result<()>;
+
     // Set accounting block undo data for specific block
     fn set_accounting_undo_data(&mut self, id: Id<Block>, undo: &AccountingBlockUndo)
         -> Result<()>;
@@ -19,13 +19,16 @@ use std::collections::BTreeMap;
 
 use chainstate_types::{BlockIndex, EpochData, EpochStorageRead, EpochStorageWrite};
 use common::chain::block::signed_block_header::SignedBlockHeader;
-use common::chain::tokens::{TokenAuxiliaryData, TokenId};
+use common::chain::tokens::{TokenAuxiliaryData, TokenId, TokenStats, TokensBlockUndo};
 use common::{
     chain::{
         block::BlockReward,
         config::EpochIndex,
-        transaction::{OutPointSourceId, Transaction, TxMainChainIndex, TxMainChainPosition},
-        AccountNonce, AccountType, Block, DelegationId, GenBlock, PoolId, UtxoOutPoint,
+        transaction::{
+            OutPointSourceId, Spender, Transaction, TxMainChainIndex, TxMainChainPosition,
+        },
+        AccountNonce, AccountType, Block, DelegationId, Destination, GenBlock, PoolId,
+        UtxoOutPoint,
     },
     primitives::{Amount, BlockHeight, Id},
 };
@@ -62,6 +65,13 @@ mockall::mock! {
             tx_index: &TxMainChainPosition,
         ) -> crate::Result<Option<Transaction>>;
 
+        fn get_is_address_index_enabled(&self) -> crate::Result<Option<bool>>;
+        fn get_address_outpoints(
+            &self,
+            destination: &Destination,
+        ) -> crate::Result<BTreeMap<UtxoOutPoint, BlockHeight>>;
+        fn get_spent_by(&self, outpoint: &UtxoOutPoint) -> crate::Result<Option<Spender>>;
+
         fn get_block_id_by_height(
             &self,
             height: &BlockHeight,
@@ -70,12 +80,19 @@ mockall::mock! {
         fn get_token_aux_data(&self, token_id: &TokenId) -> crate::Result<Option<TokenAuxiliaryData>>;
 
         fn get_token_id(&self, tx_id: &Id<Transaction>) -> crate::Result<Option<TokenId>>;
+        fn get_token_stats(&self, token_id: &TokenId) -> crate::Result<Option<TokenStats>>;
+        fn get_token_ids(
+            &self,
+            start_after: Option<TokenId>,
+            count: usize,
+        ) -> crate::Result<Vec<TokenId>>;
 
         fn get_block_tree_by_height(
             &self,
             start_from: BlockHeight,
         ) -> crate::Result<BTreeMap<BlockHeight, Vec<Id<Block>>>>;
 
+        fn get_tokens_undo(&self, id: Id<Block>) -> crate::Result<Option<TokensBlockUndo>>;
         fn get_accounting_undo(&self, id: Id<Block>) -> crate::Result<Option<AccountingBlockUndo>>;
 
         fn get_accounting_epoch_delta(
@@ -160,6 +177,21 @@ mockall::mock! {
         ) -> crate::Result<()>;
         fn del_mainchain_tx_index(&mut self, tx_id: &OutPointSourceId) -> crate::Result<()>;
 
+        fn set_is_address_index_enabled(&mut self, enabled: bool) -> crate::Result<()>;
+        fn set_address_outpoint(
+            &mut self,
+            destination: &Destination,
+            outpoint: &UtxoOutPoint,
+            height: BlockHeight,
+        ) -> crate::Result<()>;
+        fn del_address_outpoint(
+            &mut self,
+            destination: &Destination,
+            outpoint: &UtxoOutPoint,
+        ) -> crate::Result<()>;
+        fn set_spent_by(&mut self, outpoint: &UtxoOutPoint, spender: &Spender) -> crate::Result<()>;
+        fn del_spent_by(&mut self, outpoint: &UtxoOutPoint) -> crate::Result<()>;
+
         fn set_block_id_at_height(
             &mut self,
             height: &BlockHeight,
@@ -172,7 +204,11 @@ mockall::mock! {
         fn del_token_aux_data(&mut self, token_id: &TokenId) -> crate::Result<()>;
         fn set_token_id(&mut self, issuance_tx_id: &Id<Transaction>, token_id: &TokenId) -> crate::Result<()>;
         fn del_token_id(&mut self, issuance_tx_id: &Id<Transaction>) -> crate::Result<()>;
+        fn set_token_stats(&mut self, token_id: &TokenId, stats: &TokenStats) -> crate::Result<()>;
+        fn del_token_stats(&mut self, token_id: &TokenId) -> crate::Result<()>;
 
+        fn set_tokens_undo_data(&mut self, id: Id<Block>, undo: &TokensBlockUndo) -> crate::Result<()>;
+        fn del_tokens_undo_data(&mut self, id: Id<Block>) -> crate::Result<()>;
         fn set_accounting_undo_data(&mut self, id: Id<Block>, undo: &AccountingBlockUndo) -> crate::Result<()>;
         fn del_accounting_undo_data(&mut self, id: Id<Block>) -> crate::Result<()>;
 
@@ -314,6 +350,13 @@ mockall::mock! {
             tx_index: &TxMainChainPosition,
         ) -> crate::Result<Option<Transaction>>;
 
+        fn get_is_address_index_enabled(&self) -> crate::Result<Option<bool>>;
+        fn get_address_outpoints(
+            &self,
+            destination: &Destination,
+        ) -> crate::Result<BTreeMap<UtxoOutPoint, BlockHeight>>;
+        fn get_spent_by(&self, outpoint: &UtxoOutPoint) -> crate::Result<Option<Spender>>;
+
         fn get_block_id_by_height(
             &self,
             height: &BlockHeight,
@@ -321,11 +364,18 @@ mockall::mock! {
 
         fn get_token_aux_data(&self, token_id: &TokenId) -> crate::Result<Option<TokenAuxiliaryData>>;
         fn get_token_id(&self, tx_id: &Id<Transaction>) -> crate::Result<Option<TokenId>>;
+        fn get_token_stats(&self, token_id: &TokenId) -> crate::Result<Option<TokenStats>>;
+        fn get_token_ids(
+            &self,
+            start_after: Option<TokenId>,
+            count: usize,
+        ) -> crate::Result<Vec<TokenId>>;
         fn get_block_tree_by_height(
             &self,
             start_from: BlockHeight,
         ) -> crate::Result<BTreeMap<BlockHeight, Vec<Id<Block>>>>;
 
+        fn get_tokens_undo(&self, id: Id<Block>) -> crate::Result<Option<TokensBlockUndo>>;
         fn get_accounting_undo(&self, id: Id<Block>) -> crate::Result<Option<AccountingBlockUndo>>;
 
         fn get_accounting_epoch_delta(
@@ -427,6 +477,13 @@ mockall::mock! {
             tx_index: &TxMainChainPosition,
         ) -> crate::Result<Option<Transaction>>;
 
+        fn get_is_address_index_enabled(&self) -> crate::Result<Option<bool>>;
+        fn get_address_outpoints(
+            &self,
+            destination: &Destination,
+        ) -> crate::Result<BTreeMap<UtxoOutPoint, BlockHeight>>;
+        fn get_spent_by(&self, outpoint: &UtxoOutPoint) -> crate::Result<Option<Spender>>;
+
         fn get_block_id_by_height(
             &self,
             height: &BlockHeight,
@@ -434,11 +491,18 @@ mockall::mock! {
 
         fn get_token_aux_data(&self, token_id: &TokenId) -> crate::Result<Option<TokenAuxiliaryData>>;
         fn get_token_id(&self, tx_id: &Id<Transaction>) -> crate::Result<Option<TokenId>>;
+        fn get_token_stats(&self, token_id: &TokenId) -> crate::Result<Option<TokenStats>>;
+        fn get_token_ids(
+            &self,
+            start_after: Option<TokenId>,
+            count: usize,
+        ) -> crate::Result<Vec<TokenId>>;
         fn get_block_tree_by_height(
             &self,
             start_from: BlockHeight,
         ) -> crate::Result<BTreeMap<BlockHeight, Vec<Id<Block>>>>;
 
+        fn get_tokens_undo(&self, id: Id<Block>) -> crate::Result<Option<TokensBlockUndo>>;
         fn get_accounting_undo(&self, id: Id<Block>) -> crate::Result<Option<AccountingBlockUndo>>;
 
         fn get_accounting_epoch_delta(
@@ -524,6 +588,21 @@ mockall::mock! {
         ) -> crate::Result<()>;
         fn del_mainchain_tx_index(&mut self, tx_id: &OutPointSourceId) -> crate::Result<()>;
 
+        fn set_is_address_index_enabled(&mut self, enabled: bool) -> crate::Result<()>;
+        fn set_address_outpoint(
+            &mut self,
+            destination: &Destination,
+            outpoint: &UtxoOutPoint,
+            height: BlockHeight,
+        ) -> crate::Result<()>;
+        fn del_address_outpoint(
+            &mut self,
+            destination: &Destination,
+            outpoint: &UtxoOutPoint,
+        ) -> crate::Result<()>;
+        fn set_spent_by(&mut self, outpoint: &UtxoOutPoint, spender: &Spender) -> crate::Result<()>;
+        fn del_spent_by(&mut self, outpoint: &UtxoOutPoint) -> crate::Result<()>;
+
         fn set_block_id_at_height(
             &mut self,
             height: &BlockHeight,
@@ -536,7 +615,11 @@ mockall::mock! {
 
         fn set_token_id(&mut self, issuance_tx_id: &Id<Transaction>, token_id: &TokenId) -> crate::Result<()>;
         fn del_token_id(&mut self, issuance_tx_id: &Id<Transaction>) -> crate::Result<()>;
+        fn set_token_stats(&mut self, token_id: &TokenId, stats: &TokenStats) -> crate::Result<()>;
+        fn del_token_stats(&mut self, token_id: &TokenId) -> crate::Result<()>;
 
+        fn set_tokens_undo_data(&mut self, id: Id<Block>, undo: &TokensBlockUndo) -> crate::Result<()>;
+        fn del_tokens_undo_data(&mut self, id: Id<Block>) -> crate::Result<()>;
         fn set_accounting_undo_data(&mut self, id: Id<Block>, undo: &AccountingBlockUndo) -> crate::Result<()>;
         fn del_accounting_undo_data(&mut self, id: Id<Block>) -> crate::Result<()>;
 
@@ -19,9 +19,9 @@ use chainstate_types::{BlockIndex, EpochData};
 use common::{
     chain::{
         config::EpochIndex,
-        tokens::{TokenAuxiliaryData, TokenId},
-        AccountNonce, AccountType, Block, DelegationId, GenBlock, OutPointSourceId, PoolId,
-        Transaction, TxMainChainIndex, UtxoOutPoint,
+        tokens::{TokenAuxiliaryData, TokenId, TokenStats, TokensBlockUndo},
+        AccountNonce, AccountType, Block, DelegationId, Destination, GenBlock, OutPointSourceId,
+        PoolId, Spender, Transaction, TxMainChainIndex, UtxoOutPoint,
     },
     primitives::{Amount, BlockHeight, Id},
 };
@@ -41,6 +41,10 @@ storage::decl_schema! {
         pub DBBlockIndex: Map<Id<Block>, BlockIndex>,
         /// Storage for transaction indices.
         pub DBTxIndex: Map<OutPointSourceId, TxMainChainIndex>,
+        /// Storage for outputs created for a destination, with the height they were created at.
+        pub DBAddressIndex: Map<(Destination, UtxoOutPoint), BlockHeight>,
+        /// Storage for the spender of every spent output.
+        pub DBSpentByIndex: Map<UtxoOutPoint, Spender>,
         /// Storage for block IDs indexed by block height.
         pub DBBlockByHeight: Map<BlockHeight, Id<GenBlock>>,
         /// Store for Utxo Entries
@@ -53,6 +57,10 @@ storage::decl_schema! {
         pub DBTokensAuxData: Map<TokenId, TokenAuxiliaryData>,
         /// Store of issuance tx id vs token id
         pub DBIssuanceTxVsTokenId: Map<Id<Transaction>, TokenId>,
+        /// Store for token's amounts aggregated over the mainchain
+        pub DBTokenStats: Map<TokenId, TokenStats>,
+        /// Store for tokens BlockUndo
+        pub DBTokensBlockUndo: Map<Id<Block>, TokensBlockUndo>,
         /// Store the number of transactions per account
         pub DBAccountNonceCount: Map<AccountType, AccountNonce>,
 
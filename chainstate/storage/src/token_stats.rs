@@ -0,0 +1,268 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+
+use crate::{BlockchainStorageRead, BlockchainStorageWrite};
+use common::{
+    chain::{
+        tokens::{
+            get_tokens_issuance_count, output_token_data, token_id, TokenData, TokenId, TokenStats,
+        },
+        Block, OutPointSourceId, Transaction, TxInput, TxOutput,
+    },
+    primitives::{id::WithId, Amount, BlockHeight, Id, Idable},
+};
+use thiserror::Error;
+use utxo::{UtxosBlockUndo, UtxosStorageRead, UtxosTxUndo};
+
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+pub enum TokenStatsError {
+    #[error("Block storage error: `{0}`")]
+    StorageError(#[from] crate::Error),
+    #[error("Utxo undo data of block {0} not found")]
+    BlockUndoNotFound(Id<Block>),
+    #[error("Utxo undo data of transaction {0} not found")]
+    TxUndoNotFound(Id<Transaction>),
+    #[error("Token id of the issuance in {0:?} not found")]
+    IssuanceTokenIdNotFound(OutPointSourceId),
+    #[error("Stats of token {0} not found")]
+    StatsNotFound(TokenId),
+    #[error("Amounts of token {0} are inconsistent")]
+    InconsistentAmounts(TokenId),
+}
+
+/// Token amounts moved by a single transaction
+struct TxTokenAmounts {
+    /// Held by the spent outputs
+    spent: Amount,
+    /// Held by the new outputs, except for the burned ones
+    created: Amount,
+    /// Brought into existence by issuance and reissuance
+    issued: Amount,
+}
+
+impl TxTokenAmounts {
+    fn new() -> Self {
+        Self {
+            spent: Amount::ZERO,
+            created: Amount::ZERO,
+            issued: Amount::ZERO,
+        }
+    }
+
+    fn burned(&self) -> Option<Amount> {
+        (self.spent + self.issued)? - self.created
+    }
+}
+
+/// The token and its amount held by an output with given token data
+fn token_amount(
+    token_data: &TokenData,
+    issuance_token_id: impl FnOnce() -> Result<TokenId, TokenStatsError>,
+) -> Result<Option<(TokenId, Amount)>, TokenStatsError> {
+    let result = match token_data {
+        TokenData::TokenTransfer(transfer) => Some((transfer.token_id, transfer.amount)),
+        TokenData::TokenIssuance(issuance) => {
+            Some((issuance_token_id()?, issuance.amount_to_issue))
+        }
+        TokenData::TokenIssuanceV1(issuance) => {
+            Some((issuance_token_id()?, issuance.amount_to_issue))
+        }
+        TokenData::NftIssuance(_) => Some((issuance_token_id()?, Amount::from_atoms(1))),
+        TokenData::TokenReissueV1 {
+            token_id,
+            amount_to_issue,
+        } => Some((*token_id, *amount_to_issue)),
+        TokenData::LockTokenSupplyV1 { .. }
+        | TokenData::ChangeTokenAuthorityV1 { .. }
+        | TokenData::FreezeTokenV1 { .. }
+        | TokenData::UnfreezeTokenV1 { .. } => None,
+    };
+    Ok(result)
+}
+
+fn is_new_supply(token_data: &TokenData) -> bool {
+    match token_data {
+        TokenData::TokenIssuance(_)
+        | TokenData::TokenIssuanceV1(_)
+        | TokenData::NftIssuance(_)
+        | TokenData::TokenReissueV1 { .. } => true,
+        TokenData::TokenTransfer(_)
+        | TokenData::LockTokenSupplyV1 { .. }
+        | TokenData::ChangeTokenAuthorityV1 { .. }
+        | TokenData::FreezeTokenV1 { .. }
+        | TokenData::UnfreezeTokenV1 { .. } => false,
+    }
+}
+
+fn add_amount(
+    total: &mut Amount,
+    amount: Amount,
+    token_id: TokenId,
+) -> Result<(), TokenStatsError> {
+    *total = (*total + amount).ok_or(TokenStatsError::InconsistentAmounts(token_id))?;
+    Ok(())
+}
+
+fn tx_token_amounts<S: BlockchainStorageRead>(
+    db_tx: &S,
+    tx: &Transaction,
+    tx_undo: &UtxosTxUndo,
+) -> Result<BTreeMap<TokenId, TxTokenAmounts>, TokenStatsError> {
+    let mut amounts = BTreeMap::<TokenId, TxTokenAmounts>::new();
+
+    // The undo data holds the spent utxos in the order of the inputs
+    for (input, utxo) in tx.inputs().iter().zip(tx_undo.inner()) {
+        let (outpoint, utxo) = match (input, utxo) {
+            (TxInput::Utxo(outpoint), Some(utxo)) => (outpoint, utxo),
+            (TxInput::Utxo(_), None) | (TxInput::Account(_), _) => continue,
+        };
+        let token_data = match output_token_data(utxo.output()) {
+            Some(token_data) => token_data,
+            None => continue,
+        };
+
+        let issuance_token_id = || -> Result<TokenId, TokenStatsError> {
+            let source_id = outpoint.tx_id();
+            source_id
+                .get_tx_id()
+                .map(|tx_id| db_tx.get_token_id(tx_id))
+                .transpose()?
+                .flatten()
+                .ok_or(TokenStatsError::IssuanceTokenIdNotFound(source_id))
+        };
+        if let Some((token_id, amount)) = token_amount(token_data, issuance_token_id)? {
+            let entry = amounts.entry(token_id).or_insert_with(TxTokenAmounts::new);
+            add_amount(&mut entry.spent, amount, token_id)?;
+        }
+    }
+
+    for output in tx.outputs() {
+        let token_data = match output_token_data(output) {
+            Some(token_data) => token_data,
+            None => continue,
+        };
+
+        let issuance_token_id =
+            || token_id(tx).ok_or(TokenStatsError::IssuanceTokenIdNotFound(tx.get_id().into()));
+        if let Some((token_id, amount)) = token_amount(token_data, issuance_token_id)? {
+            let entry = amounts.entry(token_id).or_insert_with(TxTokenAmounts::new);
+            if is_new_supply(token_data) {
+                add_amount(&mut entry.issued, amount, token_id)?;
+            }
+            if !matches!(output, TxOutput::Burn(_)) {
+                add_amount(&mut entry.created, amount, token_id)?;
+            }
+        }
+    }
+
+    Ok(amounts)
+}
+
+/// The token issued by the transaction, if any
+fn issued_token_id(tx: &Transaction) -> Option<TokenId> {
+    (get_tokens_issuance_count(tx.outputs()) > 0).then(|| token_id(tx)).flatten()
+}
+
+fn get_block_undo<S: BlockchainStorageRead>(
+    db_tx: &S,
+    block_id: Id<Block>,
+) -> Result<UtxosBlockUndo, TokenStatsError> {
+    db_tx
+        .get_undo_data(block_id)?
+        .ok_or(TokenStatsError::BlockUndoNotFound(block_id))
+}
+
+/// Update the stats of the tokens moved by a block that became part of the mainchain.
+/// Must be called after the transactions of the block are connected.
+pub fn connect_block<S: BlockchainStorageWrite>(
+    db_tx: &mut S,
+    block: &WithId<Block>,
+    height: BlockHeight,
+) -> Result<(), TokenStatsError> {
+    // Undo data isn't stored for blocks without transactions
+    if block.transactions().is_empty() {
+        return Ok(());
+    }
+    let block_undo = get_block_undo(&*db_tx, block.get_id())?;
+
+    for tx in block.transactions() {
+        let tx = tx.transaction();
+        let tx_undo = block_undo
+            .tx_undos()
+            .get(&tx.get_id())
+            .ok_or(TokenStatsError::TxUndoNotFound(tx.get_id()))?;
+        let issued_token_id = issued_token_id(tx);
+
+        for (token_id, amounts) in tx_token_amounts(&*db_tx, tx, tx_undo)? {
+            let mut stats = if issued_token_id == Some(token_id) {
+                TokenStats::new(height)
+            } else {
+                db_tx
+                    .get_token_stats(&token_id)?
+                    .ok_or(TokenStatsError::StatsNotFound(token_id))?
+            };
+
+            let burned = amounts.burned().ok_or(TokenStatsError::InconsistentAmounts(token_id))?;
+            stats
+                .apply_transaction(amounts.spent, amounts.created, burned)
+                .ok_or(TokenStatsError::InconsistentAmounts(token_id))?;
+            db_tx.set_token_stats(&token_id, &stats)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Revert the changes made by `connect_block` for a block that is removed from the mainchain.
+/// Must be called before the transactions of the block are disconnected.
+pub fn disconnect_block<S: BlockchainStorageWrite>(
+    db_tx: &mut S,
+    block: &WithId<Block>,
+) -> Result<(), TokenStatsError> {
+    // Undo data isn't stored for blocks without transactions
+    if block.transactions().is_empty() {
+        return Ok(());
+    }
+    let block_undo = get_block_undo(&*db_tx, block.get_id())?;
+
+    for tx in block.transactions().iter().rev() {
+        let tx = tx.transaction();
+        let tx_undo = block_undo
+            .tx_undos()
+            .get(&tx.get_id())
+            .ok_or(TokenStatsError::TxUndoNotFound(tx.get_id()))?;
+        let issued_token_id = issued_token_id(tx);
+
+        for (token_id, amounts) in tx_token_amounts(&*db_tx, tx, tx_undo)? {
+            if issued_token_id == Some(token_id) {
+                db_tx.del_token_stats(&token_id)?;
+                continue;
+            }
+
+            let mut stats = db_tx
+                .get_token_stats(&token_id)?
+                .ok_or(TokenStatsError::StatsNotFound(token_id))?;
+            let burned = amounts.burned().ok_or(TokenStatsError::InconsistentAmounts(token_id))?;
+            stats
+                .undo_transaction(amounts.spent, amounts.created, burned)
+                .ok_or(TokenStatsError::InconsistentAmounts(token_id))?;
+            db_tx.set_token_stats(&token_id, &stats)?;
+        }
+    }
+
+    Ok(())
+}
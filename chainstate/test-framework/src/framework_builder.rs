@@ -56,6 +56,8 @@ impl TestFrameworkBuilder {
     pub fn new(rng: &mut (impl Rng + CryptoRng)) -> Self {
         let chain_config = ChainConfigBuilder::new(ChainType::Mainnet)
             .net_upgrades(NetUpgrades::unit_tests())
+            .token_upgrades(NetUpgrades::all_token_versions())
+            .script_hash_upgrades(NetUpgrades::script_hash_spendable())
             .genesis_unittest(Destination::AnyoneCanSpend)
             .build();
         let chainstate_config = ChainstateConfig {
@@ -63,6 +65,7 @@ impl TestFrameworkBuilder {
             max_orphan_blocks: Default::default(),
             min_max_bootstrap_import_buffer_sizes: Default::default(),
             tx_index_enabled: rng.gen::<bool>().into(),
+            address_index_enabled: rng.gen::<bool>().into(),
             max_tip_age: Default::default(),
         };
         let chainstate_storage = TestStore::new_empty().unwrap();
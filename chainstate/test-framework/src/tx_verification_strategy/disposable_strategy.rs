@@ -33,7 +33,7 @@ use tx_verifier::{
     },
     TransactionSource,
 };
-use utils::tap_error_log::LogError;
+use utils::{ensure, tap_error_log::LogError};
 use utxo::UtxosView;
 
 /// Strategy that creates separate instances of TransactionVerifier on every tx, flushing the
@@ -74,6 +74,7 @@ impl TransactionVerificationStrategy for DisposableTransactionVerificationStrate
     {
         let block_subsidy =
             chain_config.as_ref().block_subsidy_at_height(&block_index.block_height());
+        let max_block_sigops = chain_config.as_ref().max_block_sigops();
 
         let mut tx_indices = construct_tx_indices(&verifier_config, block)?;
         let block_reward_tx_index = construct_reward_tx_indices(&verifier_config, block)?;
@@ -81,11 +82,12 @@ impl TransactionVerificationStrategy for DisposableTransactionVerificationStrate
         let mut base_tx_verifier =
             tx_verifier_maker(storage_backend, chain_config, verifier_config);
 
-        let total_fees = block
+        let (total_fees, _total_sigops) = block
             .transactions()
             .iter()
-            .try_fold(Amount::from_atoms(0), |total, tx| {
+            .try_fold((Amount::from_atoms(0), 0), |(total, total_sigops), tx| {
                 let mut tx_verifier = base_tx_verifier.derive_child();
+                let sigops = tx_verifier.count_sigops(tx);
                 let fee = tx_verifier
                     .connect_transaction(
                         &TransactionSourceForConnect::Chain {
@@ -99,9 +101,20 @@ impl TransactionVerificationStrategy for DisposableTransactionVerificationStrate
                 let consumed_cache = tx_verifier.consume()?;
                 flush_to_storage(&mut base_tx_verifier, consumed_cache).log_err()?;
 
-                (total + fee.0).ok_or_else(|| {
+                let total_sigops = total_sigops + sigops?;
+                ensure!(
+                    total_sigops <= max_block_sigops,
+                    ConnectTransactionError::TooManySigOpsInBlock(
+                        block.get_id(),
+                        total_sigops,
+                        max_block_sigops
+                    )
+                );
+
+                let total = (total + fee.0).ok_or_else(|| {
                     ConnectTransactionError::FailedToAddAllFeesOfBlock(block.get_id())
-                })
+                })?;
+                Ok((total, total_sigops))
             })
             .log_err()?;
 
@@ -114,6 +127,7 @@ impl TransactionVerificationStrategy for DisposableTransactionVerificationStrate
                 block_index,
                 block.block_reward_transactable(),
                 Fee(total_fees),
+                &median_time_past,
                 block_reward_tx_index,
             )
             .log_err()?;
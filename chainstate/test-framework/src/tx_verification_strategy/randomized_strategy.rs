@@ -37,7 +37,7 @@ use tx_verifier::{
     },
     TransactionSource,
 };
-use utils::tap_error_log::LogError;
+use utils::{ensure, tap_error_log::LogError};
 use utxo::UtxosView;
 
 ///
@@ -153,17 +153,19 @@ impl RandomizedTransactionVerificationStrategy {
     {
         let block_subsidy =
             chain_config.as_ref().block_subsidy_at_height(&block_index.block_height());
+        let max_block_sigops = chain_config.as_ref().max_block_sigops();
         let mut tx_indices = construct_tx_indices(&verifier_config, block)?;
         let block_reward_tx_index = construct_reward_tx_indices(&verifier_config, block)?;
 
         let mut tx_verifier = tx_verifier_maker(storage_backend, chain_config, verifier_config);
 
         let mut total_fees = Amount::ZERO;
+        let mut total_sigops = 0usize;
         let mut tx_num = 0usize;
         while tx_num < block.transactions().len() {
             if self.rng.borrow_mut().gen::<bool>() {
                 // derive a new cache
-                let (consumed_cache, fee, new_tx_index) = self.connect_with_derived(
+                let (consumed_cache, fee, sigops, new_tx_index) = self.connect_with_derived(
                     &tx_verifier,
                     block,
                     block_index,
@@ -175,6 +177,7 @@ impl RandomizedTransactionVerificationStrategy {
                 total_fees = (total_fees + fee).ok_or_else(|| {
                     ConnectTransactionError::FailedToAddAllFeesOfBlock(block.get_id())
                 })?;
+                total_sigops = add_block_sigops(block, total_sigops, sigops, max_block_sigops)?;
 
                 flush_to_storage(&mut tx_verifier, consumed_cache)
                     .map_err(ConnectTransactionError::from)?;
@@ -182,6 +185,7 @@ impl RandomizedTransactionVerificationStrategy {
             } else {
                 // connect transactable using current verifier
 
+                let sigops = tx_verifier.count_sigops(&block.transactions()[tx_num]);
                 tx_verifier.connect_transaction(
                     &TransactionSourceForConnect::Chain {
                         new_block_index: block_index,
@@ -190,6 +194,7 @@ impl RandomizedTransactionVerificationStrategy {
                     median_time_past,
                     take_front_tx_index(&mut tx_indices),
                 )?;
+                total_sigops = add_block_sigops(block, total_sigops, sigops?, max_block_sigops)?;
                 tx_num += 1;
             }
         }
@@ -203,6 +208,7 @@ impl RandomizedTransactionVerificationStrategy {
                 block_index,
                 block.block_reward_transactable(),
                 Fee(total_fees),
+                median_time_past,
                 block_reward_tx_index,
             )
             .log_err()?;
@@ -218,7 +224,7 @@ impl RandomizedTransactionVerificationStrategy {
         median_time_past: &BlockTimestamp,
         tx_indices: &mut Option<VecDeque<TxMainChainIndex>>,
         mut tx_num: usize,
-    ) -> Result<(TransactionVerifierDelta, Amount, usize), ConnectTransactionError>
+    ) -> Result<(TransactionVerifierDelta, Amount, usize, usize), ConnectTransactionError>
     where
         C: AsRef<ChainConfig>,
         U: UtxosView,
@@ -228,12 +234,14 @@ impl RandomizedTransactionVerificationStrategy {
     {
         let mut tx_verifier = base_tx_verifier.derive_child();
         let mut total_fees = Amount::ZERO;
+        let mut total_sigops = 0usize;
         while tx_num < block.transactions().len() {
             if self.rng.borrow_mut().gen::<bool>() {
                 // break the loop, which effectively would flush current state to the parent
                 break;
             } else {
                 // connect transactable using current verifier
+                let sigops = tx_verifier.count_sigops(&block.transactions()[tx_num]);
                 let fee = tx_verifier.connect_transaction(
                     &TransactionSourceForConnect::Chain {
                         new_block_index: block_index,
@@ -246,11 +254,12 @@ impl RandomizedTransactionVerificationStrategy {
                 total_fees = (total_fees + fee.0).ok_or_else(|| {
                     ConnectTransactionError::FailedToAddAllFeesOfBlock(block.get_id())
                 })?;
+                total_sigops += sigops?;
                 tx_num += 1;
             }
         }
         let cache = tx_verifier.consume()?;
-        Ok((cache, total_fees, tx_num))
+        Ok((cache, total_fees, total_sigops, tx_num))
     }
 
     fn disconnect_with_base<C, S, M, U, A>(
@@ -328,3 +337,21 @@ impl RandomizedTransactionVerificationStrategy {
         Ok((cache, tx_num))
     }
 }
+
+fn add_block_sigops(
+    block: &WithId<Block>,
+    total_sigops: usize,
+    sigops: usize,
+    max_block_sigops: usize,
+) -> Result<usize, ConnectTransactionError> {
+    let total_sigops = total_sigops + sigops;
+    ensure!(
+        total_sigops <= max_block_sigops,
+        ConnectTransactionError::TooManySigOpsInBlock(
+            block.get_id(),
+            total_sigops,
+            max_block_sigops
+        )
+    );
+    Ok(total_sigops)
+}
@@ -104,6 +104,13 @@ pub fn create_utxo_data(
             TokenData::NftIssuance(_issuance) => {
                 new_token_transfer_output(chainstate, &outsrc, Amount::from_atoms(1))
             }
+            // Supply controlled tokens are never created by the random generators
+            TokenData::TokenIssuanceV1(_)
+            | TokenData::TokenReissueV1 { .. }
+            | TokenData::LockTokenSupplyV1 { .. }
+            | TokenData::ChangeTokenAuthorityV1 { .. }
+            | TokenData::FreezeTokenV1 { .. }
+            | TokenData::UnfreezeTokenV1 { .. } => return None,
         },
     };
 
@@ -231,6 +238,13 @@ pub fn create_multiple_utxo_data(
                     vec![new_token_transfer_output(chainstate, &outsrc, Amount::from_atoms(1))]
                 }
             }
+            // Supply controlled tokens are never created by the random generators
+            TokenData::TokenIssuanceV1(_)
+            | TokenData::TokenReissueV1 { .. }
+            | TokenData::LockTokenSupplyV1 { .. }
+            | TokenData::ChangeTokenAuthorityV1 { .. }
+            | TokenData::FreezeTokenV1 { .. }
+            | TokenData::UnfreezeTokenV1 { .. } => return None,
         },
     };
 
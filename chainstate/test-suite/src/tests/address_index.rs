@@ -0,0 +1,132 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use chainstate::{AddressHistoryEntry, ChainstateConfig, ChainstateError, PropertyQueryError};
+use chainstate_test_framework::{
+    anyonecanspend_address, empty_witness, TestFramework, TransactionBuilder,
+};
+use common::{
+    chain::{
+        tokens::OutputValue, Destination, OutPointSourceId, Spender, TxInput, TxOutput,
+        UtxoOutPoint,
+    },
+    primitives::{Amount, Idable},
+};
+use crypto::key::{KeyKind, PrivateKey};
+
+// Produce `genesis -> a` chain where `a` pays to a destination, then a parallel
+// `genesis -> b -> c` chain that triggers a reorg and removes the payment from the indexes.
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn address_index_follows_reorg(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let mut tf = TestFramework::builder(&mut rng)
+            .with_chainstate_config(
+                ChainstateConfig::new()
+                    .with_whether_tx_index_enabled(true)
+                    .with_whether_address_index_enabled(true),
+            )
+            .build();
+        let genesis_id = tf.genesis().get_id();
+
+        let (_, pub_key) = PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+        let destination = Destination::PublicKey(pub_key);
+
+        let genesis_outpoint =
+            UtxoOutPoint::new(OutPointSourceId::BlockReward(genesis_id.into()), 0);
+        let tx = TransactionBuilder::new()
+            .add_input(
+                TxInput::Utxo(genesis_outpoint.clone()),
+                empty_witness(&mut rng),
+            )
+            .add_output(TxOutput::Transfer(
+                OutputValue::Coin(Amount::from_atoms(rng.gen_range(100_000..200_000))),
+                destination.clone(),
+            ))
+            .add_output(TxOutput::Transfer(
+                OutputValue::Coin(Amount::from_atoms(rng.gen_range(100_000..200_000))),
+                anyonecanspend_address(),
+            ))
+            .build();
+        let tx_id = tx.transaction().get_id();
+        let tx_outpoint = UtxoOutPoint::new(tx_id.into(), 0);
+
+        let block_a = tf.make_block_builder().add_transaction(tx.clone()).build();
+        let block_a_id = block_a.get_id();
+        tf.process_block(block_a, BlockSource::Local).unwrap();
+        assert_eq!(tf.best_block_id(), block_a_id);
+
+        assert_eq!(
+            tf.chainstate.get_transaction(&tx_id).unwrap().as_ref(),
+            Some(tx.transaction())
+        );
+        let utxos = tf.chainstate.get_address_utxos(&destination).unwrap();
+        assert_eq!(utxos.len(), 1);
+        assert_eq!(utxos[&tx_outpoint].output(), &tx.outputs()[0]);
+        assert_eq!(
+            tf.chainstate.get_address_history(&destination).unwrap(),
+            vec![AddressHistoryEntry {
+                outpoint: tx_outpoint.clone(),
+                block_height: BlockHeight::new(1),
+                spent_by: None,
+            }]
+        );
+        assert_eq!(
+            tf.chainstate.get_spending_tx(&genesis_outpoint).unwrap(),
+            Some(Spender::RegularInput(tx_id))
+        );
+
+        // Reorg to a longer chain that doesn't include the transaction
+        let block_b = tf.make_block_builder().with_parent(genesis_id.into()).build();
+        let block_b_id = block_b.get_id();
+        tf.process_block(block_b, BlockSource::Local).unwrap();
+        let block_c = tf.make_block_builder().with_parent(block_b_id.into()).build();
+        let block_c_id = block_c.get_id();
+        tf.process_block(block_c, BlockSource::Local).unwrap();
+        assert_eq!(tf.best_block_id(), block_c_id);
+
+        assert_eq!(tf.chainstate.get_transaction(&tx_id).unwrap(), None);
+        assert!(tf.chainstate.get_address_utxos(&destination).unwrap().is_empty());
+        assert!(tf.chainstate.get_address_history(&destination).unwrap().is_empty());
+        assert_eq!(
+            tf.chainstate.get_spending_tx(&genesis_outpoint).unwrap(),
+            None
+        );
+    });
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn address_index_disabled(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let tf = TestFramework::builder(&mut rng)
+            .with_chainstate_config(
+                ChainstateConfig::new().with_whether_address_index_enabled(false),
+            )
+            .build();
+
+        assert_eq!(
+            tf.chainstate.get_address_history(&anyonecanspend_address()),
+            Err(ChainstateError::FailedToReadProperty(
+                PropertyQueryError::AddressIndexDisabled
+            ))
+        );
+    });
+}
@@ -695,6 +695,7 @@ fn reorg_store_coin_no_tx_index(#[case] seed: Seed, #[case] tx_index_enabled: bo
 
             let config_new = chainstate::ChainstateConfig {
                 tx_index_enabled: (!tx_index_enabled).into(),
+                address_index_enabled: Default::default(),
                 max_db_commit_attempts: Default::default(),
                 max_orphan_blocks: Default::default(),
                 min_max_bootstrap_import_buffer_sizes: Default::default(),
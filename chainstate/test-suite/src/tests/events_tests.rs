@@ -214,6 +214,67 @@ fn custom_orphan_error_hook(#[case] seed: Seed) {
     });
 }
 
+// Produce `genesis -> a -> b` chain, then a parallel `genesis -> c -> d -> e` chain and check
+// that switching to it triggers the `Reorg` event followed by the `NewTip` event.
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn reorg_event(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let mut tf = TestFramework::builder(&mut rng).build();
+        let genesis_id = tf.genesis().get_id();
+
+        let block_a = tf.make_block_builder().build();
+        let block_a_id = block_a.get_id();
+        tf.process_block(block_a, BlockSource::Local).unwrap();
+        let block_b = tf.make_block_builder().build();
+        let block_b_id = block_b.get_id();
+        tf.process_block(block_b, BlockSource::Local).unwrap();
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let events_ = Arc::clone(&events);
+        tf.chainstate.subscribe_to_events(Arc::new(move |event: ChainstateEvent| {
+            events_.lock().unwrap().push(event)
+        }));
+
+        let block_c = tf.make_block_builder().with_parent(genesis_id.into()).build();
+        let block_c_id = block_c.get_id();
+        tf.process_block(block_c, BlockSource::Local).unwrap();
+        let block_d = tf.make_block_builder().with_parent(block_c_id.into()).build();
+        let block_d_id = block_d.get_id();
+        tf.process_block(block_d, BlockSource::Local).unwrap();
+        tf.chainstate.wait_for_all_events();
+        assert!(events.lock().unwrap().is_empty());
+
+        let block_e = tf.make_block_builder().with_parent(block_d_id.into()).build();
+        let block_e_id = block_e.get_id();
+        tf.process_block(block_e, BlockSource::Local).unwrap();
+        tf.chainstate.wait_for_all_events();
+        assert_eq!(tf.best_block_id(), block_e_id);
+
+        let guard = events.lock().unwrap();
+        assert_eq!(guard.len(), 2);
+        match &guard[0] {
+            ChainstateEvent::Reorg {
+                disconnected,
+                connected,
+            } => {
+                assert_eq!(disconnected, &vec![block_b_id, block_a_id]);
+                assert_eq!(connected, &vec![block_c_id, block_d_id, block_e_id]);
+            }
+            ChainstateEvent::NewTip(_, _) => panic!("Reorg event expected"),
+        }
+        match &guard[1] {
+            ChainstateEvent::NewTip(id, height) => {
+                assert_eq!(id, &block_e_id);
+                assert_eq!(height, &BlockHeight::new(3));
+            }
+            ChainstateEvent::Reorg { .. } => panic!("NewTip event expected"),
+        }
+    });
+}
+
 // Subscribes to events N times emulating different subscribers.
 fn subscribe(chainstate: &mut TestChainstate, n: usize) -> EventList {
     let events = Arc::new(Mutex::new(Vec::new()));
@@ -224,6 +285,7 @@ fn subscribe(chainstate: &mut TestChainstate, n: usize) -> EventList {
             ChainstateEvent::NewTip(block_id, block_height) => {
                 events_.lock().unwrap().push((block_id, block_height));
             }
+            ChainstateEvent::Reorg { .. } => {}
         });
         chainstate.subscribe_to_events(handler);
     }
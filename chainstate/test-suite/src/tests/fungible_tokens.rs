@@ -20,15 +20,16 @@ use chainstate::{
     ConnectTransactionError, TokensError,
 };
 use chainstate_test_framework::{get_output_value, TestFramework, TransactionBuilder};
-use common::chain::tokens::{Metadata, NftIssuance, TokenIssuance, TokenTransfer};
+use common::chain::tokens::{Metadata, NftIssuance, TokenIssuance, TokenIssuanceV1, TokenTransfer};
 use common::primitives::{id, Id};
 use common::{
     chain::{
+        config::Builder as ConfigBuilder,
         signature::inputsig::InputWitness,
         tokens::{token_id, OutputValue, TokenData, TokenId},
-        Destination, OutPointSourceId, TxInput, TxOutput,
+        Destination, NetUpgrades, OutPointSourceId, TokensUpgrade, TxInput, TxOutput,
     },
-    primitives::{Amount, Idable},
+    primitives::{Amount, BlockHeight, Idable},
 };
 use crypto::{hash::StreamHasher, random::Rng};
 use expect_test::expect;
@@ -1946,3 +1947,454 @@ fn issue_and_transfer_in_the_same_block(#[case] seed: Seed) {
             .unwrap();
     })
 }
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn reissue_lock_and_change_authority(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let mut tf = TestFramework::builder(&mut rng).build();
+
+        let token_min_issuance_fee = tf.chainstate.get_chain_config().token_min_issuance_fee();
+        let initial_amount = Amount::from_atoms(rng.gen_range(1..u64::MAX as u128));
+        let reissued_amount = Amount::from_atoms(rng.gen_range(1..u64::MAX as u128));
+
+        // Issue a token controlled by an anyone-can-spend authority
+        let issuance_tx = TransactionBuilder::new()
+            .add_input(
+                TxInput::from_utxo(tf.genesis().get_id().into(), 0),
+                InputWitness::NoSignature(None),
+            )
+            .add_output(TxOutput::Transfer(
+                TokenIssuanceV1 {
+                    token_ticker: random_string(&mut rng, 1..5).as_bytes().to_vec(),
+                    amount_to_issue: initial_amount,
+                    number_of_decimals: rng.gen_range(1..18),
+                    metadata_uri: random_string(&mut rng, 1..1024).as_bytes().to_vec(),
+                    authority: Destination::AnyoneCanSpend,
+                }
+                .into(),
+                Destination::AnyoneCanSpend,
+            ))
+            .add_output(TxOutput::Burn(OutputValue::Coin(token_min_issuance_fee)))
+            .build();
+        let token_id = token_id(issuance_tx.transaction()).unwrap();
+        let issuance_tx_id = issuance_tx.transaction().get_id();
+        tf.make_block_builder()
+            .add_transaction(issuance_tx)
+            .build_and_process()
+            .unwrap();
+
+        let supply = tf.chainstate.get_token_aux_data(token_id).unwrap().unwrap().supply().cloned();
+        assert_eq!(
+            supply.map(|supply| (supply.total_issued(), supply.is_locked())),
+            Some((initial_amount, false))
+        );
+
+        // Reissue tokens by spending the issuance output which belongs to the authority
+        let reissue_tx = TransactionBuilder::new()
+            .add_input(
+                TxInput::from_utxo(issuance_tx_id.into(), 0),
+                InputWitness::NoSignature(None),
+            )
+            .add_output(TxOutput::Transfer(
+                TokenData::TokenReissueV1 {
+                    token_id,
+                    amount_to_issue: reissued_amount,
+                }
+                .into(),
+                Destination::AnyoneCanSpend,
+            ))
+            .add_output(TxOutput::Transfer(
+                TokenTransfer {
+                    token_id,
+                    amount: initial_amount,
+                }
+                .into(),
+                Destination::AnyoneCanSpend,
+            ))
+            .build();
+        let reissue_tx_id = reissue_tx.transaction().get_id();
+        let reissue_block_index = tf
+            .make_block_builder()
+            .add_transaction(reissue_tx)
+            .build_and_process()
+            .unwrap()
+            .unwrap();
+
+        let total_amount = (initial_amount + reissued_amount).unwrap();
+        let supply = tf.chainstate.get_token_aux_data(token_id).unwrap().unwrap().supply().cloned();
+        assert_eq!(
+            supply.map(|supply| (supply.total_issued(), supply.is_locked())),
+            Some((total_amount, false))
+        );
+
+        // Lock the supply and hand the authority over in the same transaction
+        let (_, new_authority_pk) =
+            crypto::key::PrivateKey::new_from_rng(&mut rng, crypto::key::KeyKind::Secp256k1Schnorr);
+        let new_authority = Destination::PublicKey(new_authority_pk);
+        let lock_tx = TransactionBuilder::new()
+            .add_input(
+                TxInput::from_utxo(reissue_tx_id.into(), 0),
+                InputWitness::NoSignature(None),
+            )
+            .add_output(TxOutput::Transfer(
+                TokenData::LockTokenSupplyV1 { token_id }.into(),
+                Destination::AnyoneCanSpend,
+            ))
+            .add_output(TxOutput::Transfer(
+                TokenData::ChangeTokenAuthorityV1 {
+                    token_id,
+                    new_authority: new_authority.clone(),
+                }
+                .into(),
+                Destination::AnyoneCanSpend,
+            ))
+            .add_output(TxOutput::Transfer(
+                TokenTransfer {
+                    token_id,
+                    amount: reissued_amount,
+                }
+                .into(),
+                Destination::AnyoneCanSpend,
+            ))
+            .build();
+        let lock_tx_id = lock_tx.transaction().get_id();
+        tf.make_block_builder().add_transaction(lock_tx).build_and_process().unwrap();
+
+        let supply = tf.chainstate.get_token_aux_data(token_id).unwrap().unwrap().supply().cloned();
+        assert_eq!(
+            supply.map(|supply| (
+                supply.total_issued(),
+                supply.is_locked(),
+                supply.authority().clone()
+            )),
+            Some((total_amount, true, new_authority))
+        );
+
+        // The old authority can't reissue anymore
+        let result = tf
+            .make_block_builder()
+            .add_transaction(
+                TransactionBuilder::new()
+                    .add_input(
+                        TxInput::from_utxo(lock_tx_id.into(), 2),
+                        InputWitness::NoSignature(None),
+                    )
+                    .add_output(TxOutput::Transfer(
+                        TokenData::TokenReissueV1 {
+                            token_id,
+                            amount_to_issue: reissued_amount,
+                        }
+                        .into(),
+                        Destination::AnyoneCanSpend,
+                    ))
+                    .build(),
+            )
+            .build_and_process();
+        assert!(matches!(
+            result,
+            Err(ChainstateError::ProcessBlockError(BlockError::StateUpdateFailed(
+                ConnectTransactionError::TokensError(TokensError::TokenAuthorityNotProven(id, _))
+            ))) if id == token_id
+        ));
+
+        // Reorg out the lock and the authority change
+        tf.create_chain(&(*reissue_block_index.block_id()).into(), 2, &mut rng).unwrap();
+
+        let supply = tf.chainstate.get_token_aux_data(token_id).unwrap().unwrap().supply().cloned();
+        assert_eq!(
+            supply.map(|supply| (
+                supply.total_issued(),
+                supply.is_locked(),
+                supply.authority().clone()
+            )),
+            Some((total_amount, false, Destination::AnyoneCanSpend))
+        );
+    })
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn freeze_and_unfreeze(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let mut tf = TestFramework::builder(&mut rng).build();
+
+        let token_min_issuance_fee = tf.chainstate.get_chain_config().token_min_issuance_fee();
+        let amount = Amount::from_atoms(rng.gen_range(1..u64::MAX as u128));
+
+        let issuance_tx = TransactionBuilder::new()
+            .add_input(
+                TxInput::from_utxo(tf.genesis().get_id().into(), 0),
+                InputWitness::NoSignature(None),
+            )
+            .add_output(TxOutput::Transfer(
+                TokenIssuanceV1 {
+                    token_ticker: random_string(&mut rng, 1..5).as_bytes().to_vec(),
+                    amount_to_issue: amount,
+                    number_of_decimals: rng.gen_range(1..18),
+                    metadata_uri: random_string(&mut rng, 1..1024).as_bytes().to_vec(),
+                    authority: Destination::AnyoneCanSpend,
+                }
+                .into(),
+                Destination::AnyoneCanSpend,
+            ))
+            .add_output(TxOutput::Burn(OutputValue::Coin(token_min_issuance_fee)))
+            .build();
+        let token_id = token_id(issuance_tx.transaction()).unwrap();
+        let issuance_tx_id = issuance_tx.transaction().get_id();
+        tf.make_block_builder()
+            .add_transaction(issuance_tx)
+            .build_and_process()
+            .unwrap();
+
+        // The transaction freezing the token may still move it
+        let freeze_tx = TransactionBuilder::new()
+            .add_input(
+                TxInput::from_utxo(issuance_tx_id.into(), 0),
+                InputWitness::NoSignature(None),
+            )
+            .add_output(TxOutput::Transfer(
+                TokenData::FreezeTokenV1 { token_id }.into(),
+                Destination::AnyoneCanSpend,
+            ))
+            .add_output(TxOutput::Transfer(
+                TokenTransfer { token_id, amount }.into(),
+                Destination::AnyoneCanSpend,
+            ))
+            .build();
+        let freeze_tx_id = freeze_tx.transaction().get_id();
+        let freeze_block_index = tf
+            .make_block_builder()
+            .add_transaction(freeze_tx)
+            .build_and_process()
+            .unwrap()
+            .unwrap();
+
+        let is_frozen = |tf: &TestFramework| {
+            tf.chainstate
+                .get_token_aux_data(token_id)
+                .unwrap()
+                .unwrap()
+                .supply()
+                .map(|supply| supply.is_frozen())
+        };
+        assert_eq!(is_frozen(&tf), Some(true));
+
+        // Frozen tokens can't be transferred
+        let transfer_tx = TransactionBuilder::new()
+            .add_input(
+                TxInput::from_utxo(freeze_tx_id.into(), 1),
+                InputWitness::NoSignature(None),
+            )
+            .add_output(TxOutput::Transfer(
+                TokenTransfer { token_id, amount }.into(),
+                Destination::AnyoneCanSpend,
+            ))
+            .build();
+        let result =
+            tf.make_block_builder().add_transaction(transfer_tx.clone()).build_and_process();
+        assert!(matches!(
+            result,
+            Err(ChainstateError::ProcessBlockError(BlockError::StateUpdateFailed(
+                ConnectTransactionError::TokensError(TokensError::TokenFrozen(id))
+            ))) if id == token_id
+        ));
+
+        // Unfreeze by spending the freeze output of the authority, then transfer
+        let unfreeze_tx = TransactionBuilder::new()
+            .add_input(
+                TxInput::from_utxo(freeze_tx_id.into(), 0),
+                InputWitness::NoSignature(None),
+            )
+            .add_output(TxOutput::Transfer(
+                TokenData::UnfreezeTokenV1 { token_id }.into(),
+                Destination::AnyoneCanSpend,
+            ))
+            .build();
+        tf.make_block_builder()
+            .add_transaction(unfreeze_tx)
+            .build_and_process()
+            .unwrap();
+        assert_eq!(is_frozen(&tf), Some(false));
+        tf.make_block_builder()
+            .add_transaction(transfer_tx)
+            .build_and_process()
+            .unwrap();
+
+        // Reorg out the unfreeze and the transfer
+        tf.create_chain(&(*freeze_block_index.block_id()).into(), 3, &mut rng).unwrap();
+        assert_eq!(is_frozen(&tf), Some(true));
+    })
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn token_issuance_v1_before_activation(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let activation_height = BlockHeight::new(2);
+        let chain_config = ConfigBuilder::test_chain()
+            .token_upgrades(
+                NetUpgrades::initialize(vec![
+                    (BlockHeight::zero(), TokensUpgrade::V0),
+                    (activation_height, TokensUpgrade::V1),
+                ])
+                .unwrap(),
+            )
+            .build();
+        let mut tf = TestFramework::builder(&mut rng).with_chain_config(chain_config).build();
+
+        let token_min_issuance_fee = tf.chainstate.get_chain_config().token_min_issuance_fee();
+        let issuance_tx = TransactionBuilder::new()
+            .add_input(
+                TxInput::from_utxo(tf.genesis().get_id().into(), 0),
+                InputWitness::NoSignature(None),
+            )
+            .add_output(TxOutput::Transfer(
+                TokenIssuanceV1 {
+                    token_ticker: random_string(&mut rng, 1..5).as_bytes().to_vec(),
+                    amount_to_issue: Amount::from_atoms(rng.gen_range(1..u128::MAX)),
+                    number_of_decimals: rng.gen_range(1..18),
+                    metadata_uri: random_string(&mut rng, 1..1024).as_bytes().to_vec(),
+                    authority: Destination::AnyoneCanSpend,
+                }
+                .into(),
+                Destination::AnyoneCanSpend,
+            ))
+            .add_output(TxOutput::Burn(OutputValue::Coin(token_min_issuance_fee)))
+            .build();
+        let issuance_tx_id = issuance_tx.transaction().get_id();
+
+        // Rejected before the activation height
+        let result =
+            tf.make_block_builder().add_transaction(issuance_tx.clone()).build_and_process();
+        assert_eq!(
+            result.unwrap_err(),
+            ChainstateError::ProcessBlockError(BlockError::StateUpdateFailed(
+                ConnectTransactionError::TokensError(TokensError::TokenDataNotActivated(
+                    issuance_tx_id,
+                    BlockHeight::new(1)
+                ))
+            ))
+        );
+
+        // Accepted once the upgrade is activated
+        tf.make_block_builder().build_and_process().unwrap();
+        tf.make_block_builder()
+            .add_transaction(issuance_tx)
+            .build_and_process()
+            .unwrap();
+    })
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn token_stats_follow_burns_and_reorgs(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let mut tf = TestFramework::builder(&mut rng).build();
+
+        let token_min_issuance_fee = tf.chainstate.get_chain_config().token_min_issuance_fee();
+        let total_funds = Amount::from_atoms(rng.gen_range(6..u128::MAX));
+        let explicitly_burned = Amount::from_atoms(rng.gen_range(1..total_funds.into_atoms() / 3));
+        let implicitly_burned = Amount::from_atoms(rng.gen_range(1..total_funds.into_atoms() / 3));
+
+        // Issue a new token
+        let issuance_tx = TransactionBuilder::new()
+            .add_input(
+                TxInput::from_utxo(tf.genesis().get_id().into(), 0),
+                InputWitness::NoSignature(None),
+            )
+            .add_output(TxOutput::Transfer(
+                TokenIssuance {
+                    token_ticker: random_string(&mut rng, 1..5).as_bytes().to_vec(),
+                    amount_to_issue: total_funds,
+                    number_of_decimals: rng.gen_range(1..18),
+                    metadata_uri: random_string(&mut rng, 1..1024).as_bytes().to_vec(),
+                }
+                .into(),
+                Destination::AnyoneCanSpend,
+            ))
+            .add_output(TxOutput::Burn(OutputValue::Coin(token_min_issuance_fee)))
+            .build();
+        let token_id = token_id(issuance_tx.transaction()).unwrap();
+        let issuance_tx_id = issuance_tx.transaction().get_id();
+        let issuance_block_index = tf
+            .make_block_builder()
+            .add_transaction(issuance_tx)
+            .build_and_process()
+            .unwrap()
+            .unwrap();
+
+        let stats = tf.chainstate.get_token_stats(token_id).unwrap().unwrap();
+        assert_eq!(
+            stats.issuance_block_height(),
+            issuance_block_index.block_height()
+        );
+        assert_eq!(stats.circulating_supply(), total_funds);
+        assert_eq!(stats.burned(), Amount::ZERO);
+        assert_eq!(
+            tf.chainstate.get_token_ids(None, 10).unwrap(),
+            vec![token_id]
+        );
+        assert!(tf.chainstate.get_token_ids(Some(token_id), 10).unwrap().is_empty());
+
+        // Burn some tokens with a burn output and leave some tokens unspent
+        let transferred = ((total_funds - explicitly_burned).unwrap() - implicitly_burned).unwrap();
+        tf.make_block_builder()
+            .add_transaction(
+                TransactionBuilder::new()
+                    .add_input(
+                        TxInput::from_utxo(issuance_tx_id.into(), 0),
+                        InputWitness::NoSignature(None),
+                    )
+                    .add_output(TxOutput::Burn(
+                        TokenTransfer {
+                            token_id,
+                            amount: explicitly_burned,
+                        }
+                        .into(),
+                    ))
+                    .add_output(TxOutput::Transfer(
+                        TokenTransfer {
+                            token_id,
+                            amount: transferred,
+                        }
+                        .into(),
+                        Destination::AnyoneCanSpend,
+                    ))
+                    .build(),
+            )
+            .build_and_process()
+            .unwrap();
+
+        let burned = (explicitly_burned + implicitly_burned).unwrap();
+        let stats = tf.chainstate.get_token_stats(token_id).unwrap().unwrap();
+        assert_eq!(
+            stats.issuance_block_height(),
+            issuance_block_index.block_height()
+        );
+        assert_eq!(stats.circulating_supply(), transferred);
+        assert_eq!(stats.burned(), burned);
+
+        // Reorg out the burn
+        tf.create_chain(&(*issuance_block_index.block_id()).into(), 2, &mut rng)
+            .unwrap();
+
+        let stats = tf.chainstate.get_token_stats(token_id).unwrap().unwrap();
+        assert_eq!(stats.circulating_supply(), total_funds);
+        assert_eq!(stats.burned(), Amount::ZERO);
+
+        // Reorg out the issuance
+        tf.create_chain(&tf.genesis().get_id().into(), 4, &mut rng).unwrap();
+
+        assert_eq!(tf.chainstate.get_token_stats(token_id).unwrap(), None);
+        assert!(tf.chainstate.get_token_ids(None, 10).unwrap().is_empty());
+    })
+}
@@ -96,6 +96,14 @@ impl TransactionVerifierStorageRef for InMemoryStorageWrapper {
             .map_err(TransactionVerifierStorageError::from)
     }
 
+    fn get_tokens_undo(
+        &self,
+        id: Id<Block>,
+    ) -> Result<Option<common::chain::tokens::TokensBlockUndo>, TransactionVerifierStorageError>
+    {
+        self.storage.get_tokens_undo(id).map_err(TransactionVerifierStorageError::from)
+    }
+
     fn get_account_nonce_count(
         &self,
         account: AccountType,
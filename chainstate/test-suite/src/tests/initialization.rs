@@ -50,15 +50,18 @@ fn genesis_check_ok(num_blocks: u64, rng: &mut (impl Rng + CryptoRng)) {
         tf.storage
     };
 
-    // Check that tx_index_enabled state is same as used in the storage.
+    // Check that tx_index_enabled and address_index_enabled states are same as used in the storage.
     // Could be removed once tx re-index is implemented.
     let tx_index_enabled =
         storage.transaction_ro().unwrap().get_is_mainchain_tx_index_enabled().unwrap();
+    let address_index_enabled =
+        storage.transaction_ro().unwrap().get_is_address_index_enabled().unwrap();
     let chainstate_config = ChainstateConfig {
         max_db_commit_attempts: Default::default(),
         max_orphan_blocks: Default::default(),
         min_max_bootstrap_import_buffer_sizes: Default::default(),
         tx_index_enabled: tx_index_enabled.map(Into::into).unwrap_or_default(),
+        address_index_enabled: address_index_enabled.map(Into::into).unwrap_or_default(),
         max_tip_age: Default::default(),
     };
 
@@ -110,15 +113,18 @@ fn genesis_check_err(num_blocks: u64, rng: &mut (impl Rng + CryptoRng)) {
         tf.storage
     };
 
-    // Check that tx_index_enabled state is same as used in the storage.
+    // Check that tx_index_enabled and address_index_enabled states are same as used in the storage.
     // Could be removed once tx re-index is implemented.
     let tx_index_enabled =
         storage.transaction_ro().unwrap().get_is_mainchain_tx_index_enabled().unwrap();
+    let address_index_enabled =
+        storage.transaction_ro().unwrap().get_is_address_index_enabled().unwrap();
     let chainstate_config = ChainstateConfig {
         max_db_commit_attempts: Default::default(),
         max_orphan_blocks: Default::default(),
         min_max_bootstrap_import_buffer_sizes: Default::default(),
         tx_index_enabled: tx_index_enabled.map(Into::into).unwrap_or_default(),
+        address_index_enabled: address_index_enabled.map(Into::into).unwrap_or_default(),
         max_tip_age: Default::default(),
     };
 
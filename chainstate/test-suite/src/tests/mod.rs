@@ -26,6 +26,7 @@ use crypto::random::Rng;
 use rstest::rstest;
 use test_utils::random::{make_seedable_rng, Seed};
 
+mod address_index;
 mod basic_tests;
 mod block_status;
 mod bootstrap;
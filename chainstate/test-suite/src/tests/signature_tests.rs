@@ -13,11 +13,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use chainstate::{BlockError, ChainstateError, ConnectTransactionError};
 use common::address::pubkeyhash::PublicKeyHash;
 use common::chain::classic_multisig::ClassicMultisigChallenge;
+use common::chain::signature::inputsig::authorize_script_hash_spend::{
+    sign_script_hash_spending, AuthorizedScriptHashSpend,
+};
 use common::chain::signature::inputsig::classical_multisig::authorize_classical_multisig::AuthorizedClassicalMultisigSpend;
+use common::chain::signature::TransactionSigError;
 use common::chain::signed_transaction::SignedTransaction;
-use common::primitives::Idable;
+use common::chain::{config::Builder as ConfigBuilder, KeyKindUpgrade, NetUpgrades};
+use common::primitives::{BlockHeight, Idable};
 use common::{
     chain::{
         signature::{inputsig::InputWitness, sighash::sighashtype::SigHashType},
@@ -34,6 +40,7 @@ use common::chain::signature::inputsig::standard_signature::StandardInputSignatu
 use common::chain::signature::sighash::signature_hash;
 use crypto::random::{Rng, SliceRandom};
 use rstest::rstest;
+use script::opcodes::all as opc;
 use serialization::Encode;
 use std::num::NonZeroU8;
 use test_utils::random::Seed;
@@ -353,6 +360,223 @@ fn signed_classical_multisig_tx_missing_sigs(#[case] seed: Seed) {
     });
 }
 
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn signed_script_hash_tx(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+        let mut tf = TestFramework::builder(&mut rng).build();
+
+        let chain_config = tf.chainstate.get_chain_config().clone();
+
+        let (private_key, public_key) =
+            PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+
+        // The next block is at height 1, so only the second lock height can be satisfied
+        for (lock_height, expected_error) in [
+            (
+                2,
+                Some(TransactionSigError::ScriptVerificationFailed(
+                    script::Error::TimeLock,
+                )),
+            ),
+            (1, None),
+        ] {
+            let redeem_script = script::Builder::new()
+                .push_int(lock_height)
+                .push_opcode(opc::OP_CLTV)
+                .push_opcode(opc::OP_DROP)
+                .push_slice(&public_key.encode())
+                .push_opcode(opc::OP_CHECKSIG)
+                .into_script();
+            let destination = Destination::ScriptHash(redeem_script.get_id());
+
+            // The first transaction uses the `AnyoneCanSpend` output of the transaction from the
+            // genesis block.
+            let tx_1 = TransactionBuilder::new()
+                .add_input(
+                    TxInput::from_utxo(
+                        OutPointSourceId::BlockReward(chain_config.genesis_block_id()),
+                        0,
+                    ),
+                    InputWitness::NoSignature(None),
+                )
+                .add_output(TxOutput::Transfer(
+                    OutputValue::Coin(Amount::from_atoms(100)),
+                    destination,
+                ))
+                .build();
+
+            let tx = TransactionBuilder::new()
+                .add_input(
+                    TxInput::from_utxo(
+                        OutPointSourceId::Transaction(tx_1.transaction().get_id()),
+                        0,
+                    ),
+                    InputWitness::NoSignature(None),
+                )
+                .add_output(TxOutput::Transfer(
+                    OutputValue::Coin(Amount::from_atoms(100)),
+                    Destination::AnyoneCanSpend,
+                ))
+                .build()
+                .transaction()
+                .clone();
+
+            // The second transaction has the signed input.
+            let tx_2 = {
+                let sighash_type = SigHashType::try_from(SigHashType::ALL).unwrap();
+                let sighash = signature_hash(
+                    sighash_type,
+                    &tx,
+                    &[Some(&tx_1.transaction().outputs()[0])],
+                    0,
+                )
+                .unwrap();
+                let signature = sign_script_hash_spending(&private_key, &sighash).unwrap();
+                let arguments =
+                    script::Builder::new().push_slice(&signature.encode()).into_script();
+                let authorization = AuthorizedScriptHashSpend::new(redeem_script, arguments);
+
+                let input_sign = StandardInputSignature::produce_script_hash_signature_for_input(
+                    &authorization,
+                    sighash_type,
+                );
+                SignedTransaction::new(tx, vec![InputWitness::Standard(input_sign)])
+                    .expect("invalid witness count")
+            };
+
+            let process_result =
+                tf.make_block_builder().with_transactions(vec![tx_1, tx_2]).build_and_process();
+
+            match expected_error {
+                None => {
+                    process_result.unwrap();
+                }
+                Some(err) => assert_eq!(
+                    process_result.unwrap_err(),
+                    chainstate::ChainstateError::ProcessBlockError(
+                        chainstate::BlockError::StateUpdateFailed(
+                            chainstate::ConnectTransactionError::SignatureVerificationFailed(err)
+                        )
+                    )
+                ),
+            }
+        }
+    });
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn script_hash_sigops_block_limit(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+        let max_block_sigops = 2;
+        let chain_config = ConfigBuilder::test_chain()
+            .script_hash_upgrades(NetUpgrades::script_hash_spendable())
+            .max_block_sigops(max_block_sigops)
+            .build();
+        let mut tf = TestFramework::builder(&mut rng).with_chain_config(chain_config).build();
+
+        let (private_key, public_key) =
+            PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+
+        // Two signature checks of the same signature
+        let redeem_script = script::Builder::new()
+            .push_slice(&public_key.encode())
+            .push_opcode(opc::OP_2DUP)
+            .push_opcode(opc::OP_CHECKSIGVERIFY)
+            .push_opcode(opc::OP_CHECKSIG)
+            .into_script();
+        let destination = Destination::ScriptHash(redeem_script.get_id());
+
+        let tx_1 = TransactionBuilder::new()
+            .add_input(
+                TxInput::from_utxo(
+                    OutPointSourceId::BlockReward(tf.genesis().get_id().into()),
+                    0,
+                ),
+                InputWitness::NoSignature(None),
+            )
+            .add_output(TxOutput::Transfer(
+                OutputValue::Coin(Amount::from_atoms(100)),
+                destination.clone(),
+            ))
+            .add_output(TxOutput::Transfer(
+                OutputValue::Coin(Amount::from_atoms(100)),
+                destination,
+            ))
+            .build();
+
+        let spend = |output_index: u32| -> SignedTransaction {
+            let tx = TransactionBuilder::new()
+                .add_input(
+                    TxInput::from_utxo(
+                        OutPointSourceId::Transaction(tx_1.transaction().get_id()),
+                        output_index,
+                    ),
+                    InputWitness::NoSignature(None),
+                )
+                .add_output(TxOutput::Transfer(
+                    OutputValue::Coin(Amount::from_atoms(100)),
+                    Destination::AnyoneCanSpend,
+                ))
+                .build()
+                .transaction()
+                .clone();
+
+            let sighash_type = SigHashType::try_from(SigHashType::ALL).unwrap();
+            let sighash = signature_hash(
+                sighash_type,
+                &tx,
+                &[Some(&tx_1.transaction().outputs()[output_index as usize])],
+                0,
+            )
+            .unwrap();
+            let signature = sign_script_hash_spending(&private_key, &sighash).unwrap();
+            let arguments = script::Builder::new().push_slice(&signature.encode()).into_script();
+            let authorization = AuthorizedScriptHashSpend::new(redeem_script.clone(), arguments);
+            let input_sign = StandardInputSignature::produce_script_hash_signature_for_input(
+                &authorization,
+                sighash_type,
+            );
+            SignedTransaction::new(tx, vec![InputWitness::Standard(input_sign)])
+                .expect("invalid witness count")
+        };
+        let tx_2 = spend(0);
+        let tx_3 = spend(1);
+
+        // Both spends do not fit into a single block
+        let block = tf
+            .make_block_builder()
+            .with_transactions(vec![tx_1.clone(), tx_2.clone(), tx_3.clone()])
+            .build();
+        let block_id = block.get_id();
+        assert_eq!(
+            tf.process_block(block, chainstate::BlockSource::Local).unwrap_err(),
+            ChainstateError::ProcessBlockError(BlockError::StateUpdateFailed(
+                ConnectTransactionError::TooManySigOpsInBlock(
+                    block_id,
+                    2 * max_block_sigops,
+                    max_block_sigops
+                )
+            ))
+        );
+
+        // Each spend fits into a block of its own
+        tf.make_block_builder()
+            .with_transactions(vec![tx_1, tx_2])
+            .build_and_process()
+            .unwrap();
+        tf.make_block_builder()
+            .with_transactions(vec![tx_3])
+            .build_and_process()
+            .unwrap();
+    });
+}
+
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy())]
@@ -426,3 +650,51 @@ fn too_large_no_sig_data(#[case] seed: Seed) {
         }
     });
 }
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn ed25519_output_before_activation(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+        let activation_height = BlockHeight::new(2);
+        let chain_config = ConfigBuilder::test_chain()
+            .key_kind_upgrades(
+                NetUpgrades::initialize(vec![
+                    (BlockHeight::zero(), KeyKindUpgrade::Secp256k1Schnorr),
+                    (activation_height, KeyKindUpgrade::Ed25519),
+                ])
+                .unwrap(),
+            )
+            .build();
+        let mut tf = TestFramework::builder(&mut rng).with_chain_config(chain_config).build();
+
+        let (_, public_key) = PrivateKey::new_from_rng(&mut rng, KeyKind::Ed25519);
+        let tx = TransactionBuilder::new()
+            .add_input(
+                TxInput::from_utxo(
+                    OutPointSourceId::BlockReward(tf.genesis().get_id().into()),
+                    0,
+                ),
+                InputWitness::NoSignature(None),
+            )
+            .add_output(TxOutput::Transfer(
+                OutputValue::Coin(Amount::from_atoms(rng.gen_range(1..100))),
+                Destination::PublicKey(public_key),
+            ))
+            .build();
+
+        // Rejected before the activation height
+        let result = tf.make_block_builder().add_transaction(tx.clone()).build_and_process();
+        assert_eq!(
+            result.unwrap_err(),
+            ChainstateError::ProcessBlockError(BlockError::StateUpdateFailed(
+                ConnectTransactionError::KeyKindNotActivated(BlockHeight::new(1))
+            ))
+        );
+
+        // Accepted once the upgrade is activated
+        tf.make_block_builder().build_and_process().unwrap();
+        tf.make_block_builder().add_transaction(tx).build_and_process().unwrap();
+    })
+}
@@ -465,6 +465,7 @@ fn initial_block_download(#[case] seed: Seed) {
                 max_orphan_blocks: Default::default(),
                 min_max_bootstrap_import_buffer_sizes: Default::default(),
                 tx_index_enabled: Default::default(),
+                address_index_enabled: Default::default(),
                 max_tip_age: Duration::from_secs(1).into(),
             })
             .build();
@@ -503,6 +504,7 @@ fn header_check_for_orphan(#[case] seed: Seed) {
                 max_orphan_blocks: Default::default(),
                 min_max_bootstrap_import_buffer_sizes: Default::default(),
                 tx_index_enabled: Default::default(),
+                address_index_enabled: Default::default(),
                 max_tip_age: Default::default(),
             })
             .build();
@@ -31,6 +31,7 @@ fn simulation(
         let mut tf = TestFramework::builder(&mut rng)
             .with_chainstate_config(chainstate::ChainstateConfig {
                 tx_index_enabled: tx_index_enabled.into(),
+                address_index_enabled: Default::default(),
                 max_db_commit_attempts: Default::default(),
                 max_orphan_blocks: Default::default(),
                 min_max_bootstrap_import_buffer_sizes: Default::default(),
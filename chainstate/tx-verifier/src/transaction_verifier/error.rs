@@ -18,7 +18,7 @@ use common::{
     chain::{
         block::{Block, GenBlock},
         signature::TransactionSigError,
-        tokens::TokenId,
+        tokens::{TokenId, TokensBlockUndoError},
         AccountNonce, AccountType, DelegationId, OutPointSourceId, PoolId, SpendError, Spender,
         Transaction, TxMainChainIndexError, UtxoOutPoint,
     },
@@ -77,6 +77,8 @@ pub enum ConnectTransactionError {
     BlockIndexCouldNotBeLoaded(Id<GenBlock>),
     #[error("Addition of all fees in block `{0}` failed")]
     FailedToAddAllFeesOfBlock(Id<Block>),
+    #[error("Too many signature operations in block `{0}` (current: {1}, limit: {2})")]
+    TooManySigOpsInBlock(Id<Block>, usize, usize),
     #[error("Block reward addition error for block {0}")]
     RewardAdditionError(Id<Block>),
     #[error("Timelock rules violated in output {0:?}")]
@@ -99,8 +101,14 @@ pub enum ConnectTransactionError {
     AttemptToSpendBurnedAmount,
     #[error("PoS accounting error")]
     PoSAccountingError(#[from] pos_accounting::Error),
+    #[error("Output public key of a kind not activated at height {0}")]
+    KeyKindNotActivated(BlockHeight),
     #[error("PoS accounting undo is missing for transaction {0}")]
     MissingPoSAccountingUndo(Id<Transaction>),
+    #[error("Tokens undo is missing for transaction {0}")]
+    MissingTokensUndo(Id<Transaction>),
+    #[error("Tokens BlockUndo error: {0}")]
+    TokensBlockUndoError(#[from] TokensBlockUndoError),
     #[error("Error during stake spending: {0}")]
     SpendStakeError(#[from] SpendStakeError),
     #[error("Attempt to use invalid input type in a transaction")]
@@ -276,6 +284,26 @@ pub enum TokensError {
     InvariantBrokenUndoIssuanceOnNonexistentToken(TokenId),
     #[error("Invariant broken - attempt register issuance on non-existent token {0}")]
     InvariantBrokenRegisterIssuanceWithDuplicateId(TokenId),
+    #[error("Token {0} not found")]
+    TokenNotFound(TokenId),
+    #[error("Supply of token {0} can't be changed")]
+    TokenSupplyNotControlled(TokenId),
+    #[error("Supply of token {0} is locked")]
+    TokenSupplyLocked(TokenId),
+    #[error("Token {0} is frozen")]
+    TokenFrozen(TokenId),
+    #[error("Token {0} isn't frozen")]
+    TokenNotFrozen(TokenId),
+    #[error("Authority of token {0} isn't proven by inputs of transaction {1}")]
+    TokenAuthorityNotProven(TokenId, Id<Transaction>),
+    #[error("Supply of token {0} overflowed")]
+    TokenSupplyOverflow(TokenId),
+    #[error("Invariant broken - attempt to undo supply change of token {0}")]
+    InvariantBrokenUndoSupplyChange(TokenId),
+    #[error("Invariant broken - tokens undo of transaction {0} not used up")]
+    InvariantBrokenTokensUndoNotUsedUp(Id<Transaction>),
+    #[error("Token data in transaction {0} is not activated at height {1}")]
+    TokenDataNotActivated(Id<Transaction>, BlockHeight),
 }
 
 #[derive(Error, Debug, PartialEq, Eq, Clone)]
@@ -39,7 +39,8 @@ fn flush_tokens<S: TransactionVerifierStorageMut>(
     storage: &mut S,
     token_cache: &ConsumedTokenIssuanceCache,
 ) -> Result<(), <S as TransactionVerifierStorageRef>::Error> {
-    debug_assert_eq!(token_cache.data.len(), token_cache.txid_vs_tokenid.len());
+    // Supply changes update the data of already issued tokens without touching the index
+    debug_assert!(token_cache.data.len() >= token_cache.txid_vs_tokenid.len());
 
     token_cache.data.iter().try_for_each(
         |(token_id, aux_data_op)| -> Result<(), <S as TransactionVerifierStorageRef>::Error> {
@@ -87,6 +88,17 @@ where
 
     flush_tokens(storage, &consumed.token_issuance_cache)?;
 
+    // flush tokens block undo
+    for (tx_source, entry) in consumed.tokens_block_undo {
+        if entry.is_fresh {
+            storage.set_tokens_undo_data(tx_source, &entry.undo)?;
+        } else if entry.undo.is_empty() {
+            storage.del_tokens_undo_data(tx_source)?;
+        } else {
+            panic!("BlockUndo was not used up completely")
+        }
+    }
+
     // flush utxo set
     storage.batch_write(consumed.utxo_cache)?;
 
@@ -27,7 +27,7 @@ use super::{
 use chainstate_types::{storage_result, GenBlockIndex};
 use common::{
     chain::{
-        tokens::{TokenAuxiliaryData, TokenId},
+        tokens::{TokenAuxiliaryData, TokenId, TokensBlockUndo},
         AccountNonce, AccountType, Block, DelegationId, GenBlock, OutPointSourceId, PoolId,
         Transaction, TxMainChainIndex, UtxoOutPoint,
     },
@@ -108,6 +108,16 @@ where
         }
     }
 
+    fn get_tokens_undo(
+        &self,
+        id: Id<Block>,
+    ) -> Result<Option<TokensBlockUndo>, <Self as TransactionVerifierStorageRef>::Error> {
+        match self.tokens_block_undo.data().get(&TransactionSource::Chain(id)) {
+            Some(v) => Ok(Some(v.undo.clone())),
+            None => self.storage.get_tokens_undo(id),
+        }
+    }
+
     fn get_account_nonce_count(
         &self,
         account: AccountType,
@@ -253,6 +263,24 @@ where
             .map_err(|e| TransactionVerifierStorageError::AccountingBlockUndoError(e).into())
     }
 
+    fn set_tokens_undo_data(
+        &mut self,
+        tx_source: TransactionSource,
+        new_undo: &TokensBlockUndo,
+    ) -> Result<(), <Self as TransactionVerifierStorageRef>::Error> {
+        self.tokens_block_undo
+            .set_undo_data(tx_source, new_undo)
+            .map_err(|e| TransactionVerifierStorageError::TokensBlockUndoError(e).into())
+    }
+
+    fn del_tokens_undo_data(
+        &mut self,
+        tx_source: TransactionSource,
+    ) -> Result<(), <Self as TransactionVerifierStorageRef>::Error> {
+        self.tokens_block_undo.del_undo_data(tx_source);
+        Ok(())
+    }
+
     fn apply_accounting_delta(
         &mut self,
         tx_source: TransactionSource,
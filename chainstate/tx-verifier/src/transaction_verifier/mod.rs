@@ -22,6 +22,7 @@ mod optional_tx_index_cache;
 mod reward_distribution;
 mod signature_check;
 mod token_issuance_cache;
+mod tokens_undo_cache;
 mod transferred_amount_check;
 mod tx_index_cache;
 mod utxos_undo_cache;
@@ -40,7 +41,7 @@ pub use tx_source::{TransactionSource, TransactionSourceForConnect};
 mod cached_operation;
 pub use cached_operation::CachedOperation;
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use self::{
     accounting_delta_adapter::PoSAccountingDeltaAdapter,
@@ -51,9 +52,13 @@ use self::{
     optional_tx_index_cache::OptionalTxIndexCache,
     signature_destination_getter::SignatureDestinationGetter,
     storage::TransactionVerifierStorageRef,
-    token_issuance_cache::{ConsumedTokenIssuanceCache, TokenIssuanceCache},
+    token_issuance_cache::{
+        changes_token_authority, ConsumedTokenIssuanceCache, TokenIssuanceCache,
+    },
+    tokens_undo_cache::{TokensBlockUndoCache, TokensBlockUndoEntry},
     transferred_amount_check::{
         check_transferred_amount_in_reward, check_transferred_amounts_and_get_fee,
+        get_transferred_token_ids,
     },
     utxos_undo_cache::{UtxosBlockUndoCache, UtxosBlockUndoEntry},
 };
@@ -63,14 +68,14 @@ use chainstate_types::BlockIndex;
 use common::{
     chain::{
         block::{timestamp::BlockTimestamp, BlockRewardTransactable, ConsensusData},
-        signature::Signable,
+        signature::{inputsig::authorize_script_hash_spend::SpendingBlockInfo, Signable},
         signed_transaction::SignedTransaction,
-        tokens::{get_tokens_issuance_count, TokenId},
+        tokens::{get_tokens_issuance_count, output_token_data, TokenId, TokensTxUndo},
         AccountNonce, AccountOutPoint, AccountSpending, AccountType, Block, ChainConfig,
-        DelegationId, GenBlock, OutPointSourceId, PoolId, Transaction, TxInput, TxMainChainIndex,
-        TxOutput, UtxoOutPoint,
+        DelegationId, Destination, GenBlock, OutPointSourceId, PoolId, Transaction, TxInput,
+        TxMainChainIndex, TxOutput, UtxoOutPoint,
     },
-    primitives::{id::WithId, Amount, Id, Idable, H256},
+    primitives::{id::WithId, Amount, BlockHeight, Id, Idable, H256},
 };
 use consensus::ConsensusPoSError;
 use pos_accounting::{
@@ -94,6 +99,7 @@ pub struct TransactionVerifierDelta {
     utxo_cache: ConsumedUtxoCache,
     utxo_block_undo: BTreeMap<TransactionSource, UtxosBlockUndoEntry>,
     token_issuance_cache: ConsumedTokenIssuanceCache,
+    tokens_block_undo: BTreeMap<TransactionSource, TokensBlockUndoEntry>,
     accounting_delta: PoSAccountingDeltaData,
     accounting_delta_undo: BTreeMap<TransactionSource, AccountingBlockUndoEntry>,
     accounting_block_deltas: BTreeMap<TransactionSource, PoSAccountingDeltaData>,
@@ -114,6 +120,7 @@ pub struct TransactionVerifier<C, S, U, A> {
 
     tx_index_cache: OptionalTxIndexCache,
     token_issuance_cache: TokenIssuanceCache,
+    tokens_block_undo: TokensBlockUndoCache,
 
     utxo_cache: UtxosCache<U>,
     utxo_block_undo: UtxosBlockUndoCache,
@@ -139,6 +146,7 @@ impl<C, S: TransactionVerifierStorageRef + ShallowClone> TransactionVerifier<C,
             best_block,
             tx_index_cache,
             token_issuance_cache: TokenIssuanceCache::new(),
+            tokens_block_undo: TokensBlockUndoCache::new(),
             utxo_cache,
             utxo_block_undo: UtxosBlockUndoCache::new(),
             accounting_delta_adapter,
@@ -171,6 +179,7 @@ where
             best_block,
             tx_index_cache,
             token_issuance_cache: TokenIssuanceCache::new(),
+            tokens_block_undo: TokensBlockUndoCache::new(),
             utxo_cache: UtxosCache::new(utxos).expect("Utxo cache setup failed"),
             utxo_block_undo: UtxosBlockUndoCache::new(),
             accounting_delta_adapter: PoSAccountingDeltaAdapter::new(accounting),
@@ -198,6 +207,7 @@ where
             utxo_cache: UtxosCache::new(&self.utxo_cache).expect("construct"),
             utxo_block_undo: UtxosBlockUndoCache::new(),
             token_issuance_cache: TokenIssuanceCache::new(),
+            tokens_block_undo: TokensBlockUndoCache::new(),
             accounting_delta_adapter: PoSAccountingDeltaAdapter::new(
                 self.accounting_delta_adapter.accounting_delta(),
             ),
@@ -396,6 +406,80 @@ where
         }
     }
 
+    /// Token data introduced by a network upgrade can't be used before the upgrade activates
+    fn check_token_data_activation(
+        &self,
+        tx: &Transaction,
+        height: BlockHeight,
+    ) -> Result<(), ConnectTransactionError> {
+        let token_upgrades = self.chain_config.as_ref().token_upgrades();
+        for token_data in tx.outputs().iter().filter_map(output_token_data) {
+            ensure!(
+                token_upgrades.is_token_data_activated(token_data, height),
+                TokensError::TokenDataNotActivated(tx.get_id(), height)
+            );
+        }
+        Ok(())
+    }
+
+    /// Public keys of a kind introduced by a network upgrade can't be put into outputs
+    /// before the upgrade activates
+    fn check_key_kind_activation(
+        &self,
+        outputs: &[TxOutput],
+        height: BlockHeight,
+    ) -> Result<(), ConnectTransactionError> {
+        let key_kind_upgrades = self.chain_config.as_ref().key_kind_upgrades();
+        let all_activated = outputs
+            .iter()
+            .flat_map(TxOutput::destinations)
+            .filter_map(|destination| match destination {
+                Destination::PublicKey(public_key) => Some(public_key),
+                Destination::AnyoneCanSpend
+                | Destination::Address(_)
+                | Destination::ScriptHash(_)
+                | Destination::ClassicMultisig(_) => None,
+            })
+            .all(|public_key| key_kind_upgrades.is_key_kind_activated(&public_key.kind(), height));
+        ensure!(
+            all_activated,
+            ConnectTransactionError::KeyKindNotActivated(height)
+        );
+        Ok(())
+    }
+
+    /// Destinations of the utxos spent by the transaction, used to prove token authority
+    fn get_input_destinations(
+        &self,
+        tx: &Transaction,
+    ) -> Result<BTreeSet<Destination>, ConnectTransactionError> {
+        let mut destinations = BTreeSet::new();
+        for input in tx.inputs() {
+            match input {
+                TxInput::Utxo(outpoint) => {
+                    let utxo = self
+                        .utxo_cache
+                        .utxo(outpoint)
+                        .map_err(|_| utxo::Error::ViewRead)?
+                        .ok_or(ConnectTransactionError::MissingOutputOrSpent)?;
+                    match utxo.output() {
+                        TxOutput::Transfer(_, destination)
+                        | TxOutput::LockThenTransfer(_, destination, _) => {
+                            destinations.insert(destination.clone());
+                        }
+                        TxOutput::Burn(_)
+                        | TxOutput::CreateStakePool(_, _)
+                        | TxOutput::ProduceBlockFromStake(_, _)
+                        | TxOutput::CreateDelegationId(_, _)
+                        | TxOutput::DelegateStaking(_, _) => {}
+                    }
+                }
+                TxInput::Account(_) => {}
+            }
+        }
+        Ok(destinations)
+    }
+
     fn spend_input_from_utxo(
         &mut self,
         tx_source: TransactionSource,
@@ -622,6 +706,18 @@ where
         })
     }
 
+    /// Count the signature operations of the transaction inputs that count towards
+    /// [ChainConfig::max_block_sigops]. Must be called before the transaction is connected.
+    pub fn count_sigops(&self, tx: &SignedTransaction) -> Result<usize, ConnectTransactionError> {
+        signature_check::count_sigops(
+            tx,
+            SignatureDestinationGetter::new_for_transaction(
+                &self.accounting_delta_adapter.accounting_delta(),
+                &self.utxo_cache,
+            ),
+        )
+    }
+
     pub fn connect_transaction(
         &mut self,
         tx_source: &TransactionSourceForConnect,
@@ -633,6 +729,12 @@ where
 
         input_output_policy::check_tx_inputs_outputs_purposes(tx.transaction(), &self.utxo_cache)?;
 
+        self.check_token_data_activation(tx.transaction(), tx_source.expected_block_height())?;
+        self.check_key_kind_activation(
+            tx.transaction().outputs(),
+            tx_source.expected_block_height(),
+        )?;
+
         // pre-cache token ids to check ensure it's not in the db when issuing
         self.token_issuance_cache.precache_token_issuance(
             |id| {
@@ -658,12 +760,43 @@ where
             issuance_token_id_getter,
         )?;
 
+        // frozen tokens can't be moved until the authority unfreezes them
+        let transferred_token_ids = get_transferred_token_ids(
+            &self.utxo_cache,
+            &self.accounting_delta_adapter.accounting_delta(),
+            tx.transaction(),
+            issuance_token_id_getter,
+        )?;
+        self.token_issuance_cache
+            .check_tokens_not_frozen(&transferred_token_ids, |id| {
+                self.storage
+                    .get_token_aux_data(id)
+                    .map_err(|_| ConnectTransactionError::TxVerifierStorage)
+            })?;
+
         // check token issuance fee
         self.check_issuance_fee_burn(tx.transaction(), &block_id)?;
 
         // Register tokens if tx has issuance data
         self.token_issuance_cache.register(block_id, tx.transaction())?;
 
+        // Apply supply changes of existing tokens
+        let input_destinations = self.get_input_destinations(tx.transaction())?;
+        let tokens_tx_undo = self.token_issuance_cache.connect_supply_changes(
+            tx.transaction(),
+            &input_destinations,
+            |id| {
+                self.storage
+                    .get_token_aux_data(id)
+                    .map_err(|_| ConnectTransactionError::TxVerifierStorage)
+            },
+        )?;
+        if !tokens_tx_undo.is_empty() {
+            self.tokens_block_undo
+                .get_or_create_block_undo(&TransactionSource::from(tx_source))
+                .insert_tx_undo(tx.transaction().get_id(), tokens_tx_undo)?;
+        }
+
         // check timelocks of the outputs and make sure there's no premature spending
         timelock_check::check_timelocks(
             &self.storage,
@@ -684,6 +817,7 @@ where
                 &self.accounting_delta_adapter.accounting_delta(),
                 &self.utxo_cache,
             ),
+            &SpendingBlockInfo::new(tx_source.expected_block_height(), *median_time_past),
         )?;
 
         self.connect_pos_accounting_outputs(tx_source.into(), tx.transaction())?;
@@ -731,6 +865,7 @@ where
         block_index: &BlockIndex,
         reward_transactable: BlockRewardTransactable,
         total_fees: Fee,
+        median_time_past: &BlockTimestamp,
         tx_index: Option<TxMainChainIndex>,
     ) -> Result<(), ConnectTransactionError> {
         // TODO: test spending block rewards from chains outside the mainchain
@@ -750,9 +885,14 @@ where
                 &self.utxo_cache,
                 &reward_transactable,
                 SignatureDestinationGetter::new_for_block_reward(&self.utxo_cache),
+                &SpendingBlockInfo::new(block_index.block_height(), *median_time_past),
             )?;
         }
 
+        if let Some(outputs) = reward_transactable.outputs() {
+            self.check_key_kind_activation(outputs, block_index.block_height())?;
+        }
+
         let block_id = *block_index.block_id();
 
         // spend inputs of the block reward
@@ -897,6 +1037,31 @@ where
 
         self.utxo_cache.disconnect_transaction(tx.transaction(), tx_undo)?;
 
+        // Revert supply changes of existing tokens
+        let tokens_tx_undo = if changes_token_authority(tx.transaction()) {
+            let block_undo_fetcher = |id: Id<Block>| {
+                self.storage
+                    .get_tokens_undo(id)
+                    .map_err(|_| ConnectTransactionError::TxVerifierStorage)
+            };
+            self.tokens_block_undo.take_tx_undo(
+                tx_source,
+                &tx.transaction().get_id(),
+                block_undo_fetcher,
+            )?
+        } else {
+            TokensTxUndo::new()
+        };
+        self.token_issuance_cache.disconnect_supply_changes(
+            tx.transaction(),
+            tokens_tx_undo,
+            |id| {
+                self.storage
+                    .get_token_aux_data(id)
+                    .map_err(|_| ConnectTransactionError::TxVerifierStorage)
+            },
+        )?;
+
         // pre-cache token ids before removing them
         self.token_issuance_cache.precache_token_issuance(
             |id| {
@@ -986,6 +1151,7 @@ where
             utxo_cache: self.utxo_cache.consume(),
             utxo_block_undo: self.utxo_block_undo.consume(),
             token_issuance_cache: self.token_issuance_cache.consume(),
+            tokens_block_undo: self.tokens_block_undo.consume(),
             accounting_delta,
             accounting_delta_undo: self.accounting_block_undo.consume(),
             accounting_block_deltas,
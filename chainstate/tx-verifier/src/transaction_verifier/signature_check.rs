@@ -14,7 +14,10 @@
 // limitations under the License.
 
 use common::chain::{
-    signature::{verify_signature, Transactable},
+    signature::{
+        input_sigop_count, inputsig::authorize_script_hash_spend::SpendingBlockInfo,
+        verify_signature_at_block, Transactable,
+    },
     ChainConfig, TxInput,
 };
 use utxo::UtxosView;
@@ -28,6 +31,7 @@ pub fn verify_signatures<U, T>(
     utxo_view: &U,
     transactable: &T,
     destination_getter: SignatureDestinationGetter,
+    spending_block: &SpendingBlockInfo,
 ) -> Result<(), ConnectTransactionError>
 where
     U: UtxosView,
@@ -54,15 +58,35 @@ where
     inputs.iter().enumerate().try_for_each(|(input_idx, input)| {
         // TODO: ensure that signature verification is tested in the test-suite, they seem to be tested only internally
         let destination = destination_getter.call(input)?;
-        verify_signature(
+        verify_signature_at_block(
             chain_config,
             &destination,
             transactable,
             &inputs_utxos,
             input_idx,
+            spending_block,
         )
         .map_err(ConnectTransactionError::SignatureVerificationFailed)
     })
 }
 
+/// Count the signature operations of the inputs, see [input_sigop_count]
+pub fn count_sigops<T>(
+    transactable: &T,
+    destination_getter: SignatureDestinationGetter,
+) -> Result<usize, ConnectTransactionError>
+where
+    T: Transactable,
+{
+    let (inputs, witnesses) = match (transactable.inputs(), transactable.signatures()) {
+        (Some(inputs), Some(witnesses)) => (inputs, witnesses),
+        (None, _) | (_, None) => return Ok(0),
+    };
+
+    inputs.iter().zip(witnesses).try_fold(0, |total, (input, witness)| {
+        let destination = destination_getter.call(input)?;
+        Ok(total + input_sigop_count(&destination, witness))
+    })
+}
+
 // TODO: unit tests
@@ -18,7 +18,7 @@ use std::ops::Deref;
 use chainstate_types::{storage_result, GenBlockIndex};
 use common::{
     chain::{
-        tokens::{TokenAuxiliaryData, TokenId},
+        tokens::{TokenAuxiliaryData, TokenId, TokensBlockUndo},
         AccountNonce, AccountType, Block, GenBlock, OutPointSourceId, Transaction,
         TxMainChainIndex,
     },
@@ -59,6 +59,8 @@ pub enum TransactionVerifierStorageError {
     PoSAccountingError(#[from] pos_accounting::Error),
     #[error("Accounting BlockUndo error: {0}")]
     AccountingBlockUndoError(#[from] pos_accounting::AccountingBlockUndoError),
+    #[error("Tokens BlockUndo error: {0}")]
+    TokensBlockUndoError(#[from] common::chain::tokens::TokensBlockUndoError),
 }
 
 pub trait HasTxIndexDisabledError {
@@ -109,6 +111,11 @@ where
         id: Id<Block>,
     ) -> Result<Option<AccountingBlockUndo>, <Self as TransactionVerifierStorageRef>::Error>;
 
+    fn get_tokens_undo(
+        &self,
+        id: Id<Block>,
+    ) -> Result<Option<TokensBlockUndo>, <Self as TransactionVerifierStorageRef>::Error>;
+
     fn get_account_nonce_count(
         &self,
         account: AccountType,
@@ -173,6 +180,17 @@ pub trait TransactionVerifierStorageMut:
         tx_source: TransactionSource,
     ) -> Result<(), <Self as TransactionVerifierStorageRef>::Error>;
 
+    fn set_tokens_undo_data(
+        &mut self,
+        tx_source: TransactionSource,
+        undo: &TokensBlockUndo,
+    ) -> Result<(), <Self as TransactionVerifierStorageRef>::Error>;
+
+    fn del_tokens_undo_data(
+        &mut self,
+        tx_source: TransactionSource,
+    ) -> Result<(), <Self as TransactionVerifierStorageRef>::Error>;
+
     fn apply_accounting_delta(
         &mut self,
         tx_source: TransactionSource,
@@ -231,6 +249,13 @@ where
         self.deref().get_accounting_undo(id)
     }
 
+    fn get_tokens_undo(
+        &self,
+        id: Id<Block>,
+    ) -> Result<Option<TokensBlockUndo>, <Self as TransactionVerifierStorageRef>::Error> {
+        self.deref().get_tokens_undo(id)
+    }
+
     fn get_account_nonce_count(
         &self,
         account: AccountType,
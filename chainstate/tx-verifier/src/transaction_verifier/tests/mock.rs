@@ -66,6 +66,11 @@ mockall::mock! {
             id: Id<Block>,
         ) -> Result<Option<pos_accounting::AccountingBlockUndo>, TransactionVerifierStorageError>;
 
+        fn get_tokens_undo(
+            &self,
+            id: Id<Block>,
+        ) -> Result<Option<common::chain::tokens::TokensBlockUndo>, TransactionVerifierStorageError>;
+
         fn get_account_nonce_count(
             &self,
             account: AccountType,
@@ -120,6 +125,17 @@ mockall::mock! {
             tx_source: TransactionSource,
         ) -> Result<(), TransactionVerifierStorageError>;
 
+        fn set_tokens_undo_data(
+            &mut self,
+            tx_source: TransactionSource,
+            undo: &common::chain::tokens::TokensBlockUndo,
+        ) -> Result<(), TransactionVerifierStorageError>;
+
+        fn del_tokens_undo_data(
+            &mut self,
+            tx_source: TransactionSource,
+        ) -> Result<(), TransactionVerifierStorageError>;
+
         fn apply_accounting_delta(
             &mut self,
             tx_source: TransactionSource,
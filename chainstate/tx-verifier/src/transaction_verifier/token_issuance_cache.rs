@@ -13,15 +13,19 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::{btree_map::Entry, BTreeMap};
+use std::collections::{btree_map::Entry, BTreeMap, BTreeSet};
 
 use common::{
     chain::{
-        tokens::{get_tokens_issuance_count, token_id, TokenAuxiliaryData, TokenId},
-        Block, Transaction,
+        tokens::{
+            get_tokens_issuance_count, output_token_data, token_id, TokenAuxiliaryData, TokenData,
+            TokenId, TokensTxUndo,
+        },
+        Block, Destination, Transaction, TxOutput,
     },
-    primitives::{Id, Idable, H256},
+    primitives::{Amount, Id, Idable, H256},
 };
+use utils::ensure;
 
 use super::{
     error::{ConnectTransactionError, TokensError},
@@ -43,6 +47,45 @@ pub struct ConsumedTokenIssuanceCache {
     pub txid_vs_tokenid: BTreeMap<Id<Transaction>, CachedTokenIndexOp>,
 }
 
+enum SupplyChange<'a> {
+    Reissue(Amount),
+    Lock,
+    ChangeAuthority(&'a Destination),
+    Freeze,
+    Unfreeze,
+}
+
+fn supply_change(output: &TxOutput) -> Option<(TokenId, SupplyChange)> {
+    match output_token_data(output)? {
+        TokenData::TokenReissueV1 {
+            token_id,
+            amount_to_issue,
+        } => Some((*token_id, SupplyChange::Reissue(*amount_to_issue))),
+        TokenData::LockTokenSupplyV1 { token_id } => Some((*token_id, SupplyChange::Lock)),
+        TokenData::ChangeTokenAuthorityV1 {
+            token_id,
+            new_authority,
+        } => Some((*token_id, SupplyChange::ChangeAuthority(new_authority))),
+        TokenData::FreezeTokenV1 { token_id } => Some((*token_id, SupplyChange::Freeze)),
+        TokenData::UnfreezeTokenV1 { token_id } => Some((*token_id, SupplyChange::Unfreeze)),
+        TokenData::TokenTransfer(_)
+        | TokenData::TokenIssuance(_)
+        | TokenData::NftIssuance(_)
+        | TokenData::TokenIssuanceV1(_) => None,
+    }
+}
+
+/// Whether the transaction changes the authority of a token, which needs undo data to revert
+pub fn changes_token_authority(tx: &Transaction) -> bool {
+    tx.outputs().iter().filter_map(supply_change).any(|(_, change)| match change {
+        SupplyChange::ChangeAuthority(_) => true,
+        SupplyChange::Reissue(_)
+        | SupplyChange::Lock
+        | SupplyChange::Freeze
+        | SupplyChange::Unfreeze => false,
+    })
+}
+
 pub struct TokenIssuanceCache {
     data: BTreeMap<TokenId, CachedAuxDataOp>,
     txid_vs_tokenid: BTreeMap<Id<Transaction>, CachedTokenIndexOp>,
@@ -162,13 +205,150 @@ impl TokenIssuanceCache {
                         e.insert(CachedAuxDataOp::Read(el));
                     }
                 }
-                Entry::Occupied(_) => {
-                    return Err(ConnectTransactionError::TokensError(
-                        TokensError::InvariantBrokenRegisterIssuanceWithDuplicateId(token_id),
-                    ));
+                // Already cached, e.g. because of a supply change in another transaction.
+                // Duplicate issuance is caught on registration.
+                Entry::Occupied(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn get_aux_data<E>(
+        &self,
+        token_id: &TokenId,
+        token_data_getter: impl Fn(&TokenId) -> Result<Option<TokenAuxiliaryData>, E>,
+    ) -> Result<Option<TokenAuxiliaryData>, E> {
+        match self.data.get(token_id) {
+            Some(CachedOperation::Write(data) | CachedOperation::Read(data)) => {
+                Ok(Some(data.clone()))
+            }
+            Some(CachedOperation::Erase) => Ok(None),
+            None => token_data_getter(token_id),
+        }
+    }
+
+    /// Fails if any of the given tokens is frozen.
+    ///
+    /// The state before the supply changes of the transaction is checked, so a transaction
+    /// that freezes a token may still move it while an unfreezing transaction may not.
+    pub fn check_tokens_not_frozen<E>(
+        &self,
+        token_ids: &BTreeSet<TokenId>,
+        token_data_getter: impl Fn(&TokenId) -> Result<Option<TokenAuxiliaryData>, E>,
+    ) -> Result<(), ConnectTransactionError>
+    where
+        ConnectTransactionError: From<E>,
+    {
+        for token_id in token_ids {
+            let aux_data = self.get_aux_data(token_id, &token_data_getter)?;
+            let is_frozen = aux_data
+                .as_ref()
+                .and_then(TokenAuxiliaryData::supply)
+                .map_or(false, |supply| supply.is_frozen());
+            ensure!(!is_frozen, TokensError::TokenFrozen(*token_id));
+        }
+        Ok(())
+    }
+
+    /// Applies reissuance, supply locking, freezing and authority changes of already issued tokens.
+    ///
+    /// The authority of a token must be the destination of one of the spent outputs.
+    /// Returns the replaced authorities, which are needed to disconnect the transaction.
+    pub fn connect_supply_changes<E>(
+        &mut self,
+        tx: &Transaction,
+        input_destinations: &BTreeSet<Destination>,
+        token_data_getter: impl Fn(&TokenId) -> Result<Option<TokenAuxiliaryData>, E>,
+    ) -> Result<TokensTxUndo, ConnectTransactionError>
+    where
+        ConnectTransactionError: From<E>,
+    {
+        let mut tx_undo = TokensTxUndo::new();
+        for (token_id, change) in tx.outputs().iter().filter_map(supply_change) {
+            let mut aux_data = self
+                .get_aux_data(&token_id, &token_data_getter)?
+                .ok_or(TokensError::TokenNotFound(token_id))?;
+            let supply =
+                aux_data.supply_mut().ok_or(TokensError::TokenSupplyNotControlled(token_id))?;
+            ensure!(
+                input_destinations.contains(supply.authority()),
+                TokensError::TokenAuthorityNotProven(token_id, tx.get_id())
+            );
+
+            match change {
+                SupplyChange::Reissue(amount) => {
+                    ensure!(
+                        !supply.is_locked(),
+                        TokensError::TokenSupplyLocked(token_id)
+                    );
+                    ensure!(!supply.is_frozen(), TokensError::TokenFrozen(token_id));
+                    supply.reissue(amount).ok_or(TokensError::TokenSupplyOverflow(token_id))?;
+                }
+                SupplyChange::Lock => {
+                    ensure!(
+                        !supply.is_locked(),
+                        TokensError::TokenSupplyLocked(token_id)
+                    );
+                    supply.lock();
+                }
+                SupplyChange::ChangeAuthority(new_authority) => {
+                    let previous_authority = supply.change_authority(new_authority.clone());
+                    tx_undo.push_previous_authority(token_id, previous_authority);
+                }
+                SupplyChange::Freeze => {
+                    ensure!(!supply.is_frozen(), TokensError::TokenFrozen(token_id));
+                    supply.freeze();
+                }
+                SupplyChange::Unfreeze => {
+                    ensure!(supply.is_frozen(), TokensError::TokenNotFrozen(token_id));
+                    supply.unfreeze();
+                }
+            }
+
+            self.data.insert(token_id, CachedAuxDataOp::Write(aux_data));
+        }
+        Ok(tx_undo)
+    }
+
+    /// Reverts the changes made by [`Self::connect_supply_changes`] using its undo data
+    pub fn disconnect_supply_changes<E>(
+        &mut self,
+        tx: &Transaction,
+        mut tx_undo: TokensTxUndo,
+        token_data_getter: impl Fn(&TokenId) -> Result<Option<TokenAuxiliaryData>, E>,
+    ) -> Result<(), ConnectTransactionError>
+    where
+        ConnectTransactionError: From<E>,
+    {
+        for (token_id, change) in tx.outputs().iter().rev().filter_map(supply_change) {
+            let mut aux_data = self
+                .get_aux_data(&token_id, &token_data_getter)?
+                .ok_or(TokensError::InvariantBrokenUndoSupplyChange(token_id))?;
+            let supply = aux_data
+                .supply_mut()
+                .ok_or(TokensError::InvariantBrokenUndoSupplyChange(token_id))?;
+
+            match change {
+                SupplyChange::Reissue(amount) => supply
+                    .undo_reissue(amount)
+                    .ok_or(TokensError::InvariantBrokenUndoSupplyChange(token_id))?,
+                SupplyChange::Lock => supply.undo_lock(),
+                SupplyChange::ChangeAuthority(_) => {
+                    let previous_authority = tx_undo
+                        .pop_previous_authority(&token_id)
+                        .ok_or(TokensError::InvariantBrokenUndoSupplyChange(token_id))?;
+                    supply.undo_change_authority(previous_authority);
                 }
+                SupplyChange::Freeze => supply.unfreeze(),
+                SupplyChange::Unfreeze => supply.freeze(),
             }
+
+            self.data.insert(token_id, CachedAuxDataOp::Write(aux_data));
         }
+        ensure!(
+            tx_undo.is_empty(),
+            TokensError::InvariantBrokenTokensUndoNotUsedUp(tx.get_id())
+        );
         Ok(())
     }
 
@@ -177,7 +357,20 @@ impl TokenIssuanceCache {
         token_id: &TokenId,
         data: TokenAuxiliaryData,
     ) -> Result<(), TokensError> {
-        self.insert_aux_data(*token_id, CachedAuxDataOp::Write(data))
+        // Supply changes overwrite the data of the same token
+        let is_update = match self.data.get(token_id) {
+            Some(CachedOperation::Write(cached) | CachedOperation::Read(cached)) => {
+                cached.issuance_tx() == data.issuance_tx()
+            }
+            Some(CachedOperation::Erase) | None => false,
+        };
+
+        if is_update {
+            self.data.insert(*token_id, CachedAuxDataOp::Write(data));
+            Ok(())
+        } else {
+            self.insert_aux_data(*token_id, CachedAuxDataOp::Write(data))
+        }
     }
 
     pub fn del_token_aux_data(&mut self, token_id: &TokenId) -> Result<(), TokensError> {
@@ -13,7 +13,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use common::{
     amount_sum,
@@ -97,6 +97,38 @@ where
     Ok(total_fee)
 }
 
+/// Tokens with a non-zero amount in the inputs or in the outputs of the transaction
+pub fn get_transferred_token_ids<U, P, IssuanceTokenIdGetterFunc>(
+    utxo_view: &U,
+    pos_accounting_view: &P,
+    tx: &Transaction,
+    issuance_token_id_getter: IssuanceTokenIdGetterFunc,
+) -> Result<BTreeSet<TokenId>, ConnectTransactionError>
+where
+    U: UtxosView,
+    P: PoSAccountingView,
+    IssuanceTokenIdGetterFunc:
+        Fn(&Id<Transaction>) -> Result<Option<TokenId>, ConnectTransactionError>,
+{
+    let inputs_total_map = calculate_total_inputs(
+        utxo_view,
+        pos_accounting_view,
+        tx.inputs(),
+        issuance_token_id_getter,
+    )?;
+    let outputs_total_map = calculate_total_outputs(pos_accounting_view, tx.outputs(), None)?;
+
+    let token_ids = inputs_total_map
+        .into_iter()
+        .chain(outputs_total_map)
+        .filter_map(|(coin_or_token_id, amount)| match coin_or_token_id {
+            CoinOrTokenId::Coin => None,
+            CoinOrTokenId::TokenId(token_id) => (amount > Amount::ZERO).then_some(token_id),
+        })
+        .collect();
+    Ok(token_ids)
+}
+
 fn get_output_value<P: PoSAccountingView>(
     pos_accounting_view: &P,
     output: &TxOutput,
@@ -222,6 +254,19 @@ fn get_output_token_id_and_amount(
                 }
                 None => None,
             },
+            TokenData::TokenIssuanceV1(issuance) => match include_issuance {
+                Some(tx) => {
+                    let token_id = token_id(tx).ok_or(TokensError::TokenIdCantBeCalculated)?;
+                    Some((CoinOrTokenId::TokenId(token_id), issuance.amount_to_issue))
+                }
+                None => None,
+            },
+            // Reissued tokens are new supply, they don't have to be covered by the inputs
+            TokenData::TokenReissueV1 { .. }
+            | TokenData::LockTokenSupplyV1 { .. }
+            | TokenData::ChangeTokenAuthorityV1 { .. }
+            | TokenData::FreezeTokenV1 { .. }
+            | TokenData::UnfreezeTokenV1 { .. } => None,
         },
     })
 }
@@ -250,6 +295,24 @@ where
                 .ok_or(ConnectTransactionError::TokensError(
                     TokensError::TokenIdCantBeCalculated,
                 ))?,
+            TokenData::TokenIssuanceV1(issuance) => issuance_token_id_getter()?
+                .map(|token_id| (CoinOrTokenId::TokenId(token_id), issuance.amount_to_issue))
+                .ok_or(ConnectTransactionError::TokensError(
+                    TokensError::TokenIdCantBeCalculated,
+                ))?,
+            TokenData::TokenReissueV1 {
+                token_id,
+                amount_to_issue,
+            } => (CoinOrTokenId::TokenId(*token_id), *amount_to_issue),
+            TokenData::LockTokenSupplyV1 { token_id }
+            | TokenData::ChangeTokenAuthorityV1 {
+                token_id,
+                new_authority: _,
+            }
+            | TokenData::FreezeTokenV1 { token_id }
+            | TokenData::UnfreezeTokenV1 { token_id } => {
+                (CoinOrTokenId::TokenId(*token_id), Amount::ZERO)
+            }
         },
     })
 }
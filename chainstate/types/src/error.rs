@@ -48,6 +48,10 @@ pub enum PropertyQueryError {
     OutpointIndexOutOfRange,
     #[error("Transaction not found")]
     TxNotFound,
+    #[error("Transaction index is disabled")]
+    TxIndexDisabled,
+    #[error("Address index is disabled")]
+    AddressIndexDisabled,
     #[error("Genesis block has no header")]
     GenesisHeaderRequested,
     #[error("Tried getting value of a token outpoint")]
@@ -58,6 +62,8 @@ pub enum PropertyQueryError {
     PoolBalanceNotFound(PoolId),
     #[error("Failed to read balance of pool {0}")]
     PoolBalanceReadError(PoolId),
+    #[error("Block {0} is not an ancestor of block {1}")]
+    NotAnAncestor(Id<GenBlock>, Id<GenBlock>),
 }
 
 #[derive(Error, Debug, PartialEq, Eq, Clone)]
@@ -137,10 +137,13 @@ mod tests {
     #[rstest]
     #[trace]
     #[case(Seed::from_entropy())]
-    fn basic(#[case] seed: Seed) {
+    fn basic(
+        #[case] seed: Seed,
+        #[values(KeyKind::Secp256k1Schnorr, KeyKind::Ed25519)] key_kind: KeyKind,
+    ) {
         let mut rng = test_utils::random::make_seedable_rng(seed);
         let cfg = create_mainnet();
-        let (_priv_key, pub_key) = PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+        let (_priv_key, pub_key) = PrivateKey::new_from_rng(&mut rng, key_kind);
         let public_key_hash = PublicKeyHash::from(&pub_key);
         let public_key_hash_dest = Destination::Address(public_key_hash);
         let address = Address::new_from_destination(&cfg, &public_key_hash_dest)
@@ -149,5 +152,12 @@ mod tests {
             .destination(&cfg)
             .expect("Failed to extract public key hash from address");
         assert_eq!(public_key_hash_restored_dest, public_key_hash_dest);
+
+        let public_key_dest = Destination::PublicKey(pub_key);
+        let address = Address::new_from_destination(&cfg, &public_key_dest)
+            .expect("Address from public key failed");
+        let public_key_restored_dest =
+            address.destination(&cfg).expect("Failed to extract public key from address");
+        assert_eq!(public_key_restored_dest, public_key_dest);
     }
 }
@@ -23,8 +23,8 @@ use crate::{
             EmissionScheduleTabular,
         },
         pos::get_initial_randomness,
-        ConsensusUpgrade, Destination, GenBlock, Genesis, Mlt, NetUpgrades, PoWChainConfig,
-        UpgradeVersion,
+        ConsensusUpgrade, Destination, GenBlock, Genesis, KeyKindUpgrade, Mlt, NetUpgrades,
+        PoWChainConfig, ScriptHashUpgrade, TokensUpgrade, UpgradeVersion,
     },
     primitives::{
         id::WithId, semver::SemVer, Amount, BlockDistance, BlockHeight, Id, Idable, H256,
@@ -83,6 +83,30 @@ impl ChainType {
             ChainType::Signet => NetUpgrades::unit_tests(),
         }
     }
+
+    fn default_key_kind_upgrades(&self) -> NetUpgrades<KeyKindUpgrade> {
+        match self {
+            // Ed25519 keys are never activated on the public networks
+            ChainType::Mainnet | ChainType::Testnet => NetUpgrades::secp256k1_only(),
+            ChainType::Regtest | ChainType::Signet => NetUpgrades::all_key_kinds(),
+        }
+    }
+
+    fn default_script_hash_upgrades(&self) -> NetUpgrades<ScriptHashUpgrade> {
+        match self {
+            // Script hash outputs are not spendable on the public networks yet
+            ChainType::Mainnet | ChainType::Testnet => NetUpgrades::script_hash_unspendable(),
+            ChainType::Regtest | ChainType::Signet => NetUpgrades::script_hash_spendable(),
+        }
+    }
+
+    fn default_token_upgrades(&self) -> NetUpgrades<TokensUpgrade> {
+        match self {
+            // Tokens with a supply authority are not activated on the public networks yet
+            ChainType::Mainnet | ChainType::Testnet => NetUpgrades::tokens_v0_only(),
+            ChainType::Regtest | ChainType::Signet => NetUpgrades::all_token_versions(),
+        }
+    }
 }
 
 // Builder support types
@@ -123,12 +147,16 @@ pub struct Builder {
     max_block_header_size: usize,
     max_block_size_with_standard_txs: usize,
     max_block_size_with_smart_contracts: usize,
+    max_block_sigops: usize,
     max_no_signature_data_size: usize,
     max_depth_for_reorg: BlockDistance,
     epoch_length: NonZeroU64,
     sealed_epoch_distance_from_tip: usize,
     initial_randomness: H256,
     net_upgrades: NetUpgrades<UpgradeVersion>,
+    key_kind_upgrades: NetUpgrades<KeyKindUpgrade>,
+    script_hash_upgrades: NetUpgrades<ScriptHashUpgrade>,
+    token_upgrades: NetUpgrades<TokensUpgrade>,
     genesis_block: GenesisBlockInit,
     emission_schedule: EmissionScheduleInit,
     token_min_issuance_fee: Amount,
@@ -159,6 +187,7 @@ impl Builder {
             max_block_header_size: super::MAX_BLOCK_HEADER_SIZE,
             max_block_size_with_standard_txs: super::MAX_BLOCK_TXS_SIZE,
             max_block_size_with_smart_contracts: super::MAX_BLOCK_CONTRACTS_SIZE,
+            max_block_sigops: super::MAX_BLOCK_SIGOPS,
             max_no_signature_data_size: super::MAX_TX_NO_SIG_WITNESS_SIZE,
             max_future_block_time_offset: super::DEFAULT_MAX_FUTURE_BLOCK_TIME_OFFSET,
             max_depth_for_reorg: super::DEFAULT_MAX_DEPTH_FOR_REORG,
@@ -169,6 +198,9 @@ impl Builder {
             genesis_block: chain_type.default_genesis_init(),
             emission_schedule: EmissionScheduleInit::Mainnet,
             net_upgrades: chain_type.default_net_upgrades(),
+            key_kind_upgrades: chain_type.default_key_kind_upgrades(),
+            script_hash_upgrades: chain_type.default_script_hash_upgrades(),
+            token_upgrades: chain_type.default_token_upgrades(),
             token_min_issuance_fee: super::TOKEN_MIN_ISSUANCE_FEE,
             token_max_uri_len: super::TOKEN_MAX_URI_LEN,
             token_max_dec_count: super::TOKEN_MAX_DEC_COUNT,
@@ -203,6 +235,7 @@ impl Builder {
             max_block_header_size,
             max_block_size_with_standard_txs,
             max_block_size_with_smart_contracts,
+            max_block_sigops,
             max_future_block_time_offset,
             max_no_signature_data_size,
             max_depth_for_reorg,
@@ -213,6 +246,9 @@ impl Builder {
             genesis_block,
             emission_schedule,
             net_upgrades,
+            key_kind_upgrades,
+            script_hash_upgrades,
+            token_upgrades,
             token_min_issuance_fee,
             token_max_uri_len,
             token_max_dec_count,
@@ -260,6 +296,7 @@ impl Builder {
             max_block_header_size,
             max_block_size_with_standard_txs,
             max_block_size_with_smart_contracts,
+            max_block_sigops,
             max_future_block_time_offset,
             max_no_signature_data_size,
             max_depth_for_reorg,
@@ -271,6 +308,9 @@ impl Builder {
             height_checkpoint_data,
             emission_schedule,
             net_upgrades,
+            key_kind_upgrades,
+            script_hash_upgrades,
+            token_upgrades,
             token_min_issuance_fee,
             token_max_uri_len,
             token_max_dec_count,
@@ -309,8 +349,12 @@ impl Builder {
     builder_method!(max_block_header_size: usize);
     builder_method!(max_block_size_with_standard_txs: usize);
     builder_method!(max_block_size_with_smart_contracts: usize);
+    builder_method!(max_block_sigops: usize);
     builder_method!(max_depth_for_reorg: BlockDistance);
     builder_method!(net_upgrades: NetUpgrades<UpgradeVersion>);
+    builder_method!(key_kind_upgrades: NetUpgrades<KeyKindUpgrade>);
+    builder_method!(script_hash_upgrades: NetUpgrades<ScriptHashUpgrade>);
+    builder_method!(token_upgrades: NetUpgrades<TokensUpgrade>);
     builder_method!(empty_consensus_reward_maturity_distance: BlockDistance);
     builder_method!(epoch_length: NonZeroU64);
     builder_method!(sealed_epoch_distance_from_tip: usize);
@@ -30,7 +30,9 @@ use crate::chain::transaction::Destination;
 use crate::chain::upgrades::NetUpgrades;
 use crate::chain::TxOutput;
 use crate::chain::{GenBlock, Genesis, PoolId};
-use crate::chain::{PoWChainConfig, UpgradeVersion};
+use crate::chain::{
+    KeyKindUpgrade, PoWChainConfig, ScriptHashUpgrade, TokensUpgrade, UpgradeVersion,
+};
 use crate::primitives::id::{Id, Idable, WithId};
 use crate::primitives::per_thousand::PerThousand;
 use crate::primitives::semver::SemVer;
@@ -148,6 +150,9 @@ pub struct ChainConfig {
     bip44_coin_type: ChildNumber,
     height_checkpoint_data: Checkpoints,
     net_upgrades: NetUpgrades<UpgradeVersion>,
+    key_kind_upgrades: NetUpgrades<KeyKindUpgrade>,
+    script_hash_upgrades: NetUpgrades<ScriptHashUpgrade>,
+    token_upgrades: NetUpgrades<TokensUpgrade>,
     magic_bytes: [u8; 4],
     p2p_port: u16,
     genesis_block: Arc<WithId<Genesis>>,
@@ -160,6 +165,7 @@ pub struct ChainConfig {
     max_block_header_size: usize,
     max_block_size_with_standard_txs: usize,
     max_block_size_with_smart_contracts: usize,
+    max_block_sigops: usize,
     max_no_signature_data_size: usize,
     max_depth_for_reorg: BlockDistance,
     epoch_length: NonZeroU64,
@@ -233,6 +239,24 @@ impl ChainConfig {
         &self.net_upgrades
     }
 
+    /// The heights at which the additional kinds of keys for signing transaction inputs activate
+    #[must_use]
+    pub fn key_kind_upgrades(&self) -> &NetUpgrades<KeyKindUpgrade> {
+        &self.key_kind_upgrades
+    }
+
+    /// The heights at which spending script hash outputs activates
+    #[must_use]
+    pub fn script_hash_upgrades(&self) -> &NetUpgrades<ScriptHashUpgrade> {
+        &self.script_hash_upgrades
+    }
+
+    /// The heights at which the additional kinds of token data in outputs activate
+    #[must_use]
+    pub fn token_upgrades(&self) -> &NetUpgrades<TokensUpgrade> {
+        &self.token_upgrades
+    }
+
     /// Checkpoints enforced by the chain, as in, a block id vs height that must be satisfied
     #[must_use]
     pub fn height_checkpoints(&self) -> &Checkpoints {
@@ -310,6 +334,13 @@ impl ChainConfig {
         self.max_block_size_with_smart_contracts
     }
 
+    /// The maximum number of signature operations in the scripts spent by the transactions of
+    /// a block, see [crate::chain::signature::input_sigop_count]
+    #[must_use]
+    pub fn max_block_sigops(&self) -> usize {
+        self.max_block_sigops
+    }
+
     /// The maximum size of any transaction submitted to the node for the mempool
     pub fn max_tx_size_for_mempool(&self) -> usize {
         std::cmp::min(
@@ -477,6 +508,7 @@ impl AsRef<ChainConfig> for ChainConfig {
 const MAX_BLOCK_HEADER_SIZE: usize = 1024;
 const MAX_BLOCK_TXS_SIZE: usize = 1_048_576;
 const MAX_BLOCK_CONTRACTS_SIZE: usize = 1_048_576;
+const MAX_BLOCK_SIGOPS: usize = 20_000;
 const MAX_TX_NO_SIG_WITNESS_SIZE: usize = 128;
 const TOKEN_MIN_ISSUANCE_FEE: Amount = Amount::from_atoms(10_000_000_000_000);
 const TOKEN_MAX_DEC_COUNT: u8 = 18;
@@ -661,6 +693,8 @@ pub fn create_regtest() -> ChainConfig {
 pub fn create_unit_test_config() -> ChainConfig {
     Builder::new(ChainType::Mainnet)
         .net_upgrades(NetUpgrades::unit_tests())
+        .token_upgrades(NetUpgrades::all_token_versions())
+        .script_hash_upgrades(NetUpgrades::script_hash_spendable())
         .genesis_unittest(Destination::AnyoneCanSpend)
         .build()
 }
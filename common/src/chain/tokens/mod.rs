@@ -17,30 +17,54 @@ use serialization::{Decode, Encode};
 
 pub type TokenId = H256;
 pub type NftDataHash = Vec<u8>;
-use crate::primitives::{Amount, Id, H256};
+use crate::primitives::{Amount, BlockHeight, Id, H256};
 
 mod nft;
 mod rpc;
+mod tokens_undo;
 mod tokens_utils;
 
 pub use nft::*;
 pub use rpc::*;
+pub use tokens_undo::*;
 pub use tokens_utils::*;
 
-use super::{Block, Transaction};
+use super::{Block, Destination, Transaction};
 
 /// The data that is created when a token is issued to track it (and to update it with ACL commands)
 #[derive(Debug, Clone, Encode, Decode, Eq, PartialEq)]
 pub struct TokenAuxiliaryData {
     issuance_tx: Transaction,
     issuance_block_id: Id<Block>,
+    /// The supply state of a token issued with an authority, `None` for tokens with fixed supply
+    supply: Option<TokenSupplyData>,
 }
 
 impl TokenAuxiliaryData {
     pub fn new(issuance_tx: Transaction, issuance_block_id: Id<Block>) -> Self {
+        let supply =
+            issuance_tx
+                .outputs()
+                .iter()
+                .find_map(|output| match output_token_data(output)? {
+                    TokenData::TokenIssuanceV1(issuance) => Some(TokenSupplyData::new(
+                        issuance.authority.clone(),
+                        issuance.amount_to_issue,
+                    )),
+                    TokenData::TokenTransfer(_)
+                    | TokenData::TokenIssuance(_)
+                    | TokenData::NftIssuance(_)
+                    | TokenData::TokenReissueV1 { .. }
+                    | TokenData::LockTokenSupplyV1 { .. }
+                    | TokenData::ChangeTokenAuthorityV1 { .. }
+                    | TokenData::FreezeTokenV1 { .. }
+                    | TokenData::UnfreezeTokenV1 { .. } => None,
+                });
+
         Self {
             issuance_tx,
             issuance_block_id,
+            supply,
         }
     }
 
@@ -51,6 +75,147 @@ impl TokenAuxiliaryData {
     pub fn issuance_block_id(&self) -> Id<Block> {
         self.issuance_block_id
     }
+
+    pub fn supply(&self) -> Option<&TokenSupplyData> {
+        self.supply.as_ref()
+    }
+
+    pub fn supply_mut(&mut self) -> Option<&mut TokenSupplyData> {
+        self.supply.as_mut()
+    }
+}
+
+/// Amounts of a token aggregated over the mainchain
+#[derive(Debug, Clone, Encode, Decode, Eq, PartialEq)]
+pub struct TokenStats {
+    issuance_block_height: BlockHeight,
+    /// Amount held by the outputs that weren't burned
+    circulating_supply: Amount,
+    /// Amount destroyed either by burn outputs or by not being transferred to any output
+    burned: Amount,
+}
+
+impl TokenStats {
+    pub fn new(issuance_block_height: BlockHeight) -> Self {
+        Self {
+            issuance_block_height,
+            circulating_supply: Amount::ZERO,
+            burned: Amount::ZERO,
+        }
+    }
+
+    pub fn issuance_block_height(&self) -> BlockHeight {
+        self.issuance_block_height
+    }
+
+    pub fn circulating_supply(&self) -> Amount {
+        self.circulating_supply
+    }
+
+    pub fn burned(&self) -> Amount {
+        self.burned
+    }
+
+    /// Accounts for the tokens moved by a transaction.
+    /// Returns `None` if the amounts overflow or the transaction spends more than exists.
+    pub fn apply_transaction(
+        &mut self,
+        spent: Amount,
+        created: Amount,
+        burned: Amount,
+    ) -> Option<()> {
+        self.circulating_supply = ((self.circulating_supply + created)? - spent)?;
+        self.burned = (self.burned + burned)?;
+        Some(())
+    }
+
+    /// Reverts [`Self::apply_transaction`] called with the same amounts
+    pub fn undo_transaction(
+        &mut self,
+        spent: Amount,
+        created: Amount,
+        burned: Amount,
+    ) -> Option<()> {
+        self.circulating_supply = ((self.circulating_supply + spent)? - created)?;
+        self.burned = (self.burned - burned)?;
+        Some(())
+    }
+}
+
+/// Supply state of a token that can be reissued by its authority
+#[derive(Debug, Clone, Encode, Decode, Eq, PartialEq)]
+pub struct TokenSupplyData {
+    authority: Destination,
+    /// Total amount of tokens issued so far, including the initial issuance
+    total_issued: Amount,
+    /// Once locked, the supply can't be increased anymore
+    locked: bool,
+    /// Frozen tokens can't be transferred or reissued until the authority unfreezes them
+    frozen: bool,
+}
+
+impl TokenSupplyData {
+    pub fn new(authority: Destination, total_issued: Amount) -> Self {
+        Self {
+            authority,
+            total_issued,
+            locked: false,
+            frozen: false,
+        }
+    }
+
+    pub fn authority(&self) -> &Destination {
+        &self.authority
+    }
+
+    pub fn total_issued(&self) -> Amount {
+        self.total_issued
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.frozen
+    }
+
+    /// Returns `None` if the total supply overflows
+    pub fn reissue(&mut self, amount: Amount) -> Option<()> {
+        self.total_issued = (self.total_issued + amount)?;
+        Some(())
+    }
+
+    /// Returns `None` if more tokens are undone than issued
+    pub fn undo_reissue(&mut self, amount: Amount) -> Option<()> {
+        self.total_issued = (self.total_issued - amount)?;
+        Some(())
+    }
+
+    pub fn lock(&mut self) {
+        self.locked = true;
+    }
+
+    pub fn undo_lock(&mut self) {
+        self.locked = false;
+    }
+
+    pub fn freeze(&mut self) {
+        self.frozen = true;
+    }
+
+    pub fn unfreeze(&mut self) {
+        self.frozen = false;
+    }
+
+    /// Returns the replaced authority, which is needed to undo the change
+    pub fn change_authority(&mut self, new_authority: Destination) -> Destination {
+        std::mem::replace(&mut self.authority, new_authority)
+    }
+
+    pub fn undo_change_authority(&mut self, previous_authority: Destination) {
+        self.authority = previous_authority;
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
@@ -95,6 +260,18 @@ pub struct TokenIssuance {
     pub metadata_uri: Vec<u8>,
 }
 
+/// Issuance of a fungible token whose supply can be increased later by the authority
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Encode, Decode)]
+pub struct TokenIssuanceV1 {
+    pub token_ticker: Vec<u8>,
+    /// The initial supply, can be zero
+    pub amount_to_issue: Amount,
+    pub number_of_decimals: u8,
+    pub metadata_uri: Vec<u8>,
+    /// The owner of the destination can reissue tokens, lock the supply and change the authority
+    pub authority: Destination,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
 pub enum TokenData {
     /// TokenTransfer data to another user. If it is a token, then the token data must also be transferred to the recipient.
@@ -106,13 +283,30 @@ pub enum TokenData {
     // A new NFT creation
     #[codec(index = 3)]
     NftIssuance(Box<NftIssuance>),
-    // TODO: These types will be implemented in the future PRs
-    // // Increase amount of tokens
-    // #[codec(index = 4)]
-    // TokenReissueV1 {
-    //     token_id: TokenId,
-    //     amount_to_issue: Amount,
-    // },
+    /// Increase amount of tokens; the new tokens are sent to the output destination
+    #[codec(index = 4)]
+    TokenReissueV1 {
+        token_id: TokenId,
+        amount_to_issue: Amount,
+    },
+    /// New token creation with an authority that controls the supply
+    #[codec(index = 5)]
+    TokenIssuanceV1(Box<TokenIssuanceV1>),
+    /// Forbid any further reissuance of the token
+    #[codec(index = 6)]
+    LockTokenSupplyV1 { token_id: TokenId },
+    /// Pass the control over the token supply to another destination
+    #[codec(index = 7)]
+    ChangeTokenAuthorityV1 {
+        token_id: TokenId,
+        new_authority: Destination,
+    },
+    /// Forbid any transfers of the token until it's unfrozen
+    #[codec(index = 8)]
+    FreezeTokenV1 { token_id: TokenId },
+    /// Allow transfers of a frozen token again
+    #[codec(index = 9)]
+    UnfreezeTokenV1 { token_id: TokenId },
 }
 
 impl From<NftIssuance> for TokenData {
@@ -127,6 +321,12 @@ impl From<TokenIssuance> for TokenData {
     }
 }
 
+impl From<TokenIssuanceV1> for TokenData {
+    fn from(d: TokenIssuanceV1) -> Self {
+        Self::TokenIssuanceV1(Box::new(d))
+    }
+}
+
 impl From<TokenTransfer> for OutputValue {
     fn from(d: TokenTransfer) -> Self {
         TokenData::TokenTransfer(d).into()
@@ -144,3 +344,9 @@ impl From<TokenIssuance> for OutputValue {
         TokenData::TokenIssuance(Box::new(d)).into()
     }
 }
+
+impl From<TokenIssuanceV1> for OutputValue {
+    fn from(d: TokenIssuanceV1) -> Self {
+        TokenData::TokenIssuanceV1(Box::new(d)).into()
+    }
+}
@@ -13,10 +13,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use super::{Metadata, TokenCreator, TokenId};
+use super::{Metadata, TokenCreator, TokenId, TokenStats};
 use crate::{
     chain::{Block, Transaction},
-    primitives::{Amount, Id},
+    primitives::{Amount, BlockHeight, Id},
 };
 use serialization::{Decode, Encode};
 
@@ -131,3 +131,22 @@ impl From<&Metadata> for RPCNonFungibleTokenMetadata {
         }
     }
 }
+
+#[derive(Debug, Clone, Encode, Decode, serde::Serialize, serde::Deserialize)]
+pub struct RPCTokenStats {
+    pub token_id: TokenId,
+    pub issuance_block_height: BlockHeight,
+    pub circulating_supply: Amount,
+    pub burned: Amount,
+}
+
+impl RPCTokenStats {
+    pub fn new(token_id: TokenId, stats: &TokenStats) -> Self {
+        Self {
+            token_id,
+            issuance_block_height: stats.issuance_block_height(),
+            circulating_supply: stats.circulating_supply(),
+            burned: stats.burned(),
+        }
+    }
+}
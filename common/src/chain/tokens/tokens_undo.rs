@@ -0,0 +1,110 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{btree_map::Entry, BTreeMap};
+
+use serialization::{Decode, Encode};
+use thiserror::Error;
+
+use super::TokenId;
+use crate::{
+    chain::{Destination, Transaction},
+    primitives::Id,
+};
+
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+pub enum TokensBlockUndoError {
+    #[error("Attempted to insert a transaction in undo that already exists: `{0}`")]
+    UndoAlreadyExists(Id<Transaction>),
+}
+
+/// Data needed to revert the token supply changes of a transaction
+#[derive(Default, Debug, Clone, Eq, PartialEq, Encode, Decode)]
+pub struct TokensTxUndo {
+    /// Authorities replaced by the transaction, in the order of its outputs
+    previous_authorities: Vec<(TokenId, Destination)>,
+}
+
+impl TokensTxUndo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.previous_authorities.is_empty()
+    }
+
+    pub fn push_previous_authority(&mut self, token_id: TokenId, authority: Destination) {
+        self.previous_authorities.push((token_id, authority));
+    }
+
+    /// Takes the authority replaced by the last authority change of the transaction.
+    ///
+    /// Returns `None` if the last change doesn't belong to the token.
+    pub fn pop_previous_authority(&mut self, token_id: &TokenId) -> Option<Destination> {
+        let (id, authority) = self.previous_authorities.pop()?;
+        (id == *token_id).then_some(authority)
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Encode, Decode)]
+pub struct TokensBlockUndo {
+    tx_undos: BTreeMap<Id<Transaction>, TokensTxUndo>,
+}
+
+impl TokensBlockUndo {
+    pub fn new(tx_undos: BTreeMap<Id<Transaction>, TokensTxUndo>) -> Self {
+        Self { tx_undos }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tx_undos.is_empty()
+    }
+
+    pub fn tx_undos(&self) -> &BTreeMap<Id<Transaction>, TokensTxUndo> {
+        &self.tx_undos
+    }
+
+    pub fn insert_tx_undo(
+        &mut self,
+        tx_id: Id<Transaction>,
+        tx_undo: TokensTxUndo,
+    ) -> Result<(), TokensBlockUndoError> {
+        match self.tx_undos.entry(tx_id) {
+            Entry::Vacant(e) => {
+                e.insert(tx_undo);
+                Ok(())
+            }
+            Entry::Occupied(_) => Err(TokensBlockUndoError::UndoAlreadyExists(tx_id)),
+        }
+    }
+
+    pub fn take_tx_undo(&mut self, tx_id: &Id<Transaction>) -> Option<TokensTxUndo> {
+        self.tx_undos.remove(tx_id)
+    }
+
+    pub fn combine(&mut self, other: TokensBlockUndo) -> Result<(), TokensBlockUndoError> {
+        other
+            .tx_undos
+            .into_iter()
+            .try_for_each(|(id, u)| match self.tx_undos.entry(id) {
+                Entry::Vacant(e) => {
+                    e.insert(u);
+                    Ok(())
+                }
+                Entry::Occupied(_) => Err(TokensBlockUndoError::UndoAlreadyExists(id)),
+            })
+    }
+}
@@ -13,7 +13,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use super::TokenId;
+use super::{TokenData, TokenId};
 use crate::{
     chain::{Transaction, TxOutput},
     primitives::id::hash_encoded,
@@ -26,3 +26,16 @@ pub fn token_id(tx: &Transaction) -> Option<TokenId> {
 pub fn get_tokens_issuance_count(outputs: &[TxOutput]) -> usize {
     outputs.iter().filter(|&output| output.is_token_or_nft_issuance()).count()
 }
+
+/// Token data carried by the output, if any
+pub fn output_token_data(output: &TxOutput) -> Option<&TokenData> {
+    match output {
+        TxOutput::Transfer(v, _) | TxOutput::LockThenTransfer(v, _, _) | TxOutput::Burn(v) => {
+            v.token_data()
+        }
+        TxOutput::CreateStakePool(_, _)
+        | TxOutput::ProduceBlockFromStake(_, _)
+        | TxOutput::CreateDelegationId(_, _)
+        | TxOutput::DelegateStaking(_, _) => None,
+    }
+}
@@ -20,7 +20,19 @@ use crate::{
 use serialization::{Decode, Encode};
 
 // Type of an account that can be used to identify series of spending from an account
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd, Encode, Decode)]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Ord,
+    PartialOrd,
+    Encode,
+    Decode,
+    serde::Serialize,
+    serde::Deserialize,
+)]
 pub enum AccountType {
     #[codec(index = 0)]
     Delegation(DelegationId),
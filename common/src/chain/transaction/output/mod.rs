@@ -19,7 +19,7 @@ use crate::{
         tokens::{OutputValue, TokenData},
         DelegationId, PoolId,
     },
-    primitives::{Amount, Id},
+    primitives::{id, Amount, Id, Idable},
 };
 use script::Script;
 use serialization::{Decode, Encode};
@@ -44,6 +44,14 @@ pub enum Destination {
     ClassicMultisig(PublicKeyHash),
 }
 
+/// The hash of a redeem script, as committed to by [Destination::ScriptHash]
+impl Idable for Script {
+    type Tag = Script;
+    fn get_id(&self) -> Id<Self::Tag> {
+        Id::new(id::hash_encoded(self))
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
 pub enum TxOutput {
     #[codec(index = 0)]
@@ -78,13 +86,34 @@ impl TxOutput {
         }
     }
 
+    /// All the destinations the output refers to, including the keys of a stake pool
+    pub fn destinations(&self) -> Vec<&Destination> {
+        match self {
+            TxOutput::Transfer(_, d)
+            | TxOutput::LockThenTransfer(_, d, _)
+            | TxOutput::ProduceBlockFromStake(d, _)
+            | TxOutput::CreateDelegationId(d, _) => vec![d],
+            TxOutput::CreateStakePool(_, pool_data) => {
+                vec![pool_data.staker(), pool_data.decommission_key()]
+            }
+            TxOutput::Burn(_) | TxOutput::DelegateStaking(_, _) => vec![],
+        }
+    }
+
     pub fn is_token_or_nft_issuance(&self) -> bool {
         match self {
             TxOutput::Transfer(v, _) | TxOutput::LockThenTransfer(v, _, _) | TxOutput::Burn(v) => {
                 match v {
                     OutputValue::Token(data) => match data.as_ref() {
-                        TokenData::TokenIssuance(_) | TokenData::NftIssuance(_) => true,
-                        TokenData::TokenTransfer(_) => false,
+                        TokenData::TokenIssuance(_)
+                        | TokenData::NftIssuance(_)
+                        | TokenData::TokenIssuanceV1(_) => true,
+                        TokenData::TokenTransfer(_)
+                        | TokenData::TokenReissueV1 { .. }
+                        | TokenData::LockTokenSupplyV1 { .. }
+                        | TokenData::ChangeTokenAuthorityV1 { .. }
+                        | TokenData::FreezeTokenV1 { .. }
+                        | TokenData::UnfreezeTokenV1 { .. } => false,
                     },
                     OutputValue::Coin(_) => false,
                 }
@@ -39,6 +39,10 @@ impl AuthorizedPublicKeyHashSpend {
             signature,
         }
     }
+
+    pub fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
 }
 
 pub fn verify_address_spending(
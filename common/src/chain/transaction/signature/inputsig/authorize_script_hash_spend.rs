@@ -0,0 +1,566 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crypto::key::{PublicKey, Signature};
+use script::{context::ParseResult, Script};
+use serialization::{Decode, DecodeAll, Encode};
+
+use crate::{
+    chain::{block::timestamp::BlockTimestamp, signature::TransactionSigError, ChainConfig},
+    primitives::{BlockHeight, Id, Idable, H256},
+};
+
+use super::is_key_kind_activated;
+
+/// Lock time values below this threshold are interpreted as block heights by
+/// OP_CHECKLOCKTIMEVERIFY, values at or above it as block timestamps (in seconds).
+pub const LOCK_TIME_THRESHOLD: i64 = 500_000_000;
+
+/// The maximum number of signature operations in the redeem script of a single input.
+pub const MAX_SIGOPS_PER_SCRIPT_HASH_SPEND: usize = 15;
+
+/// A witness that represents the authorization to spend an output locked with a script hash.
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+pub struct AuthorizedScriptHashSpend {
+    /// The script whose hash is committed to in the destination.
+    redeem_script: Script,
+    /// A push-only script that provides the arguments for the redeem script.
+    arguments: Script,
+}
+
+impl AuthorizedScriptHashSpend {
+    pub fn new(redeem_script: Script, arguments: Script) -> Self {
+        Self {
+            redeem_script,
+            arguments,
+        }
+    }
+
+    pub fn from_data(data: &[u8]) -> Result<Self, TransactionSigError> {
+        let decoded = AuthorizedScriptHashSpend::decode_all(&mut &data[..])
+            .map_err(|_| TransactionSigError::InvalidSignatureEncoding)?;
+        Ok(decoded)
+    }
+
+    pub fn redeem_script(&self) -> &Script {
+        &self.redeem_script
+    }
+
+    pub fn arguments(&self) -> &Script {
+        &self.arguments
+    }
+
+    /// The number of signature operations in the redeem script, see [Script::sigop_count]
+    pub fn sigop_count(&self) -> usize {
+        self.redeem_script
+            .sigop_count(<ScriptSpendContext as script::Context>::MAX_PUBKEYS_PER_MULTISIG)
+    }
+}
+
+/// Information about the block the spending transaction is included in,
+/// required to evaluate time lock opcodes in scripts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpendingBlockInfo {
+    height: BlockHeight,
+    /// The median time past of the block, time locks are compared against it as per BIP-113
+    timestamp: BlockTimestamp,
+}
+
+impl SpendingBlockInfo {
+    pub fn new(height: BlockHeight, timestamp: BlockTimestamp) -> Self {
+        Self { height, timestamp }
+    }
+
+    pub fn height(&self) -> BlockHeight {
+        self.height
+    }
+
+    pub fn timestamp(&self) -> BlockTimestamp {
+        self.timestamp
+    }
+}
+
+/// Script interpreter context for spending a [crate::chain::Destination::ScriptHash] output.
+///
+/// Public keys and signatures on the stack are the encoded [PublicKey] and [Signature] and
+/// signatures commit to the transaction sighash, just like the other standard signatures do.
+/// Since the sighash already covers the input being spent, OP_CODESEPARATOR has no effect
+/// on what is signed.
+pub struct ScriptSpendContext<'a> {
+    chain_config: &'a ChainConfig,
+    sighash: &'a H256,
+    spending_block: Option<&'a SpendingBlockInfo>,
+}
+
+impl<'a> ScriptSpendContext<'a> {
+    pub fn new(
+        chain_config: &'a ChainConfig,
+        sighash: &'a H256,
+        spending_block: Option<&'a SpendingBlockInfo>,
+    ) -> Self {
+        Self {
+            chain_config,
+            sighash,
+            spending_block,
+        }
+    }
+}
+
+impl script::Context for ScriptSpendContext<'_> {
+    const MAX_PUBKEYS_PER_MULTISIG: usize = 20;
+    const MAX_SCRIPT_SIZE: usize = 10_000;
+
+    type Public = PublicKey;
+    type SignatureData = (PublicKey, Signature);
+
+    fn parse_pubkey(&self, pk: &[u8]) -> ParseResult<Self::Public> {
+        PublicKey::decode_all(&mut &pk[..]).ok().into()
+    }
+
+    fn parse_signature(&self, pk: Self::Public, sig: &[u8]) -> Option<Self::SignatureData> {
+        Signature::decode_all(&mut &sig[..]).ok().map(|sig| (pk, sig))
+    }
+
+    fn verify_signature(
+        &self,
+        (pk, sig): &Self::SignatureData,
+        _subscript: &[u8],
+        _codesep_idx: u32,
+    ) -> bool {
+        is_key_kind_activated(self.chain_config, pk, self.spending_block)
+            && pk.verify_message(sig, &self.sighash.encode())
+    }
+
+    fn check_lock_time(&self, lock_time: i64) -> bool {
+        // Without knowing the block the transaction goes into, time locks cannot be satisfied
+        let spending_block = match self.spending_block {
+            Some(info) => info,
+            None => return false,
+        };
+        match u64::try_from(lock_time) {
+            Ok(lock_time) if lock_time < LOCK_TIME_THRESHOLD as u64 => {
+                spending_block.height() >= BlockHeight::new(lock_time)
+            }
+            Ok(lock_time) => spending_block.timestamp().as_int_seconds() >= lock_time,
+            Err(_) => false,
+        }
+    }
+
+    fn check_sequence(&self, _sequence: i64) -> bool {
+        // Relative time locks are not supported in scripts; use OutputTimeLock instead
+        false
+    }
+}
+
+pub fn verify_script_hash_spending(
+    chain_config: &ChainConfig,
+    script_hash: &Id<Script>,
+    spender_signature: &AuthorizedScriptHashSpend,
+    sighash: &H256,
+    spending_block: Option<&SpendingBlockInfo>,
+) -> Result<(), TransactionSigError> {
+    let activated = spending_block.map_or(true, |block| {
+        chain_config
+            .script_hash_upgrades()
+            .is_script_hash_spending_activated(block.height())
+    });
+    if !activated {
+        return Err(TransactionSigError::ScriptHashSpendingNotActivated);
+    }
+
+    if spender_signature.redeem_script().get_id() != *script_hash {
+        return Err(TransactionSigError::ScriptHashMismatch);
+    }
+
+    let sigops = spender_signature.sigop_count();
+    if sigops > MAX_SIGOPS_PER_SCRIPT_HASH_SPEND {
+        return Err(TransactionSigError::TooManySigOps(
+            sigops,
+            MAX_SIGOPS_PER_SCRIPT_HASH_SPEND,
+        ));
+    }
+
+    let context = ScriptSpendContext::new(chain_config, sighash, spending_block);
+    script::verify_witness_lock(
+        &context,
+        spender_signature.arguments(),
+        spender_signature.redeem_script(),
+    )
+    .map_err(TransactionSigError::ScriptVerificationFailed)
+}
+
+/// Produce a signature that can be pushed to the arguments of a script hash spend
+/// to satisfy OP_CHECKSIG or OP_CHECKMULTISIG in the redeem script.
+pub fn sign_script_hash_spending(
+    private_key: &crypto::key::PrivateKey,
+    sighash: &H256,
+) -> Result<Signature, TransactionSigError> {
+    let msg = sighash.encode();
+    private_key
+        .sign_message(&msg)
+        .map_err(TransactionSigError::ProducingSignatureFailed)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::chain::config::{create_unit_test_config, Builder as ConfigBuilder, ChainType};
+    use crate::chain::{KeyKindUpgrade, NetUpgrades, ScriptHashUpgrade};
+    use crypto::key::{KeyKind, PrivateKey};
+    use rstest::rstest;
+    use script::{opcodes::all as opc, Builder};
+    use test_utils::random::{CryptoRng, Rng, Seed};
+
+    fn sighash_and_keys(rng: &mut (impl Rng + CryptoRng)) -> (H256, PrivateKey, PublicKey) {
+        let (private_key, public_key) = PrivateKey::new_from_rng(rng, KeyKind::Secp256k1Schnorr);
+        (H256::random_using(rng), private_key, public_key)
+    }
+
+    fn pay_to_pubkey_script(public_key: &PublicKey) -> Script {
+        Builder::new()
+            .push_slice(&public_key.encode())
+            .push_opcode(opc::OP_CHECKSIG)
+            .into_script()
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn checksig(#[case] seed: Seed) {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+        let chain_config = create_unit_test_config();
+        let (sighash, private_key, public_key) = sighash_and_keys(&mut rng);
+
+        let redeem_script = pay_to_pubkey_script(&public_key);
+        let script_hash = redeem_script.get_id();
+
+        let signature = sign_script_hash_spending(&private_key, &sighash).unwrap();
+        let arguments = Builder::new().push_slice(&signature.encode()).into_script();
+        let spend = AuthorizedScriptHashSpend::new(redeem_script.clone(), arguments);
+        assert_eq!(
+            verify_script_hash_spending(&chain_config, &script_hash, &spend, &sighash, None),
+            Ok(())
+        );
+
+        // Signature over a different sighash
+        let other_sighash = H256::random_using(&mut rng);
+        assert_eq!(
+            verify_script_hash_spending(&chain_config, &script_hash, &spend, &other_sighash, None),
+            Err(TransactionSigError::ScriptVerificationFailed(
+                script::Error::VerifyFail
+            ))
+        );
+
+        // Signature by a different key
+        let (other_private_key, _) = PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+        let signature = sign_script_hash_spending(&other_private_key, &sighash).unwrap();
+        let arguments = Builder::new().push_slice(&signature.encode()).into_script();
+        let spend = AuthorizedScriptHashSpend::new(redeem_script, arguments);
+        assert_eq!(
+            verify_script_hash_spending(&chain_config, &script_hash, &spend, &sighash, None),
+            Err(TransactionSigError::ScriptVerificationFailed(
+                script::Error::VerifyFail
+            ))
+        );
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn script_hash_mismatch(#[case] seed: Seed) {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+        let chain_config = create_unit_test_config();
+        let (sighash, private_key, public_key) = sighash_and_keys(&mut rng);
+        let (_, _, other_public_key) = sighash_and_keys(&mut rng);
+
+        let script_hash = pay_to_pubkey_script(&other_public_key).get_id();
+
+        let signature = sign_script_hash_spending(&private_key, &sighash).unwrap();
+        let arguments = Builder::new().push_slice(&signature.encode()).into_script();
+        let spend = AuthorizedScriptHashSpend::new(pay_to_pubkey_script(&public_key), arguments);
+        assert_eq!(
+            verify_script_hash_spending(&chain_config, &script_hash, &spend, &sighash, None),
+            Err(TransactionSigError::ScriptHashMismatch)
+        );
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn hashlock(#[case] seed: Seed) {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+        let chain_config = create_unit_test_config();
+        let sighash = H256::random_using(&mut rng);
+
+        let preimage: [u8; 32] = rng.gen();
+        let image = crypto::hash::hash::<crypto::hash::Sha256, _>(preimage);
+        let redeem_script = Builder::new()
+            .push_opcode(opc::OP_SHA256)
+            .push_slice(image.as_slice())
+            .push_opcode(opc::OP_EQUAL)
+            .into_script();
+        let script_hash = redeem_script.get_id();
+
+        let arguments = Builder::new().push_slice(&preimage).into_script();
+        let spend = AuthorizedScriptHashSpend::new(redeem_script.clone(), arguments);
+        assert_eq!(
+            verify_script_hash_spending(&chain_config, &script_hash, &spend, &sighash, None),
+            Ok(())
+        );
+
+        let arguments = Builder::new().push_slice(&[0u8; 32]).into_script();
+        let spend = AuthorizedScriptHashSpend::new(redeem_script, arguments);
+        assert_eq!(
+            verify_script_hash_spending(&chain_config, &script_hash, &spend, &sighash, None),
+            Err(TransactionSigError::ScriptVerificationFailed(
+                script::Error::VerifyFail
+            ))
+        );
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn timelocked_escrow(#[case] seed: Seed) {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+        let chain_config = create_unit_test_config();
+        let (sighash, buyer_sk, buyer_pk) = sighash_and_keys(&mut rng);
+        let (_, seller_sk, seller_pk) = sighash_and_keys(&mut rng);
+        let lock_height = rng.gen_range(1..1000);
+
+        // Either both parties sign, or the buyer alone after the lock height
+        let redeem_script = Builder::new()
+            .push_opcode(opc::OP_IF)
+            .push_int(2)
+            .push_slice(&buyer_pk.encode())
+            .push_slice(&seller_pk.encode())
+            .push_int(2)
+            .push_opcode(opc::OP_CHECKMULTISIG)
+            .push_opcode(opc::OP_ELSE)
+            .push_int(lock_height)
+            .push_opcode(opc::OP_CLTV)
+            .push_opcode(opc::OP_DROP)
+            .push_slice(&buyer_pk.encode())
+            .push_opcode(opc::OP_CHECKSIG)
+            .push_opcode(opc::OP_ENDIF)
+            .into_script();
+        let script_hash = redeem_script.get_id();
+
+        let buyer_sig = sign_script_hash_spending(&buyer_sk, &sighash).unwrap().encode();
+        let seller_sig = sign_script_hash_spending(&seller_sk, &sighash).unwrap().encode();
+
+        let cooperative = AuthorizedScriptHashSpend::new(
+            redeem_script.clone(),
+            Builder::new()
+                .push_int(0)
+                .push_slice(&buyer_sig)
+                .push_slice(&seller_sig)
+                .push_int(1)
+                .into_script(),
+        );
+        assert_eq!(
+            verify_script_hash_spending(&chain_config, &script_hash, &cooperative, &sighash, None),
+            Ok(())
+        );
+
+        let refund = AuthorizedScriptHashSpend::new(
+            redeem_script,
+            Builder::new().push_slice(&buyer_sig).push_int(0).into_script(),
+        );
+        let timestamp = BlockTimestamp::from_int_seconds(rng.gen());
+        let too_early = SpendingBlockInfo::new(BlockHeight::new(lock_height as u64 - 1), timestamp);
+        let late_enough = SpendingBlockInfo::new(BlockHeight::new(lock_height as u64), timestamp);
+        assert_eq!(
+            verify_script_hash_spending(&chain_config, &script_hash, &refund, &sighash, None),
+            Err(TransactionSigError::ScriptVerificationFailed(
+                script::Error::TimeLock
+            ))
+        );
+        assert_eq!(
+            verify_script_hash_spending(
+                &chain_config,
+                &script_hash,
+                &refund,
+                &sighash,
+                Some(&too_early)
+            ),
+            Err(TransactionSigError::ScriptVerificationFailed(
+                script::Error::TimeLock
+            ))
+        );
+        assert_eq!(
+            verify_script_hash_spending(
+                &chain_config,
+                &script_hash,
+                &refund,
+                &sighash,
+                Some(&late_enough)
+            ),
+            Ok(())
+        );
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn checksig_key_kind_activation(#[case] seed: Seed) {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+        let activation_height = BlockHeight::new(rng.gen_range(1..1000));
+        let chain_config = ConfigBuilder::new(ChainType::Regtest)
+            .key_kind_upgrades(
+                NetUpgrades::initialize(vec![
+                    (BlockHeight::zero(), KeyKindUpgrade::Secp256k1Schnorr),
+                    (activation_height, KeyKindUpgrade::Ed25519),
+                ])
+                .unwrap(),
+            )
+            .build();
+
+        let sighash = H256::random_using(&mut rng);
+        let (private_key, public_key) = PrivateKey::new_from_rng(&mut rng, KeyKind::Ed25519);
+        let redeem_script = pay_to_pubkey_script(&public_key);
+        let script_hash = redeem_script.get_id();
+
+        let signature = sign_script_hash_spending(&private_key, &sighash).unwrap();
+        let arguments = Builder::new().push_slice(&signature.encode()).into_script();
+        let spend = AuthorizedScriptHashSpend::new(redeem_script, arguments);
+
+        let timestamp = BlockTimestamp::from_int_seconds(rng.gen());
+        let before_activation = SpendingBlockInfo::new(
+            BlockHeight::new(activation_height.into_int() - 1),
+            timestamp,
+        );
+        let at_activation = SpendingBlockInfo::new(activation_height, timestamp);
+        assert_eq!(
+            verify_script_hash_spending(
+                &chain_config,
+                &script_hash,
+                &spend,
+                &sighash,
+                Some(&before_activation)
+            ),
+            Err(TransactionSigError::ScriptVerificationFailed(
+                script::Error::VerifyFail
+            ))
+        );
+        assert_eq!(
+            verify_script_hash_spending(
+                &chain_config,
+                &script_hash,
+                &spend,
+                &sighash,
+                Some(&at_activation)
+            ),
+            Ok(())
+        );
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn sigop_limit(#[case] seed: Seed) {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+        let chain_config = create_unit_test_config();
+        let (sighash, private_key, public_key) = sighash_and_keys(&mut rng);
+        let signature = sign_script_hash_spending(&private_key, &sighash).unwrap();
+
+        // Each signature check consumes the signature and the key, which are put back with
+        // OP_2DUP for the next one, so only the last check leaves a result on the stack
+        let redeem_script_with_sigops = |sigops: usize| -> Script {
+            (1..sigops)
+                .fold(
+                    Builder::new().push_slice(&public_key.encode()),
+                    |builder, _| {
+                        builder.push_opcode(opc::OP_2DUP).push_opcode(opc::OP_CHECKSIGVERIFY)
+                    },
+                )
+                .push_opcode(opc::OP_CHECKSIG)
+                .into_script()
+        };
+        let arguments = Builder::new().push_slice(&signature.encode()).into_script();
+
+        let redeem_script = redeem_script_with_sigops(MAX_SIGOPS_PER_SCRIPT_HASH_SPEND);
+        let script_hash = redeem_script.get_id();
+        let spend = AuthorizedScriptHashSpend::new(redeem_script, arguments.clone());
+        assert_eq!(spend.sigop_count(), MAX_SIGOPS_PER_SCRIPT_HASH_SPEND);
+        assert_eq!(
+            verify_script_hash_spending(&chain_config, &script_hash, &spend, &sighash, None),
+            Ok(())
+        );
+
+        let redeem_script = redeem_script_with_sigops(MAX_SIGOPS_PER_SCRIPT_HASH_SPEND + 1);
+        let script_hash = redeem_script.get_id();
+        let spend = AuthorizedScriptHashSpend::new(redeem_script, arguments);
+        assert_eq!(
+            verify_script_hash_spending(&chain_config, &script_hash, &spend, &sighash, None),
+            Err(TransactionSigError::TooManySigOps(
+                MAX_SIGOPS_PER_SCRIPT_HASH_SPEND + 1,
+                MAX_SIGOPS_PER_SCRIPT_HASH_SPEND
+            ))
+        );
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn script_hash_spending_activation(#[case] seed: Seed) {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+        let activation_height = BlockHeight::new(rng.gen_range(1..1000));
+        let chain_config = ConfigBuilder::new(ChainType::Regtest)
+            .script_hash_upgrades(
+                NetUpgrades::initialize(vec![
+                    (BlockHeight::zero(), ScriptHashUpgrade::Unspendable),
+                    (activation_height, ScriptHashUpgrade::Spendable),
+                ])
+                .unwrap(),
+            )
+            .build();
+
+        let (sighash, private_key, public_key) = sighash_and_keys(&mut rng);
+        let redeem_script = pay_to_pubkey_script(&public_key);
+        let script_hash = redeem_script.get_id();
+
+        let signature = sign_script_hash_spending(&private_key, &sighash).unwrap();
+        let arguments = Builder::new().push_slice(&signature.encode()).into_script();
+        let spend = AuthorizedScriptHashSpend::new(redeem_script, arguments);
+
+        let timestamp = BlockTimestamp::from_int_seconds(rng.gen());
+        let before_activation = SpendingBlockInfo::new(
+            BlockHeight::new(activation_height.into_int() - 1),
+            timestamp,
+        );
+        let at_activation = SpendingBlockInfo::new(activation_height, timestamp);
+        assert_eq!(
+            verify_script_hash_spending(
+                &chain_config,
+                &script_hash,
+                &spend,
+                &sighash,
+                Some(&before_activation)
+            ),
+            Err(TransactionSigError::ScriptHashSpendingNotActivated)
+        );
+        assert_eq!(
+            verify_script_hash_spending(
+                &chain_config,
+                &script_hash,
+                &spend,
+                &sighash,
+                Some(&at_activation)
+            ),
+            Ok(())
+        );
+    }
+}
@@ -15,11 +15,16 @@
 
 pub mod authorize_pubkey_spend;
 pub mod authorize_pubkeyhash_spend;
+pub mod authorize_script_hash_spend;
 pub mod classical_multisig;
 pub mod standard_signature;
 
+use crypto::key::PublicKey;
 use serialization::{Decode, Encode};
 
+use crate::chain::ChainConfig;
+
+use authorize_script_hash_spend::SpendingBlockInfo;
 use standard_signature::StandardInputSignature;
 
 #[derive(Debug, Encode, Decode, Clone, Eq, PartialEq, Ord, PartialOrd)]
@@ -29,3 +34,19 @@ pub enum InputWitness {
     #[codec(index = 1)]
     Standard(StandardInputSignature),
 }
+
+/// Whether the key may sign inputs of a transaction that is included in the given block.
+///
+/// Key kinds are activated at certain heights, so without knowing the block
+/// the activation can't be checked and all the kinds of keys are accepted.
+pub fn is_key_kind_activated(
+    chain_config: &ChainConfig,
+    public_key: &PublicKey,
+    spending_block: Option<&SpendingBlockInfo>,
+) -> bool {
+    spending_block.map_or(true, |block| {
+        chain_config
+            .key_kind_upgrades()
+            .is_key_kind_activated(&public_key.kind(), block.height())
+    })
+}
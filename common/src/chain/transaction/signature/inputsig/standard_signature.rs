@@ -15,6 +15,7 @@
 
 use std::io::BufWriter;
 
+use crypto::key::PublicKey;
 use serialization::{Decode, DecodeAll, Encode};
 
 use crate::{
@@ -35,12 +36,16 @@ use super::{
     authorize_pubkeyhash_spend::{
         sign_address_spending, verify_address_spending, AuthorizedPublicKeyHashSpend,
     },
+    authorize_script_hash_spend::{
+        verify_script_hash_spending, AuthorizedScriptHashSpend, SpendingBlockInfo,
+    },
     classical_multisig::{
         authorize_classical_multisig::{
             verify_classical_multisig_spending, AuthorizedClassicalMultisigSpend,
         },
         multisig_partial_signature::PartiallySignedMultisigChallenge,
     },
+    is_key_kind_activated,
 };
 
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
@@ -72,17 +77,37 @@ impl StandardInputSignature {
         chain_config: &ChainConfig,
         outpoint_destination: &Destination,
         sighash: &H256,
+        spending_block: Option<&SpendingBlockInfo>,
     ) -> Result<(), TransactionSigError> {
+        let ensure_activated = |public_key: &PublicKey| -> Result<(), TransactionSigError> {
+            utils::ensure!(
+                is_key_kind_activated(chain_config, public_key, spending_block),
+                TransactionSigError::KeyKindNotActivated(public_key.kind())
+            );
+            Ok(())
+        };
+
         match outpoint_destination {
             Destination::Address(addr) => {
                 let sig_components = AuthorizedPublicKeyHashSpend::from_data(&self.raw_signature)?;
+                ensure_activated(sig_components.public_key())?;
                 verify_address_spending(addr, &sig_components, sighash)?
             }
             Destination::PublicKey(pubkey) => {
                 let sig_components = AuthorizedPublicKeySpend::from_data(&self.raw_signature)?;
+                ensure_activated(pubkey)?;
                 verify_public_key_spending(pubkey, &sig_components, sighash)?
             }
-            Destination::ScriptHash(_) => return Err(TransactionSigError::Unsupported),
+            Destination::ScriptHash(script_hash) => {
+                let sig_components = AuthorizedScriptHashSpend::from_data(&self.raw_signature)?;
+                verify_script_hash_spending(
+                    chain_config,
+                    script_hash,
+                    &sig_components,
+                    sighash,
+                    spending_block,
+                )?
+            }
             Destination::AnyoneCanSpend => {
                 // AnyoneCanSpend must use InputWitness::NoSignature, so this is unreachable
                 return Err(
@@ -92,6 +117,7 @@ impl StandardInputSignature {
             Destination::ClassicMultisig(h) => {
                 let sig_components =
                     AuthorizedClassicalMultisigSpend::from_data(&self.raw_signature)?;
+                sig_components.challenge().public_keys().iter().try_for_each(ensure_activated)?;
                 verify_classical_multisig_spending(chain_config, h, &sig_components, sighash)?
             }
         }
@@ -116,7 +142,12 @@ impl StandardInputSignature {
                 let sig = sign_pubkey_spending(private_key, pubkey, &sighash)?;
                 sig.encode()
             }
-            Destination::ScriptHash(_) => return Err(TransactionSigError::Unsupported),
+            Destination::ScriptHash(_) => {
+                return Err(
+                    // The redeem script and its arguments are needed, see produce_script_hash_signature_for_input
+                    TransactionSigError::AttemptedToProduceScriptHashSignatureInUniPartyFunction,
+                );
+            }
 
             Destination::AnyoneCanSpend => {
                 // AnyoneCanSpend must use InputWitness::NoSignature, so this is unreachable
@@ -163,6 +194,20 @@ impl StandardInputSignature {
         })
     }
 
+    /// Wrap a script hash spend authorization into a signature.
+    ///
+    /// The signatures pushed to the arguments of the authorization are expected to be made over
+    /// the sighash of the given type, see `sign_script_hash_spending`.
+    pub fn produce_script_hash_signature_for_input(
+        authorization: &AuthorizedScriptHashSpend,
+        sighash_type: SigHashType,
+    ) -> Self {
+        Self {
+            sighash_type,
+            raw_signature: authorization.encode(),
+        }
+    }
+
     pub fn raw_signature(&self) -> &[u8] {
         &self.raw_signature
     }
@@ -215,13 +260,17 @@ mod test {
     };
 
     use super::*;
+    use crate::chain::block::timestamp::BlockTimestamp;
+    use crate::chain::config::{Builder as ConfigBuilder, ChainType};
     use crate::chain::signature::tests::utils::generate_inputs_utxos;
     use crate::chain::signature::{sighash::signature_hash, TransactionSigError};
     use crate::chain::Destination;
+    use crate::chain::{KeyKindUpgrade, NetUpgrades};
+    use crate::primitives::BlockHeight;
     use crypto::key::{KeyKind, PrivateKey};
     use itertools::Itertools;
     use rstest::rstest;
-    use test_utils::random::Seed;
+    use test_utils::random::{Rng, Seed};
 
     const INPUT_NUM: usize = 0;
 
@@ -290,13 +339,15 @@ mod test {
     #[rstest]
     #[trace]
     #[case(Seed::from_entropy())]
-    fn produce_and_verify(#[case] seed: Seed) {
+    fn produce_and_verify(
+        #[case] seed: Seed,
+        #[values(KeyKind::Secp256k1Schnorr, KeyKind::Ed25519)] key_kind: KeyKind,
+    ) {
         let mut rng = test_utils::random::make_seedable_rng(seed);
 
         let chain_config = create_mainnet();
 
-        let (private_key, public_key) =
-            PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+        let (private_key, public_key) = PrivateKey::new_from_rng(&mut rng, key_kind);
         let outpoints = [
             Destination::Address(PublicKeyHash::from(&public_key)),
             Destination::PublicKey(public_key),
@@ -322,8 +373,90 @@ mod test {
             let sighash =
                 signature_hash(witness.sighash_type(), &tx, &inputs_utxos_refs, INPUT_NUM).unwrap();
             witness
-                .verify_signature(&chain_config, &destination, &sighash)
+                .verify_signature(&chain_config, &destination, &sighash, None)
                 .unwrap_or_else(|_| panic!("{sighash_type:X?} {destination:?}"));
         }
     }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn verify_key_kind_activation(#[case] seed: Seed) {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+
+        let activation_height = BlockHeight::new(rng.gen_range(1..1000));
+        let chain_config = ConfigBuilder::new(ChainType::Regtest)
+            .key_kind_upgrades(
+                NetUpgrades::initialize(vec![
+                    (BlockHeight::zero(), KeyKindUpgrade::Secp256k1Schnorr),
+                    (activation_height, KeyKindUpgrade::Ed25519),
+                ])
+                .unwrap(),
+            )
+            .build();
+        let timestamp = BlockTimestamp::from_int_seconds(rng.gen());
+        let before_activation = SpendingBlockInfo::new(
+            BlockHeight::new(activation_height.into_int() - 1),
+            timestamp,
+        );
+        let at_activation = SpendingBlockInfo::new(activation_height, timestamp);
+
+        let (secp_private_key, secp_public_key) =
+            PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+        let (ed_private_key, ed_public_key) = PrivateKey::new_from_rng(&mut rng, KeyKind::Ed25519);
+        let spends = [
+            (
+                &secp_private_key,
+                Destination::Address(PublicKeyHash::from(&secp_public_key)),
+            ),
+            (&secp_private_key, Destination::PublicKey(secp_public_key)),
+            (
+                &ed_private_key,
+                Destination::Address(PublicKeyHash::from(&ed_public_key)),
+            ),
+            (&ed_private_key, Destination::PublicKey(ed_public_key)),
+        ];
+
+        for (private_key, destination) in spends {
+            let (inputs_utxos, _priv_keys) = generate_inputs_utxos(&mut rng, 1);
+            let inputs_utxos_refs =
+                inputs_utxos.iter().map(|utxo| utxo.as_ref()).collect::<Vec<_>>();
+
+            let tx = generate_unsigned_tx(&mut rng, &destination, &inputs_utxos, 2).unwrap();
+            let witness = StandardInputSignature::produce_uniparty_signature_for_input(
+                private_key,
+                SigHashType::try_from(SigHashType::ALL).unwrap(),
+                destination.clone(),
+                &tx,
+                &inputs_utxos_refs,
+                INPUT_NUM,
+            )
+            .unwrap();
+            let sighash =
+                signature_hash(witness.sighash_type(), &tx, &inputs_utxos_refs, INPUT_NUM).unwrap();
+
+            let result_before_activation = witness.verify_signature(
+                &chain_config,
+                &destination,
+                &sighash,
+                Some(&before_activation),
+            );
+            match private_key.kind() {
+                KeyKind::Secp256k1Schnorr => assert_eq!(result_before_activation, Ok(())),
+                KeyKind::Ed25519 => assert_eq!(
+                    result_before_activation,
+                    Err(TransactionSigError::KeyKindNotActivated(KeyKind::Ed25519))
+                ),
+            }
+            assert_eq!(
+                witness.verify_signature(
+                    &chain_config,
+                    &destination,
+                    &sighash,
+                    Some(&at_activation)
+                ),
+                Ok(())
+            );
+        }
+    }
 }
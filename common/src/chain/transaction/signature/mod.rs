@@ -17,6 +17,7 @@ use crate::chain::{ChainConfig, SignedTransaction, TxInput};
 
 use self::{
     inputsig::{
+        authorize_script_hash_spend::{AuthorizedScriptHashSpend, SpendingBlockInfo},
         classical_multisig::{
             authorize_classical_multisig::ClassicalMultisigSigningError,
             multisig_partial_signature::PartiallySignedMultisigStructureError,
@@ -90,6 +91,18 @@ pub enum TransactionSigError {
     InvalidClassicalMultisigAuthorization,
     #[error("Standard signature creation failed. Incomplete classical multisig authorization")]
     IncompleteClassicalMultisigAuthorization,
+    #[error("Script hash signature attempted in uni-party function")]
+    AttemptedToProduceScriptHashSignatureInUniPartyFunction,
+    #[error("The hash of the redeem script does not match the destination")]
+    ScriptHashMismatch,
+    #[error("Script verification failed: {0}")]
+    ScriptVerificationFailed(script::Error),
+    #[error("Signing with keys of kind {0:?} is not activated yet")]
+    KeyKindNotActivated(crypto::key::KeyKind),
+    #[error("Spending script hash outputs is not activated yet")]
+    ScriptHashSpendingNotActivated,
+    #[error("Too many signature operations in the redeem script (current: {0}, limit: {1})")]
+    TooManySigOps(usize, usize),
     #[error("Unsupported yet!")]
     Unsupported,
 }
@@ -147,12 +160,78 @@ impl Transactable for SignedTransaction {
     }
 }
 
+/// Verify the signature of the given input.
+///
+/// Time locks in scripts can't be evaluated without knowing the block the transaction goes into,
+/// so script hash spends that use them fail verification here; use [verify_signature_at_block]
+/// to verify such spends.
 pub fn verify_signature<T: Transactable>(
     chain_config: &ChainConfig,
     outpoint_destination: &Destination,
     tx: &T,
     inputs_utxos: &[Option<&TxOutput>],
     input_num: usize,
+) -> Result<(), TransactionSigError> {
+    verify_signature_impl(
+        chain_config,
+        outpoint_destination,
+        tx,
+        inputs_utxos,
+        input_num,
+        None,
+    )
+}
+
+/// The number of signature operations performed when verifying the given witness of an input
+/// spending an output locked with the given destination.
+///
+/// Only script hash spends are counted, the cost of verifying the other kinds of destinations
+/// is already bounded by the size of the witness.
+pub fn input_sigop_count(
+    outpoint_destination: &Destination,
+    input_witness: &InputWitness,
+) -> usize {
+    match outpoint_destination {
+        Destination::ScriptHash(_) => match input_witness {
+            InputWitness::Standard(sig) => {
+                AuthorizedScriptHashSpend::from_data(sig.raw_signature())
+                    .map_or(0, |spend| spend.sigop_count())
+            }
+            InputWitness::NoSignature(_) => 0,
+        },
+        Destination::AnyoneCanSpend
+        | Destination::Address(_)
+        | Destination::PublicKey(_)
+        | Destination::ClassicMultisig(_) => 0,
+    }
+}
+
+/// Verify the signature of the given input of a transaction that is included in the given block.
+pub fn verify_signature_at_block<T: Transactable>(
+    chain_config: &ChainConfig,
+    outpoint_destination: &Destination,
+    tx: &T,
+    inputs_utxos: &[Option<&TxOutput>],
+    input_num: usize,
+    spending_block: &SpendingBlockInfo,
+) -> Result<(), TransactionSigError> {
+    verify_signature_impl(
+        chain_config,
+        outpoint_destination,
+        tx,
+        inputs_utxos,
+        input_num,
+        Some(spending_block),
+    )
+}
+
+fn verify_signature_impl<T: Transactable>(
+    chain_config: &ChainConfig,
+    outpoint_destination: &Destination,
+    tx: &T,
+    inputs_utxos: &[Option<&TxOutput>],
+    input_num: usize,
+    spending_block: Option<&SpendingBlockInfo>,
 ) -> Result<(), TransactionSigError> {
     let inputs = tx.inputs().ok_or(TransactionSigError::SignatureVerificationWithoutInputs)?;
     let sigs = tx.signatures().ok_or(TransactionSigError::SignatureVerificationWithoutSigs)?;
@@ -178,6 +257,7 @@ pub fn verify_signature<T: Transactable>(
             tx,
             inputs_utxos,
             input_num,
+            spending_block,
         )?,
     }
     Ok(())
@@ -190,9 +270,10 @@ fn verify_standard_input_signature<T: Transactable>(
     tx: &T,
     inputs_utxos: &[Option<&TxOutput>],
     input_num: usize,
+    spending_block: Option<&SpendingBlockInfo>,
 ) -> Result<(), TransactionSigError> {
     let sighash = signature_hash(witness.sighash_type(), tx, inputs_utxos, input_num)?;
-    witness.verify_signature(chain_config, outpoint_destination, &sighash)?;
+    witness.verify_signature(chain_config, outpoint_destination, &sighash, spending_block)?;
     Ok(())
 }
 
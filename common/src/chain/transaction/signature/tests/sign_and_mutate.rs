@@ -94,8 +94,8 @@ fn test_mutate_tx_internal_data(#[case] seed: Seed) {
                     expected
                 );
             }
-            // Not implemented.
-            Err(TransactionSigError::Unsupported) => {
+            // Script hash spends need the redeem script.
+            Err(TransactionSigError::AttemptedToProduceScriptHashSignatureInUniPartyFunction) => {
                 assert!(matches!(destination, Destination::ScriptHash(_)))
             }
             Err(TransactionSigError::AttemptedToProduceSignatureForAnyoneCanSpend) => {
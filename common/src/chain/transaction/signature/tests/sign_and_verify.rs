@@ -70,8 +70,11 @@ fn sign_and_verify_all_and_none(#[case] seed: Seed) {
                 Err(TransactionSigError::AttemptedToProduceSignatureForAnyoneCanSpend)
             );
         } else if matches!(destination, Destination::ScriptHash(_)) && inputs > 0 {
-            // This should be updated after ScriptHash support is implemented.
-            assert_eq!(signed_tx, Err(TransactionSigError::Unsupported));
+            // Spending a script hash requires the redeem script, which uni-party signing doesn't have
+            assert_eq!(
+                signed_tx,
+                Err(TransactionSigError::AttemptedToProduceScriptHashSignatureInUniPartyFunction)
+            );
         } else {
             let signed_tx = signed_tx.expect("{sighash_type:?} {destination:?}");
             verify_signed_tx(&chain_config, &signed_tx, &inputs_utxos_refs, &destination)
@@ -225,22 +228,20 @@ fn sign_and_verify_single(#[case] seed: Seed) {
             Err(TransactionSigError::AttemptedToProduceSignatureForAnyoneCanSpend),
         ),
         // SigHashType::SINGLE. Destination = ScriptHash.
-        // This is currently unsupported, so test should be updated in the future.
         (
             Destination::ScriptHash(Id::<Script>::from(H256::random_using(&mut rng))),
             SigHashType::try_from(SigHashType::SINGLE).unwrap(),
             21,
             33,
-            Err(TransactionSigError::Unsupported),
+            Err(TransactionSigError::AttemptedToProduceScriptHashSignatureInUniPartyFunction),
         ),
         // SigHashType::SINGLE | SigHashType::ANYONECANPAY. Destination = ScriptHash
-        // This is currently unsupported, so test should be updated in the future.
         (
             Destination::ScriptHash(Id::<Script>::from(H256::random_using(&mut rng))),
             SigHashType::try_from(SigHashType::SINGLE | SigHashType::ANYONECANPAY).unwrap(),
             21,
             33,
-            Err(TransactionSigError::Unsupported),
+            Err(TransactionSigError::AttemptedToProduceScriptHashSignatureInUniPartyFunction),
         ),
     ];
 
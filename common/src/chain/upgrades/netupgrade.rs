@@ -17,8 +17,10 @@ use std::ops::Range;
 
 use crate::chain::config::ChainType;
 use crate::chain::pow::limit;
+use crate::chain::tokens::TokenData;
 use crate::chain::{create_regtest_pos_config, initial_difficulty, PoSChainConfig};
 use crate::primitives::{BlockHeight, Compact};
+use crypto::key::KeyKind;
 
 #[derive(Debug, Clone)]
 pub struct NetUpgrades<T>(Vec<(BlockHeight, T)>);
@@ -141,6 +143,112 @@ impl From<ConsensusUpgrade> for RequiredConsensus {
 
 impl Activate for UpgradeVersion {}
 
+/// Upgrades that allow signing transaction inputs with additional kinds of keys.
+///
+/// Versions are ordered, each one allows the kinds of keys of the previous ones.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Ord, PartialOrd)]
+pub enum KeyKindUpgrade {
+    /// Only the original secp256k1 Schnorr keys are allowed
+    Secp256k1Schnorr,
+    /// Ed25519 keys are allowed as well
+    Ed25519,
+}
+
+impl Activate for KeyKindUpgrade {}
+
+impl NetUpgrades<KeyKindUpgrade> {
+    /// Only the original kind of keys, without any later activations
+    pub fn secp256k1_only() -> Self {
+        Self(vec![(
+            BlockHeight::zero(),
+            KeyKindUpgrade::Secp256k1Schnorr,
+        )])
+    }
+
+    /// All the kinds of keys are allowed from genesis
+    pub fn all_key_kinds() -> Self {
+        Self(vec![(BlockHeight::zero(), KeyKindUpgrade::Ed25519)])
+    }
+
+    /// Whether inputs in a block at the given height may be signed with keys of the given kind
+    pub fn is_key_kind_activated(&self, key_kind: &KeyKind, height: BlockHeight) -> bool {
+        match key_kind {
+            KeyKind::Secp256k1Schnorr => true,
+            KeyKind::Ed25519 => KeyKindUpgrade::Ed25519.is_activated(height, self),
+        }
+    }
+}
+
+/// Upgrades that allow spending outputs locked with a script hash.
+///
+/// Script hash outputs can be created before activation, spending them requires it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Ord, PartialOrd)]
+pub enum ScriptHashUpgrade {
+    /// Script hash outputs cannot be spent
+    Unspendable,
+    /// Script hash outputs can be spent by providing the redeem script and its arguments
+    Spendable,
+}
+
+impl Activate for ScriptHashUpgrade {}
+
+impl NetUpgrades<ScriptHashUpgrade> {
+    /// Script hash outputs are never spendable, without any later activations
+    pub fn script_hash_unspendable() -> Self {
+        Self(vec![(BlockHeight::zero(), ScriptHashUpgrade::Unspendable)])
+    }
+
+    /// Script hash outputs are spendable from genesis
+    pub fn script_hash_spendable() -> Self {
+        Self(vec![(BlockHeight::zero(), ScriptHashUpgrade::Spendable)])
+    }
+
+    /// Whether inputs in a block at the given height may spend script hash outputs
+    pub fn is_script_hash_spending_activated(&self, height: BlockHeight) -> bool {
+        ScriptHashUpgrade::Spendable.is_activated(height, self)
+    }
+}
+
+/// Upgrades that allow additional kinds of token data in transaction outputs.
+///
+/// Versions are ordered, each one allows the token data of the previous ones.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Ord, PartialOrd)]
+pub enum TokensUpgrade {
+    /// Only transfers, fixed supply issuance and NFT issuance are allowed
+    V0,
+    /// Issuance with a supply authority, reissuance, supply locking and authority changes
+    V1,
+}
+
+impl Activate for TokensUpgrade {}
+
+impl NetUpgrades<TokensUpgrade> {
+    /// Only the original token data, without any later activations
+    pub fn tokens_v0_only() -> Self {
+        Self(vec![(BlockHeight::zero(), TokensUpgrade::V0)])
+    }
+
+    /// All the kinds of token data are allowed from genesis
+    pub fn all_token_versions() -> Self {
+        Self(vec![(BlockHeight::zero(), TokensUpgrade::V1)])
+    }
+
+    /// Whether outputs in a block at the given height may carry the given token data
+    pub fn is_token_data_activated(&self, token_data: &TokenData, height: BlockHeight) -> bool {
+        match token_data {
+            TokenData::TokenTransfer(_)
+            | TokenData::TokenIssuance(_)
+            | TokenData::NftIssuance(_) => true,
+            TokenData::TokenIssuanceV1(_)
+            | TokenData::TokenReissueV1 { .. }
+            | TokenData::LockTokenSupplyV1 { .. }
+            | TokenData::ChangeTokenAuthorityV1 { .. }
+            | TokenData::FreezeTokenV1 { .. }
+            | TokenData::UnfreezeTokenV1 { .. } => TokensUpgrade::V1.is_activated(height, self),
+        }
+    }
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum NetUpgradesInitializeError {
     #[error("Must be initialized with a non-empty vector of upgrades")]
@@ -234,9 +342,10 @@ impl NetUpgrades<UpgradeVersion> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::chain::tokens::TokenTransfer;
     use crate::chain::upgrades::netupgrade::NetUpgrades;
     use crate::chain::{create_unittest_pos_config, Activate};
-    use crate::primitives::{BlockDistance, BlockHeight};
+    use crate::primitives::{Amount, BlockDistance, BlockHeight, H256};
     use crate::Uint256;
 
     #[derive(Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd)]
@@ -336,6 +445,83 @@ mod tests {
         check(three_height.next_height(), MockVersion::Three);
     }
 
+    #[test]
+    fn key_kind_activation() {
+        let activation_height = BlockHeight::new(100);
+        let upgrades = NetUpgrades::initialize(vec![
+            (BlockHeight::zero(), KeyKindUpgrade::Secp256k1Schnorr),
+            (activation_height, KeyKindUpgrade::Ed25519),
+        ])
+        .expect("valid net upgrades");
+
+        for height in [BlockHeight::zero(), BlockHeight::new(99), activation_height] {
+            assert!(upgrades.is_key_kind_activated(&KeyKind::Secp256k1Schnorr, height));
+        }
+        assert!(!upgrades.is_key_kind_activated(&KeyKind::Ed25519, BlockHeight::zero()));
+        assert!(!upgrades.is_key_kind_activated(&KeyKind::Ed25519, BlockHeight::new(99)));
+        assert!(upgrades.is_key_kind_activated(&KeyKind::Ed25519, activation_height));
+        assert!(upgrades.is_key_kind_activated(&KeyKind::Ed25519, BlockHeight::max()));
+
+        let secp256k1_only = NetUpgrades::secp256k1_only();
+        assert!(!secp256k1_only.is_key_kind_activated(&KeyKind::Ed25519, BlockHeight::max()));
+        let all_key_kinds = NetUpgrades::all_key_kinds();
+        assert!(all_key_kinds.is_key_kind_activated(&KeyKind::Ed25519, BlockHeight::zero()));
+    }
+
+    #[test]
+    fn script_hash_activation() {
+        let activation_height = BlockHeight::new(100);
+        let upgrades = NetUpgrades::initialize(vec![
+            (BlockHeight::zero(), ScriptHashUpgrade::Unspendable),
+            (activation_height, ScriptHashUpgrade::Spendable),
+        ])
+        .expect("valid net upgrades");
+
+        assert!(!upgrades.is_script_hash_spending_activated(BlockHeight::zero()));
+        assert!(!upgrades.is_script_hash_spending_activated(BlockHeight::new(99)));
+        assert!(upgrades.is_script_hash_spending_activated(activation_height));
+        assert!(upgrades.is_script_hash_spending_activated(BlockHeight::max()));
+
+        let unspendable = NetUpgrades::script_hash_unspendable();
+        assert!(!unspendable.is_script_hash_spending_activated(BlockHeight::max()));
+        let spendable = NetUpgrades::script_hash_spendable();
+        assert!(spendable.is_script_hash_spending_activated(BlockHeight::zero()));
+    }
+
+    #[test]
+    fn tokens_activation() {
+        let activation_height = BlockHeight::new(100);
+        let upgrades = NetUpgrades::initialize(vec![
+            (BlockHeight::zero(), TokensUpgrade::V0),
+            (activation_height, TokensUpgrade::V1),
+        ])
+        .expect("valid net upgrades");
+
+        let transfer = TokenData::TokenTransfer(TokenTransfer {
+            token_id: H256::zero(),
+            amount: Amount::from_atoms(1),
+        });
+        let lock = TokenData::LockTokenSupplyV1 {
+            token_id: H256::zero(),
+        };
+        let freeze = TokenData::FreezeTokenV1 {
+            token_id: H256::zero(),
+        };
+
+        assert!(upgrades.is_token_data_activated(&transfer, BlockHeight::zero()));
+        assert!(!upgrades.is_token_data_activated(&lock, BlockHeight::zero()));
+        assert!(!upgrades.is_token_data_activated(&lock, BlockHeight::new(99)));
+        assert!(upgrades.is_token_data_activated(&lock, activation_height));
+        assert!(!upgrades.is_token_data_activated(&freeze, BlockHeight::new(99)));
+        assert!(upgrades.is_token_data_activated(&freeze, activation_height));
+
+        let v0_only = NetUpgrades::tokens_v0_only();
+        assert!(v0_only.is_token_data_activated(&transfer, BlockHeight::max()));
+        assert!(!v0_only.is_token_data_activated(&lock, BlockHeight::max()));
+        let all_versions = NetUpgrades::all_token_versions();
+        assert!(all_versions.is_token_data_activated(&lock, BlockHeight::zero()));
+    }
+
     fn mock_consensus_upgrades() -> Result<NetUpgrades<UpgradeVersion>, NetUpgradesInitializeError>
     {
         let genesis_pow = BlockHeight::new(0);
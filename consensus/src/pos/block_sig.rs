@@ -16,9 +16,9 @@
 use common::{
     chain::{
         block::signed_block_header::{BlockHeaderSignature, SignedBlockHeader},
-        Block, Destination, TxOutput,
+        Block, ChainConfig, Destination, TxOutput,
     },
-    primitives::{Id, Idable},
+    primitives::{BlockHeight, Id, Idable},
 };
 use crypto::key::PublicKey;
 use serialization::Encode;
@@ -34,6 +34,8 @@ pub enum BlockSignatureError {
     WrongDestination(Id<Block>),
     #[error("Bad block signature in block {0}")]
     BadSignature(Id<Block>),
+    #[error("Block {0} is signed with a key of a kind that is not activated yet")]
+    KeyKindNotActivated(Id<Block>),
 }
 
 /// Given a staking kernel output that is spent in this block,
@@ -70,11 +72,20 @@ fn get_staking_kernel_destination(
 /// Checks the signature of the block (in its header) against
 /// the staking kernel output's destination
 pub fn check_block_signature(
+    chain_config: &ChainConfig,
     header: &SignedBlockHeader,
+    height: BlockHeight,
     kernel_output: &TxOutput,
 ) -> Result<(), BlockSignatureError> {
     let public_key = get_staking_kernel_destination(header, kernel_output)?;
 
+    utils::ensure!(
+        chain_config
+            .key_kind_upgrades()
+            .is_key_kind_activated(&public_key.kind(), height),
+        BlockSignatureError::KeyKindNotActivated(header.get_id())
+    );
+
     let signature_in_header = header.signature_data();
 
     let sig_data = match signature_in_header {
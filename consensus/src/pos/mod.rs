@@ -174,7 +174,7 @@ where
     let kernel_output = get_kernel_output(pos_data.kernel_inputs(), utxos_view)?;
 
     // Proof of stake mandates signing the block with the same key of the kernel output
-    check_block_signature(header, &kernel_output)?;
+    check_block_signature(chain_config, header, current_height, &kernel_output)?;
 
     let vrf_pub_key = match kernel_output {
         TxOutput::Transfer(_, _)
@@ -44,6 +44,7 @@ impl_hasher_trait!(Blake2b, blake2::Blake2b<typenum::U64>, typenum::U64);
 impl_hasher_trait!(Blake2b32, blake2::Blake2b<typenum::U64>, typenum::U32);
 impl_hasher_trait!(Sha1, sha1::Sha1, typenum::U20);
 impl_hasher_trait!(Sha256, sha2::Sha256, typenum::U32);
+impl_hasher_trait!(Sha3_256, sha3::Sha3_256, typenum::U32);
 impl_hasher_trait!(Sha3_512, sha3::Sha3_512, typenum::U64);
 impl_hasher_trait!(Ripemd160, ripemd::Ripemd160, typenum::U20);
 
@@ -235,6 +236,18 @@ mod tests {
         assert!(buf_sha2.iter().zip(exp_res.iter()).all(|(a, b)| a == b));
     }
 
+    #[test]
+    fn test_hash_sha3_256() {
+        let exp_res = [
+            0xa7, 0xff, 0xc6, 0xf8, 0xbf, 0x1e, 0xd7, 0x66, 0x51, 0xc1, 0x47, 0x56, 0xa0, 0x61,
+            0xd6, 0x62, 0xf5, 0x80, 0xff, 0x4d, 0xe4, 0x3b, 0x49, 0xfa, 0x82, 0xd8, 0x0a, 0x4b,
+            0x80, 0xf8, 0x43, 0x4a,
+        ];
+        let buf_sha3 = hash::<Sha3_256, _>(b"");
+        assert_eq!(buf_sha3.len(), exp_res.len());
+        assert!(buf_sha3.iter().zip(exp_res.iter()).all(|(a, b)| a == b));
+    }
+
     #[test]
     fn test_hash_sha3_512() {
         let exp_res = [
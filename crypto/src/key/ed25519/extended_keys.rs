@@ -0,0 +1,298 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::key::ed25519::{Ed25519PrivateKey, Ed25519PublicKey};
+use crate::key::hdkd::derivation_path::DerivationPath;
+use crate::key::hdkd::{
+    chain_code::ChainCode,
+    child_number::ChildNumber,
+    derivable::{Derivable, DerivationError},
+};
+use crate::random::{CryptoRng, Rng};
+use crate::util::{self, new_hmac_sha_512};
+use hmac::{Hmac, Mac};
+use serialization::{Decode, Encode};
+use sha2::Sha512;
+use std::cmp::Ordering;
+
+/// Given a tree of keys that are derived from a master key, this struct represents the private key
+/// at one of the nodes of this tree.
+///
+/// The keys are derived as specified in SLIP-0010, which is what hardware wallets implement
+/// for ed25519. SLIP-0010 only defines hardened derivation for ed25519 keys.
+#[derive(Debug, PartialEq, Eq, Clone, Encode, Decode)]
+pub struct Ed25519ExtendedPrivateKey {
+    /// The absolute derivation path that was used to derive this key
+    derivation_path: DerivationPath,
+    /// The chain code is used in conjunction with the private key to allow derivation
+    /// of child keys
+    chain_code: ChainCode,
+    /// The private key to be used to derive child keys of this node
+    private_key: Ed25519PrivateKey,
+}
+
+impl PartialOrd for Ed25519ExtendedPrivateKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Ed25519ExtendedPrivateKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.derivation_path.cmp(&other.derivation_path)
+    }
+}
+
+fn to_key_and_chain_code(
+    mac: Hmac<Sha512>,
+) -> Result<(Ed25519PrivateKey, ChainCode), DerivationError> {
+    util::to_key_and_chain_code(mac, |secret_key_bytes| {
+        Ed25519PrivateKey::from_bytes(secret_key_bytes)
+            .map_err(|_| DerivationError::KeyDerivationError)
+    })
+}
+
+impl Ed25519ExtendedPrivateKey {
+    pub fn new_master(seed: &[u8]) -> Result<Ed25519ExtendedPrivateKey, DerivationError> {
+        // Create a new mac with the appropriate SLIP-0010 constant
+        let mut mac = new_hmac_sha_512(b"ed25519 seed");
+
+        mac.update(seed);
+
+        let (private_key, chain_code) = to_key_and_chain_code(mac)?;
+
+        Ok(Ed25519ExtendedPrivateKey {
+            derivation_path: DerivationPath::empty(),
+            private_key,
+            chain_code,
+        })
+    }
+
+    pub fn new<R: Rng + CryptoRng>(
+        rng: &mut R,
+    ) -> (Ed25519ExtendedPrivateKey, Ed25519ExtendedPublicKey) {
+        // Create a new chain code
+        let mut chain_code = [0u8; 32];
+        rng.fill_bytes(&mut chain_code);
+        let chain_code = chain_code.into();
+        let (private_key, _) = Ed25519PrivateKey::new(rng);
+        let ext_priv = Ed25519ExtendedPrivateKey {
+            derivation_path: DerivationPath::empty(),
+            private_key,
+            chain_code,
+        };
+        let ext_pub = Ed25519ExtendedPublicKey::from_private_key(&ext_priv);
+        (ext_priv, ext_pub)
+    }
+
+    pub fn private_key(&self) -> &Ed25519PrivateKey {
+        &self.private_key
+    }
+
+    pub fn into_private_key(self) -> Ed25519PrivateKey {
+        self.private_key
+    }
+}
+
+impl Derivable for Ed25519ExtendedPrivateKey {
+    fn derive_child(self, num: ChildNumber) -> Result<Self, DerivationError> {
+        if num.is_normal() {
+            return Err(DerivationError::CannotDeriveNormalKey(num));
+        }
+
+        let mut mac = new_hmac_sha_512(&self.chain_code.into_array());
+        mac.update(&[0u8]);
+        mac.update(self.private_key.as_native().as_bytes());
+        mac.update(&num.into_encoded_be_bytes());
+
+        let (private_key, chain_code) = to_key_and_chain_code(mac)?;
+
+        let derivation_path = {
+            let mut child_path = self.derivation_path.into_vec();
+            child_path.push(num);
+            child_path.try_into()?
+        };
+
+        Ok(Ed25519ExtendedPrivateKey {
+            derivation_path,
+            chain_code,
+            private_key,
+        })
+    }
+
+    fn get_derivation_path(&self) -> &DerivationPath {
+        &self.derivation_path
+    }
+}
+
+/// Given a tree of keys that are derived from a master key, this struct represents the public key
+/// at one of the nodes of this tree.
+///
+/// No child keys can be derived from it because only hardened derivation is possible.
+#[derive(Debug, PartialEq, Eq, Clone, Encode, Decode)]
+pub struct Ed25519ExtendedPublicKey {
+    /// The absolute derivation path that was used to derive this key
+    derivation_path: DerivationPath,
+    /// The chain code of the corresponding private key
+    chain_code: ChainCode,
+    /// The public key of this node
+    public_key: Ed25519PublicKey,
+}
+
+impl PartialOrd for Ed25519ExtendedPublicKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Ed25519ExtendedPublicKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.derivation_path.cmp(&other.derivation_path)
+    }
+}
+
+impl Ed25519ExtendedPublicKey {
+    pub fn public_key(&self) -> &Ed25519PublicKey {
+        &self.public_key
+    }
+
+    pub fn into_public_key(self) -> Ed25519PublicKey {
+        self.public_key
+    }
+
+    pub fn from_private_key(private_key: &Ed25519ExtendedPrivateKey) -> Self {
+        Ed25519ExtendedPublicKey {
+            derivation_path: private_key.derivation_path.clone(),
+            chain_code: private_key.chain_code,
+            public_key: Ed25519PublicKey::from_private_key(&private_key.private_key),
+        }
+    }
+}
+
+impl Derivable for Ed25519ExtendedPublicKey {
+    fn derive_child(self, num: ChildNumber) -> Result<Self, DerivationError> {
+        if num.is_hardened() {
+            Err(DerivationError::CannotDeriveHardenedKeyFromPublicKey(num))
+        } else {
+            Err(DerivationError::CannotDeriveNormalKey(num))
+        }
+    }
+
+    fn get_derivation_path(&self) -> &DerivationPath {
+        &self.derivation_path
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use hex::FromHex;
+    use rstest::rstest;
+    use serialization::DecodeAll;
+    use std::str::FromStr;
+    use test_utils::random::{make_seedable_rng, Seed};
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn serialization(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+        let (sk, pk) = Ed25519ExtendedPrivateKey::new(&mut rng);
+        let sk = sk.derive_absolute_path(&DerivationPath::from_str("m/1h/2h").unwrap()).unwrap();
+
+        let sk_encoded = sk.encode();
+        let pk_encoded = pk.encode();
+        assert_eq!(
+            Ed25519ExtendedPrivateKey::decode_all(&mut sk_encoded.as_slice()).unwrap(),
+            sk
+        );
+        assert_eq!(
+            Ed25519ExtendedPublicKey::decode_all(&mut pk_encoded.as_slice()).unwrap(),
+            pk
+        );
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn only_hardened_derivation(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+        let (sk, pk) = Ed25519ExtendedPrivateKey::new(&mut rng);
+
+        let hardened = ChildNumber::from_hardened(1.try_into().unwrap());
+        let normal = ChildNumber::from_normal(1.try_into().unwrap());
+        assert_eq!(
+            pk.clone().derive_child(hardened).unwrap_err(),
+            DerivationError::CannotDeriveHardenedKeyFromPublicKey(hardened)
+        );
+        assert_eq!(
+            pk.derive_child(normal).unwrap_err(),
+            DerivationError::CannotDeriveNormalKey(normal)
+        );
+        assert_eq!(
+            sk.clone().derive_child(normal).unwrap_err(),
+            DerivationError::CannotDeriveNormalKey(normal)
+        );
+
+        let child = sk.clone().derive_child(hardened).unwrap();
+        assert_eq!(child, sk.derive_child(hardened).unwrap());
+    }
+
+    #[test]
+    fn slip10_test_vector() {
+        // Test vector 1 for ed25519 from SLIP-0010
+        let seed = Vec::from_hex("000102030405060708090a0b0c0d0e0f").unwrap();
+        let master_key = Ed25519ExtendedPrivateKey::new_master(&seed).unwrap();
+        assert_eq!(
+            master_key.chain_code.into_array(),
+            <[u8; 32]>::from_hex(
+                "90046a93de5380a72b5e45010748567d5ea02bbf6522f979e05c0d8d8ca9fffb"
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            master_key.private_key().as_native().to_bytes(),
+            <[u8; 32]>::from_hex(
+                "2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7"
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            Ed25519ExtendedPublicKey::from_private_key(&master_key).public_key().as_bytes(),
+            <[u8; 32]>::from_hex(
+                "a4b2856bfec510abab89753fac1ac0e1112364e7d250545963f135f2a33188ed"
+            )
+            .unwrap()
+        );
+
+        let child = master_key
+            .derive_absolute_path(&DerivationPath::from_str("m/0h").unwrap())
+            .unwrap();
+        assert_eq!(
+            child.chain_code.into_array(),
+            <[u8; 32]>::from_hex(
+                "8b59aa11380b624e81507a27fedda59fea6d0b779a778918a2fd3590e16e9c69"
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            child.private_key().as_native().to_bytes(),
+            <[u8; 32]>::from_hex(
+                "68e0fe46dfb67e368c75379acec591dad19df3cde26e63b93a8e704f1dade7a3"
+            )
+            .unwrap()
+        );
+    }
+}
@@ -0,0 +1,249 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Edwards-curve signatures over Curve25519 (ed25519), as defined in RFC 8032.
+//!
+//! The messages are signed as they are, without any domain separation, so that hardware wallets
+//! and secure elements with plain ed25519 support can sign them.
+
+pub mod extended_keys;
+
+use std::cmp::Ordering;
+
+use crate::random::{CryptoRng, Rng};
+use ed25519_dalek::Signer;
+use serialization::{Decode, Encode};
+use zeroize::Zeroize;
+
+pub const PUBLIC_KEY_SIZE: usize = ed25519_dalek::PUBLIC_KEY_LENGTH;
+pub const SECRET_KEY_SIZE: usize = ed25519_dalek::SECRET_KEY_LENGTH;
+pub const SIGNATURE_SIZE: usize = ed25519_dalek::SIGNATURE_LENGTH;
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub enum Ed25519KeyError {
+    InvalidData,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Ed25519PrivateKey {
+    data: ed25519_dalek::SigningKey,
+}
+
+impl Encode for Ed25519PrivateKey {
+    fn using_encoded<R, F: FnOnce(&[u8]) -> R>(&self, f: F) -> R {
+        let mut bytes = self.data.to_bytes();
+        let result = bytes.using_encoded(f);
+        bytes.zeroize();
+        result
+    }
+}
+
+impl Decode for Ed25519PrivateKey {
+    fn decode<I: serialization::Input>(input: &mut I) -> Result<Self, serialization::Error> {
+        let mut v = <[u8; SECRET_KEY_SIZE]>::decode(input)?;
+        let result = Self::from_bytes(&v)
+            .map_err(|_| serialization::Error::from("Private Key deserialization failed"));
+        v.zeroize();
+        result
+    }
+}
+
+impl Ed25519PrivateKey {
+    pub fn new<R: Rng + CryptoRng>(rng: &mut R) -> (Ed25519PrivateKey, Ed25519PublicKey) {
+        let mut secret: ed25519_dalek::SecretKey = [0u8; SECRET_KEY_SIZE];
+        rng.fill_bytes(&mut secret);
+        let signing_key = ed25519_dalek::SigningKey::from_bytes(&secret);
+        secret.zeroize();
+        let public = signing_key.verifying_key();
+        (
+            Ed25519PrivateKey::from_native(signing_key),
+            Ed25519PublicKey::from_native(public),
+        )
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Ed25519KeyError> {
+        let secret = bytes.try_into().map_err(|_| Ed25519KeyError::InvalidData)?;
+        Ok(Self::from_native(ed25519_dalek::SigningKey::from_bytes(
+            secret,
+        )))
+    }
+
+    pub fn as_native(&self) -> &ed25519_dalek::SigningKey {
+        &self.data
+    }
+
+    pub fn from_native(native: ed25519_dalek::SigningKey) -> Self {
+        Self { data: native }
+    }
+
+    pub(crate) fn sign_message(&self, msg: &[u8]) -> ed25519_dalek::Signature {
+        self.data.sign(msg)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Ed25519PublicKey {
+    pubkey_data: ed25519_dalek::VerifyingKey,
+}
+
+impl PartialOrd for Ed25519PublicKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Ed25519PublicKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.pubkey_data.as_bytes().cmp(other.pubkey_data.as_bytes())
+    }
+}
+
+impl Encode for Ed25519PublicKey {
+    fn using_encoded<R, F: FnOnce(&[u8]) -> R>(&self, f: F) -> R {
+        self.as_bytes().using_encoded(f)
+    }
+}
+
+impl Decode for Ed25519PublicKey {
+    fn decode<I: serialization::Input>(input: &mut I) -> Result<Self, serialization::Error> {
+        let v = <[u8; PUBLIC_KEY_SIZE]>::decode(input)?;
+        Self::from_bytes(&v)
+            .map_err(|_| serialization::Error::from("Public Key deserialization failed"))
+    }
+}
+
+impl Ed25519PublicKey {
+    pub fn as_bytes(&self) -> [u8; PUBLIC_KEY_SIZE] {
+        self.pubkey_data.to_bytes()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Ed25519KeyError> {
+        let bytes: &[u8; PUBLIC_KEY_SIZE] =
+            bytes.try_into().map_err(|_| Ed25519KeyError::InvalidData)?;
+        ed25519_dalek::VerifyingKey::from_bytes(bytes)
+            .map(Self::from_native)
+            .map_err(|_| Ed25519KeyError::InvalidData)
+    }
+
+    pub fn as_native(&self) -> &ed25519_dalek::VerifyingKey {
+        &self.pubkey_data
+    }
+
+    pub fn from_native(native: ed25519_dalek::VerifyingKey) -> Self {
+        Self {
+            pubkey_data: native,
+        }
+    }
+
+    pub fn from_private_key(private_key: &Ed25519PrivateKey) -> Self {
+        Self::from_native(private_key.data.verifying_key())
+    }
+
+    pub(crate) fn verify_message(&self, signature: &ed25519_dalek::Signature, msg: &[u8]) -> bool {
+        // The strict verification rejects weak keys and malleable signatures
+        self.pubkey_data.verify_strict(msg, signature).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use hex::FromHex;
+    use rstest::rstest;
+    use serialization::DecodeAll;
+    use test_utils::random::{make_seedable_rng, Seed};
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn basic(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+        let (sk, pk) = Ed25519PrivateKey::new(&mut rng);
+        let pk2 = Ed25519PublicKey::from_private_key(&sk);
+        assert_eq!(pk, pk2);
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn serialize(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+        let (sk, pk) = Ed25519PrivateKey::new(&mut rng);
+        let sk_encoded = sk.encode();
+        let pk_encoded = pk.encode();
+        assert_eq!(sk_encoded.len(), SECRET_KEY_SIZE);
+        assert_eq!(pk_encoded.len(), PUBLIC_KEY_SIZE);
+        let sk2 = Ed25519PrivateKey::decode_all(&mut sk_encoded.as_slice()).unwrap();
+        let pk2 = Ed25519PublicKey::decode_all(&mut pk_encoded.as_slice()).unwrap();
+        assert_eq!(sk, sk2);
+        assert_eq!(pk, pk2);
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn sign_and_verify(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+        let msg_size = 1 + rng.gen::<usize>() % 10000;
+        let msg: Vec<u8> = (0..msg_size).map(|_| rng.gen::<u8>()).collect();
+        let (sk, pk) = Ed25519PrivateKey::new(&mut rng);
+        let sig = sk.sign_message(&msg);
+        assert!(pk.verify_message(&sig, &msg));
+
+        let (_, other_pk) = Ed25519PrivateKey::new(&mut rng);
+        assert!(!other_pk.verify_message(&sig, &msg));
+        assert!(!pk.verify_message(&sig, b"other message"));
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn sign_empty(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+        let (sk, pk) = Ed25519PrivateKey::new(&mut rng);
+        let sig = sk.sign_message(&[]);
+        assert!(pk.verify_message(&sig, &[]));
+    }
+
+    #[test]
+    fn rfc8032_test_vector() {
+        // Test 1 from RFC 8032, section 7.1, signatures must match the ones of other implementations
+        let sk = Ed25519PrivateKey::from_bytes(
+            &<[u8; 32]>::from_hex(
+                "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        let pk = Ed25519PublicKey::from_private_key(&sk);
+        assert_eq!(
+            pk.as_bytes(),
+            <[u8; 32]>::from_hex(
+                "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a"
+            )
+            .unwrap()
+        );
+        let sig = sk.sign_message(&[]);
+        assert_eq!(
+            sig.to_bytes(),
+            <[u8; 64]>::from_hex(
+                "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e06522490155\
+                 5fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b"
+            )
+            .unwrap()
+        );
+        assert!(pk.verify_message(&sig, &[]));
+    }
+}
@@ -15,6 +15,7 @@
 
 use serialization::{Decode, Encode};
 
+use crate::key::ed25519::extended_keys::{Ed25519ExtendedPrivateKey, Ed25519ExtendedPublicKey};
 use crate::key::hdkd::child_number::ChildNumber;
 use crate::key::hdkd::derivable::{Derivable, DerivationError};
 use crate::key::hdkd::derivation_path::DerivationPath;
@@ -30,6 +31,8 @@ use crate::random::{CryptoRng, Rng};
 pub enum ExtendedKeyKind {
     #[codec(index = 0)]
     Secp256k1Schnorr,
+    #[codec(index = 1)]
+    Ed25519,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Decode, Encode)]
@@ -54,6 +57,12 @@ impl ExtendedPrivateKey {
                     key: ExtendedPrivateKeyHolder::Secp256k1Schnorr(secp_key),
                 })
             }
+            ExtendedKeyKind::Ed25519 => {
+                let ed_key = Ed25519ExtendedPrivateKey::new_master(seed)?;
+                Ok(ExtendedPrivateKey {
+                    key: ExtendedPrivateKeyHolder::Ed25519(ed_key),
+                })
+            }
         }
     }
 
@@ -77,12 +86,24 @@ impl ExtendedPrivateKey {
                     },
                 )
             }
+            ExtendedKeyKind::Ed25519 => {
+                let k = Ed25519ExtendedPrivateKey::new(rng);
+                (
+                    ExtendedPrivateKey {
+                        key: ExtendedPrivateKeyHolder::Ed25519(k.0),
+                    },
+                    ExtendedPublicKey {
+                        pub_key: ExtendedPublicKeyHolder::Ed25519(k.1),
+                    },
+                )
+            }
         }
     }
 
     pub fn kind(&self) -> ExtendedKeyKind {
         match self.key {
             ExtendedPrivateKeyHolder::Secp256k1Schnorr(_) => ExtendedKeyKind::Secp256k1Schnorr,
+            ExtendedPrivateKeyHolder::Ed25519(_) => ExtendedKeyKind::Ed25519,
         }
     }
 
@@ -93,6 +114,7 @@ impl ExtendedPrivateKey {
     pub fn private_key(self) -> PrivateKey {
         match self.key {
             ExtendedPrivateKeyHolder::Secp256k1Schnorr(k) => k.into_private_key().into(),
+            ExtendedPrivateKeyHolder::Ed25519(k) => k.into_private_key().into(),
         }
     }
 
@@ -105,6 +127,7 @@ impl ExtendedPublicKey {
     pub fn kind(&self) -> ExtendedKeyKind {
         match self.pub_key {
             ExtendedPublicKeyHolder::Secp256k1Schnorr(_) => ExtendedKeyKind::Secp256k1Schnorr,
+            ExtendedPublicKeyHolder::Ed25519(_) => ExtendedKeyKind::Ed25519,
         }
     }
 
@@ -120,12 +143,19 @@ impl ExtendedPublicKey {
                     pub_key: ExtendedPublicKeyHolder::Secp256k1Schnorr(secp_key),
                 }
             }
+            ExtendedPrivateKeyHolder::Ed25519(ref k) => {
+                let ed_key = Ed25519ExtendedPublicKey::from_private_key(k);
+                ExtendedPublicKey {
+                    pub_key: ExtendedPublicKeyHolder::Ed25519(ed_key),
+                }
+            }
         }
     }
 
     pub fn into_public_key(self) -> PublicKey {
         match self.pub_key {
             ExtendedPublicKeyHolder::Secp256k1Schnorr(k) => k.into_public_key().into(),
+            ExtendedPublicKeyHolder::Ed25519(k) => k.into_public_key().into(),
         }
     }
 }
@@ -139,12 +169,19 @@ impl Derivable for ExtendedPrivateKey {
                     key: ExtendedPrivateKeyHolder::Secp256k1Schnorr(secp_key),
                 })
             }
+            ExtendedPrivateKeyHolder::Ed25519(key) => {
+                let ed_key = key.derive_child(num)?;
+                Ok(ExtendedPrivateKey {
+                    key: ExtendedPrivateKeyHolder::Ed25519(ed_key),
+                })
+            }
         }
     }
 
     fn get_derivation_path(&self) -> &DerivationPath {
         match self.key {
             ExtendedPrivateKeyHolder::Secp256k1Schnorr(ref key) => key.get_derivation_path(),
+            ExtendedPrivateKeyHolder::Ed25519(ref key) => key.get_derivation_path(),
         }
     }
 }
@@ -158,12 +195,19 @@ impl Derivable for ExtendedPublicKey {
                     pub_key: ExtendedPublicKeyHolder::Secp256k1Schnorr(child_pub_key),
                 })
             }
+            ExtendedPublicKeyHolder::Ed25519(pub_key) => {
+                let child_pub_key = pub_key.derive_child(num)?;
+                Ok(ExtendedPublicKey {
+                    pub_key: ExtendedPublicKeyHolder::Ed25519(child_pub_key),
+                })
+            }
         }
     }
 
     fn get_derivation_path(&self) -> &DerivationPath {
         match self.pub_key {
             ExtendedPublicKeyHolder::Secp256k1Schnorr(ref pub_key) => pub_key.get_derivation_path(),
+            ExtendedPublicKeyHolder::Ed25519(ref pub_key) => pub_key.get_derivation_path(),
         }
     }
 }
@@ -216,6 +260,47 @@ mod test {
         assert_eq!(sk4, sk4_alt);
     }
 
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn derive_ed25519(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+        let (sk, pk) = ExtendedPrivateKey::new_from_rng(&mut rng, ExtendedKeyKind::Ed25519);
+        assert_eq!(sk.kind(), ExtendedKeyKind::Ed25519);
+        assert_eq!(pk.kind(), ExtendedKeyKind::Ed25519);
+        assert_eq!(sk.to_public_key(), pk);
+
+        let sk3 = sk
+            .clone()
+            .derive_absolute_path(&DerivationPath::from_str("m/1h/2h/3h").unwrap())
+            .unwrap();
+        let sk3_alt = sk
+            .derive_child(ChildNumber::from_hardened(1.try_into().unwrap()))
+            .unwrap()
+            .derive_child(ChildNumber::from_hardened(2.try_into().unwrap()))
+            .unwrap()
+            .derive_child(ChildNumber::from_hardened(3.try_into().unwrap()))
+            .unwrap();
+        assert_eq!(sk3, sk3_alt);
+
+        // Only hardened derivation is defined for ed25519 keys
+        let pk3 = sk3.to_public_key();
+        assert_eq!(
+            sk3.clone().derive_child(ChildNumber::from_normal(4.try_into().unwrap())),
+            Err(DerivationError::CannotDeriveNormalKey(
+                ChildNumber::from_normal(4.try_into().unwrap())
+            ))
+        );
+        assert!(pk3
+            .clone()
+            .derive_child(ChildNumber::from_normal(4.try_into().unwrap()))
+            .is_err());
+
+        let msg = b"abc";
+        let sig = sk3.private_key().sign_message(msg).unwrap();
+        assert!(pk3.into_public_key().verify_message(&sig, msg));
+    }
+
     #[test]
     fn master_key_from_mnemonic_secp256k1schnorr() {
         let mnemonic_str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
@@ -35,6 +35,8 @@ pub enum DerivationError {
     CannotDerivePath(DerivationPath),
     #[error("Cannot derive hardened key from public key: {0}")]
     CannotDeriveHardenedKeyFromPublicKey(ChildNumber),
+    #[error("Only hardened keys can be derived for the key type: {0}")]
+    CannotDeriveNormalKey(ChildNumber),
 }
 
 pub trait Derivable: Sized {
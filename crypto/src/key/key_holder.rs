@@ -13,6 +13,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::key::ed25519::extended_keys::{Ed25519ExtendedPrivateKey, Ed25519ExtendedPublicKey};
+use crate::key::ed25519::{Ed25519PrivateKey, Ed25519PublicKey};
 use crate::key::secp256k1::extended_keys::{
     Secp256k1ExtendedPrivateKey, Secp256k1ExtendedPublicKey,
 };
@@ -23,22 +25,30 @@ use serialization::{Decode, Encode};
 pub enum PrivateKeyHolder {
     #[codec(index = 0)]
     Secp256k1Schnorr(Secp256k1PrivateKey),
+    #[codec(index = 1)]
+    Ed25519(Ed25519PrivateKey),
 }
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Decode, Encode)]
 pub enum PublicKeyHolder {
     #[codec(index = 0)]
     Secp256k1Schnorr(Secp256k1PublicKey),
+    #[codec(index = 1)]
+    Ed25519(Ed25519PublicKey),
 }
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Decode, Encode)]
 pub enum ExtendedPrivateKeyHolder {
     #[codec(index = 0)]
     Secp256k1Schnorr(Secp256k1ExtendedPrivateKey),
+    #[codec(index = 1)]
+    Ed25519(Ed25519ExtendedPrivateKey),
 }
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Decode, Encode)]
 pub enum ExtendedPublicKeyHolder {
     #[codec(index = 0)]
     Secp256k1Schnorr(Secp256k1ExtendedPublicKey),
+    #[codec(index = 1)]
+    Ed25519(Ed25519ExtendedPublicKey),
 }
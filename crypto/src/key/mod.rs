@@ -13,16 +13,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod ed25519;
 pub mod extended;
 pub mod hdkd;
 mod key_holder;
+pub mod musig2;
 pub mod secp256k1;
 pub mod signature;
 
 use serialization::{Decode, Encode};
 
+use crate::key::ed25519::{Ed25519PrivateKey, Ed25519PublicKey};
 use crate::key::secp256k1::{Secp256k1PrivateKey, Secp256k1PublicKey};
-use crate::key::Signature::Secp256k1Schnorr;
 use crate::random::make_true_rng;
 use crate::random::{CryptoRng, Rng};
 pub use signature::Signature;
@@ -42,6 +44,8 @@ pub enum SignatureError {
 pub enum KeyKind {
     #[codec(index = 0)]
     Secp256k1Schnorr,
+    #[codec(index = 1)]
+    Ed25519,
 }
 
 #[must_use]
@@ -70,12 +74,17 @@ impl PrivateKey {
                 let k = Secp256k1PrivateKey::new(rng);
                 (k.0.into(), k.1.into())
             }
+            KeyKind::Ed25519 => {
+                let k = Ed25519PrivateKey::new(rng);
+                (k.0.into(), k.1.into())
+            }
         }
     }
 
     pub fn kind(&self) -> KeyKind {
         match self.key {
             PrivateKeyHolder::Secp256k1Schnorr(_) => KeyKind::Secp256k1Schnorr,
+            PrivateKeyHolder::Ed25519(_) => KeyKind::Ed25519,
         }
     }
 
@@ -85,7 +94,10 @@ impl PrivateKey {
 
     pub fn sign_message(&self, msg: &[u8]) -> Result<Signature, SignatureError> {
         let signature = match &self.key {
-            PrivateKeyHolder::Secp256k1Schnorr(ref k) => Secp256k1Schnorr(k.sign_message(msg)),
+            PrivateKeyHolder::Secp256k1Schnorr(ref k) => {
+                Signature::Secp256k1Schnorr(k.sign_message(msg))
+            }
+            PrivateKeyHolder::Ed25519(ref k) => Signature::Ed25519(k.sign_message(msg)),
         };
         Ok(signature)
     }
@@ -99,33 +111,49 @@ impl From<Secp256k1PrivateKey> for PrivateKey {
     }
 }
 
+impl From<Ed25519PrivateKey> for PrivateKey {
+    fn from(sk: Ed25519PrivateKey) -> Self {
+        Self {
+            key: PrivateKeyHolder::Ed25519(sk),
+        }
+    }
+}
+
 impl PublicKey {
     pub fn from_private_key(private_key: &PrivateKey) -> Self {
         match private_key.get_internal_key() {
             PrivateKeyHolder::Secp256k1Schnorr(ref k) => {
                 Secp256k1PublicKey::from_private_key(k).into()
             }
+            PrivateKeyHolder::Ed25519(ref k) => Ed25519PublicKey::from_private_key(k).into(),
         }
     }
 
     pub fn kind(&self) -> KeyKind {
         match self.pub_key {
             PublicKeyHolder::Secp256k1Schnorr(_) => KeyKind::Secp256k1Schnorr,
+            PublicKeyHolder::Ed25519(_) => KeyKind::Ed25519,
         }
     }
 
     #[must_use]
     pub fn verify_message(&self, signature: &Signature, msg: &[u8]) -> bool {
-        match &self.pub_key {
-            PublicKeyHolder::Secp256k1Schnorr(ref k) => match signature {
-                Secp256k1Schnorr(s) => k.verify_message(s, msg),
-            },
+        match (&self.pub_key, signature) {
+            (PublicKeyHolder::Secp256k1Schnorr(ref k), Signature::Secp256k1Schnorr(s)) => {
+                k.verify_message(s, msg)
+            }
+            (PublicKeyHolder::Ed25519(ref k), Signature::Ed25519(s)) => k.verify_message(s, msg),
+            // A signature of another scheme can never be valid for the key
+            (PublicKeyHolder::Secp256k1Schnorr(_), Signature::Ed25519(_))
+            | (PublicKeyHolder::Ed25519(_), Signature::Secp256k1Schnorr(_)) => false,
         }
     }
 
+    /// Whether the key can be aggregated with other keys of the same kind, see [musig2]
     pub fn is_aggregable(&self) -> bool {
         match self.pub_key {
-            PublicKeyHolder::Secp256k1Schnorr(_) => false,
+            PublicKeyHolder::Secp256k1Schnorr(_) => true,
+            PublicKeyHolder::Ed25519(_) => false,
         }
     }
 }
@@ -138,10 +166,19 @@ impl From<Secp256k1PublicKey> for PublicKey {
     }
 }
 
+impl From<Ed25519PublicKey> for PublicKey {
+    fn from(pk: Ed25519PublicKey) -> Self {
+        Self {
+            pub_key: PublicKeyHolder::Ed25519(pk),
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use rstest::rstest;
+    use serialization::DecodeAll;
     use test_utils::random::make_seedable_rng;
     use test_utils::random::Seed;
 
@@ -157,4 +194,40 @@ mod test {
         let sig = sk.sign_message(&msg).unwrap();
         assert!(pk.verify_message(&sig, &msg));
     }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn sign_and_verify_ed25519(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+        let (sk, pk) = PrivateKey::new_from_rng(&mut rng, KeyKind::Ed25519);
+        assert_eq!(sk.kind(), KeyKind::Ed25519);
+        assert_eq!(pk.kind(), KeyKind::Ed25519);
+        assert_eq!(PublicKey::from_private_key(&sk), pk);
+        let msg_size = 1 + rng.gen::<usize>() % 10000;
+        let msg: Vec<u8> = (0..msg_size).map(|_| rng.gen::<u8>()).collect();
+        let sig = sk.sign_message(&msg).unwrap();
+        assert!(pk.verify_message(&sig, &msg));
+
+        let encoded_pk = pk.encode();
+        let decoded_pk = PublicKey::decode_all(&mut encoded_pk.as_slice()).unwrap();
+        assert_eq!(decoded_pk, pk);
+        let encoded_sk = sk.encode();
+        let decoded_sk = PrivateKey::decode_all(&mut encoded_sk.as_slice()).unwrap();
+        assert_eq!(decoded_sk, sk);
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn signatures_of_other_kind_are_rejected(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+        let (secp_sk, secp_pk) = PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+        let (ed_sk, ed_pk) = PrivateKey::new_from_rng(&mut rng, KeyKind::Ed25519);
+        let msg = b"abc";
+        let secp_sig = secp_sk.sign_message(msg).unwrap();
+        let ed_sig = ed_sk.sign_message(msg).unwrap();
+        assert!(!secp_pk.verify_message(&ed_sig, msg));
+        assert!(!ed_pk.verify_message(&secp_sig, msg));
+    }
 }
@@ -0,0 +1,893 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! MuSig2 multi-signatures of Secp256k1 Schnorr keys, following BIP-327
+//!
+//! The public keys of n signers are aggregated into a single public key. Signing takes two rounds:
+//! 1. Every signer generates a nonce with [generate_nonce] and shares the public part of it.
+//! 2. Once all the public nonces are known, every signer creates a [SigningSession] and produces
+//!    a partial signature with [SigningSession::sign].
+//!
+//! The partial signatures are then aggregated into an ordinary BIP-340 signature that verifies
+//! with the aggregated public key like any other signature of a single key.
+//!
+//! The message is hashed with Blake2b32 before signing, the same way as in
+//! [PrivateKey::sign_message].
+
+use secp256k1::{Parity, Scalar, SecretKey, SECP256K1};
+use serialization::{Decode, Encode};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+use crate::hash::{hash, Blake2b32Stream, Sha256, Sha256Stream, StreamHasher};
+use crate::key::key_holder::{PrivateKeyHolder, PublicKeyHolder};
+use crate::key::secp256k1::Secp256k1PublicKey;
+use crate::key::{PrivateKey, PublicKey, Signature};
+use crate::random::{CryptoRng, Rng};
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq, Clone)]
+pub enum MuSig2Error {
+    #[error("No public keys to aggregate")]
+    NoPublicKeys,
+    #[error("The key kind does not support aggregation")]
+    KeyNotAggregable,
+    #[error("The key is not one of the aggregated keys")]
+    KeyNotInAggregation,
+    #[error("Expected {expected} public nonces, got {actual}")]
+    NonceCountMismatch { expected: usize, actual: usize },
+    #[error("Expected {expected} partial signatures, got {actual}")]
+    PartialSignatureCountMismatch { expected: usize, actual: usize },
+    #[error("The secret nonce does not belong to the signing session")]
+    SecretNonceMismatch,
+    #[error("Signer index {0} is out of range")]
+    SignerIndexOutOfRange(usize),
+    #[error("Invalid partial signature of signer {0}")]
+    InvalidPartialSignature(usize),
+    #[error("The aggregation results in the point at infinity")]
+    PointAtInfinity,
+    #[error("The generated nonce is zero")]
+    ZeroNonce,
+}
+
+/// SHA256 hash with the domain separation of BIP-340
+fn tagged_hash<T: AsRef<[u8]>>(tag: &str, data: impl IntoIterator<Item = T>) -> [u8; 32] {
+    let tag_hash = hash::<Sha256, _>(tag.as_bytes());
+    let mut hasher = Sha256Stream::new();
+    hasher.write(tag_hash).write(tag_hash);
+    for item in data {
+        hasher.write(item);
+    }
+    hasher.finalize().into()
+}
+
+fn message_hash(msg: &[u8]) -> [u8; 32] {
+    Blake2b32Stream::new().write(msg).finalize().into()
+}
+
+// The scalar arithmetic goes through SecretKey so that the constant time implementation
+// of libsecp256k1 is used for the operations on secret values.
+
+fn add_scalars(a: &Scalar, b: &Scalar) -> Scalar {
+    let a_bytes = Zeroizing::new(a.to_be_bytes());
+    match SecretKey::from_slice(&a_bytes[..]) {
+        Ok(mut a) => {
+            // Fails only if the sum is zero
+            let sum = a.add_tweak(b).map_or(Scalar::ZERO, Scalar::from);
+            a.non_secure_erase();
+            sum
+        }
+        // `a` is zero
+        Err(_) => *b,
+    }
+}
+
+fn mul_scalars(a: &Scalar, b: &Scalar) -> Scalar {
+    let a_bytes = Zeroizing::new(a.to_be_bytes());
+    match SecretKey::from_slice(&a_bytes[..]) {
+        Ok(mut a) => {
+            // Fails only if `b` is zero
+            let product = a.mul_tweak(b).map_or(Scalar::ZERO, Scalar::from);
+            a.non_secure_erase();
+            product
+        }
+        // `a` is zero
+        Err(_) => Scalar::ZERO,
+    }
+}
+
+fn negate_scalar(a: &Scalar) -> Scalar {
+    let a_bytes = Zeroizing::new(a.to_be_bytes());
+    match SecretKey::from_slice(&a_bytes[..]) {
+        Ok(mut a) => {
+            let negated = Scalar::from(a.negate());
+            a.non_secure_erase();
+            negated
+        }
+        // `a` is zero
+        Err(_) => Scalar::ZERO,
+    }
+}
+
+/// Interpret the big-endian bytes as an integer modulo the curve order.
+///
+/// Both 128-bit halves of the value are below the order, so the value is reduced by
+/// recombining them as `high * 2^128 + low` with the modular arithmetic of libsecp256k1.
+fn reduce_scalar(bytes: &[u8; 32]) -> Scalar {
+    let mut two_pow_128 = [0u8; 32];
+    two_pow_128[15] = 1;
+    let two_pow_128 = Scalar::from_be_bytes(two_pow_128).expect("2^128 is below the order");
+
+    let mut high_bytes = Zeroizing::new([0u8; 32]);
+    high_bytes[16..].copy_from_slice(&bytes[..16]);
+    let mut low_bytes = Zeroizing::new([0u8; 32]);
+    low_bytes[16..].copy_from_slice(&bytes[16..]);
+    let mut high = Scalar::from_be_bytes(*high_bytes).expect("128-bit values are below the order");
+    let mut low = Scalar::from_be_bytes(*low_bytes).expect("128-bit values are below the order");
+
+    let mut shifted_high = mul_scalars(&high, &two_pow_128);
+    let reduced = add_scalars(&shifted_high, &low);
+    high.non_secure_erase();
+    low.non_secure_erase();
+    shifted_high.non_secure_erase();
+    reduced
+}
+
+/// A scalar holding a secret value, erased from memory when dropped
+struct SecretScalar(Scalar);
+
+impl SecretScalar {
+    /// Take the value of the key, erasing the key itself
+    fn take_secret_key(secret_key: &mut SecretKey) -> Self {
+        let scalar = Self(Scalar::from(*secret_key));
+        secret_key.non_secure_erase();
+        scalar
+    }
+
+    fn add(&self, other: &Scalar) -> Self {
+        Self(add_scalars(&self.0, other))
+    }
+
+    fn mul(&self, other: &Scalar) -> Self {
+        Self(mul_scalars(&self.0, other))
+    }
+
+    fn negate(&self) -> Self {
+        Self(negate_scalar(&self.0))
+    }
+}
+
+impl Zeroize for SecretScalar {
+    fn zeroize(&mut self) {
+        self.0.non_secure_erase();
+    }
+}
+
+impl Drop for SecretScalar {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for SecretScalar {}
+
+fn mul_point(
+    point: secp256k1::PublicKey,
+    scalar: &Scalar,
+) -> Result<secp256k1::PublicKey, MuSig2Error> {
+    point.mul_tweak(SECP256K1, scalar).map_err(|_| MuSig2Error::PointAtInfinity)
+}
+
+fn sum_points(
+    points: impl IntoIterator<Item = secp256k1::PublicKey>,
+) -> Result<secp256k1::PublicKey, MuSig2Error> {
+    let points = points.into_iter().collect::<Vec<_>>();
+    secp256k1::PublicKey::combine_keys(&points.iter().collect::<Vec<_>>())
+        .map_err(|_| MuSig2Error::PointAtInfinity)
+}
+
+fn has_even_y(point: &secp256k1::PublicKey) -> bool {
+    point.x_only_public_key().1 == Parity::Even
+}
+
+fn x_only_bytes(point: &secp256k1::PublicKey) -> [u8; 32] {
+    point.x_only_public_key().0.serialize()
+}
+
+fn native_public_key(public_key: &PublicKey) -> Result<secp256k1::PublicKey, MuSig2Error> {
+    match &public_key.pub_key {
+        PublicKeyHolder::Secp256k1Schnorr(k) => Ok(*k.as_native()),
+        PublicKeyHolder::Ed25519(_) => Err(MuSig2Error::KeyNotAggregable),
+    }
+}
+
+fn native_private_key(private_key: &PrivateKey) -> Result<SecretKey, MuSig2Error> {
+    match private_key.get_internal_key() {
+        PrivateKeyHolder::Secp256k1Schnorr(k) => Ok(*k.as_native()),
+        PrivateKeyHolder::Ed25519(_) => Err(MuSig2Error::KeyNotAggregable),
+    }
+}
+
+/// The aggregation of the public keys of all the signers.
+/// The order of the keys matters, all the signers must use the same one.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct KeyAggContext {
+    public_keys: Vec<secp256k1::PublicKey>,
+    coefficients: Vec<Scalar>,
+    aggregate_key: secp256k1::PublicKey,
+}
+
+impl KeyAggContext {
+    pub fn new(public_keys: &[PublicKey]) -> Result<Self, MuSig2Error> {
+        if public_keys.is_empty() {
+            return Err(MuSig2Error::NoPublicKeys);
+        }
+
+        let public_keys =
+            public_keys.iter().map(native_public_key).collect::<Result<Vec<_>, _>>()?;
+        let serialized_keys = public_keys.iter().map(|pk| pk.serialize()).collect::<Vec<_>>();
+
+        let list_hash = tagged_hash("KeyAgg list", &serialized_keys);
+        // The coefficient of the second distinct key is 1, which saves one multiplication
+        let second_key = serialized_keys.iter().find(|pk| **pk != serialized_keys[0]);
+        let coefficients = serialized_keys
+            .iter()
+            .map(|pk| {
+                if Some(pk) == second_key {
+                    Scalar::ONE
+                } else {
+                    reduce_scalar(&tagged_hash(
+                        "KeyAgg coefficient",
+                        [&list_hash[..], &pk[..]],
+                    ))
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let aggregate_key = sum_points(
+            public_keys
+                .iter()
+                .zip(&coefficients)
+                .map(|(pk, coefficient)| mul_point(*pk, coefficient))
+                .collect::<Result<Vec<_>, _>>()?,
+        )?;
+
+        Ok(Self {
+            public_keys,
+            coefficients,
+            aggregate_key,
+        })
+    }
+
+    /// The key that verifies the aggregated signatures
+    pub fn aggregate_public_key(&self) -> PublicKey {
+        Secp256k1PublicKey::from_native(self.aggregate_key).into()
+    }
+
+    pub fn public_keys(&self) -> Vec<PublicKey> {
+        self.public_keys
+            .iter()
+            .map(|pk| Secp256k1PublicKey::from_native(*pk).into())
+            .collect()
+    }
+
+    pub fn signers_count(&self) -> usize {
+        self.public_keys.len()
+    }
+}
+
+/// Aggregate the public keys into a single key, see [KeyAggContext]
+pub fn aggregate_public_keys(public_keys: &[PublicKey]) -> Result<PublicKey, MuSig2Error> {
+    KeyAggContext::new(public_keys).map(|key_agg| key_agg.aggregate_public_key())
+}
+
+/// The secret part of the nonce of a signer.
+///
+/// It must be used for a single signature, reusing it leaks the private key.
+/// Because of that, it can't be cloned or serialized, it is consumed by [SigningSession::sign]
+/// and it is erased from memory when dropped.
+pub struct SecretNonce {
+    k1: SecretKey,
+    k2: SecretKey,
+    public_key: secp256k1::PublicKey,
+}
+
+impl SecretNonce {
+    fn public_nonce(&self) -> PublicNonce {
+        PublicNonce {
+            r1: self.k1.public_key(SECP256K1),
+            r2: self.k2.public_key(SECP256K1),
+        }
+    }
+}
+
+impl Zeroize for SecretNonce {
+    fn zeroize(&mut self) {
+        self.k1.non_secure_erase();
+        self.k2.non_secure_erase();
+    }
+}
+
+impl Drop for SecretNonce {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for SecretNonce {}
+
+/// The public part of the nonce of a signer, shared with the other signers in the first round
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PublicNonce {
+    r1: secp256k1::PublicKey,
+    r2: secp256k1::PublicKey,
+}
+
+impl Encode for PublicNonce {
+    fn using_encoded<R, F: FnOnce(&[u8]) -> R>(&self, f: F) -> R {
+        (self.r1.serialize(), self.r2.serialize()).using_encoded(f)
+    }
+}
+
+impl Decode for PublicNonce {
+    fn decode<I: serialization::Input>(input: &mut I) -> Result<Self, serialization::Error> {
+        let (r1, r2) = <(
+            [u8; secp256k1::constants::PUBLIC_KEY_SIZE],
+            [u8; secp256k1::constants::PUBLIC_KEY_SIZE],
+        )>::decode(input)?;
+        let decode_point = |bytes: &[u8]| {
+            secp256k1::PublicKey::from_slice(bytes)
+                .map_err(|_| serialization::Error::from("Public nonce deserialization failed"))
+        };
+        Ok(Self {
+            r1: decode_point(&r1)?,
+            r2: decode_point(&r2)?,
+        })
+    }
+}
+
+/// Generate the nonce of the signer with the given key for the next signature, as NonceGen
+/// of BIP-327 does.
+///
+/// Besides the randomness, the private key, the aggregated key and the message, if it's already
+/// known, are mixed into the nonce, so that a weak random number generator doesn't lead
+/// to the reuse of the nonce for a different message.
+pub fn generate_nonce<R: Rng + CryptoRng>(
+    rng: &mut R,
+    key_agg: &KeyAggContext,
+    private_key: &PrivateKey,
+    msg: Option<&[u8]>,
+) -> Result<(SecretNonce, PublicNonce), MuSig2Error> {
+    let mut secret_key = native_private_key(private_key)?;
+    let public_key = secret_key.public_key(SECP256K1);
+    let secret_key_bytes = Zeroizing::new(secret_key.secret_bytes());
+    secret_key.non_secure_erase();
+    if !key_agg.public_keys.contains(&public_key) {
+        return Err(MuSig2Error::KeyNotInAggregation);
+    }
+
+    // rand = sk XOR hash_MuSig/aux(rand')
+    let mut rand = Zeroizing::new([0u8; 32]);
+    rng.fill(&mut rand[..]);
+    let aux_hash = Zeroizing::new(tagged_hash("MuSig/aux", [&rand[..]]));
+    for ((byte, sk_byte), aux_byte) in
+        rand.iter_mut().zip(secret_key_bytes.iter()).zip(aux_hash.iter())
+    {
+        *byte = sk_byte ^ aux_byte;
+    }
+
+    let public_key_bytes = public_key.serialize();
+    let aggregate_key = x_only_bytes(&key_agg.aggregate_key);
+    // The message is the one that is actually signed, see SigningSession::new
+    let msg_prefixed = match msg {
+        Some(msg) => {
+            let msg_hash = message_hash(msg);
+            [&[1][..], &(msg_hash.len() as u64).to_be_bytes()[..], &msg_hash[..]].concat()
+        }
+        None => vec![0],
+    };
+    // No extra input is used
+    let extra_in_length = 0u32.to_be_bytes();
+
+    let make_nonce = |index: u8| -> Result<SecretKey, MuSig2Error> {
+        let hash = Zeroizing::new(tagged_hash(
+            "MuSig/nonce",
+            [
+                &rand[..],
+                &[public_key_bytes.len() as u8][..],
+                &public_key_bytes[..],
+                &[aggregate_key.len() as u8][..],
+                &aggregate_key[..],
+                &msg_prefixed[..],
+                &extra_in_length[..],
+                &[index][..],
+            ],
+        ));
+        let mut scalar = SecretScalar(reduce_scalar(&hash));
+        let scalar_bytes = Zeroizing::new(scalar.0.to_be_bytes());
+        scalar.zeroize();
+        SecretKey::from_slice(&scalar_bytes[..]).map_err(|_| MuSig2Error::ZeroNonce)
+    };
+
+    let secret_nonce = SecretNonce {
+        k1: make_nonce(0)?,
+        k2: make_nonce(1)?,
+        public_key,
+    };
+    let public_nonce = secret_nonce.public_nonce();
+    Ok((secret_nonce, public_nonce))
+}
+
+/// The share of a signer in the aggregated signature, produced in the second round
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct PartialSignature(Scalar);
+
+impl Encode for PartialSignature {
+    fn using_encoded<R, F: FnOnce(&[u8]) -> R>(&self, f: F) -> R {
+        self.0.to_be_bytes().using_encoded(f)
+    }
+}
+
+impl Decode for PartialSignature {
+    fn decode<I: serialization::Input>(input: &mut I) -> Result<Self, serialization::Error> {
+        let bytes = <[u8; 32]>::decode(input)?;
+        Scalar::from_be_bytes(bytes)
+            .map(Self)
+            .map_err(|_| serialization::Error::from("Partial signature deserialization failed"))
+    }
+}
+
+/// The second round of signing a message, once the public nonces of all the signers are known
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SigningSession {
+    key_agg: KeyAggContext,
+    /// The public nonces in the order of the aggregated keys
+    public_nonces: Vec<PublicNonce>,
+    nonce_coefficient: Scalar,
+    final_nonce: secp256k1::PublicKey,
+    challenge: Scalar,
+}
+
+impl SigningSession {
+    pub fn new(
+        key_agg: KeyAggContext,
+        public_nonces: Vec<PublicNonce>,
+        msg: &[u8],
+    ) -> Result<Self, MuSig2Error> {
+        if public_nonces.len() != key_agg.signers_count() {
+            return Err(MuSig2Error::NonceCountMismatch {
+                expected: key_agg.signers_count(),
+                actual: public_nonces.len(),
+            });
+        }
+
+        let aggregate_r1 = sum_points(public_nonces.iter().map(|nonce| nonce.r1))?;
+        let aggregate_r2 = sum_points(public_nonces.iter().map(|nonce| nonce.r2))?;
+        let aggregate_key = x_only_bytes(&key_agg.aggregate_key);
+        let msg_hash = message_hash(msg);
+
+        let nonce_coefficient = reduce_scalar(&tagged_hash(
+            "MuSig/noncecoef",
+            [
+                &aggregate_r1.serialize()[..],
+                &aggregate_r2.serialize()[..],
+                &aggregate_key[..],
+                &msg_hash[..],
+            ],
+        ));
+        let final_nonce = sum_points([aggregate_r1, mul_point(aggregate_r2, &nonce_coefficient)?])?;
+        let challenge = reduce_scalar(&tagged_hash(
+            "BIP0340/challenge",
+            [&x_only_bytes(&final_nonce)[..], &aggregate_key[..], &msg_hash[..]],
+        ));
+
+        Ok(Self {
+            key_agg,
+            public_nonces,
+            nonce_coefficient,
+            final_nonce,
+            challenge,
+        })
+    }
+
+    pub fn key_agg_context(&self) -> &KeyAggContext {
+        &self.key_agg
+    }
+
+    /// Produce the partial signature of the signer, the secret nonce must be the one whose
+    /// public part was used to create the session.
+    pub fn sign(
+        &self,
+        mut secret_nonce: SecretNonce,
+        private_key: &PrivateKey,
+    ) -> Result<PartialSignature, MuSig2Error> {
+        let mut native_secret_key = native_private_key(private_key)?;
+        let public_key = native_secret_key.public_key(SECP256K1);
+        let mut secret_key = SecretScalar::take_secret_key(&mut native_secret_key);
+        if public_key != secret_nonce.public_key {
+            return Err(MuSig2Error::SecretNonceMismatch);
+        }
+
+        // The same key may be aggregated more than once, the nonce tells the signers apart
+        let public_nonce = secret_nonce.public_nonce();
+        let signer_index = self
+            .key_agg
+            .public_keys
+            .iter()
+            .zip(&self.public_nonces)
+            .position(|(pk, nonce)| *pk == public_key && *nonce == public_nonce)
+            .ok_or(if self.key_agg.public_keys.contains(&public_key) {
+                MuSig2Error::SecretNonceMismatch
+            } else {
+                MuSig2Error::KeyNotInAggregation
+            })?;
+
+        // The intermediate values are erased as soon as they are dropped
+        let mut k1 = SecretScalar::take_secret_key(&mut secret_nonce.k1);
+        let mut k2 = SecretScalar::take_secret_key(&mut secret_nonce.k2);
+        drop(secret_nonce);
+
+        // The signature is made for the points with even y coordinates, as in BIP-340
+        if !has_even_y(&self.final_nonce) {
+            k1 = k1.negate();
+            k2 = k2.negate();
+        }
+        if !has_even_y(&self.key_agg.aggregate_key) {
+            secret_key = secret_key.negate();
+        }
+
+        // s = k1 + b * k2 + e * a * d
+        let key_coefficient =
+            mul_scalars(&self.challenge, &self.key_agg.coefficients[signer_index]);
+        let nonce_part = k2.mul(&self.nonce_coefficient).add(&k1.0);
+        let key_part = secret_key.mul(&key_coefficient);
+        let partial_signature = PartialSignature(nonce_part.add(&key_part.0).0);
+
+        // Make sure that a faulty signature isn't handed out
+        self.verify_partial_signature(signer_index, &partial_signature)?;
+
+        Ok(partial_signature)
+    }
+
+    /// Check the partial signature of the signer with the given index in the aggregation
+    pub fn verify_partial_signature(
+        &self,
+        signer_index: usize,
+        partial_signature: &PartialSignature,
+    ) -> Result<(), MuSig2Error> {
+        let public_key = self
+            .key_agg
+            .public_keys
+            .get(signer_index)
+            .ok_or(MuSig2Error::SignerIndexOutOfRange(signer_index))?;
+        let public_nonce = &self.public_nonces[signer_index];
+
+        // s * G == R1 + b * R2 + e * a * P
+        let expected = || -> Result<secp256k1::PublicKey, MuSig2Error> {
+            let nonce = sum_points([
+                public_nonce.r1,
+                mul_point(public_nonce.r2, &self.nonce_coefficient)?,
+            ])?;
+            let nonce = if has_even_y(&self.final_nonce) {
+                nonce
+            } else {
+                nonce.negate(SECP256K1)
+            };
+
+            let key_coefficient =
+                mul_scalars(&self.challenge, &self.key_agg.coefficients[signer_index]);
+            let key_coefficient = if has_even_y(&self.key_agg.aggregate_key) {
+                key_coefficient
+            } else {
+                negate_scalar(&key_coefficient)
+            };
+
+            sum_points([nonce, mul_point(*public_key, &key_coefficient)?])
+        };
+        let actual = SecretKey::from_slice(&partial_signature.0.to_be_bytes())
+            .map(|s| s.public_key(SECP256K1));
+
+        let is_valid =
+            matches!((actual, expected()), (Ok(actual), Ok(expected)) if actual == expected);
+        if !is_valid {
+            return Err(MuSig2Error::InvalidPartialSignature(signer_index));
+        }
+        Ok(())
+    }
+
+    /// Combine the partial signatures of all the signers, in the order of the aggregated keys,
+    /// into a signature that verifies with the aggregated public key
+    pub fn aggregate_signatures(
+        &self,
+        partial_signatures: &[PartialSignature],
+    ) -> Result<Signature, MuSig2Error> {
+        if partial_signatures.len() != self.key_agg.signers_count() {
+            return Err(MuSig2Error::PartialSignatureCountMismatch {
+                expected: self.key_agg.signers_count(),
+                actual: partial_signatures.len(),
+            });
+        }
+
+        for (signer_index, partial_signature) in partial_signatures.iter().enumerate() {
+            self.verify_partial_signature(signer_index, partial_signature)?;
+        }
+
+        let s = partial_signatures.iter().fold(Scalar::ZERO, |sum, partial_signature| {
+            add_scalars(&sum, &partial_signature.0)
+        });
+
+        let mut signature = [0u8; secp256k1::constants::SCHNORR_SIGNATURE_SIZE];
+        signature[..32].copy_from_slice(&x_only_bytes(&self.final_nonce));
+        signature[32..].copy_from_slice(&s.to_be_bytes());
+        let signature = secp256k1::schnorr::Signature::from_slice(&signature)
+            .expect("The signature has the right size");
+
+        Ok(Signature::Secp256k1Schnorr(signature))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::key::KeyKind;
+    use crate::random::SeedableRng;
+    use rand_chacha::ChaChaRng;
+    use rstest::rstest;
+    use secp256k1::constants::CURVE_ORDER;
+    use serialization::DecodeAll;
+    use test_utils::random::{make_seedable_rng, Seed};
+
+    fn make_keys(rng: &mut (impl Rng + CryptoRng), count: usize) -> Vec<(PrivateKey, PublicKey)> {
+        (0..count)
+            .map(|_| PrivateKey::new_from_rng(rng, KeyKind::Secp256k1Schnorr))
+            .collect()
+    }
+
+    fn sign_all(
+        rng: &mut (impl Rng + CryptoRng),
+        keys: &[(PrivateKey, PublicKey)],
+        msg: &[u8],
+    ) -> (SigningSession, Vec<PartialSignature>) {
+        let public_keys = keys.iter().map(|(_, pk)| pk.clone()).collect::<Vec<_>>();
+        let key_agg = KeyAggContext::new(&public_keys).unwrap();
+
+        let (secret_nonces, public_nonces): (Vec<_>, Vec<_>) = keys
+            .iter()
+            .map(|(sk, _)| generate_nonce(rng, &key_agg, sk, Some(msg)).unwrap())
+            .unzip();
+
+        let session = SigningSession::new(key_agg, public_nonces, msg).unwrap();
+        let partial_signatures = secret_nonces
+            .into_iter()
+            .zip(keys)
+            .map(|(secret_nonce, (sk, _))| session.sign(secret_nonce, sk).unwrap())
+            .collect();
+        (session, partial_signatures)
+    }
+
+    #[test]
+    fn scalar_reduction() {
+        assert_eq!(reduce_scalar(&CURVE_ORDER), Scalar::ZERO);
+        let reduced_max: [u8; 32] =
+            hex::decode("000000000000000000000000000000014551231950b75fc4402da1732fc9bebe")
+                .unwrap()
+                .try_into()
+                .unwrap();
+        assert_eq!(reduce_scalar(&[0xff; 32]).to_be_bytes(), reduced_max);
+        assert_eq!(reduce_scalar(&Scalar::ONE.to_be_bytes()), Scalar::ONE);
+        assert_eq!(reduce_scalar(&Scalar::MAX.to_be_bytes()), Scalar::MAX);
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn nonce_generation(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+        let keys = make_keys(&mut rng, 2);
+        let public_keys = keys.iter().map(|(_, pk)| pk.clone()).collect::<Vec<_>>();
+        let key_agg = KeyAggContext::new(&public_keys).unwrap();
+        let other_key_agg = KeyAggContext::new(&public_keys[..1]).unwrap();
+
+        // The private key, the aggregated key and the message are mixed into the nonce,
+        // so the same randomness doesn't produce the same nonce
+        let randomness_seed = rng.gen::<[u8; 32]>();
+        let nonce_with = |key_agg: &KeyAggContext, sk: &PrivateKey, msg: Option<&[u8]>| {
+            let mut nonce_rng = ChaChaRng::from_seed(randomness_seed);
+            generate_nonce(&mut nonce_rng, key_agg, sk, msg).unwrap().1
+        };
+        let public_nonce = nonce_with(&key_agg, &keys[0].0, Some(b"msg"));
+        assert_eq!(nonce_with(&key_agg, &keys[0].0, Some(b"msg")), public_nonce);
+        assert_ne!(
+            nonce_with(&key_agg, &keys[0].0, Some(b"other msg")),
+            public_nonce
+        );
+        assert_ne!(nonce_with(&key_agg, &keys[0].0, None), public_nonce);
+        assert_ne!(nonce_with(&key_agg, &keys[1].0, Some(b"msg")), public_nonce);
+        assert_ne!(
+            nonce_with(&other_key_agg, &keys[0].0, Some(b"msg")),
+            public_nonce
+        );
+
+        let (mut secret_nonce, public_nonce) =
+            generate_nonce(&mut rng, &key_agg, &keys[0].0, None).unwrap();
+        assert_eq!(secret_nonce.public_nonce(), public_nonce);
+        assert_ne!(public_nonce.r1, public_nonce.r2);
+
+        secret_nonce.zeroize();
+        assert_ne!(secret_nonce.public_nonce(), public_nonce);
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn sign_and_verify(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+        let signers_count = rng.gen_range(1..6);
+        let keys = make_keys(&mut rng, signers_count);
+        let msg: Vec<u8> = (0..rng.gen_range(0..100)).map(|_| rng.gen::<u8>()).collect();
+
+        let (session, partial_signatures) = sign_all(&mut rng, &keys, &msg);
+        let signature = session.aggregate_signatures(&partial_signatures).unwrap();
+        assert!(signature.is_aggregable());
+
+        let public_keys = keys.iter().map(|(_, pk)| pk.clone()).collect::<Vec<_>>();
+        let aggregate_key = aggregate_public_keys(&public_keys).unwrap();
+        assert_eq!(
+            aggregate_key,
+            session.key_agg_context().aggregate_public_key()
+        );
+        assert!(aggregate_key.verify_message(&signature, &msg));
+        assert!(!aggregate_key.verify_message(&signature, b"other message"));
+        for (_, public_key) in &keys {
+            assert!(!public_key.verify_message(&signature, &msg));
+        }
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn key_order_matters(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+        let public_keys = make_keys(&mut rng, 3).into_iter().map(|(_, pk)| pk).collect::<Vec<_>>();
+        let mut reversed = public_keys.clone();
+        reversed.reverse();
+        assert_ne!(
+            aggregate_public_keys(&public_keys).unwrap(),
+            aggregate_public_keys(&reversed).unwrap()
+        );
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn duplicate_keys(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+        let mut keys = make_keys(&mut rng, 2);
+        keys.push(keys[0].clone());
+        let msg = b"duplicate keys";
+
+        let (session, partial_signatures) = sign_all(&mut rng, &keys, msg);
+        let signature = session.aggregate_signatures(&partial_signatures).unwrap();
+        assert!(session.key_agg_context().aggregate_public_key().verify_message(&signature, msg));
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn invalid_partial_signatures(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+        let keys = make_keys(&mut rng, 3);
+        let msg = b"message";
+
+        let (session, mut partial_signatures) = sign_all(&mut rng, &keys, msg);
+        assert_eq!(
+            session.aggregate_signatures(&partial_signatures[1..]),
+            Err(MuSig2Error::PartialSignatureCountMismatch {
+                expected: 3,
+                actual: 2
+            })
+        );
+        assert_eq!(
+            session.verify_partial_signature(3, &partial_signatures[0]),
+            Err(MuSig2Error::SignerIndexOutOfRange(3))
+        );
+
+        partial_signatures.swap(0, 1);
+        assert_eq!(
+            session.verify_partial_signature(0, &partial_signatures[0]),
+            Err(MuSig2Error::InvalidPartialSignature(0))
+        );
+        assert_eq!(
+            session.aggregate_signatures(&partial_signatures),
+            Err(MuSig2Error::InvalidPartialSignature(0))
+        );
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn wrong_signer(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+        let keys = make_keys(&mut rng, 2);
+        let (other_sk, _) = PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+        let public_keys = keys.iter().map(|(_, pk)| pk.clone()).collect::<Vec<_>>();
+        let key_agg = KeyAggContext::new(&public_keys).unwrap();
+
+        assert!(matches!(
+            generate_nonce(&mut rng, &key_agg, &other_sk, None),
+            Err(MuSig2Error::KeyNotInAggregation)
+        ));
+
+        let (secret_nonce0, public_nonce0) =
+            generate_nonce(&mut rng, &key_agg, &keys[0].0, Some(b"msg")).unwrap();
+        let (_, public_nonce1) =
+            generate_nonce(&mut rng, &key_agg, &keys[1].0, Some(b"msg")).unwrap();
+        let session =
+            SigningSession::new(key_agg.clone(), vec![public_nonce0, public_nonce1], b"msg")
+                .unwrap();
+        assert_eq!(
+            session.sign(secret_nonce0, &keys[1].0),
+            Err(MuSig2Error::SecretNonceMismatch)
+        );
+
+        // A nonce that was not shared with the other signers can't be used
+        let (secret_nonce, _) =
+            generate_nonce(&mut rng, &key_agg, &keys[0].0, Some(b"msg")).unwrap();
+        assert_eq!(
+            session.sign(secret_nonce, &keys[0].0),
+            Err(MuSig2Error::SecretNonceMismatch)
+        );
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn non_aggregable_keys(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+        let (_, secp_pk) = PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+        let (_, ed_pk) = PrivateKey::new_from_rng(&mut rng, KeyKind::Ed25519);
+        assert!(secp_pk.is_aggregable());
+        assert!(!ed_pk.is_aggregable());
+
+        assert_eq!(
+            aggregate_public_keys(&[secp_pk, ed_pk]),
+            Err(MuSig2Error::KeyNotAggregable)
+        );
+        assert_eq!(aggregate_public_keys(&[]), Err(MuSig2Error::NoPublicKeys));
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn serialization(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+        let keys = make_keys(&mut rng, 2);
+        let (session, partial_signatures) = sign_all(&mut rng, &keys, b"msg");
+
+        for public_nonce in &session.public_nonces {
+            let encoded = public_nonce.encode();
+            assert_eq!(encoded.len(), 2 * secp256k1::constants::PUBLIC_KEY_SIZE);
+            assert_eq!(
+                &PublicNonce::decode_all(&mut encoded.as_slice()).unwrap(),
+                public_nonce
+            );
+        }
+        for partial_signature in &partial_signatures {
+            let encoded = partial_signature.encode();
+            assert_eq!(
+                &PartialSignature::decode_all(&mut encoded.as_slice()).unwrap(),
+                partial_signature
+            );
+        }
+    }
+}
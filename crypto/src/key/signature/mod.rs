@@ -22,12 +22,14 @@ use serialization::{Decode, DecodeAll, Encode};
 #[derive(FromPrimitive)]
 pub enum SignatureKind {
     Secp256k1Schnorr = 0,
+    Ed25519 = 1,
 }
 
 // #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum Signature {
     Secp256k1Schnorr(secp256k1::schnorr::Signature),
+    Ed25519(ed25519_dalek::Signature),
 }
 
 impl Encode for Signature {
@@ -44,6 +46,10 @@ impl Encode for Signature {
                 dest.write(&[(SignatureKind::Secp256k1Schnorr as u8)]);
                 s.as_ref().encode_to(dest);
             }
+            Signature::Ed25519(s) => {
+                dest.write(&[(SignatureKind::Ed25519 as u8)]);
+                s.to_bytes().encode_to(dest);
+            }
         }
     }
 }
@@ -62,6 +68,11 @@ impl Decode for Signature {
                     .map_err(|_| serialization::Error::from("Signature deserialization failed"))?;
                 Ok(Signature::Secp256k1Schnorr(sig))
             }
+            SignatureKind::Ed25519 => {
+                let data = <[u8; crate::key::ed25519::SIGNATURE_SIZE]>::decode(input)?;
+                let sig = ed25519_dalek::Signature::from_bytes(&data);
+                Ok(Signature::Ed25519(sig))
+            }
         }
     }
 }
@@ -74,13 +85,15 @@ impl Signature {
 
     pub fn is_aggregable(&self) -> bool {
         match self {
-            Self::Secp256k1Schnorr(_) => false,
+            Self::Secp256k1Schnorr(_) => true,
+            Self::Ed25519(_) => false,
         }
     }
 
     pub fn kind(&self) -> SignatureKind {
         match self {
             Self::Secp256k1Schnorr(_) => SignatureKind::Secp256k1Schnorr,
+            Self::Ed25519(_) => SignatureKind::Ed25519,
         }
     }
 }
@@ -109,6 +122,23 @@ mod test {
         assert_eq!(decoded_sig, sig);
     }
 
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn serialize_ed25519(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+        let (sk, pk) = PrivateKey::new_from_rng(&mut rng, KeyKind::Ed25519);
+        let msg = b"abc";
+        let sig = sk.sign_message(msg).unwrap();
+        assert!(pk.verify_message(&sig, msg));
+        assert_eq!(sig.kind() as u8, SignatureKind::Ed25519 as u8);
+
+        let encoded_sig = sig.encode();
+        assert_eq!(encoded_sig.len(), 1 + crate::key::ed25519::SIGNATURE_SIZE);
+        let decoded_sig = Signature::decode_all(&mut encoded_sig.as_slice()).unwrap();
+        assert_eq!(decoded_sig, sig);
+    }
+
     #[test]
     fn serialize_chosen_data_secp256k1() {
         let msg = b"abc";
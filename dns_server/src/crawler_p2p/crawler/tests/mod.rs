@@ -65,6 +65,7 @@ fn basic(#[case] seed: Seed) {
                 version: *chain_config.version(),
                 user_agent: mintlayer_core_user_agent(),
                 services: NodeType::Full.into(),
+                node_key: None,
             },
         },
         &mut rng,
@@ -97,6 +98,7 @@ fn basic(#[case] seed: Seed) {
                 version: *chain_config.version(),
                 user_agent: mintlayer_core_user_agent(),
                 services: NodeType::Full.into(),
+                node_key: None,
             },
         },
         &mut rng,
@@ -176,6 +178,7 @@ fn randomized(#[case] seed: Seed) {
                         version: *chain_config.version(),
                         user_agent: mintlayer_core_user_agent(),
                         services: NodeType::Full.into(),
+                        node_key: None,
                     },
                 },
                 &mut rng,
@@ -195,6 +198,7 @@ fn randomized(#[case] seed: Seed) {
                         version: *chain_config.version(),
                         user_agent: mintlayer_core_user_agent(),
                         services: NodeType::Full.into(),
+                        node_key: None,
                     },
                 },
                 &mut rng,
@@ -246,6 +250,7 @@ fn incompatible_node(#[case] seed: Seed) {
                 version: *chain_config.version(),
                 user_agent: mintlayer_core_user_agent(),
                 services: NodeType::Full.into(),
+                node_key: None,
             },
         },
         &mut rng,
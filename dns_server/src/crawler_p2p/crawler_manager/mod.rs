@@ -267,7 +267,7 @@ where
     ) {
         match cmd {
             CrawlerCommand::Connect { address } => {
-                conn.connect(address).expect("connect must succeed");
+                conn.connect(address, None).expect("connect must succeed");
             }
             CrawlerCommand::Disconnect { peer_id } => {
                 conn.disconnect(peer_id).expect("disconnect must succeed");
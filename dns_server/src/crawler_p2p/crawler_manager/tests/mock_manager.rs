@@ -41,12 +41,12 @@ use p2p::{
     message::{AnnounceAddrRequest, PeerManagerMessage},
     net::{
         default_backend::transport::TransportAddress,
-        types::{ConnectivityEvent, PeerInfo, SyncingEvent},
+        types::{services::Services, ConnectivityEvent, PeerInfo, SyncingEvent},
         ConnectivityService, NetworkingService, SyncingEventReceiver,
     },
     protocol::NETWORK_PROTOCOL_CURRENT,
     types::peer_id::PeerId,
-    P2pEventHandler,
+    P2pEventSubscription,
 };
 use p2p_test_utils::P2pBasicTestTimeGetter;
 use utils::atomics::SeqCstAtomicBool;
@@ -130,7 +130,7 @@ impl NetworkingService for MockNetworkingService {
         _time_getter: TimeGetter,
         _shutdown: Arc<SeqCstAtomicBool>,
         _shutdown_receiver: oneshot::Receiver<()>,
-        _subscribers_receiver: mpsc::UnboundedReceiver<P2pEventHandler>,
+        _subscribers_receiver: mpsc::UnboundedReceiver<P2pEventSubscription>,
     ) -> p2p::Result<(
         Self::ConnectivityHandle,
         Self::MessagingHandle,
@@ -143,7 +143,11 @@ impl NetworkingService for MockNetworkingService {
 
 #[async_trait]
 impl ConnectivityService<MockNetworkingService> for MockConnectivityHandle {
-    fn connect(&mut self, address: SocketAddr) -> p2p::Result<()> {
+    fn connect(
+        &mut self,
+        address: SocketAddr,
+        _local_services_override: Option<Services>,
+    ) -> p2p::Result<()> {
         self.state.connection_attempts.lock().unwrap().push(address);
         if let Some(node) = self.state.online.lock().unwrap().get(&address) {
             let peer_id = PeerId::new();
@@ -154,6 +158,7 @@ impl ConnectivityService<MockNetworkingService> for MockConnectivityHandle {
                 version: SemVer::new(1, 2, 3),
                 user_agent: mintlayer_core_user_agent(),
                 services: NodeType::Full.into(),
+                node_key: None,
             };
             let old = self.state.connected.lock().unwrap().insert(address, peer_id);
             assert!(old.is_none());
@@ -52,6 +52,7 @@ async fn run(config: Arc<DnsServerConfig>) -> Result<Never, error::DnsServerErro
     let p2p_config = Arc::new(P2pConfig {
         bind_addresses: Vec::new(),
         socks5_proxy: None,
+        tor_control_address: None,
         disable_noise: Default::default(),
         boot_nodes: Vec::new(),
         reserved_nodes: Vec::new(),
@@ -74,7 +75,7 @@ async fn run(config: Arc<DnsServerConfig>) -> Result<Never, error::DnsServerErro
         sync_stalling_timeout: Default::default(),
     });
 
-    let transport = p2p::make_p2p_transport();
+    let transport = p2p::make_p2p_transport(p2p::node_key::NodeKeypair::generate());
     let shutdown = Arc::new(SeqCstAtomicBool::new(false));
     let (_shutdown_sender, shutdown_receiver) = oneshot::channel();
     let (_subscribers_sender, subscribers_receiver) = mpsc::unbounded_channel();
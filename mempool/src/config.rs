@@ -43,7 +43,12 @@ impl std::fmt::Display for MempoolMaxSize {
     }
 }
 
-pub const ENABLE_RBF: bool = false;
+/// The mempool subsystem configuration.
+#[derive(Debug, Clone, Default)]
+pub struct MempoolConfig {
+    /// Accept transactions replacing conflicting transactions that signal replaceability.
+    pub enable_rbf: bool,
+}
 
 // Number of times we try to add transaction if the tip moves during validation
 pub const MAX_TX_ADDITION_ATTEMPTS: usize = 3;
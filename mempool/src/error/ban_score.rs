@@ -36,6 +36,8 @@ impl MempoolBanScore for Error {
             Error::Policy(err) => err.mempool_ban_score(),
             // Orphan errors may be race / out of sync errors, allowed
             Error::Orphan(_) => 0,
+            // Fee estimation is a local query, never triggered by peers
+            Error::FeeEstimation(_) => 0,
         }
     }
 }
@@ -47,6 +49,7 @@ impl MempoolBanScore for MempoolPolicyError {
             MempoolPolicyError::NoInputs => 100,
             MempoolPolicyError::NoOutputs => 100,
             MempoolPolicyError::ExceedsMaxBlockSize => 100,
+            MempoolPolicyError::ExceedsMaxBlockSigOps => 100,
             MempoolPolicyError::RelayFeeOverflow => 100,
 
             // Errors to do with transaction conflicts and replacements are not punished since the
@@ -150,6 +153,7 @@ impl MempoolBanScore for ConnectTransactionError {
             ConnectTransactionError::TxNumWrongInBlockOnConnect(_, _) => 0,
             ConnectTransactionError::TxNumWrongInBlockOnDisconnect(_, _) => 0,
             ConnectTransactionError::FailedToAddAllFeesOfBlock(_) => 0,
+            ConnectTransactionError::TooManySigOpsInBlock(_, _, _) => 0,
 
             // Internal errors, not peer's fault
             ConnectTransactionError::InvariantBrokenAlreadyUnspent => 0,
@@ -158,6 +162,9 @@ impl MempoolBanScore for ConnectTransactionError {
             ConnectTransactionError::InvariantErrorHeaderCouldNotBeLoadedFromHeight(_, _) => 0,
             ConnectTransactionError::TxUndoWithDependency(_) => 0,
             ConnectTransactionError::MissingPoSAccountingUndo(_) => 0,
+            ConnectTransactionError::MissingTokensUndo(_) => 0,
+            ConnectTransactionError::KeyKindNotActivated(_) => 0,
+            ConnectTransactionError::TokensBlockUndoError(_) => 0,
             ConnectTransactionError::UtxoBlockUndoError(_) => 0,
             ConnectTransactionError::AccountingBlockUndoError(_) => 0,
             ConnectTransactionError::PoolOwnerBalanceNotFound(_) => 0,
@@ -213,6 +220,7 @@ impl MempoolBanScore for TransactionVerifierStorageError {
             TransactionVerifierStorageError::DuplicateBlockUndo(_) => 0,
             TransactionVerifierStorageError::TransactionIndexDisabled => 0,
             TransactionVerifierStorageError::AccountingBlockUndoError(_) => 0,
+            TransactionVerifierStorageError::TokensBlockUndoError(_) => 0,
         }
     }
 }
@@ -32,6 +32,8 @@ pub enum Error {
     Policy(#[from] MempoolPolicyError),
     #[error("Orphan transaction error: {0}")]
     Orphan(#[from] OrphanPoolError),
+    #[error("Fee estimation error: {0}")]
+    FeeEstimation(#[from] FeeEstimationError),
 }
 
 #[derive(Debug, Clone, Error, PartialEq, Eq)]
@@ -46,6 +48,8 @@ pub enum MempoolPolicyError {
     NoOutputs,
     #[error("Transaction exceeds the maximum block size.")]
     ExceedsMaxBlockSize,
+    #[error("Transaction exceeds the maximum number of signature operations in a block.")]
+    ExceedsMaxBlockSigOps,
     #[error("Transaction already exists in the mempool.")]
     TransactionAlreadyInMempool,
     #[error("Replacement transaction has fee lower than the original. Replacement fee is {replacement_fee:?}, original fee {original_fee:?}")]
@@ -109,6 +113,14 @@ pub enum OrphanPoolError {
     MempoolConflict,
 }
 
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum FeeEstimationError {
+    #[error("Confirmation target of {0} blocks is out of the supported range 1..={1}")]
+    InvalidConfirmationTarget(usize, usize),
+    #[error("Confidence of {0}% is out of the range 1..=100")]
+    InvalidConfidence(u8),
+}
+
 impl From<ConnectTransactionError> for Error {
     fn from(e: ConnectTransactionError) -> Self {
         TxValidationError::from(e).into()
@@ -14,8 +14,11 @@
 // limitations under the License.
 
 use crate::{
-    error::Error, event::MempoolEvent, tx_accumulator::TransactionAccumulator, FeeRate,
-    MempoolMaxSize, TxOrigin, TxStatus,
+    error::Error,
+    event::MempoolEvent,
+    tx_accumulator::TransactionAccumulator,
+    tx_info::{MempoolLimits, OrphanTxInfo, TxMempoolInfo},
+    FeeRate, MempoolMaxSize, TxOrigin, TxStatus,
 };
 use common::{
     chain::{GenBlock, SignedTransaction, Transaction},
@@ -23,6 +26,7 @@ use common::{
 };
 use std::sync::Arc;
 use subsystem::{CallRequest, ShutdownRequest};
+use utils::eventhandler::WeakEventHandler;
 
 pub trait MempoolInterface: Send + Sync {
     /// Add a transaction to mempool
@@ -35,6 +39,12 @@ pub trait MempoolInterface: Send + Sync {
     /// Get all transactions from mempool
     fn get_all(&self) -> Vec<SignedTransaction>;
 
+    /// Get the transactions from the main mempool with the ids matching the predicate
+    fn find_transactions(
+        &self,
+        predicate: &dyn Fn(&Id<Transaction>) -> bool,
+    ) -> Vec<SignedTransaction>;
+
     /// Get a specific transaction from the main mempool (non-orphan)
     fn transaction(&self, id: &Id<Transaction>) -> Option<SignedTransaction>;
 
@@ -47,6 +57,18 @@ pub trait MempoolInterface: Send + Sync {
     /// Check given transaction is contained in the main mempool (non-orphan)
     fn contains_orphan_transaction(&self, tx: &Id<Transaction>) -> bool;
 
+    /// Get information about all transactions in the main mempool, best descendant score first
+    fn get_all_tx_info(&self) -> Vec<TxMempoolInfo>;
+
+    /// Get information about a specific transaction in the main mempool
+    fn tx_info(&self, id: &Id<Transaction>) -> Option<TxMempoolInfo>;
+
+    /// Get information about all transactions in the orphan pool
+    fn get_all_orphan_info(&self) -> Vec<OrphanTxInfo>;
+
+    /// Check whether given transaction is in the main mempool, the orphan pool or neither
+    fn tx_status(&self, id: &Id<Transaction>) -> Option<TxStatus>;
+
     /// Best block ID according to mempool. May be temporarily out of sync with chainstate.
     fn best_block_id(&self) -> Id<GenBlock>;
 
@@ -60,18 +82,36 @@ pub trait MempoolInterface: Send + Sync {
     /// Subscribe to events emitted by mempool
     fn subscribe_to_events(&mut self, handler: Arc<dyn Fn(MempoolEvent) + Send + Sync>);
 
+    /// Subscribe to events emitted by mempool until the handler is dropped by the caller
+    fn subscribe_to_events_weak(&mut self, handler: WeakEventHandler<MempoolEvent>);
+
+    /// Emit the transaction processed events of the local transactions restored on startup again
+    fn announce_restored_transactions(&mut self);
+
     /// Get current memory usage
     fn memory_usage(&self) -> usize;
 
     /// Get maximum mempool size
     fn get_max_size(&self) -> MempoolMaxSize;
 
+    /// Get the limits mempool currently enforces
+    fn get_limits(&self) -> MempoolLimits;
+
     /// Set the maximum mempool size
     fn set_max_size(&mut self, max_size: MempoolMaxSize) -> Result<(), Error>;
 
     /// Get the fee rate such that it would put the new transaction in the top X MB of the mempool
     /// making it less likely to get rejected or trimmed in the case the mempool is full
     fn get_fee_rate(&self, in_top_x_mb: usize) -> Result<FeeRate, Error>;
+
+    /// Estimate the fee rate needed for a transaction to get confirmed within `target_blocks`
+    /// blocks with the given confidence, based on how long past transactions took to confirm.
+    /// Returns `None` if not enough transactions have been observed yet.
+    fn estimate_fee_rate(
+        &self,
+        target_blocks: usize,
+        confidence_percent: u8,
+    ) -> Result<Option<FeeRate>, Error>;
 }
 
 #[async_trait::async_trait]
@@ -14,9 +14,13 @@
 // limitations under the License.
 
 use crate::{
-    error::Error, event::MempoolEvent, pool::memory_usage_estimator::StoreMemoryUsageEstimator,
-    tx_accumulator::TransactionAccumulator, FeeRate, MempoolInterface, MempoolMaxSize,
-    MempoolSubsystemInterface, TxOrigin, TxStatus,
+    error::Error,
+    event::MempoolEvent,
+    pool::memory_usage_estimator::StoreMemoryUsageEstimator,
+    tx_accumulator::TransactionAccumulator,
+    tx_info::{MempoolLimits, OrphanTxInfo, TxMempoolInfo},
+    FeeRate, MempoolConfig, MempoolInterface, MempoolMaxSize, MempoolSubsystemInterface, TxOrigin,
+    TxStatus,
 };
 use chainstate::chainstate_interface::ChainstateInterface;
 use common::{
@@ -25,32 +29,44 @@ use common::{
     time_getter::TimeGetter,
 };
 use logging::log;
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 use subsystem::{CallRequest, ShutdownRequest};
 use tokio::sync::mpsc;
-use utils::tap_error_log::LogError;
+use utils::{eventhandler::WeakEventHandler, tap_error_log::LogError};
 
 type Mempool = crate::pool::Mempool<StoreMemoryUsageEstimator>;
 
+/// Name of the file in the data directory the fee estimator statistics are kept in
+const FEE_ESTIMATES_FILE_NAME: &str = "fee_estimates.dat";
+
+/// Name of the file in the data directory the mempool transactions are saved to on shutdown
+const MEMPOOL_FILE_NAME: &str = "mempool.dat";
+
 /// Mempool initializer
 ///
 /// Contains all the information required to spin up the mempool subsystem
 struct MempoolInit {
     chain_config: Arc<ChainConfig>,
+    mempool_config: MempoolConfig,
     chainstate_handle: subsystem::Handle<Box<dyn ChainstateInterface>>,
     time_getter: TimeGetter,
+    data_dir: Option<PathBuf>,
 }
 
 impl MempoolInit {
     fn new(
         chain_config: Arc<ChainConfig>,
+        mempool_config: MempoolConfig,
         chainstate_handle: subsystem::Handle<Box<dyn ChainstateInterface>>,
         time_getter: TimeGetter,
+        data_dir: Option<PathBuf>,
     ) -> Self {
         Self {
             chain_config,
+            mempool_config,
             chainstate_handle,
             time_getter,
+            data_dir,
         }
     }
 
@@ -80,11 +96,20 @@ impl MempoolSubsystemInterface for MempoolInit {
         log::info!("Starting mempool");
         let mut mempool = Mempool::new(
             self.chain_config,
+            self.mempool_config,
             self.chainstate_handle,
             self.time_getter,
             StoreMemoryUsageEstimator,
         );
 
+        let fee_estimates_path =
+            self.data_dir.as_ref().map(|dir| dir.join(FEE_ESTIMATES_FILE_NAME));
+        let mempool_path = self.data_dir.as_ref().map(|dir| dir.join(MEMPOOL_FILE_NAME));
+        if let Some(path) = fee_estimates_path.as_ref().filter(|path| path.exists()) {
+            log::trace!("Loading fee estimates from {}", path.display());
+            let _ = mempool.load_fee_estimates(path).log_err_pfx("Loading fee estimates");
+        }
+
         log::trace!("Subscribing to chainstate events");
         let mut chainstate_events_rx =
             Self::subscribe_to_chainstate_events(mempool.chainstate_handle())
@@ -92,6 +117,16 @@ impl MempoolSubsystemInterface for MempoolInit {
                 .log_err()
                 .expect("chainstate event subscription");
 
+        // Restored only after subscribing so no tip change in the meantime goes unnoticed
+        if let Some(path) = mempool_path.as_ref().filter(|path| path.exists()) {
+            log::trace!("Restoring mempool transactions from {}", path.display());
+            if let Ok(count) =
+                mempool.load_transactions(path).log_err_pfx("Restoring mempool transactions")
+            {
+                log::info!("Restored {count} mempool transactions");
+            }
+        }
+
         log::trace!("Entering mempool main loop");
         loop {
             tokio::select! {
@@ -100,6 +135,20 @@ impl MempoolSubsystemInterface for MempoolInit {
                 Some(evt) = chainstate_events_rx.recv() => mempool.process_chainstate_event(evt),
             }
         }
+
+        if let Some(path) = mempool_path {
+            log::trace!("Saving mempool transactions to {}", path.display());
+            if let Ok(count) =
+                mempool.save_transactions(&path).log_err_pfx("Saving mempool transactions")
+            {
+                log::info!("Saved {count} mempool transactions");
+            }
+        }
+
+        if let Some(path) = fee_estimates_path {
+            log::trace!("Saving fee estimates to {}", path.display());
+            let _ = mempool.save_fee_estimates(&path).log_err_pfx("Saving fee estimates");
+        }
     }
 }
 
@@ -116,6 +165,13 @@ impl MempoolInterface for Mempool {
         self.get_all()
     }
 
+    fn find_transactions(
+        &self,
+        predicate: &dyn Fn(&Id<Transaction>) -> bool,
+    ) -> Vec<SignedTransaction> {
+        Mempool::find_transactions(self, predicate)
+    }
+
     fn contains_transaction(&self, tx_id: &Id<Transaction>) -> bool {
         self.contains_transaction(tx_id)
     }
@@ -132,6 +188,22 @@ impl MempoolInterface for Mempool {
         self.orphan_transaction(id).cloned()
     }
 
+    fn get_all_tx_info(&self) -> Vec<TxMempoolInfo> {
+        self.get_all_tx_info()
+    }
+
+    fn tx_info(&self, id: &Id<Transaction>) -> Option<TxMempoolInfo> {
+        self.tx_info(id)
+    }
+
+    fn get_all_orphan_info(&self) -> Vec<OrphanTxInfo> {
+        self.get_all_orphan_info()
+    }
+
+    fn tx_status(&self, id: &Id<Transaction>) -> Option<TxStatus> {
+        self.tx_status(id)
+    }
+
     fn best_block_id(&self) -> Id<GenBlock> {
         self.best_block_id()
     }
@@ -147,6 +219,14 @@ impl MempoolInterface for Mempool {
         self.subscribe_to_events(handler);
     }
 
+    fn subscribe_to_events_weak(&mut self, handler: WeakEventHandler<MempoolEvent>) {
+        self.subscribe_to_events_weak(handler);
+    }
+
+    fn announce_restored_transactions(&mut self) {
+        Mempool::announce_restored_transactions(self)
+    }
+
     fn memory_usage(&self) -> usize {
         Mempool::memory_usage(self)
     }
@@ -155,6 +235,10 @@ impl MempoolInterface for Mempool {
         self.max_size()
     }
 
+    fn get_limits(&self) -> MempoolLimits {
+        self.limits()
+    }
+
     fn set_max_size(&mut self, max_size: MempoolMaxSize) -> Result<(), Error> {
         self.set_max_size(max_size)
     }
@@ -162,13 +246,30 @@ impl MempoolInterface for Mempool {
     fn get_fee_rate(&self, in_top_x_mb: usize) -> Result<FeeRate, Error> {
         self.get_fee_rate(in_top_x_mb).map_err(Error::Policy)
     }
+
+    fn estimate_fee_rate(
+        &self,
+        target_blocks: usize,
+        confidence_percent: u8,
+    ) -> Result<Option<FeeRate>, Error> {
+        self.estimate_fee_rate(target_blocks, confidence_percent)
+            .map_err(Error::FeeEstimation)
+    }
 }
 
 /// Mempool constructor
 pub fn make_mempool(
     chain_config: Arc<ChainConfig>,
+    mempool_config: MempoolConfig,
     chainstate_handle: subsystem::Handle<Box<dyn ChainstateInterface>>,
     time_getter: TimeGetter,
+    data_dir: Option<PathBuf>,
 ) -> impl MempoolSubsystemInterface {
-    MempoolInit::new(chain_config, chainstate_handle, time_getter)
+    MempoolInit::new(
+        chain_config,
+        mempool_config,
+        chainstate_handle,
+        time_getter,
+        data_dir,
+    )
 }
@@ -15,7 +15,7 @@
 
 #![deny(clippy::clone_on_ref_ptr)]
 
-pub use config::MempoolMaxSize;
+pub use config::{MempoolConfig, MempoolMaxSize};
 pub use interface::{make_mempool, MempoolInterface, MempoolSubsystemInterface};
 pub use mempool_types::{TxOrigin, TxStatus};
 
@@ -26,8 +26,9 @@ mod interface;
 mod pool;
 pub mod rpc;
 pub mod tx_accumulator;
+pub mod tx_info;
 
-pub use pool::FeeRate;
+pub use pool::{FeeRate, INCREMENTAL_RELAY_FEE_RATE};
 
 pub type MempoolHandle = subsystem::Handle<dyn MempoolInterface>;
 
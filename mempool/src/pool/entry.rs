@@ -149,11 +149,12 @@ impl TxEntry {
 pub struct TxEntryWithFee {
     entry: TxEntry,
     fee: Fee,
+    sigops: usize,
 }
 
 impl TxEntryWithFee {
-    pub fn new(entry: TxEntry, fee: Fee) -> Self {
-        Self { entry, fee }
+    pub fn new(entry: TxEntry, fee: Fee, sigops: usize) -> Self {
+        Self { entry, fee, sigops }
     }
 
     pub fn tx_id(&self) -> &Id<Transaction> {
@@ -172,6 +173,12 @@ impl TxEntryWithFee {
         self.fee
     }
 
+    /// Signature operations counting towards the block limit, see
+    /// [common::chain::ChainConfig::max_block_sigops]
+    pub fn sigops(&self) -> usize {
+        self.sigops
+    }
+
     pub fn into_tx_entry(self) -> TxEntry {
         self.entry
     }
@@ -0,0 +1,271 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Fee rate estimation based on how long it took transactions to get confirmed
+//!
+//! Transactions entering the mempool are sorted into exponentially spaced fee rate buckets.
+//! Once a transaction is included in a block, the number of blocks it had to wait is recorded
+//! for its bucket. Transactions that leave the mempool without being confirmed count as
+//! failures. All the counters decay with each block so recent history weighs more.
+
+use std::{collections::BTreeMap, path::Path};
+
+use common::{
+    chain::Transaction,
+    primitives::{amount::Amount, BlockHeight, Id},
+};
+use serialization::{Decode, DecodeAll, Encode};
+use utils::ensure;
+
+use super::{persist::write_atomically, FeeRate};
+use crate::error::FeeEstimationError;
+
+/// The longest confirmation target (in blocks) estimates are available for
+pub const MAX_CONFIRMATION_TARGET: usize = 48;
+
+/// Lower bound of the lowest fee rate bucket, in atoms per kB
+const MIN_BUCKET_FEE_RATE: u128 = 1_000;
+
+/// Fee rates above this all end up in the last bucket, in atoms per kB
+const MAX_BUCKET_FEE_RATE: u128 = 10_000_000_000_000;
+
+/// Each bucket starts this many percent above the previous one
+const BUCKET_SPACING_PERCENT: u128 = 10;
+
+/// Weight of a single transaction in the decayed counters
+const TX_WEIGHT: u64 = 1_000_000;
+
+/// All the counters are multiplied by `DECAY_NUMERATOR / DECAY_DENOMINATOR` with each block
+const DECAY_NUMERATOR: u64 = 998;
+const DECAY_DENOMINATOR: u64 = 1_000;
+
+/// Minimal (decayed) weight of transactions a range of buckets needs to produce an estimate
+const SUFFICIENT_TX_WEIGHT: u64 = 2 * TX_WEIGHT;
+
+#[derive(thiserror::Error, Debug)]
+pub enum FeeEstimatesFileError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Decoding error: {0}")]
+    Decode(#[from] serialization::Error),
+    #[error("Fee estimates were saved with an incompatible bucket layout")]
+    IncompatibleLayout,
+}
+
+fn bucket_boundaries() -> Vec<u128> {
+    std::iter::successors(Some(MIN_BUCKET_FEE_RATE), |rate| {
+        Some(rate + rate * BUCKET_SPACING_PERCENT / 100).filter(|next| *next <= MAX_BUCKET_FEE_RATE)
+    })
+    .collect()
+}
+
+fn blocks_between(from: BlockHeight, to: BlockHeight) -> usize {
+    let blocks = to.into_int().saturating_sub(from.into_int());
+    usize::try_from(blocks).unwrap_or(usize::MAX)
+}
+
+/// Decayed confirmation statistics, the part of the estimator that is persisted
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+struct FeeStats {
+    /// `confirmed[target - 1][bucket]` is the weight of transactions confirmed within `target` blocks
+    confirmed: Vec<Vec<u64>>,
+    /// Weight of all the transactions that left the mempool, confirmed or not, per bucket
+    total: Vec<u64>,
+}
+
+impl FeeStats {
+    fn new(num_buckets: usize) -> Self {
+        Self {
+            confirmed: vec![vec![0; num_buckets]; MAX_CONFIRMATION_TARGET],
+            total: vec![0; num_buckets],
+        }
+    }
+
+    fn has_layout(&self, num_buckets: usize) -> bool {
+        self.confirmed.len() == MAX_CONFIRMATION_TARGET
+            && self.confirmed.iter().all(|per_bucket| per_bucket.len() == num_buckets)
+            && self.total.len() == num_buckets
+    }
+
+    fn decay(&mut self) {
+        let decay = |weight: &mut u64| {
+            let decayed =
+                u128::from(*weight) * u128::from(DECAY_NUMERATOR) / u128::from(DECAY_DENOMINATOR);
+            *weight = u64::try_from(decayed).expect("decayed weight to be smaller");
+        };
+        self.confirmed.iter_mut().flatten().for_each(decay);
+        self.total.iter_mut().for_each(decay);
+    }
+
+    fn record_confirmed(&mut self, bucket: usize, blocks: usize) {
+        let first_target = blocks.max(1);
+        for per_bucket in self.confirmed.iter_mut().skip(first_target - 1) {
+            per_bucket[bucket] = per_bucket[bucket].saturating_add(TX_WEIGHT);
+        }
+        self.total[bucket] = self.total[bucket].saturating_add(TX_WEIGHT);
+    }
+
+    fn record_failed(&mut self, bucket: usize) {
+        self.total[bucket] = self.total[bucket].saturating_add(TX_WEIGHT);
+    }
+}
+
+/// A transaction waiting in the mempool to be confirmed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct TrackedTx {
+    /// The tip height at the time the transaction entered the mempool
+    height: BlockHeight,
+    bucket: usize,
+}
+
+pub struct FeeEstimator {
+    /// Lower bound of each fee rate bucket in atoms per kB, the last bucket is unbounded
+    buckets: Vec<u128>,
+    stats: FeeStats,
+    tracked: BTreeMap<Id<Transaction>, TrackedTx>,
+    /// Transactions are only tracked once the tip height is known
+    tip_height: Option<BlockHeight>,
+}
+
+impl FeeEstimator {
+    pub fn new() -> Self {
+        let buckets = bucket_boundaries();
+        let stats = FeeStats::new(buckets.len());
+        Self {
+            buckets,
+            stats,
+            tracked: BTreeMap::new(),
+            tip_height: None,
+        }
+    }
+
+    /// Load the statistics previously saved by [FeeEstimator::save]
+    pub fn load(path: &Path) -> Result<Self, FeeEstimatesFileError> {
+        let data = std::fs::read(path)?;
+        let stats = FeeStats::decode_all(&mut data.as_slice())?;
+        let mut estimator = Self::new();
+        ensure!(
+            stats.has_layout(estimator.buckets.len()),
+            FeeEstimatesFileError::IncompatibleLayout
+        );
+        estimator.stats = stats;
+        Ok(estimator)
+    }
+
+    /// Save the statistics. Transactions currently being tracked are not saved.
+    pub fn save(&self, path: &Path) -> Result<(), FeeEstimatesFileError> {
+        write_atomically(path, &self.stats.encode())?;
+        Ok(())
+    }
+
+    fn bucket_index(&self, fee_rate: FeeRate) -> usize {
+        self.buckets
+            .partition_point(|lower_bound| *lower_bound <= fee_rate.atoms_per_kb())
+            .saturating_sub(1)
+    }
+
+    /// Start tracking a transaction that has just entered the mempool
+    pub fn track_transaction(&mut self, tx_id: Id<Transaction>, fee_rate: FeeRate) {
+        if let Some(height) = self.tip_height {
+            let bucket = self.bucket_index(fee_rate);
+            self.tracked.entry(tx_id).or_insert(TrackedTx { height, bucket });
+        }
+    }
+
+    /// Process a newly connected block. Blocks have to be processed in chronological order.
+    pub fn process_block(
+        &mut self,
+        height: BlockHeight,
+        tx_ids: impl IntoIterator<Item = Id<Transaction>>,
+    ) {
+        self.stats.decay();
+        for tx_id in tx_ids {
+            if let Some(tracked) = self.tracked.remove(&tx_id) {
+                let blocks = blocks_between(tracked.height, height);
+                self.stats.record_confirmed(tracked.bucket, blocks);
+            }
+        }
+        self.tip_height = Some(height);
+    }
+
+    /// Stop tracking the transactions that left the mempool without being confirmed
+    pub fn remove_evicted(&mut self, in_mempool: impl Fn(&Id<Transaction>) -> bool) {
+        let stats = &mut self.stats;
+        self.tracked.retain(|tx_id, tracked| {
+            let keep = in_mempool(tx_id);
+            if !keep {
+                stats.record_failed(tracked.bucket);
+            }
+            keep
+        });
+    }
+
+    /// Estimate the lowest fee rate at which at least `confidence_percent` of transactions got
+    /// confirmed within `target_blocks` blocks. Returns `None` if there is not enough data.
+    pub fn estimate_fee_rate(
+        &self,
+        target_blocks: usize,
+        confidence_percent: u8,
+    ) -> Result<Option<FeeRate>, FeeEstimationError> {
+        ensure!(
+            (1..=MAX_CONFIRMATION_TARGET).contains(&target_blocks),
+            FeeEstimationError::InvalidConfirmationTarget(target_blocks, MAX_CONFIRMATION_TARGET)
+        );
+        ensure!(
+            (1..=100).contains(&confidence_percent),
+            FeeEstimationError::InvalidConfidence(confidence_percent)
+        );
+
+        // Transactions still waiting for longer than the target have already failed to meet it
+        let mut waiting_too_long = vec![0u64; self.buckets.len()];
+        if let Some(tip_height) = self.tip_height {
+            for tracked in self.tracked.values() {
+                if blocks_between(tracked.height, tip_height) >= target_blocks {
+                    waiting_too_long[tracked.bucket] =
+                        waiting_too_long[tracked.bucket].saturating_add(TX_WEIGHT);
+                }
+            }
+        }
+
+        // Go from the highest fee rate down, grouping buckets until there is enough data in the
+        // group, and stop at the first group that does not meet the required confidence.
+        let confirmed = &self.stats.confirmed[target_blocks - 1];
+        let mut best_bucket = None;
+        let mut group_confirmed = 0u64;
+        let mut group_total = 0u64;
+        for bucket in (0..self.buckets.len()).rev() {
+            group_confirmed = group_confirmed.saturating_add(confirmed[bucket]);
+            group_total = group_total
+                .saturating_add(self.stats.total[bucket])
+                .saturating_add(waiting_too_long[bucket]);
+
+            if group_total >= SUFFICIENT_TX_WEIGHT {
+                if u128::from(group_confirmed) * 100
+                    < u128::from(group_total) * u128::from(confidence_percent)
+                {
+                    break;
+                }
+                best_bucket = Some(bucket);
+                group_confirmed = 0;
+                group_total = 0;
+            }
+        }
+
+        Ok(best_bucket.map(|bucket| FeeRate::new(Amount::from_atoms(self.buckets[bucket]))))
+    }
+}
+
+#[cfg(test)]
+mod test;
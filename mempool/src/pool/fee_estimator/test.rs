@@ -0,0 +1,256 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use common::primitives::H256;
+use rstest::rstest;
+use test_utils::random::{make_seedable_rng, Rng, Seed};
+
+fn random_tx_id(rng: &mut impl Rng) -> Id<Transaction> {
+    Id::new(H256(rng.gen()))
+}
+
+fn fee_rate(atoms_per_kb: u128) -> FeeRate {
+    FeeRate::new(Amount::from_atoms(atoms_per_kb))
+}
+
+#[test]
+fn invalid_parameters() {
+    let estimator = FeeEstimator::new();
+    assert_eq!(
+        estimator.estimate_fee_rate(0, 50),
+        Err(FeeEstimationError::InvalidConfirmationTarget(
+            0,
+            MAX_CONFIRMATION_TARGET
+        ))
+    );
+    assert_eq!(
+        estimator.estimate_fee_rate(MAX_CONFIRMATION_TARGET + 1, 50),
+        Err(FeeEstimationError::InvalidConfirmationTarget(
+            MAX_CONFIRMATION_TARGET + 1,
+            MAX_CONFIRMATION_TARGET
+        ))
+    );
+    assert_eq!(
+        estimator.estimate_fee_rate(1, 0),
+        Err(FeeEstimationError::InvalidConfidence(0))
+    );
+    assert_eq!(
+        estimator.estimate_fee_rate(1, 101),
+        Err(FeeEstimationError::InvalidConfidence(101))
+    );
+    assert_eq!(estimator.estimate_fee_rate(1, 100), Ok(None));
+}
+
+#[test]
+fn buckets_cover_fee_rates() {
+    let estimator = FeeEstimator::new();
+    assert_eq!(estimator.bucket_index(fee_rate(0)), 0);
+    assert_eq!(estimator.bucket_index(fee_rate(MIN_BUCKET_FEE_RATE)), 0);
+    assert_eq!(
+        estimator.bucket_index(fee_rate(MIN_BUCKET_FEE_RATE * 11 / 10)),
+        1
+    );
+    assert_eq!(
+        estimator.bucket_index(fee_rate(u128::MAX)),
+        estimator.buckets.len() - 1
+    );
+    assert!(estimator.buckets.windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn untracked_before_tip_known(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let mut estimator = FeeEstimator::new();
+
+    let tx_ids: Vec<_> = (0..10).map(|_| random_tx_id(&mut rng)).collect();
+    tx_ids
+        .iter()
+        .for_each(|tx_id| estimator.track_transaction(*tx_id, fee_rate(50_000)));
+    assert!(estimator.tracked.is_empty());
+
+    estimator.process_block(BlockHeight::new(1), tx_ids);
+    assert_eq!(estimator.stats, FeeStats::new(estimator.buckets.len()));
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn estimate_by_confirmation_time(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let mut estimator = FeeEstimator::new();
+    estimator.process_block(BlockHeight::new(100), []);
+
+    let high_rate = fee_rate(rng.gen_range(100_000..1_000_000));
+    let low_rate = fee_rate(rng.gen_range(1_000..10_000));
+
+    for height in 101..=120 {
+        let high_fee_txs: Vec<_> = (0..5).map(|_| random_tx_id(&mut rng)).collect();
+        let low_fee_txs: Vec<_> = (0..5).map(|_| random_tx_id(&mut rng)).collect();
+        high_fee_txs
+            .iter()
+            .for_each(|tx_id| estimator.track_transaction(*tx_id, high_rate));
+        low_fee_txs
+            .iter()
+            .for_each(|tx_id| estimator.track_transaction(*tx_id, low_rate));
+
+        // High fee transactions get into the next block, the low fee ones are evicted
+        estimator.process_block(BlockHeight::new(height), high_fee_txs);
+        estimator.remove_evicted(|tx_id| !low_fee_txs.contains(tx_id));
+    }
+    assert!(estimator.tracked.is_empty());
+
+    let estimate = estimator.estimate_fee_rate(1, 90).unwrap().unwrap();
+    assert!(estimate <= high_rate);
+    assert!(estimate > low_rate);
+    assert_eq!(
+        estimator.bucket_index(estimate),
+        estimator.bucket_index(high_rate)
+    );
+
+    // Longer targets do not help the low fee transactions
+    let estimate = estimator.estimate_fee_rate(MAX_CONFIRMATION_TARGET, 90).unwrap().unwrap();
+    assert!(estimate > low_rate);
+
+    // The low fee bucket never confirms so even a low confidence does not make it pass
+    let estimate = estimator.estimate_fee_rate(1, 10).unwrap().unwrap();
+    assert_eq!(
+        estimator.bucket_index(estimate),
+        estimator.bucket_index(high_rate)
+    );
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn waiting_transactions_count_as_failures(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let mut estimator = FeeEstimator::new();
+    estimator.process_block(BlockHeight::new(1), []);
+
+    let rate = fee_rate(rng.gen_range(1_000..1_000_000));
+    let confirmed_txs: Vec<_> = (0..5).map(|_| random_tx_id(&mut rng)).collect();
+    confirmed_txs.iter().for_each(|tx_id| estimator.track_transaction(*tx_id, rate));
+    estimator.process_block(BlockHeight::new(4), confirmed_txs);
+    assert_eq!(estimator.estimate_fee_rate(2, 80), Ok(None));
+    assert!(estimator.estimate_fee_rate(3, 80).unwrap().is_some());
+
+    // Many transactions at the same fee rate now stuck for 3 blocks
+    (0..20).for_each(|_| estimator.track_transaction(random_tx_id(&mut rng), rate));
+    for height in 5..=7 {
+        estimator.process_block(BlockHeight::new(height), []);
+    }
+    assert_eq!(estimator.estimate_fee_rate(3, 80), Ok(None));
+    assert!(estimator.estimate_fee_rate(4, 20).unwrap().is_some());
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn stats_encoding_roundtrip(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let mut estimator = FeeEstimator::new();
+    estimator.process_block(BlockHeight::new(1), []);
+
+    let txs: Vec<_> = (0..10)
+        .map(|_| {
+            (
+                random_tx_id(&mut rng),
+                fee_rate(rng.gen_range(0..10_000_000)),
+            )
+        })
+        .collect();
+    txs.iter().for_each(|(tx_id, rate)| estimator.track_transaction(*tx_id, *rate));
+    estimator.process_block(
+        BlockHeight::new(rng.gen_range(2..100)),
+        txs.iter().map(|(tx_id, _)| *tx_id),
+    );
+
+    let encoded = estimator.stats.encode();
+    let decoded = FeeStats::decode_all(&mut encoded.as_slice()).unwrap();
+    assert_eq!(decoded, estimator.stats);
+    assert!(decoded.has_layout(estimator.buckets.len()));
+    assert!(!decoded.has_layout(estimator.buckets.len() + 1));
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn save_and_load(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let mut estimator = FeeEstimator::new();
+    estimator.process_block(BlockHeight::new(1), []);
+
+    for height in 2..=rng.gen_range(10..50) {
+        let txs: Vec<_> = (0..rng.gen_range(1..10))
+            .map(|_| {
+                (
+                    random_tx_id(&mut rng),
+                    fee_rate(rng.gen_range(1_000..1_000_000)),
+                )
+            })
+            .collect();
+        txs.iter().for_each(|(tx_id, rate)| estimator.track_transaction(*tx_id, *rate));
+
+        // Some of the transactions get confirmed and the others are evicted
+        let (confirmed, evicted): (Vec<_>, Vec<_>) =
+            txs.into_iter().map(|(tx_id, _)| tx_id).partition(|_| rng.gen_bool(0.7));
+        estimator.process_block(BlockHeight::new(height), confirmed);
+        estimator.remove_evicted(|tx_id| !evicted.contains(tx_id));
+    }
+    assert!(estimator.tracked.is_empty());
+
+    let data_dir = tempfile::TempDir::new().unwrap();
+    let path = data_dir.path().join("fee_estimates.dat");
+    estimator.save(&path).unwrap();
+    let loaded = FeeEstimator::load(&path).unwrap();
+
+    assert_eq!(loaded.stats, estimator.stats);
+    for target_blocks in 1..=MAX_CONFIRMATION_TARGET {
+        for confidence_percent in [10, 50, 80, 95, 100] {
+            assert_eq!(
+                loaded.estimate_fee_rate(target_blocks, confidence_percent),
+                estimator.estimate_fee_rate(target_blocks, confidence_percent)
+            );
+        }
+    }
+}
+
+#[test]
+fn load_invalid_file() {
+    let data_dir = tempfile::TempDir::new().unwrap();
+    let path = data_dir.path().join("fee_estimates.dat");
+
+    assert!(matches!(
+        FeeEstimator::load(&path),
+        Err(FeeEstimatesFileError::Io(_))
+    ));
+
+    // Saved with a different number of buckets
+    let num_buckets = FeeEstimator::new().buckets.len();
+    std::fs::write(&path, FeeStats::new(num_buckets + 1).encode()).unwrap();
+    assert!(matches!(
+        FeeEstimator::load(&path),
+        Err(FeeEstimatesFileError::IncompatibleLayout)
+    ));
+
+    std::fs::write(&path, [1, 2, 3]).unwrap();
+    assert!(matches!(
+        FeeEstimator::load(&path),
+        Err(FeeEstimatesFileError::Decode(_))
+    ));
+}
@@ -19,6 +19,7 @@ use std::{
     mem,
     num::NonZeroUsize,
     ops::Deref,
+    path::Path,
     sync::Arc,
     time::Duration,
 };
@@ -41,25 +42,34 @@ use common::{
 use logging::log;
 use serialization::Encode;
 use utils::{
-    ensure, eventhandler::EventsController, shallow_clone::ShallowClone, tap_error_log::LogError,
+    ensure,
+    eventhandler::{EventsController, WeakEventHandler},
+    shallow_clone::ShallowClone,
+    tap_error_log::LogError,
 };
 
-pub use self::feerate::FeeRate;
+pub use self::feerate::{FeeRate, INCREMENTAL_RELAY_FEE_RATE};
 pub use self::memory_usage_estimator::MemoryUsageEstimator;
 use self::{
     entry::{TxDependency, TxEntry, TxEntryWithFee},
     fee::Fee,
-    feerate::{INCREMENTAL_RELAY_FEE_RATE, INCREMENTAL_RELAY_THRESHOLD},
+    fee_estimator::{FeeEstimatesFileError, FeeEstimator},
+    feerate::INCREMENTAL_RELAY_THRESHOLD,
     orphans::{OrphanType, TxOrphanPool},
+    persist::MempoolFileError,
     rolling_fee_rate::RollingFeeRate,
     spends_unconfirmed::SpendsUnconfirmed,
     store::{Conflicts, MempoolRemovalReason, MempoolStore, TxMempoolEntry},
 };
 use crate::{
     config,
-    error::{Error, MempoolConflictError, MempoolPolicyError, OrphanPoolError, TxValidationError},
+    error::{
+        Error, FeeEstimationError, MempoolConflictError, MempoolPolicyError, OrphanPoolError,
+        TxValidationError,
+    },
     event::{self, MempoolEvent},
     tx_accumulator::TransactionAccumulator,
+    tx_info::{MempoolLimits, OrphanTxInfo, RelativesInfo, TxMempoolInfo},
     TxOrigin, TxStatus,
 };
 
@@ -67,9 +77,11 @@ use crate::config::*;
 
 mod entry;
 pub mod fee;
+mod fee_estimator;
 mod feerate;
 pub mod memory_usage_estimator;
 mod orphans;
+mod persist;
 mod reorg;
 mod rolling_fee_rate;
 mod spends_unconfirmed;
@@ -84,6 +96,7 @@ fn get_relay_fee(tx: &SignedTransaction) -> Result<Fee, MempoolPolicyError> {
 
 pub struct Mempool<M> {
     chain_config: Arc<ChainConfig>,
+    mempool_config: MempoolConfig,
     store: MempoolStore,
     rolling_fee_rate: RwLock<RollingFeeRate>,
     max_size: MempoolMaxSize,
@@ -94,6 +107,9 @@ pub struct Mempool<M> {
     events_controller: EventsController<MempoolEvent>,
     tx_verifier: tx_verifier::TransactionVerifier,
     orphans: TxOrphanPool,
+    fee_estimator: FeeEstimator,
+    /// Local transactions restored from the mempool file that haven't been announced yet
+    restored_local_txs: Vec<Id<Transaction>>,
 }
 
 impl<M> std::fmt::Debug for Mempool<M> {
@@ -105,6 +121,7 @@ impl<M> std::fmt::Debug for Mempool<M> {
 impl<M> Mempool<M> {
     pub fn new(
         chain_config: Arc<ChainConfig>,
+        mempool_config: MempoolConfig,
         chainstate_handle: subsystem::Handle<Box<dyn ChainstateInterface>>,
         clock: TimeGetter,
         memory_usage_estimator: M,
@@ -118,6 +135,7 @@ impl<M> Mempool<M> {
         log::trace!("Creating mempool object");
         Self {
             chain_config,
+            mempool_config,
             store: MempoolStore::new(),
             chainstate_handle,
             max_size: MempoolMaxSize::default(),
@@ -128,6 +146,8 @@ impl<M> Mempool<M> {
             events_controller: Default::default(),
             tx_verifier,
             orphans: TxOrphanPool::new(),
+            fee_estimator: FeeEstimator::new(),
+            restored_local_txs: Vec::new(),
         }
     }
 
@@ -365,6 +385,19 @@ impl<M: MemoryUsageEstimator> Mempool<M> {
 
             let mut tx_verifier = self.tx_verifier.derive_child();
 
+            // The transactions a replacement would evict are disconnected first so that the
+            // replacement is verified against the outputs they spend
+            if self.mempool_config.enable_rbf {
+                for conflict in self.conflicts_with_descendants(&transaction) {
+                    tx_verifier.disconnect_transaction(
+                        &TransactionSource::Mempool,
+                        conflict.transaction(),
+                    )?;
+                }
+            }
+
+            // The inputs have to be counted before they are spent by connecting the transaction
+            let sigops = tx_verifier.count_sigops(transaction.transaction());
             let res = tx_verifier.connect_transaction(
                 &TransactionSourceForConnect::Mempool {
                     current_best: &current_best,
@@ -380,7 +413,7 @@ impl<M: MemoryUsageEstimator> Mempool<M> {
 
             let result = match res {
                 Ok(fee) => {
-                    let transaction = TxEntryWithFee::new(transaction, fee.into());
+                    let transaction = TxEntryWithFee::new(transaction, fee.into(), sigops?);
                     let delta = tx_verifier.consume()?;
                     VerificationOutcome::Valid { transaction, delta }
                 }
@@ -433,10 +466,15 @@ impl<M: MemoryUsageEstimator> Mempool<M> {
         &self,
         entry: &TxEntryWithFee,
     ) -> Result<Conflicts, MempoolPolicyError> {
+        ensure!(
+            entry.sigops() <= self.chain_config.max_block_sigops(),
+            MempoolPolicyError::ExceedsMaxBlockSigOps,
+        );
+
         self.pays_minimum_relay_fees(entry)?;
         self.pays_minimum_mempool_fee(entry)?;
 
-        if ENABLE_RBF {
+        if self.mempool_config.enable_rbf {
             self.rbf_checks(entry)
         } else {
             // Without RBF enabled, any conflicting transaction results in an error
@@ -510,6 +548,16 @@ impl<M: MemoryUsageEstimator> Mempool<M> {
         Ok(())
     }
 
+    /// Transactions conflicting with given entry together with their descendants, leaves first
+    fn conflicts_with_descendants<'a>(&'a self, entry: &'a TxEntry) -> Vec<&'a TxMempoolEntry> {
+        let mut seen = BTreeSet::new();
+        self.conflicting_tx_ids(entry)
+            .map(|id_conflict| self.store.get_entry(id_conflict).expect("entry for id"))
+            .flat_map(|conflict| conflict.depth_postorder_descendants(&self.store))
+            .filter(|descendant| seen.insert(*descendant.tx_id()))
+            .collect()
+    }
+
     fn conflicting_tx_ids<'a>(
         &'a self,
         entry: &'a TxEntry,
@@ -540,9 +588,7 @@ impl<M: MemoryUsageEstimator> Mempool<M> {
             return Ok(());
         }
 
-        if ENABLE_RBF {
-            // Note: Since RBF is currently disabled, the following is effectively dead code and
-            // completely untested. Needs to be reviewed when RBF is re-enabled.
+        if self.mempool_config.enable_rbf {
             let conflicts: Vec<_> = conflicts
                 .map(|id_conflict| self.store.get_entry(id_conflict).expect("entry for id"))
                 .collect();
@@ -655,7 +701,7 @@ impl<M: MemoryUsageEstimator> Mempool<M> {
             unconfirmed && new
         });
         ensure!(
-            spends_new_unconfirmed,
+            !spends_new_unconfirmed,
             MempoolConflictError::SpendsNewUnconfirmed,
         );
         Ok(())
@@ -893,13 +939,21 @@ impl<M: MemoryUsageEstimator> Mempool<M> {
                 conflicts,
                 delta,
             }) => {
-                if ENABLE_RBF {
+                if self.mempool_config.enable_rbf {
                     self.store.drop_conflicts(conflicts);
                 }
                 tx_verifier::flush_to_storage(&mut self.tx_verifier, delta)?;
                 self.finalize_tx(transaction)?;
                 self.store.assert_valid();
 
+                match origin {
+                    TxOrigin::LocalMempool | TxOrigin::LocalP2p | TxOrigin::Peer(_) => {
+                        self.track_for_fee_estimation(&tx_id)
+                    }
+                    // The time the transaction has been waiting for confirmation is unknown
+                    TxOrigin::PastBlock | TxOrigin::Restored => {}
+                }
+
                 let event = event::TransactionProcessed::accepted(tx_id, origin);
                 self.events_controller.broadcast(event.into());
 
@@ -917,6 +971,31 @@ impl<M: MemoryUsageEstimator> Mempool<M> {
         }
     }
 
+    fn track_for_fee_estimation(&mut self, tx_id: &Id<Transaction>) {
+        let fee_rate = self.store.get_entry(tx_id).map(|entry| {
+            let size = NonZeroUsize::new(entry.size()).expect("transaction size is nonzero");
+            FeeRate::from_total_tx_fee(entry.fee(), size)
+        });
+
+        match fee_rate {
+            Some(Ok(fee_rate)) => self.fee_estimator.track_transaction(*tx_id, fee_rate),
+            Some(Err(e)) => log::debug!("Not tracking fee rate of {tx_id:?}: {e}"),
+            None => (),
+        }
+    }
+
+    pub fn find_transactions(
+        &self,
+        predicate: impl Fn(&Id<Transaction>) -> bool,
+    ) -> Vec<SignedTransaction> {
+        self.store
+            .txs_by_id
+            .iter()
+            .filter(|(id, _)| predicate(id))
+            .map(|(_, entry)| entry.transaction().clone())
+            .collect()
+    }
+
     pub fn get_all(&self) -> Vec<SignedTransaction> {
         self.store
             .txs_by_descendant_score
@@ -926,6 +1005,76 @@ impl<M: MemoryUsageEstimator> Mempool<M> {
             .collect()
     }
 
+    fn make_tx_info(entry: &TxMempoolEntry) -> TxMempoolInfo {
+        TxMempoolInfo {
+            tx_id: *entry.tx_id(),
+            origin: entry.tx_entry().origin(),
+            fee: *entry.fee(),
+            size: entry.size(),
+            creation_time: entry.creation_time().as_secs(),
+            parents: entry.parents().copied().collect(),
+            children: entry.children().copied().collect(),
+            with_ancestors: RelativesInfo {
+                count: entry.count_with_ancestors(),
+                size: entry.size_with_ancestors(),
+                fee: *entry.fees_with_ancestors(),
+            },
+            with_descendants: RelativesInfo {
+                count: entry.count_with_descendants(),
+                size: entry.size_with_descendants(),
+                fee: *entry.fees_with_descendants(),
+            },
+        }
+    }
+
+    /// Information about all the transactions in the main mempool, best descendant score first
+    pub fn get_all_tx_info(&self) -> Vec<TxMempoolInfo> {
+        self.store
+            .txs_by_descendant_score
+            .values()
+            .rev()
+            .flat_map(Deref::deref)
+            .map(|id| Self::make_tx_info(self.store.get_entry(id).expect("entry")))
+            .collect()
+    }
+
+    pub fn tx_info(&self, id: &Id<Transaction>) -> Option<TxMempoolInfo> {
+        self.store.get_entry(id).map(Self::make_tx_info)
+    }
+
+    /// Information about all the transactions in the orphan pool
+    pub fn get_all_orphan_info(&self) -> Vec<OrphanTxInfo> {
+        self.orphans
+            .entries()
+            .map(|entry| OrphanTxInfo {
+                tx_id: *entry.tx_id(),
+                origin: entry.origin(),
+                size: entry.size(),
+                creation_time: entry.creation_time().as_secs(),
+            })
+            .collect()
+    }
+
+    /// Whether the transaction is in the main mempool or in the orphan pool
+    pub fn tx_status(&self, id: &Id<Transaction>) -> Option<TxStatus> {
+        if self.contains_transaction(id) {
+            Some(TxStatus::InMempool)
+        } else if self.contains_orphan_transaction(id) {
+            Some(TxStatus::InOrphanPool)
+        } else {
+            None
+        }
+    }
+
+    pub fn limits(&self) -> MempoolLimits {
+        MempoolLimits {
+            max_size: self.max_size.as_bytes(),
+            max_tx_age: self.max_tx_age.as_secs(),
+            orphan_pool_capacity: self.orphans.count_limit(),
+            max_orphan_tx_size: MAX_ORPHAN_TX_SIZE,
+        }
+    }
+
     pub fn collect_txs(
         &self,
         mut tx_accumulator: Box<dyn TransactionAccumulator>,
@@ -953,6 +1102,8 @@ impl<M: MemoryUsageEstimator> Mempool<M> {
         let mut pending = BTreeMap::new();
         // A queue of transactions that can be emitted
         let mut ready = BinaryHeap::<store::TxMempoolEntryByScore<&TxMempoolEntry>>::new();
+        // Signature operations of the transactions placed into the accumulator
+        let mut block_sigops = 0usize;
 
         while !tx_accumulator.done() {
             // Take out the transactions from tx_iter until there is one ready
@@ -979,6 +1130,13 @@ impl<M: MemoryUsageEstimator> Mempool<M> {
                 (None, None) => break,
             };
 
+            // A transaction exceeding the signature operations limit of the block is skipped,
+            // its descendants never become ready
+            let sigops = block_sigops + next_tx.sigops();
+            if sigops > self.chain_config.max_block_sigops() {
+                continue;
+            }
+
             if let Err(err) = tx_accumulator.add_tx(next_tx.transaction().clone(), next_tx.fee()) {
                 log::error!(
                     "CRITICAL: Failed to add transaction {} from mempool. Error: {}",
@@ -989,6 +1147,7 @@ impl<M: MemoryUsageEstimator> Mempool<M> {
             }
 
             emitted.insert(next_tx.tx_id());
+            block_sigops = sigops;
 
             // Release newly ready transactions
             for child in next_tx.children() {
@@ -1014,18 +1173,24 @@ impl<M: MemoryUsageEstimator> Mempool<M> {
         self.events_controller.subscribe_to_events(handler)
     }
 
+    pub fn subscribe_to_events_weak(&mut self, handler: WeakEventHandler<MempoolEvent>) {
+        self.events_controller.subscribe_to_events_weak(handler)
+    }
+
     pub fn process_chainstate_event(&mut self, evt: chainstate::ChainstateEvent) {
         log::info!("mempool: Processing chainstate event {evt:?}");
         match evt {
             chainstate::ChainstateEvent::NewTip(block_id, block_height) => {
                 self.on_new_tip(block_id, block_height);
             }
+            // Transactions from the disconnected blocks are handled once the new tip arrives
+            chainstate::ChainstateEvent::Reorg { .. } => {}
         }
     }
 
     pub fn on_new_tip(&mut self, block_id: Id<Block>, block_height: BlockHeight) {
         log::info!("new tip: block {block_id:?} height {block_height:?}");
-        reorg::handle_new_tip(self, block_id);
+        reorg::handle_new_tip(self, block_id, block_height);
         let event = event::NewTip::new(block_id, block_height);
         self.events_controller.broadcast(event.into());
     }
@@ -1059,6 +1224,55 @@ impl<M: MemoryUsageEstimator> Mempool<M> {
             )
             .map(|feerate| std::cmp::max(feerate, INCREMENTAL_RELAY_FEE_RATE))
     }
+
+    /// Estimate the fee rate for a transaction to get confirmed within `target_blocks` blocks
+    /// with the given confidence, based on the confirmation history of past transactions
+    pub fn estimate_fee_rate(
+        &self,
+        target_blocks: usize,
+        confidence_percent: u8,
+    ) -> Result<Option<FeeRate>, FeeEstimationError> {
+        let estimate = self.fee_estimator.estimate_fee_rate(target_blocks, confidence_percent)?;
+        Ok(estimate.map(|feerate| {
+            let minimum = std::cmp::max(
+                self.rolling_fee_rate.read().rolling_minimum_fee_rate(),
+                INCREMENTAL_RELAY_FEE_RATE,
+            );
+            std::cmp::max(feerate, minimum)
+        }))
+    }
+
+    pub fn load_fee_estimates(&mut self, path: &Path) -> Result<(), FeeEstimatesFileError> {
+        self.fee_estimator = FeeEstimator::load(path)?;
+        Ok(())
+    }
+
+    pub fn save_fee_estimates(&self, path: &Path) -> Result<(), FeeEstimatesFileError> {
+        self.fee_estimator.save(path)
+    }
+
+    /// Save the main mempool transactions, returning how many were saved
+    pub fn save_transactions(&self, path: &Path) -> Result<usize, MempoolFileError> {
+        persist::save(self, path)
+    }
+
+    /// Restore the transactions saved by [Mempool::save_transactions], returning how many of
+    /// them are still valid and got added back
+    pub fn load_transactions(&mut self, path: &Path) -> Result<usize, MempoolFileError> {
+        persist::load(self, path)
+    }
+
+    /// Emit the events of the restored local transactions that are still in the mempool again,
+    /// so the subscribers that started after the mempool (like p2p) can announce them
+    pub fn announce_restored_transactions(&mut self) {
+        for tx_id in std::mem::take(&mut self.restored_local_txs) {
+            if let Some(entry) = self.store.get_entry(&tx_id) {
+                let origin = entry.tx_entry().origin();
+                let event = event::TransactionProcessed::accepted(tx_id, origin);
+                self.events_controller.broadcast(event.into());
+            }
+        }
+    }
 }
 
 #[cfg(test)]
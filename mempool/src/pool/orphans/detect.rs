@@ -55,6 +55,7 @@ impl OrphanType {
             | CTE::InvariantErrorHeaderCouldNotBeLoadedFromHeight(_, _)
             | CTE::BlockIndexCouldNotBeLoaded(_)
             | CTE::FailedToAddAllFeesOfBlock(_)
+            | CTE::TooManySigOpsInBlock(_, _, _)
             | CTE::RewardAdditionError(_)
             | CTE::TimeLockViolation(_)
             | CTE::UtxoError(_)
@@ -63,10 +64,13 @@ impl OrphanType {
             | CTE::TransactionVerifierError(_)
             | CTE::UtxoBlockUndoError(_)
             | CTE::AccountingBlockUndoError(_)
+            | CTE::TokensBlockUndoError(_)
             | CTE::BurnAmountSumError(_)
             | CTE::AttemptToSpendBurnedAmount
             | CTE::PoSAccountingError(_)
             | CTE::MissingPoSAccountingUndo(_)
+            | CTE::MissingTokensUndo(_)
+            | CTE::KeyKindNotActivated(_)
             | CTE::SpendStakeError(_)
             | CTE::InvalidInputTypeInTx
             | CTE::InvalidOutputTypeInTx
@@ -166,6 +166,16 @@ impl TxOrphanPool {
         self.transactions.len()
     }
 
+    /// Maximum number of transactions in the orphan pool
+    pub fn count_limit(&self) -> usize {
+        *self.transaction_count_limit
+    }
+
+    /// Iterate over transaction entries (in arbitrary order)
+    pub fn entries(&self) -> impl Iterator<Item = &TxEntry> {
+        self.transactions.iter()
+    }
+
     /// Convert into transaction entries (in arbitrary order)
     pub fn into_transactions(self) -> impl Iterator<Item = TxEntry> {
         self.transactions.into_iter()
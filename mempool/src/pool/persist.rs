@@ -0,0 +1,148 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Saving the mempool contents on shutdown and restoring them on startup
+
+use std::{io::Write, path::Path, time::Duration};
+
+use common::chain::SignedTransaction;
+use logging::log;
+use serialization::{Decode, DecodeAll, Encode};
+
+use super::{entry::TxEntry, MemoryUsageEstimator, Mempool, TxOrigin, TxStatus};
+
+#[derive(thiserror::Error, Debug)]
+pub enum MempoolFileError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Decoding error: {0}")]
+    Decode(#[from] serialization::Error),
+}
+
+/// Transaction origin as it is saved to disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+enum PersistedTxOrigin {
+    #[codec(index = 0)]
+    LocalMempool,
+    #[codec(index = 1)]
+    LocalP2p,
+    #[codec(index = 2)]
+    PastBlock,
+    #[codec(index = 3)]
+    Peer,
+}
+
+impl From<TxOrigin> for PersistedTxOrigin {
+    fn from(origin: TxOrigin) -> Self {
+        match origin {
+            TxOrigin::LocalMempool => Self::LocalMempool,
+            TxOrigin::LocalP2p => Self::LocalP2p,
+            TxOrigin::PastBlock => Self::PastBlock,
+            TxOrigin::Peer(_) | TxOrigin::Restored => Self::Peer,
+        }
+    }
+}
+
+impl From<PersistedTxOrigin> for TxOrigin {
+    fn from(origin: PersistedTxOrigin) -> Self {
+        match origin {
+            PersistedTxOrigin::LocalMempool => TxOrigin::LocalMempool,
+            PersistedTxOrigin::LocalP2p => TxOrigin::LocalP2p,
+            PersistedTxOrigin::PastBlock => TxOrigin::PastBlock,
+            PersistedTxOrigin::Peer => TxOrigin::Restored,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+struct PersistedTx {
+    transaction: SignedTransaction,
+    /// Time the transaction entered the mempool, in seconds since the UNIX epoch
+    creation_time: u64,
+    origin: PersistedTxOrigin,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+enum PersistedMempool {
+    /// Transactions ordered so that parents always come before their children
+    #[codec(index = 0)]
+    V0(Vec<PersistedTx>),
+}
+
+/// Write the file contents through a temporary file so a crash never leaves it half written
+pub fn write_atomically(path: &Path, data: &[u8]) -> Result<(), std::io::Error> {
+    let tmp_path = path.with_extension("tmp");
+    let mut file = std::fs::File::create(&tmp_path)?;
+    file.write_all(data)?;
+    // The data must reach the disk before the rename, or a crash could leave an empty file
+    file.sync_all()?;
+    std::fs::rename(&tmp_path, path)
+}
+
+/// Save all the main mempool transactions. Returns the number of transactions saved.
+pub fn save<M>(mempool: &Mempool<M>, path: &Path) -> Result<usize, MempoolFileError> {
+    let mut entries: Vec<_> = mempool.store.txs_by_id.values().collect();
+    // Ancestors of a transaction always have fewer ancestors than the transaction itself
+    entries.sort_by_key(|entry| entry.count_with_ancestors());
+
+    let txs: Vec<_> = entries
+        .into_iter()
+        .map(|entry| PersistedTx {
+            transaction: entry.transaction().clone(),
+            creation_time: entry.creation_time().as_secs(),
+            origin: entry.tx_entry().origin().into(),
+        })
+        .collect();
+    let tx_count = txs.len();
+
+    write_atomically(path, &PersistedMempool::V0(txs).encode())?;
+    Ok(tx_count)
+}
+
+/// Load the transactions saved by [save], validating each of them against the current tip.
+/// Returns the number of transactions restored to the main mempool (not counting orphans).
+pub fn load<M: MemoryUsageEstimator>(
+    mempool: &mut Mempool<M>,
+    path: &Path,
+) -> Result<usize, MempoolFileError> {
+    let data = std::fs::read(path)?;
+    let txs = match PersistedMempool::decode_all(&mut data.as_slice())? {
+        PersistedMempool::V0(txs) => txs,
+    };
+
+    let mut restored = 0;
+    for tx in txs {
+        let creation_time = Duration::from_secs(tx.creation_time);
+        let origin = tx.origin.into();
+        let entry = TxEntry::new(tx.transaction, creation_time, origin);
+        let tx_id = *entry.tx_id();
+        match mempool.add_transaction_entry(entry) {
+            Ok(TxStatus::InMempool) => {
+                restored += 1;
+                match origin {
+                    // Nobody has subscribed to the events at the startup yet
+                    TxOrigin::LocalMempool | TxOrigin::LocalP2p => {
+                        mempool.restored_local_txs.push(tx_id)
+                    }
+                    TxOrigin::PastBlock | TxOrigin::Peer(_) | TxOrigin::Restored => {}
+                }
+            }
+            Ok(TxStatus::InOrphanPool) => log::debug!("Transaction {tx_id:?} restored as orphan"),
+            Err(e) => log::debug!("Not restoring transaction {tx_id:?}: {e}"),
+        }
+    }
+
+    Ok(restored)
+}
@@ -20,7 +20,7 @@ use std::collections::BTreeSet;
 use chainstate::chainstate_interface::ChainstateInterface;
 use common::{
     chain::{Block, GenBlock, SignedTransaction},
-    primitives::{Id, Idable},
+    primitives::{BlockHeight, Id, Idable},
 };
 use logging::log;
 use utils::tap_error_log::LogError;
@@ -116,27 +116,52 @@ impl ReorgData {
     }
 }
 
-fn fetch_disconnected_txs<M>(
-    mempool: &Mempool<M>,
-    new_tip: Id<Block>,
-) -> Result<impl Iterator<Item = SignedTransaction>, ReorgError> {
+fn fetch_reorg_data<M>(mempool: &Mempool<M>, new_tip: Id<Block>) -> Result<ReorgData, ReorgError> {
     let old_tip = mempool.tx_verifier.get_best_block_for_utxos().map_err(|_| ReorgError::OldTip)?;
     mempool
         .blocking_chainstate_handle()
         .call(move |c| ReorgData::from_chainstate(c, old_tip, new_tip.into()))?
-        .map(ReorgData::into_disconnected_transactions)
 }
 
-pub fn handle_new_tip<M: MemoryUsageEstimator>(mempool: &mut Mempool<M>, new_tip: Id<Block>) {
+/// Feed the newly connected blocks to the fee estimator, oldest first
+fn record_connected_blocks<M>(
+    mempool: &mut Mempool<M>,
+    reorg_data: &ReorgData,
+    new_tip_height: BlockHeight,
+) {
+    for (depth, block) in reorg_data.connected.iter().enumerate().rev() {
+        let height = u64::try_from(depth)
+            .ok()
+            .and_then(|depth| new_tip_height.into_int().checked_sub(depth))
+            .map(BlockHeight::new);
+        if let Some(height) = height {
+            let tx_ids = block.transactions().iter().map(|tx| tx.transaction().get_id());
+            mempool.fee_estimator.process_block(height, tx_ids);
+        }
+    }
+}
+
+pub fn handle_new_tip<M: MemoryUsageEstimator>(
+    mempool: &mut Mempool<M>,
+    new_tip: Id<Block>,
+    new_tip_height: BlockHeight,
+) {
     mempool.rolling_fee_rate.get_mut().set_block_since_last_rolling_fee_bump(true);
 
-    let disconnected_txs = fetch_disconnected_txs(mempool, new_tip)
+    let reorg_data = fetch_reorg_data(mempool, new_tip)
         .log_err_pfx("Fetching disconnected transactions on a reorg");
 
-    match disconnected_txs {
-        Ok(to_insert) => refresh_mempool(mempool, to_insert),
+    match reorg_data {
+        Ok(reorg_data) => {
+            record_connected_blocks(mempool, &reorg_data, new_tip_height);
+            refresh_mempool(mempool, reorg_data.into_disconnected_transactions())
+        }
         Err(_) => refresh_mempool(mempool, std::iter::empty()),
     }
+
+    // Whatever was tracked and is not in the mempool any more has not made it into a block
+    let store = &mempool.store;
+    mempool.fee_estimator.remove_evicted(|tx_id| store.contains(tx_id));
 }
 
 pub fn refresh_mempool<M: MemoryUsageEstimator>(
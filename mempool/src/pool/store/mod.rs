@@ -578,6 +578,7 @@ impl Drop for MempoolStore {
 pub struct TxMempoolEntry {
     entry: TxEntry,
     fee: Fee,
+    sigops: usize,
     parents: BTreeSet<Id<Transaction>>,
     children: BTreeSet<Id<Transaction>>,
     count_with_descendants: usize,
@@ -595,6 +596,7 @@ impl TxMempoolEntry {
         ancestors: BTreeSet<TxMempoolEntry>,
     ) -> Result<TxMempoolEntry, MempoolPolicyError> {
         let fee = entry.fee();
+        let sigops = entry.sigops();
         let entry = entry.into_tx_entry();
         let size = entry.size();
         let size_with_ancestors: usize =
@@ -612,6 +614,7 @@ impl TxMempoolEntry {
             size_with_descendants: size,
             entry,
             fee,
+            sigops,
             parents,
             children: BTreeSet::default(),
             count_with_descendants: 1,
@@ -629,7 +632,7 @@ impl TxMempoolEntry {
         creation_time: Time,
     ) -> Result<TxMempoolEntry, MempoolPolicyError> {
         let entry = TxEntry::new(tx, creation_time, crate::TxOrigin::LocalMempool);
-        Self::new(TxEntryWithFee::new(entry, fee), parents, ancestors)
+        Self::new(TxEntryWithFee::new(entry, fee, 0), parents, ancestors)
     }
 
     pub fn transaction(&self) -> &SignedTransaction {
@@ -640,20 +643,34 @@ impl TxMempoolEntry {
         self.fee
     }
 
+    pub fn sigops(&self) -> usize {
+        self.sigops
+    }
+
     pub fn count_with_descendants(&self) -> usize {
         self.count_with_descendants
     }
 
-    #[cfg(test)]
+    pub fn count_with_ancestors(&self) -> usize {
+        self.count_with_ancestors
+    }
+
     pub fn fees_with_descendants(&self) -> Fee {
         self.fees_with_descendants
     }
 
-    #[cfg(test)]
     pub fn fees_with_ancestors(&self) -> Fee {
         self.fees_with_ancestors
     }
 
+    pub fn size_with_descendants(&self) -> usize {
+        self.size_with_descendants
+    }
+
+    pub fn size_with_ancestors(&self) -> usize {
+        self.size_with_ancestors
+    }
+
     pub fn descendant_score(&self) -> DescendantScore {
         let a: Fee = (*self.fees_with_descendants
             / u128::try_from(self.size_with_descendants).expect("conversion"))
@@ -51,6 +51,7 @@ async fn descendant_of_expired_entry(#[case] seed: Seed) -> anyhow::Result<()> {
     let chainstate = tf.chainstate();
     let mut mempool = Mempool::new(
         Arc::clone(chainstate.get_chain_config()),
+        Default::default(),
         start_chainstate(chainstate).await,
         mock_clock,
         StoreMemoryUsageEstimator,
@@ -111,6 +112,7 @@ async fn only_expired_entries_removed(#[case] seed: Seed) -> anyhow::Result<()>
 
     let mut mempool = Mempool::new(
         config,
+        Default::default(),
         chainstate_interface,
         mock_clock,
         StoreMemoryUsageEstimator,
@@ -35,6 +35,7 @@ use std::sync::Arc;
 mod accumulator;
 mod expiry;
 mod orphans;
+mod persist;
 mod reorg;
 mod replacement;
 mod utils;
@@ -118,6 +119,29 @@ async fn add_single_tx() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn find_transactions() -> anyhow::Result<()> {
+    let mut mempool = setup().await;
+
+    let outpoint_source_id = mempool.chain_config.genesis_block_id().into();
+    let input = TxInput::from_utxo(outpoint_source_id, 0);
+    let relay_fee: Fee = Amount::from_atoms(get_relay_fee_from_tx_size(TX_SPEND_INPUT_SIZE)).into();
+    let tx = tx_spend_input(
+        &mempool,
+        input,
+        InputWitness::NoSignature(Some(DUMMY_WITNESS_MSG.to_vec())),
+        relay_fee,
+        0,
+    )
+    .await?;
+    let tx_id = tx.transaction().get_id();
+    mempool.add_transaction(tx.clone(), TxOrigin::TEST)?.assert_in_mempool();
+
+    assert_eq!(mempool.find_transactions(|id| *id == tx_id), vec![tx]);
+    assert!(mempool.find_transactions(|id| *id != tx_id).is_empty());
+    Ok(())
+}
+
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy())]
@@ -205,6 +229,7 @@ async fn setup() -> Mempool<StoreMemoryUsageEstimator> {
     let chainstate_interface = start_chainstate_with_config(Arc::clone(&config)).await;
     Mempool::new(
         config,
+        Default::default(),
         chainstate_interface,
         Default::default(),
         StoreMemoryUsageEstimator,
@@ -213,12 +238,20 @@ async fn setup() -> Mempool<StoreMemoryUsageEstimator> {
 
 async fn setup_with_chainstate(
     chainstate: Box<dyn ChainstateInterface>,
+) -> Mempool<StoreMemoryUsageEstimator> {
+    setup_with_chainstate_and_config(chainstate, MempoolConfig::default()).await
+}
+
+async fn setup_with_chainstate_and_config(
+    chainstate: Box<dyn ChainstateInterface>,
+    mempool_config: MempoolConfig,
 ) -> Mempool<StoreMemoryUsageEstimator> {
     logging::init_logging::<&str>(None);
     let config = Arc::new(common::chain::config::create_unit_test_config());
     let chainstate_handle = start_chainstate(chainstate).await;
     Mempool::new(
         config,
+        mempool_config,
         chainstate_handle,
         Default::default(),
         StoreMemoryUsageEstimator,
@@ -479,7 +512,10 @@ async fn tx_spend_several_inputs<M>(
 #[trace]
 #[case(Seed::from_entropy())]
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
-async fn one_ancestor_replaceability_signal_is_enough(#[case] seed: Seed) -> anyhow::Result<()> {
+async fn one_ancestor_replaceability_signal_is_enough(
+    #[case] seed: Seed,
+    #[values(false, true)] enable_rbf: bool,
+) -> anyhow::Result<()> {
     let mut rng = make_seedable_rng(seed);
     let tf = TestFramework::builder(&mut rng).build();
     let genesis = tf.genesis();
@@ -497,7 +533,8 @@ async fn one_ancestor_replaceability_signal_is_enough(#[case] seed: Seed) -> any
     }
     let tx = tx_builder.build();
 
-    let mut mempool = setup_with_chainstate(tf.chainstate()).await;
+    let mempool_config = MempoolConfig { enable_rbf };
+    let mut mempool = setup_with_chainstate_and_config(tf.chainstate(), mempool_config).await;
     mempool.add_transaction(tx.clone(), TxOrigin::TEST)?.assert_in_mempool();
 
     let flags_replaceable = 1;
@@ -571,7 +608,7 @@ async fn one_ancestor_replaceability_signal_is_enough(#[case] seed: Seed) -> any
     .expect("invalid witness count");
 
     let result = mempool.add_transaction(replacing_tx, TxOrigin::TEST);
-    if ENABLE_RBF {
+    if enable_rbf {
         assert_eq!(result, Ok(TxStatus::InMempool));
         assert!(!mempool.contains_transaction(&replaced_tx_id));
     } else {
@@ -716,6 +753,132 @@ async fn tx_mempool_entry() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn tx_info(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let tf = TestFramework::builder(&mut rng).build();
+    let genesis_id = tf.genesis().get_id();
+    let transfer = |atoms| {
+        TxOutput::Transfer(
+            OutputValue::Coin(Amount::from_atoms(atoms)),
+            Destination::AnyoneCanSpend,
+        )
+    };
+
+    // tx2 spends from both tx0 and tx1, which spends from tx0
+    let tx0 = TransactionBuilder::new()
+        .add_input(
+            TxInput::from_utxo(genesis_id.into(), 0),
+            empty_witness(&mut rng),
+        )
+        .add_output(transfer(100_000_000))
+        .add_output(transfer(100_000_000))
+        .build();
+    let tx0_id = tx0.transaction().get_id();
+    let tx1 = TransactionBuilder::new()
+        .add_input(
+            TxInput::from_utxo(tx0_id.into(), 0),
+            empty_witness(&mut rng),
+        )
+        .add_output(transfer(90_000_000))
+        .build();
+    let tx1_id = tx1.transaction().get_id();
+    let tx2 = TransactionBuilder::new()
+        .add_input(
+            TxInput::from_utxo(tx0_id.into(), 1),
+            empty_witness(&mut rng),
+        )
+        .add_input(
+            TxInput::from_utxo(tx1_id.into(), 0),
+            empty_witness(&mut rng),
+        )
+        .add_output(transfer(180_000_000))
+        .build();
+    let tx2_id = tx2.transaction().get_id();
+    let sizes = [tx0.encoded_size(), tx1.encoded_size(), tx2.encoded_size()];
+
+    let mut mempool = setup_with_chainstate(tf.chainstate()).await;
+    assert_eq!(mempool.tx_status(&tx0_id), None);
+    assert_eq!(mempool.tx_info(&tx0_id), None);
+
+    for tx in [tx0, tx1, tx2] {
+        assert_eq!(
+            mempool.add_transaction(tx, TxOrigin::TEST),
+            Ok(TxStatus::InMempool)
+        );
+    }
+
+    let info0 = mempool.tx_info(&tx0_id).expect("info");
+    let info1 = mempool.tx_info(&tx1_id).expect("info");
+    let info2 = mempool.tx_info(&tx2_id).expect("info");
+
+    assert_eq!(info1.tx_id, tx1_id);
+    assert_eq!(info1.origin, TxOrigin::TEST);
+    assert_eq!(info1.fee, Amount::from_atoms(10_000_000));
+    assert_eq!(info1.size, sizes[1]);
+    assert_eq!(info1.parents, vec![tx0_id]);
+    assert_eq!(info1.children, vec![tx2_id]);
+    assert_eq!(info1.with_ancestors.count, 2);
+    assert_eq!(info1.with_ancestors.size, sizes[0] + sizes[1]);
+    assert_eq!(
+        Some(info1.with_ancestors.fee),
+        info0.fee + Amount::from_atoms(10_000_000)
+    );
+    assert_eq!(info1.with_descendants.count, 2);
+    assert_eq!(info1.with_descendants.size, sizes[1] + sizes[2]);
+    assert_eq!(info1.with_descendants.fee, Amount::from_atoms(20_000_000));
+
+    assert!(info0.parents.is_empty());
+    assert_eq!(
+        info0.children.iter().collect::<BTreeSet<_>>(),
+        [tx1_id, tx2_id].iter().collect::<BTreeSet<_>>()
+    );
+    assert_eq!(info0.with_ancestors.count, 1);
+    assert_eq!(info0.with_descendants.count, 3);
+    assert_eq!(info0.with_descendants.size, sizes.iter().sum::<usize>());
+    assert_eq!(
+        Some(info0.with_descendants.fee),
+        info0.fee + Amount::from_atoms(20_000_000)
+    );
+
+    assert_eq!(
+        info2.parents.iter().collect::<BTreeSet<_>>(),
+        [tx0_id, tx1_id].iter().collect::<BTreeSet<_>>()
+    );
+    assert!(info2.children.is_empty());
+    assert_eq!(info2.with_ancestors.count, 3);
+    assert_eq!(info2.with_descendants.count, 1);
+
+    let all_info = mempool.get_all_tx_info();
+    assert_eq!(all_info.len(), 3);
+    for info in [info0, info1, info2] {
+        assert!(all_info.contains(&info));
+        assert_eq!(mempool.tx_status(&info.tx_id), Some(TxStatus::InMempool));
+    }
+    assert!(mempool.get_all_orphan_info().is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn limits() -> anyhow::Result<()> {
+    let mut mempool = setup().await;
+    assert_eq!(
+        mempool.limits(),
+        MempoolLimits {
+            max_size: MempoolMaxSize::default().as_bytes(),
+            max_tx_age: config::DEFAULT_MEMPOOL_EXPIRY.as_secs(),
+            orphan_pool_capacity: config::DEFAULT_ORPHAN_POOL_CAPACITY,
+            max_orphan_tx_size: config::MAX_ORPHAN_TX_SIZE,
+        }
+    );
+
+    mempool.set_max_size(MempoolMaxSize::from_bytes(1_000_000))?;
+    assert_eq!(mempool.limits().max_size, 1_000_000);
+    Ok(())
+}
+
 async fn test_bip125_max_replacements(
     seed: Seed,
     num_potential_replacements: usize,
@@ -922,6 +1085,7 @@ async fn rolling_fee(#[case] seed: Seed) -> anyhow::Result<()> {
     log::debug!("before adding parent");
     let mut mempool = Mempool::new(
         Arc::clone(&config),
+        Default::default(),
         chainstate_interface,
         mock_clock,
         mock_usage,
@@ -1526,7 +1690,13 @@ async fn mempool_full_mock(#[case] seed: Seed) -> anyhow::Result<()> {
     let config = Arc::clone(chainstate.get_chain_config());
     let chainstate_handle = start_chainstate(chainstate).await;
 
-    let mut mempool = Mempool::new(config, chainstate_handle, Default::default(), mock_usage);
+    let mut mempool = Mempool::new(
+        config,
+        Default::default(),
+        chainstate_handle,
+        Default::default(),
+        mock_usage,
+    );
 
     let tx = TransactionBuilder::new()
         .add_input(
@@ -84,6 +84,68 @@ async fn two_transactions_in_sequence(
     assert_eq!(mempool.contains_transaction(&tx1_id), expected_in_mempool);
 }
 
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn orphan_info(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let tf = TestFramework::builder(&mut rng).build();
+    let genesis_id = tf.genesis().get_id();
+
+    let tx0 = TransactionBuilder::new()
+        .add_input(
+            TxInput::from_utxo(genesis_id.into(), 0),
+            empty_witness(&mut rng),
+        )
+        .add_output(TxOutput::Transfer(
+            OutputValue::Coin(Amount::from_atoms(100_000_000)),
+            Destination::AnyoneCanSpend,
+        ))
+        .build();
+    let tx0_id = tx0.transaction().get_id();
+
+    let tx1 = TransactionBuilder::new()
+        .add_input(
+            TxInput::from_utxo(tx0_id.into(), 0),
+            empty_witness(&mut rng),
+        )
+        .add_output(TxOutput::Transfer(
+            OutputValue::Coin(Amount::from_atoms(90_000_000)),
+            Destination::AnyoneCanSpend,
+        ))
+        .build();
+    let tx1_id = tx1.transaction().get_id();
+    let tx1_size = tx1.encoded_size();
+
+    let mut mempool = setup_with_chainstate(tf.chainstate()).await;
+    assert!(mempool.get_all_orphan_info().is_empty());
+    assert_eq!(mempool.tx_status(&tx1_id), None);
+
+    let res = mempool.add_transaction(tx1, TxOrigin::LocalP2p);
+    assert_eq!(res, Ok(TxStatus::InOrphanPool));
+    assert_eq!(mempool.tx_status(&tx1_id), Some(TxStatus::InOrphanPool));
+    assert_eq!(mempool.tx_status(&tx0_id), None);
+    assert_eq!(mempool.tx_info(&tx1_id), None);
+    assert!(mempool.get_all_tx_info().is_empty());
+
+    let orphan_info = mempool.get_all_orphan_info();
+    assert_eq!(orphan_info.len(), 1);
+    assert_eq!(orphan_info[0].tx_id, tx1_id);
+    assert_eq!(orphan_info[0].origin, TxOrigin::LocalP2p);
+    assert_eq!(orphan_info[0].size, tx1_size);
+
+    // Once the parent arrives, the orphan is moved to the main mempool
+    let res = mempool.add_transaction(tx0, TxOrigin::TEST);
+    assert_eq!(res, Ok(TxStatus::InMempool));
+    assert_eq!(mempool.tx_status(&tx1_id), Some(TxStatus::InMempool));
+    assert!(mempool.get_all_orphan_info().is_empty());
+    assert_eq!(
+        mempool.tx_info(&tx1_id).map(|info| info.origin),
+        Some(TxOrigin::LocalP2p)
+    );
+}
+
 // Below, each test case encodes a sequence of transaction insertions.
 //
 // Each element of the Vec contains:
@@ -0,0 +1,110 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common::chain::tokens::OutputValue;
+
+use super::*;
+use ::utils::atomics::SeqCstAtomicU64;
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn restore_after_restart(#[case] seed: Seed) -> anyhow::Result<()> {
+    let mock_time = Arc::new(SeqCstAtomicU64::new(1_000));
+    let mock_clock = mocked_time_getter_seconds(Arc::clone(&mock_time));
+    logging::init_logging::<&str>(None);
+
+    let mut rng = make_seedable_rng(seed);
+    let tf = TestFramework::builder(&mut rng).build();
+    let genesis = tf.genesis();
+
+    let parent = TransactionBuilder::new()
+        .add_input(
+            TxInput::from_utxo(OutPointSourceId::BlockReward(genesis.get_id().into()), 0),
+            empty_witness(&mut rng),
+        )
+        .add_output(TxOutput::Transfer(
+            OutputValue::Coin(Amount::from_atoms(999_999_999_000)),
+            anyonecanspend_address(),
+        ))
+        .build();
+    let parent_id = parent.transaction().get_id();
+
+    let chainstate = tf.chainstate();
+    let chain_config = Arc::clone(chainstate.get_chain_config());
+    let chainstate_handle = start_chainstate(chainstate).await;
+    let mut mempool = Mempool::new(
+        Arc::clone(&chain_config),
+        Default::default(),
+        chainstate_handle.clone(),
+        mock_clock.clone(),
+        StoreMemoryUsageEstimator,
+    );
+    mempool.add_transaction(parent, TxOrigin::LocalP2p)?.assert_in_mempool();
+
+    let child = tx_spend_input(
+        &mempool,
+        TxInput::from_utxo(OutPointSourceId::Transaction(parent_id), 0),
+        InputWitness::NoSignature(Some(DUMMY_WITNESS_MSG.to_vec())),
+        None,
+        0,
+    )
+    .await?;
+    let child_id = child.transaction().get_id();
+    mock_time.store(1_500);
+    mempool.add_transaction(child, TxOrigin::LocalMempool)?.assert_in_mempool();
+
+    let data_dir = tempfile::TempDir::new()?;
+    let path = data_dir.path().join("mempool.dat");
+    assert_eq!(mempool.save_transactions(&path)?, 2);
+    let tx_info = mempool.get_all_tx_info();
+
+    // Restart later, the transactions keep their original entry times and origins
+    mock_time.store(2_000);
+    let mut restarted = Mempool::new(
+        chain_config,
+        Default::default(),
+        chainstate_handle,
+        mock_clock,
+        StoreMemoryUsageEstimator,
+    );
+    assert_eq!(restarted.load_transactions(&path)?, 2);
+    assert!(restarted.contains_transaction(&parent_id));
+    assert!(restarted.contains_transaction(&child_id));
+    assert_eq!(restarted.get_all_tx_info(), tx_info);
+    restarted.store.assert_valid();
+
+    // Only the local transactions are announced, once the subscribers are in place
+    let processed = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let processed_ = Arc::clone(&processed);
+    restarted.subscribe_to_events(Arc::new(move |event| match event {
+        MempoolEvent::TransactionProcessed(tpe) => {
+            assert!(tpe.result().is_ok());
+            processed_.lock().unwrap().push((*tpe.tx_id(), tpe.origin()));
+        }
+        MempoolEvent::NewTip(_) => {}
+    }));
+    restarted.announce_restored_transactions();
+    restarted.announce_restored_transactions();
+    restarted.events_controller.wait_for_all_events();
+    let mut processed = processed.lock().unwrap().clone();
+    processed.sort();
+    let mut expected = vec![(parent_id, TxOrigin::LocalP2p), (child_id, TxOrigin::LocalMempool)];
+    expected.sort();
+    assert_eq!(processed, expected);
+
+    Ok(())
+}
@@ -18,6 +18,12 @@ use crypto::random::{CryptoRng, Rng};
 
 use super::*;
 
+async fn setup_with_rbf(
+    chainstate: Box<dyn ChainstateInterface>,
+) -> Mempool<StoreMemoryUsageEstimator> {
+    setup_with_chainstate_and_config(chainstate, MempoolConfig { enable_rbf: true }).await
+}
+
 async fn test_replace_tx(
     rng: &mut (impl Rng + CryptoRng),
     original_fee: Fee,
@@ -36,7 +42,7 @@ async fn test_replace_tx(
     let input = TxInput::from_utxo(outpoint_source_id, 0);
     let flags = 1;
 
-    let mut mempool = setup_with_chainstate(tf.chainstate()).await;
+    let mut mempool = setup_with_rbf(tf.chainstate()).await;
     let original = tx_spend_input(
         &mempool,
         input.clone(),
@@ -78,7 +84,6 @@ async fn test_replace_tx(
 #[trace]
 #[case(Seed::from_entropy())]
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
-#[ignore = "RBF not implemented"]
 async fn try_replace_irreplaceable(#[case] seed: Seed) -> anyhow::Result<()> {
     let mut rng = make_seedable_rng(seed);
     let tf = TestFramework::builder(&mut rng).build();
@@ -89,7 +94,7 @@ async fn try_replace_irreplaceable(#[case] seed: Seed) -> anyhow::Result<()> {
     let flags = 0;
     let original_fee: Fee =
         Amount::from_atoms(get_relay_fee_from_tx_size(TX_SPEND_INPUT_SIZE)).into();
-    let mut mempool = setup_with_chainstate(tf.chainstate()).await;
+    let mut mempool = setup_with_rbf(tf.chainstate()).await;
     let original = tx_spend_input(
         &mempool,
         input.clone(),
@@ -118,7 +123,7 @@ async fn try_replace_irreplaceable(#[case] seed: Seed) -> anyhow::Result<()> {
         Err(MempoolPolicyError::from(MempoolConflictError::Irreplacable).into())
     );
 
-    mempool.store.remove_tx(&original_id, MempoolRemovalReason::Block);
+    mempool.remove_tx_and_descendants(&original_id, MempoolRemovalReason::Block);
     mempool.add_transaction(replacement, TxOrigin::TEST)?.assert_in_mempool();
     mempool.store.assert_valid();
 
@@ -129,7 +134,6 @@ async fn try_replace_irreplaceable(#[case] seed: Seed) -> anyhow::Result<()> {
 #[trace]
 #[case(Seed::from_entropy())]
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
-#[ignore = "RBF not implemented"]
 async fn tx_replace(#[case] seed: Seed) -> anyhow::Result<()> {
     let mut rng = make_seedable_rng(seed);
     let relay_fee = get_relay_fee_from_tx_size(TX_SPEND_INPUT_SIZE);
@@ -173,7 +177,6 @@ async fn tx_replace(#[case] seed: Seed) -> anyhow::Result<()> {
 #[trace]
 #[case(Seed::from_entropy())]
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
-#[ignore = "RBF not implemented"]
 async fn tx_replace_child(#[case] seed: Seed) -> anyhow::Result<()> {
     let mut rng = make_seedable_rng(seed);
     let tf = TestFramework::builder(&mut rng).build();
@@ -189,7 +192,7 @@ async fn tx_replace_child(#[case] seed: Seed) -> anyhow::Result<()> {
         ))
         .with_flags(1)
         .build();
-    let mut mempool = setup_with_chainstate(tf.chainstate()).await;
+    let mut mempool = setup_with_rbf(tf.chainstate()).await;
     mempool.add_transaction(tx.clone(), TxOrigin::TEST)?.assert_in_mempool();
 
     let outpoint_source_id = OutPointSourceId::Transaction(tx.transaction().get_id());
@@ -226,7 +229,6 @@ async fn tx_replace_child(#[case] seed: Seed) -> anyhow::Result<()> {
 #[trace]
 #[case(Seed::from_entropy())]
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
-#[ignore = "RBF not implemented"]
 async fn pays_more_than_conflicts_with_descendants(#[case] seed: Seed) -> anyhow::Result<()> {
     let mut rng = make_seedable_rng(seed);
     let tf = TestFramework::builder(&mut rng).build();
@@ -242,7 +244,7 @@ async fn pays_more_than_conflicts_with_descendants(#[case] seed: Seed) -> anyhow
         ))
         .with_flags(1)
         .build();
-    let mut mempool = setup_with_chainstate(tf.chainstate()).await;
+    let mut mempool = setup_with_rbf(tf.chainstate()).await;
     let tx_id = tx.transaction().get_id();
     mempool.add_transaction(tx, TxOrigin::TEST)?.assert_in_mempool();
 
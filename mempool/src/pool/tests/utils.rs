@@ -254,6 +254,6 @@ pub fn generate_transaction_graph(
         );
 
         let entry = TxEntry::new(tx, time, TxOrigin::TEST);
-        TxEntryWithFee::new(entry, Fee::new(Amount::from_atoms(total)))
+        TxEntryWithFee::new(entry, Fee::new(Amount::from_atoms(total)), 0)
     })
 }
@@ -25,7 +25,7 @@ use chainstate::{
 use chainstate_types::storage_result;
 use common::{
     chain::{
-        tokens::{TokenAuxiliaryData, TokenId},
+        tokens::{TokenAuxiliaryData, TokenId, TokensBlockUndo},
         AccountNonce, AccountType, Block, DelegationId, GenBlock, OutPointSourceId, PoolId,
         Transaction, TxMainChainIndex, UtxoOutPoint,
     },
@@ -226,6 +226,10 @@ impl TransactionVerifierStorageRef for ChainstateHandle {
         panic!("Mempool should not undo stuff in chainstate")
     }
 
+    fn get_tokens_undo(&self, _id: Id<Block>) -> Result<Option<TokensBlockUndo>, Error> {
+        panic!("Mempool should not undo stuff in chainstate")
+    }
+
     fn get_account_nonce_count(&self, account: AccountType) -> Result<Option<AccountNonce>, Error> {
         self.call(move |c| c.get_account_nonce_count(account))
     }
@@ -15,6 +15,8 @@
 
 //! Mempool subsystem RPC handler
 
+use std::sync::Arc;
+
 use common::{
     chain::{GenBlock, SignedTransaction, Transaction},
     primitives::Id,
@@ -22,9 +24,16 @@ use common::{
 use serialization::hex_encoded::HexEncoded;
 use utils::tap_error_log::LogError;
 
-use crate::{FeeRate, MempoolMaxSize, TxOrigin, TxStatus};
+use crate::{
+    event::MempoolEvent,
+    tx_info::{MempoolLimits, OrphanTxInfo, TxMempoolInfo},
+    FeeRate, MempoolMaxSize, TxOrigin, TxStatus,
+};
 
-use rpc::Result as RpcResult;
+use rpc::{
+    subscription::{PendingSubscriptionSink, SubscriptionResult},
+    Result as RpcResult,
+};
 
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct GetTxResponse {
@@ -33,6 +42,15 @@ pub struct GetTxResponse {
     transaction: HexEncoded<SignedTransaction>,
 }
 
+/// Outcome of a transaction validation as it is sent to RPC subscribers
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RpcTransactionProcessed {
+    pub tx_id: Id<Transaction>,
+    pub origin: TxOrigin,
+    /// The reason the transaction was rejected, `None` if it was accepted
+    pub error: Option<String>,
+}
+
 #[rpc::rpc(server, client, namespace = "mempool")]
 trait MempoolRpc {
     #[method(name = "contains_tx")]
@@ -48,6 +66,23 @@ trait MempoolRpc {
     #[method(name = "transactions")]
     async fn get_all_transactions(&self) -> RpcResult<Vec<HexEncoded<SignedTransaction>>>;
 
+    /// Get fee, size and ancestor/descendant information of all main mempool transactions,
+    /// best descendant score first
+    #[method(name = "transactions_info")]
+    async fn get_all_tx_info(&self) -> RpcResult<Vec<TxMempoolInfo>>;
+
+    /// Get fee, size and ancestor/descendant information of a main mempool transaction
+    #[method(name = "transaction_info")]
+    async fn tx_info(&self, tx_id: Id<Transaction>) -> RpcResult<Option<TxMempoolInfo>>;
+
+    /// Get information about all transactions in the orphan pool
+    #[method(name = "orphans_info")]
+    async fn get_all_orphan_info(&self) -> RpcResult<Vec<OrphanTxInfo>>;
+
+    /// Check whether a transaction is in the main mempool, in the orphan pool or neither
+    #[method(name = "transaction_status")]
+    async fn tx_status(&self, tx_id: Id<Transaction>) -> RpcResult<Option<TxStatus>>;
+
     #[method(name = "submit_transaction")]
     async fn submit_transaction(&self, tx: HexEncoded<SignedTransaction>) -> RpcResult<TxStatus>;
 
@@ -60,6 +95,10 @@ trait MempoolRpc {
     #[method(name = "get_max_size")]
     async fn get_max_size(&self) -> RpcResult<usize>;
 
+    /// Get the limits the mempool currently enforces
+    #[method(name = "limits")]
+    async fn get_limits(&self) -> RpcResult<MempoolLimits>;
+
     // TODO: We should accept more convenient ways of setting the size in addition to plain byte
     // count, e.g. "200MB" instead of 200000000
     #[method(name = "set_max_size")]
@@ -67,6 +106,23 @@ trait MempoolRpc {
 
     #[method(name = "get_fee_rate")]
     async fn get_fee_rate(&self, in_top_x_mb: usize) -> RpcResult<FeeRate>;
+
+    /// Estimate the fee rate needed to get a transaction confirmed within the given number of
+    /// blocks with the given confidence (in percent), based on past confirmation times
+    #[method(name = "estimate_fee_rate")]
+    async fn estimate_fee_rate(
+        &self,
+        target_blocks: usize,
+        confidence_percent: u8,
+    ) -> RpcResult<Option<FeeRate>>;
+
+    /// Subscribe to the results of transaction validation (WebSocket only)
+    #[subscription(
+        name = "subscribe_tx_processed",
+        unsubscribe = "unsubscribe_tx_processed",
+        item = RpcTransactionProcessed
+    )]
+    async fn subscribe_tx_processed(&self) -> SubscriptionResult;
 }
 
 #[async_trait::async_trait]
@@ -88,6 +144,22 @@ impl MempoolRpcServer for super::MempoolHandle {
         )
     }
 
+    async fn get_all_tx_info(&self) -> rpc::Result<Vec<TxMempoolInfo>> {
+        rpc::handle_result(self.call(|this| this.get_all_tx_info()).await)
+    }
+
+    async fn tx_info(&self, tx_id: Id<Transaction>) -> rpc::Result<Option<TxMempoolInfo>> {
+        rpc::handle_result(self.call(move |this| this.tx_info(&tx_id)).await)
+    }
+
+    async fn get_all_orphan_info(&self) -> rpc::Result<Vec<OrphanTxInfo>> {
+        rpc::handle_result(self.call(|this| this.get_all_orphan_info()).await)
+    }
+
+    async fn tx_status(&self, tx_id: Id<Transaction>) -> rpc::Result<Option<TxStatus>> {
+        rpc::handle_result(self.call(move |this| this.tx_status(&tx_id)).await)
+    }
+
     async fn get_transaction(&self, tx_id: Id<Transaction>) -> rpc::Result<Option<GetTxResponse>> {
         let res: Option<_> = rpc::handle_result(
             self.call(move |this| {
@@ -125,6 +197,10 @@ impl MempoolRpcServer for super::MempoolHandle {
         rpc::handle_result(self.call(|this| this.get_max_size().as_bytes()).await)
     }
 
+    async fn get_limits(&self) -> rpc::Result<MempoolLimits> {
+        rpc::handle_result(self.call(|this| this.get_limits()).await)
+    }
+
     async fn set_max_size(&self, max_size: usize) -> rpc::Result<()> {
         let max_size = MempoolMaxSize::from_bytes(max_size);
         rpc::handle_result(self.call_mut(move |this| this.set_max_size(max_size)).await)
@@ -133,4 +209,39 @@ impl MempoolRpcServer for super::MempoolHandle {
     async fn get_fee_rate(&self, in_top_x_mb: usize) -> rpc::Result<FeeRate> {
         rpc::handle_result(self.call(move |this| this.get_fee_rate(in_top_x_mb)).await)
     }
+
+    async fn estimate_fee_rate(
+        &self,
+        target_blocks: usize,
+        confidence_percent: u8,
+    ) -> rpc::Result<Option<FeeRate>> {
+        rpc::handle_result(
+            self.call(move |this| this.estimate_fee_rate(target_blocks, confidence_percent))
+                .await,
+        )
+    }
+
+    async fn subscribe_tx_processed(&self, pending: PendingSubscriptionSink) -> SubscriptionResult {
+        let (sender, receiver) = rpc::subscription::event_channel();
+
+        // Mempool only keeps a weak reference, so the handler is unsubscribed
+        // when it's dropped at the end of the subscription
+        let handler: Arc<dyn Fn(MempoolEvent) + Send + Sync> =
+            Arc::new(move |event: MempoolEvent| match event {
+                MempoolEvent::TransactionProcessed(event) => {
+                    sender.send(RpcTransactionProcessed {
+                        tx_id: *event.tx_id(),
+                        origin: event.origin(),
+                        error: event.result().as_ref().err().map(ToString::to_string),
+                    });
+                }
+                MempoolEvent::NewTip(_) => {}
+            });
+        let weak_handler = Arc::downgrade(&handler);
+        self.call_mut(move |this| this.subscribe_to_events_weak(weak_handler)).await?;
+
+        let result = rpc::subscription::forward_events(pending, receiver).await;
+        drop(handler);
+        result
+    }
 }
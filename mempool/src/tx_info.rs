@@ -0,0 +1,73 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Summaries of mempool contents for inspection by node operators
+
+use common::{
+    chain::Transaction,
+    primitives::{Amount, Id},
+};
+
+use crate::TxOrigin;
+
+/// Totals over a transaction together with all its unconfirmed ancestors or descendants
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RelativesInfo {
+    pub count: usize,
+    pub size: usize,
+    pub fee: Amount,
+}
+
+/// Information about a transaction in the main mempool
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct TxMempoolInfo {
+    pub tx_id: Id<Transaction>,
+    pub origin: TxOrigin,
+    pub fee: Amount,
+    pub size: usize,
+    /// Time the transaction entered the mempool, in seconds since the UNIX epoch
+    pub creation_time: u64,
+    /// Unconfirmed transactions this one spends from
+    pub parents: Vec<Id<Transaction>>,
+    /// Unconfirmed transactions spending from this one
+    pub children: Vec<Id<Transaction>>,
+    /// Totals including this transaction and all its unconfirmed ancestors
+    pub with_ancestors: RelativesInfo,
+    /// Totals including this transaction and all its descendants
+    pub with_descendants: RelativesInfo,
+}
+
+/// Information about a transaction in the orphan pool
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct OrphanTxInfo {
+    pub tx_id: Id<Transaction>,
+    pub origin: TxOrigin,
+    pub size: usize,
+    /// Time the transaction entered the orphan pool, in seconds since the UNIX epoch
+    pub creation_time: u64,
+}
+
+/// Limits the mempool currently enforces
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct MempoolLimits {
+    /// Maximum total size of the mempool transactions in bytes
+    pub max_size: usize,
+    /// Transactions older than this many seconds are evicted
+    pub max_tx_age: u64,
+    /// Maximum number of transactions in the orphan pool
+    pub orphan_pool_capacity: usize,
+    /// Maximum size of an orphan transaction in bytes
+    pub max_orphan_tx_size: usize,
+}
@@ -16,7 +16,9 @@
 use p2p_types::peer_id::PeerId;
 
 /// Tracks where a transaction originates
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+#[derive(
+    Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, serde::Serialize, serde::Deserialize,
+)]
 pub enum TxOrigin {
     /// Transaction was submitted to local node's mempool. It should not be propagated further.
     LocalMempool,
@@ -32,6 +34,12 @@ pub enum TxOrigin {
     /// If it eventually turns out to be valid, it should be propagated further to other peers.
     /// If it's not valid, the original peer should be penalized as appropriate.
     Peer(PeerId),
+
+    /// Transaction was received from a peer before the restart and restored from the mempool file.
+    ///
+    /// Peer IDs are not stable across restarts, so the original peer can't be penalized any more.
+    /// The transaction has already been propagated, so it's not propagated again.
+    Restored,
 }
 
 impl std::fmt::Display for TxOrigin {
@@ -41,6 +49,7 @@ impl std::fmt::Display for TxOrigin {
             TxOrigin::LocalP2p => write!(f, "local node p2p"),
             TxOrigin::PastBlock => write!(f, "reorged-out block"),
             TxOrigin::Peer(peer_id) => write!(f, "peer node {peer_id}"),
+            TxOrigin::Restored => write!(f, "restored mempool file"),
         }
     }
 }
@@ -16,7 +16,8 @@
 use std::sync::Arc;
 
 use chainstate::{
-    BlockSource, ChainInfo, ChainstateConfig, ChainstateError, ChainstateEvent, Locator,
+    AddressHistoryEntry, BlockSource, ChainInfo, ChainstateConfig, ChainstateError,
+    ChainstateEvent, Locator,
 };
 use chainstate_types::{BlockIndex, EpochData, GenBlockIndex};
 use common::{
@@ -25,14 +26,14 @@ use common::{
             signed_block_header::SignedBlockHeader, timestamp::BlockTimestamp, Block, BlockReward,
             GenBlock,
         },
-        tokens::{RPCTokenInfo, TokenAuxiliaryData, TokenId},
-        AccountNonce, AccountType, ChainConfig, DelegationId, OutPointSourceId, PoolId, TxInput,
-        TxMainChainIndex, UtxoOutPoint,
+        tokens::{RPCTokenInfo, TokenAuxiliaryData, TokenId, TokenStats},
+        AccountNonce, AccountType, ChainConfig, DelegationId, Destination, OutPointSourceId,
+        PoolId, Spender, TxInput, TxMainChainIndex, UtxoOutPoint,
     },
     primitives::{Amount, BlockHeight, Id},
 };
 use pos_accounting::PoolData;
-use utils::eventhandler::EventHandler;
+use utils::eventhandler::{EventHandler, WeakEventHandler};
 use utxo::Utxo;
 
 use chainstate::chainstate_interface::ChainstateInterface;
@@ -42,6 +43,7 @@ mockall::mock! {
 
     impl ChainstateInterface for ChainstateInterfaceMock {
         fn subscribe_to_events(&mut self, handler: Arc<dyn Fn(ChainstateEvent) + Send + Sync>);
+        fn subscribe_to_events_weak(&mut self, handler: WeakEventHandler<ChainstateEvent>);
         fn process_block(&mut self, block: Block, source: BlockSource) -> Result<Option<BlockIndex>, ChainstateError>;
         fn preliminary_block_check(&self, block: Block) -> Result<Block, ChainstateError>;
         fn preliminary_header_check(&self, header: SignedBlockHeader) -> Result<(), ChainstateError>;
@@ -119,6 +121,12 @@ mockall::mock! {
             &self,
             tx_id: &Id<common::chain::Transaction>,
         ) -> Result<Option<TokenId>, ChainstateError>;
+        fn get_token_stats(&self, token_id: TokenId) -> Result<Option<TokenStats>, ChainstateError>;
+        fn get_token_ids(
+            &self,
+            start_after: Option<TokenId>,
+            count: usize,
+        ) -> Result<Vec<TokenId>, ChainstateError>;
         fn get_inputs_outpoints_coin_amount(
             &self,
             inputs: &[TxInput],
@@ -161,6 +169,19 @@ mockall::mock! {
             &self,
             account: AccountType,
         ) -> Result<Option<AccountNonce>, ChainstateError>;
+        fn get_transaction(
+            &self,
+            tx_id: &Id<common::chain::Transaction>,
+        ) -> Result<Option<common::chain::Transaction>, ChainstateError>;
+        fn get_address_utxos(
+            &self,
+            destination: &Destination,
+        ) -> Result<std::collections::BTreeMap<UtxoOutPoint, Utxo>, ChainstateError>;
+        fn get_address_history(
+            &self,
+            destination: &Destination,
+        ) -> Result<Vec<AddressHistoryEntry>, ChainstateError>;
+        fn get_spending_tx(&self, outpoint: &UtxoOutPoint) -> Result<Option<Spender>, ChainstateError>;
     }
 }
 
@@ -25,10 +25,11 @@ use mempool::{
     error::{Error, TxValidationError},
     event::MempoolEvent,
     tx_accumulator::TransactionAccumulator,
+    tx_info::{MempoolLimits, OrphanTxInfo, TxMempoolInfo},
     FeeRate, MempoolInterface, MempoolMaxSize, MempoolSubsystemInterface, TxOrigin, TxStatus,
 };
 use subsystem::{subsystem::CallError, CallRequest, ShutdownRequest};
-use utils::atomics::AcqRelAtomicBool;
+use utils::{atomics::AcqRelAtomicBool, eventhandler::WeakEventHandler};
 
 #[derive(Clone)]
 pub struct MempoolInterfaceMock {
@@ -89,6 +90,13 @@ impl MempoolInterface for MempoolInterfaceMock {
         Vec::new()
     }
 
+    fn find_transactions(
+        &self,
+        _predicate: &dyn Fn(&Id<Transaction>) -> bool,
+    ) -> Vec<SignedTransaction> {
+        Vec::new()
+    }
+
     fn contains_transaction(&self, _tx: &Id<Transaction>) -> bool {
         self.contains_transaction_called.store(true);
         true
@@ -106,6 +114,22 @@ impl MempoolInterface for MempoolInterfaceMock {
         None
     }
 
+    fn get_all_tx_info(&self) -> Vec<TxMempoolInfo> {
+        Vec::new()
+    }
+
+    fn tx_info(&self, _id: &Id<Transaction>) -> Option<TxMempoolInfo> {
+        None
+    }
+
+    fn get_all_orphan_info(&self) -> Vec<OrphanTxInfo> {
+        Vec::new()
+    }
+
+    fn tx_status(&self, _id: &Id<Transaction>) -> Option<TxStatus> {
+        None
+    }
+
     fn best_block_id(&self) -> Id<GenBlock> {
         unimplemented!()
     }
@@ -127,6 +151,12 @@ impl MempoolInterface for MempoolInterfaceMock {
         self.subscribe_to_events_called.store(true);
     }
 
+    fn subscribe_to_events_weak(&mut self, _handler: WeakEventHandler<MempoolEvent>) {
+        self.subscribe_to_events_called.store(true);
+    }
+
+    fn announce_restored_transactions(&mut self) {}
+
     fn memory_usage(&self) -> usize {
         unimplemented!()
     }
@@ -135,6 +165,10 @@ impl MempoolInterface for MempoolInterfaceMock {
         unimplemented!()
     }
 
+    fn get_limits(&self) -> MempoolLimits {
+        unimplemented!()
+    }
+
     fn set_max_size(&mut self, _max_size: MempoolMaxSize) -> Result<(), Error> {
         unimplemented!()
     }
@@ -142,6 +176,14 @@ impl MempoolInterface for MempoolInterfaceMock {
     fn get_fee_rate(&self, _in_top_x_mb: usize) -> Result<FeeRate, Error> {
         Ok(FeeRate::new(Amount::ZERO))
     }
+
+    fn estimate_fee_rate(
+        &self,
+        _target_blocks: usize,
+        _confidence_percent: u8,
+    ) -> Result<Option<FeeRate>, Error> {
+        Ok(None)
+    }
 }
 
 #[async_trait::async_trait]
@@ -16,12 +16,13 @@
 use std::{collections::BTreeMap, path::PathBuf, sync::Arc};
 
 use common::{
-    chain::{ChainConfig, GenBlock, SignedTransaction},
+    chain::{ChainConfig, DelegationId, GenBlock, PoolId, SignedTransaction},
     primitives::{Amount, BlockHeight, Id},
 };
 use crypto::key::hdkd::u31::U31;
 use logging::log;
 use node_lib::node_controller::NodeController;
+use serialization::hex::HexDecode;
 use tokio::{
     sync::mpsc::{Sender, UnboundedReceiver, UnboundedSender},
     task::JoinHandle,
@@ -36,8 +37,9 @@ use super::{
     chainstate_event_handler::ChainstateEventHandler,
     error::BackendError,
     messages::{
-        AccountId, AccountInfo, AddressInfo, BackendEvent, BackendRequest, EncryptionAction,
-        EncryptionState, SendRequest, StakeRequest, TransactionInfo, WalletId, WalletInfo,
+        AccountId, AccountInfo, AddressInfo, BackendEvent, BackendRequest, CreateDelegationRequest,
+        DelegateStakingRequest, EncryptionAction, EncryptionState, SendRequest, StakeRequest,
+        TransactionInfo, WalletId, WalletInfo, WithdrawFromDelegationRequest,
     },
     p2p_event_handler::P2pEventHandler,
     parse_address, parse_coin_amount,
@@ -60,8 +62,8 @@ struct AccountData {
     /// The variable is stored here so that the backend can send transaction list updates automatically.
     transaction_list_skip: usize,
 
-    /// If set, pool balances should be updated in the UI.
-    /// The flag is necessary because the pool balances load requires RPC call and may fail.
+    /// If set, pool and delegation balances should be updated in the UI.
+    /// The flag is necessary because the balances load requires RPC call and may fail.
     update_pool_balance: bool,
 }
 
@@ -155,6 +157,7 @@ impl Backend {
             staking_enabled: false,
             balance: Self::get_account_balance(controller, account_index),
             staking_balance: BTreeMap::new(),
+            delegations_balance: BTreeMap::new(),
             transaction_list,
         }
     }
@@ -359,7 +362,7 @@ impl Backend {
 
         let transaction = wallet
             .controller
-            .send_to_address(account_id.account_index(), address, amount)
+            .send_to_address(account_id.account_index(), address, amount, false)
             .await
             .map_err(|e| BackendError::WalletError(e.to_string()))?;
 
@@ -393,6 +396,102 @@ impl Backend {
         Ok(TransactionInfo { transaction })
     }
 
+    async fn create_delegation(
+        &mut self,
+        request: CreateDelegationRequest,
+    ) -> Result<TransactionInfo, BackendError> {
+        let CreateDelegationRequest {
+            wallet_id,
+            account_id,
+            pool_id,
+        } = request;
+
+        let wallet = self
+            .wallets
+            .get_mut(&wallet_id)
+            .ok_or(BackendError::UnknownWalletIndex(wallet_id))?;
+
+        let pool_id =
+            PoolId::hex_decode_all(&pool_id).map_err(|_| BackendError::InvalidPoolId(pool_id))?;
+
+        // The delegation is owned by a new address of the same account
+        let (_index, owner_address) = wallet
+            .controller
+            .new_address(account_id.account_index())
+            .map_err(|e| BackendError::WalletError(e.to_string()))?;
+
+        let (_delegation_id, transaction) = wallet
+            .controller
+            .create_delegation(account_id.account_index(), owner_address, pool_id)
+            .await
+            .map_err(|e| BackendError::WalletError(e.to_string()))?;
+
+        Ok(TransactionInfo { transaction })
+    }
+
+    async fn delegate_staking(
+        &mut self,
+        request: DelegateStakingRequest,
+    ) -> Result<TransactionInfo, BackendError> {
+        let DelegateStakingRequest {
+            wallet_id,
+            account_id,
+            delegation_id,
+            amount,
+        } = request;
+
+        let wallet = self
+            .wallets
+            .get_mut(&wallet_id)
+            .ok_or(BackendError::UnknownWalletIndex(wallet_id))?;
+
+        let delegation_id = DelegationId::hex_decode_all(&delegation_id)
+            .map_err(|_| BackendError::InvalidDelegationId(delegation_id))?;
+        let amount = parse_coin_amount(&self.chain_config, &amount)
+            .ok_or(BackendError::InvalidAmount(amount))?;
+
+        let transaction = wallet
+            .controller
+            .delegate_staking(account_id.account_index(), amount, delegation_id)
+            .await
+            .map_err(|e| BackendError::WalletError(e.to_string()))?;
+
+        Ok(TransactionInfo { transaction })
+    }
+
+    async fn withdraw_from_delegation(
+        &mut self,
+        request: WithdrawFromDelegationRequest,
+    ) -> Result<TransactionInfo, BackendError> {
+        let WithdrawFromDelegationRequest {
+            wallet_id,
+            account_id,
+            delegation_id,
+            address,
+            amount,
+        } = request;
+
+        let wallet = self
+            .wallets
+            .get_mut(&wallet_id)
+            .ok_or(BackendError::UnknownWalletIndex(wallet_id))?;
+
+        let delegation_id = DelegationId::hex_decode_all(&delegation_id)
+            .map_err(|_| BackendError::InvalidDelegationId(delegation_id))?;
+        let address = parse_address(&self.chain_config, &address)
+            .map_err(|err| BackendError::AddressError(err.to_string()))?;
+        let amount = parse_coin_amount(&self.chain_config, &amount)
+            .ok_or(BackendError::InvalidAmount(amount))?;
+
+        let transaction = wallet
+            .controller
+            .withdraw_from_delegation(account_id.account_index(), address, amount, delegation_id)
+            .await
+            .map_err(|e| BackendError::WalletError(e.to_string()))?;
+
+        Ok(TransactionInfo { transaction })
+    }
+
     async fn broadcast(&mut self, transaction: SignedTransaction) -> Result<(), BackendError> {
         let tx_status = self
             .controller
@@ -491,6 +590,18 @@ impl Backend {
                 let stake_res = self.stake_amount(stake_request).await;
                 Self::send_event(&self.event_tx, BackendEvent::StakeAmount(stake_res)).await;
             }
+            BackendRequest::CreateDelegation(request) => {
+                let res = self.create_delegation(request).await;
+                Self::send_event(&self.event_tx, BackendEvent::CreateDelegation(res)).await;
+            }
+            BackendRequest::DelegateStaking(request) => {
+                let res = self.delegate_staking(request).await;
+                Self::send_event(&self.event_tx, BackendEvent::DelegateStaking(res)).await;
+            }
+            BackendRequest::WithdrawFromDelegation(request) => {
+                let res = self.withdraw_from_delegation(request).await;
+                Self::send_event(&self.event_tx, BackendEvent::WithdrawFromDelegation(res)).await;
+            }
             BackendRequest::Broadcast(transaction) => {
                 let broadcast_res = self.broadcast(transaction).await;
                 Self::send_event(&self.event_tx, BackendEvent::Broadcast(broadcast_res)).await;
@@ -588,23 +699,39 @@ impl Backend {
                     .controller
                     .get_stake_pool_balances(account_id.account_index())
                     .await;
-                match staking_balance_res {
-                    Ok(staking_balance) => {
-                        Self::send_event(
-                            &self.event_tx,
-                            BackendEvent::StakingBalance(
-                                *wallet_id,
-                                *account_id,
-                                staking_balance.clone(),
-                            ),
-                        )
-                        .await;
-                        account_data.update_pool_balance = false;
-                    }
+                let staking_balance = match staking_balance_res {
+                    Ok(staking_balance) => staking_balance,
                     Err(err) => {
                         log::error!("Staking balance loading failed: {err}");
+                        continue;
                     }
-                }
+                };
+                let delegations_res =
+                    wallet_data.controller.get_delegations(account_id.account_index()).await;
+                let delegations_balance = match delegations_res {
+                    Ok(delegations) => delegations
+                        .into_iter()
+                        .map(|(delegation_id, pool_id, balance)| {
+                            (delegation_id, (pool_id, balance))
+                        })
+                        .collect(),
+                    Err(err) => {
+                        log::error!("Delegations balance loading failed: {err}");
+                        continue;
+                    }
+                };
+
+                Self::send_event(
+                    &self.event_tx,
+                    BackendEvent::StakingBalance(*wallet_id, *account_id, staking_balance),
+                )
+                .await;
+                Self::send_event(
+                    &self.event_tx,
+                    BackendEvent::DelegationsBalance(*wallet_id, *account_id, delegations_balance),
+                )
+                .await;
+                account_data.update_pool_balance = false;
             }
         }
     }
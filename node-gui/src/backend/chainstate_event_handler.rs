@@ -75,6 +75,7 @@ impl ChainstateEventHandler {
                     ChainstateEvent::NewTip(_, _) => {
                         self.chain_info_updated = true;
                     }
+                    ChainstateEvent::Reorg { .. } => {}
                 },
                 None => {
                     // Node is stopped
@@ -29,4 +29,8 @@ pub enum BackendError {
     AddressError(String),
     #[error("Invalid amount: {0}")]
     InvalidAmount(String),
+    #[error("Invalid pool id: {0}")]
+    InvalidPoolId(String),
+    #[error("Invalid delegation id: {0}")]
+    InvalidDelegationId(String),
 }
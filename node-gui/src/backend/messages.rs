@@ -22,7 +22,7 @@ use std::{
 use chainstate::ChainInfo;
 use common::{
     address::Address,
-    chain::{GenBlock, PoolId, SignedTransaction},
+    chain::{DelegationId, GenBlock, PoolId, SignedTransaction},
     primitives::{Amount, BlockHeight, Id},
 };
 use crypto::key::hdkd::{child_number::ChildNumber, u31::U31};
@@ -71,6 +71,7 @@ pub struct AccountInfo {
     pub staking_enabled: bool,
     pub balance: BTreeMap<Currency, Amount>,
     pub staking_balance: BTreeMap<PoolId, Amount>,
+    pub delegations_balance: BTreeMap<DelegationId, (PoolId, Amount)>,
     pub transaction_list: TransactionList,
 }
 
@@ -97,6 +98,30 @@ pub struct StakeRequest {
     pub amount: String,
 }
 
+#[derive(Debug, Clone)]
+pub struct CreateDelegationRequest {
+    pub wallet_id: WalletId,
+    pub account_id: AccountId,
+    pub pool_id: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct DelegateStakingRequest {
+    pub wallet_id: WalletId,
+    pub account_id: AccountId,
+    pub delegation_id: String,
+    pub amount: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct WithdrawFromDelegationRequest {
+    pub wallet_id: WalletId,
+    pub account_id: AccountId,
+    pub delegation_id: String,
+    pub address: String,
+    pub amount: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct TransactionInfo {
     pub transaction: SignedTransaction,
@@ -147,6 +172,9 @@ pub enum BackendRequest {
     ToggleStaking(WalletId, AccountId, bool),
     SendAmount(SendRequest),
     StakeAmount(StakeRequest),
+    CreateDelegation(CreateDelegationRequest),
+    DelegateStaking(DelegateStakingRequest),
+    WithdrawFromDelegation(WithdrawFromDelegationRequest),
     Broadcast(SignedTransaction),
 
     TransactionList {
@@ -174,10 +202,18 @@ pub enum BackendEvent {
     WalletBestBlock(WalletId, (Id<GenBlock>, BlockHeight)),
     Balance(WalletId, AccountId, BTreeMap<Currency, Amount>),
     StakingBalance(WalletId, AccountId, BTreeMap<PoolId, Amount>),
+    DelegationsBalance(
+        WalletId,
+        AccountId,
+        BTreeMap<DelegationId, (PoolId, Amount)>,
+    ),
     NewAddress(Result<AddressInfo, BackendError>),
     ToggleStaking(Result<(WalletId, AccountId, bool), BackendError>),
     SendAmount(Result<TransactionInfo, BackendError>),
     StakeAmount(Result<TransactionInfo, BackendError>),
+    CreateDelegation(Result<TransactionInfo, BackendError>),
+    DelegateStaking(Result<TransactionInfo, BackendError>),
+    WithdrawFromDelegation(Result<TransactionInfo, BackendError>),
     Broadcast(Result<(), BackendError>),
 
     TransactionList(WalletId, AccountId, Result<TransactionList, BackendError>),
@@ -0,0 +1,97 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common::chain::ChainConfig;
+use iced::{
+    widget::{column, container, row, text_input, Text},
+    Element,
+};
+use iced_aw::Grid;
+use serialization::hex::HexEncode;
+
+use crate::{backend::messages::AccountInfo, main_window::print_coin_amount};
+
+use super::{AccountState, WalletMessage};
+
+pub fn view_delegation(
+    chain_config: &ChainConfig,
+    account: &AccountInfo,
+    account_state: &AccountState,
+) -> Element<'static, WalletMessage> {
+    let field = |text: String| container(Text::new(text)).padding(5);
+
+    let delegations_balance_grid = {
+        // We print the table only if there are delegations
+        if account.delegations_balance.is_empty() {
+            Grid::with_columns(3)
+                .push(field("No delegations found".to_owned()))
+                .push(field(String::new()))
+                .push(field(String::new()))
+        } else {
+            let mut delegations_balance_grid = Grid::with_columns(3)
+                .push(field("Delegation Id".to_owned()))
+                .push(field("Pool Id".to_owned()))
+                .push(field("Delegation balance".to_owned()));
+            for (delegation_id, (pool_id, balance)) in account.delegations_balance.iter() {
+                delegations_balance_grid = delegations_balance_grid
+                    .push(field(delegation_id.hex_encode()))
+                    .push(field(pool_id.hex_encode()))
+                    .push(field(print_coin_amount(chain_config, *balance)));
+            }
+            delegations_balance_grid
+        }
+    };
+
+    column![
+        row![
+            text_input(
+                "Pool Id for the new delegation",
+                &account_state.delegation_pool_id
+            )
+            .on_input(|value| { WalletMessage::DelegationPoolIdEdit(value) })
+            .padding(15),
+            iced::widget::button(Text::new("Create delegation"))
+                .padding(15)
+                .on_press(WalletMessage::CreateDelegation)
+        ],
+        iced::widget::horizontal_rule(10),
+        text_input("Delegation Id", &account_state.delegation_id)
+            .on_input(|value| { WalletMessage::DelegationIdEdit(value) })
+            .padding(15),
+        row![
+            text_input("Amount", &account_state.delegation_amount)
+                .on_input(|value| { WalletMessage::DelegationAmountEdit(value) })
+                .padding(15),
+            iced::widget::button(Text::new("Delegate"))
+                .padding(15)
+                .on_press(WalletMessage::DelegateStaking)
+        ],
+        row![
+            text_input(
+                "Address to withdraw the amount to",
+                &account_state.withdraw_address
+            )
+            .on_input(|value| { WalletMessage::WithdrawAddressEdit(value) })
+            .padding(15),
+            iced::widget::button(Text::new("Withdraw"))
+                .padding(15)
+                .on_press(WalletMessage::WithdrawFromDelegation)
+        ],
+        iced::widget::horizontal_rule(10),
+        delegations_balance_grid,
+    ]
+    .spacing(10)
+    .into()
+}
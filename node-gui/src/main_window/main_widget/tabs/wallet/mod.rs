@@ -14,6 +14,7 @@
 // limitations under the License.
 
 mod addresses;
+mod delegation;
 mod left_panel;
 mod send;
 mod stake;
@@ -32,7 +33,10 @@ use wallet_controller::DEFAULT_ACCOUNT_INDEX;
 
 use crate::{
     backend::{
-        messages::{AccountId, BackendRequest, SendRequest, StakeRequest, WalletId},
+        messages::{
+            AccountId, BackendRequest, CreateDelegationRequest, DelegateStakingRequest,
+            SendRequest, StakeRequest, WalletId, WithdrawFromDelegationRequest,
+        },
         BackendSender,
     },
     main_window::NodeState,
@@ -46,6 +50,7 @@ pub enum SelectedPanel {
     Addresses,
     Send,
     Staking,
+    Delegation,
 }
 
 #[derive(Debug, Clone)]
@@ -73,6 +78,14 @@ pub enum WalletMessage {
 
     ToggleStaking(bool),
 
+    DelegationPoolIdEdit(String),
+    CreateDelegation,
+    DelegationIdEdit(String),
+    DelegationAmountEdit(String),
+    DelegateStaking,
+    WithdrawAddressEdit(String),
+    WithdrawFromDelegation,
+
     TransactionList { skip: usize },
 
     Close,
@@ -84,6 +97,10 @@ pub struct AccountState {
     send_amount: String,
     send_address: String,
     stake_amount: String,
+    delegation_pool_id: String,
+    delegation_id: String,
+    delegation_amount: String,
+    withdraw_address: String,
 }
 
 pub struct WalletTab {
@@ -205,6 +222,52 @@ impl WalletTab {
                 ));
                 Command::none()
             }
+            WalletMessage::DelegationPoolIdEdit(value) => {
+                self.account_state.delegation_pool_id = value;
+                Command::none()
+            }
+            WalletMessage::CreateDelegation => {
+                let request = CreateDelegationRequest {
+                    wallet_id: self.wallet_id,
+                    account_id: self.selected_account,
+                    pool_id: self.account_state.delegation_pool_id.clone(),
+                };
+                backend_sender.send(BackendRequest::CreateDelegation(request));
+                Command::none()
+            }
+            WalletMessage::DelegationIdEdit(value) => {
+                self.account_state.delegation_id = value;
+                Command::none()
+            }
+            WalletMessage::DelegationAmountEdit(value) => {
+                self.account_state.delegation_amount = value;
+                Command::none()
+            }
+            WalletMessage::DelegateStaking => {
+                let request = DelegateStakingRequest {
+                    wallet_id: self.wallet_id,
+                    account_id: self.selected_account,
+                    delegation_id: self.account_state.delegation_id.clone(),
+                    amount: self.account_state.delegation_amount.clone(),
+                };
+                backend_sender.send(BackendRequest::DelegateStaking(request));
+                Command::none()
+            }
+            WalletMessage::WithdrawAddressEdit(value) => {
+                self.account_state.withdraw_address = value;
+                Command::none()
+            }
+            WalletMessage::WithdrawFromDelegation => {
+                let request = WithdrawFromDelegationRequest {
+                    wallet_id: self.wallet_id,
+                    account_id: self.selected_account,
+                    delegation_id: self.account_state.delegation_id.clone(),
+                    address: self.account_state.withdraw_address.clone(),
+                    amount: self.account_state.delegation_amount.clone(),
+                };
+                backend_sender.send(BackendRequest::WithdrawFromDelegation(request));
+                Command::none()
+            }
             WalletMessage::TransactionList { skip } => {
                 backend_sender.send(BackendRequest::TransactionList {
                     wallet_id: self.wallet_id,
@@ -271,6 +334,11 @@ impl Tab for WalletTab {
                             account,
                             &self.account_state.stake_amount,
                         ),
+                        SelectedPanel::Delegation => delegation::view_delegation(
+                            &node_state.chain_config,
+                            account,
+                            &self.account_state,
+                        ),
                     };
 
                     let body = Scrollable::new(container(body).padding(10))
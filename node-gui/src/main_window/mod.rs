@@ -307,6 +307,7 @@ impl MainWindow {
                     inbound,
                     user_agent,
                     version,
+                    node_key: _,
                 }) => {
                     self.node_state.connected_peers.insert(
                         id,
@@ -407,6 +408,17 @@ impl MainWindow {
                         .staking_balance = staking_balance;
                     Command::none()
                 }
+                BackendEvent::DelegationsBalance(wallet_id, account_id, delegations_balance) => {
+                    self.node_state
+                        .wallets
+                        .get_mut(&wallet_id)
+                        .expect("wallet must be known (delegations balance)")
+                        .accounts
+                        .get_mut(&account_id)
+                        .expect("account must be known (delegations balance)")
+                        .delegations_balance = delegations_balance;
+                    Command::none()
+                }
                 BackendEvent::NewAddress(Ok(address_info)) => {
                     self.node_state
                         .wallets
@@ -454,6 +466,18 @@ impl MainWindow {
                     self.show_error(error.to_string());
                     Command::none()
                 }
+                BackendEvent::CreateDelegation(Ok(transaction_info))
+                | BackendEvent::DelegateStaking(Ok(transaction_info))
+                | BackendEvent::WithdrawFromDelegation(Ok(transaction_info)) => {
+                    backend_sender.send(BackendRequest::Broadcast(transaction_info.transaction));
+                    Command::none()
+                }
+                BackendEvent::CreateDelegation(Err(error))
+                | BackendEvent::DelegateStaking(Err(error))
+                | BackendEvent::WithdrawFromDelegation(Err(error)) => {
+                    self.show_error(error.to_string());
+                    Command::none()
+                }
                 BackendEvent::Broadcast(Ok(())) => {
                     self.show_info("Success".to_owned());
                     Command::none()
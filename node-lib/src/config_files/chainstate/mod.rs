@@ -31,6 +31,8 @@ pub struct ChainstateConfigFile {
     pub min_max_bootstrap_import_buffer_sizes: Option<(usize, usize)>,
     /// Maintain a full transaction index.
     pub tx_index_enabled: Option<bool>,
+    /// Maintain the address and the spent-by indexes.
+    pub address_index_enabled: Option<bool>,
     /// A maximum tip age in seconds.
     ///
     /// The initial block download is finished if the difference between the current time and the
@@ -45,6 +47,7 @@ impl From<ChainstateConfigFile> for ChainstateConfig {
             max_orphan_blocks: c.max_orphan_blocks.into(),
             min_max_bootstrap_import_buffer_sizes: c.min_max_bootstrap_import_buffer_sizes.into(),
             tx_index_enabled: c.tx_index_enabled.into(),
+            address_index_enabled: c.address_index_enabled.into(),
             max_tip_age: c.max_tip_age.map(Duration::from_secs).into(),
         }
     }
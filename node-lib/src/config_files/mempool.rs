@@ -0,0 +1,33 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use mempool::MempoolConfig;
+use serde::{Deserialize, Serialize};
+
+/// The mempool subsystem configuration.
+#[must_use]
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct MempoolConfigFile {
+    /// Accept transactions replacing conflicting transactions that signal replaceability.
+    pub enable_rbf: Option<bool>,
+}
+
+impl From<MempoolConfigFile> for MempoolConfig {
+    fn from(config: MempoolConfigFile) -> Self {
+        Self {
+            enable_rbf: config.enable_rbf.unwrap_or_default(),
+        }
+    }
+}
@@ -20,6 +20,7 @@ pub use self::{chainstate_launcher::StorageBackendConfigFile, p2p::NodeTypeConfi
 mod blockprod;
 mod chainstate;
 mod chainstate_launcher;
+mod mempool;
 mod p2p;
 mod rpc;
 
@@ -32,7 +33,8 @@ use crate::RunOptions;
 
 use self::{
     blockprod::BlockProdConfigFile, chainstate::ChainstateConfigFile,
-    chainstate_launcher::ChainstateLauncherConfigFile, p2p::P2pConfigFile, rpc::RpcConfigFile,
+    chainstate_launcher::ChainstateLauncherConfigFile, mempool::MempoolConfigFile,
+    p2p::P2pConfigFile, rpc::RpcConfigFile,
 };
 
 /// The node configuration.
@@ -42,6 +44,7 @@ pub struct NodeConfigFile {
     // Subsystems configurations.
     pub blockprod: Option<BlockProdConfigFile>,
     pub chainstate: Option<ChainstateLauncherConfigFile>,
+    pub mempool: Option<MempoolConfigFile>,
     pub p2p: Option<P2pConfigFile>,
     pub rpc: Option<RpcConfigFile>,
 }
@@ -51,6 +54,7 @@ impl NodeConfigFile {
         Ok(Self {
             blockprod: None,
             chainstate: None,
+            mempool: None,
             p2p: None,
             rpc: None,
         })
@@ -75,18 +79,21 @@ impl NodeConfigFile {
         let NodeConfigFile {
             blockprod,
             chainstate,
+            mempool,
             p2p,
             rpc,
         } = toml::from_str(&config_as_str).context("Failed to parse config")?;
 
         let blockprod = blockprod_config(blockprod.unwrap_or_default(), options);
         let chainstate = chainstate_config(chainstate.unwrap_or_default(), options);
+        let mempool = mempool_config(mempool.unwrap_or_default(), options);
         let p2p = p2p_config(p2p.unwrap_or_default(), options);
         let rpc = rpc_config(rpc.unwrap_or_default(), options);
 
         Ok(Self {
             blockprod: Some(blockprod),
             chainstate: Some(chainstate),
+            mempool: Some(mempool),
             p2p: Some(p2p),
             rpc: Some(rpc),
         })
@@ -123,6 +130,7 @@ fn chainstate_config(
         max_orphan_blocks,
         min_max_bootstrap_import_buffer_sizes,
         tx_index_enabled,
+        address_index_enabled,
         max_tip_age,
     } = chainstate_config;
 
@@ -130,6 +138,7 @@ fn chainstate_config(
     let max_db_commit_attempts = options.max_db_commit_attempts.or(max_db_commit_attempts);
     let max_orphan_blocks = options.max_orphan_blocks.or(max_orphan_blocks);
     let tx_index_enabled = options.tx_index_enabled.or(tx_index_enabled);
+    let address_index_enabled = options.address_index_enabled.or(address_index_enabled);
     let max_tip_age = options.max_tip_age.or(max_tip_age);
 
     let chainstate_config = ChainstateConfigFile {
@@ -137,6 +146,7 @@ fn chainstate_config(
         max_orphan_blocks,
         min_max_bootstrap_import_buffer_sizes,
         tx_index_enabled,
+        address_index_enabled,
         max_tip_age,
     };
     ChainstateLauncherConfigFile {
@@ -145,10 +155,19 @@ fn chainstate_config(
     }
 }
 
+fn mempool_config(config: MempoolConfigFile, options: &RunOptions) -> MempoolConfigFile {
+    let MempoolConfigFile { enable_rbf } = config;
+
+    let enable_rbf = options.mempool_enable_rbf.or(enable_rbf);
+
+    MempoolConfigFile { enable_rbf }
+}
+
 fn p2p_config(config: P2pConfigFile, options: &RunOptions) -> P2pConfigFile {
     let P2pConfigFile {
         bind_addresses,
         socks5_proxy,
+        tor_control_address,
         disable_noise,
         boot_nodes,
         reserved_nodes,
@@ -165,6 +184,7 @@ fn p2p_config(config: P2pConfigFile, options: &RunOptions) -> P2pConfigFile {
 
     let bind_addresses = options.p2p_addr.clone().or(bind_addresses);
     let socks5_proxy = options.p2p_socks5_proxy.clone().or(socks5_proxy);
+    let tor_control_address = options.p2p_tor_control_address.clone().or(tor_control_address);
     let disable_noise = options.p2p_disable_noise.or(disable_noise);
     let boot_nodes = options.p2p_boot_node.clone().or(boot_nodes);
     let reserved_nodes = options.p2p_reserved_node.clone().or(reserved_nodes);
@@ -181,6 +201,7 @@ fn p2p_config(config: P2pConfigFile, options: &RunOptions) -> P2pConfigFile {
     P2pConfigFile {
         bind_addresses,
         socks5_proxy,
+        tor_control_address,
         disable_noise,
         boot_nodes,
         reserved_nodes,
@@ -254,6 +275,7 @@ mod tests {
         let _config: BlockProdConfigFile = toml::from_str("").unwrap();
         let _config: ChainstateLauncherConfigFile = toml::from_str("").unwrap();
         let _config: ChainstateConfigFile = toml::from_str("").unwrap();
+        let _config: MempoolConfigFile = toml::from_str("").unwrap();
         let _config: P2pConfigFile = toml::from_str("").unwrap();
         let _config: RpcConfigFile = toml::from_str("").unwrap();
     }
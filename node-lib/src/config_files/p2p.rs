@@ -57,11 +57,15 @@ pub struct P2pConfigFile {
     pub bind_addresses: Option<Vec<String>>,
     /// SOCKS5 proxy.
     pub socks5_proxy: Option<String>,
+    /// Tor control port address (to accept inbound connections over Tor).
+    pub tor_control_address: Option<String>,
     /// Disable p2p encryption (for tests only).
     pub disable_noise: Option<bool>,
     /// Optional list of boot node addresses to connect.
+    /// The address can be prefixed with the expected node public key (`<pubkey>@<address>`).
     pub boot_nodes: Option<Vec<String>>,
     /// Optional list of reserved node addresses to connect.
+    /// The address can be prefixed with the expected node public key (`<pubkey>@<address>`).
     pub reserved_nodes: Option<Vec<String>>,
     /// Maximum allowed number of inbound connections.
     pub max_inbound_connections: Option<usize>,
@@ -89,6 +93,7 @@ impl From<P2pConfigFile> for P2pConfig {
         P2pConfig {
             bind_addresses: c.bind_addresses.clone().unwrap_or_default(),
             socks5_proxy: c.socks5_proxy.clone(),
+            tor_control_address: c.tor_control_address.clone(),
             disable_noise: c.disable_noise,
             boot_nodes: c.boot_nodes.clone().unwrap_or_default(),
             reserved_nodes: c.reserved_nodes.clone().unwrap_or_default(),
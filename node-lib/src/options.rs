@@ -81,6 +81,14 @@ pub struct RunOptions {
     #[clap(long)]
     pub tx_index_enabled: Option<bool>,
 
+    /// Maintain the address and the spent-by indexes.
+    #[clap(long)]
+    pub address_index_enabled: Option<bool>,
+
+    /// Accept transactions replacing conflicting transactions that signal replaceability.
+    #[clap(long)]
+    pub mempool_enable_rbf: Option<bool>,
+
     /// Address to bind P2P to.
     #[clap(long, value_name = "ADDR")]
     pub p2p_addr: Option<Vec<String>>,
@@ -89,16 +97,22 @@ pub struct RunOptions {
     #[clap(long)]
     pub p2p_socks5_proxy: Option<String>,
 
+    /// Tor control port address (to accept inbound connections over Tor).
+    #[clap(long)]
+    pub p2p_tor_control_address: Option<String>,
+
     /// Disable p2p encryption (for tests only).
     #[clap(long)]
     #[arg(hide = true)]
     pub p2p_disable_noise: Option<bool>,
 
     /// Optional list of boot node addresses to connect.
+    /// The address can be prefixed with the expected node public key (`<pubkey>@<address>`).
     #[clap(long, value_name = "NODE")]
     pub p2p_boot_node: Option<Vec<String>>,
 
     /// Optional list of reserved node addresses to connect.
+    /// The address can be prefixed with the expected node public key (`<pubkey>@<address>`).
     #[clap(long, value_name = "NODE")]
     pub p2p_reserved_node: Option<Vec<String>>,
 
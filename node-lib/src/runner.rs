@@ -98,8 +98,10 @@ async fn initialize(
     // Mempool subsystem
     let mempool = mempool::make_mempool(
         Arc::clone(&chain_config),
+        node_config.mempool.unwrap_or_default().into(),
         subsystem::Handle::clone(&chainstate),
         Default::default(),
+        Some(data_dir.clone()),
     );
     let mempool = manager.add_subsystem_with_custom_eventloop("mempool", {
         move |call, shutdn| mempool.run(call, shutdn)
@@ -113,6 +115,8 @@ async fn initialize(
         Default::default(),
         Default::default(),
     ))?;
+    let node_key = p2p::node_key::load_or_generate_node_key(&data_dir)?;
+    log::info!("P2P node public key: {}", node_key.public_key());
     let p2p = p2p::make_p2p(
         Arc::clone(&chain_config),
         Arc::new(node_config.p2p.unwrap_or_default().into()),
@@ -120,6 +124,7 @@ async fn initialize(
         mempool.clone(),
         Default::default(),
         peerdb_storage,
+        node_key,
     )?;
     let p2p = manager.add_subsystem_with_custom_eventloop("p2p", {
         move |call, shutdown| p2p.run(call, shutdown)
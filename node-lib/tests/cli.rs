@@ -86,6 +86,7 @@ fn read_config_override_values() {
     let max_orphan_blocks = 2;
     let p2p_addr = "address";
     let p2p_socks5_proxy = "socks5_proxy";
+    let p2p_tor_control_address = "tor_control_address";
     let p2p_disable_noise = false;
     let p2p_boot_node = "boot_node";
     let p2p_reserved_node = "reserved_node";
@@ -113,8 +114,11 @@ fn read_config_override_values() {
         max_db_commit_attempts: Some(max_db_commit_attempts),
         max_orphan_blocks: Some(max_orphan_blocks),
         tx_index_enabled: Some(false),
+        address_index_enabled: Some(true),
+        mempool_enable_rbf: Some(true),
         p2p_addr: Some(vec![p2p_addr.to_owned()]),
         p2p_socks5_proxy: Some(p2p_socks5_proxy.to_owned()),
+        p2p_tor_control_address: Some(p2p_tor_control_address.to_owned()),
         p2p_disable_noise: Some(p2p_disable_noise),
         p2p_boot_node: Some(vec![p2p_boot_node.to_owned()]),
         p2p_reserved_node: Some(vec![p2p_reserved_node.to_owned()]),
@@ -153,11 +157,17 @@ fn read_config_override_values() {
         config.chainstate.clone().unwrap().chainstate_config.tx_index_enabled,
         Some(false)
     );
+    assert_eq!(
+        config.chainstate.clone().unwrap().chainstate_config.address_index_enabled,
+        Some(true)
+    );
     assert_eq!(
         config.chainstate.clone().unwrap().chainstate_config.max_tip_age,
         Some(max_tip_age)
     );
 
+    assert_eq!(config.mempool.clone().unwrap().enable_rbf, Some(true));
+
     assert_eq!(
         config.p2p.clone().unwrap().bind_addresses,
         Some(vec!(p2p_addr.to_owned()))
@@ -166,6 +176,10 @@ fn read_config_override_values() {
         config.p2p.clone().unwrap().socks5_proxy,
         Some(p2p_socks5_proxy.to_owned())
     );
+    assert_eq!(
+        config.p2p.clone().unwrap().tor_control_address,
+        Some(p2p_tor_control_address.to_owned())
+    );
     assert_eq!(
         config.p2p.clone().unwrap().disable_noise,
         Some(p2p_disable_noise)
@@ -121,6 +121,9 @@ where
             SyncingEvent::Connected {
                 peer_id,
                 services: _,
+                local_services: _,
+                inbound: _,
+                protocol: _,
                 sync_rx,
             } => (peer_id, sync_rx),
             e => panic!("Unexpected event type: {e:?}"),
@@ -99,6 +99,9 @@ where
         SyncingEvent::Connected {
             peer_id: _,
             services: _,
+            local_services: _,
+            inbound: _,
+            protocol: _,
             sync_rx,
         } => sync_rx,
         event => panic!("Unexpected event: {event:?}"),
@@ -134,6 +137,9 @@ where
         SyncingEvent::Connected {
             peer_id: _,
             services: _,
+            local_services: _,
+            inbound: _,
+            protocol: _,
             sync_rx,
         } => sync_rx,
         event => panic!("Unexpected event: {event:?}"),
@@ -167,6 +173,7 @@ where
     let p2p_config = Arc::new(P2pConfig {
         bind_addresses: Vec::new(),
         socks5_proxy: None,
+        tor_control_address: None,
         disable_noise: Default::default(),
         boot_nodes: Vec::new(),
         reserved_nodes: Vec::new(),
@@ -30,7 +30,7 @@ use p2p::{
         types::services::Service, ConnectivityService, MessagingService, NetworkingService,
         SyncingEventReceiver,
     },
-    P2pEvent,
+    P2pEvent, P2pEventSubscription,
 };
 use utils::atomics::SeqCstAtomicBool;
 
@@ -65,10 +65,10 @@ where
     .unwrap();
 
     let (events_sender, mut events_receiver) = mpsc::unbounded_channel();
-    let handler = Arc::new(move |event| {
+    let handler = Arc::new(move |event: P2pEvent| {
         events_sender.send(event).unwrap();
     });
-    assert!(subscribers_sender.send(handler).is_ok());
+    assert!(subscribers_sender.send(P2pEventSubscription::Permanent(handler)).is_ok());
 
     let (shutdown_sender_2, shutdown_receiver) = oneshot::channel();
     let (_subscribers_sender, subscribers_receiver) = mpsc::unbounded_channel();
@@ -96,6 +96,7 @@ where
             inbound: _,
             user_agent: _,
             version: _,
+            node_key: _,
         }) => {
             assert_eq!(id, info.peer_id);
             assert_eq!(
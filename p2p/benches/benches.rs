@@ -35,7 +35,7 @@ pub fn peer_db(c: &mut Criterion) {
         PeerDb::<SocketAddr, IpAddr, _>::new(p2p_config, Default::default(), db_store).unwrap();
 
     for _ in 0..100000 {
-        peerdb.peer_discovered(TestTcpAddressMaker::new());
+        peerdb.peer_discovered(TestTcpAddressMaker::new(), None);
     }
 
     for _ in 0..1000 {
@@ -45,7 +45,7 @@ pub fn peer_db(c: &mut Criterion) {
     let normal_outbound = (0..5).map(|_| TestTcpAddressMaker::new()).collect::<BTreeSet<_>>();
 
     c.bench_function("PeerDb", |b| {
-        b.iter(|| peerdb.select_new_outbound_addresses(&normal_outbound))
+        b.iter(|| peerdb.select_new_outbound_addresses(&normal_outbound, 3))
     });
 }
 
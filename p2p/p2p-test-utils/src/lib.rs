@@ -66,7 +66,13 @@ pub fn start_subsystems_with_chainstate(
 
     let chainstate = manager.add_subsystem("p2p-test-chainstate", chainstate);
 
-    let mempool = mempool::make_mempool(chain_config, chainstate.clone(), Default::default());
+    let mempool = mempool::make_mempool(
+        chain_config,
+        Default::default(),
+        chainstate.clone(),
+        Default::default(),
+        None,
+    );
     let mempool = manager.add_subsystem_with_custom_eventloop("p2p-test-mempool", {
         move |call, shutdn| mempool.run(call, shutdn)
     });
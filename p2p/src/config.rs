@@ -72,14 +72,18 @@ pub struct P2pConfig {
     pub bind_addresses: Vec<String>,
     /// SOCKS5 proxy.
     pub socks5_proxy: Option<String>,
+    /// Tor control port address, used to create an onion service for inbound connections (requires socks5_proxy).
+    pub tor_control_address: Option<String>,
     /// Disable p2p encryption (for tests only).
     pub disable_noise: Option<bool>,
     /// Optional list of initial node addresses.
     /// Boot node addresses are added to PeerDb as regular discovered addresses.
+    /// An address can be prefixed with the expected node public key (`<pubkey>@<address>`).
     pub boot_nodes: Vec<String>,
     /// Optional list of reserved node addresses.
     /// PeerManager will try to maintain persistent connections to the reserved nodes.
     /// Ban scores are not adjusted for the reserved nodes.
+    /// An address can be prefixed with the expected node public key (`<pubkey>@<address>`).
     pub reserved_nodes: Vec<String>,
     /// Maximum allowed number of inbound connections.
     pub max_inbound_connections: MaxInboundConnections,
@@ -60,6 +60,14 @@ pub enum ProtocolError {
     DuplicatedTransactionAnnouncement(Id<Transaction>),
     #[error("Announced too many transactions (limit is {0})")]
     TransactionAnnouncementLimitExceeded(usize),
+    #[error("Number of transactions in inventory ({0}) exceeds allowed limit ({1})")]
+    TransactionInventoryLimitExceeded(usize, usize),
+    #[error("Compact block {0} has inconsistent transaction positions")]
+    InvalidCompactBlock(Id<Block>),
+    #[error("Requested transaction index {1} is out of range for block {0}")]
+    BlockTransactionIndexOutOfRange(Id<Block>, u32),
+    #[error("Expected {1} missing transactions of block {0}, received {2}")]
+    UnexpectedBlockTransactionsCount(Id<Block>, usize, usize),
 }
 
 /// Peer state errors (Errors either for an individual peer or for the [`PeerManager`](crate::peer_manager::PeerManager))
@@ -73,6 +81,8 @@ pub enum PeerError {
     PeerAlreadyExists,
     #[error("Address {0} is banned")]
     BannedAddress(String),
+    #[error("Node key {0} is banned")]
+    BannedNodeKey(String),
     #[error("PeerManager has too many peers")]
     TooManyPeers,
     #[error("Connection to address {0} already pending")]
@@ -94,6 +104,8 @@ pub enum DialError {
     IoError(std::io::ErrorKind),
     #[error("Proxy error: {0}")]
     ProxyError(String),
+    #[error("Remote node public key doesn't match the pinned one")]
+    NodeKeyMismatch,
 }
 
 /// Conversion errors
@@ -101,6 +113,8 @@ pub enum DialError {
 pub enum ConversionError {
     #[error("Invalid address: `{0}`")]
     InvalidAddress(String),
+    #[error("Invalid node public key: `{0}`")]
+    InvalidNodeKey(String),
     #[error("Failed to decode data: `{0}`")]
     DecodeError(serialization::Error),
 }
@@ -131,6 +145,8 @@ pub enum P2pError {
     InvalidStorageState(String),
     #[error("Mempool error: `{0}`")]
     MempoolError(#[from] MempoolError),
+    #[error("Tor control error: {0}")]
+    TorControlError(String),
 }
 
 impl From<DialError> for P2pError {
@@ -191,6 +207,7 @@ impl BanScore for P2pError {
             P2pError::InvalidConfigurationValue(_) => 0,
             P2pError::InvalidStorageState(_) => 0,
             P2pError::MempoolError(err) => err.mempool_ban_score(),
+            P2pError::TorControlError(_) => 0,
         }
     }
 }
@@ -213,6 +230,10 @@ impl BanScore for ProtocolError {
             ProtocolError::AddressListLimitExceeded => 100,
             ProtocolError::DuplicatedTransactionAnnouncement(_) => 20,
             ProtocolError::TransactionAnnouncementLimitExceeded(_) => 20,
+            ProtocolError::TransactionInventoryLimitExceeded(_, _) => 20,
+            ProtocolError::InvalidCompactBlock(_) => 20,
+            ProtocolError::BlockTransactionIndexOutOfRange(_, _) => 20,
+            ProtocolError::UnexpectedBlockTransactionsCount(_, _, _) => 20,
         }
     }
 }
@@ -221,6 +242,7 @@ impl BanScore for ConversionError {
     fn ban_score(&self) -> u32 {
         match self {
             ConversionError::InvalidAddress(_) => 0,
+            ConversionError::InvalidNodeKey(_) => 0,
             ConversionError::DecodeError(_) => 100,
         }
     }
@@ -16,7 +16,7 @@
 use std::sync::Arc;
 
 use common::chain::SignedTransaction;
-use p2p_types::p2p_event::P2pEvent;
+use p2p_types::p2p_event::{P2pEvent, WeakP2pEventHandler};
 
 use crate::{interface::types::ConnectedPeer, types::peer_id::PeerId};
 
@@ -41,4 +41,7 @@ pub trait P2pInterface: Send + Sync {
         &mut self,
         handler: Arc<dyn Fn(P2pEvent) + Send + Sync>,
     ) -> crate::Result<()>;
+
+    /// Subscribe to the p2p events until the handler is dropped by the caller
+    fn subscribe_to_events_weak(&mut self, handler: WeakP2pEventHandler) -> crate::Result<()>;
 }
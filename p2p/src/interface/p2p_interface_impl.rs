@@ -24,7 +24,7 @@ use crate::{
     net::NetworkingService,
     types::peer_id::PeerId,
     utils::oneshot_nofail,
-    MessagingService, P2p, P2pEvent, PeerManagerEvent,
+    MessagingService, P2p, P2pEvent, P2pEventSubscription, PeerManagerEvent, WeakP2pEventHandler,
 };
 
 #[async_trait::async_trait]
@@ -106,6 +106,10 @@ where
         &mut self,
         handler: Arc<dyn Fn(P2pEvent) + Send + Sync>,
     ) -> crate::Result<()> {
-        Ok(self.subscribers_sender.send(handler)?)
+        Ok(self.subscribers_sender.send(P2pEventSubscription::Permanent(handler))?)
+    }
+
+    fn subscribe_to_events_weak(&mut self, handler: WeakP2pEventHandler) -> crate::Result<()> {
+        Ok(self.subscribers_sender.send(P2pEventSubscription::Weak(handler))?)
     }
 }
@@ -20,7 +20,7 @@ use std::{
 
 use common::chain::SignedTransaction;
 
-use crate::{types::peer_id::PeerId, P2pEvent};
+use crate::{types::peer_id::PeerId, P2pEvent, WeakP2pEventHandler};
 
 use super::{p2p_interface::P2pInterface, types::ConnectedPeer};
 
@@ -69,4 +69,8 @@ impl<T: Deref<Target = dyn P2pInterface> + DerefMut<Target = dyn P2pInterface> +
     ) -> crate::Result<()> {
         self.deref_mut().subscribe_to_events(handler)
     }
+
+    fn subscribe_to_events_weak(&mut self, handler: WeakP2pEventHandler) -> crate::Result<()> {
+        self.deref_mut().subscribe_to_events_weak(handler)
+    }
 }
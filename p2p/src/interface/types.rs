@@ -28,6 +28,9 @@ pub struct ConnectedPeer {
 
     pub inbound: bool,
 
+    /// True for the outbound connections that only relay headers and blocks
+    pub block_relay_only: bool,
+
     pub ban_score: u32,
 
     pub user_agent: String,
@@ -18,6 +18,7 @@ pub mod error;
 pub mod interface;
 pub mod message;
 pub mod net;
+pub mod node_key;
 pub mod peer_manager;
 pub mod protocol;
 pub mod rpc;
@@ -31,7 +32,7 @@ pub use p2p_types as types;
 
 pub use crate::{
     peer_manager_event::PeerManagerEvent,
-    types::p2p_event::{P2pEvent, P2pEventHandler},
+    types::p2p_event::{P2pEvent, P2pEventHandler, P2pEventSubscription, WeakP2pEventHandler},
 };
 
 use std::{
@@ -66,11 +67,12 @@ use crate::{
     error::{ConversionError, P2pError},
     net::{
         default_backend::{
-            transport::{NoiseEncryptionAdapter, NoiseTcpTransport},
+            transport::{NodeKeypair, NoiseEncryptionAdapter, NoiseTcpTransport},
             DefaultNetworkingService,
         },
         ConnectivityService, MessagingService, NetworkingService, SyncingEventReceiver,
     },
+    types::peer_address::PeerAddress,
 };
 
 /// Result type with P2P errors
@@ -91,7 +93,7 @@ struct P2p<T: NetworkingService> {
     peer_manager_task: JoinHandle<()>,
     sync_manager_task: JoinHandle<()>,
 
-    subscribers_sender: mpsc::UnboundedSender<P2pEventHandler>,
+    subscribers_sender: mpsc::UnboundedSender<P2pEventSubscription>,
 }
 
 impl<T> P2p<T>
@@ -236,15 +238,19 @@ pub type P2pNetworkingService = DefaultNetworkingService<NoiseTcpTransport>;
 pub type P2pNetworkingServiceSocks5Proxy = DefaultNetworkingService<NoiseSocks5Transport>;
 pub type P2pNetworkingServiceUnencrypted = DefaultNetworkingService<TcpTransportSocket>;
 
-pub fn make_p2p_transport() -> NoiseTcpTransport {
-    let stream_adapter = NoiseEncryptionAdapter::gen_new();
+pub fn make_p2p_transport(node_key: NodeKeypair) -> NoiseTcpTransport {
+    let stream_adapter = NoiseEncryptionAdapter::new(node_key);
     let base_transport = TcpTransportSocket::new();
     NoiseTcpTransport::new(stream_adapter, base_transport)
 }
 
-pub fn make_p2p_transport_socks5_proxy(proxy: &str) -> NoiseSocks5Transport {
-    let stream_adapter = NoiseEncryptionAdapter::gen_new();
-    let base_transport = Socks5TransportSocket::new(proxy);
+pub fn make_p2p_transport_socks5_proxy(
+    proxy: &str,
+    tor_control: Option<&str>,
+    node_key: NodeKeypair,
+) -> NoiseSocks5Transport {
+    let stream_adapter = NoiseEncryptionAdapter::new(node_key);
+    let base_transport = Socks5TransportSocket::new(proxy, tor_control);
     NoiseSocks5Transport::new(stream_adapter, base_transport)
 }
 
@@ -256,6 +262,7 @@ fn get_p2p_bind_addresses<S: AsRef<str>>(
     bind_addresses: &[S],
     p2p_port: u16,
     proxy_used: bool,
+    onion_service_used: bool,
 ) -> Result<Vec<SocketAddr>> {
     if !bind_addresses.is_empty() {
         bind_addresses
@@ -277,6 +284,9 @@ fn get_p2p_bind_addresses<S: AsRef<str>>(
             SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), p2p_port),
             SocketAddr::new(Ipv6Addr::UNSPECIFIED.into(), p2p_port),
         ])
+    } else if onion_service_used {
+        // Tor forwards inbound onion connections to the local address
+        Ok(vec![SocketAddr::new(Ipv4Addr::LOCALHOST.into(), p2p_port)])
     } else {
         Ok(Vec::new())
     }
@@ -291,6 +301,7 @@ pub struct P2pInit<S: PeerDbStorage + 'static> {
     mempool_handle: MempoolHandle,
     time_getter: TimeGetter,
     peerdb_storage: S,
+    node_key: NodeKeypair,
     bind_addresses: Vec<SocketAddr>,
 }
 
@@ -303,6 +314,7 @@ impl<S: PeerDbStorage + 'static> P2pInit<S> {
             self.mempool_handle,
             self.time_getter,
             self.peerdb_storage,
+            self.node_key,
             self.bind_addresses,
             call,
             shutdown,
@@ -321,14 +333,23 @@ pub fn make_p2p<S: PeerDbStorage + 'static>(
     mempool_handle: MempoolHandle,
     time_getter: TimeGetter,
     peerdb_storage: S,
+    node_key: NodeKeypair,
 ) -> Result<P2pInit<S>> {
     // Perform some early checks to prevent a failure in the run method.
     let bind_addresses = get_p2p_bind_addresses(
         &p2p_config.bind_addresses,
         chain_config.p2p_port(),
         p2p_config.socks5_proxy.is_some(),
+        p2p_config.tor_control_address.is_some(),
     )?;
 
+    ensure!(
+        p2p_config.tor_control_address.is_none() || p2p_config.socks5_proxy.is_some(),
+        P2pError::InvalidConfigurationValue(
+            "Tor control port can only be used with SOCKS5 proxy".to_owned()
+        )
+    );
+
     if let Some(true) = p2p_config.disable_noise {
         ensure!(
             *chain_config.chain_type() == ChainType::Regtest,
@@ -351,6 +372,7 @@ pub fn make_p2p<S: PeerDbStorage + 'static>(
         mempool_handle,
         time_getter,
         peerdb_storage,
+        node_key,
         bind_addresses,
     })
 }
@@ -363,6 +385,7 @@ async fn run_p2p<S: PeerDbStorage + 'static>(
     mempool_handle: MempoolHandle,
     time_getter: TimeGetter,
     peerdb_storage: S,
+    node_key: NodeKeypair,
     bind_addresses: Vec<SocketAddr>,
     call: CallRequest<dyn P2pInterface>,
     shutdown: ShutdownRequest,
@@ -387,11 +410,15 @@ async fn run_p2p<S: PeerDbStorage + 'static>(
         .run(call, shutdown)
         .await;
     } else if let Some(socks5_proxy) = &p2p_config.socks5_proxy {
-        let transport = make_p2p_transport_socks5_proxy(socks5_proxy);
+        let transport = make_p2p_transport_socks5_proxy(
+            socks5_proxy,
+            p2p_config.tor_control_address.as_deref(),
+            node_key,
+        );
 
         P2p::<P2pNetworkingServiceSocks5Proxy>::new(
             transport,
-            bind_addresses,
+            bind_addresses.into_iter().map(PeerAddress::from).collect(),
             chain_config,
             p2p_config,
             chainstate_handle,
@@ -403,7 +430,7 @@ async fn run_p2p<S: PeerDbStorage + 'static>(
         .run(call, shutdown)
         .await;
     } else {
-        let transport = make_p2p_transport();
+        let transport = make_p2p_transport(node_key);
 
         P2p::<P2pNetworkingService>::new(
             transport,
@@ -16,10 +16,10 @@
 use chainstate::Locator;
 use common::{
     chain::{
-        block::{signed_block_header::SignedBlockHeader, Block},
+        block::{signed_block_header::SignedBlockHeader, Block, BlockReward},
         SignedTransaction, Transaction,
     },
-    primitives::Id,
+    primitives::{id, Id, Idable},
 };
 use serialization::{Decode, Encode};
 
@@ -32,8 +32,13 @@ pub enum SyncMessage {
     HeaderList(HeaderList),
     BlockResponse(BlockResponse),
     NewTransaction(Id<Transaction>),
+    TransactionInventory(TransactionInventory),
     TransactionRequest(Id<Transaction>),
     TransactionResponse(TransactionResponse),
+    CompactBlockRequest(Id<Block>),
+    CompactBlock(CompactBlock),
+    BlockTransactionsRequest(BlockTransactionsRequest),
+    BlockTransactions(BlockTransactions),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -119,6 +124,29 @@ impl HeaderList {
     }
 }
 
+/// A batch of transaction announcements.
+///
+/// New transactions are not announced immediately, but are collected and sent to each peer after
+/// a random delay, which makes it harder to find the node a transaction originates from.
+#[derive(Debug, Encode, Decode, Clone, PartialEq, Eq)]
+pub struct TransactionInventory {
+    transactions: Vec<Id<Transaction>>,
+}
+
+impl TransactionInventory {
+    pub fn new(transactions: Vec<Id<Transaction>>) -> Self {
+        Self { transactions }
+    }
+
+    pub fn transactions(&self) -> &[Id<Transaction>] {
+        &self.transactions
+    }
+
+    pub fn into_transactions(self) -> Vec<Id<Transaction>> {
+        self.transactions
+    }
+}
+
 #[derive(Debug, Encode, Decode, Clone, PartialEq, Eq)]
 pub struct BlockResponse {
     block: Box<Block>,
@@ -140,6 +168,182 @@ impl BlockResponse {
     }
 }
 
+/// A short transaction identifier used in compact blocks.
+///
+/// The identifier is salted with the block id, so it isn't possible to precompute collisions
+/// before the block is produced.
+#[derive(Debug, Encode, Decode, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ShortTxId([u8; 6]);
+
+impl ShortTxId {
+    pub fn new(block_id: &Id<Block>, tx_id: &Id<Transaction>) -> Self {
+        let hash = id::hash_encoded(&(block_id, tx_id));
+        let mut short_id = [0; 6];
+        short_id.copy_from_slice(&hash.as_bytes()[..6]);
+        Self(short_id)
+    }
+}
+
+/// A transaction sent as a part of the compact block.
+#[derive(Debug, Encode, Decode, Clone, PartialEq, Eq)]
+pub struct PrefilledTransaction {
+    /// The position of the transaction in the block.
+    index: u32,
+    transaction: SignedTransaction,
+}
+
+impl PrefilledTransaction {
+    pub fn new(index: u32, transaction: SignedTransaction) -> Self {
+        Self { index, transaction }
+    }
+
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    pub fn transaction(&self) -> &SignedTransaction {
+        &self.transaction
+    }
+
+    pub fn into_transaction(self) -> SignedTransaction {
+        self.transaction
+    }
+}
+
+/// A block with the transactions replaced by short identifiers.
+///
+/// The receiver is expected to restore the transactions from its mempool. The transactions that
+/// the receiver is unlikely to have are sent in full. The positions not taken by the prefilled
+/// transactions are occupied by the short ids in order.
+#[derive(Debug, Encode, Decode, Clone, PartialEq, Eq)]
+pub struct CompactBlock {
+    header: SignedBlockHeader,
+    block_reward: BlockReward,
+    short_ids: Vec<ShortTxId>,
+    prefilled_transactions: Vec<PrefilledTransaction>,
+}
+
+impl CompactBlock {
+    pub fn new(
+        header: SignedBlockHeader,
+        block_reward: BlockReward,
+        short_ids: Vec<ShortTxId>,
+        prefilled_transactions: Vec<PrefilledTransaction>,
+    ) -> Self {
+        Self {
+            header,
+            block_reward,
+            short_ids,
+            prefilled_transactions,
+        }
+    }
+
+    /// Creates a compact block sending in full the transactions for which `prefill` returns true.
+    pub fn from_block(block: &Block, mut prefill: impl FnMut(&SignedTransaction) -> bool) -> Self {
+        let block_id = block.get_id();
+        let mut short_ids = Vec::new();
+        let mut prefilled_transactions = Vec::new();
+        for (index, tx) in block.transactions().iter().enumerate() {
+            if prefill(tx) {
+                prefilled_transactions.push(PrefilledTransaction::new(index as u32, tx.clone()));
+            } else {
+                short_ids.push(ShortTxId::new(&block_id, &tx.transaction().get_id()));
+            }
+        }
+
+        Self {
+            header: block.header().clone(),
+            block_reward: block.block_reward().clone(),
+            short_ids,
+            prefilled_transactions,
+        }
+    }
+
+    pub fn header(&self) -> &SignedBlockHeader {
+        &self.header
+    }
+
+    pub fn block_reward(&self) -> &BlockReward {
+        &self.block_reward
+    }
+
+    pub fn short_ids(&self) -> &[ShortTxId] {
+        &self.short_ids
+    }
+
+    pub fn prefilled_transactions(&self) -> &[PrefilledTransaction] {
+        &self.prefilled_transactions
+    }
+
+    pub fn into_parts(
+        self,
+    ) -> (
+        SignedBlockHeader,
+        BlockReward,
+        Vec<ShortTxId>,
+        Vec<PrefilledTransaction>,
+    ) {
+        (
+            self.header,
+            self.block_reward,
+            self.short_ids,
+            self.prefilled_transactions,
+        )
+    }
+}
+
+/// A request for the transactions of a compact block that the node failed to restore.
+#[derive(Debug, Encode, Decode, Clone, PartialEq, Eq)]
+pub struct BlockTransactionsRequest {
+    block_id: Id<Block>,
+    tx_indexes: Vec<u32>,
+}
+
+impl BlockTransactionsRequest {
+    pub fn new(block_id: Id<Block>, tx_indexes: Vec<u32>) -> Self {
+        Self {
+            block_id,
+            tx_indexes,
+        }
+    }
+
+    pub fn block_id(&self) -> &Id<Block> {
+        &self.block_id
+    }
+
+    pub fn tx_indexes(&self) -> &[u32] {
+        &self.tx_indexes
+    }
+}
+
+/// The transactions requested by `BlockTransactionsRequest` in the same order.
+#[derive(Debug, Encode, Decode, Clone, PartialEq, Eq)]
+pub struct BlockTransactions {
+    block_id: Id<Block>,
+    transactions: Vec<SignedTransaction>,
+}
+
+impl BlockTransactions {
+    pub fn new(block_id: Id<Block>, transactions: Vec<SignedTransaction>) -> Self {
+        Self {
+            block_id,
+            transactions,
+        }
+    }
+
+    pub fn block_id(&self) -> &Id<Block> {
+        &self.block_id
+    }
+
+    pub fn transactions(&self) -> &[SignedTransaction] {
+        &self.transactions
+    }
+
+    pub fn into_transactions(self) -> Vec<SignedTransaction> {
+        self.transactions
+    }
+}
+
 #[derive(Debug, Encode, Decode, Clone, PartialEq, Eq)]
 pub enum TransactionResponse {
     #[codec(index = 0)]
@@ -17,7 +17,10 @@
 //!
 //! Every connected peer gets unique ID (generated locally from a counter).
 
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
 
 use futures::{future::BoxFuture, never::Never, stream::FuturesUnordered, FutureExt, StreamExt};
 use tokio::{
@@ -41,13 +44,15 @@ use crate::{
     net::{
         default_backend::{
             peer,
-            transport::{TransportListener, TransportSocket},
+            transport::{NodePublicKey, PeerStream, TransportListener, TransportSocket},
             types::{Command, Event, Message, PeerEvent},
         },
         types::{services::Services, ConnectivityEvent, PeerInfo, SyncingEvent},
     },
+    node_key::split_pinned_node_key,
+    protocol::{negotiated_protocol, NetworkProtocol},
     types::{peer_address::PeerAddress, peer_id::PeerId},
-    P2pEvent, P2pEventHandler,
+    P2pEvent, P2pEventSubscription,
 };
 
 use super::{
@@ -79,7 +84,16 @@ struct PeerContext<A> {
 
     version: SemVer,
 
+    /// Negotiated network protocol version
+    protocol: NetworkProtocol,
+
     services: Services,
+
+    /// Services advertised by this node to the peer
+    local_services: Services,
+
+    /// Static public key of the remote node (if the transport authenticates peers)
+    node_key: Option<NodePublicKey>,
 }
 
 /// Pending peer data (until handshake message is received)
@@ -90,7 +104,11 @@ struct PendingPeerContext<A> {
 
     peer_role: PeerRole,
 
+    local_services: Services,
+
     tx: mpsc::UnboundedSender<Event>,
+
+    node_key: Option<NodePublicKey>,
 }
 
 pub struct Backend<T: TransportSocket> {
@@ -106,6 +124,9 @@ pub struct Backend<T: TransportSocket> {
     /// A p2p specific configuration.
     p2p_config: Arc<P2pConfig>,
 
+    /// Expected public keys of the boot and reserved nodes, pinned in the configuration
+    pinned_node_keys: BTreeMap<T::Address, NodePublicKey>,
+
     time_getter: TimeGetter,
 
     /// RX channel for receiving commands from the frontend
@@ -138,7 +159,7 @@ pub struct Backend<T: TransportSocket> {
     shutdown_receiver: oneshot::Receiver<()>,
 
     events_controller: EventsController<P2pEvent>,
-    subscribers_receiver: mpsc::UnboundedReceiver<P2pEventHandler>,
+    subscribers_receiver: mpsc::UnboundedReceiver<P2pEventSubscription>,
 }
 
 impl<T> Backend<T>
@@ -157,8 +178,19 @@ where
         sync_tx: mpsc::UnboundedSender<SyncingEvent>,
         shutdown: Arc<SeqCstAtomicBool>,
         shutdown_receiver: oneshot::Receiver<()>,
-        subscribers_receiver: mpsc::UnboundedReceiver<P2pEventHandler>,
+        subscribers_receiver: mpsc::UnboundedReceiver<P2pEventSubscription>,
     ) -> Self {
+        // Invalid addresses are reported by PeerDb, so just skip them here
+        let pinned_node_keys = p2p_config
+            .boot_nodes
+            .iter()
+            .chain(p2p_config.reserved_nodes.iter())
+            .filter_map(|addr| {
+                let (node_key, address) = split_pinned_node_key(addr).ok()?;
+                Some((address.parse::<T::Address>().ok()?, node_key?))
+            })
+            .collect();
+
         Self {
             transport,
             socket,
@@ -166,6 +198,7 @@ where
             conn_tx,
             chain_config,
             p2p_config,
+            pinned_node_keys,
             time_getter,
             sync_tx,
             peers: HashMap::new(),
@@ -183,17 +216,37 @@ where
     fn handle_connect_res(
         &mut self,
         address: T::Address,
+        local_services_override: Option<Services>,
         connection_res: crate::Result<T::Stream>,
     ) -> crate::Result<()> {
         match connection_res {
             Ok(socket) => {
+                if let Some(pinned_key) = self.pinned_node_keys.get(&address) {
+                    let node_key = socket.remote_public_key();
+                    if node_key.as_ref() != Some(pinned_key) {
+                        log::warn!(
+                            "Refusing connection to {address:?}: expected node key {pinned_key}, got {}",
+                            node_key.map_or_else(|| "none".to_owned(), |key| key.to_string())
+                        );
+
+                        return Ok(self.conn_tx.send(ConnectivityEvent::ConnectionError {
+                            address,
+                            error: P2pError::DialError(DialError::NodeKeyMismatch),
+                        })?);
+                    }
+                }
+
                 let handshake_nonce = make_pseudo_rng().gen();
 
+                let local_services =
+                    local_services_override.unwrap_or_else(|| (*self.p2p_config.node_type).into());
+
                 self.create_pending_peer(
                     socket,
                     PeerId::new(),
                     PeerRole::Outbound { handshake_nonce },
                     address,
+                    local_services,
                 )
             }
             Err(err) => {
@@ -226,6 +279,9 @@ where
             SyncingEvent::Connected {
                 peer_id,
                 services: peer.services,
+                local_services: peer.local_services,
+                inbound: peer.inbound,
+                protocol: peer.protocol,
                 sync_rx,
             },
             &self.shutdown,
@@ -237,6 +293,7 @@ where
             inbound: peer.inbound,
             user_agent: peer.user_agent.clone(),
             version: peer.version,
+            node_key: peer.node_key.map(|key| key.to_string()),
         });
 
         Ok(())
@@ -289,6 +346,7 @@ where
                                 PeerId::new(),
                                 PeerRole::Inbound,
                                 address,
+                                (*self.p2p_config.node_type).into(),
                             )?;
                         },
                         Err(err) => {
@@ -297,8 +355,15 @@ where
                         },
                     }
                 }
-                handler = self.subscribers_receiver.recv() => {
-                    self.events_controller.subscribe_to_events(handler.ok_or(P2pError::ChannelClosed)?);
+                subscription = self.subscribers_receiver.recv() => {
+                    match subscription.ok_or(P2pError::ChannelClosed)? {
+                        P2pEventSubscription::Permanent(handler) => {
+                            self.events_controller.subscribe_to_events(handler)
+                        }
+                        P2pEventSubscription::Weak(handler) => {
+                            self.events_controller.subscribe_to_events_weak(handler)
+                        }
+                    }
                 }
                 _ = &mut self.shutdown_receiver => {
                     return Err(P2pError::ChannelClosed);
@@ -318,9 +383,12 @@ where
         remote_peer_id: PeerId,
         peer_role: PeerRole,
         address: T::Address,
+        local_services: Services,
     ) -> crate::Result<()> {
         let (peer_tx, peer_rx) = mpsc::unbounded_channel();
 
+        let node_key = socket.remote_public_key();
+
         // Sending the remote socket address makes no sense and can leak private information when using a proxy
         let receiver_address = if self.p2p_config.socks5_proxy.is_some() {
             None
@@ -335,6 +403,7 @@ where
             peer_role,
             Arc::clone(&self.chain_config),
             Arc::clone(&self.p2p_config),
+            local_services,
             socket,
             receiver_address,
             backend_tx,
@@ -356,7 +425,9 @@ where
                 handle,
                 address,
                 peer_role,
+                local_services,
                 tx: peer_tx,
+                node_key,
             },
         );
 
@@ -377,7 +448,9 @@ where
             handle,
             address,
             peer_role,
+            local_services,
             tx,
+            node_key,
         } = match self.pending.remove(&peer_id) {
             Some(pending) => pending,
             // Could be removed if self-connection was detected earlier
@@ -388,10 +461,17 @@ where
             return Ok(());
         }
 
+        // The key is known from the transport handshake, not from the peer's own claims
+        let peer_info = PeerInfo {
+            node_key,
+            ..peer_info
+        };
+
         let services = peer_info.services;
         let inbound = peer_role == PeerRole::Inbound;
         let user_agent = peer_info.user_agent.clone();
         let version = peer_info.version;
+        let protocol = negotiated_protocol(peer_info.protocol);
 
         match peer_role {
             PeerRole::Outbound { handshake_nonce: _ } => {
@@ -418,7 +498,10 @@ where
                 inbound,
                 user_agent,
                 version,
+                protocol,
                 services,
+                local_services,
+                node_key,
                 tx,
                 was_accepted: SetFlag::new(),
             },
@@ -515,6 +598,7 @@ where
                     version,
                     user_agent,
                     services,
+                    node_key: None,
                 },
                 receiver_address,
             ),
@@ -570,7 +654,10 @@ where
         // Because the second part depends on result of the first part boxed closures are used.
 
         match command {
-            Command::Connect { address } => {
+            Command::Connect {
+                address,
+                local_services_override,
+            } => {
                 let connection_fut = timeout(
                     *self.p2p_config.outbound_connection_timeout,
                     self.transport.connect(address.clone()),
@@ -581,7 +668,9 @@ where
                         DialError::ConnectionRefusedOrTimedOut,
                     )));
 
-                    boxed_cb(move |this| this.handle_connect_res(address, connection_res))
+                    boxed_cb(move |this| {
+                        this.handle_connect_res(address, local_services_override, connection_res)
+                    })
                 }
                 .boxed();
 
@@ -35,11 +35,11 @@ use crate::{
     message::{PeerManagerMessage, SyncMessage},
     net::{
         default_backend::transport::{TransportListener, TransportSocket},
-        types::{ConnectivityEvent, SyncingEvent},
+        types::{services::Services, ConnectivityEvent, SyncingEvent},
         ConnectivityService, MessagingService, NetworkingService, SyncingEventReceiver,
     },
     types::peer_id::PeerId,
-    P2pConfig, P2pEventHandler,
+    P2pConfig, P2pEventSubscription,
 };
 
 #[derive(Debug)]
@@ -115,7 +115,7 @@ impl<T: TransportSocket> NetworkingService for DefaultNetworkingService<T> {
         time_getter: TimeGetter,
         shutdown: Arc<SeqCstAtomicBool>,
         shutdown_receiver: oneshot::Receiver<()>,
-        subscribers_receiver: mpsc::UnboundedReceiver<P2pEventHandler>,
+        subscribers_receiver: mpsc::UnboundedReceiver<P2pEventSubscription>,
     ) -> crate::Result<(
         Self::ConnectivityHandle,
         Self::MessagingHandle,
@@ -169,13 +169,20 @@ where
     S: NetworkingService<Address = T::Address> + Send,
     T: TransportSocket,
 {
-    fn connect(&mut self, address: S::Address) -> crate::Result<()> {
+    fn connect(
+        &mut self,
+        address: S::Address,
+        local_services_override: Option<Services>,
+    ) -> crate::Result<()> {
         log::debug!(
             "try to establish outbound connection, address {:?}",
             address
         );
 
-        Ok(self.cmd_tx.send(types::Command::Connect { address })?)
+        Ok(self.cmd_tx.send(types::Command::Connect {
+            address,
+            local_services_override,
+        })?)
     }
 
     fn accept(&mut self, peer_id: PeerId) -> crate::Result<()> {
@@ -32,7 +32,7 @@ use crate::{
             transport::TransportSocket,
             types::{self, Event, PeerEvent},
         },
-        types::Role,
+        types::{services::Services, Role},
     },
     protocol::NETWORK_PROTOCOL_CURRENT,
     types::{peer_address::PeerAddress, peer_id::PeerId},
@@ -69,6 +69,9 @@ pub struct Peer<T: TransportSocket> {
 
     p2p_config: Arc<P2pConfig>,
 
+    /// Services advertised to the remote peer in the handshake
+    local_services: Services,
+
     /// Is the connection inbound or outbound
     peer_role: PeerRole,
 
@@ -95,6 +98,7 @@ where
         peer_role: PeerRole,
         chain_config: Arc<ChainConfig>,
         p2p_config: Arc<P2pConfig>,
+        local_services: Services,
         socket: T::Stream,
         receiver_address: Option<PeerAddress>,
         tx: mpsc::UnboundedSender<(PeerId, PeerEvent)>,
@@ -107,6 +111,7 @@ where
             peer_role,
             chain_config,
             p2p_config,
+            local_services,
             socket,
             receiver_address,
             tx,
@@ -178,7 +183,7 @@ where
                             network: *self.chain_config.magic_bytes(),
                             user_agent: self.p2p_config.user_agent.clone(),
                             version: *self.chain_config.version(),
-                            services: (*self.p2p_config.node_type).into(),
+                            services: self.local_services,
                             receiver_address: self.receiver_address.clone(),
                             current_time: local_time,
                         },
@@ -190,7 +195,7 @@ where
                     .send(types::Message::Handshake(types::HandshakeMessage::Hello {
                         protocol: NETWORK_PROTOCOL_CURRENT,
                         network: *self.chain_config.magic_bytes(),
-                        services: (*self.p2p_config.node_type).into(),
+                        services: self.local_services,
                         user_agent: self.p2p_config.user_agent.clone(),
                         version: *self.chain_config.version(),
                         receiver_address: self.receiver_address.clone(),
@@ -287,11 +292,24 @@ where
                 sync_tx.send(SyncMessage::TransactionRequest(v)).await?
             }
             Message::NewTransaction(v) => sync_tx.send(SyncMessage::NewTransaction(v)).await?,
+            Message::TransactionInventory(v) => {
+                sync_tx.send(SyncMessage::TransactionInventory(v)).await?
+            }
             Message::TransactionResponse(v) => {
                 sync_tx.send(SyncMessage::TransactionResponse(v)).await?
             }
             Message::HeaderList(v) => sync_tx.send(SyncMessage::HeaderList(v)).await?,
             Message::BlockResponse(v) => sync_tx.send(SyncMessage::BlockResponse(v)).await?,
+            Message::CompactBlockRequest(v) => {
+                sync_tx.send(SyncMessage::CompactBlockRequest(v)).await?
+            }
+            Message::CompactBlock(v) => sync_tx.send(SyncMessage::CompactBlock(v)).await?,
+            Message::BlockTransactionsRequest(v) => {
+                sync_tx.send(SyncMessage::BlockTransactionsRequest(v)).await?
+            }
+            Message::BlockTransactions(v) => {
+                sync_tx.send(SyncMessage::BlockTransactions(v)).await?
+            }
         }
 
         Ok(())
@@ -383,6 +401,7 @@ mod tests {
             PeerRole::Inbound,
             Arc::clone(&chain_config),
             Arc::clone(&p2p_config),
+            (*p2p_config.node_type).into(),
             socket1,
             None,
             tx1,
@@ -457,6 +476,7 @@ mod tests {
             PeerRole::Outbound { handshake_nonce: 1 },
             Arc::clone(&chain_config),
             Arc::clone(&p2p_config),
+            (*p2p_config.node_type).into(),
             socket1,
             None,
             tx1,
@@ -535,6 +555,7 @@ mod tests {
             PeerRole::Inbound,
             Arc::clone(&chain_config),
             Arc::clone(&p2p_config),
+            (*p2p_config.node_type).into(),
             socket1,
             None,
             tx1,
@@ -595,6 +616,7 @@ mod tests {
             PeerRole::Inbound,
             chain_config,
             Arc::clone(&p2p_config),
+            (*p2p_config.node_type).into(),
             socket1,
             None,
             tx1,
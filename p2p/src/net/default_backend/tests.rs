@@ -14,17 +14,22 @@
 // limitations under the License.
 
 use super::{transport::NoiseTcpTransport, *};
-use crate::config::NodeType;
+use crate::config::{NodeType, P2pConfig};
 use crate::error::DialError;
 use crate::protocol::NETWORK_PROTOCOL_CURRENT;
 use crate::testing_utils::{
     test_p2p_config, TestTransportChannel, TestTransportMaker, TestTransportTcp,
 };
 use crate::{
-    net::default_backend::transport::{MpscChannelTransport, TcpTransportSocket},
+    net::default_backend::transport::{
+        MpscChannelTransport, NodeKeypair, NodePublicKey, NoiseEncryptionAdapter,
+        TcpTransportSocket,
+    },
     testing_utils::TestTransportNoise,
 };
+use common::chain::ChainConfig;
 use std::fmt::Debug;
+use std::net::SocketAddr;
 use std::time::Duration;
 use tokio::io::AsyncWriteExt;
 use tokio::time::timeout;
@@ -70,7 +75,7 @@ where
     .unwrap();
 
     let addr = conn2.local_addresses();
-    conn1.connect(addr[0].clone()).unwrap();
+    conn1.connect(addr[0].clone(), None).unwrap();
 
     if let Ok(ConnectivityEvent::OutboundAccepted {
         address,
@@ -144,7 +149,7 @@ where
     .unwrap();
 
     let bind_address = conn2.local_addresses();
-    conn1.connect(bind_address[0].clone()).unwrap();
+    conn1.connect(bind_address[0].clone(), None).unwrap();
     let res2 = conn2.poll_next().await;
     match res2.unwrap() {
         ConnectivityEvent::InboundAccepted {
@@ -215,7 +220,7 @@ where
     .await
     .unwrap();
 
-    conn1.connect(conn2.local_addresses()[0].clone()).unwrap();
+    conn1.connect(conn2.local_addresses()[0].clone(), None).unwrap();
     let res2 = conn2.poll_next().await;
 
     match res2.unwrap() {
@@ -287,7 +292,7 @@ where
 
     // Try connect to self
     let addr = conn1.local_addresses();
-    conn1.connect(addr[0].clone()).unwrap();
+    conn1.connect(addr[0].clone(), None).unwrap();
 
     // ConnectionError should be reported
     if let Ok(ConnectivityEvent::ConnectionError { address, error }) = conn1.poll_next().await {
@@ -299,7 +304,7 @@ where
 
     // Check that we can still connect normally after
     let addr = conn2.local_addresses();
-    conn1.connect(addr[0].clone()).unwrap();
+    conn1.connect(addr[0].clone(), None).unwrap();
     if let Ok(ConnectivityEvent::OutboundAccepted {
         address,
         peer_info,
@@ -365,7 +370,7 @@ where
     .unwrap();
 
     // Try to connect to some broken peer
-    conn.connect(addr[0].clone()).unwrap();
+    conn.connect(addr[0].clone(), None).unwrap();
     // `ConnectionError` should be reported
     let event = timeout(Duration::from_secs(60), conn.poll_next()).await.unwrap().unwrap();
 
@@ -391,3 +396,82 @@ async fn invalid_outbound_peer_connect_channels() {
 async fn invalid_outbound_peer_connect_noise() {
     invalid_outbound_peer_connect::<TestTransportNoise, NoiseTcpTransport>().await;
 }
+
+/// Connect to the node with the address, with the expected node key pinned in the configuration
+async fn connect_with_pinned_node_key(
+    config: Arc<ChainConfig>,
+    address: SocketAddr,
+    pinned_node_key: NodePublicKey,
+) -> ConnectivityEvent<SocketAddr> {
+    let p2p_config = Arc::new(P2pConfig {
+        reserved_nodes: vec![format!("{pinned_node_key}@{address}")],
+        ..test_p2p_config()
+    });
+    let shutdown = Arc::new(SeqCstAtomicBool::new(false));
+    let (_shutdown_sender, shutdown_receiver) = oneshot::channel();
+    let (_subscribers_sender, subscribers_receiver) = mpsc::unbounded_channel();
+    let (mut conn, _, _, _) = DefaultNetworkingService::<NoiseTcpTransport>::start(
+        TestTransportNoise::make_transport(),
+        vec![],
+        config,
+        p2p_config,
+        TimeGetter::default(),
+        shutdown,
+        shutdown_receiver,
+        subscribers_receiver,
+    )
+    .await
+    .unwrap();
+
+    conn.connect(address, None).unwrap();
+    timeout(Duration::from_secs(60), conn.poll_next()).await.unwrap().unwrap()
+}
+
+#[tokio::test]
+async fn pinned_node_key_noise() {
+    let config = Arc::new(common::chain::config::create_mainnet());
+    let node_key = NodeKeypair::generate();
+    let node_public_key = *node_key.public_key();
+
+    let (_shutdown_sender, shutdown_receiver) = oneshot::channel();
+    let (_subscribers_sender, subscribers_receiver) = mpsc::unbounded_channel();
+    let (conn, _, _, _) = DefaultNetworkingService::<NoiseTcpTransport>::start(
+        NoiseTcpTransport::new(
+            NoiseEncryptionAdapter::new(node_key),
+            TcpTransportSocket::new(),
+        ),
+        vec![TestTransportNoise::make_address()],
+        Arc::clone(&config),
+        Arc::new(test_p2p_config()),
+        TimeGetter::default(),
+        Arc::new(SeqCstAtomicBool::new(false)),
+        shutdown_receiver,
+        subscribers_receiver,
+    )
+    .await
+    .unwrap();
+    let address = conn.local_addresses()[0];
+
+    // The node presents the pinned key
+    match connect_with_pinned_node_key(Arc::clone(&config), address, node_public_key).await {
+        ConnectivityEvent::OutboundAccepted {
+            address: accepted_address,
+            peer_info: _,
+            receiver_address: _,
+        } => assert_eq!(accepted_address, address),
+        event => panic!("invalid event received: {event:?}"),
+    }
+
+    // The node presents a different key than the pinned one
+    let other_public_key = *NodeKeypair::generate().public_key();
+    match connect_with_pinned_node_key(config, address, other_public_key).await {
+        ConnectivityEvent::ConnectionError {
+            address: failed_address,
+            error,
+        } => {
+            assert_eq!(failed_address, address);
+            assert_eq!(error, P2pError::DialError(DialError::NodeKeyMismatch));
+        }
+        event => panic!("invalid event received: {event:?}"),
+    }
+}
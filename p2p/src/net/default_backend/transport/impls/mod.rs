@@ -17,3 +17,4 @@ pub mod channel;
 pub mod socks5;
 pub mod stream_adapter;
 pub mod tcp;
+pub mod tor_control;
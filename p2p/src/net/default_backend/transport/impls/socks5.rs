@@ -14,46 +14,91 @@
 // limitations under the License.
 
 use std::{
-    net::{IpAddr, SocketAddr},
+    collections::BTreeSet,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
     sync::Arc,
 };
 
 use async_trait::async_trait;
 use futures::future::BoxFuture;
 use tokio::net::TcpStream;
-use tokio_socks::tcp::Socks5Stream;
+use tokio_socks::{tcp::Socks5Stream, TargetAddr};
+
+use logging::log;
 
 use crate::{
     error::{DialError, P2pError},
-    net::default_backend::transport::{PeerStream, TransportListener, TransportSocket},
+    net::{
+        default_backend::transport::{
+            traits::TransportAddress, TransportListener, TransportSocket,
+        },
+        AsBannableAddress,
+    },
+    types::peer_address::{BannablePeerAddress, PeerAddress, PeerAddressOnion3},
     Result,
 };
 
+use super::{
+    tcp::{TcpTransportListener, TcpTransportSocket, TcpTransportStream},
+    tor_control::TorControl,
+};
+
 // TODO: Add tests. A typical way to test this is to create a forwarding proxy with a socks interface
 // that can test that things sent through the proxy are delivered to the other end.
 
+impl TransportAddress for PeerAddress {
+    fn as_peer_address(&self) -> PeerAddress {
+        self.clone()
+    }
+
+    fn from_peer_address(address: &PeerAddress, allow_private_ips: bool) -> Option<Self> {
+        match address {
+            PeerAddress::Ip4(_) | PeerAddress::Ip6(_) => {
+                <SocketAddr as TransportAddress>::from_peer_address(address, allow_private_ips)
+                    .map(Into::into)
+            }
+            PeerAddress::Onion3(onion) if onion.port != 0 => Some(address.clone()),
+            PeerAddress::Onion3(_) => None,
+        }
+    }
+}
+
+impl AsBannableAddress for PeerAddress {
+    type BannableAddress = BannablePeerAddress;
+
+    fn as_bannable(&self) -> Self::BannableAddress {
+        self.into()
+    }
+}
+
 #[derive(Debug)]
 pub struct Socks5TransportSocket {
     proxy: Arc<String>,
+    tor_control: Option<String>,
 }
 
 impl Socks5TransportSocket {
-    pub fn new(proxy: &str) -> Self {
+    /// Creates a new transport that connects through the SOCKS5 proxy.
+    ///
+    /// Listening is only possible if the Tor control port address is specified: an ephemeral
+    /// onion service is created for the bind addresses in that case.
+    pub fn new(proxy: &str, tor_control: Option<&str>) -> Self {
         Self {
             proxy: Arc::new(proxy.to_owned()),
+            tor_control: tor_control.map(ToOwned::to_owned),
         }
     }
 }
 
 #[async_trait]
 impl TransportSocket for Socks5TransportSocket {
-    type Address = SocketAddr;
-    type BannableAddress = IpAddr;
+    type Address = PeerAddress;
+    type BannableAddress = BannablePeerAddress;
     type Listener = Socks5TransportListener;
     type Stream = Socks5TransportStream;
 
     async fn bind(&self, addresses: Vec<Self::Address>) -> Result<Self::Listener> {
-        Socks5TransportListener::new(addresses)
+        Socks5TransportListener::new(addresses, self.tor_control.as_deref()).await
     }
 
     fn connect(&self, address: Self::Address) -> BoxFuture<'static, Result<Self::Stream>> {
@@ -63,43 +108,118 @@ impl TransportSocket for Socks5TransportSocket {
                 DialError::ProxyError(format!("Connection to the SOCKS5 proxy failed: {e}"))
             })?;
 
-            let stream = Socks5Stream::connect_with_socket(socket, address)
+            let target = match address {
+                PeerAddress::Ip4(socket4) => TargetAddr::Ip(SocketAddr::new(
+                    Ipv4Addr::from(socket4.ip).into(),
+                    socket4.port,
+                )),
+                PeerAddress::Ip6(socket6) => TargetAddr::Ip(SocketAddr::new(
+                    Ipv6Addr::from(socket6.ip).into(),
+                    socket6.port,
+                )),
+                // Onion addresses are resolved by the proxy
+                PeerAddress::Onion3(onion) => {
+                    TargetAddr::Domain(onion.address.to_string().into(), onion.port)
+                }
+            };
+
+            let stream = Socks5Stream::connect_with_socket(socket, target)
                 .await
                 .map_err(|e| DialError::ProxyError(format!("Unexpected SOCKS5 error: {e}")))?;
 
-            Ok(stream)
+            // After the handshake the proxy just forwards the data, so the stream can be used directly
+            Ok(stream.into_inner())
         })
     }
 }
 
-pub struct Socks5TransportListener {}
+pub struct Socks5TransportListener {
+    listener: TcpTransportListener,
+    onion_addresses: Vec<PeerAddress>,
+    /// Tor removes the onion service when the control connection is closed.
+    _tor_control: Option<TorControl>,
+}
 
 impl Socks5TransportListener {
-    fn new(addresses: Vec<SocketAddr>) -> Result<Self> {
-        utils::ensure!(
-            addresses.is_empty(),
+    async fn new(addresses: Vec<PeerAddress>, tor_control: Option<&str>) -> Result<Self> {
+        let addresses = addresses
+            .iter()
+            .map(|address| {
+                address.as_socket_address().ok_or_else(|| {
+                    P2pError::InvalidConfigurationValue(format!("Can't listen on {address}"))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if addresses.is_empty() {
+            return Ok(Self {
+                listener: TcpTransportSocket::new().bind(Vec::new()).await?,
+                onion_addresses: Vec::new(),
+                _tor_control: None,
+            });
+        }
+
+        let tor_control = tor_control.ok_or_else(|| {
             P2pError::InvalidConfigurationValue(
-                "Listening with socks5 proxy not implemented".to_owned()
-            ),
-        );
-        Ok(Self {})
+                "Listening with socks5 proxy requires the Tor control port".to_owned(),
+            )
+        })?;
+
+        let listener = TcpTransportSocket::new().bind(addresses).await?;
+
+        // Use the actual local addresses because the bind port could be 0
+        let ports = listener
+            .local_addresses()?
+            .into_iter()
+            .map(|address| {
+                let target_ip = match address.ip() {
+                    IpAddr::V4(ip) if ip.is_unspecified() => Ipv4Addr::LOCALHOST.into(),
+                    IpAddr::V6(ip) if ip.is_unspecified() => Ipv6Addr::LOCALHOST.into(),
+                    ip => ip,
+                };
+                (address.port(), SocketAddr::new(target_ip, address.port()))
+            })
+            .collect::<Vec<_>>();
+
+        let mut control = TorControl::connect(tor_control).await?;
+        let onion = control.add_onion(&ports).await?;
+        log::info!("Onion service is created: {onion}");
+
+        let onion_addresses = ports
+            .iter()
+            .map(|(port, _target)| {
+                PeerAddress::Onion3(PeerAddressOnion3 {
+                    address: onion,
+                    port: *port,
+                })
+            })
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+
+        Ok(Self {
+            listener,
+            onion_addresses,
+            _tor_control: Some(control),
+        })
     }
 }
 
 #[async_trait]
 impl TransportListener for Socks5TransportListener {
     type Stream = Socks5TransportStream;
-    type Address = SocketAddr;
+    type Address = PeerAddress;
 
-    async fn accept(&mut self) -> Result<(Socks5TransportStream, SocketAddr)> {
-        std::future::pending().await
+    async fn accept(&mut self) -> Result<(Socks5TransportStream, PeerAddress)> {
+        // Inbound connections come from the local Tor daemon
+        let (stream, address) = self.listener.accept().await?;
+        Ok((stream, address.into()))
     }
 
-    fn local_addresses(&self) -> Result<Vec<SocketAddr>> {
-        Ok(Vec::new())
+    fn local_addresses(&self) -> Result<Vec<PeerAddress>> {
+        // Only the onion addresses can be reached by other nodes
+        Ok(self.onion_addresses.clone())
     }
 }
 
-pub type Socks5TransportStream = Socks5Stream<TcpStream>;
-
-impl PeerStream for Socks5TransportStream {}
+pub type Socks5TransportStream = TcpTransportStream;
@@ -13,14 +13,16 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{sync::Arc, time::Duration};
+use std::{str::FromStr, sync::Arc, time::Duration};
 
 use futures::future::BoxFuture;
 use snowstorm::NoiseStream;
 use tokio::time::timeout;
 
+use serialization::{Decode, Encode};
+
 use crate::{
-    error::P2pError,
+    error::{ConversionError, P2pError},
     net::{default_backend::transport::PeerStream, types::Role},
 };
 
@@ -34,25 +36,89 @@ static NOISE_HANDSHAKE_PATTERN: &str = "Noise_XX_25519_ChaChaPoly_SHA256";
 static NOISE_HANDSHAKE_PARAMS: once_cell::sync::Lazy<snowstorm::NoiseParams> =
     once_cell::sync::Lazy::new(|| NOISE_HANDSHAKE_PATTERN.parse().expect("valid pattern"));
 
+/// Length of the X25519 keys used in the noise handshake
+const NODE_KEY_LEN: usize = 32;
+
+/// Static public key that identifies a node in the noise handshake
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Encode, Decode)]
+pub struct NodePublicKey([u8; NODE_KEY_LEN]);
+
+impl NodePublicKey {
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        bytes.try_into().ok().map(Self)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for NodePublicKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&hex::encode(self.0))
+    }
+}
+
+impl FromStr for NodePublicKey {
+    type Err = P2pError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        hex::decode(s)
+            .ok()
+            .and_then(|bytes| Self::from_bytes(&bytes))
+            .ok_or_else(|| P2pError::ConversionError(ConversionError::InvalidNodeKey(s.to_owned())))
+    }
+}
+
+/// Static keypair of the local node, used to authenticate it in the noise handshake
+#[derive(Clone, Encode, Decode)]
+pub struct NodeKeypair {
+    private_key: [u8; NODE_KEY_LEN],
+    public_key: NodePublicKey,
+}
+
+impl NodeKeypair {
+    pub fn generate() -> Self {
+        let keypair = snowstorm::Builder::new(NOISE_HANDSHAKE_PARAMS.clone())
+            .generate_keypair()
+            .expect("key generation must succeed");
+        Self {
+            private_key: keypair.private.try_into().expect("valid private key length"),
+            public_key: NodePublicKey::from_bytes(&keypair.public)
+                .expect("valid public key length"),
+        }
+    }
+
+    pub fn public_key(&self) -> &NodePublicKey {
+        &self.public_key
+    }
+}
+
+impl std::fmt::Debug for NodeKeypair {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Do not leak the private key into logs
+        f.debug_struct("NodeKeypair").field("public_key", &self.public_key).finish()
+    }
+}
+
 #[derive(Clone)]
 pub struct NoiseEncryptionAdapter {
-    local_key: Arc<snowstorm::Keypair>,
+    local_key: Arc<NodeKeypair>,
     handshake_timeout: Duration,
 }
 
 impl NoiseEncryptionAdapter {
-    pub fn gen_new() -> Self {
-        let local_key = Arc::new(
-            snowstorm::Builder::new(NOISE_HANDSHAKE_PARAMS.clone())
-                .generate_keypair()
-                .expect("key generation must succeed"),
-        );
+    pub fn new(local_key: NodeKeypair) -> Self {
         Self {
-            local_key,
+            local_key: Arc::new(local_key),
             handshake_timeout: DEFAULT_HANDSHAKE_TIMEOUT,
         }
     }
 
+    pub fn gen_new() -> Self {
+        Self::new(NodeKeypair::generate())
+    }
+
     pub fn with_handshake_timeout(self, handshake_timeout: Duration) -> Self {
         Self {
             local_key: self.local_key,
@@ -76,7 +142,7 @@ impl<T: PeerStream + 'static> StreamAdapter<T> for NoiseEncryptionAdapter {
         let handshake_timeout = self.handshake_timeout;
         Box::pin(async move {
             let builder = snowstorm::Builder::new(NOISE_HANDSHAKE_PARAMS.clone())
-                .local_private_key(&local_key.private);
+                .local_private_key(&local_key.private_key);
             let state = match role {
                 Role::Outbound => builder.build_initiator(),
                 Role::Inbound => builder.build_responder(),
@@ -96,4 +162,8 @@ impl<T: PeerStream + 'static> StreamAdapter<T> for NoiseEncryptionAdapter {
     }
 }
 
-impl<T: PeerStream> PeerStream for NoiseStream<T> {}
+impl<T: PeerStream> PeerStream for NoiseStream<T> {
+    fn remote_public_key(&self) -> Option<NodePublicKey> {
+        self.get_state().get_remote_static().and_then(NodePublicKey::from_bytes)
+    }
+}
@@ -40,7 +40,7 @@ use crate::{
         transport::{
             impls::stream_adapter::wrapped_transport::wrapped_listener::MAX_CONCURRENT_HANDSHAKES,
             BufferedTranscoder, ChannelListener, IdentityStreamAdapter, MpscChannelTransport,
-            NoiseEncryptionAdapter, PeerStream, TcpTransportSocket, TransportListener,
+            NodeKeypair, NoiseEncryptionAdapter, PeerStream, TcpTransportSocket, TransportListener,
             TransportSocket,
         },
         types::Message,
@@ -119,6 +119,44 @@ async fn test_send_recv() {
     .await;
 }
 
+#[tokio::test]
+async fn remote_node_keys() {
+    let server_key = NodeKeypair::generate();
+    let client_key = NodeKeypair::generate();
+    let server_transport = WrappedTransportSocket::new(
+        NoiseEncryptionAdapter::new(server_key.clone()),
+        TcpTransportSocket::new(),
+    );
+    let client_transport = WrappedTransportSocket::new(
+        NoiseEncryptionAdapter::new(client_key.clone()),
+        TcpTransportSocket::new(),
+    );
+
+    let mut server = server_transport.bind(vec![TestTransportTcp::make_address()]).await.unwrap();
+    let peer_fut = client_transport.connect(server.local_addresses().unwrap()[0]);
+
+    let (server_res, peer_res) = tokio::join!(server.accept(), peer_fut);
+    let server_stream = server_res.unwrap().0;
+    let peer_stream = peer_res.unwrap();
+
+    assert_eq!(
+        server_stream.remote_public_key().as_ref(),
+        Some(client_key.public_key())
+    );
+    assert_eq!(
+        peer_stream.remote_public_key().as_ref(),
+        Some(server_key.public_key())
+    );
+
+    // Unencrypted streams can't identify the remote node
+    let transport = TcpTransportSocket::new();
+    let mut server = transport.bind(vec![TestTransportTcp::make_address()]).await.unwrap();
+    let peer_fut = transport.connect(server.local_addresses().unwrap()[0]);
+    let (server_res, peer_res) = tokio::join!(server.accept(), peer_fut);
+    assert_eq!(server_res.unwrap().0.remote_public_key(), None);
+    assert_eq!(peer_res.unwrap().remote_public_key(), None);
+}
+
 pub struct TestTransport {
     transport: MpscChannelTransport,
     port_open: Arc<Mutex<bool>>,
@@ -41,13 +41,13 @@ impl TransportAddress for SocketAddr {
                 if (Ipv4Addr::from(socket.ip).is_global_unicast_ip() || allow_private_ips)
                     && socket.port != 0 =>
             {
-                Some(address.into())
+                address.as_socket_address()
             }
             PeerAddress::Ip6(socket)
                 if (Ipv6Addr::from(socket.ip).is_global_unicast_ip() || allow_private_ips)
                     && socket.port != 0 =>
             {
-                Some(address.into())
+                address.as_socket_address()
             }
             _ => None,
         }
@@ -0,0 +1,318 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A minimal client for the Tor control protocol (see `control-spec.txt` in the Tor repository).
+//!
+//! It's used to create an ephemeral onion service, so the node can accept inbound connections
+//! over Tor.
+
+use std::net::SocketAddr;
+
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::TcpStream,
+};
+
+use crate::{error::P2pError, types::onion_address::OnionV3Address, Result};
+
+const STATUS_OK: &str = "250";
+
+fn control_error(message: impl Into<String>) -> P2pError {
+    P2pError::TorControlError(message.into())
+}
+
+/// A connection to the Tor control port.
+///
+/// Tor removes the onion services created with `ADD_ONION` when the control connection is
+/// closed, so the connection must be kept open while the services are used.
+pub struct TorControl {
+    stream: BufReader<TcpStream>,
+}
+
+impl TorControl {
+    /// Connects to the control port and authenticates.
+    ///
+    /// The `NULL` and `COOKIE` authentication methods are supported.
+    pub async fn connect(address: &str) -> Result<Self> {
+        let stream = TcpStream::connect(address).await.map_err(|e| {
+            control_error(format!("Connection to the Tor control port failed: {e}"))
+        })?;
+        let mut control = Self {
+            stream: BufReader::new(stream),
+        };
+        control.authenticate().await?;
+        Ok(control)
+    }
+
+    async fn authenticate(&mut self) -> Result<()> {
+        let reply = self.command("PROTOCOLINFO 1").await?;
+        let auth_line = reply
+            .iter()
+            .find_map(|line| line.strip_prefix("AUTH "))
+            .ok_or_else(|| control_error("Missing AUTH line in the PROTOCOLINFO reply"))?
+            .to_owned();
+        let methods = auth_line
+            .split(' ')
+            .find_map(|item| item.strip_prefix("METHODS="))
+            .ok_or_else(|| control_error("Missing authentication methods"))?
+            .split(',')
+            .collect::<Vec<_>>();
+
+        if methods.contains(&"NULL") {
+            self.command("AUTHENTICATE").await?;
+        } else if methods.contains(&"COOKIE") {
+            let cookie_file = parse_cookie_file(&auth_line)
+                .ok_or_else(|| control_error("Missing authentication cookie file"))?;
+            let cookie = tokio::fs::read(&cookie_file).await.map_err(|e| {
+                control_error(format!("Failed to read the cookie file {cookie_file}: {e}"))
+            })?;
+            self.command(&format!("AUTHENTICATE {}", hex::encode(cookie))).await?;
+        } else {
+            return Err(control_error(format!(
+                "Unsupported authentication methods: {}",
+                methods.join(",")
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Creates a new ephemeral onion service.
+    ///
+    /// Connections to each virtual port of the service are forwarded to the corresponding
+    /// target address. The private key of the service is discarded, so a new onion address is
+    /// used after every restart.
+    pub async fn add_onion(&mut self, ports: &[(u16, SocketAddr)]) -> Result<OnionV3Address> {
+        let ports = ports
+            .iter()
+            .map(|(virtual_port, target)| format!(" Port={virtual_port},{target}"))
+            .collect::<String>();
+        let reply = self
+            .command(&format!("ADD_ONION NEW:ED25519-V3 Flags=DiscardPK{ports}"))
+            .await?;
+
+        let service_id = reply
+            .iter()
+            .find_map(|line| line.strip_prefix("ServiceID="))
+            .ok_or_else(|| control_error("Missing ServiceID in the ADD_ONION reply"))?;
+        OnionV3Address::from_service_id(service_id)
+            .map_err(|e| control_error(format!("Invalid service id {service_id}: {e}")))
+    }
+
+    /// Sends a command and returns the reply lines (without the status codes).
+    ///
+    /// Returns an error if the command has failed.
+    async fn command(&mut self, command: &str) -> Result<Vec<String>> {
+        self.stream
+            .get_mut()
+            .write_all(format!("{command}\r\n").as_bytes())
+            .await
+            .map_err(|e| control_error(format!("Failed to send a command: {e}")))?;
+
+        let mut lines = Vec::new();
+        loop {
+            let line = self.read_line().await?;
+
+            // Each reply line starts with a status code followed by a separator: '-' for the
+            // intermediate lines, '+' for the data lines and ' ' for the last line.
+            if line.get(..3) != Some(STATUS_OK) {
+                return Err(control_error(format!("Tor control command failed: {line}")));
+            }
+            let separator = line.get(3..4);
+            let text = line.get(4..).unwrap_or_default().to_owned();
+
+            match separator {
+                Some(" ") => {
+                    lines.push(text);
+                    return Ok(lines);
+                }
+                Some("-") => lines.push(text),
+                Some("+") => {
+                    // The data is terminated by a line with a single dot
+                    lines.push(text);
+                    loop {
+                        let data = self.read_line().await?;
+                        if data == "." {
+                            break;
+                        }
+                        lines.push(data);
+                    }
+                }
+                _ => return Err(control_error(format!("Invalid reply line: {line}"))),
+            }
+        }
+    }
+
+    async fn read_line(&mut self) -> Result<String> {
+        let mut line = String::new();
+        let size = self
+            .stream
+            .read_line(&mut line)
+            .await
+            .map_err(|e| control_error(format!("Failed to read a reply: {e}")))?;
+        utils::ensure!(size != 0, control_error("The control connection is closed"));
+        Ok(line.trim_end_matches(&['\r', '\n'][..]).to_owned())
+    }
+}
+
+/// Extracts the `COOKIEFILE` value (a quoted string) from the `AUTH` line.
+fn parse_cookie_file(auth_line: &str) -> Option<String> {
+    let (_, quoted) = auth_line.split_once("COOKIEFILE=\"")?;
+
+    let mut result = String::new();
+    let mut chars = quoted.chars();
+    loop {
+        match chars.next()? {
+            '"' => return Some(result),
+            '\\' => result.push(chars.next()?),
+            c => result.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    const SERVICE_ID: &str = "duckduckgogg42xjoc72x3sjasowoarfbgcmvfimaftt6twagswzczad";
+
+    /// A stand-in for the Tor control port.
+    ///
+    /// Answers `PROTOCOLINFO`, `AUTHENTICATE` and `ADD_ONION` and returns the received commands
+    /// when the connection is closed.
+    async fn run_control_port(
+        listener: TcpListener,
+        auth_line: String,
+        cookie: Option<Vec<u8>>,
+    ) -> Vec<String> {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut stream = BufReader::new(stream);
+        let mut commands = Vec::new();
+
+        loop {
+            let mut line = String::new();
+            if stream.read_line(&mut line).await.unwrap() == 0 {
+                return commands;
+            }
+            let command = line.trim_end().to_owned();
+
+            let reply = if command == "PROTOCOLINFO 1" {
+                format!("250-PROTOCOLINFO 1\r\n250-{auth_line}\r\n250-VERSION Tor=\"0.4.8.4\"\r\n250 OK\r\n")
+            } else if let Some(auth) = command.strip_prefix("AUTHENTICATE") {
+                let expected = cookie.as_ref().map(|cookie| format!(" {}", hex::encode(cookie)));
+                if auth == expected.unwrap_or_default() {
+                    "250 OK\r\n".to_owned()
+                } else {
+                    "515 Authentication failed: Wrong length on authentication cookie.\r\n"
+                        .to_owned()
+                }
+            } else if command.starts_with("ADD_ONION ") {
+                format!("250-ServiceID={SERVICE_ID}\r\n250 OK\r\n")
+            } else {
+                "510 Unrecognized command\r\n".to_owned()
+            };
+            commands.push(command);
+
+            stream.get_mut().write_all(reply.as_bytes()).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn add_onion_null_auth() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let control_port = tokio::spawn(run_control_port(
+            listener,
+            "AUTH METHODS=NULL".to_owned(),
+            None,
+        ));
+
+        let mut control = TorControl::connect(&address).await.unwrap();
+        let onion = control.add_onion(&[(3031, "127.0.0.1:13031".parse().unwrap())]).await.unwrap();
+        assert_eq!(onion.service_id(), SERVICE_ID);
+        drop(control);
+
+        assert_eq!(
+            control_port.await.unwrap(),
+            vec![
+                "PROTOCOLINFO 1".to_owned(),
+                "AUTHENTICATE".to_owned(),
+                "ADD_ONION NEW:ED25519-V3 Flags=DiscardPK Port=3031,127.0.0.1:13031".to_owned(),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn cookie_auth() {
+        let cookie = vec![0x5a; 32];
+        let mut cookie_file = tempfile::NamedTempFile::new().unwrap();
+        cookie_file.write_all(&cookie).unwrap();
+        let cookie_path = cookie_file.path().to_str().unwrap().replace('\\', "\\\\");
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let control_port = tokio::spawn(run_control_port(
+            listener,
+            format!("AUTH METHODS=COOKIE,SAFECOOKIE COOKIEFILE=\"{cookie_path}\""),
+            Some(cookie.clone()),
+        ));
+
+        let control = TorControl::connect(&address).await.unwrap();
+        drop(control);
+
+        assert_eq!(
+            control_port.await.unwrap(),
+            vec!["PROTOCOLINFO 1".to_owned(), format!("AUTHENTICATE {}", hex::encode(cookie)),]
+        );
+    }
+
+    #[tokio::test]
+    async fn auth_failure() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        // The control port expects a cookie, but the client uses the NULL method
+        let _control_port = tokio::spawn(run_control_port(
+            listener,
+            "AUTH METHODS=NULL".to_owned(),
+            Some(vec![1, 2, 3]),
+        ));
+
+        let res = TorControl::connect(&address).await;
+        assert!(matches!(res, Err(P2pError::TorControlError(_))));
+    }
+
+    #[test]
+    fn cookie_file_parsing() {
+        assert_eq!(
+            parse_cookie_file(
+                r#"AUTH METHODS=COOKIE,SAFECOOKIE COOKIEFILE="/var/run/tor/control.authcookie""#
+            ),
+            Some("/var/run/tor/control.authcookie".to_owned())
+        );
+        assert_eq!(
+            parse_cookie_file(r#"AUTH METHODS=COOKIE COOKIEFILE="C:\\Tor Data\\\"cookie\"""#),
+            Some(r#"C:\Tor Data\"cookie""#.to_owned())
+        );
+        assert_eq!(parse_cookie_file("AUTH METHODS=NULL"), None);
+        assert_eq!(
+            parse_cookie_file(r#"AUTH METHODS=COOKIE COOKIEFILE="/tmp"#),
+            None
+        );
+    }
+}
@@ -25,7 +25,8 @@ pub use self::{
     channel::{ChannelListener, ChannelStream, MpscChannelTransport},
     socks5::Socks5TransportSocket,
     stream_adapter::{
-        identity::IdentityStreamAdapter, noise::NoiseEncryptionAdapter,
+        identity::IdentityStreamAdapter,
+        noise::{NodeKeypair, NodePublicKey, NoiseEncryptionAdapter},
         wrapped_transport::wrapped_socket::WrappedTransportSocket,
     },
     tcp::TcpTransportSocket,
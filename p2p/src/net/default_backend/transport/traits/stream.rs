@@ -16,6 +16,13 @@
 use async_trait::async_trait;
 use tokio::io::{AsyncRead, AsyncWrite};
 
+use crate::net::default_backend::transport::NodePublicKey;
+
 /// An abstraction layer over some network stream that can be used to send and receive messages.
 #[async_trait]
-pub trait PeerStream: Unpin + Send + AsyncRead + AsyncWrite {}
+pub trait PeerStream: Unpin + Send + AsyncRead + AsyncWrite {
+    /// Static public key of the remote node, if the stream authenticates it
+    fn remote_public_key(&self) -> Option<NodePublicKey> {
+        None
+    }
+}
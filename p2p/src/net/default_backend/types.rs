@@ -16,7 +16,7 @@
 use std::time::Duration;
 
 use common::{
-    chain::Transaction,
+    chain::{Block, Transaction},
     primitives::{semver::SemVer, user_agent::UserAgent, Id},
 };
 use serialization::{Decode, Encode};
@@ -25,7 +25,8 @@ use tokio::sync::mpsc::Sender;
 use crate::{
     message::{
         AddrListRequest, AddrListResponse, AnnounceAddrRequest, BlockListRequest, BlockResponse,
-        HeaderList, HeaderListRequest, PeerManagerMessage, PingRequest, PingResponse, SyncMessage,
+        BlockTransactions, BlockTransactionsRequest, CompactBlock, HeaderList, HeaderListRequest,
+        PeerManagerMessage, PingRequest, PingResponse, SyncMessage, TransactionInventory,
         TransactionResponse,
     },
     net::types::services::Services,
@@ -35,10 +36,20 @@ use crate::{
 
 #[derive(Debug)]
 pub enum Command<A> {
-    Connect { address: A },
-    Accept { peer_id: PeerId },
-    Disconnect { peer_id: PeerId },
-    SendMessage { peer: PeerId, message: Message },
+    Connect {
+        address: A,
+        local_services_override: Option<Services>,
+    },
+    Accept {
+        peer_id: PeerId,
+    },
+    Disconnect {
+        peer_id: PeerId,
+    },
+    SendMessage {
+        peer: PeerId,
+        message: Message,
+    },
 }
 
 /// Random nonce sent in outbound handshake.
@@ -148,6 +159,16 @@ pub enum Message {
     TransactionRequest(Id<Transaction>),
     #[codec(index = 12)]
     TransactionResponse(TransactionResponse),
+    #[codec(index = 13)]
+    CompactBlockRequest(Id<Block>),
+    #[codec(index = 14)]
+    CompactBlock(CompactBlock),
+    #[codec(index = 15)]
+    BlockTransactionsRequest(BlockTransactionsRequest),
+    #[codec(index = 16)]
+    BlockTransactions(BlockTransactions),
+    #[codec(index = 17)]
+    TransactionInventory(TransactionInventory),
 
     #[codec(index = 8)]
     AnnounceAddrRequest(AnnounceAddrRequest),
@@ -177,8 +198,13 @@ impl From<SyncMessage> for Message {
             SyncMessage::HeaderList(r) => Message::HeaderList(r),
             SyncMessage::BlockResponse(r) => Message::BlockResponse(r),
             SyncMessage::NewTransaction(id) => Message::NewTransaction(id),
+            SyncMessage::TransactionInventory(r) => Message::TransactionInventory(r),
             SyncMessage::TransactionRequest(id) => Message::TransactionRequest(id),
             SyncMessage::TransactionResponse(tx) => Message::TransactionResponse(tx),
+            SyncMessage::CompactBlockRequest(id) => Message::CompactBlockRequest(id),
+            SyncMessage::CompactBlock(b) => Message::CompactBlock(b),
+            SyncMessage::BlockTransactionsRequest(r) => Message::BlockTransactionsRequest(r),
+            SyncMessage::BlockTransactions(r) => Message::BlockTransactions(r),
         }
     }
 }
@@ -31,10 +31,10 @@ use crate::{
     config,
     message::{PeerManagerMessage, SyncMessage},
     types::peer_id::PeerId,
-    P2pEventHandler,
+    P2pEventSubscription,
 };
 
-use self::default_backend::transport::TransportAddress;
+use self::{default_backend::transport::TransportAddress, types::services::Services};
 
 /// [NetworkingService] provides the low-level network interface
 /// that each network service provider must implement
@@ -90,7 +90,7 @@ pub trait NetworkingService {
         time_getter: TimeGetter,
         shutdown: Arc<SeqCstAtomicBool>,
         shutdown_receiver: oneshot::Receiver<()>,
-        subscribers_receiver: mpsc::UnboundedReceiver<P2pEventHandler>,
+        subscribers_receiver: mpsc::UnboundedReceiver<P2pEventSubscription>,
     ) -> crate::Result<(
         Self::ConnectivityHandle,
         Self::MessagingHandle,
@@ -114,7 +114,13 @@ where
     ///
     /// # Arguments
     /// `address` - socket address of the peer
-    fn connect(&mut self, address: T::Address) -> crate::Result<()>;
+    /// `local_services_override` - services advertised to the peer instead of the node type services
+    /// (used for block-relay-only connections)
+    fn connect(
+        &mut self,
+        address: T::Address,
+        local_services_override: Option<Services>,
+    ) -> crate::Result<()>;
 
     /// Accept the peer as valid and allow reading of network messages
     fn accept(&mut self, peer_id: PeerId) -> crate::Result<()>;
@@ -25,6 +25,7 @@ use tokio::sync::mpsc::Receiver;
 
 use crate::{
     message::{PeerManagerMessage, SyncMessage},
+    node_key::NodePublicKey,
     protocol::NetworkProtocol,
     types::{peer_address::PeerAddress, peer_id::PeerId},
     P2pError,
@@ -65,6 +66,9 @@ pub struct PeerInfo {
 
     /// The announcements list that a peer interested is.
     pub services: Services,
+
+    /// Static public key of the remote node (if the transport authenticates peers)
+    pub node_key: Option<NodePublicKey>,
 }
 
 impl PeerInfo {
@@ -149,7 +153,14 @@ pub enum SyncingEvent {
     /// Peer connected
     Connected {
         peer_id: PeerId,
+        /// Services advertised by the remote peer
         services: Services,
+        /// Services advertised by this node to the remote peer
+        local_services: Services,
+        /// Whether the connection was initiated by the remote peer
+        inbound: bool,
+        /// Negotiated network protocol version
+        protocol: NetworkProtocol,
         sync_rx: Receiver<SyncMessage>,
     },
 
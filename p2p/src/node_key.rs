@@ -0,0 +1,135 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Persistent static key of the node and pinning of the remote node keys
+//!
+//! The key is used in the noise handshake, so peers can recognize the node across reconnects.
+//! Boot and reserved node addresses may be prefixed with the expected public key of the remote
+//! node (`<hex public key>@<address>`); outbound connections to such nodes are refused
+//! if the handshake reveals a different key.
+//!
+//! The key of the remote node is passed to the peer manager in `PeerInfo`, which uses it as
+//! the peer identity: the key is banned along with the address, only one connection per key
+//! is accepted, and reserved nodes pinned with a key are recognized from any address.
+
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use serialization::{DecodeAll, Encode};
+
+pub use crate::net::default_backend::transport::{NodeKeypair, NodePublicKey};
+
+pub const NODE_KEY_FILE_NAME: &str = "p2p_node_key.dat";
+
+const PINNED_KEY_SEPARATOR: char = '@';
+
+#[derive(thiserror::Error, Debug)]
+pub enum NodeKeyFileError {
+    #[error("Node key file {0} I/O error: {1}")]
+    Io(PathBuf, std::io::Error),
+    #[error("Node key file {0} is corrupted: {1}")]
+    Decode(PathBuf, serialization::Error),
+}
+
+fn write_key_file(path: &Path, data: &[u8]) -> Result<(), std::io::Error> {
+    let path_tmp = path.with_extension("tmp");
+    let mut options = std::fs::OpenOptions::new();
+
+    #[cfg(unix)]
+    {
+        // Prevent other users from reading the private key
+        use std::os::unix::prelude::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    options
+        .create(true)
+        .write(true)
+        .truncate(true)
+        .open(&path_tmp)?
+        .write_all(data)?;
+
+    std::fs::rename(path_tmp, path)
+}
+
+/// Load the node key from the data directory, generating and storing a new one on the first run
+pub fn load_or_generate_node_key(data_dir: &Path) -> Result<NodeKeypair, NodeKeyFileError> {
+    let path = data_dir.join(NODE_KEY_FILE_NAME);
+
+    if path.exists() {
+        let data = std::fs::read(&path).map_err(|e| NodeKeyFileError::Io(path.clone(), e))?;
+        return NodeKeypair::decode_all(&mut data.as_slice())
+            .map_err(|e| NodeKeyFileError::Decode(path, e));
+    }
+
+    let node_key = NodeKeypair::generate();
+    write_key_file(&path, &node_key.encode()).map_err(|e| NodeKeyFileError::Io(path, e))?;
+    Ok(node_key)
+}
+
+/// Split the optional pinned public key from a boot or reserved node address
+pub fn split_pinned_node_key(address: &str) -> crate::Result<(Option<NodePublicKey>, &str)> {
+    match address.split_once(PINNED_KEY_SEPARATOR) {
+        Some((node_key, address)) => Ok((Some(node_key.parse()?), address)),
+        None => Ok((None, address)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_persists_between_loads() {
+        let data_dir = tempfile::TempDir::new().unwrap();
+
+        let key1 = load_or_generate_node_key(data_dir.path()).unwrap();
+        let key2 = load_or_generate_node_key(data_dir.path()).unwrap();
+        assert_eq!(key1.public_key(), key2.public_key());
+
+        let other_dir = tempfile::TempDir::new().unwrap();
+        let key3 = load_or_generate_node_key(other_dir.path()).unwrap();
+        assert_ne!(key1.public_key(), key3.public_key());
+    }
+
+    #[test]
+    fn corrupted_key_file() {
+        let data_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(data_dir.path().join(NODE_KEY_FILE_NAME), [1, 2, 3]).unwrap();
+
+        assert!(matches!(
+            load_or_generate_node_key(data_dir.path()),
+            Err(NodeKeyFileError::Decode(_, _))
+        ));
+    }
+
+    #[test]
+    fn pinned_addresses() {
+        let node_key = *NodeKeypair::generate().public_key();
+
+        assert_eq!(
+            split_pinned_node_key("127.0.0.1:3031").unwrap(),
+            (None, "127.0.0.1:3031")
+        );
+        assert_eq!(
+            split_pinned_node_key(&format!("{node_key}@[::1]:3031")).unwrap(),
+            (Some(node_key), "[::1]:3031")
+        );
+        assert!(split_pinned_node_key("abcd@127.0.0.1:3031").is_err());
+        assert!(split_pinned_node_key("@127.0.0.1:3031").is_err());
+    }
+}
@@ -15,19 +15,29 @@
 
 use std::net::{Ipv4Addr, Ipv6Addr};
 
+use serialization::{Decode, Encode};
+
 use crate::types::peer_address::PeerAddress;
 
 // IPv4 addresses grouped into /16 subnets
 const IPV4_GROUP_BYTES: usize = 2;
 // IPv6 addresses grouped into /32 subnets
 const IPV6_GROUP_BYTES: usize = 4;
+// Onion addresses grouped by the first 4 bits of the public key
+const ONION_GROUP_SHIFT: u8 = 4;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Encode, Decode)]
 pub enum AddressGroup {
+    #[codec(index = 0)]
     Local,
+    #[codec(index = 1)]
     Private,
+    #[codec(index = 2)]
     PublicV4([u8; IPV4_GROUP_BYTES]),
+    #[codec(index = 3)]
     PublicV6([u8; IPV6_GROUP_BYTES]),
+    #[codec(index = 4)]
+    Onion(u8),
 }
 
 impl AddressGroup {
@@ -39,23 +49,29 @@ impl AddressGroup {
     ///
     /// See `NetGroupManager::GetGroup` in Bitcoin Core for a reference.
     pub fn from_peer_address(address: &PeerAddress) -> AddressGroup {
-        if address.is_global_unicast_ip() {
-            match address {
-                PeerAddress::Ip4(addr) => AddressGroup::PublicV4(
-                    Ipv4Addr::from(addr.ip).octets()[0..IPV4_GROUP_BYTES]
-                        .try_into()
-                        .expect("must be valid"),
-                ),
-                PeerAddress::Ip6(addr) => AddressGroup::PublicV6(
-                    Ipv6Addr::from(addr.ip).octets()[0..IPV6_GROUP_BYTES]
-                        .try_into()
-                        .expect("must be valid"),
-                ),
+        match address {
+            PeerAddress::Ip4(_) | PeerAddress::Ip6(_) if !address.is_global_unicast_ip() => {
+                if address.is_loopback() {
+                    AddressGroup::Local
+                } else {
+                    AddressGroup::Private
+                }
+            }
+            PeerAddress::Ip4(addr) => AddressGroup::PublicV4(
+                Ipv4Addr::from(addr.ip).octets()[0..IPV4_GROUP_BYTES]
+                    .try_into()
+                    .expect("must be valid"),
+            ),
+            PeerAddress::Ip6(addr) => AddressGroup::PublicV6(
+                Ipv6Addr::from(addr.ip).octets()[0..IPV6_GROUP_BYTES]
+                    .try_into()
+                    .expect("must be valid"),
+            ),
+            // Onion addresses are cheap to generate, so it doesn't make sense to use many groups
+            // for them (the same as in Bitcoin Core)
+            PeerAddress::Onion3(addr) => {
+                AddressGroup::Onion(addr.address.public_key()[0] >> ONION_GROUP_SHIFT)
             }
-        } else if address.is_loopback() {
-            AddressGroup::Local
-        } else {
-            AddressGroup::Private
         }
     }
 }
@@ -87,5 +103,14 @@ mod tests {
             "2a00:1450:4017:815::200e",
             AddressGroup::PublicV6([0x2a, 0x00, 0x14, 0x50]),
         );
+
+        let onion: PeerAddress =
+            "duckduckgogg42xjoc72x3sjasowoarfbgcmvfimaftt6twagswzczad.onion:3031"
+                .parse()
+                .unwrap();
+        assert_eq!(
+            AddressGroup::from_peer_address(&onion),
+            AddressGroup::Onion(0x1)
+        );
     }
 }
@@ -56,7 +56,7 @@ use crate::{
         },
         AsBannableAddress, ConnectivityService, NetworkingService,
     },
-    protocol::{NetworkProtocol, NETWORK_PROTOCOL_MIN},
+    protocol::{negotiated_protocol, NetworkProtocol, NETWORK_PROTOCOL_MIN, NETWORK_PROTOCOL_V4},
     types::{
         peer_address::{PeerAddress, PeerAddressIp4, PeerAddressIp6},
         peer_id::PeerId,
@@ -70,10 +70,16 @@ use self::{
     peerdb::storage::PeerDbStorage,
 };
 
-/// Maximum number of outbound connections the [`PeerManager`] is allowed to have open.
+/// Maximum number of full-relay outbound connections the [`PeerManager`] is allowed to have open.
 /// This value is constant because users should not change this.
 const MAX_OUTBOUND_CONNECTIONS: usize = 8;
 
+/// Maximum number of block-relay-only outbound connections.
+///
+/// Only headers and blocks are exchanged over these connections, which makes them
+/// hard to detect by observing transaction and address relay (value as in Bitcoin Core).
+const MAX_OUTBOUND_BLOCK_RELAY_CONNECTIONS: usize = 2;
+
 /// Lower bound for how often [`PeerManager::heartbeat()`] is called
 const PEER_MGR_HEARTBEAT_INTERVAL_MIN: Duration = Duration::from_secs(5);
 /// Upper bound for how often [`PeerManager::heartbeat()`] is called
@@ -93,6 +99,9 @@ pub const ADDR_RATE_INITIAL_SIZE: u32 = 1;
 /// Bucket size used to rate limit address announcements from a peer.
 pub const ADDR_RATE_BUCKET_SIZE: u32 = 10;
 
+/// How often feeler connections are made (on average) when all outbound connection slots are used
+const FEELER_CONNECTION_INTERVAL: Duration = Duration::from_secs(2 * 60);
+
 /// To how many peers resend received address
 const PEER_ADDRESS_RESEND_COUNT: usize = 2;
 
@@ -142,6 +151,20 @@ where
     /// List of connected peers that subscribed to PeerAddresses topic
     subscribed_to_peer_addresses: BTreeSet<PeerId>,
 
+    /// Addresses of the short-lived outbound connections used to test addresses from the "new" table.
+    /// Feeler connections are closed immediately after the handshake.
+    feeler_connections: BTreeSet<T::Address>,
+
+    /// Addresses of the pending and connected block-relay-only outbound connections
+    block_relay_connections: BTreeSet<T::Address>,
+
+    /// Addresses that can't be dialed with the used transport, but are still relayed to other peers
+    /// (onion addresses on nodes without a Tor proxy). Kept in memory only.
+    undialable_addresses: BTreeSet<PeerAddress>,
+
+    /// The time of the next feeler connection attempt
+    next_feeler_connection: Duration,
+
     peer_eviction_random_state: peers_eviction::RandomState,
 }
 
@@ -164,6 +187,8 @@ where
             peerdb::PeerDb::new(Arc::clone(&p2p_config), time_getter.clone(), peerdb_storage)?;
         assert!(!p2p_config.outbound_connection_timeout.is_zero());
         assert!(!p2p_config.ping_timeout.is_zero());
+        let next_feeler_connection =
+            time_getter.get_time() + Self::next_feeler_connection_delay(&mut rng);
         Ok(PeerManager {
             chain_config,
             p2p_config,
@@ -175,6 +200,10 @@ where
             peers: BTreeMap::new(),
             peerdb,
             subscribed_to_peer_addresses: BTreeSet::new(),
+            feeler_connections: BTreeSet::new(),
+            block_relay_connections: BTreeSet::new(),
+            undialable_addresses: BTreeSet::new(),
+            next_feeler_connection,
             peer_eviction_random_state: peers_eviction::RandomState::new(&mut rng),
         })
     }
@@ -196,11 +225,26 @@ where
         .is_some()
     }
 
+    /// Verify that the peer address can be relayed to other peers.
+    /// Onion addresses are relayed even if this node can't connect to them.
+    fn is_peer_address_relayable(&self, address: &PeerAddress) -> bool {
+        match address {
+            PeerAddress::Ip4(_) | PeerAddress::Ip6(_) => self.is_peer_address_valid(address),
+            PeerAddress::Onion3(onion) => onion.port != 0,
+        }
+    }
+
+    /// Check that the peer can decode the address (onion addresses require a newer protocol)
+    fn peer_accepts_address(peer: &PeerContext<T::Address>, address: &PeerAddress) -> bool {
+        !address.is_onion() || negotiated_protocol(peer.info.protocol) >= NETWORK_PROTOCOL_V4
+    }
+
     /// Discover public addresses for this node after a new outbound connection is made
     ///
     /// *receiver_address* is this host socket address as seen and reported by remote peer.
     /// This should work for hosts with public IPs and for hosts behind NAT with port forwarding (same port is assumed).
     /// This won't work for majority of nodes but that should be accepted.
+    /// Onion addresses don't depend on *receiver_address* and are always public.
     fn discover_own_address(
         &mut self,
         role: Role,
@@ -211,6 +255,17 @@ where
             return None;
         }
 
+        let own_onion_address = self
+            .peer_connectivity_handle
+            .local_addresses()
+            .iter()
+            .filter(|address| address.as_peer_address().is_onion())
+            .choose(&mut make_pseudo_rng())
+            .cloned();
+        if own_onion_address.is_some() {
+            return own_onion_address;
+        }
+
         let receiver_address = receiver_address?;
 
         // Take IP and use port numbers from all listening sockets (with same IP version)
@@ -251,14 +306,16 @@ where
 
     /// Send address announcement to the selected peer (if the address is new)
     /// `peer_id` must be from the connected peer.
-    fn announce_address(&mut self, peer_id: PeerId, address: T::Address) {
+    fn announce_address(&mut self, peer_id: PeerId, address: PeerAddress) {
         let peer = self.peers.get_mut(&peer_id).expect("peer must be known");
-        if !peer.announced_addresses.contains(&address) {
+        if Self::peer_accepts_address(peer, &address)
+            && !peer.announced_addresses.contains(&address)
+        {
             Self::send_peer_message(
                 &mut self.peer_connectivity_handle,
                 peer_id,
                 PeerManagerMessage::AnnounceAddrRequest(AnnounceAddrRequest {
-                    address: address.as_peer_address(),
+                    address: address.clone(),
                 }),
             );
             peer.announced_addresses.insert(&address, &mut make_pseudo_rng());
@@ -270,13 +327,14 @@ where
         peer: &PeerContext<T::Address>,
     ) {
         if let Some(discovered_addr) = peer.discovered_own_address.as_ref() {
-            Self::send_peer_message(
-                peer_connectivity_handle,
-                peer.info.peer_id,
-                PeerManagerMessage::AnnounceAddrRequest(AnnounceAddrRequest {
-                    address: discovered_addr.as_peer_address(),
-                }),
-            );
+            let address = discovered_addr.as_peer_address();
+            if Self::peer_accepts_address(peer, &address) {
+                Self::send_peer_message(
+                    peer_connectivity_handle,
+                    peer.info.peer_id,
+                    PeerManagerMessage::AnnounceAddrRequest(AnnounceAddrRequest { address }),
+                );
+            }
         }
     }
 
@@ -298,20 +356,24 @@ where
     /// Unknown peers are reported as to be disconnected.
     ///
     /// If peer is banned, it is removed from the connected peers
-    /// and its address is marked as banned.
+    /// and its address and node key (if known) are marked as banned.
     fn adjust_peer_score(&mut self, peer_id: PeerId, score: u32) {
         let peer = match self.peers.get_mut(&peer_id) {
             Some(peer) => peer,
             None => return,
         };
 
-        let whitelisted_node = match peer.role {
-            Role::Inbound => {
-                // TODO: Add whitelisted IPs option and check it here
-                false
-            }
-            Role::Outbound => self.peerdb.is_reserved_node(&peer.address),
-        };
+        let reserved_node_key = peer.info.node_key.map_or(false, |node_key| {
+            self.peerdb.is_reserved_node_key(&node_key)
+        });
+        let whitelisted_node = reserved_node_key
+            || match peer.role {
+                Role::Inbound => {
+                    // TODO: Add whitelisted IPs option and check it here
+                    false
+                }
+                Role::Outbound => self.peerdb.is_reserved_node(&peer.address),
+            };
 
         if whitelisted_node {
             log::info!(
@@ -328,7 +390,22 @@ where
         );
 
         if peer.score >= *self.p2p_config.ban_threshold {
-            self.peerdb.ban_peer(&peer.address);
+            if let Some(node_key) = peer.info.node_key {
+                self.peerdb.ban_node_key(&node_key);
+            }
+
+            // Inbound connections to the onion service come from the local Tor daemon,
+            // banning its address would reject all of them (the node key is still banned)
+            let onion_service_peer = peer.role == Role::Inbound
+                && self.p2p_config.tor_control_address.is_some()
+                && peer.address.as_peer_address().is_loopback();
+            if onion_service_peer {
+                log::info!(
+                    "Disconnecting onion service peer {peer_id} without banning its address"
+                );
+            } else {
+                self.peerdb.ban_peer(&peer.address);
+            }
             self.disconnect(peer_id, None);
         }
     }
@@ -338,7 +415,7 @@ where
     /// This function doesn't block on the call but sends a command to the
     /// networking backend which then reports at some point in the future
     /// whether the connection failed or succeeded.
-    fn try_connect(&mut self, address: T::Address) -> crate::Result<()> {
+    fn try_connect(&mut self, address: T::Address, block_relay_only: bool) -> crate::Result<()> {
         ensure!(
             !self.pending_outbound_connects.contains_key(&address),
             P2pError::PeerError(PeerError::Pending(address.to_string())),
@@ -355,22 +432,34 @@ where
             P2pError::PeerError(PeerError::BannedAddress(address.to_string())),
         );
 
-        self.peer_connectivity_handle.connect(address)?;
+        // Don't advertise transactions and peer addresses so that the remote peer does not relay them to us
+        let local_services_override = block_relay_only.then(|| {
+            Services::from(*self.p2p_config.node_type)
+                & Services::from([Service::Blocks].as_slice())
+        });
+
+        self.peer_connectivity_handle.connect(address, local_services_override)?;
 
         Ok(())
     }
 
     /// Initiate a new outbound connection or send error to `response` if it's not possible
+    ///
+    /// Block-relay-only connections only exchange headers and blocks with the peer.
     fn connect(
         &mut self,
         address: T::Address,
+        block_relay_only: bool,
         response: Option<oneshot_nofail::Sender<crate::Result<()>>>,
     ) {
-        log::debug!("try to establish outbound connection to peer at address {address:?}");
-        let res = self.try_connect(address.clone());
+        log::debug!("try to establish outbound connection to peer at address {address:?}, block_relay_only: {block_relay_only}");
+        let res = self.try_connect(address.clone(), block_relay_only);
 
         match res {
             Ok(()) => {
+                if block_relay_only {
+                    self.block_relay_connections.insert(address.clone());
+                }
                 let old_value = self.pending_outbound_connects.insert(address, response);
                 assert!(old_value.is_none());
             }
@@ -461,6 +550,18 @@ where
             !self.peerdb.is_address_banned(&address.as_bannable()),
             P2pError::PeerError(PeerError::BannedAddress(address.to_string())),
         );
+        if let Some(node_key) = &info.node_key {
+            ensure!(
+                !self.peerdb.is_node_key_banned(node_key)
+                    || self.peerdb.is_reserved_node_key(node_key),
+                P2pError::PeerError(PeerError::BannedNodeKey(node_key.to_string())),
+            );
+            // The same node connected from another address
+            ensure!(
+                !self.peers.values().any(|peer| peer.info.node_key.as_ref() == Some(node_key)),
+                P2pError::PeerError(PeerError::PeerAlreadyExists),
+            );
+        }
 
         // If the maximum number of inbound connections is reached,
         // the new inbound connection cannot be accepted even if it's valid.
@@ -532,13 +633,17 @@ where
 
         self.peer_connectivity_handle.accept(peer_id)?;
 
-        log::info!("new peer accepted, peer_id: {peer_id}, address: {address:?}, role: {role:?}");
+        let block_relay_only =
+            role == Role::Outbound && self.block_relay_connections.contains(&address);
+
+        log::info!("new peer accepted, peer_id: {peer_id}, address: {address:?}, role: {role:?}, block_relay_only: {block_relay_only}");
 
-        if info.services.has_service(Service::PeerAddresses) {
+        // Peer addresses are never exchanged over block-relay-only connections
+        if info.services.has_service(Service::PeerAddresses) && !block_relay_only {
             self.subscribed_to_peer_addresses.insert(info.peer_id);
         }
 
-        if Self::load_addresses_from(role) {
+        if Self::load_addresses_from(role) && !block_relay_only {
             Self::send_peer_message(
                 &mut self.peer_connectivity_handle,
                 peer_id,
@@ -559,13 +664,17 @@ where
             &mut make_pseudo_rng(),
         );
 
-        let discovered_own_address =
-            self.discover_own_address(role, info.services, receiver_address);
+        let discovered_own_address = if block_relay_only {
+            None
+        } else {
+            self.discover_own_address(role, info.services, receiver_address)
+        };
 
         let peer = PeerContext {
             info,
             address: address.clone(),
             role,
+            block_relay_only,
             score: 0,
             sent_ping: None,
             ping_last: None,
@@ -575,6 +684,7 @@ where
             announced_addresses,
             address_rate_limiter,
             discovered_own_address,
+            last_tip_block_time: None,
         };
 
         Self::send_own_address_to_peer(&mut self.peer_connectivity_handle, &peer);
@@ -611,9 +721,22 @@ where
 
             if role == Role::Outbound {
                 self.peerdb.report_outbound_failure(address.clone(), accept_err);
+                self.feeler_connections.remove(&address);
+                self.block_relay_connections.remove(&address);
             }
         }
 
+        // The address is tested (and moved to the tried table), the feeler connection is not needed anymore.
+        // Block-relay-only connections are never closed this way.
+        if accept_res.is_ok()
+            && role == Role::Outbound
+            && self.feeler_connections.contains(&address)
+            && !self.block_relay_connections.contains(&address)
+        {
+            log::debug!("close feeler connection to {address:?}");
+            self.disconnect(peer_id, None);
+        }
+
         if role == Role::Outbound {
             let pending_connect = self
                 .pending_outbound_connects
@@ -635,6 +758,8 @@ where
     /// update its own records.
     fn handle_outbound_error(&mut self, address: T::Address, error: P2pError) {
         self.peerdb.report_outbound_failure(address.clone(), &error);
+        self.feeler_connections.remove(&address);
+        self.block_relay_connections.remove(&address);
 
         let pending_connect = self
             .pending_outbound_connects
@@ -674,6 +799,10 @@ where
             }
 
             self.subscribed_to_peer_addresses.remove(&peer_id);
+            self.feeler_connections.remove(&peer.address);
+            if peer.role == Role::Outbound {
+                self.block_relay_connections.remove(&peer.address);
+            }
         }
     }
 
@@ -726,7 +855,7 @@ where
                     .into_iter()
                     .for_each(|addr| {
                         total += 1;
-                        self.peerdb.peer_discovered(addr);
+                        self.peerdb.peer_discovered(addr, None);
                     });
                 }
                 Err(err) => {
@@ -741,7 +870,10 @@ where
         let pending_outbound = self
             .pending_outbound_connects
             .keys()
-            .filter(|addr| self.peerdb.is_reserved_node(addr) == reserved)
+            .filter(|addr| {
+                self.peerdb.is_reserved_node(addr) == reserved
+                    && !self.feeler_connections.contains(addr)
+            })
             .cloned();
         let connected_outbound = self
             .peers
@@ -749,6 +881,7 @@ where
             .filter(|peer| {
                 peer.role == Role::Outbound
                     && self.peerdb.is_reserved_node(&peer.address) == reserved
+                    && !self.feeler_connections.contains(&peer.address)
             })
             .map(|peer| peer.address.clone());
         pending_outbound.chain(connected_outbound).collect()
@@ -768,7 +901,6 @@ where
     /// reputation. It also updates peer scores and forgets those peers that are no longer needed.
     ///
     /// TODO: IP address diversity check?
-    /// TODO: exploratory peer connections?
     /// TODO: close connection with low-score peers in favor of peers with higher score?
     ///
     /// The process starts by first checking if the number of active connections is less than
@@ -779,8 +911,19 @@ where
         // Expired banned addresses are dropped here, keep this call!
         self.peerdb.heartbeat();
 
+        // Full-relay and block-relay-only connections are counted separately,
+        // but they never share address groups
         let all_normal_outbound = self.outbound_peers(false);
-        let new_addresses = self.peerdb.select_new_outbound_addresses(&all_normal_outbound);
+        let block_relay_count = all_normal_outbound
+            .iter()
+            .filter(|address| self.block_relay_connections.contains(address))
+            .count();
+        let full_relay_count = all_normal_outbound.len() - block_relay_count;
+
+        let full_relay_needed = MAX_OUTBOUND_CONNECTIONS.saturating_sub(full_relay_count);
+        let new_addresses = self
+            .peerdb
+            .select_new_outbound_addresses(&all_normal_outbound, full_relay_needed);
 
         // Try to get some records from DNS servers if there are no addresses to connect.
         // Do this only if no peers are currently connected.
@@ -789,17 +932,67 @@ where
             && self.pending_outbound_connects.is_empty()
         {
             self.reload_dns_seed().await;
-            self.peerdb.select_new_outbound_addresses(&all_normal_outbound)
+            self.peerdb
+                .select_new_outbound_addresses(&all_normal_outbound, full_relay_needed)
         } else {
             new_addresses
         };
 
+        let block_relay_needed =
+            MAX_OUTBOUND_BLOCK_RELAY_CONNECTIONS.saturating_sub(block_relay_count);
+        let block_relay_addresses = self.peerdb.select_new_outbound_addresses(
+            &all_normal_outbound.iter().chain(new_addresses.iter()).cloned().collect(),
+            block_relay_needed,
+        );
+
         let all_reserved_outbound = self.outbound_peers(true);
         let reserved_addresses =
             self.peerdb.select_reserved_outbound_addresses(&all_reserved_outbound);
 
         for address in new_addresses.into_iter().chain(reserved_addresses.into_iter()) {
-            self.connect(address, None);
+            self.connect(address, false, None);
+        }
+
+        for address in block_relay_addresses {
+            self.connect(address, true, None);
+        }
+
+        if full_relay_count >= MAX_OUTBOUND_CONNECTIONS {
+            self.try_feeler_connection();
+        }
+    }
+
+    fn next_feeler_connection_delay(rng: &mut impl Rng) -> Duration {
+        FEELER_CONNECTION_INTERVAL.mul_f64(utils::exp_rand::exponential_rand(rng))
+    }
+
+    /// Opens a short-lived outbound connection to test an address from the "new" table,
+    /// so that working addresses move to the "tried" table.
+    ///
+    /// Feelers are only needed when all outbound connection slots are used,
+    /// otherwise new addresses are tested by the normal outbound connections.
+    fn try_feeler_connection(&mut self) {
+        let now = self.time_getter.get_time();
+        if now < self.next_feeler_connection || !self.feeler_connections.is_empty() {
+            return;
+        }
+        self.next_feeler_connection =
+            now + Self::next_feeler_connection_delay(&mut make_pseudo_rng());
+
+        let address = match self.peerdb.select_feeler_address() {
+            Some(address) => address,
+            None => return,
+        };
+        if self.pending_outbound_connects.contains_key(&address)
+            || self.is_address_connected(&address)
+        {
+            return;
+        }
+
+        log::debug!("open feeler connection to {address:?}");
+        self.connect(address.clone(), false, None);
+        if self.pending_outbound_connects.contains_key(&address) {
+            self.feeler_connections.insert(address);
         }
     }
 
@@ -817,15 +1010,39 @@ where
         }
     }
 
-    fn handle_announce_addr_request(&mut self, peer_id: PeerId, address: PeerAddress) {
-        if let Some(address) = <T::Address as TransportAddress>::from_peer_address(
+    /// Store the address received from the peer.
+    /// Addresses that can't be dialed are kept for relaying only.
+    fn address_discovered(&mut self, address: PeerAddress, source: &T::Address) {
+        match <T::Address as TransportAddress>::from_peer_address(
             &address,
             *self.p2p_config.allow_discover_private_ips,
         ) {
+            Some(address) => self.peerdb.peer_discovered(address, Some(source)),
+            None => {
+                if self.undialable_addresses.len() >= MAX_ADDRESS_COUNT {
+                    let evicted = self
+                        .undialable_addresses
+                        .iter()
+                        .choose(&mut make_pseudo_rng())
+                        .cloned()
+                        .expect("must not be empty");
+                    self.undialable_addresses.remove(&evicted);
+                }
+                self.undialable_addresses.insert(address);
+            }
+        }
+    }
+
+    fn handle_announce_addr_request(&mut self, peer_id: PeerId, address: PeerAddress) {
+        if self.is_peer_address_relayable(&address) {
             let peer = self
                 .peers
                 .get_mut(&peer_id)
                 .expect("peer sending AnnounceAddrRequest must be known");
+            if peer.block_relay_only {
+                log::debug!("ignore address announcement from block-relay-only peer {peer_id}");
+                return;
+            }
             if !peer.address_rate_limiter.accept(self.time_getter.get_time()) {
                 log::debug!("address announcement is rate limited from peer {peer_id}");
                 return;
@@ -833,11 +1050,18 @@ where
 
             peer.announced_addresses.insert(&address, &mut make_pseudo_rng());
 
-            self.peerdb.peer_discovered(address.clone());
+            let source = peer.address.clone();
+            self.address_discovered(address.clone(), &source);
 
             let peer_ids = self
                 .subscribed_to_peer_addresses
                 .iter()
+                .filter(|peer_id| {
+                    Self::peer_accepts_address(
+                        self.peers.get(peer_id).expect("subscribed peer must be known"),
+                        &address,
+                    )
+                })
                 .cloned()
                 .choose_multiple(&mut make_pseudo_rng(), PEER_ADDRESS_RESEND_COUNT);
             for new_peer_id in peer_ids {
@@ -854,11 +1078,14 @@ where
             return;
         }
 
+        let peer = self.peers.get(&peer_id).expect("peer must be known");
         let addresses = self
             .peerdb
             .known_addresses()
             .map(TransportAddress::as_peer_address)
             .filter(|address| self.is_peer_address_valid(address))
+            .chain(self.undialable_addresses.iter().cloned())
+            .filter(|address| Self::peer_accepts_address(peer, address))
             .choose_multiple(&mut make_pseudo_rng(), MAX_ADDRESS_COUNT);
 
         assert!(addresses.len() <= MAX_ADDRESS_COUNT);
@@ -884,18 +1111,18 @@ where
             P2pError::ProtocolError(ProtocolError::AddressListLimitExceeded)
         );
         ensure!(
-            Self::load_addresses_from(peer.role) && !peer.addr_list_resp_received.test_and_set(),
+            Self::load_addresses_from(peer.role)
+                && !peer.block_relay_only
+                && !peer.addr_list_resp_received.test_and_set(),
             P2pError::ProtocolError(ProtocolError::UnexpectedMessage(
                 "AddrListResponse".to_owned()
             ))
         );
 
+        let source = peer.address.clone();
         for address in addresses {
-            if let Some(address) = TransportAddress::from_peer_address(
-                &address,
-                *self.p2p_config.allow_discover_private_ips,
-            ) {
-                self.peerdb.peer_discovered(address);
+            if self.is_peer_address_relayable(&address) {
+                self.address_discovered(address, &source);
             }
         }
 
@@ -957,7 +1184,7 @@ where
     fn handle_control_event(&mut self, event: PeerManagerEvent<T>) {
         match event {
             PeerManagerEvent::Connect(address, response) => {
-                self.connect(address, Some(response));
+                self.connect(address, false, Some(response));
             }
             PeerManagerEvent::Disconnect(peer_id, response) => {
                 self.disconnect(peer_id, Some(response));
@@ -967,6 +1194,12 @@ where
                 self.adjust_peer_score(peer_id, score);
                 response.send(Ok(()));
             }
+            PeerManagerEvent::NewTipReceived(peer_id, block_id) => {
+                if let Some(peer) = self.peers.get_mut(&peer_id) {
+                    log::debug!("new tip {block_id} received from peer {peer_id}");
+                    peer.last_tip_block_time = Some(self.time_getter.get_time());
+                }
+            }
             PeerManagerEvent::GetPeerCount(response) => {
                 response.send(self.active_peer_count());
             }
@@ -986,7 +1219,7 @@ where
             PeerManagerEvent::AddReserved(address) => {
                 self.peerdb.add_reserved_node(address.clone());
                 // Initiate new outbound connection without waiting for `heartbeat`
-                self.connect(address, None);
+                self.connect(address, false, None);
             }
             PeerManagerEvent::RemoveReserved(address) => {
                 self.peerdb.remove_reserved_node(address);
@@ -1040,6 +1273,7 @@ where
                 peer_id: context.info.peer_id,
                 address: context.address.to_string(),
                 inbound: context.role == Role::Inbound,
+                block_relay_only: context.block_relay_only,
                 ban_score: context.score,
                 user_agent: context.info.user_agent.to_string(),
                 version: context.info.version.to_string(),
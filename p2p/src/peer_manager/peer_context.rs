@@ -19,6 +19,7 @@ use utils::{bloom_filters::rolling_bloom_filter::RollingBloomFilter, set_flag::S
 
 use crate::{
     net::types::{self, Role},
+    types::peer_address::PeerAddress,
     utils::rate_limiter::RateLimiter,
 };
 
@@ -38,6 +39,10 @@ pub struct PeerContext<A> {
     /// Peer's role (inbound or outbound)
     pub role: Role,
 
+    /// Set for the outbound connections that only exchange headers and blocks
+    /// (no transactions and no peer addresses)
+    pub block_relay_only: bool,
+
     /// Peer score
     pub score: u32,
 
@@ -58,11 +63,14 @@ pub struct PeerContext<A> {
 
     /// All addresses that were announced to or from this peer.
     /// Used to prevent infinity loops while broadcasting addresses.
-    pub announced_addresses: RollingBloomFilter<A>,
+    pub announced_addresses: RollingBloomFilter<PeerAddress>,
 
     pub address_rate_limiter: RateLimiter,
 
     /// Expected listening address of this node (publicly routable IP + local listening port).
     /// Can be set for outbound connections only.
     pub discovered_own_address: Option<A>,
+
+    /// The last time the peer has sent a block that became the new tip
+    pub last_tip_block_time: Option<Duration>,
 }
@@ -33,6 +33,12 @@ const PURGE_REACHABLE_TIME: Duration = Duration::from_secs(3600 * 24 * 7 * 4);
 const PURGE_REACHABLE_FAIL_COUNT: u32 =
     (PURGE_REACHABLE_TIME.as_secs() / MAX_DELAY_REACHABLE.as_secs()) as u32;
 
+/// Addresses that were tried recently are never replaced in the address tables
+const RECENT_ATTEMPT_TIME: Duration = Duration::from_secs(60);
+
+/// Addresses that failed after this time since the last success can be replaced in the address tables
+const MAX_SUCCESS_AGE: Duration = Duration::from_secs(3600 * 24 * 30);
+
 pub enum AddressState {
     Connected {},
 
@@ -84,6 +90,12 @@ pub struct AddressData {
     state: AddressState,
 
     reserved: bool,
+
+    /// Time of the last outbound connection attempt (successful or not)
+    last_attempt: Option<Duration>,
+
+    /// Time of the last successful outbound connection
+    last_success: Option<Duration>,
 }
 
 impl AddressData {
@@ -96,6 +108,21 @@ impl AddressData {
                 disconnected_by_user: false,
             },
             reserved,
+            last_attempt: None,
+            last_success: None,
+        }
+    }
+
+    /// Restore the connection history loaded from the PeerDb storage
+    pub fn with_history(
+        self,
+        last_attempt: Option<Duration>,
+        last_success: Option<Duration>,
+    ) -> Self {
+        Self {
+            last_attempt,
+            last_success,
+            ..self
         }
     }
 
@@ -103,6 +130,14 @@ impl AddressData {
         self.reserved
     }
 
+    pub fn last_attempt(&self) -> Option<Duration> {
+        self.last_attempt
+    }
+
+    pub fn last_success(&self) -> Option<Duration> {
+        self.last_success
+    }
+
     /// Returns true when it is time to attempt a new outbound connection
     pub fn connect_now(&self, now: Duration) -> bool {
         match self.state {
@@ -136,17 +171,31 @@ impl AddressData {
         }
     }
 
-    /// Returns true if the address should be stored in the DB
-    pub fn is_persistent(&self) -> bool {
+    /// Returns true if the address is not worth keeping in the address tables
+    /// and can be replaced with another one (see `IsTerrible` in Bitcoin Core)
+    pub fn is_terrible(&self, now: Duration) -> bool {
+        if self.reserved
+            || self.last_attempt.map_or(false, |last_attempt| {
+                last_attempt + RECENT_ATTEMPT_TIME > now
+            })
+        {
+            return false;
+        }
+
         match self.state {
-            AddressState::Connected {} => true,
+            AddressState::Connected {} => false,
             AddressState::Disconnected {
-                fail_count: _,
+                fail_count,
                 next_connect_after: _,
-                was_reachable,
+                was_reachable: _,
                 disconnected_by_user: _,
-            } => was_reachable,
-            AddressState::Unreachable { erase_after: _ } => false,
+            } => {
+                fail_count > 0
+                    && self
+                        .last_success
+                        .map_or(true, |last_success| last_success + MAX_SUCCESS_AGE < now)
+            }
+            AddressState::Unreachable { erase_after: _ } => true,
         }
     }
 
@@ -188,6 +237,20 @@ impl AddressData {
         now: Duration,
         rng: &mut impl Rng,
     ) {
+        match transition {
+            AddressStateTransitionTo::Connected => {
+                self.last_attempt = Some(now);
+                self.last_success = Some(now);
+            }
+            AddressStateTransitionTo::ConnectionFailed => {
+                self.last_attempt = Some(now);
+            }
+            AddressStateTransitionTo::Disconnected
+            | AddressStateTransitionTo::DisconnectedByUser
+            | AddressStateTransitionTo::SetReserved
+            | AddressStateTransitionTo::UnsetReserved => {}
+        }
+
         self.state = match transition {
             AddressStateTransitionTo::Connected => match self.state {
                 AddressState::Connected {} => unreachable!(),
@@ -0,0 +1,230 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Bucketed address tables, modeled after `AddrMan` in Bitcoin Core
+//!
+//! Addresses are kept in two tables:
+//! - "new" for addresses that have not been successfully connected to yet;
+//! - "tried" for addresses with at least one successful outbound connection.
+//!
+//! Each table consists of buckets with a fixed number of slots. The bucket of a new address
+//! depends on the address group of the address and of the peer that announced it, so peers from
+//! one network range can only fill a limited number of buckets. The bucket of a tried address
+//! depends on its own address group only. Because slots are selected with a secret key,
+//! an attacker can't predict which addresses will collide.
+
+use std::{collections::BTreeMap, hash::Hasher};
+
+use crypto::random::Rng;
+use serialization::{Decode, Encode};
+
+use crate::{
+    net::default_backend::transport::TransportAddress, peer_manager::address_groups::AddressGroup,
+};
+
+pub const NEW_BUCKET_COUNT: usize = 1024;
+pub const TRIED_BUCKET_COUNT: usize = 256;
+pub const BUCKET_SIZE: usize = 64;
+
+/// How many new buckets addresses announced from a single source group can occupy
+const NEW_BUCKETS_PER_SOURCE_GROUP: u64 = 64;
+
+/// How many tried buckets addresses from a single address group can occupy
+const TRIED_BUCKETS_PER_GROUP: u64 = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub enum TableKind {
+    #[codec(index = 0)]
+    New,
+    #[codec(index = 1)]
+    Tried,
+}
+
+/// Secret key used to select buckets and slots.
+///
+/// The key is stored in the PeerDb storage so the addresses keep their positions after restarts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub struct TableKey(u64, u64);
+
+impl TableKey {
+    pub fn new_random(rng: &mut impl Rng) -> Self {
+        Self(rng.gen(), rng.gen())
+    }
+
+    fn hash(&self, parts: &[&[u8]]) -> u64 {
+        let mut hasher = siphasher::sip::SipHasher::new_with_keys(self.0, self.1);
+        for part in parts {
+            hasher.write(part);
+        }
+        hasher.finish()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct SlotPosition {
+    bucket: usize,
+    slot: usize,
+}
+
+struct TableEntry {
+    position: SlotPosition,
+
+    /// Address group of the peer that announced the address
+    source_group: AddressGroup,
+}
+
+struct Table<A> {
+    slots: BTreeMap<SlotPosition, A>,
+    entries: BTreeMap<A, TableEntry>,
+}
+
+impl<A: Ord + Clone> Table<A> {
+    fn new() -> Self {
+        Self {
+            slots: BTreeMap::new(),
+            entries: BTreeMap::new(),
+        }
+    }
+
+    fn insert(&mut self, address: A, entry: TableEntry) {
+        let old_address = self.slots.insert(entry.position, address.clone());
+        assert!(old_address.is_none(), "slot must be free");
+        let old_entry = self.entries.insert(address, entry);
+        assert!(old_entry.is_none(), "address must not be in the table");
+    }
+
+    fn remove(&mut self, address: &A) -> Option<TableEntry> {
+        let entry = self.entries.remove(address)?;
+        self.slots.remove(&entry.position);
+        Some(entry)
+    }
+}
+
+pub struct AddressTables<A> {
+    key: TableKey,
+    new: Table<A>,
+    tried: Table<A>,
+}
+
+impl<A: Ord + Clone + TransportAddress> AddressTables<A> {
+    pub fn new(key: TableKey) -> Self {
+        Self {
+            key,
+            new: Table::new(),
+            tried: Table::new(),
+        }
+    }
+
+    fn table(&self, kind: TableKind) -> &Table<A> {
+        match kind {
+            TableKind::New => &self.new,
+            TableKind::Tried => &self.tried,
+        }
+    }
+
+    fn table_mut(&mut self, kind: TableKind) -> &mut Table<A> {
+        match kind {
+            TableKind::New => &mut self.new,
+            TableKind::Tried => &mut self.tried,
+        }
+    }
+
+    fn position(&self, kind: TableKind, address: &A, source_group: AddressGroup) -> SlotPosition {
+        let address_bytes = address.as_peer_address().encode();
+        let group_bytes = AddressGroup::from_peer_address(&address.as_peer_address()).encode();
+        let source_group_bytes = source_group.encode();
+
+        let bucket = match kind {
+            TableKind::New => {
+                let index = self.key.hash(&[&source_group_bytes, &group_bytes])
+                    % NEW_BUCKETS_PER_SOURCE_GROUP;
+                self.key.hash(&[&source_group_bytes, &index.to_le_bytes()])
+                    % NEW_BUCKET_COUNT as u64
+            }
+            TableKind::Tried => {
+                let index = self.key.hash(&[&address_bytes]) % TRIED_BUCKETS_PER_GROUP;
+                self.key.hash(&[&group_bytes, &index.to_le_bytes()]) % TRIED_BUCKET_COUNT as u64
+            }
+        };
+        let slot = self.key.hash(&[&kind.encode(), &bucket.to_le_bytes(), &address_bytes])
+            % BUCKET_SIZE as u64;
+
+        SlotPosition {
+            bucket: bucket as usize,
+            slot: slot as usize,
+        }
+    }
+
+    pub fn key(&self) -> TableKey {
+        self.key
+    }
+
+    /// Returns the table the address is in and the address group of its source
+    pub fn location(&self, address: &A) -> Option<(TableKind, AddressGroup)> {
+        [TableKind::New, TableKind::Tried].into_iter().find_map(|kind| {
+            self.table(kind).entries.get(address).map(|entry| (kind, entry.source_group))
+        })
+    }
+
+    pub fn addresses(&self, kind: TableKind) -> impl Iterator<Item = &A> {
+        self.table(kind).entries.keys()
+    }
+
+    pub fn len(&self, kind: TableKind) -> usize {
+        self.table(kind).entries.len()
+    }
+
+    /// Returns the address that occupies the slot where the address would be placed
+    pub fn slot_owner(
+        &self,
+        kind: TableKind,
+        address: &A,
+        source_group: AddressGroup,
+    ) -> Option<&A> {
+        self.table(kind).slots.get(&self.position(kind, address, source_group))
+    }
+
+    /// Inserts the address if its slot is free, returns false otherwise.
+    ///
+    /// The address must not be in any table.
+    pub fn try_insert(&mut self, kind: TableKind, address: A, source_group: AddressGroup) -> bool {
+        assert!(self.location(&address).is_none());
+
+        let position = self.position(kind, &address, source_group);
+        if self.table(kind).slots.contains_key(&position) {
+            return false;
+        }
+
+        self.table_mut(kind).insert(
+            address,
+            TableEntry {
+                position,
+                source_group,
+            },
+        );
+        true
+    }
+
+    /// Removes the address from the table it's in and returns the address group of its source
+    pub fn remove(&mut self, address: &A) -> Option<AddressGroup> {
+        self.new
+            .remove(address)
+            .or_else(|| self.tried.remove(address))
+            .map(|entry| entry.source_group)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,148 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    collections::BTreeSet,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+};
+
+use rstest::rstest;
+use test_utils::random::{make_seedable_rng, Seed};
+
+use crate::testing_utils::{RandomAddressMaker, TestTcpAddressMaker};
+
+use super::*;
+
+fn random_address_in_group(rng: &mut impl Rng) -> SocketAddr {
+    SocketAddr::new(
+        IpAddr::V4(Ipv4Addr::new(1, 2, rng.gen(), rng.gen())),
+        rng.gen(),
+    )
+}
+
+fn source_group(address: &SocketAddr) -> AddressGroup {
+    AddressGroup::from_peer_address(&address.as_peer_address())
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn insert_remove(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let mut tables = AddressTables::new(TableKey::new_random(&mut rng));
+
+    let address = TestTcpAddressMaker::new();
+    let source = source_group(&TestTcpAddressMaker::new());
+
+    assert!(tables.try_insert(TableKind::New, address, source));
+    assert_eq!(tables.location(&address), Some((TableKind::New, source)));
+    assert_eq!(
+        tables.slot_owner(TableKind::New, &address, source),
+        Some(&address)
+    );
+    assert_eq!(tables.len(TableKind::New), 1);
+
+    assert_eq!(tables.remove(&address), Some(source));
+    assert_eq!(tables.location(&address), None);
+    assert_eq!(tables.remove(&address), None);
+
+    assert!(tables.try_insert(TableKind::Tried, address, source));
+    assert_eq!(tables.location(&address), Some((TableKind::Tried, source)));
+    assert_eq!(
+        tables.addresses(TableKind::Tried).collect::<Vec<_>>(),
+        vec![&address]
+    );
+    assert_eq!(tables.len(TableKind::New), 0);
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn positions_depend_on_key(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let key = TableKey::new_random(&mut rng);
+    let tables1 = AddressTables::<SocketAddr>::new(key);
+    let tables2 = AddressTables::<SocketAddr>::new(key);
+    let tables3 = AddressTables::<SocketAddr>::new(TableKey::new_random(&mut rng));
+
+    let addresses = (0..100).map(|_| TestTcpAddressMaker::new()).collect::<Vec<_>>();
+    let source = source_group(&TestTcpAddressMaker::new());
+
+    let positions = |tables: &AddressTables<SocketAddr>| {
+        addresses
+            .iter()
+            .map(|address| tables.position(TableKind::New, address, source))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(positions(&tables1), positions(&tables2));
+    assert_ne!(positions(&tables1), positions(&tables3));
+}
+
+// Addresses announced by peers from one address group must not fill the whole new table
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn new_buckets_limited_per_source(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let tables = AddressTables::<SocketAddr>::new(TableKey::new_random(&mut rng));
+    let source = source_group(&random_address_in_group(&mut rng));
+
+    let buckets = (0..10000)
+        .map(|_| {
+            let address = TestTcpAddressMaker::new();
+            tables.position(TableKind::New, &address, source).bucket
+        })
+        .collect::<BTreeSet<_>>();
+    assert!(buckets.len() <= NEW_BUCKETS_PER_SOURCE_GROUP as usize);
+}
+
+// Addresses from one address group must not fill the whole tried table
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn tried_buckets_limited_per_group(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let tables = AddressTables::<SocketAddr>::new(TableKey::new_random(&mut rng));
+
+    let buckets = (0..10000)
+        .map(|_| {
+            let address = random_address_in_group(&mut rng);
+            let source = source_group(&TestTcpAddressMaker::new());
+            tables.position(TableKind::Tried, &address, source).bucket
+        })
+        .collect::<BTreeSet<_>>();
+    assert!(buckets.len() <= TRIED_BUCKETS_PER_GROUP as usize);
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn occupied_slot(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let mut tables = AddressTables::new(TableKey::new_random(&mut rng));
+    let source = source_group(&TestTcpAddressMaker::new());
+
+    // Fill the table until some address collides with an existing one
+    loop {
+        let address = TestTcpAddressMaker::new();
+        if let Some(owner) = tables.slot_owner(TableKind::New, &address, source).copied() {
+            assert!(!tables.try_insert(TableKind::New, address, source));
+            assert_eq!(tables.location(&address), None);
+            assert_eq!(tables.location(&owner), Some((TableKind::New, source)));
+            break;
+        }
+        assert!(tables.try_insert(TableKind::New, address, source));
+    }
+}
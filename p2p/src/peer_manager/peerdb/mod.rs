@@ -18,13 +18,23 @@
 //! The peer database stores:
 //! - all outbound peer addresses
 //! - banned addresses
+//! - banned node public keys
+//!
+//! Peers that authenticate with a static node key (see [`crate::node_key`]) are also identified by it:
+//! the key is banned along with the address, so a misbehaving peer can't come back from another address,
+//! and reserved nodes pinned with a key are recognized whatever address they connect from.
 //!
 //! Connected peers are those peers that the [`crate::peer_manager::PeerManager`] has an active
 //! connection with. Available addresses are discovered through various peer discovery mechanisms and they are
 //! used by [`crate::peer_manager::PeerManager::heartbeat()`] to establish new outbound connections
 //! if the actual number of active connection is less than the desired number of connections.
+//!
+//! Non-reserved addresses are kept in the bucketed "new" and "tried" tables (see [`address_tables`]),
+//! which limits how many addresses a single network range can insert.
+//! The tables and the connection history of the addresses are persisted in the storage.
 
 pub mod address_data;
+pub mod address_tables;
 pub mod storage;
 pub mod storage_impl;
 mod storage_load;
@@ -37,23 +47,40 @@ use std::{
 };
 
 use common::time_getter::TimeGetter;
-use crypto::random::{make_pseudo_rng, seq::IteratorRandom, SliceRandom};
-use itertools::Itertools;
+use crypto::random::{make_pseudo_rng, seq::IteratorRandom, Rng, SliceRandom};
 use logging::log;
 
 use crate::{
     config,
-    error::P2pError,
+    error::{P2pError, PeerError},
     net::{default_backend::transport::TransportAddress, AsBannableAddress},
+    node_key::{split_pinned_node_key, NodePublicKey},
 };
 
 use self::{
     address_data::{AddressData, AddressStateTransitionTo},
-    storage::{PeerDbStorage, PeerDbStorageWrite},
+    address_tables::{AddressTables, TableKind},
+    storage::{KnownAddressRecord, PeerDbStorage, PeerDbStorageWrite},
     storage_load::LoadedStorage,
 };
 
-use super::{address_groups::AddressGroup, MAX_OUTBOUND_CONNECTIONS};
+use super::address_groups::AddressGroup;
+
+/// An existing tried address with a recent successful connection is never evicted by a collision
+const TRIED_COLLISION_RECENT_SUCCESS: Duration = Duration::from_secs(4 * 3600);
+
+/// If the existing tried address could not be tested in this time, it's evicted anyway
+const TRIED_COLLISION_TIMEOUT: Duration = Duration::from_secs(40 * 60);
+
+/// A new address that has connected successfully, but whose tried slot is occupied by another address.
+///
+/// The existing address is evicted only if a feeler connection to it fails
+/// ("test-before-evict" in Bitcoin Core).
+struct TriedCollision<A> {
+    existing: A,
+
+    since: Duration,
+}
 
 pub struct PeerDb<A, B, S> {
     /// P2P configuration
@@ -62,6 +89,13 @@ pub struct PeerDb<A, B, S> {
     /// Map of all outbound peer addresses
     addresses: BTreeMap<A, AddressData>,
 
+    /// The "new" and "tried" tables of the non-reserved addresses.
+    /// Every address in the tables must exist in the `addresses` map.
+    tables: AddressTables<A>,
+
+    /// Unresolved collisions in the tried table
+    tried_collisions: BTreeMap<A, TriedCollision<A>>,
+
     /// Set of addresses that have the `reserved` flag set.
     /// Used as an optimization to not iterate over the entire `addresses` map.
     /// Every listed address must exist in the `addresses` map.
@@ -73,6 +107,12 @@ pub struct PeerDb<A, B, S> {
     /// when `current_time > ban_duration`.
     banned_addresses: BTreeMap<B, Duration>,
 
+    /// Public keys of the reserved nodes that are pinned in the configuration
+    reserved_node_keys: BTreeSet<NodePublicKey>,
+
+    /// Banned node public keys along with the duration of the ban (same as `banned_addresses`)
+    banned_node_keys: BTreeMap<NodePublicKey, Duration>,
+
     time_getter: TimeGetter,
 
     storage: S,
@@ -96,47 +136,85 @@ where
             .boot_nodes
             .iter()
             .map(|addr| {
-                addr.parse::<A>().map_err(|_err| {
+                let (_node_key, address) = split_pinned_node_key(addr)?;
+                address.parse::<A>().map_err(|_err| {
                     P2pError::InvalidConfigurationValue(format!("Invalid address: {addr}"))
                 })
             })
             .collect::<Result<BTreeSet<_>, _>>()?;
+        let mut reserved_node_keys = BTreeSet::new();
         let reserved_nodes = p2p_config
             .reserved_nodes
             .iter()
             .map(|addr| {
-                addr.parse::<A>().map_err(|_err| {
+                let (node_key, address) = split_pinned_node_key(addr)?;
+                reserved_node_keys.extend(node_key);
+                address.parse::<A>().map_err(|_err| {
                     P2pError::InvalidConfigurationValue(format!("Invalid address: {addr}"))
                 })
             })
             .collect::<Result<BTreeSet<_>, _>>()?;
 
         let now = time_getter.get_time();
-        let addresses = loaded_storage
-            .known_addresses
-            .iter()
-            .chain(boot_nodes.iter())
-            .chain(reserved_nodes.iter())
-            .map(|addr| {
-                (
-                    addr.clone(),
-                    AddressData::new(
-                        loaded_storage.known_addresses.contains(addr),
-                        reserved_nodes.contains(addr),
-                        now,
-                    ),
-                )
-            })
-            .collect();
+        let mut tables = AddressTables::new(loaded_storage.address_table_key);
+        let mut addresses = BTreeMap::new();
+        let mut dropped_addresses = Vec::new();
+
+        for (address, record) in loaded_storage.known_addresses {
+            // Slots could only be occupied if the table parameters have changed
+            let inserted = tables.try_insert(record.table, address.clone(), record.source_group)
+                || tables.try_insert(TableKind::New, address.clone(), record.source_group);
+            if !inserted {
+                dropped_addresses.push(address);
+                continue;
+            }
+
+            let address_data = AddressData::new(
+                record.table == TableKind::Tried || record.last_success.is_some(),
+                reserved_nodes.contains(&address),
+                now,
+            )
+            .with_history(record.last_attempt, record.last_success);
+            addresses.insert(address, address_data);
+        }
 
-        Ok(Self {
+        let mut new_boot_nodes = Vec::new();
+        for address in boot_nodes {
+            if !addresses.contains_key(&address)
+                && tables.try_insert(TableKind::New, address.clone(), AddressGroup::Local)
+            {
+                addresses.insert(
+                    address.clone(),
+                    AddressData::new(false, reserved_nodes.contains(&address), now),
+                );
+                new_boot_nodes.push(address);
+            }
+        }
+
+        for address in reserved_nodes.iter() {
+            addresses
+                .entry(address.clone())
+                .or_insert_with(|| AddressData::new(false, true, now));
+        }
+
+        let peerdb = Self {
             addresses,
+            tables,
+            tried_collisions: BTreeMap::new(),
             banned_addresses: loaded_storage.banned_addresses,
             reserved_nodes,
+            reserved_node_keys,
+            banned_node_keys: loaded_storage.banned_node_keys,
             p2p_config,
             time_getter,
             storage,
-        })
+        };
+
+        for address in dropped_addresses.iter().chain(new_boot_nodes.iter()) {
+            peerdb.update_stored_address(address);
+        }
+
+        Ok(peerdb)
     }
 
     /// Iterator of all known addresses.
@@ -146,45 +224,72 @@ where
         self.addresses.keys()
     }
 
-    /// Selects peer addresses for outbound connections (except reserved).
+    /// Checks if the address can be used for a new outbound connection
+    fn is_outbound_candidate(&self, address: &A, now: Duration) -> bool {
+        let address_data =
+            self.addresses.get(address).expect("addresses in the tables must be known");
+        address_data.connect_now(now)
+            && !address_data.reserved()
+            && !self.banned_addresses.contains_key(&address.as_bannable())
+    }
+
+    /// Selects up to `count` peer addresses for outbound connections (except reserved).
     /// Only one outbound connection is allowed per address group.
-    pub fn select_new_outbound_addresses(&self, all_normal_outbound: &BTreeSet<A>) -> Vec<A> {
-        let count = MAX_OUTBOUND_CONNECTIONS.saturating_sub(all_normal_outbound.len());
+    ///
+    /// Addresses are selected from the tried and new tables with equal probability.
+    pub fn select_new_outbound_addresses(
+        &self,
+        all_normal_outbound: &BTreeSet<A>,
+        count: usize,
+    ) -> Vec<A> {
         if count == 0 {
             return Vec::new();
         }
 
         let now = self.time_getter.get_time();
+        let mut rng = make_pseudo_rng();
 
         // Only consider outbound connections, as inbound connections are open to attackers
-        let all_outbound_groups = all_normal_outbound
+        let mut selected_groups = all_normal_outbound
             .iter()
             .map(|a| AddressGroup::from_peer_address(&a.as_peer_address()))
             .collect::<BTreeSet<_>>();
 
-        let mut selected = self
-            .addresses
-            .iter()
-            .filter_map(|(addr, address_data)| {
-                if address_data.connect_now(now)
-                    && !all_outbound_groups
-                        .contains(&AddressGroup::from_peer_address(&addr.as_peer_address()))
-                    && !address_data.reserved()
-                    && !self.banned_addresses.contains_key(&addr.as_bannable())
-                {
-                    Some(addr.clone())
-                } else {
-                    None
-                }
-            })
-            .choose_multiple(&mut make_pseudo_rng(), count);
+        let mut candidates = |kind: TableKind| {
+            let mut candidates = self
+                .tables
+                .addresses(kind)
+                .filter(|addr| {
+                    self.is_outbound_candidate(addr, now)
+                        && !selected_groups
+                            .contains(&AddressGroup::from_peer_address(&addr.as_peer_address()))
+                })
+                .cloned()
+                .collect::<Vec<_>>();
+            candidates.shuffle(&mut rng);
+            candidates
+        };
+        let mut tried = candidates(TableKind::Tried);
+        let mut new = candidates(TableKind::New);
+
+        let mut selected = Vec::new();
+        while selected.len() < count {
+            let use_tried = match (tried.is_empty(), new.is_empty()) {
+                (true, true) => break,
+                (false, true) => true,
+                (true, false) => false,
+                (false, false) => rng.gen::<bool>(),
+            };
+            let address = if use_tried { tried.pop() } else { new.pop() }
+                .expect("candidate list must not be empty");
+
+            // Drop duplicate address groups as needed
+            if selected_groups.insert(AddressGroup::from_peer_address(&address.as_peer_address())) {
+                selected.push(address);
+            }
+        }
 
-        // Drop duplicate address groups as needed (shuffle selected addresses first to make the selection fair)
-        selected.shuffle(&mut make_pseudo_rng());
         selected
-            .into_iter()
-            .unique_by(|a| AddressGroup::from_peer_address(&a.as_peer_address()))
-            .collect()
     }
 
     /// Selects reserved peer addresses for outbound connections
@@ -209,12 +314,46 @@ where
             .collect()
     }
 
+    /// Selects an address for a short-lived "feeler" connection.
+    ///
+    /// Feeler connections test tried addresses with pending collisions first,
+    /// then random addresses from the new table, moving the reachable ones to the tried table.
+    pub fn select_feeler_address(&self) -> Option<A> {
+        let now = self.time_getter.get_time();
+
+        let collision_address = self
+            .tried_collisions
+            .values()
+            .map(|collision| &collision.existing)
+            .find(|addr| {
+                self.addresses.contains_key(addr) && self.is_outbound_candidate(addr, now)
+            });
+
+        collision_address
+            .or_else(|| {
+                self.tables
+                    .addresses(TableKind::New)
+                    .filter(|addr| self.is_outbound_candidate(addr, now))
+                    .choose(&mut make_pseudo_rng())
+            })
+            .cloned()
+    }
+
     /// Perform the PeerDb maintenance
     pub fn heartbeat(&mut self) {
         let now = self.time_getter.get_time();
-        self.addresses.retain(|_addr, address_data| address_data.retain(now));
+        let expired_addresses = self
+            .addresses
+            .iter()
+            .filter(|(_addr, address_data)| !address_data.retain(now))
+            .map(|(addr, _address_data)| addr.clone())
+            .collect::<Vec<_>>();
+        for address in expired_addresses {
+            self.forget_address(&address);
+        }
+
+        self.resolve_tried_collisions();
 
-        let now = self.time_getter.get_time();
         self.banned_addresses.retain(|address, banned_till| {
             let banned = now <= *banned_till;
 
@@ -227,22 +366,236 @@ where
 
             banned
         });
+
+        self.banned_node_keys.retain(|node_key, banned_till| {
+            let banned = now <= *banned_till;
+
+            if !banned {
+                storage::update_db(&self.storage, |tx| {
+                    tx.del_banned_node_key(&node_key.to_string())
+                })
+                .expect("removing banned node key is expected to succeed");
+            }
+
+            banned
+        });
     }
 
     /// Add new peer addresses
-    pub fn peer_discovered(&mut self, address: A) {
-        if let Entry::Vacant(entry) = self.addresses.entry(address.clone()) {
-            log::debug!("new address discovered: {}", address.to_string());
-            entry.insert(AddressData::new(false, false, self.time_getter.get_time()));
+    ///
+    /// `source` is the address of the peer that announced the address,
+    /// it's `None` for the addresses from trusted sources (like DNS seeds).
+    pub fn peer_discovered(&mut self, address: A, source: Option<&A>) {
+        if self.addresses.contains_key(&address) {
+            return;
+        }
+
+        let source_group = source.map_or(AddressGroup::Local, |source| {
+            AddressGroup::from_peer_address(&source.as_peer_address())
+        });
+
+        if !self.try_add_to_new_table(&address, source_group) {
+            log::debug!("no room for the new address {}", address.to_string());
+            return;
+        }
+
+        log::debug!("new address discovered: {}", address.to_string());
+        self.addresses.insert(
+            address.clone(),
+            AddressData::new(false, false, self.time_getter.get_time()),
+        );
+        self.update_stored_address(&address);
+    }
+
+    /// Places the address into the new table, replacing a terrible address that occupies its slot.
+    ///
+    /// Returns false if the slot is occupied by an address that is still useful.
+    fn try_add_to_new_table(&mut self, address: &A, source_group: AddressGroup) -> bool {
+        let now = self.time_getter.get_time();
+
+        if let Some(existing) =
+            self.tables.slot_owner(TableKind::New, address, source_group).cloned()
+        {
+            let is_terrible = self
+                .addresses
+                .get(&existing)
+                .map_or(true, |address_data| address_data.is_terrible(now));
+            if !is_terrible {
+                return false;
+            }
+
+            log::debug!(
+                "address {} is replaced in the new table",
+                existing.to_string()
+            );
+            self.forget_address(&existing);
+        }
+
+        let inserted = self.tables.try_insert(TableKind::New, address.clone(), source_group);
+        assert!(inserted);
+        true
+    }
+
+    /// Moves the address to the tried table after a successful outbound connection.
+    ///
+    /// If the tried slot is occupied, the collision is resolved later (see `resolve_tried_collisions`).
+    fn mark_tried(&mut self, address: &A) {
+        let location = self.tables.location(address);
+        let source_group = match location {
+            Some((TableKind::Tried, _)) => return,
+            Some((TableKind::New, source_group)) => source_group,
+            // The address was not announced by anyone (e.g. the connection was requested by RPC)
+            None => AddressGroup::Local,
+        };
+
+        match self.tables.slot_owner(TableKind::Tried, address, source_group).cloned() {
+            Some(existing) => {
+                log::debug!(
+                    "tried table collision between {} and {}",
+                    address.to_string(),
+                    existing.to_string()
+                );
+                if location.is_none() && !self.try_add_to_new_table(address, source_group) {
+                    return;
+                }
+                if let Entry::Vacant(entry) = self.tried_collisions.entry(address.clone()) {
+                    entry.insert(TriedCollision {
+                        existing,
+                        since: self.time_getter.get_time(),
+                    });
+                }
+            }
+            None => {
+                self.tables.remove(address);
+                let inserted =
+                    self.tables.try_insert(TableKind::Tried, address.clone(), source_group);
+                assert!(inserted);
+            }
+        }
+    }
+
+    /// Moves the existing tried address back to the new table (or forgets it if there is no room)
+    fn evict_from_tried(&mut self, address: &A) {
+        let source_group = self.tables.remove(address).unwrap_or(AddressGroup::Local);
+        if !self.try_add_to_new_table(address, source_group) {
+            self.forget_address(address);
+        } else {
+            self.update_stored_address(address);
+        }
+    }
+
+    fn resolve_tried_collisions(&mut self) {
+        let now = self.time_getter.get_time();
+
+        for (address, collision) in std::mem::take(&mut self.tried_collisions) {
+            // The address could have been forgotten or moved in the meantime
+            if !self.addresses.contains_key(&address)
+                || self.tables.location(&address).map(|(kind, _)| kind) != Some(TableKind::New)
+            {
+                continue;
+            }
+
+            let source_group = self
+                .tables
+                .location(&address)
+                .map_or(AddressGroup::Local, |(_, source_group)| source_group);
+            let slot_owner =
+                self.tables.slot_owner(TableKind::Tried, &address, source_group).cloned();
+            if slot_owner.as_ref() != Some(&collision.existing) {
+                if slot_owner.is_none() {
+                    self.mark_tried(&address);
+                    self.update_stored_address(&address);
+                }
+                continue;
+            }
+
+            let existing_data = self
+                .addresses
+                .get(&collision.existing)
+                .expect("addresses in the tables must be known");
+            let recent_success = existing_data.is_connected()
+                || existing_data.last_success().map_or(false, |last_success| {
+                    last_success + TRIED_COLLISION_RECENT_SUCCESS > now
+                });
+            if recent_success {
+                log::debug!(
+                    "tried table collision resolved, {} is kept",
+                    collision.existing.to_string()
+                );
+                continue;
+            }
+
+            let tested = existing_data
+                .last_attempt()
+                .map_or(false, |last_attempt| last_attempt >= collision.since);
+            if tested || now >= collision.since + TRIED_COLLISION_TIMEOUT {
+                log::debug!(
+                    "tried table collision resolved, {} is evicted",
+                    collision.existing.to_string()
+                );
+                self.evict_from_tried(&collision.existing);
+                self.mark_tried(&address);
+                self.update_stored_address(&address);
+            } else {
+                self.tried_collisions.insert(address, collision);
+            }
+        }
+    }
+
+    /// Removes the address from the tables (and from memory, unless it's reserved)
+    fn forget_address(&mut self, address: &A) {
+        self.tables.remove(address);
+        self.tried_collisions.remove(address);
+        if !self.reserved_nodes.contains(address) {
+            self.addresses.remove(address);
         }
+        self.update_stored_address(address);
+    }
+
+    /// Stores the address with its connection history if it's in the tables, deletes it otherwise
+    fn update_stored_address(&self, address: &A) {
+        let record = self.tables.location(address).and_then(|(table, source_group)| {
+            self.addresses.get(address).map(|address_data| KnownAddressRecord {
+                table,
+                source_group,
+                last_attempt: address_data.last_attempt(),
+                last_success: address_data.last_success(),
+            })
+        });
+
+        storage::update_db(&self.storage, |tx| match &record {
+            Some(record) => tx.add_known_address(&address.to_string(), record),
+            None => tx.del_known_address(&address.to_string()),
+        })
+        .expect("updating known address is expected to succeed");
     }
 
     /// Report outbound connection failure
     ///
     /// When [`crate::peer_manager::PeerManager::heartbeat()`] has initiated an outbound connection
     /// and the connection is refused, it's reported back to the `PeerDb` so it marks the address as unreachable.
-    pub fn report_outbound_failure(&mut self, address: A, _error: &P2pError) {
-        self.change_address_state(address, AddressStateTransitionTo::ConnectionFailed);
+    ///
+    /// Errors that say nothing about the address itself (for example, a duplicate connection) are ignored.
+    pub fn report_outbound_failure(&mut self, address: A, error: &P2pError) {
+        let is_address_failure = !matches!(
+            error,
+            P2pError::PeerError(
+                PeerError::PeerAlreadyExists
+                    | PeerError::Pending(_)
+                    | PeerError::BannedAddress(_)
+                    | PeerError::BannedNodeKey(_)
+                    | PeerError::TooManyPeers
+            )
+        );
+
+        if is_address_failure {
+            self.change_address_state(address, AddressStateTransitionTo::ConnectionFailed);
+        } else {
+            log::debug!(
+                "outbound connection to {} failed: {error}",
+                address.to_string()
+            );
+        }
     }
 
     /// Mark peer as connected
@@ -250,7 +603,11 @@ where
     /// After `PeerManager` has established either an inbound or an outbound connection,
     /// it informs the `PeerDb` about it.
     pub fn outbound_peer_connected(&mut self, address: A) {
-        self.change_address_state(address, AddressStateTransitionTo::Connected);
+        self.change_address_state(address.clone(), AddressStateTransitionTo::Connected);
+        if !self.reserved_nodes.contains(&address) {
+            self.mark_tried(&address);
+            self.update_stored_address(&address);
+        }
     }
 
     /// Handle peer disconnect event with unspecified reason
@@ -273,8 +630,6 @@ where
             .entry(address.clone())
             .or_insert_with(|| AddressData::new(false, false, now));
 
-        let is_persistent_old = address_data.is_persistent();
-
         log::debug!(
             "update address {} state to {:?}",
             address.to_string(),
@@ -283,29 +638,18 @@ where
 
         address_data.transition_to(transition, now, &mut make_pseudo_rng());
 
-        let is_persistent_new = address_data.is_persistent();
-
-        match (is_persistent_old, is_persistent_new) {
-            (false, true) => {
-                storage::update_db(&self.storage, |tx| {
-                    tx.add_known_address(&address.to_string())
-                })
-                .expect("adding address expected to succeed (peer_connected)");
-            }
-            (true, false) => {
-                storage::update_db(&self.storage, |tx| {
-                    tx.del_known_address(&address.to_string())
-                })
-                .expect("adding address expected to succeed (peer_connected)");
-            }
-            _ => {}
-        }
+        self.update_stored_address(&address);
     }
 
     pub fn is_reserved_node(&self, address: &A) -> bool {
         self.reserved_nodes.contains(address)
     }
 
+    /// Checks if the node key is pinned for one of the reserved nodes
+    pub fn is_reserved_node_key(&self, node_key: &NodePublicKey) -> bool {
+        self.reserved_node_keys.contains(node_key)
+    }
+
     pub fn add_reserved_node(&mut self, address: A) {
         self.change_address_state(address.clone(), AddressStateTransitionTo::SetReserved);
         self.reserved_nodes.insert(address);
@@ -314,6 +658,15 @@ where
     pub fn remove_reserved_node(&mut self, address: A) {
         self.change_address_state(address.clone(), AddressStateTransitionTo::UnsetReserved);
         self.reserved_nodes.remove(&address);
+
+        // Make the address available for the normal outbound connections again
+        if self.tables.location(&address).is_none() {
+            if self.try_add_to_new_table(&address, AddressGroup::Local) {
+                self.update_stored_address(&address);
+            } else {
+                self.addresses.remove(&address);
+            }
+        }
     }
 
     /// Checks if the given address is banned
@@ -321,6 +674,23 @@ where
         self.banned_addresses.contains_key(address)
     }
 
+    /// Checks if the given node key is banned
+    pub fn is_node_key_banned(&self, node_key: &NodePublicKey) -> bool {
+        self.banned_node_keys.contains_key(node_key)
+    }
+
+    /// Bans the node key, so the peer is rejected whatever address it connects from
+    pub fn ban_node_key(&mut self, node_key: &NodePublicKey) {
+        let ban_till = self.time_getter.get_time() + *self.p2p_config.ban_duration;
+
+        storage::update_db(&self.storage, |tx| {
+            tx.add_banned_node_key(&node_key.to_string(), ban_till)
+        })
+        .expect("adding banned node key is expected to succeed");
+
+        self.banned_node_keys.insert(*node_key, ban_till);
+    }
+
     /// Changes the address state to banned
     pub fn ban_peer(&mut self, address: &A) {
         let bannable_address = address.as_bannable();
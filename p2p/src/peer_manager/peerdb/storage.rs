@@ -15,21 +15,59 @@
 
 use std::time::Duration;
 
+use serialization::{Decode, Encode};
+
+use crate::peer_manager::address_groups::AddressGroup;
+
+use super::address_tables::{TableKey, TableKind};
+
+/// Stored information about a known address
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct KnownAddressRecord {
+    /// The address table the address is in
+    pub table: TableKind,
+
+    /// Address group of the peer that announced the address
+    pub source_group: AddressGroup,
+
+    /// Time of the last outbound connection attempt
+    pub last_attempt: Option<Duration>,
+
+    /// Time of the last successful outbound connection
+    pub last_success: Option<Duration>,
+}
+
 pub trait PeerDbStorageRead {
     fn get_version(&self) -> Result<Option<u32>, storage::Error>;
 
-    fn get_known_addresses(&self) -> Result<Vec<String>, storage::Error>;
+    fn get_address_table_key(&self) -> Result<Option<TableKey>, storage::Error>;
+
+    fn get_known_addresses(&self) -> Result<Vec<(String, KnownAddressRecord)>, storage::Error>;
+
+    /// Known addresses from the storage version 1 (used for the migration only)
+    fn get_known_addresses_v1(&self) -> Result<Vec<String>, storage::Error>;
 
     fn get_banned_addresses(&self) -> Result<Vec<(String, Duration)>, storage::Error>;
+
+    fn get_banned_node_keys(&self) -> Result<Vec<(String, Duration)>, storage::Error>;
 }
 
 pub trait PeerDbStorageWrite {
     fn set_version(&mut self, version: u32) -> Result<(), storage::Error>;
 
-    fn add_known_address(&mut self, address: &str) -> Result<(), storage::Error>;
+    fn set_address_table_key(&mut self, key: &TableKey) -> Result<(), storage::Error>;
+
+    fn add_known_address(
+        &mut self,
+        address: &str,
+        record: &KnownAddressRecord,
+    ) -> Result<(), storage::Error>;
 
     fn del_known_address(&mut self, address: &str) -> Result<(), storage::Error>;
 
+    /// Delete a known address from the storage version 1 (used for the migration only)
+    fn del_known_address_v1(&mut self, address: &str) -> Result<(), storage::Error>;
+
     fn add_banned_address(
         &mut self,
         address: &str,
@@ -37,6 +75,14 @@ pub trait PeerDbStorageWrite {
     ) -> Result<(), storage::Error>;
 
     fn del_banned_address(&mut self, address: &str) -> Result<(), storage::Error>;
+
+    fn add_banned_node_key(
+        &mut self,
+        node_key: &str,
+        duration: Duration,
+    ) -> Result<(), storage::Error>;
+
+    fn del_banned_node_key(&mut self, node_key: &str) -> Result<(), storage::Error>;
 }
 
 pub trait PeerDbTransactionRo: PeerDbStorageRead {
@@ -15,9 +15,12 @@
 
 use std::time::Duration;
 
-use super::storage::{
-    PeerDbStorage, PeerDbStorageRead, PeerDbStorageWrite, PeerDbTransactionRo, PeerDbTransactionRw,
-    PeerDbTransactional,
+use super::{
+    address_tables::TableKey,
+    storage::{
+        KnownAddressRecord, PeerDbStorage, PeerDbStorageRead, PeerDbStorageWrite,
+        PeerDbTransactionRo, PeerDbTransactionRw, PeerDbTransactional,
+    },
 };
 use serialization::{encoded::Encoded, DecodeAll, Encode};
 
@@ -29,15 +32,22 @@ storage::decl_schema! {
         /// Storage for individual values
         pub DBValue: Map<ValueId, Vec<u8>>,
 
-        /// Table for known addresses
+        /// Table for known addresses (storage version 1)
         pub DBKnownAddresses: Map<String, ()>,
 
+        /// Table for known addresses with their connection history
+        pub DBAddresses: Map<String, KnownAddressRecord>,
+
         /// Table for banned addresses
         pub DBBannedAddresses: Map<String, Duration>,
+
+        /// Table for banned node public keys
+        pub DBBannedNodeKeys: Map<String, Duration>,
     }
 }
 
 const VALUE_ID_VERSION: ValueId = 1;
+const VALUE_ID_ADDRESS_TABLE_KEY: ValueId = 2;
 
 pub struct PeerDbStoreTxRo<'st, B: storage::Backend>(storage::TransactionRo<'st, B, Schema>);
 
@@ -72,11 +82,23 @@ impl<'st, B: storage::Backend> PeerDbStorageWrite for PeerDbStoreTxRw<'st, B> {
         self.0.get_mut::<DBValue, _>().put(VALUE_ID_VERSION, version.encode())
     }
 
-    fn add_known_address(&mut self, address: &str) -> Result<(), storage::Error> {
-        self.0.get_mut::<DBKnownAddresses, _>().put(address, ())
+    fn set_address_table_key(&mut self, key: &TableKey) -> Result<(), storage::Error> {
+        self.0.get_mut::<DBValue, _>().put(VALUE_ID_ADDRESS_TABLE_KEY, key.encode())
+    }
+
+    fn add_known_address(
+        &mut self,
+        address: &str,
+        record: &KnownAddressRecord,
+    ) -> Result<(), storage::Error> {
+        self.0.get_mut::<DBAddresses, _>().put(address, record)
     }
 
     fn del_known_address(&mut self, address: &str) -> Result<(), storage::Error> {
+        self.0.get_mut::<DBAddresses, _>().del(address)
+    }
+
+    fn del_known_address_v1(&mut self, address: &str) -> Result<(), storage::Error> {
         self.0.get_mut::<DBKnownAddresses, _>().del(address)
     }
 
@@ -91,6 +113,18 @@ impl<'st, B: storage::Backend> PeerDbStorageWrite for PeerDbStoreTxRw<'st, B> {
     fn del_banned_address(&mut self, address: &str) -> Result<(), storage::Error> {
         self.0.get_mut::<DBBannedAddresses, _>().del(address)
     }
+
+    fn add_banned_node_key(
+        &mut self,
+        node_key: &str,
+        duration: Duration,
+    ) -> Result<(), storage::Error> {
+        self.0.get_mut::<DBBannedNodeKeys, _>().put(node_key, duration)
+    }
+
+    fn del_banned_node_key(&mut self, node_key: &str) -> Result<(), storage::Error> {
+        self.0.get_mut::<DBBannedNodeKeys, _>().del(node_key)
+    }
 }
 
 impl<'st, B: storage::Backend> PeerDbTransactionRw for PeerDbStoreTxRw<'st, B> {
@@ -112,7 +146,21 @@ impl<'st, B: storage::Backend> PeerDbStorageRead for PeerDbStoreTxRo<'st, B> {
         }))
     }
 
-    fn get_known_addresses(&self) -> Result<Vec<String>, storage::Error> {
+    fn get_address_table_key(&self) -> Result<Option<TableKey>, storage::Error> {
+        let map = self.0.get::<DBValue, _>();
+        let vec_opt = map.get(VALUE_ID_ADDRESS_TABLE_KEY)?.as_ref().map(Encoded::decode);
+        Ok(vec_opt.map(|vec| {
+            TableKey::decode_all(&mut vec.as_ref()).expect("db values to be encoded correctly")
+        }))
+    }
+
+    fn get_known_addresses(&self) -> Result<Vec<(String, KnownAddressRecord)>, storage::Error> {
+        let map = self.0.get::<DBAddresses, _>();
+        let iter = map.prefix_iter_decoded(&())?;
+        Ok(iter.collect::<Vec<_>>())
+    }
+
+    fn get_known_addresses_v1(&self) -> Result<Vec<String>, storage::Error> {
         let map = self.0.get::<DBKnownAddresses, _>();
         let iter = map.prefix_iter_decoded(&())?;
         Ok(iter.map(|(key, _value)| key).collect::<Vec<_>>())
@@ -123,6 +171,12 @@ impl<'st, B: storage::Backend> PeerDbStorageRead for PeerDbStoreTxRo<'st, B> {
         let iter = map.prefix_iter_decoded(&())?;
         Ok(iter.collect::<Vec<_>>())
     }
+
+    fn get_banned_node_keys(&self) -> Result<Vec<(String, Duration)>, storage::Error> {
+        let map = self.0.get::<DBBannedNodeKeys, _>();
+        let iter = map.prefix_iter_decoded(&())?;
+        Ok(iter.collect::<Vec<_>>())
+    }
 }
 
 impl<'st, B: storage::Backend> PeerDbTransactionRo for PeerDbStoreTxRo<'st, B> {
@@ -13,23 +13,30 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{
-    collections::{BTreeMap, BTreeSet},
-    str::FromStr,
-    time::Duration,
-};
+use std::{collections::BTreeMap, str::FromStr, time::Duration};
+
+use crypto::random::make_pseudo_rng;
 
-use crate::error::P2pError;
+use crate::{error::P2pError, node_key::NodePublicKey, peer_manager::address_groups::AddressGroup};
 
-use super::storage::{
-    PeerDbStorage, PeerDbStorageRead, PeerDbStorageWrite, PeerDbTransactionRo, PeerDbTransactionRw,
+use super::{
+    address_tables::{TableKey, TableKind},
+    storage::{
+        KnownAddressRecord, PeerDbStorage, PeerDbStorageRead, PeerDbStorageWrite,
+        PeerDbTransactionRo, PeerDbTransactionRw,
+    },
 };
 
-const STORAGE_VERSION: u32 = 1;
+/// Version 1: known addresses are stored without any connection history
+const STORAGE_VERSION_V1: u32 = 1;
+/// Version 2: known addresses are stored with their address table and connection history
+const STORAGE_VERSION_V2: u32 = 2;
 
 pub struct LoadedStorage<A, B> {
-    pub known_addresses: BTreeSet<A>,
+    pub known_addresses: BTreeMap<A, KnownAddressRecord>,
     pub banned_addresses: BTreeMap<B, Duration>,
+    pub banned_node_keys: BTreeMap<NodePublicKey, Duration>,
+    pub address_table_key: TableKey,
 }
 
 impl<A: Ord + FromStr, B: Ord + FromStr> LoadedStorage<A, B> {
@@ -40,7 +47,11 @@ impl<A: Ord + FromStr, B: Ord + FromStr> LoadedStorage<A, B> {
 
         match version {
             None => Self::init_storage(storage),
-            Some(STORAGE_VERSION) => Self::load_storage_v1(storage),
+            Some(STORAGE_VERSION_V1) => {
+                Self::migrate_storage_v1(storage)?;
+                Self::load_storage_v2(storage)
+            }
+            Some(STORAGE_VERSION_V2) => Self::load_storage_v2(storage),
             Some(version) => Err(P2pError::InvalidStorageState(format!(
                 "Unexpected PeerDb storage version: {version}"
             ))),
@@ -48,30 +59,65 @@ impl<A: Ord + FromStr, B: Ord + FromStr> LoadedStorage<A, B> {
     }
 
     fn init_storage<S: PeerDbStorage>(storage: &S) -> crate::Result<LoadedStorage<A, B>> {
+        let address_table_key = TableKey::new_random(&mut make_pseudo_rng());
         let mut tx = storage.transaction_rw()?;
-        tx.set_version(STORAGE_VERSION)?;
+        tx.set_version(STORAGE_VERSION_V2)?;
+        tx.set_address_table_key(&address_table_key)?;
         tx.commit()?;
         Ok(LoadedStorage {
-            known_addresses: BTreeSet::new(),
+            known_addresses: BTreeMap::new(),
             banned_addresses: BTreeMap::new(),
+            banned_node_keys: BTreeMap::new(),
+            address_table_key,
         })
     }
 
-    fn load_storage_v1<S: PeerDbStorage>(storage: &S) -> crate::Result<LoadedStorage<A, B>> {
+    /// Version 1 only stored addresses that were reachable at least once,
+    /// so they are moved to the tried table.
+    fn migrate_storage_v1<S: PeerDbStorage>(storage: &S) -> crate::Result<()> {
         let tx = storage.transaction_ro()?;
+        let known_addresses = tx.get_known_addresses_v1()?;
+        tx.close();
+
+        let mut tx = storage.transaction_rw()?;
+        for address in known_addresses {
+            let record = KnownAddressRecord {
+                table: TableKind::Tried,
+                source_group: AddressGroup::Local,
+                last_attempt: None,
+                last_success: None,
+            };
+            tx.add_known_address(&address, &record)?;
+            tx.del_known_address_v1(&address)?;
+        }
+        tx.set_address_table_key(&TableKey::new_random(&mut make_pseudo_rng()))?;
+        tx.set_version(STORAGE_VERSION_V2)?;
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    fn load_storage_v2<S: PeerDbStorage>(storage: &S) -> crate::Result<LoadedStorage<A, B>> {
+        let tx = storage.transaction_ro()?;
+
+        let address_table_key = tx.get_address_table_key()?.ok_or_else(|| {
+            P2pError::InvalidStorageState("Missing address table key in PeerDb storage".to_owned())
+        })?;
 
         // TODO: Is there a concern that the number of addresses will be so huge that it'll cause a hiccup?
         let known_addresses = tx
             .get_known_addresses()?
-            .iter()
-            .map(|addr| {
-                addr.parse::<A>().map_err(|_err| {
-                    P2pError::InvalidStorageState(format!(
-                        "Invalid address in PeerDb storage: {addr}"
-                    ))
-                })
+            .into_iter()
+            .map(|(addr, record)| {
+                addr.parse::<A>()
+                    .map_err(|_err| {
+                        P2pError::InvalidStorageState(format!(
+                            "Invalid address in PeerDb storage: {addr}"
+                        ))
+                    })
+                    .map(|addr| (addr, record))
             })
-            .collect::<Result<BTreeSet<_>, _>>()?;
+            .collect::<Result<BTreeMap<_, _>, _>>()?;
 
         let banned_addresses = tx
             .get_banned_addresses()?
@@ -87,9 +133,26 @@ impl<A: Ord + FromStr, B: Ord + FromStr> LoadedStorage<A, B> {
             })
             .collect::<Result<BTreeMap<_, _>, _>>()?;
 
+        let banned_node_keys = tx
+            .get_banned_node_keys()?
+            .iter()
+            .map(|(node_key, duration)| {
+                node_key
+                    .parse::<NodePublicKey>()
+                    .map_err(|_err| {
+                        P2pError::InvalidStorageState(format!(
+                            "Invalid banned node key in PeerDb storage: {node_key}"
+                        ))
+                    })
+                    .map(|node_key| (node_key, *duration))
+            })
+            .collect::<Result<BTreeMap<_, _>, _>>()?;
+
         Ok(LoadedStorage {
             known_addresses,
             banned_addresses,
+            banned_node_keys,
+            address_table_key,
         })
     }
 }
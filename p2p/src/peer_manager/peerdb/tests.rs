@@ -20,9 +20,16 @@ use p2p_test_utils::P2pBasicTestTimeGetter;
 
 use crate::{
     config::P2pConfig,
-    error::{DialError, P2pError},
-    net::AsBannableAddress,
-    peer_manager::peerdb::storage::{PeerDbStorageRead, PeerDbTransactional},
+    error::{DialError, P2pError, PeerError},
+    net::{default_backend::transport::TransportAddress, AsBannableAddress},
+    node_key::NodeKeypair,
+    peer_manager::{
+        address_groups::AddressGroup,
+        peerdb::{
+            address_tables::TableKind,
+            storage::{PeerDbStorageRead, PeerDbTransactional},
+        },
+    },
     testing_utils::{
         peerdb_inmemory_store, test_p2p_config, RandomAddressMaker, TestTcpAddressMaker,
     },
@@ -38,6 +45,7 @@ fn unban_peer() {
         Arc::new(P2pConfig {
             bind_addresses: Default::default(),
             socks5_proxy: None,
+            tor_control_address: None,
             disable_noise: Default::default(),
             boot_nodes: Default::default(),
             reserved_nodes: Default::default(),
@@ -81,6 +89,41 @@ fn unban_peer() {
     assert_eq!(banned_addresses.len(), 0);
 }
 
+#[test]
+fn unban_node_key() {
+    let db_store = peerdb_inmemory_store();
+    let time_getter = P2pBasicTestTimeGetter::new();
+    let p2p_config = Arc::new(P2pConfig {
+        ban_duration: Duration::from_secs(60).into(),
+        ..test_p2p_config()
+    });
+    let mut peerdb: PeerDb<std::net::SocketAddr, _, _> = PeerDb::new(
+        Arc::clone(&p2p_config),
+        time_getter.get_time_getter(),
+        db_store,
+    )
+    .unwrap();
+
+    let node_key = *NodeKeypair::generate().public_key();
+    peerdb.ban_node_key(&node_key);
+
+    assert!(peerdb.is_node_key_banned(&node_key));
+    let banned_node_keys = peerdb.storage.transaction_ro().unwrap().get_banned_node_keys().unwrap();
+    assert_eq!(banned_node_keys.len(), 1);
+
+    // The ban is loaded from the storage
+    let mut peerdb: PeerDb<std::net::SocketAddr, _, _> =
+        PeerDb::new(p2p_config, time_getter.get_time_getter(), peerdb.storage).unwrap();
+    assert!(peerdb.is_node_key_banned(&node_key));
+
+    time_getter.advance_time(Duration::from_secs(120));
+    peerdb.heartbeat();
+
+    assert!(!peerdb.is_node_key_banned(&node_key));
+    let banned_node_keys = peerdb.storage.transaction_ro().unwrap().get_banned_node_keys().unwrap();
+    assert_eq!(banned_node_keys.len(), 0);
+}
+
 #[test]
 fn connected_unreachable() {
     let db_store = peerdb_inmemory_store();
@@ -89,7 +132,7 @@ fn connected_unreachable() {
     let mut peerdb = PeerDb::new(p2p_config, time_getter.get_time_getter(), db_store).unwrap();
 
     let address = TestTcpAddressMaker::new();
-    peerdb.peer_discovered(address);
+    peerdb.peer_discovered(address, None);
     peerdb.report_outbound_failure(
         address,
         &P2pError::DialError(DialError::ConnectionRefusedOrTimedOut),
@@ -116,3 +159,107 @@ fn connected_unknown() {
     peerdb.outbound_peer_connected(address);
     assert!(peerdb.addresses.get(&address).unwrap().is_connected());
 }
+
+#[test]
+fn tried_on_connect() {
+    let db_store = peerdb_inmemory_store();
+    let time_getter = P2pBasicTestTimeGetter::new();
+    let p2p_config = Arc::new(test_p2p_config());
+    let mut peerdb = PeerDb::new(p2p_config, time_getter.get_time_getter(), db_store).unwrap();
+
+    let address = TestTcpAddressMaker::new();
+    peerdb.peer_discovered(address, None);
+    assert_eq!(
+        peerdb.tables.location(&address),
+        Some((TableKind::New, AddressGroup::Local))
+    );
+    assert_eq!(peerdb.select_feeler_address(), Some(address));
+
+    peerdb.outbound_peer_connected(address);
+    assert_eq!(
+        peerdb.tables.location(&address),
+        Some((TableKind::Tried, AddressGroup::Local))
+    );
+    assert_eq!(peerdb.select_feeler_address(), None);
+
+    let known_addresses = peerdb.storage.transaction_ro().unwrap().get_known_addresses().unwrap();
+    assert_eq!(known_addresses.len(), 1);
+    assert_eq!(known_addresses[0].0, address.to_string());
+    assert_eq!(known_addresses[0].1.table, TableKind::Tried);
+    assert_eq!(
+        known_addresses[0].1.last_success,
+        Some(time_getter.get_time_getter().get_time())
+    );
+}
+
+#[test]
+fn address_history_persisted() {
+    let db_store = peerdb_inmemory_store();
+    let time_getter = P2pBasicTestTimeGetter::new();
+    let p2p_config = Arc::new(test_p2p_config());
+    let mut peerdb = PeerDb::new(
+        Arc::clone(&p2p_config),
+        time_getter.get_time_getter(),
+        db_store,
+    )
+    .unwrap();
+
+    let tried_address = TestTcpAddressMaker::new();
+    let source = TestTcpAddressMaker::new();
+    peerdb.peer_discovered(tried_address, Some(&source));
+    peerdb.outbound_peer_connected(tried_address);
+    let connected_time = time_getter.get_time_getter().get_time();
+    time_getter.advance_time(Duration::from_secs(60));
+    peerdb.outbound_peer_disconnected(tried_address);
+
+    let new_address = TestTcpAddressMaker::new();
+    peerdb.peer_discovered(new_address, Some(&source));
+    peerdb.report_outbound_failure(
+        new_address,
+        &P2pError::DialError(DialError::ConnectionRefusedOrTimedOut),
+    );
+    let failed_time = time_getter.get_time_getter().get_time();
+
+    let source_group = AddressGroup::from_peer_address(&source.as_peer_address());
+    let table_key = peerdb.tables.key();
+    let peerdb =
+        PeerDb::<_, _, _>::new(p2p_config, time_getter.get_time_getter(), peerdb.storage).unwrap();
+
+    assert_eq!(peerdb.tables.key(), table_key);
+    assert_eq!(
+        peerdb.tables.location(&tried_address),
+        Some((TableKind::Tried, source_group))
+    );
+    let tried_data = peerdb.addresses.get(&tried_address).unwrap();
+    assert_eq!(tried_data.last_attempt(), Some(connected_time));
+    assert_eq!(tried_data.last_success(), Some(connected_time));
+
+    assert_eq!(
+        peerdb.tables.location(&new_address),
+        Some((TableKind::New, source_group))
+    );
+    let new_data = peerdb.addresses.get(&new_address).unwrap();
+    assert_eq!(new_data.last_attempt(), Some(failed_time));
+    assert_eq!(new_data.last_success(), None);
+}
+
+#[test]
+fn outbound_failure_ignored() {
+    let db_store = peerdb_inmemory_store();
+    let time_getter = P2pBasicTestTimeGetter::new();
+    let p2p_config = Arc::new(test_p2p_config());
+    let mut peerdb = PeerDb::new(p2p_config, time_getter.get_time_getter(), db_store).unwrap();
+
+    let address = TestTcpAddressMaker::new();
+    peerdb.peer_discovered(address, None);
+
+    // The error is not caused by the address, so it must not affect the address state
+    peerdb.report_outbound_failure(address, &P2pError::PeerError(PeerError::TooManyPeers));
+    assert_eq!(peerdb.addresses.get(&address).unwrap().last_attempt(), None);
+
+    peerdb.report_outbound_failure(
+        address,
+        &P2pError::DialError(DialError::ConnectionRefusedOrTimedOut),
+    );
+    assert!(peerdb.addresses.get(&address).unwrap().last_attempt().is_some());
+}
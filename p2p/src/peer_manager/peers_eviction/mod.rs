@@ -18,7 +18,10 @@ use std::{collections::BTreeMap, hash::Hasher};
 use crypto::random::Rng;
 
 use crate::{
-    net::{default_backend::transport::TransportAddress, types::Role},
+    net::{
+        default_backend::transport::TransportAddress,
+        types::{services::Service, Role},
+    },
     types::peer_id::PeerId,
 };
 
@@ -29,6 +32,7 @@ struct NetGroupKeyed(u64);
 
 const PRESERVED_COUNT_ADDRESS_GROUP: usize = 4;
 const PRESERVED_COUNT_PING: usize = 8;
+const PRESERVED_COUNT_BLOCK_RELAY: usize = 8;
 
 #[cfg(test)]
 const PRESERVED_COUNT_TOTAL: usize = PRESERVED_COUNT_ADDRESS_GROUP + PRESERVED_COUNT_PING;
@@ -48,6 +52,9 @@ pub struct EvictionCandidate {
 
     /// Inbound or Outbound
     role: Role,
+
+    /// Only headers and blocks are exchanged with the peer
+    block_relay_only: bool,
 }
 
 pub struct RandomState(u64, u64);
@@ -73,6 +80,13 @@ impl EvictionCandidate {
             )),
             ping_min: peer.ping_min.map_or(i64::MAX, |val| val.as_micros() as i64),
             role: peer.role,
+            // Inbound peers that don't relay transactions are protected only if they have
+            // actually sent a novel block, the advertised services alone are easy to fake
+            block_relay_only: peer.block_relay_only
+                || (peer.role == Role::Inbound
+                    && peer.info.services.has_service(Service::Blocks)
+                    && !peer.info.services.has_service(Service::Transactions)
+                    && peer.last_tip_block_time.is_some()),
         }
     }
 }
@@ -105,6 +119,25 @@ fn filter_fast_ping(
     candidates
 }
 
+// Preserve the oldest block-relay-only peers.
+// Such connections are cheap to keep and they help the peers avoid network partitioning.
+fn filter_block_relay(
+    mut candidates: Vec<EvictionCandidate>,
+    count: usize,
+) -> Vec<EvictionCandidate> {
+    // Block-relay-only peers are moved to the end, the oldest ones (with min `peer_id`) last
+    candidates
+        .sort_unstable_by_key(|peer| (peer.block_relay_only, std::cmp::Reverse(peer.peer_id)));
+    let preserved = candidates
+        .iter()
+        .rev()
+        .take(count)
+        .take_while(|peer| peer.block_relay_only)
+        .count();
+    candidates.truncate(candidates.len() - preserved);
+    candidates
+}
+
 fn find_group_most_connections(candidates: Vec<EvictionCandidate>) -> Option<PeerId> {
     if candidates.is_empty() {
         return None;
@@ -142,6 +175,7 @@ pub fn select_for_eviction(candidates: Vec<EvictionCandidate>) -> Option<PeerId>
     let candidates = filter_inbound(candidates);
     let candidates = filter_address_group(candidates, PRESERVED_COUNT_ADDRESS_GROUP);
     let candidates = filter_fast_ping(candidates, PRESERVED_COUNT_PING);
+    let candidates = filter_block_relay(candidates, PRESERVED_COUNT_BLOCK_RELAY);
 
     // TODO: Preserve 4 nodes that most recently sent us novel transactions accepted into our mempool.
     // TODO: Preserve up to 8 peers that have sent us novel blocks.
@@ -31,20 +31,23 @@ fn test_filter_inbound() {
                 peer_id: peer1,
                 net_group_keyed: NetGroupKeyed(123),
                 ping_min: 0,
-                role: Role::Inbound
+                role: Role::Inbound,
+                block_relay_only: false,
             },
             EvictionCandidate {
                 peer_id: peer2,
                 net_group_keyed: NetGroupKeyed(123),
                 ping_min: 0,
-                role: Role::Outbound
+                role: Role::Outbound,
+                block_relay_only: false,
             }
         ]),
         vec![EvictionCandidate {
             peer_id: peer1,
             net_group_keyed: NetGroupKeyed(123),
             ping_min: 0,
-            role: Role::Inbound
+            role: Role::Inbound,
+            block_relay_only: false,
         },]
     );
 }
@@ -61,7 +64,8 @@ fn test_filter_address_group() {
                 peer_id: peer1,
                 net_group_keyed: NetGroupKeyed(1),
                 ping_min: 0,
-                role: Role::Inbound
+                role: Role::Inbound,
+                block_relay_only: false,
             },],
             1
         ),
@@ -75,13 +79,15 @@ fn test_filter_address_group() {
                     peer_id: peer1,
                     net_group_keyed: NetGroupKeyed(1),
                     ping_min: 0,
-                    role: Role::Inbound
+                    role: Role::Inbound,
+                    block_relay_only: false,
                 },
                 EvictionCandidate {
                     peer_id: peer2,
                     net_group_keyed: NetGroupKeyed(2),
                     ping_min: 0,
-                    role: Role::Inbound
+                    role: Role::Inbound,
+                    block_relay_only: false,
                 },
             ],
             1
@@ -90,7 +96,8 @@ fn test_filter_address_group() {
             peer_id: peer1,
             net_group_keyed: NetGroupKeyed(1),
             ping_min: 0,
-            role: Role::Inbound
+            role: Role::Inbound,
+            block_relay_only: false,
         },]
     );
 
@@ -101,13 +108,15 @@ fn test_filter_address_group() {
                     peer_id: peer2,
                     net_group_keyed: NetGroupKeyed(2),
                     ping_min: 0,
-                    role: Role::Inbound
+                    role: Role::Inbound,
+                    block_relay_only: false,
                 },
                 EvictionCandidate {
                     peer_id: peer1,
                     net_group_keyed: NetGroupKeyed(1),
                     ping_min: 0,
-                    role: Role::Inbound
+                    role: Role::Inbound,
+                    block_relay_only: false,
                 },
             ],
             1
@@ -116,7 +125,8 @@ fn test_filter_address_group() {
             peer_id: peer1,
             net_group_keyed: NetGroupKeyed(1),
             ping_min: 0,
-            role: Role::Inbound
+            role: Role::Inbound,
+            block_relay_only: false,
         },]
     );
 
@@ -127,19 +137,22 @@ fn test_filter_address_group() {
                     peer_id: peer1,
                     net_group_keyed: NetGroupKeyed(2),
                     ping_min: 0,
-                    role: Role::Inbound
+                    role: Role::Inbound,
+                    block_relay_only: false,
                 },
                 EvictionCandidate {
                     peer_id: peer2,
                     net_group_keyed: NetGroupKeyed(1),
                     ping_min: 0,
-                    role: Role::Inbound
+                    role: Role::Inbound,
+                    block_relay_only: false,
                 },
                 EvictionCandidate {
                     peer_id: peer3,
                     net_group_keyed: NetGroupKeyed(2),
                     ping_min: 0,
-                    role: Role::Inbound
+                    role: Role::Inbound,
+                    block_relay_only: false,
                 },
             ],
             2
@@ -148,7 +161,8 @@ fn test_filter_address_group() {
             peer_id: peer2,
             net_group_keyed: NetGroupKeyed(1),
             ping_min: 0,
-            role: Role::Inbound
+            role: Role::Inbound,
+            block_relay_only: false,
         },]
     );
 }
@@ -165,7 +179,8 @@ fn test_ping() {
                 peer_id: peer1,
                 net_group_keyed: NetGroupKeyed(1),
                 ping_min: 123,
-                role: Role::Inbound
+                role: Role::Inbound,
+                block_relay_only: false,
             },],
             1
         ),
@@ -179,13 +194,15 @@ fn test_ping() {
                     peer_id: peer1,
                     net_group_keyed: NetGroupKeyed(1),
                     ping_min: 123,
-                    role: Role::Inbound
+                    role: Role::Inbound,
+                    block_relay_only: false,
                 },
                 EvictionCandidate {
                     peer_id: peer2,
                     net_group_keyed: NetGroupKeyed(1),
                     ping_min: 234,
-                    role: Role::Inbound
+                    role: Role::Inbound,
+                    block_relay_only: false,
                 },
             ],
             1
@@ -194,7 +211,8 @@ fn test_ping() {
             peer_id: peer2,
             net_group_keyed: NetGroupKeyed(1),
             ping_min: 234,
-            role: Role::Inbound
+            role: Role::Inbound,
+            block_relay_only: false,
         },]
     );
 
@@ -205,19 +223,22 @@ fn test_ping() {
                     peer_id: peer1,
                     net_group_keyed: NetGroupKeyed(1),
                     ping_min: 123,
-                    role: Role::Inbound
+                    role: Role::Inbound,
+                    block_relay_only: false,
                 },
                 EvictionCandidate {
                     peer_id: peer2,
                     net_group_keyed: NetGroupKeyed(1),
                     ping_min: 234,
-                    role: Role::Inbound
+                    role: Role::Inbound,
+                    block_relay_only: false,
                 },
                 EvictionCandidate {
                     peer_id: peer3,
                     net_group_keyed: NetGroupKeyed(1),
                     ping_min: 123,
-                    role: Role::Inbound
+                    role: Role::Inbound,
+                    block_relay_only: false,
                 },
             ],
             2
@@ -226,7 +247,8 @@ fn test_ping() {
             peer_id: peer2,
             net_group_keyed: NetGroupKeyed(1),
             ping_min: 234,
-            role: Role::Inbound
+            role: Role::Inbound,
+            block_relay_only: false,
         },]
     );
 }
@@ -244,7 +266,8 @@ fn test_find_group_most_connections() {
             peer_id: peer1,
             net_group_keyed: NetGroupKeyed(1),
             ping_min: 123,
-            role: Role::Inbound
+            role: Role::Inbound,
+            block_relay_only: false,
         }]),
         Some(peer1)
     );
@@ -256,13 +279,15 @@ fn test_find_group_most_connections() {
                 peer_id: peer1,
                 net_group_keyed: NetGroupKeyed(1),
                 ping_min: 123,
-                role: Role::Inbound
+                role: Role::Inbound,
+                block_relay_only: false,
             },
             EvictionCandidate {
                 peer_id: peer2,
                 net_group_keyed: NetGroupKeyed(1),
                 ping_min: 123,
-                role: Role::Inbound
+                role: Role::Inbound,
+                block_relay_only: false,
             }
         ]),
         Some(peer2)
@@ -274,31 +299,129 @@ fn test_find_group_most_connections() {
                 peer_id: peer1,
                 net_group_keyed: NetGroupKeyed(1),
                 ping_min: 123,
-                role: Role::Inbound
+                role: Role::Inbound,
+                block_relay_only: false,
             },
             EvictionCandidate {
                 peer_id: peer2,
                 net_group_keyed: NetGroupKeyed(1),
                 ping_min: 123,
-                role: Role::Inbound
+                role: Role::Inbound,
+                block_relay_only: false,
             },
             EvictionCandidate {
                 peer_id: peer3,
                 net_group_keyed: NetGroupKeyed(2),
                 ping_min: 123,
-                role: Role::Inbound
+                role: Role::Inbound,
+                block_relay_only: false,
             },
         ]),
         Some(peer2)
     );
 }
 
+#[test]
+fn test_block_relay() {
+    let peer1 = PeerId::new();
+    let peer2 = PeerId::new();
+    let peer3 = PeerId::new();
+
+    let candidate = |peer_id, block_relay_only| EvictionCandidate {
+        peer_id,
+        net_group_keyed: NetGroupKeyed(1),
+        ping_min: 123,
+        role: Role::Inbound,
+        block_relay_only,
+    };
+
+    assert_eq!(
+        filter_block_relay(vec![candidate(peer1, false), candidate(peer2, false)], 1),
+        vec![candidate(peer2, false), candidate(peer1, false)]
+    );
+
+    // The oldest block-relay-only peer is preserved
+    assert_eq!(
+        filter_block_relay(
+            vec![candidate(peer3, true), candidate(peer1, false), candidate(peer2, true)],
+            1
+        ),
+        vec![candidate(peer1, false), candidate(peer3, true)]
+    );
+
+    assert_eq!(
+        filter_block_relay(
+            vec![candidate(peer3, true), candidate(peer1, false), candidate(peer2, true)],
+            5
+        ),
+        vec![candidate(peer1, false)]
+    );
+}
+
+#[test]
+fn test_block_relay_candidate() {
+    let chain_config = common::chain::config::create_mainnet();
+    let random_state = RandomState::new(&mut crypto::random::make_pseudo_rng());
+    let make_peer = |role, block_relay_only, services: &[Service]| PeerContext {
+        info: crate::net::types::PeerInfo {
+            peer_id: PeerId::new(),
+            protocol: crate::protocol::NETWORK_PROTOCOL_CURRENT,
+            network: *chain_config.magic_bytes(),
+            version: *chain_config.version(),
+            user_agent: common::primitives::user_agent::mintlayer_core_user_agent(),
+            services: services.into(),
+            node_key: None,
+        },
+        address: "1.2.3.4:3031".parse::<std::net::SocketAddr>().unwrap(),
+        role,
+        block_relay_only,
+        score: 0,
+        sent_ping: None,
+        ping_last: None,
+        ping_min: None,
+        addr_list_req_received: utils::set_flag::SetFlag::new(),
+        addr_list_resp_received: utils::set_flag::SetFlag::new(),
+        announced_addresses: utils::bloom_filters::rolling_bloom_filter::RollingBloomFilter::new(
+            10,
+            0.001,
+            &mut crypto::random::make_pseudo_rng(),
+        ),
+        address_rate_limiter: crate::utils::rate_limiter::RateLimiter::new(
+            std::time::Duration::ZERO,
+            0.1,
+            1,
+            10,
+        ),
+        discovered_own_address: None,
+        last_tip_block_time: None,
+    };
+
+    // Outbound block-relay-only connections are protected
+    let peer = make_peer(Role::Outbound, true, &[Service::Blocks]);
+    assert!(EvictionCandidate::new(&peer, &random_state).block_relay_only);
+
+    // Inbound peers that don't relay transactions are protected only after sending a novel block
+    let mut peer = make_peer(Role::Inbound, false, &[Service::Blocks]);
+    assert!(!EvictionCandidate::new(&peer, &random_state).block_relay_only);
+    peer.last_tip_block_time = Some(std::time::Duration::from_secs(1));
+    assert!(EvictionCandidate::new(&peer, &random_state).block_relay_only);
+
+    let mut peer = make_peer(
+        Role::Inbound,
+        false,
+        &[Service::Blocks, Service::Transactions],
+    );
+    peer.last_tip_block_time = Some(std::time::Duration::from_secs(1));
+    assert!(!EvictionCandidate::new(&peer, &random_state).block_relay_only);
+}
+
 fn random_eviction_candidate(rng: &mut impl Rng) -> EvictionCandidate {
     EvictionCandidate {
         peer_id: PeerId::new(),
         net_group_keyed: NetGroupKeyed(rng.gen()),
         ping_min: rng.gen_range(0..100),
         role: Role::Inbound,
+        block_relay_only: false,
     }
 }
 
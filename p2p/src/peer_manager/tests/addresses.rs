@@ -20,23 +20,23 @@ use p2p_test_utils::P2pBasicTestTimeGetter;
 
 use crate::{
     config::NodeType,
-    message::AnnounceAddrRequest,
+    message::{AddrListResponse, AnnounceAddrRequest},
     net::{
         default_backend::{
             transport::{MpscChannelTransport, TcpTransportSocket, TransportAddress},
             types::{Command, Message},
             ConnectivityHandle, DefaultNetworkingService,
         },
-        types::{PeerInfo, Role},
+        types::{services::Service, PeerInfo, Role},
         ConnectivityService, NetworkingService,
     },
     peer_manager::{tests::make_peer_manager_custom, PeerManager, MAX_OUTBOUND_CONNECTIONS},
-    protocol::NETWORK_PROTOCOL_CURRENT,
+    protocol::{NETWORK_PROTOCOL_CURRENT, NETWORK_PROTOCOL_V3, NETWORK_PROTOCOL_V4},
     testing_utils::{
         peerdb_inmemory_store, test_p2p_config, RandomAddressMaker, TestTcpAddressMaker,
         TestTransportChannel, TestTransportMaker,
     },
-    types::peer_id::PeerId,
+    types::{peer_address::PeerAddress, peer_id::PeerId},
     utils::oneshot_nofail,
     PeerManagerEvent,
 };
@@ -70,6 +70,7 @@ where
         version: *config.version(),
         user_agent: mintlayer_core_user_agent(),
         services: NodeType::Full.into(),
+        node_key: None,
     };
     pm.accept_connection(address, Role::Inbound, peer_info, None);
     assert_eq!(pm.peers.len(), 1);
@@ -147,6 +148,7 @@ fn test_addr_list_handling_inbound() {
         version: *chain_config.version(),
         user_agent: mintlayer_core_user_agent(),
         services: NodeType::Full.into(),
+        node_key: None,
     };
     pm.accept_connection(TestTcpAddressMaker::new(), Role::Inbound, peer_info, None);
     assert_eq!(pm.peers.len(), 1);
@@ -232,12 +234,16 @@ fn test_addr_list_handling_outbound() {
         version: *chain_config.version(),
         user_agent: mintlayer_core_user_agent(),
         services: NodeType::Full.into(),
+        node_key: None,
     };
-    pm.connect(peer_address, None);
+    pm.connect(peer_address, false, None);
 
     // New peer connection is requested
     match cmd_rx.try_recv() {
-        Ok(Command::Connect { address }) if address == peer_address => {}
+        Ok(Command::Connect {
+            address,
+            local_services_override: _,
+        }) if address == peer_address => {}
         v => panic!("unexpected command: {v:?}"),
     }
 
@@ -286,6 +292,90 @@ fn test_addr_list_handling_outbound() {
     assert_ne!(pm.peers.get(&peer_id_1).unwrap().score, 0);
 }
 
+// Verify that no addresses are exchanged over block-relay-only connections
+#[test]
+fn test_addr_list_handling_block_relay() {
+    type TestNetworkingService = DefaultNetworkingService<TcpTransportSocket>;
+
+    let chain_config = Arc::new(config::create_mainnet());
+    let p2p_config = Arc::new(test_p2p_config());
+    let (cmd_tx, mut cmd_rx) = tokio::sync::mpsc::unbounded_channel();
+    let (_conn_tx, conn_rx) = tokio::sync::mpsc::unbounded_channel();
+    let (_peer_tx, peer_rx) =
+        tokio::sync::mpsc::unbounded_channel::<PeerManagerEvent<TestNetworkingService>>();
+    let time_getter = P2pBasicTestTimeGetter::new();
+    let connectivity_handle = ConnectivityHandle::<TestNetworkingService, TcpTransportSocket>::new(
+        vec![],
+        cmd_tx,
+        conn_rx,
+    );
+
+    let mut pm = PeerManager::new(
+        Arc::clone(&chain_config),
+        Arc::clone(&p2p_config),
+        connectivity_handle,
+        peer_rx,
+        time_getter.get_time_getter(),
+        peerdb_inmemory_store(),
+    )
+    .unwrap();
+
+    let peer_id_1 = PeerId::new();
+    let peer_address = TestTcpAddressMaker::new();
+    let peer_info = PeerInfo {
+        peer_id: peer_id_1,
+        protocol: NETWORK_PROTOCOL_CURRENT,
+        network: *chain_config.magic_bytes(),
+        version: *chain_config.version(),
+        user_agent: mintlayer_core_user_agent(),
+        services: NodeType::Full.into(),
+        node_key: None,
+    };
+    pm.connect(peer_address, true, None);
+
+    // New peer connection is requested, only blocks are advertised to the peer
+    match cmd_rx.try_recv() {
+        Ok(Command::Connect {
+            address,
+            local_services_override: Some(services),
+        }) if address == peer_address
+            && services.has_service(Service::Blocks)
+            && !services.has_service(Service::Transactions)
+            && !services.has_service(Service::PeerAddresses) => {}
+        v => panic!("unexpected command: {v:?}"),
+    }
+
+    pm.accept_connection(peer_address, Role::Outbound, peer_info, None);
+    assert_eq!(pm.peers.len(), 1);
+    assert!(pm.peers.get(&peer_id_1).unwrap().block_relay_only);
+    assert!(pm.subscribed_to_peer_addresses.is_empty());
+
+    // Peer is accepted by the peer manager
+    match cmd_rx.try_recv() {
+        Ok(Command::Accept { peer_id }) if peer_id == peer_id_1 => {}
+        v => panic!("unexpected command: {v:?}"),
+    }
+
+    // Address list is not requested
+    match cmd_rx.try_recv() {
+        Err(_) => {}
+        v => panic!("unexpected command: {v:?}"),
+    }
+
+    // Announced addresses are ignored
+    let known_address_count = pm.peerdb.known_addresses().count();
+    pm.handle_announce_addr_request(peer_id_1, TestTcpAddressMaker::new().as_peer_address());
+    assert_eq!(pm.peerdb.known_addresses().count(), known_address_count);
+
+    // Check that the peer is scored if it tries to send an unexpected address list response
+    pm.handle_addr_list_response(
+        peer_id_1,
+        vec![TestTcpAddressMaker::new().as_peer_address()],
+    );
+    assert_ne!(pm.peers.get(&peer_id_1).unwrap().score, 0);
+    assert_eq!(pm.peerdb.known_addresses().count(), known_address_count);
+}
+
 // Verify that the node periodically resends its own address
 #[tokio::test]
 async fn resend_own_addresses() {
@@ -332,11 +422,18 @@ async fn resend_own_addresses() {
             version: *chain_config.version(),
             user_agent: mintlayer_core_user_agent(),
             services: NodeType::Full.into(),
+            node_key: None,
         };
-        pm.connect(peer_address, None);
+        pm.connect(peer_address, false, None);
 
         // New peer connection is requested
-        while !matches!(cmd_rx.try_recv().unwrap(), Command::Connect { address: _ }) {}
+        while !matches!(
+            cmd_rx.try_recv().unwrap(),
+            Command::Connect {
+                address: _,
+                local_services_override: _
+            }
+        ) {}
 
         let own_ip = if peer_index % 2 == 0 {
             outbound_address_1
@@ -377,3 +474,92 @@ async fn resend_own_addresses() {
         }
     }
 }
+
+// Onion addresses are kept and relayed by TCP nodes, but only to peers that support them
+#[test]
+fn test_onion_address_relay() {
+    type TestNetworkingService = DefaultNetworkingService<TcpTransportSocket>;
+
+    let chain_config = Arc::new(config::create_mainnet());
+    let p2p_config = Arc::new(test_p2p_config());
+    let (cmd_tx, mut cmd_rx) = tokio::sync::mpsc::unbounded_channel();
+    let (_conn_tx, conn_rx) = tokio::sync::mpsc::unbounded_channel();
+    let (_peer_tx, peer_rx) =
+        tokio::sync::mpsc::unbounded_channel::<PeerManagerEvent<TestNetworkingService>>();
+    let time_getter = P2pBasicTestTimeGetter::new();
+    let connectivity_handle = ConnectivityHandle::<TestNetworkingService, TcpTransportSocket>::new(
+        vec![],
+        cmd_tx,
+        conn_rx,
+    );
+
+    let mut pm = PeerManager::new(
+        Arc::clone(&chain_config),
+        Arc::clone(&p2p_config),
+        connectivity_handle,
+        peer_rx,
+        time_getter.get_time_getter(),
+        peerdb_inmemory_store(),
+    )
+    .unwrap();
+
+    let mut connect_peer = |protocol| {
+        let peer_id = PeerId::new();
+        let peer_info = PeerInfo {
+            peer_id,
+            protocol,
+            network: *chain_config.magic_bytes(),
+            version: *chain_config.version(),
+            user_agent: mintlayer_core_user_agent(),
+            services: NodeType::Full.into(),
+            node_key: None,
+        };
+        pm.accept_connection(TestTcpAddressMaker::new(), Role::Inbound, peer_info, None);
+        peer_id
+    };
+    let source_peer = connect_peer(NETWORK_PROTOCOL_CURRENT);
+    let new_peer = connect_peer(NETWORK_PROTOCOL_V4);
+    let old_peer = connect_peer(NETWORK_PROTOCOL_V3);
+    assert_eq!(pm.peers.len(), 3);
+
+    // Flush all pending messages
+    while cmd_rx.try_recv().is_ok() {}
+
+    let onion_address: PeerAddress =
+        "duckduckgogg42xjoc72x3sjasowoarfbgcmvfimaftt6twagswzczad.onion:3031"
+            .parse()
+            .unwrap();
+    pm.handle_announce_addr_request(source_peer, onion_address.clone());
+    assert_eq!(pm.peers.get(&source_peer).unwrap().score, 0);
+
+    // The address is relayed to the new peer only
+    match cmd_rx.try_recv() {
+        Ok(Command::SendMessage {
+            peer,
+            message: Message::AnnounceAddrRequest(AnnounceAddrRequest { address }),
+        }) if peer == new_peer && address == onion_address => {}
+        v => panic!("unexpected command: {v:?}"),
+    }
+    match cmd_rx.try_recv() {
+        Err(_) => {}
+        v => panic!("unexpected command: {v:?}"),
+    }
+
+    // The address is kept and sent in the address list, but not to the old peer
+    pm.handle_addr_list_request(new_peer);
+    match cmd_rx.try_recv() {
+        Ok(Command::SendMessage {
+            peer,
+            message: Message::AddrListResponse(AddrListResponse { addresses }),
+        }) if peer == new_peer => assert_eq!(addresses, vec![onion_address.clone()]),
+        v => panic!("unexpected command: {v:?}"),
+    }
+    pm.handle_addr_list_request(old_peer);
+    match cmd_rx.try_recv() {
+        Ok(Command::SendMessage {
+            peer,
+            message: Message::AddrListResponse(AddrListResponse { addresses }),
+        }) if peer == old_peer => assert_eq!(addresses, vec![]),
+        v => panic!("unexpected command: {v:?}"),
+    }
+}
@@ -16,12 +16,14 @@
 use std::sync::Arc;
 
 use crate::{
+    config::P2pConfig,
     net::types::{services::Service, Role},
+    node_key::{NodeKeypair, NodePublicKey},
     protocol::{NETWORK_PROTOCOL_CURRENT, NETWORK_PROTOCOL_MIN},
     testing_utils::{
-        connect_and_accept_services, connect_services, get_connectivity_event, RandomAddressMaker,
-        TestChannelAddressMaker, TestTcpAddressMaker, TestTransportChannel, TestTransportMaker,
-        TestTransportNoise, TestTransportTcp,
+        connect_and_accept_services, connect_services, get_connectivity_event, test_p2p_config,
+        RandomAddressMaker, TestChannelAddressMaker, TestTcpAddressMaker, TestTransportChannel,
+        TestTransportMaker, TestTransportNoise, TestTransportTcp,
     },
     types::peer_id::PeerId,
     utils::oneshot_nofail,
@@ -41,7 +43,7 @@ use crate::{
         },
         AsBannableAddress, ConnectivityService, NetworkingService,
     },
-    peer_manager::tests::make_peer_manager,
+    peer_manager::tests::{make_peer_manager, make_peer_manager_custom},
 };
 
 // ban peer whose connected to us
@@ -190,7 +192,7 @@ where
     pm2.handle_connectivity_event(event.unwrap());
 
     let (tx, rx) = oneshot_nofail::channel();
-    pm2.connect(remote_addr, Some(tx));
+    pm2.connect(remote_addr, false, Some(tx));
     let res = rx.await.unwrap();
     match res {
         Err(P2pError::PeerError(PeerError::BannedAddress(_))) => {}
@@ -242,6 +244,7 @@ where
                 version: *config.version(),
                 user_agent: mintlayer_core_user_agent(),
                 services: [Service::Blocks, Service::Transactions].as_slice().into(),
+                node_key: None,
             },
             None,
         );
@@ -260,6 +263,7 @@ where
                 version: *config.version(),
                 user_agent: mintlayer_core_user_agent(),
                 services: [Service::Blocks, Service::Transactions].as_slice().into(),
+                node_key: None,
             },
             None,
         );
@@ -281,6 +285,7 @@ where
                     version: SemVer::new(123, 123, 12345),
                     user_agent: mintlayer_core_user_agent(),
                     services: [Service::Blocks, Service::Transactions].as_slice().into(),
+                    node_key: None,
                 },
                 None,
             );
@@ -387,3 +392,95 @@ async fn inbound_connection_invalid_magic_noise() {
     >()
     .await;
 }
+
+fn make_peer_info_with_node_key(
+    config: &common::chain::ChainConfig,
+    node_key: NodePublicKey,
+) -> net::types::PeerInfo {
+    net::types::PeerInfo {
+        peer_id: PeerId::new(),
+        protocol: NETWORK_PROTOCOL_CURRENT,
+        network: *config.magic_bytes(),
+        version: *config.version(),
+        user_agent: mintlayer_core_user_agent(),
+        services: [Service::Blocks, Service::Transactions].as_slice().into(),
+        node_key: Some(node_key),
+    }
+}
+
+// The node key is banned along with the address, so the peer can't reconnect from another address
+#[tokio::test]
+async fn ban_node_key() {
+    let config = Arc::new(config::create_mainnet());
+    let (mut peer_manager, _shutdown_sender, _subscribers_sender) =
+        make_peer_manager::<DefaultNetworkingService<TcpTransportSocket>>(
+            TestTransportTcp::make_transport(),
+            TestTransportTcp::make_address(),
+            Arc::clone(&config),
+        )
+        .await;
+    let node_key = *NodeKeypair::generate().public_key();
+
+    let peer_info = make_peer_info_with_node_key(&config, node_key);
+    let peer_id = peer_info.peer_id;
+    peer_manager
+        .try_accept_connection(TestTcpAddressMaker::new(), Role::Inbound, peer_info, None)
+        .unwrap();
+
+    // Only one connection per node key is accepted
+    let res = peer_manager.try_accept_connection(
+        TestTcpAddressMaker::new(),
+        Role::Inbound,
+        make_peer_info_with_node_key(&config, node_key),
+        None,
+    );
+    assert_eq!(res, Err(P2pError::PeerError(PeerError::PeerAlreadyExists)));
+
+    peer_manager.adjust_peer_score(peer_id, 1000);
+    assert!(peer_manager.peerdb.is_node_key_banned(&node_key));
+
+    let res = peer_manager.try_accept_connection(
+        TestTcpAddressMaker::new(),
+        Role::Inbound,
+        make_peer_info_with_node_key(&config, node_key),
+        None,
+    );
+    assert_eq!(
+        res,
+        Err(P2pError::PeerError(PeerError::BannedNodeKey(
+            node_key.to_string()
+        )))
+    );
+}
+
+// Reserved nodes pinned with a key are whitelisted whatever address they connect from
+#[tokio::test]
+async fn reserved_node_key_is_not_banned() {
+    let config = Arc::new(config::create_mainnet());
+    let node_key = *NodeKeypair::generate().public_key();
+    let p2p_config = Arc::new(P2pConfig {
+        reserved_nodes: vec![format!("{node_key}@{}", TestTcpAddressMaker::new())],
+        ..test_p2p_config()
+    });
+    let (mut peer_manager, _tx, _shutdown_sender, _subscribers_sender) =
+        make_peer_manager_custom::<DefaultNetworkingService<TcpTransportSocket>>(
+            TestTransportTcp::make_transport(),
+            TestTransportTcp::make_address(),
+            Arc::clone(&config),
+            p2p_config,
+            Default::default(),
+        )
+        .await;
+
+    let peer_info = make_peer_info_with_node_key(&config, node_key);
+    let peer_id = peer_info.peer_id;
+    let address = TestTcpAddressMaker::new();
+    peer_manager
+        .try_accept_connection(address, Role::Inbound, peer_info, None)
+        .unwrap();
+
+    peer_manager.adjust_peer_score(peer_id, 1000);
+    assert!(!peer_manager.peerdb.is_node_key_banned(&node_key));
+    assert!(!peer_manager.peerdb.is_address_banned(&address.as_bannable()));
+    assert!(peer_manager.is_peer_connected(peer_id));
+}
@@ -136,7 +136,7 @@ where
     });
 
     // "discover" the other networking service
-    pm1.peerdb.peer_discovered(addr);
+    pm1.peerdb.peer_discovered(addr, None);
     pm1.heartbeat().await;
 
     assert_eq!(pm1.pending_outbound_connects.len(), 1);
@@ -472,6 +472,7 @@ async fn inbound_connection_too_many_peers_tcp() {
                     version: *config.version(),
                     user_agent: mintlayer_core_user_agent(),
                     services: [Service::Blocks, Service::Transactions].as_slice().into(),
+                    node_key: None,
                 },
             )
         })
@@ -498,6 +499,7 @@ async fn inbound_connection_too_many_peers_channels() {
                     version: *config.version(),
                     user_agent: mintlayer_core_user_agent(),
                     services: [Service::Blocks, Service::Transactions].as_slice().into(),
+                    node_key: None,
                 },
             )
         })
@@ -524,6 +526,7 @@ async fn inbound_connection_too_many_peers_noise() {
                     version: *config.version(),
                     user_agent: mintlayer_core_user_agent(),
                     services: [Service::Blocks, Service::Transactions].as_slice().into(),
+                    node_key: None,
                 },
             )
         })
@@ -561,7 +564,7 @@ where
     .unwrap();
 
     // This will fail immediately because it is trying to connect to the closed port
-    conn.connect(addr2).expect("dial to succeed");
+    conn.connect(addr2, None).expect("dial to succeed");
 
     match timeout(Duration::from_secs(1), conn.poll_next()).await {
         Ok(res) => assert!(std::matches!(
@@ -704,6 +707,7 @@ where
     let p2p_config_1 = Arc::new(P2pConfig {
         bind_addresses: Default::default(),
         socks5_proxy: None,
+        tor_control_address: None,
         disable_noise: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: Default::default(),
@@ -744,6 +748,7 @@ where
     let p2p_config_2 = Arc::new(P2pConfig {
         bind_addresses: Default::default(),
         socks5_proxy: None,
+        tor_control_address: None,
         disable_noise: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: bind_addresses,
@@ -827,6 +832,7 @@ where
     let p2p_config_1 = Arc::new(P2pConfig {
         bind_addresses: Default::default(),
         socks5_proxy: None,
+        tor_control_address: None,
         disable_noise: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: Default::default(),
@@ -868,6 +874,7 @@ where
     let p2p_config_2 = Arc::new(P2pConfig {
         bind_addresses: Default::default(),
         socks5_proxy: None,
+        tor_control_address: None,
         disable_noise: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: bind_addresses.clone(),
@@ -902,6 +909,7 @@ where
     let p2p_config_3 = Arc::new(P2pConfig {
         bind_addresses: Default::default(),
         socks5_proxy: None,
+        tor_control_address: None,
         disable_noise: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: bind_addresses,
@@ -43,7 +43,7 @@ use crate::{
     testing_utils::{peerdb_inmemory_store, test_p2p_config},
     types::peer_id::PeerId,
     utils::oneshot_nofail,
-    P2pConfig, P2pEventHandler, PeerManagerEvent,
+    P2pConfig, P2pEventSubscription, PeerManagerEvent,
 };
 
 use super::peerdb::storage::PeerDbStorage;
@@ -58,7 +58,7 @@ async fn make_peer_manager_custom<T>(
     PeerManager<T, impl PeerDbStorage>,
     UnboundedSender<PeerManagerEvent<T>>,
     oneshot::Sender<()>,
-    UnboundedSender<P2pEventHandler>,
+    UnboundedSender<P2pEventSubscription>,
 )
 where
     T: NetworkingService + 'static,
@@ -101,7 +101,7 @@ async fn make_peer_manager<T>(
 ) -> (
     PeerManager<T, impl PeerDbStorage>,
     oneshot::Sender<()>,
-    UnboundedSender<P2pEventHandler>,
+    UnboundedSender<P2pEventSubscription>,
 )
 where
     T: NetworkingService + 'static,
@@ -128,7 +128,7 @@ async fn run_peer_manager<T>(
 ) -> (
     UnboundedSender<PeerManagerEvent<T>>,
     oneshot::Sender<()>,
-    UnboundedSender<P2pEventHandler>,
+    UnboundedSender<P2pEventSubscription>,
 )
 where
     T: NetworkingService + 'static,
@@ -45,6 +45,7 @@ async fn ping_timeout() {
     let p2p_config: Arc<P2pConfig> = Arc::new(P2pConfig {
         bind_addresses: Default::default(),
         socks5_proxy: None,
+        tor_control_address: None,
         disable_noise: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: Default::default(),
@@ -105,6 +106,7 @@ async fn ping_timeout() {
                 version: *chain_config.version(),
                 user_agent: p2p_config.user_agent.clone(),
                 services: NodeType::Full.into(),
+                node_key: None,
             },
             receiver_address: None,
         })
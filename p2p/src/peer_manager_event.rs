@@ -13,6 +13,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use common::{chain::Block, primitives::Id};
+
 use crate::{
     interface::types::ConnectedPeer, net::NetworkingService, types::peer_id::PeerId,
     utils::oneshot_nofail,
@@ -40,6 +42,9 @@ pub enum PeerManagerEvent<T: NetworkingService> {
     /// The peer is banned if the new score exceeds the threshold (`P2pConfig::ban_threshold`).
     AdjustPeerScore(PeerId, u32, oneshot_nofail::Sender<crate::Result<()>>),
 
+    /// The block received from the peer has become the new tip (the peer has sent a novel block)
+    NewTipReceived(PeerId, Id<Block>),
+
     AddReserved(T::Address),
 
     RemoveReserved(T::Address),
@@ -22,8 +22,25 @@ pub type NetworkProtocol = u32;
 /// Initial protocol version
 pub const NETWORK_PROTOCOL_V1: NetworkProtocol = 1;
 
+/// Compact block relay (`CompactBlockRequest`, `CompactBlock`, `BlockTransactionsRequest`
+/// and `BlockTransactions` messages)
+pub const NETWORK_PROTOCOL_V2: NetworkProtocol = 2;
+
+/// Batched transaction announcements (`TransactionInventory` message instead of
+/// `NewTransaction`)
+pub const NETWORK_PROTOCOL_V3: NetworkProtocol = 3;
+
+/// Tor onion service addresses (`PeerAddress::Onion3`) in `AnnounceAddrRequest` and
+/// `AddrListResponse`
+pub const NETWORK_PROTOCOL_V4: NetworkProtocol = 4;
+
 /// Latest known network protocol version
-pub const NETWORK_PROTOCOL_CURRENT: NetworkProtocol = NETWORK_PROTOCOL_V1;
+pub const NETWORK_PROTOCOL_CURRENT: NetworkProtocol = NETWORK_PROTOCOL_V4;
 
 /// Minimum supported network protocol version
 pub const NETWORK_PROTOCOL_MIN: NetworkProtocol = NETWORK_PROTOCOL_V1;
+
+/// Returns the protocol version used with a peer that has advertised the `remote` version
+pub fn negotiated_protocol(remote: NetworkProtocol) -> NetworkProtocol {
+    std::cmp::min(remote, NETWORK_PROTOCOL_CURRENT)
+}
@@ -13,12 +13,60 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::Arc;
+
 use common::chain::SignedTransaction;
 use mempool::TxStatus;
 use serialization::hex_encoded::HexEncoded;
 
-use crate::{interface::types::ConnectedPeer, types::peer_id::PeerId};
-use rpc::Result as RpcResult;
+use crate::{interface::types::ConnectedPeer, types::peer_id::PeerId, P2pEvent};
+use rpc::{
+    subscription::{PendingSubscriptionSink, SubscriptionResult},
+    Result as RpcResult,
+};
+
+/// P2P event as it is sent to RPC subscribers
+///
+/// `String` is used for types that implement `Display`, but do not have `serde::Serialize`.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type")]
+pub enum RpcP2pEvent {
+    PeerConnected {
+        peer_id: PeerId,
+        address: String,
+        inbound: bool,
+        user_agent: String,
+        version: String,
+        node_key: Option<String>,
+    },
+    PeerDisconnected {
+        peer_id: PeerId,
+    },
+}
+
+impl From<P2pEvent> for RpcP2pEvent {
+    fn from(event: P2pEvent) -> Self {
+        match event {
+            P2pEvent::PeerConnected {
+                id,
+                services: _,
+                address,
+                inbound,
+                user_agent,
+                version,
+                node_key,
+            } => Self::PeerConnected {
+                peer_id: id,
+                address,
+                inbound,
+                user_agent: user_agent.to_string(),
+                version: version.to_string(),
+                node_key,
+            },
+            P2pEvent::PeerDisconnected(peer_id) => Self::PeerDisconnected { peer_id },
+        }
+    }
+}
 
 #[rpc::rpc(server, client, namespace = "p2p")]
 trait P2pRpc {
@@ -56,6 +104,14 @@ trait P2pRpc {
     /// Submits a transaction to mempool, and if it is valid, broadcasts it to the network.
     #[method(name = "submit_transaction")]
     async fn submit_transaction(&self, tx: HexEncoded<SignedTransaction>) -> RpcResult<TxStatus>;
+
+    /// Subscribe to peer connection and disconnection notifications (WebSocket only)
+    #[subscription(
+        name = "subscribe_events",
+        unsubscribe = "unsubscribe_events",
+        item = RpcP2pEvent
+    )]
+    async fn subscribe_events(&self) -> SubscriptionResult;
 }
 
 #[async_trait::async_trait]
@@ -99,4 +155,20 @@ impl P2pRpcServer for super::P2pHandle {
         let res = self.call_async_mut(move |this| this.submit_transaction(tx.take())).await;
         rpc::handle_result(res)
     }
+
+    async fn subscribe_events(&self, pending: PendingSubscriptionSink) -> SubscriptionResult {
+        let (sender, receiver) = rpc::subscription::event_channel();
+
+        // P2p only keeps a weak reference, so the handler is unsubscribed
+        // when it's dropped at the end of the subscription
+        let handler: Arc<dyn Fn(P2pEvent) + Send + Sync> = Arc::new(move |event: P2pEvent| {
+            sender.send(RpcP2pEvent::from(event));
+        });
+        let weak_handler = Arc::downgrade(&handler);
+        self.call_mut(move |this| this.subscribe_to_events_weak(weak_handler)).await??;
+
+        let result = rpc::subscription::forward_events(pending, receiver).await;
+        drop(handler);
+        result
+    }
 }
@@ -0,0 +1,156 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Restoring blocks received in the compact form.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use common::{
+    chain::{
+        block::{
+            block_body::BlockBody, signed_block_header::SignedBlockHeader, BlockCreationError,
+            BlockReward,
+        },
+        Block, SignedTransaction,
+    },
+    primitives::{Id, Idable},
+};
+
+use crate::{
+    error::ProtocolError,
+    message::{CompactBlock, ShortTxId},
+};
+
+/// A block received in the compact form, some transactions of which may still be missing.
+pub struct PartialBlock {
+    block_id: Id<Block>,
+    header: SignedBlockHeader,
+    block_reward: BlockReward,
+    transactions: Vec<Option<SignedTransaction>>,
+    /// Short ids of the transactions that haven't been restored yet, by their position in the block.
+    missing: BTreeMap<usize, ShortTxId>,
+}
+
+impl PartialBlock {
+    /// Places the prefilled transactions and the short ids at their positions.
+    ///
+    /// The prefilled transactions must be sorted by their positions, which must be unique and
+    /// within the block.
+    pub fn new(compact_block: CompactBlock) -> Result<Self, ProtocolError> {
+        let block_id = compact_block.header().block_id();
+        let (header, block_reward, short_ids, prefilled_transactions) = compact_block.into_parts();
+
+        let tx_count = short_ids.len() + prefilled_transactions.len();
+        let mut transactions = vec![None; tx_count];
+        let mut missing = BTreeMap::new();
+        let mut short_ids = short_ids.into_iter();
+        let mut prefilled_transactions = prefilled_transactions.into_iter().peekable();
+        for (position, transaction) in transactions.iter_mut().enumerate() {
+            match prefilled_transactions.next_if(|tx| tx.index() as usize == position) {
+                Some(tx) => *transaction = Some(tx.into_transaction()),
+                None => {
+                    let short_id =
+                        short_ids.next().ok_or(ProtocolError::InvalidCompactBlock(block_id))?;
+                    missing.insert(position, short_id);
+                }
+            }
+        }
+        // The remaining prefilled transactions are either out of order or out of range.
+        utils::ensure!(
+            prefilled_transactions.next().is_none(),
+            ProtocolError::InvalidCompactBlock(block_id)
+        );
+
+        Ok(Self {
+            block_id,
+            header,
+            block_reward,
+            transactions,
+            missing,
+        })
+    }
+
+    pub fn block_id(&self) -> &Id<Block> {
+        &self.block_id
+    }
+
+    /// Positions of the transactions that haven't been restored yet.
+    pub fn missing_tx_indexes(&self) -> Vec<u32> {
+        self.missing.keys().map(|position| *position as u32).collect()
+    }
+
+    /// Short ids of the transactions that haven't been restored yet.
+    pub fn missing_short_ids(&self) -> BTreeSet<ShortTxId> {
+        self.missing.values().copied().collect()
+    }
+
+    /// Restores the missing transactions from the given candidates (normally the matching
+    /// mempool transactions). Positions with the short id matching several candidates are left missing.
+    pub fn fill_from(&mut self, candidates: impl IntoIterator<Item = SignedTransaction>) {
+        let mut matches = BTreeMap::<ShortTxId, Option<SignedTransaction>>::new();
+        for tx in candidates {
+            let short_id = ShortTxId::new(&self.block_id, &tx.transaction().get_id());
+            matches
+                .entry(short_id)
+                .and_modify(|collision| *collision = None)
+                .or_insert(Some(tx));
+        }
+
+        let transactions = &mut self.transactions;
+        self.missing.retain(
+            |position, short_id| match matches.get(short_id).cloned().flatten() {
+                Some(tx) => {
+                    transactions[*position] = Some(tx);
+                    false
+                }
+                None => true,
+            },
+        );
+    }
+
+    /// Places the transactions received in response to `BlockTransactionsRequest`.
+    pub fn fill_missing(
+        &mut self,
+        transactions: Vec<SignedTransaction>,
+    ) -> Result<(), ProtocolError> {
+        utils::ensure!(
+            transactions.len() == self.missing.len(),
+            ProtocolError::UnexpectedBlockTransactionsCount(
+                self.block_id,
+                self.missing.len(),
+                transactions.len()
+            )
+        );
+
+        for (position, tx) in std::mem::take(&mut self.missing).into_keys().zip(transactions) {
+            self.transactions[position] = Some(tx);
+        }
+
+        Ok(())
+    }
+
+    /// Assembles the block, checking the restored transactions against the merkle roots of
+    /// the header. All the transactions must be restored at this point.
+    pub fn into_block(self) -> Result<Block, BlockCreationError> {
+        debug_assert!(self.missing.is_empty());
+
+        let transactions = self
+            .transactions
+            .into_iter()
+            .map(|tx| tx.expect("All transactions must be restored"))
+            .collect();
+        Block::new_from_header(self.header, BlockBody::new(self.block_reward, transactions))
+    }
+}
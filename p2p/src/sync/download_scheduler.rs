@@ -0,0 +1,232 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The block download state shared between the peer tasks.
+//!
+//! Every block is requested from one peer only, so the blocks of a long header list announced by
+//! several peers are downloaded from all of them in parallel. Blocks that arrive before their
+//! parents are kept here until the parents are processed.
+//!
+//! Only the blocks within [`BLOCK_DOWNLOAD_WINDOW`] past the tip are requested, so a slow peer
+//! can't make the others download an unlimited number of blocks that can't be processed yet.
+//! If the window is full and the block that follows the tip isn't received in time, the blocks
+//! of the peer it's been requested from are reassigned to other peers.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    time::Duration,
+};
+
+use common::{
+    chain::{Block, GenBlock},
+    primitives::{Id, Idable},
+};
+
+use crate::types::peer_id::PeerId;
+
+/// The maximum number of blocks past the tip that can be downloaded.
+pub const BLOCK_DOWNLOAD_WINDOW: u64 = 1024;
+
+/// If the download window is full, the blocks of the peer that hasn't sent the block that follows
+/// the tip in this time are reassigned to other peers.
+const BLOCKING_BLOCK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// A block that has been downloaded, but can't be processed yet because its parent is unknown.
+pub struct PendingBlock {
+    /// The peer that has sent the block.
+    pub peer_id: PeerId,
+    pub block: Block,
+}
+
+/// A block that is being downloaded.
+struct InFlightBlock {
+    /// The peer the block has been requested from.
+    peer_id: PeerId,
+    /// The time the block has been requested.
+    requested_at: Duration,
+}
+
+#[derive(Default)]
+pub struct DownloadScheduler {
+    /// Blocks that are being downloaded.
+    in_flight: BTreeMap<Id<Block>, InFlightBlock>,
+    /// The time of the last block request or response for every peer that is downloading blocks.
+    last_activity: BTreeMap<PeerId, Duration>,
+    /// Downloaded blocks with unknown parents, by their parent ids.
+    pending_blocks: BTreeMap<Id<GenBlock>, Vec<PendingBlock>>,
+    /// Identifiers of the blocks in `pending_blocks`.
+    pending_block_ids: BTreeSet<Id<Block>>,
+}
+
+impl DownloadScheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns true if the block is already downloaded but hasn't been processed yet.
+    pub fn is_pending(&self, block_id: &Id<Block>) -> bool {
+        self.pending_block_ids.contains(block_id)
+    }
+
+    /// Assigns the block to the given peer unless it is being downloaded from another peer.
+    pub fn try_assign(&mut self, block_id: Id<Block>, peer_id: PeerId, now: Duration) -> bool {
+        if self.pending_block_ids.contains(&block_id) {
+            return false;
+        }
+
+        match self.in_flight.get(&block_id) {
+            Some(in_flight) => in_flight.peer_id == peer_id,
+            None => {
+                self.in_flight.insert(
+                    block_id,
+                    InFlightBlock {
+                        peer_id,
+                        requested_at: now,
+                    },
+                );
+                self.last_activity.insert(peer_id, now);
+                true
+            }
+        }
+    }
+
+    /// Reassigns the blocks of the peer that stalls the download window.
+    ///
+    /// `block_ids` are the blocks within the window in the chain order, the first of them that is
+    /// being downloaded from another peer is the one that blocks the window.
+    ///
+    /// Returns true if the blocks have been released.
+    pub fn release_blocking_peer(
+        &mut self,
+        block_ids: impl Iterator<Item = Id<Block>>,
+        peer_id: PeerId,
+        now: Duration,
+    ) -> bool {
+        let blocking_block = block_ids
+            .filter_map(|block_id| self.in_flight.get(&block_id))
+            .find(|in_flight| in_flight.peer_id != peer_id);
+
+        match blocking_block {
+            Some(in_flight) if now >= in_flight.requested_at + BLOCKING_BLOCK_TIMEOUT => {
+                let blocking_peer = in_flight.peer_id;
+                self.release_in_flight(blocking_peer)
+            }
+            Some(_) | None => false,
+        }
+    }
+
+    /// Marks the block as received from the given peer.
+    pub fn block_received(&mut self, block_id: &Id<Block>, peer_id: PeerId, now: Duration) {
+        // The block could have been reassigned to another peer that is still downloading it.
+        if self
+            .in_flight
+            .get(block_id)
+            .map_or(false, |in_flight| in_flight.peer_id == peer_id)
+        {
+            self.in_flight.remove(block_id);
+        }
+
+        if self.in_flight.values().any(|in_flight| in_flight.peer_id == peer_id) {
+            self.last_activity.insert(peer_id, now);
+        } else {
+            self.last_activity.remove(&peer_id);
+        }
+    }
+
+    /// Stores a block whose parent isn't processed yet.
+    pub fn add_pending_block(&mut self, peer_id: PeerId, block: Block) {
+        if !self.pending_block_ids.insert(block.get_id()) {
+            return;
+        }
+
+        self.pending_blocks
+            .entry(block.prev_block_id())
+            .or_default()
+            .push(PendingBlock { peer_id, block });
+    }
+
+    /// Removes and returns the pending children of the given block.
+    pub fn take_pending_children(&mut self, parent_id: &Id<GenBlock>) -> Vec<PendingBlock> {
+        let children = self.pending_blocks.remove(parent_id).unwrap_or_default();
+        for child in &children {
+            self.pending_block_ids.remove(&child.block.get_id());
+        }
+        children
+    }
+
+    /// Drops the pending descendants of the given block (for example, if the block is rejected).
+    ///
+    /// Returns the number of the dropped blocks.
+    pub fn drop_pending_descendants(&mut self, block_id: &Id<GenBlock>) -> usize {
+        let mut parent_ids = vec![*block_id];
+        let mut dropped = 0;
+        while let Some(parent_id) = parent_ids.pop() {
+            for child in self.take_pending_children(&parent_id) {
+                parent_ids.push(child.block.get_id().into());
+                dropped += 1;
+            }
+        }
+        dropped
+    }
+
+    /// Releases all the blocks assigned to the disconnected peer, so they can be requested from
+    /// other peers. The pending blocks sent by the peer are dropped and will be downloaded again.
+    ///
+    /// Returns true if there were such blocks.
+    pub fn release_peer(&mut self, peer_id: PeerId) -> bool {
+        let released = self.release_in_flight(peer_id);
+
+        let pending_count = self.pending_block_ids.len();
+        self.pending_blocks.retain(|_, children| {
+            children.retain(|child| {
+                let keep = child.peer_id != peer_id;
+                if !keep {
+                    self.pending_block_ids.remove(&child.block.get_id());
+                }
+                keep
+            });
+            !children.is_empty()
+        });
+
+        released || self.pending_block_ids.len() != pending_count
+    }
+
+    /// Releases the blocks that are being downloaded from the peer.
+    ///
+    /// Returns true if there were such blocks.
+    fn release_in_flight(&mut self, peer_id: PeerId) -> bool {
+        self.last_activity.remove(&peer_id);
+
+        let assigned_count = self.in_flight.len();
+        self.in_flight.retain(|_, in_flight| in_flight.peer_id != peer_id);
+        self.in_flight.len() != assigned_count
+    }
+
+    /// Releases the blocks of the peers that haven't sent anything for the `stalling_timeout`.
+    ///
+    /// Returns true if there were such blocks.
+    pub fn release_stalled(&mut self, now: Duration, stalling_timeout: Duration) -> bool {
+        let stalled_peers = self
+            .last_activity
+            .iter()
+            .filter(|(_, last_activity)| now >= **last_activity + stalling_timeout)
+            .map(|(peer_id, _)| *peer_id)
+            .collect::<Vec<_>>();
+
+        stalled_peers.into_iter().fold(false, |released, peer_id| {
+            self.release_in_flight(peer_id) || released
+        })
+    }
+}
@@ -16,15 +16,18 @@
 //! This module is responsible for both initial syncing and further blocks processing (the reaction
 //! to block announcement from peers and the announcement of blocks produced by this node).
 
+mod compact_block;
+mod download_scheduler;
 mod peer;
 mod types;
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 use futures::never::Never;
 use tokio::{
     sync::mpsc::{self, Receiver, UnboundedReceiver, UnboundedSender},
     task::JoinHandle,
+    time::MissedTickBehavior,
 };
 
 use chainstate::{chainstate_interface::ChainstateInterface, ChainstateHandle};
@@ -40,7 +43,7 @@ use common::{
 use logging::log;
 use mempool::{event::TransactionProcessed, MempoolHandle, TxOrigin};
 use utils::atomics::AcqRelAtomicBool;
-use utils::sync::Arc;
+use utils::sync::{Arc, Mutex};
 use utils::tap_error_log::LogError;
 
 use crate::{
@@ -48,10 +51,14 @@ use crate::{
     error::P2pError,
     message::SyncMessage,
     net::{
-        types::{services::Services, SyncingEvent},
+        types::{
+            services::{Service, Services},
+            SyncingEvent,
+        },
         MessagingService, NetworkingService, SyncingEventReceiver,
     },
-    sync::peer::Peer,
+    protocol::NetworkProtocol,
+    sync::{download_scheduler::DownloadScheduler, peer::Peer},
     types::peer_id::PeerId,
     PeerManagerEvent, Result,
 };
@@ -59,6 +66,8 @@ use crate::{
 pub enum LocalEvent {
     ChainstateNewTip(SignedBlockHeader),
     MempoolNewTx(Id<Transaction>),
+    /// Some blocks have been downloaded or released by other peers.
+    BlockDownloadProgress,
 }
 
 pub struct PeerContext {
@@ -90,6 +99,12 @@ pub struct BlockSyncManager<T: NetworkingService> {
     /// The list of connected peers
     peers: HashMap<PeerId, PeerContext>,
 
+    /// Local transactions that were accepted while there were no connected peers
+    unannounced_local_txs: BTreeSet<Id<Transaction>>,
+
+    /// The block download state shared between the peers.
+    download_scheduler: Arc<Mutex<DownloadScheduler>>,
+
     time_getter: TimeGetter,
 }
 
@@ -122,6 +137,8 @@ where
             mempool_handle,
             is_initial_block_download: Arc::new(true.into()),
             peers: Default::default(),
+            unannounced_local_txs: Default::default(),
+            download_scheduler: Arc::new(Mutex::new(DownloadScheduler::new())),
             time_getter,
         }
     }
@@ -141,6 +158,16 @@ where
         );
 
         let mut tx_processed_receiver = subscribe_to_tx_processed(&self.mempool_handle).await?;
+        // The local transactions restored from the mempool file were processed before the subscription
+        self.mempool_handle
+            .call_mut(|m| m.announce_restored_transactions())
+            .await
+            .map_err(|_| P2pError::SubsystemFailure)?;
+
+        let mut stalling_interval = tokio::time::interval(*self.p2p_config.sync_stalling_timeout);
+        stalling_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        // The first tick completes immediately.
+        stalling_interval.tick().await;
 
         loop {
             tokio::select! {
@@ -158,6 +185,10 @@ where
                 event = self.sync_event_receiver.poll_next() => {
                     self.handle_peer_event(event?);
                 },
+
+                _ = stalling_interval.tick() => {
+                    self.release_stalled_blocks();
+                },
             }
         }
     }
@@ -167,15 +198,23 @@ where
         &mut self,
         peer_id: PeerId,
         remote_services: Services,
+        local_services: Services,
+        inbound: bool,
+        protocol: NetworkProtocol,
         sync_rx: Receiver<SyncMessage>,
     ) {
         log::debug!("Register peer {peer_id} to sync manager");
 
+        // Local services can be narrower than the node type (for example, for block-relay-only connections)
+        let common_services = local_services & remote_services;
+
         let (local_event_tx, local_event_rx) = mpsc::unbounded_channel();
 
         let mut peer = Peer::<T>::new(
             peer_id,
-            remote_services,
+            common_services,
+            inbound,
+            protocol,
             Arc::clone(&self.chain_config),
             Arc::clone(&self.p2p_config),
             self.chainstate_handle.clone(),
@@ -185,6 +224,7 @@ where
             self.messaging_handle.clone(),
             local_event_rx,
             Arc::clone(&self.is_initial_block_download),
+            Arc::clone(&self.download_scheduler),
             self.time_getter.clone(),
         );
 
@@ -197,6 +237,12 @@ where
             local_event_tx,
         };
 
+        if common_services.has_service(Service::Transactions) {
+            for tx_id in std::mem::take(&mut self.unannounced_local_txs) {
+                let _ = peer_context.local_event_tx.send(LocalEvent::MempoolNewTx(tx_id));
+            }
+        }
+
         let prev_task = self.peers.insert(peer_id, peer_context);
         assert!(prev_task.is_none(), "Registered duplicated peer: {peer_id}");
     }
@@ -210,10 +256,46 @@ where
             .unwrap_or_else(|| panic!("Unregistering unknown peer: {peer_id}"));
         // Call `abort` because the peer task may be sleeping for a long time in the `sync_clock` function
         peer.task.abort();
+
+        let released = self
+            .download_scheduler
+            .lock()
+            .expect("Download scheduler mutex is poisoned")
+            .release_peer(peer_id);
+        if released {
+            log::debug!("Blocks requested from peer {peer_id} are released");
+            self.notify_block_download_progress();
+        }
+    }
+
+    /// Releases the blocks requested from the stalled peers, so they can be downloaded from the
+    /// other ones.
+    fn release_stalled_blocks(&mut self) {
+        let released = self
+            .download_scheduler
+            .lock()
+            .expect("Download scheduler mutex is poisoned")
+            .release_stalled(
+                self.time_getter.get_time(),
+                *self.p2p_config.sync_stalling_timeout,
+            );
+        if released {
+            log::debug!("Blocks requested from stalled peers are released");
+            self.notify_block_download_progress();
+        }
+    }
+
+    fn notify_block_download_progress(&mut self) {
+        for peer in self.peers.values_mut() {
+            let _ = peer.local_event_tx.send(LocalEvent::BlockDownloadProgress);
+        }
     }
 
     /// Announces the header of a new block to peers.
     async fn handle_new_tip(&mut self, block_id: Id<Block>) -> Result<()> {
+        // Peers waiting for the blocks requested from other peers may continue downloading.
+        self.notify_block_download_progress();
+
         let is_initial_block_download = if self.is_initial_block_download.load() {
             let is_ibd = self.chainstate_handle.call(|c| c.is_initial_block_download()).await??;
             self.is_initial_block_download.store(is_ibd);
@@ -248,11 +330,14 @@ where
             Ok(()) => match origin {
                 TxOrigin::Peer(_) | TxOrigin::LocalP2p => {
                     log::info!("Broadcasting transaction {tx_id} originating in {origin}");
+                    if self.peers.is_empty() && origin == TxOrigin::LocalP2p {
+                        self.unannounced_local_txs.insert(tx_id);
+                    }
                     for peer in self.peers.values_mut() {
                         let _ = peer.local_event_tx.send(LocalEvent::MempoolNewTx(tx_id));
                     }
                 }
-                TxOrigin::LocalMempool | TxOrigin::PastBlock => {
+                TxOrigin::LocalMempool | TxOrigin::PastBlock | TxOrigin::Restored => {
                     log::trace!("Not propagating transaction {tx_id} originating in {origin}");
                 }
             },
@@ -269,7 +354,10 @@ where
                             .map_err(|_| P2pError::ChannelClosed)?;
                     }
                 }
-                TxOrigin::PastBlock | TxOrigin::LocalMempool | TxOrigin::LocalP2p => (),
+                TxOrigin::PastBlock
+                | TxOrigin::LocalMempool
+                | TxOrigin::LocalP2p
+                | TxOrigin::Restored => (),
             },
         }
         Ok(())
@@ -281,8 +369,18 @@ where
             SyncingEvent::Connected {
                 peer_id,
                 services,
+                local_services,
+                inbound,
+                protocol,
+                sync_rx,
+            } => self.register_peer(
+                peer_id,
+                services,
+                local_services,
+                inbound,
+                protocol,
                 sync_rx,
-            } => self.register_peer(peer_id, services, sync_rx),
+            ),
             SyncingEvent::Disconnected { peer_id } => self.unregister_peer(peer_id),
         }
     }
@@ -300,6 +398,7 @@ pub async fn subscribe_to_new_tip(
                 chainstate::ChainstateEvent::NewTip(block_id, _) => {
                     let _ = sender.send(block_id).log_err_pfx("The new tip receiver closed");
                 }
+                chainstate::ChainstateEvent::Reorg { .. } => {}
             },
         );
 
@@ -31,7 +31,9 @@ use chainstate::{
     BlockSource, ChainstateError, Locator,
 };
 use common::{
-    chain::{block::signed_block_header::SignedBlockHeader, Block, ChainConfig, Transaction},
+    chain::{
+        block::signed_block_header::SignedBlockHeader, Block, ChainConfig, GenBlock, Transaction,
+    },
     primitives::{Id, Idable},
     time_getter::TimeGetter,
 };
@@ -41,21 +43,27 @@ use mempool::{
     MempoolHandle,
 };
 use utils::const_value::ConstValue;
-use utils::sync::Arc;
+use utils::sync::{Arc, Mutex};
 use utils::{atomics::AcqRelAtomicBool, bloom_filters::rolling_bloom_filter::RollingBloomFilter};
 
 use crate::{
     config::P2pConfig,
     error::{P2pError, PeerError, ProtocolError},
     message::{
-        BlockListRequest, BlockResponse, HeaderList, HeaderListRequest, SyncMessage,
+        BlockListRequest, BlockResponse, BlockTransactions, BlockTransactionsRequest, CompactBlock,
+        HeaderList, HeaderListRequest, ShortTxId, SyncMessage, TransactionInventory,
         TransactionResponse,
     },
     net::{
         types::services::{Service, Services},
         NetworkingService,
     },
-    sync::types::PeerActivity,
+    protocol::{NetworkProtocol, NETWORK_PROTOCOL_V2, NETWORK_PROTOCOL_V3},
+    sync::{
+        compact_block::PartialBlock,
+        download_scheduler::{DownloadScheduler, PendingBlock, BLOCK_DOWNLOAD_WINDOW},
+        types::PeerActivity,
+    },
     types::peer_id::PeerId,
     utils::oneshot_nofail,
     MessagingService, PeerManagerEvent, Result,
@@ -67,6 +75,18 @@ use super::LocalEvent;
 const KNOWN_TRANSACTIONS_ROLLING_BLOOM_FILTER_SIZE: usize = 50000;
 const KNOWN_TRANSACTIONS_ROLLING_BLOOM_FPP: f64 = 0.000001;
 
+/// The average delay between transaction inventory messages sent to an outbound peer
+/// (the same as `OUTBOUND_INVENTORY_BROADCAST_INTERVAL` in Bitcoin Core).
+const OUTBOUND_TX_INVENTORY_INTERVAL: Duration = Duration::from_secs(2);
+/// The delay is longer for inbound peers because it's cheap for an attacker to open many inbound
+/// connections and compare the time at which a transaction is announced on each of them.
+const INBOUND_TX_INVENTORY_INTERVAL: Duration = Duration::from_secs(5);
+/// The maximum number of transaction identifiers in one `TransactionInventory` message.
+pub const MAX_TX_INVENTORY_SIZE: usize = 1000;
+/// The maximum number of transactions waiting to be announced to a peer. New transactions aren't
+/// announced to the peer if it doesn't keep up.
+const MAX_PENDING_TX_INVENTORY_SIZE: usize = 10 * MAX_TX_INVENTORY_SIZE;
+
 /// Helper for `RollingBloomFilter` because `Id` does not implement `Hash`
 struct TxIdWrapper(Id<Transaction>);
 
@@ -85,6 +105,10 @@ pub struct Peer<T: NetworkingService> {
     chain_config: Arc<ChainConfig>,
     p2p_config: Arc<P2pConfig>,
     common_services: Services,
+    /// Whether the connection was initiated by the peer.
+    inbound: bool,
+    /// Negotiated network protocol version.
+    protocol: NetworkProtocol,
     chainstate_handle: subsystem::Handle<Box<dyn ChainstateInterface>>,
     mempool_handle: MempoolHandle,
     peer_manager_sender: UnboundedSender<PeerManagerEvent<T>>,
@@ -92,13 +116,18 @@ pub struct Peer<T: NetworkingService> {
     sync_rx: Receiver<SyncMessage>,
     local_event_rx: UnboundedReceiver<LocalEvent>,
     is_initial_block_download: Arc<AcqRelAtomicBool>,
+    /// The block download state shared between the peers.
+    download_scheduler: Arc<Mutex<DownloadScheduler>>,
     /// A list of headers received via the `HeaderListResponse` message that we haven't yet
-    /// requested the blocks for.
+    /// requested the blocks for. It also contains the blocks that are being downloaded from
+    /// other peers, so they can be requested from this peer if the other peers stall.
     known_headers: Vec<SignedBlockHeader>,
     /// A list of blocks that we requested from this peer.
     requested_blocks: BTreeSet<Id<Block>>,
     /// A queue of the blocks requested this peer.
     blocks_queue: VecDeque<Id<Block>>,
+    /// A compact block received from this peer for which we wait for the missing transactions.
+    partial_block: Option<PartialBlock>,
     /// The index of the best known block of a peer.
     best_known_block: Option<BlockIndex>,
     /// A rolling filter of all known transactions (sent to us or sent by us)
@@ -107,6 +136,10 @@ pub struct Peer<T: NetworkingService> {
     /// A list of transactions that have been announced by this peer. An entry is added when the
     /// identifier is announced and removed when the actual transaction or not found response is received.
     announced_transactions: BTreeSet<Id<Transaction>>,
+    /// New transactions that will be announced to the peer in the next inventory message.
+    pending_tx_inventory: BTreeSet<Id<Transaction>>,
+    /// The time when the next inventory message can be sent.
+    next_tx_inventory_time: Duration,
     /// A number of consecutive unconnected headers received from a peer. This counter is reset
     /// after receiving a valid header.
     unconnected_headers: usize,
@@ -128,7 +161,9 @@ where
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         id: PeerId,
-        remote_services: Services,
+        common_services: Services,
+        inbound: bool,
+        protocol: NetworkProtocol,
         chain_config: Arc<ChainConfig>,
         p2p_config: Arc<P2pConfig>,
         chainstate_handle: subsystem::Handle<Box<dyn ChainstateInterface>>,
@@ -138,22 +173,23 @@ where
         messaging_handle: T::MessagingHandle,
         local_event_rx: UnboundedReceiver<LocalEvent>,
         is_initial_block_download: Arc<AcqRelAtomicBool>,
+        download_scheduler: Arc<Mutex<DownloadScheduler>>,
         time_getter: TimeGetter,
     ) -> Self {
-        let local_services: Services = (*p2p_config.node_type).into();
-        let common_services = local_services & remote_services;
-
         let known_transactions = RollingBloomFilter::new(
             KNOWN_TRANSACTIONS_ROLLING_BLOOM_FILTER_SIZE,
             KNOWN_TRANSACTIONS_ROLLING_BLOOM_FPP,
             &mut make_pseudo_rng(),
         );
+        let next_tx_inventory_time = time_getter.get_time();
 
         Self {
             id: id.into(),
             chain_config,
             p2p_config,
             common_services,
+            inbound,
+            protocol,
             chainstate_handle,
             mempool_handle,
             peer_manager_sender,
@@ -161,12 +197,16 @@ where
             sync_rx,
             local_event_rx,
             is_initial_block_download,
+            download_scheduler,
             known_headers: Vec::new(),
             requested_blocks: BTreeSet::new(),
             blocks_queue: VecDeque::new(),
+            partial_block: None,
             best_known_block: None,
             known_transactions,
             announced_transactions: BTreeSet::new(),
+            pending_tx_inventory: BTreeSet::new(),
+            next_tx_inventory_time,
             unconnected_headers: 0,
             last_activity: PeerActivity::Pending,
             time_getter,
@@ -211,10 +251,18 @@ where
 
                 event = self.local_event_rx.recv() => {
                     let event = event.ok_or(P2pError::ChannelClosed)?;
-                    self.handle_new_event(event)?;
+                    self.handle_new_event(event).await?;
                 }
 
                 _ = stalling_interval.tick(), if !matches!(self.last_activity, PeerActivity::Pending) => {}
+
+                _ = tokio::time::sleep(self.next_tx_inventory_time.saturating_sub(self.time_getter.get_time())), if !self.pending_tx_inventory.is_empty() => {}
+            }
+
+            if !self.pending_tx_inventory.is_empty()
+                && self.time_getter.get_time() >= self.next_tx_inventory_time
+            {
+                self.send_tx_inventory().await?;
             }
 
             // Run on each loop iteration, so it's easier to test
@@ -226,7 +274,7 @@ where
         }
     }
 
-    fn handle_new_event(&mut self, event: LocalEvent) -> Result<()> {
+    async fn handle_new_event(&mut self, event: LocalEvent) -> Result<()> {
         match event {
             LocalEvent::ChainstateNewTip(header) => {
                 if self.send_tip_updates && self.common_services.has_service(Service::Blocks) {
@@ -242,8 +290,14 @@ where
                 if !self.known_transactions.contains(&TxIdWrapper(txid))
                     && self.common_services.has_service(Service::Transactions)
                 {
-                    self.add_known_transaction(txid);
-                    self.messaging_handle.send_message(self.id(), SyncMessage::NewTransaction(txid))
+                    self.queue_tx_announcement(txid);
+                }
+                Ok(())
+            }
+            LocalEvent::BlockDownloadProgress => {
+                // Only continue if the blocks that were requested from this peer are received.
+                if self.requested_blocks.is_empty() && !self.known_headers.is_empty() {
+                    self.request_next_blocks().await
                 } else {
                     Ok(())
                 }
@@ -274,9 +328,18 @@ where
             SyncMessage::BlockListRequest(r) => self.handle_block_request(r.into_block_ids()).await,
             SyncMessage::HeaderList(l) => self.handle_header_list(l.into_headers()).await,
             SyncMessage::BlockResponse(r) => self.handle_block_response(r.into_block()).await,
-            SyncMessage::NewTransaction(id) => self.handle_transaction_announcement(id).await,
+            SyncMessage::NewTransaction(id) => self.handle_new_transaction(id).await,
+            SyncMessage::TransactionInventory(i) => {
+                self.handle_transaction_inventory(i.into_transactions()).await
+            }
             SyncMessage::TransactionRequest(id) => self.handle_transaction_request(id).await,
             SyncMessage::TransactionResponse(tx) => self.handle_transaction_response(tx).await,
+            SyncMessage::CompactBlockRequest(id) => self.handle_compact_block_request(id).await,
+            SyncMessage::CompactBlock(b) => self.handle_compact_block(b).await,
+            SyncMessage::BlockTransactionsRequest(r) => {
+                self.handle_block_transactions_request(r).await
+            }
+            SyncMessage::BlockTransactions(r) => self.handle_block_transactions(r).await,
         };
         Self::handle_result(&self.peer_manager_sender, self.id(), res).await
     }
@@ -518,62 +581,328 @@ where
             .await??;
         self.unconnected_headers = 0;
 
-        self.request_blocks(headers)
+        // A single new block is most likely made of the transactions we already have.
+        if headers.len() == 1
+            && self.requested_blocks.is_empty()
+            && !self.is_initial_block_download.load()
+            && self.common_services.has_service(Service::Transactions)
+            && self.protocol >= NETWORK_PROTOCOL_V2
+        {
+            let header = headers.into_iter().next().expect("Headers shouldn't be empty");
+            return self.request_compact_block(header);
+        }
+
+        self.request_blocks(headers).await
     }
 
     async fn handle_block_response(&mut self, block: Block) -> Result<()> {
-        log::debug!("Block ({}) from peer {}", block.get_id(), self.id());
+        let block_id = block.get_id();
+        log::debug!("Block ({block_id}) from peer {}", self.id());
 
-        if self.requested_blocks.take(&block.get_id()).is_none() {
+        if self.requested_blocks.take(&block_id).is_none() {
             return Err(P2pError::ProtocolError(ProtocolError::UnexpectedMessage(
                 "block response".to_owned(),
             )));
         }
+        self.download_scheduler
+            .lock()
+            .expect("Download scheduler mutex is poisoned")
+            .block_received(&block_id, self.id(), self.time_getter.get_time());
+
+        let prev_block_id = block.prev_block_id();
+        if self.is_block_known(prev_block_id).await? {
+            let res = self.process_block(self.id(), block).await;
+            if res.is_err() {
+                self.drop_pending_descendants(block_id.into());
+            }
+            res?;
+            self.process_pending_blocks(block_id.into()).await?;
+        } else {
+            // The parent block is being downloaded from another peer.
+            log::debug!(
+                "Block {block_id} from peer {} is received before its parent",
+                self.id()
+            );
+            self.download_scheduler
+                .lock()
+                .expect("Download scheduler mutex is poisoned")
+                .add_pending_block(self.id(), block);
+
+            // The parent block could have been processed in the meantime.
+            if self.is_block_known(prev_block_id).await? {
+                self.process_pending_blocks(prev_block_id).await?;
+            }
+        }
 
+        if self.requested_blocks.is_empty() {
+            self.request_next_blocks().await?;
+        } else {
+            // We expect additional blocks from the peer. Update the timestamp we received the
+            // current one.
+            self.last_activity = PeerActivity::ExpectingBlocks {
+                time: self.time_getter.get_time(),
+            };
+        }
+
+        Ok(())
+    }
+
+    async fn is_block_known(&self, block_id: Id<GenBlock>) -> Result<bool> {
+        Ok(self
+            .chainstate_handle
+            .call(move |c| c.get_gen_block_index(&block_id))
+            .await??
+            .is_some())
+    }
+
+    /// Processes the block sent by the peer, the peer manager is notified if it becomes the new tip
+    async fn process_block(&mut self, peer_id: PeerId, block: Block) -> Result<()> {
         let block = self.chainstate_handle.call(|c| c.preliminary_block_check(block)).await??;
-        let peer_id = self.id();
+        let block_id = block.get_id();
         match self
             .chainstate_handle
             .call_mut(move |c| {
-                // If the block already exists in the block tree, return the existing block index.
+                // If the block already exists in the block tree, don't process it again.
                 // It's used to prevent chainstate from printing "Block already exists" errors.
-                if let Some(block_index) = c.get_block_index(&block.get_id())? {
+                if c.get_block_index(&block.get_id())?.is_some() {
                     log::debug!(
                         "Peer {} sent a block that already exists ({})",
                         peer_id,
                         block.get_id()
                     );
-                    return Ok(Some(block_index));
+                    return Ok(None);
                 }
                 c.process_block(block, BlockSource::Peer)
             })
             .await?
         {
-            Ok(_) => Ok(()),
+            Ok(Some(_)) => {
+                self.peer_manager_sender
+                    .send(PeerManagerEvent::NewTipReceived(peer_id, block_id))?;
+                Ok(())
+            }
+            Ok(None) => Ok(()),
             // It is OK to receive an already processed block
             // This should not happen because of the `get_block_index` check above.
             Err(ChainstateError::ProcessBlockError(BlockError::BlockAlreadyExists(_))) => Ok(()),
-            Err(e) => Err(e),
-        }?;
+            Err(e) => Err(e.into()),
+        }
+    }
 
-        if self.requested_blocks.is_empty() {
-            if self.known_headers.is_empty() {
-                // Request more headers.
-                self.request_headers().await?;
-            } else {
-                // Download remaining blocks.
-                let headers = mem::take(&mut self.known_headers);
-                self.request_blocks(headers)?;
+    /// Processes the downloaded blocks that were waiting for the given parent block.
+    ///
+    /// Such blocks may have been sent by other peers, so their errors are handled here.
+    async fn process_pending_blocks(&mut self, parent_id: Id<GenBlock>) -> Result<()> {
+        let mut parent_ids = vec![parent_id];
+        while let Some(parent_id) = parent_ids.pop() {
+            let children = self
+                .download_scheduler
+                .lock()
+                .expect("Download scheduler mutex is poisoned")
+                .take_pending_children(&parent_id);
+
+            for PendingBlock { peer_id, block } in children {
+                let block_id = block.get_id();
+                let res = self.process_block(peer_id, block).await;
+                if res.is_ok() {
+                    parent_ids.push(block_id.into());
+                } else {
+                    self.drop_pending_descendants(block_id.into());
+                }
+                Self::handle_result(&self.peer_manager_sender, peer_id, res).await?;
             }
-        } else {
-            // We expect additional blocks from the peer. Update the timestamp we received the
-            // current one.
+        }
+
+        Ok(())
+    }
+
+    /// Drops the downloaded blocks that can't be processed because their ancestor is rejected.
+    fn drop_pending_descendants(&self, block_id: Id<GenBlock>) {
+        let dropped = self
+            .download_scheduler
+            .lock()
+            .expect("Download scheduler mutex is poisoned")
+            .drop_pending_descendants(&block_id);
+        if dropped > 0 {
+            log::debug!(
+                "{dropped} pending descendants of the rejected block {block_id} are dropped"
+            );
+        }
+    }
+
+    /// Compact block messages aren't known to the peers using older protocol versions.
+    fn ensure_compact_blocks_supported(&self, message: &str) -> Result<()> {
+        utils::ensure!(
+            self.protocol >= NETWORK_PROTOCOL_V2,
+            P2pError::ProtocolError(ProtocolError::UnexpectedMessage(message.to_owned()))
+        );
+        Ok(())
+    }
+
+    /// Sends the requested block in the compact form. The transactions that the peer isn't known
+    /// to have are sent in full.
+    async fn handle_compact_block_request(&mut self, block_id: Id<Block>) -> Result<()> {
+        log::debug!("Compact block request from peer {}: {block_id}", self.id());
+        self.ensure_compact_blocks_supported("compact block request")?;
+
+        if self.is_initial_block_download.load() {
+            log::debug!(
+                "Ignoring compact block request because the node is in initial block download"
+            );
+            return Ok(());
+        }
+
+        let (block, index) = self
+            .chainstate_handle
+            .call(move |c| (c.get_block(block_id), c.get_block_index(&block_id)))
+            .await?;
+        let block = block?.ok_or(P2pError::ProtocolError(
+            ProtocolError::UnknownBlockRequested(block_id),
+        ))?;
+        self.best_known_block = index?;
+
+        let compact_block = CompactBlock::from_block(&block, |tx| {
+            !self.known_transactions.contains(&TxIdWrapper(tx.transaction().get_id()))
+        });
+        log::debug!(
+            "Sending compact block {block_id} with {} prefilled transactions to {} peer",
+            compact_block.prefilled_transactions().len(),
+            self.id()
+        );
+        self.messaging_handle
+            .send_message(self.id(), SyncMessage::CompactBlock(compact_block))
+    }
+
+    /// Sends the transactions of a block that the peer failed to restore from a compact block.
+    async fn handle_block_transactions_request(
+        &mut self,
+        request: BlockTransactionsRequest,
+    ) -> Result<()> {
+        let block_id = *request.block_id();
+        log::debug!(
+            "Block transactions request from peer {}: {block_id} ({})",
+            self.id(),
+            request.tx_indexes().len()
+        );
+        self.ensure_compact_blocks_supported("block transactions request")?;
+
+        let block = self.chainstate_handle.call(move |c| c.get_block(block_id)).await??.ok_or(
+            P2pError::ProtocolError(ProtocolError::UnknownBlockRequested(block_id)),
+        )?;
+
+        let transactions =
+            request
+                .tx_indexes()
+                .iter()
+                .map(|index| {
+                    block.transactions().get(*index as usize).cloned().ok_or(
+                        P2pError::ProtocolError(ProtocolError::BlockTransactionIndexOutOfRange(
+                            block_id, *index,
+                        )),
+                    )
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+        self.messaging_handle.send_message(
+            self.id(),
+            SyncMessage::BlockTransactions(BlockTransactions::new(block_id, transactions)),
+        )
+    }
+
+    async fn handle_compact_block(&mut self, compact_block: CompactBlock) -> Result<()> {
+        let block_id = compact_block.header().block_id();
+        log::debug!("Compact block ({block_id}) from peer {}", self.id());
+
+        if !self.requested_blocks.contains(&block_id) || self.partial_block.is_some() {
+            return Err(P2pError::ProtocolError(ProtocolError::UnexpectedMessage(
+                "compact block".to_owned(),
+            )));
+        }
+
+        let mut partial_block = PartialBlock::new(compact_block)?;
+        let partial_block = self
+            .mempool_handle
+            .call(move |m| {
+                // Only clone the mempool transactions the block may consist of
+                let block_id = *partial_block.block_id();
+                let short_ids = partial_block.missing_short_ids();
+                let candidates = m.find_transactions(&|tx_id| {
+                    short_ids.contains(&ShortTxId::new(&block_id, tx_id))
+                });
+                partial_block.fill_from(candidates);
+                partial_block
+            })
+            .await?;
+
+        self.complete_partial_block(partial_block).await
+    }
+
+    async fn handle_block_transactions(&mut self, response: BlockTransactions) -> Result<()> {
+        log::debug!(
+            "Block transactions ({}) from peer {}",
+            response.block_id(),
+            self.id()
+        );
+
+        let mut partial_block = match self.partial_block.take() {
+            Some(partial_block) if partial_block.block_id() == response.block_id() => partial_block,
+            partial_block => {
+                self.partial_block = partial_block;
+                return Err(P2pError::ProtocolError(ProtocolError::UnexpectedMessage(
+                    "block transactions".to_owned(),
+                )));
+            }
+        };
+
+        partial_block.fill_missing(response.into_transactions())?;
+        self.complete_partial_block(partial_block).await
+    }
+
+    /// Requests the transactions missing from the partial block or processes the restored block.
+    ///
+    /// If the restored block doesn't match its header (because of a short id collision or
+    /// wrong transactions sent by the peer), the full block is requested instead.
+    async fn complete_partial_block(&mut self, partial_block: PartialBlock) -> Result<()> {
+        let block_id = *partial_block.block_id();
+
+        let missing_tx_indexes = partial_block.missing_tx_indexes();
+        if !missing_tx_indexes.is_empty() {
+            log::debug!(
+                "Request {} missing transactions of block {block_id} from peer {}",
+                missing_tx_indexes.len(),
+                self.id()
+            );
+            self.messaging_handle.send_message(
+                self.id(),
+                SyncMessage::BlockTransactionsRequest(BlockTransactionsRequest::new(
+                    block_id,
+                    missing_tx_indexes,
+                )),
+            )?;
+            self.partial_block = Some(partial_block);
             self.last_activity = PeerActivity::ExpectingBlocks {
                 time: self.time_getter.get_time(),
             };
+            return Ok(());
         }
 
-        Ok(())
+        match partial_block.into_block() {
+            Ok(block) => self.handle_block_response(block).await,
+            Err(e) => {
+                log::debug!(
+                    "Failed to restore compact block {block_id} from peer {}: {e}",
+                    self.id()
+                );
+                self.messaging_handle.send_message(
+                    self.id(),
+                    SyncMessage::BlockListRequest(BlockListRequest::new(vec![block_id])),
+                )?;
+                self.last_activity = PeerActivity::ExpectingBlocks {
+                    time: self.time_getter.get_time(),
+                };
+                Ok(())
+            }
+        }
     }
 
     async fn handle_transaction_request(&mut self, id: Id<Transaction>) -> Result<()> {
@@ -622,44 +951,186 @@ where
         self.known_transactions.insert(&TxIdWrapper(txid), &mut make_pseudo_rng());
     }
 
-    // TODO: This can be optimized, see https://github.com/mintlayer/mintlayer-core/issues/829
-    // for details.
-    async fn handle_transaction_announcement(&mut self, tx: Id<Transaction>) -> Result<()> {
-        log::debug!("Transaction announcement from {} peer: {tx}", self.id());
+    /// Adds the transaction to the next inventory message for the peer.
+    ///
+    /// Transactions are never announced immediately: if there is no inventory message scheduled
+    /// already, the next one is scheduled after a random delay.
+    fn queue_tx_announcement(&mut self, txid: Id<Transaction>) {
+        if self.pending_tx_inventory.len() >= MAX_PENDING_TX_INVENTORY_SIZE {
+            log::debug!(
+                "Too many transactions are waiting to be announced to {} peer, skipping {txid}",
+                self.id()
+            );
+            return;
+        }
+        if self.pending_tx_inventory.is_empty() {
+            let now = self.time_getter.get_time();
+            if self.next_tx_inventory_time <= now {
+                self.schedule_tx_inventory(now);
+            }
+        }
+        self.pending_tx_inventory.insert(txid);
+    }
 
-        self.add_known_transaction(tx);
+    /// Picks the time of the next inventory message. The delays are exponentially distributed,
+    /// so the announcements to different peers happen at independent random moments.
+    fn schedule_tx_inventory(&mut self, now: Duration) {
+        let interval = if self.inbound {
+            INBOUND_TX_INVENTORY_INTERVAL
+        } else {
+            OUTBOUND_TX_INVENTORY_INTERVAL
+        };
+        self.next_tx_inventory_time =
+            now + interval.mul_f64(utils::exp_rand::exponential_rand(&mut make_pseudo_rng()));
+    }
+
+    /// Announces the pending transactions to the peer.
+    async fn send_tx_inventory(&mut self) -> Result<()> {
+        self.schedule_tx_inventory(self.time_getter.get_time());
+
+        let candidates = std::iter::from_fn(|| self.pending_tx_inventory.pop_first())
+            .take(MAX_TX_INVENTORY_SIZE)
+            .filter(|txid| !self.known_transactions.contains(&TxIdWrapper(*txid)))
+            .collect::<Vec<_>>();
+        if candidates.is_empty() {
+            return Ok(());
+        }
+
+        // Skip the transactions that were removed from the mempool in the meantime (for example,
+        // included in a block).
+        let transactions = self
+            .mempool_handle
+            .call(move |m| {
+                candidates
+                    .into_iter()
+                    .filter(|txid| m.contains_transaction(txid))
+                    .collect::<Vec<_>>()
+            })
+            .await?;
+        if transactions.is_empty() {
+            return Ok(());
+        }
+
+        log::debug!(
+            "Sending transaction inventory to {} peer, count: {}",
+            self.id(),
+            transactions.len()
+        );
+
+        for txid in &transactions {
+            self.add_known_transaction(*txid);
+        }
+
+        // Peers using older protocol versions only know the single transaction announcements
+        if self.protocol < NETWORK_PROTOCOL_V3 {
+            for txid in transactions {
+                self.messaging_handle
+                    .send_message(self.id(), SyncMessage::NewTransaction(txid))?;
+            }
+            return Ok(());
+        }
+
+        self.messaging_handle.send_message(
+            self.id(),
+            SyncMessage::TransactionInventory(TransactionInventory::new(transactions)),
+        )
+    }
+
+    async fn handle_new_transaction(&mut self, txid: Id<Transaction>) -> Result<()> {
+        log::debug!(
+            "New transaction announcement from {} peer: {txid}",
+            self.id()
+        );
+
+        utils::ensure!(
+            self.protocol < NETWORK_PROTOCOL_V3,
+            P2pError::ProtocolError(ProtocolError::UnexpectedMessage(
+                "A single transaction announcement is received instead of an inventory".to_owned()
+            ))
+        );
+
+        self.handle_tx_announcements(vec![txid]).await
+    }
+
+    async fn handle_transaction_inventory(
+        &mut self,
+        transactions: Vec<Id<Transaction>>,
+    ) -> Result<()> {
+        log::debug!(
+            "Transaction inventory from {} peer, count: {}",
+            self.id(),
+            transactions.len()
+        );
+
+        utils::ensure!(
+            self.protocol >= NETWORK_PROTOCOL_V3,
+            P2pError::ProtocolError(ProtocolError::UnexpectedMessage(
+                "A transaction inventory is received from a peer using an older protocol version"
+                    .to_owned()
+            ))
+        );
+
+        self.handle_tx_announcements(transactions).await
+    }
+
+    /// Requests the announced transactions that are missing in the mempool.
+    async fn handle_tx_announcements(&mut self, transactions: Vec<Id<Transaction>>) -> Result<()> {
+        if transactions.len() > MAX_TX_INVENTORY_SIZE {
+            return Err(P2pError::ProtocolError(
+                ProtocolError::TransactionInventoryLimitExceeded(
+                    transactions.len(),
+                    MAX_TX_INVENTORY_SIZE,
+                ),
+            ));
+        }
+
+        for tx in &transactions {
+            self.add_known_transaction(*tx);
+        }
 
         if self.is_initial_block_download.load() {
             log::debug!(
-                "Ignoring transaction announcement because the node is in initial block download"
+                "Ignoring transaction inventory because the node is in initial block download"
             );
             return Ok(());
         }
 
         if !self.common_services.has_service(Service::Transactions) {
             return Err(P2pError::ProtocolError(ProtocolError::UnexpectedMessage(
-                "A transaction announcement is received, but this node doesn't have the corresponding service".to_owned(),
+                "A transaction inventory is received, but this node doesn't have the corresponding service".to_owned(),
             )));
         }
 
-        if self.announced_transactions.len() >= *self.p2p_config.max_peer_tx_announcements {
+        if let Some(tx) = transactions.iter().find(|tx| self.announced_transactions.contains(tx)) {
             return Err(P2pError::ProtocolError(
-                ProtocolError::TransactionAnnouncementLimitExceeded(
-                    *self.p2p_config.max_peer_tx_announcements,
-                ),
+                ProtocolError::DuplicatedTransactionAnnouncement(*tx),
             ));
         }
 
-        if self.announced_transactions.contains(&tx) {
-            return Err(P2pError::ProtocolError(
-                ProtocolError::DuplicatedTransactionAnnouncement(tx),
-            ));
-        }
+        let unknown_transactions = self
+            .mempool_handle
+            .call(move |m| {
+                transactions
+                    .into_iter()
+                    .filter(|tx| !m.contains_transaction(tx))
+                    .collect::<Vec<_>>()
+            })
+            .await?;
+
+        for tx in unknown_transactions {
+            if self.announced_transactions.len() >= *self.p2p_config.max_peer_tx_announcements {
+                return Err(P2pError::ProtocolError(
+                    ProtocolError::TransactionAnnouncementLimitExceeded(
+                        *self.p2p_config.max_peer_tx_announcements,
+                    ),
+                ));
+            }
 
-        if !(self.mempool_handle.call(move |m| m.contains_transaction(&tx)).await?) {
-            self.messaging_handle
-                .send_message(self.id(), SyncMessage::TransactionRequest(tx))?;
-            assert!(self.announced_transactions.insert(tx));
+            // The same identifier can be repeated in the message, request it only once
+            if self.announced_transactions.insert(tx) {
+                self.messaging_handle
+                    .send_message(self.id(), SyncMessage::TransactionRequest(tx))?;
+            }
         }
 
         Ok(())
@@ -729,7 +1200,8 @@ where
             | P2pError::ConversionError(_)
             | P2pError::PeerError(_)
             | P2pError::NoiseHandshakeError(_)
-            | P2pError::InvalidConfigurationValue(_)) => panic!("Unexpected error {e:?}"),
+            | P2pError::InvalidConfigurationValue(_)
+            | P2pError::TorControlError(_)) => panic!("Unexpected error {e:?}"),
 
             // Fatal errors, simply propagate them to stop the sync manager.
             e @ (P2pError::ChannelClosed
@@ -739,24 +1211,101 @@ where
         }
     }
 
+    /// Requests the next blocks from the known headers or, if all of them have been downloaded,
+    /// more headers.
+    async fn request_next_blocks(&mut self) -> Result<()> {
+        let headers = mem::take(&mut self.known_headers);
+        let headers = self
+            .chainstate_handle
+            .call(move |c| -> Result<_> {
+                let mut unknown_headers = Vec::new();
+                for header in headers {
+                    if c.get_block_index(&header.get_id())?.is_none() {
+                        unknown_headers.push(header);
+                    }
+                }
+                Ok(unknown_headers)
+            })
+            .await??;
+
+        if headers.is_empty() {
+            self.request_headers().await
+        } else {
+            self.request_blocks(headers).await
+        }
+    }
+
     /// Sends a block list request.
     ///
-    /// The number of headers sent equals to `P2pConfig::requested_blocks_limit`, the remaining
-    /// headers are stored in the peer context.
-    fn request_blocks(&mut self, mut headers: Vec<SignedBlockHeader>) -> Result<()> {
+    /// The blocks that are being downloaded from other peers are skipped. The number of blocks
+    /// requested is limited by `P2pConfig::max_request_blocks_count` and by the download window
+    /// (`BLOCK_DOWNLOAD_WINDOW` blocks past the tip), the remaining headers are stored in the
+    /// peer context.
+    async fn request_blocks(&mut self, headers: Vec<SignedBlockHeader>) -> Result<()> {
         debug_assert!(self.known_headers.is_empty());
 
-        // Remove already requested blocks.
-        headers.retain(|h| !self.requested_blocks.contains(&h.get_id()));
-        if headers.is_empty() {
-            return Ok(());
+        let first_parent_id = headers.first().expect("Headers shouldn't be empty").prev_block_id();
+        let (first_parent_height, tip_height) = self
+            .chainstate_handle
+            .call(move |c| -> Result<_> {
+                let first_parent_height =
+                    c.get_gen_block_index(&first_parent_id)?.map(|index| index.block_height());
+                Ok((first_parent_height, c.get_best_block_height()?))
+            })
+            .await??;
+        // The first header is always connected to the block tree, so the parent should be known
+        let first_parent_height = first_parent_height.unwrap_or(tip_height);
+        let window_len = (tip_height.into_int() + BLOCK_DOWNLOAD_WINDOW)
+            .saturating_sub(first_parent_height.into_int()) as usize;
+
+        let peer_id = self.id();
+        let now = self.time_getter.get_time();
+        let mut block_ids = Vec::new();
+        {
+            let mut download_scheduler =
+                self.download_scheduler.lock().expect("Download scheduler mutex is poisoned");
+
+            // The window is full, check that it isn't stalled by another peer
+            if headers.len() > window_len {
+                let released = download_scheduler.release_blocking_peer(
+                    headers.iter().take(window_len).map(|header| header.get_id()),
+                    peer_id,
+                    now,
+                );
+                if released {
+                    log::debug!(
+                        "Blocks stalling the download window are released by peer {peer_id}"
+                    );
+                }
+            }
+
+            for (index, header) in headers.into_iter().enumerate() {
+                let block_id = header.get_id();
+                // Remove already requested blocks.
+                if self.requested_blocks.contains(&block_id) {
+                    continue;
+                }
+
+                if index < window_len
+                    && block_ids.len() < *self.p2p_config.max_request_blocks_count
+                    && download_scheduler.try_assign(block_id, peer_id, now)
+                {
+                    block_ids.push(block_id);
+                } else {
+                    self.known_headers.push(header);
+                }
+            }
         }
 
-        if headers.len() > *self.p2p_config.max_request_blocks_count {
-            self.known_headers = headers.split_off(*self.p2p_config.max_request_blocks_count);
+        if block_ids.is_empty() {
+            if !self.known_headers.is_empty() && self.requested_blocks.is_empty() {
+                // All the blocks are being downloaded from other peers or are outside the download
+                // window, wait for the download progress.
+                self.last_activity = PeerActivity::Pending;
+            }
+            return Ok(());
         }
 
-        let block_ids: Vec<_> = headers.into_iter().map(|h| h.get_id()).collect();
         log::debug!(
             "Request blocks from peer {}: {}-{} ({})",
             self.id(),
@@ -777,6 +1326,34 @@ where
         Ok(())
     }
 
+    /// Requests a new block in the compact form unless it is being downloaded from another peer.
+    fn request_compact_block(&mut self, header: SignedBlockHeader) -> Result<()> {
+        debug_assert!(self.requested_blocks.is_empty());
+
+        let block_id = header.get_id();
+        let is_assigned = self
+            .download_scheduler
+            .lock()
+            .expect("Download scheduler mutex is poisoned")
+            .try_assign(block_id, self.id(), self.time_getter.get_time());
+        if !is_assigned {
+            log::debug!("Block {block_id} is already being downloaded from another peer");
+            self.known_headers.push(header);
+            return Ok(());
+        }
+
+        log::debug!("Request compact block {block_id} from peer {}", self.id());
+        self.messaging_handle
+            .send_message(self.id(), SyncMessage::CompactBlockRequest(block_id))?;
+        self.requested_blocks.insert(block_id);
+
+        self.last_activity = PeerActivity::ExpectingBlocks {
+            time: self.time_getter.get_time(),
+        };
+
+        Ok(())
+    }
+
     async fn send_block(&mut self, id: Id<Block>) -> Result<()> {
         let (block, index) = self
             .chainstate_handle
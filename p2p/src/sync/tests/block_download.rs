@@ -0,0 +1,232 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{collections::BTreeSet, sync::Arc, time::Duration};
+
+use chainstate_test_framework::TestFramework;
+use common::{
+    chain::{config::create_unit_test_config, Block, GenBlock},
+    primitives::{Id, Idable},
+};
+use p2p_test_utils::create_n_blocks;
+use test_utils::random::Seed;
+
+use crate::{
+    message::{BlockListRequest, BlockResponse, HeaderList, SyncMessage},
+    sync::{download_scheduler::DownloadScheduler, tests::helpers::SyncManagerHandle},
+    testing_utils::test_p2p_config,
+    types::peer_id::PeerId,
+    P2pConfig,
+};
+
+/// Starts the sync manager with two peers, sends the same headers of 4 blocks from both of them
+/// and checks that the blocks are split between the peers.
+async fn start_with_two_peers(
+    rng: &mut impl crypto::random::Rng,
+) -> (SyncManagerHandle, PeerId, PeerId, Vec<Block>) {
+    let chain_config = Arc::new(create_unit_test_config());
+    let mut tf = TestFramework::builder(rng)
+        .with_chain_config(chain_config.as_ref().clone())
+        .build();
+    let blocks = create_n_blocks(&mut tf, 4);
+
+    let p2p_config = Arc::new(P2pConfig {
+        max_request_blocks_count: 2.into(),
+        ..test_p2p_config()
+    });
+    let mut handle = SyncManagerHandle::builder()
+        .with_chain_config(chain_config)
+        .with_p2p_config(p2p_config)
+        .with_chainstate(tf.into_chainstate())
+        .build()
+        .await;
+
+    let peer1 = PeerId::new();
+    let peer2 = PeerId::new();
+    handle.connect_peer(peer1).await;
+    handle.connect_peer(peer2).await;
+
+    let headers: Vec<_> = blocks.iter().map(|b| b.header().clone()).collect();
+    handle
+        .send_message(
+            peer1,
+            SyncMessage::HeaderList(HeaderList::new(headers.clone())),
+        )
+        .await;
+    assert_eq!(
+        handle.message().await,
+        (
+            peer1,
+            SyncMessage::BlockListRequest(BlockListRequest::new(vec![
+                blocks[0].get_id(),
+                blocks[1].get_id()
+            ]))
+        )
+    );
+
+    handle
+        .send_message(peer2, SyncMessage::HeaderList(HeaderList::new(headers)))
+        .await;
+    assert_eq!(
+        handle.message().await,
+        (
+            peer2,
+            SyncMessage::BlockListRequest(BlockListRequest::new(vec![
+                blocks[2].get_id(),
+                blocks[3].get_id()
+            ]))
+        )
+    );
+
+    (handle, peer1, peer2, blocks)
+}
+
+// The blocks from the second peer are received before their parents and processed after them.
+#[rstest::rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn out_of_order_blocks(#[case] seed: Seed) {
+    let mut rng = test_utils::random::make_seedable_rng(seed);
+    let (mut handle, peer1, peer2, blocks) = start_with_two_peers(&mut rng).await;
+
+    for block in &blocks[2..] {
+        handle
+            .send_message(
+                peer2,
+                SyncMessage::BlockResponse(BlockResponse::new(block.clone())),
+            )
+            .await;
+    }
+    handle.assert_no_event().await;
+
+    for block in &blocks[..2] {
+        handle
+            .send_message(
+                peer1,
+                SyncMessage::BlockResponse(BlockResponse::new(block.clone())),
+            )
+            .await;
+    }
+
+    // Both peers request more headers once all the blocks are processed.
+    let mut peers = BTreeSet::new();
+    for _ in 0..2 {
+        let (peer, message) = handle.message().await;
+        assert!(matches!(message, SyncMessage::HeaderListRequest(_)));
+        peers.insert(peer);
+    }
+    assert_eq!(peers, BTreeSet::from([peer1, peer2]));
+
+    let best_block_id = handle.chainstate().call(|c| c.get_best_block_id()).await.unwrap().unwrap();
+    assert_eq!(best_block_id, Id::<GenBlock>::from(blocks[3].get_id()));
+
+    handle.assert_no_error().await;
+    handle.assert_no_peer_manager_event().await;
+
+    handle.join_subsystem_manager().await;
+}
+
+// The blocks requested from a disconnected peer are requested from another one.
+#[rstest::rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn reassign_blocks_of_disconnected_peer(#[case] seed: Seed) {
+    let mut rng = test_utils::random::make_seedable_rng(seed);
+    let (mut handle, peer1, peer2, blocks) = start_with_two_peers(&mut rng).await;
+
+    handle.disconnect_peer(peer1);
+    for block in &blocks[2..] {
+        handle
+            .send_message(
+                peer2,
+                SyncMessage::BlockResponse(BlockResponse::new(block.clone())),
+            )
+            .await;
+    }
+    assert_eq!(
+        handle.message().await,
+        (
+            peer2,
+            SyncMessage::BlockListRequest(BlockListRequest::new(vec![
+                blocks[0].get_id(),
+                blocks[1].get_id()
+            ]))
+        )
+    );
+
+    for block in &blocks[..2] {
+        handle
+            .send_message(
+                peer2,
+                SyncMessage::BlockResponse(BlockResponse::new(block.clone())),
+            )
+            .await;
+    }
+    let (peer, message) = handle.message().await;
+    assert_eq!(peer, peer2);
+    assert!(matches!(message, SyncMessage::HeaderListRequest(_)));
+
+    let best_block_id = handle.chainstate().call(|c| c.get_best_block_id()).await.unwrap().unwrap();
+    assert_eq!(best_block_id, Id::<GenBlock>::from(blocks[3].get_id()));
+
+    handle.assert_no_error().await;
+
+    handle.join_subsystem_manager().await;
+}
+
+// The pending blocks of a released peer and the pending descendants of a rejected block are
+// dropped, and the peer that stalls the download window loses its blocks after a timeout.
+#[rstest::rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn download_scheduler_cleanup(#[case] seed: Seed) {
+    let mut rng = test_utils::random::make_seedable_rng(seed);
+    let mut tf = TestFramework::builder(&mut rng).build();
+    let blocks = create_n_blocks(&mut tf, 4);
+    let peer1 = PeerId::new();
+    let peer2 = PeerId::new();
+    let now = Duration::from_secs(1000);
+
+    let mut scheduler = DownloadScheduler::new();
+    assert!(scheduler.try_assign(blocks[0].get_id(), peer1, now));
+    for block in &blocks[1..] {
+        assert!(scheduler.try_assign(block.get_id(), peer2, now));
+        scheduler.block_received(&block.get_id(), peer2, now);
+        scheduler.add_pending_block(peer2, block.clone());
+    }
+    assert!(blocks[1..].iter().all(|block| scheduler.is_pending(&block.get_id())));
+
+    assert!(scheduler.release_peer(peer2));
+    assert!(blocks[1..].iter().all(|block| !scheduler.is_pending(&block.get_id())));
+
+    for block in &blocks[1..] {
+        scheduler.add_pending_block(peer2, block.clone());
+    }
+    assert_eq!(
+        scheduler.drop_pending_descendants(&blocks[0].get_id().into()),
+        3
+    );
+    assert!(blocks[1..].iter().all(|block| !scheduler.is_pending(&block.get_id())));
+
+    let window = || blocks.iter().map(|block| block.get_id());
+    assert!(!scheduler.release_blocking_peer(window(), peer2, now));
+    assert!(!scheduler.try_assign(blocks[0].get_id(), peer2, now));
+    let later = now + Duration::from_secs(2);
+    assert!(!scheduler.release_blocking_peer(window(), peer1, later));
+    assert!(scheduler.release_blocking_peer(window(), peer2, later));
+    assert!(scheduler.try_assign(blocks[0].get_id(), peer2, later));
+}
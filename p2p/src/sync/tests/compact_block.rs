@@ -0,0 +1,322 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use chainstate::{ban_score::BanScore, BlockSource};
+use chainstate_test_framework::TestFramework;
+use common::{
+    chain::{
+        block::{timestamp::BlockTimestamp, BlockReward, ConsensusData},
+        config::create_unit_test_config,
+        signature::inputsig::InputWitness,
+        Block, SignedTransaction, Transaction, TxInput,
+    },
+    primitives::{Id, Idable},
+};
+use crypto::random::Rng;
+use test_utils::random::Seed;
+
+use crate::{
+    error::ProtocolError,
+    message::{
+        BlockListRequest, BlockTransactions, BlockTransactionsRequest, CompactBlock, HeaderList,
+        PrefilledTransaction, SyncMessage,
+    },
+    protocol::{NetworkProtocol, NETWORK_PROTOCOL_V1, NETWORK_PROTOCOL_V2},
+    sync::{
+        compact_block::PartialBlock,
+        tests::helpers::{get_random_hash, SyncManagerHandle},
+    },
+    types::peer_id::PeerId,
+    P2pError,
+};
+
+fn random_transaction(rng: &mut impl Rng) -> SignedTransaction {
+    let tx = Transaction::new(
+        0x00,
+        vec![TxInput::from_utxo(
+            Id::<Transaction>::new(get_random_hash(rng)).into(),
+            rng.gen(),
+        )],
+        vec![],
+    )
+    .unwrap();
+    SignedTransaction::new(tx, vec![InputWitness::NoSignature(None)]).unwrap()
+}
+
+fn random_block(rng: &mut impl Rng) -> Block {
+    let tx_count = rng.gen_range(1..20);
+    let transactions = (0..tx_count).map(|_| random_transaction(rng)).collect();
+    Block::new(
+        transactions,
+        Id::new(get_random_hash(rng)),
+        BlockTimestamp::from_int_seconds(rng.gen()),
+        ConsensusData::None,
+        BlockReward::new(Vec::new()),
+    )
+    .unwrap()
+}
+
+// Restore a block from a compact block, the mempool and the transactions requested from the peer.
+#[rstest::rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn restore_block(#[case] seed: Seed) {
+    let mut rng = test_utils::random::make_seedable_rng(seed);
+
+    let block = random_block(&mut rng);
+    let compact_block = CompactBlock::from_block(&block, |_| rng.gen_bool(0.3));
+    let mut partial_block = PartialBlock::new(compact_block.clone()).unwrap();
+    assert_eq!(partial_block.block_id(), &block.get_id());
+
+    let prefilled_indexes: Vec<_> =
+        compact_block.prefilled_transactions().iter().map(|tx| tx.index()).collect();
+    let (in_mempool, not_in_mempool): (Vec<_>, Vec<_>) = (0..block.transactions().len() as u32)
+        .filter(|index| !prefilled_indexes.contains(index))
+        .partition(|_| rng.gen_bool(0.5));
+
+    let mempool = in_mempool
+        .iter()
+        .map(|index| block.transactions()[*index as usize].clone())
+        .chain((0..rng.gen_range(0..10)).map(|_| random_transaction(&mut rng)));
+    partial_block.fill_from(mempool);
+    assert_eq!(partial_block.missing_tx_indexes(), not_in_mempool);
+
+    let missing = not_in_mempool
+        .iter()
+        .map(|index| block.transactions()[*index as usize].clone())
+        .collect();
+    partial_block.fill_missing(missing).unwrap();
+    assert_eq!(partial_block.into_block().unwrap(), block);
+}
+
+// Wrong transactions are detected by the merkle root check.
+#[rstest::rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn restore_block_wrong_transactions(#[case] seed: Seed) {
+    let mut rng = test_utils::random::make_seedable_rng(seed);
+
+    let block = random_block(&mut rng);
+    let tx_count = block.transactions().len();
+    let mut partial_block = PartialBlock::new(CompactBlock::from_block(&block, |_| false)).unwrap();
+    assert_eq!(
+        partial_block.missing_tx_indexes(),
+        (0..tx_count as u32).collect::<Vec<_>>()
+    );
+
+    assert_eq!(
+        partial_block.fill_missing(Vec::new()),
+        Err(ProtocolError::UnexpectedBlockTransactionsCount(
+            block.get_id(),
+            tx_count,
+            0
+        ))
+    );
+
+    let wrong_transactions = (0..tx_count).map(|_| random_transaction(&mut rng)).collect();
+    partial_block.fill_missing(wrong_transactions).unwrap();
+    assert!(partial_block.into_block().is_err());
+}
+
+#[rstest::rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn invalid_prefilled_positions(#[case] seed: Seed) {
+    let mut rng = test_utils::random::make_seedable_rng(seed);
+
+    let block = random_block(&mut rng);
+    let compact_block = CompactBlock::new(
+        block.header().clone(),
+        block.block_reward().clone(),
+        Vec::new(),
+        vec![PrefilledTransaction::new(1, random_transaction(&mut rng))],
+    );
+    assert_eq!(
+        PartialBlock::new(compact_block).err(),
+        Some(ProtocolError::InvalidCompactBlock(block.get_id()))
+    );
+}
+
+// A peer requests a compact block and then its transactions.
+#[rstest::rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn compact_block_request(#[case] seed: Seed) {
+    let mut rng = test_utils::random::make_seedable_rng(seed);
+
+    let chain_config = Arc::new(create_unit_test_config());
+    let mut tf = TestFramework::builder(&mut rng)
+        .with_chain_config(chain_config.as_ref().clone())
+        .build();
+    // Process a block to finish the initial block download.
+    let block = tf.make_block_builder().add_test_transaction_from_best_block(&mut rng).build();
+    tf.process_block(block.clone(), BlockSource::Local).unwrap().unwrap();
+
+    let mut handle = SyncManagerHandle::builder()
+        .with_chain_config(chain_config)
+        .with_chainstate(tf.into_chainstate())
+        .build()
+        .await;
+
+    let peer = PeerId::new();
+    handle.connect_peer(peer).await;
+
+    // The peer doesn't know about the transaction, so it is prefilled.
+    handle
+        .send_message(peer, SyncMessage::CompactBlockRequest(block.get_id()))
+        .await;
+    let (sent_to, message) = handle.message().await;
+    assert_eq!(peer, sent_to);
+    assert_eq!(
+        message,
+        SyncMessage::CompactBlock(CompactBlock::from_block(&block, |_| true))
+    );
+
+    handle
+        .send_message(
+            peer,
+            SyncMessage::BlockTransactionsRequest(BlockTransactionsRequest::new(
+                block.get_id(),
+                vec![0],
+            )),
+        )
+        .await;
+    let (sent_to, message) = handle.message().await;
+    assert_eq!(peer, sent_to);
+    assert_eq!(
+        message,
+        SyncMessage::BlockTransactions(BlockTransactions::new(
+            block.get_id(),
+            block.transactions().to_vec()
+        ))
+    );
+    handle.assert_no_error().await;
+    handle.assert_no_peer_manager_event().await;
+
+    // Request a transaction that doesn't exist.
+    let index = block.transactions().len() as u32;
+    handle
+        .send_message(
+            peer,
+            SyncMessage::BlockTransactionsRequest(BlockTransactionsRequest::new(
+                block.get_id(),
+                vec![index],
+            )),
+        )
+        .await;
+    let (adjusted_peer, score) = handle.adjust_peer_score_event().await;
+    assert_eq!(peer, adjusted_peer);
+    assert_eq!(
+        score,
+        P2pError::ProtocolError(ProtocolError::BlockTransactionIndexOutOfRange(
+            block.get_id(),
+            index
+        ))
+        .ban_score()
+    );
+    handle.assert_no_event().await;
+
+    handle.join_subsystem_manager().await;
+}
+
+// A new block is requested in the compact form only from the peers that support it.
+#[rstest::rstest]
+#[trace]
+#[case(Seed::from_entropy(), NETWORK_PROTOCOL_V1)]
+#[case(Seed::from_entropy(), NETWORK_PROTOCOL_V2)]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn new_block_request(#[case] seed: Seed, #[case] protocol: NetworkProtocol) {
+    let mut rng = test_utils::random::make_seedable_rng(seed);
+
+    let chain_config = Arc::new(create_unit_test_config());
+    let mut tf = TestFramework::builder(&mut rng)
+        .with_chain_config(chain_config.as_ref().clone())
+        .build();
+    // Process a block to finish the initial block download.
+    let block = tf.make_block_builder().add_test_transaction_from_best_block(&mut rng).build();
+    tf.process_block(block, BlockSource::Local).unwrap().unwrap();
+    let new_block = tf.make_block_builder().build();
+
+    let mut handle = SyncManagerHandle::builder()
+        .with_chain_config(chain_config)
+        .with_chainstate(tf.into_chainstate())
+        .build()
+        .await;
+
+    let peer = PeerId::new();
+    handle.connect_peer_with_protocol(peer, protocol).await;
+
+    handle
+        .send_message(
+            peer,
+            SyncMessage::HeaderList(HeaderList::new(vec![new_block.header().clone()])),
+        )
+        .await;
+    let (sent_to, message) = handle.message().await;
+    assert_eq!(peer, sent_to);
+    let expected = if protocol >= NETWORK_PROTOCOL_V2 {
+        SyncMessage::CompactBlockRequest(new_block.get_id())
+    } else {
+        SyncMessage::BlockListRequest(BlockListRequest::new(vec![new_block.get_id()]))
+    };
+    assert_eq!(message, expected);
+    handle.assert_no_error().await;
+
+    handle.join_subsystem_manager().await;
+}
+
+// A peer using the old protocol version can't request compact blocks.
+#[rstest::rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn compact_block_request_old_protocol(#[case] seed: Seed) {
+    let mut rng = test_utils::random::make_seedable_rng(seed);
+
+    let chain_config = Arc::new(create_unit_test_config());
+    let mut tf = TestFramework::builder(&mut rng)
+        .with_chain_config(chain_config.as_ref().clone())
+        .build();
+    let block = tf.make_block_builder().add_test_transaction_from_best_block(&mut rng).build();
+    tf.process_block(block.clone(), BlockSource::Local).unwrap().unwrap();
+
+    let mut handle = SyncManagerHandle::builder()
+        .with_chain_config(chain_config)
+        .with_chainstate(tf.into_chainstate())
+        .build()
+        .await;
+
+    let peer = PeerId::new();
+    handle.connect_peer_with_protocol(peer, NETWORK_PROTOCOL_V1).await;
+
+    handle
+        .send_message(peer, SyncMessage::CompactBlockRequest(block.get_id()))
+        .await;
+    let (adjusted_peer, score) = handle.adjust_peer_score_event().await;
+    assert_eq!(peer, adjusted_peer);
+    assert_eq!(
+        score,
+        P2pError::ProtocolError(ProtocolError::UnexpectedMessage(
+            "compact block request".to_owned()
+        ))
+        .ban_score()
+    );
+    handle.assert_no_event().await;
+
+    handle.join_subsystem_manager().await;
+}
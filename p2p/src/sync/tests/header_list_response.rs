@@ -203,6 +203,7 @@ async fn disconnect() {
     let p2p_config = Arc::new(P2pConfig {
         bind_addresses: Default::default(),
         socks5_proxy: Default::default(),
+        tor_control_address: Default::default(),
         disable_noise: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: Default::default(),
@@ -56,12 +56,16 @@ use utils::atomics::SeqCstAtomicBool;
 use crate::{
     config::NodeType,
     message::{SyncMessage, TransactionResponse},
-    net::{default_backend::transport::TcpTransportSocket, types::SyncingEvent},
+    net::{
+        default_backend::transport::TcpTransportSocket,
+        types::{services::Services, SyncingEvent},
+    },
+    protocol::{NetworkProtocol, NETWORK_PROTOCOL_CURRENT},
     sync::{subscribe_to_new_tip, BlockSyncManager},
     testing_utils::test_p2p_config,
     types::peer_id::PeerId,
-    MessagingService, NetworkingService, P2pConfig, P2pError, P2pEventHandler, PeerManagerEvent,
-    Result, SyncingEventReceiver,
+    MessagingService, NetworkingService, P2pConfig, P2pError, P2pEventSubscription,
+    PeerManagerEvent, Result, SyncingEventReceiver,
 };
 
 /// A timeout for blocking calls.
@@ -85,6 +89,7 @@ pub struct SyncManagerHandle {
     mempool_handle: MempoolHandle,
     _new_tip_receiver: UnboundedReceiver<Id<Block>>,
     connected_peers: BTreeMap<PeerId, Sender<SyncMessage>>,
+    local_services: Services,
 }
 
 impl SyncManagerHandle {
@@ -109,6 +114,7 @@ impl SyncManagerHandle {
     ) -> Self {
         let (peer_manager_sender, peer_manager_receiver) = mpsc::unbounded_channel();
         let connected_peers = Default::default();
+        let local_services = (*p2p_config.node_type).into();
 
         let (messaging_sender, handle_receiver) = mpsc::unbounded_channel();
         let (handle_sender, messaging_receiver) = mpsc::unbounded_channel();
@@ -151,6 +157,7 @@ impl SyncManagerHandle {
             mempool_handle,
             _new_tip_receiver: new_tip_receiver,
             connected_peers,
+            local_services,
         }
     }
 
@@ -164,11 +171,19 @@ impl SyncManagerHandle {
 
     /// Sends the `SyncControlEvent::Connected` event without checking outgoing messages.
     pub fn try_connect_peer(&mut self, peer: PeerId) {
+        self.try_connect_peer_with_protocol(peer, NETWORK_PROTOCOL_CURRENT);
+    }
+
+    /// Same as `try_connect_peer`, but with the specified negotiated protocol version.
+    pub fn try_connect_peer_with_protocol(&mut self, peer: PeerId, protocol: NetworkProtocol) {
         let (sync_tx, sync_rx) = mpsc::channel(20);
         self.sync_event_sender
             .send(SyncingEvent::Connected {
                 peer_id: peer,
                 services: NodeType::Full.into(),
+                local_services: self.local_services,
+                inbound: false,
+                protocol,
                 sync_rx,
             })
             .unwrap();
@@ -177,7 +192,12 @@ impl SyncManagerHandle {
 
     /// Connects a peer and checks that the header list request is sent to that peer.
     pub async fn connect_peer(&mut self, peer: PeerId) {
-        self.try_connect_peer(peer);
+        self.connect_peer_with_protocol(peer, NETWORK_PROTOCOL_CURRENT).await;
+    }
+
+    /// Same as `connect_peer`, but with the specified negotiated protocol version.
+    pub async fn connect_peer_with_protocol(&mut self, peer: PeerId, protocol: NetworkProtocol) {
+        self.try_connect_peer_with_protocol(peer, protocol);
 
         let (sent_to, message) = self.message().await;
         assert_eq!(peer, sent_to);
@@ -220,9 +240,19 @@ impl SyncManagerHandle {
         time::timeout(SHORT_TIMEOUT, self.error_receiver.recv()).await.unwrap_err();
     }
 
+    /// Receives the next peer manager event, skipping `NewTipReceived` notifications.
+    async fn peer_manager_event(&mut self) -> PeerManagerEvent<NetworkingServiceStub> {
+        loop {
+            match self.peer_manager_receiver.recv().await.unwrap() {
+                PeerManagerEvent::NewTipReceived(_, _) => {}
+                event => return event,
+            }
+        }
+    }
+
     /// Receives the `AdjustPeerScore` event from the peer manager.
     pub async fn adjust_peer_score_event(&mut self) -> (PeerId, u32) {
-        match self.peer_manager_receiver.recv().await.unwrap() {
+        match self.peer_manager_event().await {
             PeerManagerEvent::AdjustPeerScore(peer, score, sender) => {
                 sender.send(Ok(()));
                 (peer, score)
@@ -232,7 +262,7 @@ impl SyncManagerHandle {
     }
 
     pub async fn assert_disconnect_peer_event(&mut self, id: PeerId) {
-        match self.peer_manager_receiver.recv().await.unwrap() {
+        match self.peer_manager_event().await {
             PeerManagerEvent::Disconnect(peer_id, sender) => {
                 assert_eq!(id, peer_id);
                 sender.send(Ok(()));
@@ -256,11 +286,9 @@ impl SyncManagerHandle {
         .unwrap_err();
     }
 
-    /// Panics if there is an event from the peer manager.
+    /// Panics if there is an event from the peer manager (except `NewTipReceived`).
     pub async fn assert_no_peer_manager_event(&mut self) {
-        time::timeout(SHORT_TIMEOUT, self.peer_manager_receiver.recv())
-            .await
-            .unwrap_err();
+        time::timeout(SHORT_TIMEOUT, self.peer_manager_event()).await.unwrap_err();
     }
 
     /// Panics if the sync manager sends an event (message or announcement).
@@ -320,9 +348,12 @@ pub async fn try_sync_managers_once(
             .send_message(tx_peer_id, SyncMessage::TransactionRequest(requested_txid))
             .await;
 
-        if let Ok(peer_event) = manager.peer_manager_receiver.try_recv() {
+        while let Ok(peer_event) = manager.peer_manager_receiver.try_recv() {
             // There should be no peer scoring or disconnections
-            panic!("Unexpected message: {peer_event:?}");
+            assert!(
+                matches!(peer_event, PeerManagerEvent::NewTipReceived(_, _)),
+                "Unexpected message: {peer_event:?}"
+            );
         }
 
         for _ in 0..message_limit {
@@ -436,8 +467,10 @@ impl SyncManagerHandleBuilder {
 
         let mempool = mempool::make_mempool(
             Arc::clone(&chain_config),
+            Default::default(),
             chainstate.clone(),
             time_getter.clone(),
+            None,
         );
         let mempool = manager.add_subsystem_with_custom_eventloop("p2p-sync-test-mempool", {
             move |call, shutdn| mempool.run(call, shutdn)
@@ -482,7 +515,7 @@ impl NetworkingService for NetworkingServiceStub {
         _: TimeGetter,
         _: Arc<SeqCstAtomicBool>,
         _: oneshot::Receiver<()>,
-        _: mpsc::UnboundedReceiver<P2pEventHandler>,
+        _: mpsc::UnboundedReceiver<P2pEventSubscription>,
     ) -> Result<(
         Self::ConnectivityHandle,
         Self::MessagingHandle,
@@ -15,8 +15,10 @@
 
 mod ban_scores;
 mod block_announcement;
+mod block_download;
 mod block_list_request;
 mod block_response;
+mod compact_block;
 mod header_list_request;
 mod header_list_response;
 mod helpers;
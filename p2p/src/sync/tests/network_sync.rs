@@ -46,6 +46,7 @@ async fn basic(#[case] seed: Seed) {
 
         bind_addresses: Default::default(),
         socks5_proxy: Default::default(),
+        tor_control_address: Default::default(),
         disable_noise: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: Default::default(),
@@ -142,6 +143,7 @@ async fn initial_download_unexpected_disconnect(#[case] seed: Seed) {
     let p2p_config = Arc::new(P2pConfig {
         bind_addresses: Default::default(),
         socks5_proxy: Default::default(),
+        tor_control_address: Default::default(),
         disable_noise: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: Default::default(),
@@ -222,6 +224,7 @@ async fn reorg(#[case] seed: Seed) {
     let p2p_config = Arc::new(P2pConfig {
         bind_addresses: Default::default(),
         socks5_proxy: Default::default(),
+        tor_control_address: Default::default(),
         disable_noise: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: Default::default(),
@@ -324,6 +327,7 @@ async fn block_production(#[case] seed: Seed) {
 
         bind_addresses: Default::default(),
         socks5_proxy: Default::default(),
+        tor_control_address: Default::default(),
         disable_noise: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: Default::default(),
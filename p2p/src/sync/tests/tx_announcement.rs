@@ -13,7 +13,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use chainstate::ban_score::BanScore;
 use chainstate_test_framework::TestFramework;
@@ -28,13 +28,15 @@ use mempool::{
     error::{Error as MempoolError, MempoolPolicyError},
     TxOrigin,
 };
+use p2p_test_utils::P2pBasicTestTimeGetter;
 use test_utils::random::Seed;
 
 use crate::{
     config::NodeType,
     error::ProtocolError,
-    message::{SyncMessage, TransactionResponse},
-    sync::tests::helpers::SyncManagerHandle,
+    message::{HeaderList, SyncMessage, TransactionInventory, TransactionResponse},
+    protocol::{NetworkProtocol, NETWORK_PROTOCOL_V2, NETWORK_PROTOCOL_V3},
+    sync::{peer::MAX_TX_INVENTORY_SIZE, tests::helpers::SyncManagerHandle},
     testing_utils::test_p2p_config,
     types::peer_id::PeerId,
     P2pConfig, P2pError,
@@ -68,7 +70,12 @@ async fn invalid_transaction(#[case] seed: Seed) {
     let tx = Transaction::new(0x00, vec![], vec![]).unwrap();
     let tx = SignedTransaction::new(tx, vec![]).unwrap();
     handle
-        .send_message(peer, SyncMessage::NewTransaction(tx.transaction().get_id()))
+        .send_message(
+            peer,
+            SyncMessage::TransactionInventory(TransactionInventory::new(vec![tx
+                .transaction()
+                .get_id()])),
+        )
         .await;
 
     let (sent_to, message) = handle.message().await;
@@ -111,7 +118,12 @@ async fn initial_block_download() {
 
     let tx = transaction(chain_config.genesis_block_id());
     handle
-        .send_message(peer, SyncMessage::NewTransaction(tx.transaction().get_id()))
+        .send_message(
+            peer,
+            SyncMessage::TransactionInventory(TransactionInventory::new(vec![tx
+                .transaction()
+                .get_id()])),
+        )
         .await;
 
     handle.assert_no_event().await;
@@ -138,6 +150,7 @@ async fn no_transaction_service(#[case] seed: Seed) {
     let p2p_config = Arc::new(P2pConfig {
         bind_addresses: Default::default(),
         socks5_proxy: Default::default(),
+        tor_control_address: Default::default(),
         disable_noise: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: Default::default(),
@@ -171,7 +184,12 @@ async fn no_transaction_service(#[case] seed: Seed) {
 
     let tx = transaction(chain_config.genesis_block_id());
     handle
-        .send_message(peer, SyncMessage::NewTransaction(tx.transaction().get_id()))
+        .send_message(
+            peer,
+            SyncMessage::TransactionInventory(TransactionInventory::new(vec![tx
+                .transaction()
+                .get_id()])),
+        )
         .await;
 
     let (adjusted_peer, score) = handle.adjust_peer_score_event().await;
@@ -202,6 +220,7 @@ async fn too_many_announcements(#[case] seed: Seed) {
     let p2p_config = Arc::new(P2pConfig {
         bind_addresses: Default::default(),
         socks5_proxy: Default::default(),
+        tor_control_address: Default::default(),
         disable_noise: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: Default::default(),
@@ -235,7 +254,12 @@ async fn too_many_announcements(#[case] seed: Seed) {
 
     let tx = transaction(chain_config.genesis_block_id());
     handle
-        .send_message(peer, SyncMessage::NewTransaction(tx.transaction().get_id()))
+        .send_message(
+            peer,
+            SyncMessage::TransactionInventory(TransactionInventory::new(vec![tx
+                .transaction()
+                .get_id()])),
+        )
         .await;
 
     let (adjusted_peer, score) = handle.adjust_peer_score_event().await;
@@ -276,7 +300,12 @@ async fn duplicated_announcement(#[case] seed: Seed) {
 
     let tx = transaction(chain_config.genesis_block_id());
     handle
-        .send_message(peer, SyncMessage::NewTransaction(tx.transaction().get_id()))
+        .send_message(
+            peer,
+            SyncMessage::TransactionInventory(TransactionInventory::new(vec![tx
+                .transaction()
+                .get_id()])),
+        )
         .await;
 
     let (sent_to, message) = handle.message().await;
@@ -287,7 +316,12 @@ async fn duplicated_announcement(#[case] seed: Seed) {
     );
 
     handle
-        .send_message(peer, SyncMessage::NewTransaction(tx.transaction().get_id()))
+        .send_message(
+            peer,
+            SyncMessage::TransactionInventory(TransactionInventory::new(vec![tx
+                .transaction()
+                .get_id()])),
+        )
         .await;
 
     let (adjusted_peer, score) = handle.adjust_peer_score_event().await;
@@ -331,7 +365,12 @@ async fn valid_transaction(#[case] seed: Seed) {
 
     let tx = transaction(chain_config.genesis_block_id());
     handle
-        .send_message(peer, SyncMessage::NewTransaction(tx.transaction().get_id()))
+        .send_message(
+            peer,
+            SyncMessage::TransactionInventory(TransactionInventory::new(vec![tx
+                .transaction()
+                .get_id()])),
+        )
         .await;
 
     let (sent_to, message) = handle.message().await;
@@ -348,7 +387,7 @@ async fn valid_transaction(#[case] seed: Seed) {
         )
         .await;
 
-    // There should be no `NewTransaction` message because the transaction is already known
+    // There should be no `TransactionInventory` message because the transaction is already known
     handle.assert_no_event().await;
 
     handle.join_subsystem_manager().await;
@@ -427,16 +466,19 @@ async fn transaction_sequence_via_orphan_pool(#[case] seed: Seed) {
         logging::log::error!("Tx: {tid:?}");
     }
 
-    // Now the orphan has been resolved, both transactions should be announced.
-    for _ in 0..2 {
+    // Now the orphan has been resolved, both transactions should be announced (in one or two
+    // inventory messages).
+    while !txs.is_empty() {
         let (_peer, msg) = handle.message().await;
         logging::log::error!("Msg new: {msg:?}");
-        let tx_id = match msg {
-            SyncMessage::NewTransaction(tx_id) => tx_id,
+        let tx_ids = match msg {
+            SyncMessage::TransactionInventory(inventory) => inventory.into_transactions(),
             msg => panic!("Unexpected message {msg:?}"),
         };
 
-        let _expected_tx = txs.remove(&tx_id).expect("An existing transaction");
+        for tx_id in tx_ids {
+            let _expected_tx = txs.remove(&tx_id).expect("An existing transaction");
+        }
     }
 
     // A small sanity check that we have sent all transactions
@@ -445,6 +487,220 @@ async fn transaction_sequence_via_orphan_pool(#[case] seed: Seed) {
     handle.join_subsystem_manager().await;
 }
 
+#[rstest::rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn inventory_limit_exceeded(#[case] seed: Seed) {
+    let mut rng = test_utils::random::make_seedable_rng(seed);
+
+    let chain_config = Arc::new(create_unit_test_config());
+    let mut tf = TestFramework::builder(&mut rng)
+        .with_chain_config(chain_config.as_ref().clone())
+        .build();
+    // Process a block to finish the initial block download.
+    tf.make_block_builder().build_and_process().unwrap().unwrap();
+
+    let p2p_config = Arc::new(test_p2p_config());
+    let mut handle = SyncManagerHandle::builder()
+        .with_chain_config(Arc::clone(&chain_config))
+        .with_p2p_config(Arc::clone(&p2p_config))
+        .with_chainstate(tf.into_chainstate())
+        .build()
+        .await;
+
+    let peer = PeerId::new();
+    handle.connect_peer(peer).await;
+
+    let tx = transaction(chain_config.genesis_block_id());
+    handle
+        .send_message(
+            peer,
+            SyncMessage::TransactionInventory(TransactionInventory::new(vec![
+                tx.transaction()
+                    .get_id();
+                MAX_TX_INVENTORY_SIZE
+                    + 1
+            ])),
+        )
+        .await;
+
+    let (adjusted_peer, score) = handle.adjust_peer_score_event().await;
+    assert_eq!(peer, adjusted_peer);
+    assert_eq!(
+        score,
+        P2pError::ProtocolError(ProtocolError::TransactionInventoryLimitExceeded(
+            MAX_TX_INVENTORY_SIZE + 1,
+            MAX_TX_INVENTORY_SIZE
+        ))
+        .ban_score()
+    );
+    handle.assert_no_event().await;
+
+    handle.join_subsystem_manager().await;
+}
+
+// New transactions are not announced immediately, but after a random delay.
+#[rstest::rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn delayed_announcement(#[case] seed: Seed) {
+    let mut rng = test_utils::random::make_seedable_rng(seed);
+
+    let chain_config = Arc::new(create_unit_test_config());
+    let mut tf = TestFramework::builder(&mut rng)
+        .with_chain_config(chain_config.as_ref().clone())
+        .build();
+    // Process a block to finish the initial block download.
+    tf.make_block_builder().build_and_process().unwrap().unwrap();
+
+    let time_getter = P2pBasicTestTimeGetter::new();
+    let p2p_config = Arc::new(test_p2p_config());
+    let mut handle = SyncManagerHandle::builder()
+        .with_chain_config(Arc::clone(&chain_config))
+        .with_p2p_config(Arc::clone(&p2p_config))
+        .with_chainstate(tf.into_chainstate())
+        .with_time_getter(time_getter.get_time_getter())
+        .build()
+        .await;
+
+    let peer = PeerId::new();
+    handle.connect_peer(peer).await;
+    // Respond to the header request, so the peer isn't considered stalling when the time moves.
+    handle
+        .send_message(peer, SyncMessage::HeaderList(HeaderList::new(Vec::new())))
+        .await;
+
+    let tx = transaction(chain_config.genesis_block_id());
+    let tx_id = tx.transaction().get_id();
+    let res = handle
+        .mempool()
+        .call_mut(|m| m.add_transaction(tx, TxOrigin::LocalP2p))
+        .await
+        .unwrap();
+    assert_eq!(res, Ok(mempool::TxStatus::InMempool));
+
+    // The time doesn't move, so the transaction must not be announced yet
+    handle.assert_no_event().await;
+
+    time_getter.advance_time(Duration::from_secs(3600));
+
+    let (sent_to, message) = handle.message().await;
+    assert_eq!(peer, sent_to);
+    assert_eq!(
+        message,
+        SyncMessage::TransactionInventory(TransactionInventory::new(vec![tx_id]))
+    );
+
+    handle.join_subsystem_manager().await;
+}
+
+// Peers using an older protocol version announce transactions one by one in both directions.
+#[rstest::rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn old_protocol_announcements(#[case] seed: Seed) {
+    let mut rng = test_utils::random::make_seedable_rng(seed);
+
+    let chain_config = Arc::new(create_unit_test_config());
+    let mut tf = TestFramework::builder(&mut rng)
+        .with_chain_config(chain_config.as_ref().clone())
+        .build();
+    // Process a block to finish the initial block download.
+    tf.make_block_builder().build_and_process().unwrap().unwrap();
+
+    let time_getter = P2pBasicTestTimeGetter::new();
+    let p2p_config = Arc::new(test_p2p_config());
+    let mut handle = SyncManagerHandle::builder()
+        .with_chain_config(Arc::clone(&chain_config))
+        .with_p2p_config(Arc::clone(&p2p_config))
+        .with_chainstate(tf.into_chainstate())
+        .with_time_getter(time_getter.get_time_getter())
+        .build()
+        .await;
+
+    let peer = PeerId::new();
+    handle.connect_peer_with_protocol(peer, NETWORK_PROTOCOL_V2).await;
+    // Respond to the header request, so the peer isn't considered stalling when the time moves.
+    handle
+        .send_message(peer, SyncMessage::HeaderList(HeaderList::new(Vec::new())))
+        .await;
+
+    // A new transaction is announced to the peer in the old format
+    let tx = transaction(chain_config.genesis_block_id());
+    let tx_id = tx.transaction().get_id();
+    let res = handle
+        .mempool()
+        .call_mut(|m| m.add_transaction(tx, TxOrigin::LocalP2p))
+        .await
+        .unwrap();
+    assert_eq!(res, Ok(mempool::TxStatus::InMempool));
+
+    time_getter.advance_time(Duration::from_secs(3600));
+
+    let (sent_to, message) = handle.message().await;
+    assert_eq!(peer, sent_to);
+    assert_eq!(message, SyncMessage::NewTransaction(tx_id));
+
+    // An announcement from the peer in the old format is accepted
+    let tx_id = transaction(tx_id.into()).transaction().get_id();
+    handle.send_message(peer, SyncMessage::NewTransaction(tx_id)).await;
+
+    let (sent_to, message) = handle.message().await;
+    assert_eq!(peer, sent_to);
+    assert_eq!(message, SyncMessage::TransactionRequest(tx_id));
+
+    handle.join_subsystem_manager().await;
+}
+
+// The kind of transaction announcements must match the negotiated protocol version.
+#[rstest::rstest]
+#[trace]
+#[case(Seed::from_entropy(), NETWORK_PROTOCOL_V2)]
+#[case(Seed::from_entropy(), NETWORK_PROTOCOL_V3)]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn unexpected_announcement_kind(#[case] seed: Seed, #[case] protocol: NetworkProtocol) {
+    let mut rng = test_utils::random::make_seedable_rng(seed);
+
+    let chain_config = Arc::new(create_unit_test_config());
+    let mut tf = TestFramework::builder(&mut rng)
+        .with_chain_config(chain_config.as_ref().clone())
+        .build();
+    // Process a block to finish the initial block download.
+    tf.make_block_builder().build_and_process().unwrap().unwrap();
+
+    let p2p_config = Arc::new(test_p2p_config());
+    let mut handle = SyncManagerHandle::builder()
+        .with_chain_config(Arc::clone(&chain_config))
+        .with_p2p_config(Arc::clone(&p2p_config))
+        .with_chainstate(tf.into_chainstate())
+        .build()
+        .await;
+
+    let peer = PeerId::new();
+    handle.connect_peer_with_protocol(peer, protocol).await;
+
+    let tx_id = transaction(chain_config.genesis_block_id()).transaction().get_id();
+    let message = if protocol >= NETWORK_PROTOCOL_V3 {
+        SyncMessage::NewTransaction(tx_id)
+    } else {
+        SyncMessage::TransactionInventory(TransactionInventory::new(vec![tx_id]))
+    };
+    handle.send_message(peer, message).await;
+
+    let (adjusted_peer, score) = handle.adjust_peer_score_event().await;
+    assert_eq!(peer, adjusted_peer);
+    assert_eq!(
+        score,
+        P2pError::ProtocolError(ProtocolError::UnexpectedMessage("".to_owned())).ban_score()
+    );
+    handle.assert_no_event().await;
+
+    handle.join_subsystem_manager().await;
+}
+
 /// Creates a simple transaction.
 fn transaction(out_point: Id<GenBlock>) -> SignedTransaction {
     let tx = Transaction::new(
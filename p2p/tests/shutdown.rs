@@ -21,7 +21,8 @@ use mempool::MempoolSubsystemInterface;
 use storage_inmemory::InMemory;
 
 use p2p::{
-    make_p2p, peer_manager::peerdb::storage_impl::PeerDbStorageImpl, testing_utils::test_p2p_config,
+    make_p2p, node_key::NodeKeypair, peer_manager::peerdb::storage_impl::PeerDbStorageImpl,
+    testing_utils::test_p2p_config,
 };
 
 // Check that the p2p shutdown isn't timed out.
@@ -50,8 +51,10 @@ async fn shutdown_timeout() {
 
     let mempool = mempool::make_mempool(
         Arc::clone(&chain_config),
+        Default::default(),
         chainstate.clone(),
         Default::default(),
+        None,
     );
     let mempool = manager.add_subsystem_with_custom_eventloop("shutdown-test-mempool", {
         move |call, shutdown| mempool.run(call, shutdown)
@@ -65,6 +68,7 @@ async fn shutdown_timeout() {
         mempool.clone(),
         Default::default(),
         peerdb_storage,
+        NodeKeypair::generate(),
     )
     .unwrap();
     let _p2p = manager.add_subsystem_with_custom_eventloop("shutdown-test-p2p", {
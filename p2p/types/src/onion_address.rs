@@ -0,0 +1,232 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tor onion service addresses (version 3).
+//!
+//! The address format is described in the "Encoding onion addresses" section of
+//! the Tor rend-spec-v3 document:
+//! `base32(PUBKEY | CHECKSUM | VERSION) + ".onion"`, where
+//! `CHECKSUM = SHA3_256(".onion checksum" | PUBKEY | VERSION)[..2]`.
+
+use std::{fmt::Display, str::FromStr};
+
+use crypto::hash::{hash, Sha3_256};
+use serialization::{Decode, Encode};
+
+const ONION_SUFFIX: &str = ".onion";
+const ONION_CHECKSUM_PREFIX: &[u8] = b".onion checksum";
+const ONION_V3_VERSION: u8 = 3;
+const PUBLIC_KEY_SIZE: usize = 32;
+const CHECKSUM_SIZE: usize = 2;
+/// Length of the base32 encoded public key, checksum and version
+const ONION_V3_ENCODED_LENGTH: usize = 56;
+
+const BASE32_ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum OnionAddressError {
+    #[error("The address doesn't end with .onion")]
+    MissingSuffix,
+    #[error("Invalid onion address length: {0}")]
+    InvalidLength(usize),
+    #[error("Invalid base32 character in onion address")]
+    InvalidCharacter,
+    #[error("Unsupported onion address version: {0}")]
+    UnsupportedVersion(u8),
+    #[error("Invalid onion address checksum")]
+    InvalidChecksum,
+}
+
+/// Tor onion service v3 address (the ed25519 public key of the service).
+#[derive(Debug, Encode, Decode, Clone, Copy, PartialOrd, Ord, PartialEq, Eq, Hash)]
+pub struct OnionV3Address {
+    public_key: [u8; PUBLIC_KEY_SIZE],
+}
+
+impl OnionV3Address {
+    pub fn from_public_key(public_key: [u8; PUBLIC_KEY_SIZE]) -> Self {
+        Self { public_key }
+    }
+
+    pub fn public_key(&self) -> &[u8; PUBLIC_KEY_SIZE] {
+        &self.public_key
+    }
+
+    fn checksum(public_key: &[u8; PUBLIC_KEY_SIZE]) -> [u8; CHECKSUM_SIZE] {
+        let mut data = Vec::with_capacity(ONION_CHECKSUM_PREFIX.len() + PUBLIC_KEY_SIZE + 1);
+        data.extend_from_slice(ONION_CHECKSUM_PREFIX);
+        data.extend_from_slice(public_key);
+        data.push(ONION_V3_VERSION);
+        let digest = hash::<Sha3_256, _>(data);
+        [digest[0], digest[1]]
+    }
+
+    /// Parses the service id (the onion address without the `.onion` suffix), as returned by the
+    /// Tor control port.
+    pub fn from_service_id(service_id: &str) -> Result<Self, OnionAddressError> {
+        if service_id.len() != ONION_V3_ENCODED_LENGTH {
+            return Err(OnionAddressError::InvalidLength(service_id.len()));
+        }
+
+        let decoded = base32_decode(&service_id.to_ascii_lowercase())
+            .ok_or(OnionAddressError::InvalidCharacter)?;
+        let (public_key, rest) = decoded.split_at(PUBLIC_KEY_SIZE);
+        let (checksum, version) = rest.split_at(CHECKSUM_SIZE);
+
+        if version != [ONION_V3_VERSION] {
+            return Err(OnionAddressError::UnsupportedVersion(
+                version.first().copied().unwrap_or_default(),
+            ));
+        }
+
+        let public_key: [u8; PUBLIC_KEY_SIZE] =
+            public_key.try_into().expect("decoded data size must be valid");
+        if checksum != Self::checksum(&public_key) {
+            return Err(OnionAddressError::InvalidChecksum);
+        }
+
+        Ok(Self { public_key })
+    }
+
+    /// Returns the service id (the onion address without the `.onion` suffix).
+    pub fn service_id(&self) -> String {
+        let mut data = Vec::with_capacity(PUBLIC_KEY_SIZE + CHECKSUM_SIZE + 1);
+        data.extend_from_slice(&self.public_key);
+        data.extend_from_slice(&Self::checksum(&self.public_key));
+        data.push(ONION_V3_VERSION);
+        base32_encode(&data)
+    }
+}
+
+impl Display for OnionV3Address {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{ONION_SUFFIX}", self.service_id())
+    }
+}
+
+impl FromStr for OnionV3Address {
+    type Err = OnionAddressError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let service_id = s.strip_suffix(ONION_SUFFIX).ok_or(OnionAddressError::MissingSuffix)?;
+        Self::from_service_id(service_id)
+    }
+}
+
+/// Encodes data as lowercase base32 (RFC 4648) without padding
+fn base32_encode(data: &[u8]) -> String {
+    let mut result = String::with_capacity((data.len() * 8 + 4) / 5);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for byte in data {
+        buffer = (buffer << 8) | u32::from(*byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            result.push(char::from(
+                BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize],
+            ));
+        }
+    }
+    if bits > 0 {
+        result.push(char::from(
+            BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize],
+        ));
+    }
+    result
+}
+
+/// Decodes lowercase base32 (RFC 4648) without padding, the trailing incomplete bits are ignored
+fn base32_decode(data: &str) -> Option<Vec<u8>> {
+    let mut result = Vec::with_capacity(data.len() * 5 / 8);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for c in data.bytes() {
+        let value = BASE32_ALPHABET.iter().position(|a| *a == c)?;
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            result.push((buffer >> bits) as u8);
+        }
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_addresses() {
+        for (address, public_key) in [
+            (
+                "duckduckgogg42xjoc72x3sjasowoarfbgcmvfimaftt6twagswzczad.onion",
+                "1d04a1d04a338c6e6ae970bfabee49049d6702250984ca950c01673f4ec034ad",
+            ),
+            (
+                "2gzyxa5ihm7nsggfxnu52rck2vv4rvmdlkiu3zzui5du4xyclen53wid.onion",
+                "d1b38b83a83b3ed918c5bb69dd444ad56bc8d5835a914de73447474e5f02591b",
+            ),
+        ] {
+            let parsed = address.parse::<OnionV3Address>().unwrap();
+            let public_key_hex =
+                parsed.public_key().iter().map(|b| format!("{b:02x}")).collect::<String>();
+            assert_eq!(public_key_hex, public_key);
+            assert_eq!(parsed.to_string(), address);
+        }
+    }
+
+    #[test]
+    fn invalid_addresses() {
+        assert_eq!(
+            "duckduckgogg42xjoc72x3sjasowoarfbgcmvfimaftt6twagswzczad".parse::<OnionV3Address>(),
+            Err(OnionAddressError::MissingSuffix)
+        );
+        assert_eq!(
+            "duckduckgo.onion".parse::<OnionV3Address>(),
+            Err(OnionAddressError::InvalidLength(10))
+        );
+        assert_eq!(
+            "duckduckgogg42xjoc72x3sjasowoarfbgcmvfimaftt6twagswzcza1.onion"
+                .parse::<OnionV3Address>(),
+            Err(OnionAddressError::InvalidCharacter)
+        );
+        // The last character is changed, so the version is different
+        assert_eq!(
+            "duckduckgogg42xjoc72x3sjasowoarfbgcmvfimaftt6twagswzczae.onion"
+                .parse::<OnionV3Address>(),
+            Err(OnionAddressError::UnsupportedVersion(4))
+        );
+        // One character of the public key is changed
+        assert_eq!(
+            "euckduckgogg42xjoc72x3sjasowoarfbgcmvfimaftt6twagswzczad.onion"
+                .parse::<OnionV3Address>(),
+            Err(OnionAddressError::InvalidChecksum)
+        );
+    }
+
+    #[test]
+    fn encoding_roundtrip() {
+        for public_key in [[0; PUBLIC_KEY_SIZE], [0xff; PUBLIC_KEY_SIZE]] {
+            let address = OnionV3Address::from_public_key(public_key);
+            assert_eq!(
+                address.to_string().len(),
+                ONION_V3_ENCODED_LENGTH + ONION_SUFFIX.len()
+            );
+            assert_eq!(address.to_string().parse::<OnionV3Address>(), Ok(address));
+        }
+    }
+}
@@ -13,7 +13,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
 
 use common::primitives::{semver::SemVer, user_agent::UserAgent};
 
@@ -21,6 +21,15 @@ use crate::{peer_id::PeerId, services::Services};
 
 pub type P2pEventHandler = Arc<dyn Fn(P2pEvent) + Send + Sync>;
 
+/// An event handler that is unsubscribed once all the strong references to it are dropped
+pub type WeakP2pEventHandler = Weak<dyn Fn(P2pEvent) + Send + Sync>;
+
+/// A request to subscribe to the p2p events
+pub enum P2pEventSubscription {
+    Permanent(P2pEventHandler),
+    Weak(WeakP2pEventHandler),
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum P2pEvent {
     PeerConnected {
@@ -30,6 +39,8 @@ pub enum P2pEvent {
         inbound: bool,
         user_agent: UserAgent,
         version: SemVer,
+        /// Hex encoded static public key of the peer, if the connection is authenticated
+        node_key: Option<String>,
     },
     PeerDisconnected(PeerId),
 }
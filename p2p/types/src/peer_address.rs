@@ -13,12 +13,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::fmt::Display;
+use std::{
+    fmt::Display,
+    net::{IpAddr, SocketAddr},
+    str::FromStr,
+};
 
 use serialization::{Decode, Encode};
 
 use crate::{
     ip_address::{Ip4, Ip6},
+    onion_address::OnionV3Address,
     IsGlobalIp,
 };
 
@@ -34,43 +39,116 @@ pub struct PeerAddressIp6 {
     pub port: u16,
 }
 
+#[derive(Debug, Encode, Decode, Clone, PartialOrd, Ord, PartialEq, Eq, Hash)]
+pub struct PeerAddressOnion3 {
+    pub address: OnionV3Address,
+    pub port: u16,
+}
+
 /// Type used to serialize information about peer address.
 ///
-/// Same as std::net::SocketAddr for now but can be later extended with other address types.
+/// Same as std::net::SocketAddr, but can also hold Tor onion service addresses.
 /// Use custom type to be able implement Encode and Decode.
-#[derive(Debug, Encode, Decode, Clone, PartialEq, Eq)]
+#[derive(Debug, Encode, Decode, Clone, PartialOrd, Ord, PartialEq, Eq, Hash)]
 pub enum PeerAddress {
     #[codec(index = 0)]
     Ip4(PeerAddressIp4),
     #[codec(index = 1)]
     Ip6(PeerAddressIp6),
+    #[codec(index = 2)]
+    Onion3(PeerAddressOnion3),
 }
 
 impl PeerAddress {
+    /// Returns the socket address if this is an IP address.
+    pub fn as_socket_address(&self) -> Option<SocketAddr> {
+        match self {
+            PeerAddress::Ip4(socket4) => Some(SocketAddr::V4(std::net::SocketAddrV4::new(
+                socket4.ip.into(),
+                socket4.port,
+            ))),
+            PeerAddress::Ip6(socket6) => Some(SocketAddr::V6(std::net::SocketAddrV6::new(
+                socket6.ip.into(),
+                socket6.port,
+                0,
+                0,
+            ))),
+            PeerAddress::Onion3(_) => None,
+        }
+    }
+
+    pub fn port(&self) -> u16 {
+        match self {
+            PeerAddress::Ip4(socket4) => socket4.port,
+            PeerAddress::Ip6(socket6) => socket6.port,
+            PeerAddress::Onion3(onion) => onion.port,
+        }
+    }
+
     pub fn is_loopback(&self) -> bool {
-        std::net::SocketAddr::from(self).ip().is_loopback()
+        self.as_socket_address().map_or(false, |address| address.ip().is_loopback())
     }
 
     pub fn is_global_unicast_ip(&self) -> bool {
-        std::net::SocketAddr::from(self).ip().is_global_unicast_ip()
+        self.as_socket_address()
+            .map_or(false, |address| address.ip().is_global_unicast_ip())
+    }
+
+    pub fn is_onion(&self) -> bool {
+        match self {
+            PeerAddress::Ip4(_) | PeerAddress::Ip6(_) => false,
+            PeerAddress::Onion3(_) => true,
+        }
     }
 }
 
 impl Display for PeerAddress {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let self_copy: std::net::SocketAddr = self.into();
-        std::fmt::Display::fmt(&self_copy, f)
+        match self {
+            PeerAddress::Ip4(socket4) => std::fmt::Display::fmt(
+                &std::net::SocketAddrV4::new(socket4.ip.into(), socket4.port),
+                f,
+            ),
+            PeerAddress::Ip6(socket6) => std::fmt::Display::fmt(
+                &std::net::SocketAddrV6::new(socket6.ip.into(), socket6.port, 0, 0),
+                f,
+            ),
+            PeerAddress::Onion3(onion) => write!(f, "{}:{}", onion.address, onion.port),
+        }
     }
 }
 
-impl From<std::net::SocketAddr> for PeerAddress {
-    fn from(address: std::net::SocketAddr) -> Self {
+impl FromStr for PeerAddress {
+    type Err = PeerAddressParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(address) = s.parse::<SocketAddr>() {
+            return Ok(address.into());
+        }
+
+        let (host, port) = s.rsplit_once(':').ok_or(PeerAddressParseError::InvalidFormat)?;
+        let address = host.parse::<OnionV3Address>()?;
+        let port = port.parse::<u16>().map_err(|_| PeerAddressParseError::InvalidFormat)?;
+        Ok(PeerAddress::Onion3(PeerAddressOnion3 { address, port }))
+    }
+}
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum PeerAddressParseError {
+    #[error("Invalid peer address format")]
+    InvalidFormat,
+    #[error("Invalid onion address: {0}")]
+    InvalidOnionAddress(#[from] crate::onion_address::OnionAddressError),
+}
+
+impl From<SocketAddr> for PeerAddress {
+    fn from(address: SocketAddr) -> Self {
         match address {
-            std::net::SocketAddr::V4(ip) => PeerAddress::Ip4(PeerAddressIp4 {
+            SocketAddr::V4(ip) => PeerAddress::Ip4(PeerAddressIp4 {
                 ip: (*ip.ip()).into(),
                 port: address.port(),
             }),
-            std::net::SocketAddr::V6(ip) => PeerAddress::Ip6(PeerAddressIp6 {
+            SocketAddr::V6(ip) => PeerAddress::Ip6(PeerAddressIp6 {
                 ip: (*ip.ip()).into(),
                 port: address.port(),
             }),
@@ -78,19 +156,102 @@ impl From<std::net::SocketAddr> for PeerAddress {
     }
 }
 
-impl From<&PeerAddress> for std::net::SocketAddr {
+/// The part of a peer address that is banned.
+///
+/// The port is ignored for the IP addresses. Onion services are banned by their public keys.
+#[derive(Debug, Clone, PartialOrd, Ord, PartialEq, Eq, Hash)]
+pub enum BannablePeerAddress {
+    Ip(IpAddr),
+    Onion3(OnionV3Address),
+}
+
+impl Display for BannablePeerAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BannablePeerAddress::Ip(ip) => std::fmt::Display::fmt(ip, f),
+            BannablePeerAddress::Onion3(onion) => std::fmt::Display::fmt(onion, f),
+        }
+    }
+}
+
+impl FromStr for BannablePeerAddress {
+    type Err = PeerAddressParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(ip) = s.parse::<IpAddr>() {
+            return Ok(BannablePeerAddress::Ip(ip));
+        }
+        Ok(BannablePeerAddress::Onion3(s.parse()?))
+    }
+}
+
+impl From<&PeerAddress> for BannablePeerAddress {
     fn from(address: &PeerAddress) -> Self {
         match address {
-            PeerAddress::Ip4(socket4) => std::net::SocketAddr::V4(std::net::SocketAddrV4::new(
-                socket4.ip.into(),
-                socket4.port,
-            )),
-            PeerAddress::Ip6(socket6) => std::net::SocketAddr::V6(std::net::SocketAddrV6::new(
-                socket6.ip.into(),
-                socket6.port,
-                0,
-                0,
-            )),
+            PeerAddress::Ip4(socket4) => BannablePeerAddress::Ip(IpAddr::V4(socket4.ip.into())),
+            PeerAddress::Ip6(socket6) => BannablePeerAddress::Ip(IpAddr::V6(socket6.ip.into())),
+            PeerAddress::Onion3(onion) => BannablePeerAddress::Onion3(onion.address),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_roundtrip() {
+        for address in [
+            "1.2.3.4:3031",
+            "[2001:bc8:1600::1]:12345",
+            "duckduckgogg42xjoc72x3sjasowoarfbgcmvfimaftt6twagswzczad.onion:3031",
+        ] {
+            let parsed = address.parse::<PeerAddress>().unwrap();
+            assert_eq!(parsed.to_string(), address);
+            assert_eq!(
+                parsed.port(),
+                address.rsplit_once(':').unwrap().1.parse::<u16>().unwrap()
+            );
         }
+
+        let onion = "duckduckgogg42xjoc72x3sjasowoarfbgcmvfimaftt6twagswzczad.onion:3031"
+            .parse::<PeerAddress>()
+            .unwrap();
+        assert!(onion.is_onion());
+        assert_eq!(onion.as_socket_address(), None);
+        let bannable = BannablePeerAddress::from(&onion);
+        assert_eq!(
+            bannable.to_string().parse::<BannablePeerAddress>(),
+            Ok(bannable)
+        );
+
+        assert!("example.com:3031".parse::<PeerAddress>().is_err());
+        assert!(
+            "duckduckgogg42xjoc72x3sjasowoarfbgcmvfimaftt6twagswzczad.onion"
+                .parse::<PeerAddress>()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn encoding_is_stable() {
+        let address: PeerAddress = "1.2.3.4:3031".parse().unwrap();
+        assert_eq!(
+            PeerAddress::decode(&mut address.encode().as_slice()).unwrap(),
+            address
+        );
+
+        let address: PeerAddress =
+            "duckduckgogg42xjoc72x3sjasowoarfbgcmvfimaftt6twagswzczad.onion:3031"
+                .parse()
+                .unwrap();
+        let encoded = address.encode();
+        // Variant index, public key and port
+        assert_eq!(encoded.len(), 1 + 32 + 2);
+        assert_eq!(encoded[0], 2);
+        assert_eq!(
+            PeerAddress::decode(&mut encoded.as_slice()).unwrap(),
+            address
+        );
     }
 }
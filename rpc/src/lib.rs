@@ -17,6 +17,7 @@ mod config;
 mod error;
 mod rpc_auth;
 pub mod rpc_creds;
+pub mod subscription;
 
 use std::{net::SocketAddr, path::PathBuf};
 
@@ -284,7 +285,7 @@ mod tests {
         distributions::{Alphanumeric, DistString},
         Rng,
     };
-    use jsonrpsee::core::client::ClientT;
+    use jsonrpsee::core::client::{ClientT, Subscription, SubscriptionClientT};
     use jsonrpsee::rpc_params;
     use rstest::rstest;
     use test_utils::random::{make_seedable_rng, Seed};
@@ -296,10 +297,14 @@ mod tests {
 
         #[method(name = "add")]
         fn add(&self, a: u64, b: u64) -> Result<u64>;
+
+        #[subscription(name = "subscribe_count", unsubscribe = "unsubscribe_count", item = u64)]
+        async fn subscribe_count(&self, count: u64) -> subscription::SubscriptionResult;
     }
 
     pub struct SubsystemRpcImpl;
 
+    #[async_trait::async_trait]
     impl SubsystemRpcServer for SubsystemRpcImpl {
         fn name(&self) -> Result<String> {
             Ok("sub1".into())
@@ -308,6 +313,17 @@ mod tests {
         fn add(&self, a: u64, b: u64) -> Result<u64> {
             Ok(a + b)
         }
+
+        async fn subscribe_count(
+            &self,
+            pending: subscription::PendingSubscriptionSink,
+            count: u64,
+        ) -> subscription::SubscriptionResult {
+            let (sender, receiver) = subscription::event_channel();
+            (1..=count).for_each(|i| sender.send(i));
+            drop(sender);
+            subscription::forward_events(pending, receiver).await
+        }
     }
 
     #[rstest]
@@ -365,6 +381,50 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn ws_subscription() {
+        let rpc_config = RpcConfig {
+            http_bind_address: "127.0.0.1:0".parse::<SocketAddr>().unwrap().into(),
+            http_enabled: false.into(),
+            ws_bind_address: "127.0.0.1:0".parse::<SocketAddr>().unwrap().into(),
+            ws_enabled: true.into(),
+        };
+
+        let rpc = Builder::new(rpc_config, None)
+            .register(SubsystemRpcImpl.into_rpc())
+            .build()
+            .await
+            .unwrap();
+
+        let url = format!("ws://{}", rpc.websocket_address().unwrap());
+        let client = new_ws_client(url, RpcAuthData::None).await.unwrap();
+        let mut subscription: Subscription<u64> = client
+            .subscribe(
+                "some_subsystem_subscribe_count",
+                rpc_params!(3),
+                "some_subsystem_unsubscribe_count",
+            )
+            .await
+            .unwrap();
+        for expected in 1..=3 {
+            assert_eq!(subscription.next().await.unwrap().unwrap(), expected);
+        }
+
+        subsystem::Subsystem::shutdown(rpc).await;
+    }
+
+    #[test]
+    fn subscription_lag() {
+        let (sender, receiver) = subscription::event_channel();
+        for i in 0..subscription::EVENT_BUFFER_SIZE {
+            sender.send(i);
+        }
+        assert!(!receiver.lagged());
+
+        sender.send(subscription::EVENT_BUFFER_SIZE);
+        assert!(receiver.lagged());
+    }
+
     async fn http_request(rpc: &Rpc, rpc_auth: RpcAuthData) -> anyhow::Result<()> {
         let url = format!("http://{}", rpc.http_address().unwrap());
         let client = new_http_client(url, rpc_auth)?;
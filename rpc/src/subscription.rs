@@ -0,0 +1,107 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Helpers for implementing RPC subscriptions (only available over WebSocket)
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+pub use jsonrpsee::{core::SubscriptionResult, PendingSubscriptionSink};
+
+use jsonrpsee::SubscriptionMessage;
+use tokio::sync::mpsc;
+
+/// The maximum number of events buffered for a single subscriber
+pub const EVENT_BUFFER_SIZE: usize = 1024;
+
+/// Sending half of the event channel of a subscription.
+///
+/// Events are sent from the subsystem event handlers, so sending never blocks. If the subscriber
+/// doesn't keep up and the buffer is full, the event is dropped and the subscription gets closed.
+pub struct EventSender<T> {
+    sender: mpsc::Sender<T>,
+    lagged: Arc<AtomicBool>,
+}
+
+impl<T> EventSender<T> {
+    pub fn send(&self, event: T) {
+        match self.sender.try_send(event) {
+            Ok(()) => {}
+            Err(mpsc::error::TrySendError::Full(_)) => self.lagged.store(true, Ordering::Release),
+            // The subscription is over
+            Err(mpsc::error::TrySendError::Closed(_)) => {}
+        }
+    }
+}
+
+/// Receiving half of the event channel of a subscription, see [`forward_events`]
+pub struct EventReceiver<T> {
+    receiver: mpsc::Receiver<T>,
+    lagged: Arc<AtomicBool>,
+}
+
+impl<T> EventReceiver<T> {
+    /// Returns true if some events have been dropped because the buffer was full
+    pub fn lagged(&self) -> bool {
+        self.lagged.load(Ordering::Acquire)
+    }
+}
+
+/// Create a bounded channel for the events of a subscription
+pub fn event_channel<T>() -> (EventSender<T>, EventReceiver<T>) {
+    let (sender, receiver) = mpsc::channel(EVENT_BUFFER_SIZE);
+    let lagged = Arc::new(AtomicBool::new(false));
+    (
+        EventSender {
+            sender,
+            lagged: Arc::clone(&lagged),
+        },
+        EventReceiver { receiver, lagged },
+    )
+}
+
+/// Accept the subscription and forward the items received from `events` to the subscriber.
+///
+/// Returns once the subscriber goes away or the sending half of the channel is dropped.
+/// If the subscriber lags behind and some events are dropped, the subscription is closed
+/// with an error, so the subscriber knows it has missed them.
+pub async fn forward_events<T: serde::Serialize>(
+    pending: PendingSubscriptionSink,
+    mut events: EventReceiver<T>,
+) -> SubscriptionResult {
+    let sink = pending.accept().await?;
+
+    loop {
+        tokio::select! {
+            event = events.receiver.recv() => match event {
+                Some(event) => {
+                    if events.lagged() {
+                        return Err("Subscriber lags behind, some events are dropped".into());
+                    }
+                    let message = SubscriptionMessage::from_json(&event)?;
+                    if sink.send(message).await.is_err() {
+                        break;
+                    }
+                }
+                None => break,
+            },
+            () = sink.closed() => break,
+        }
+    }
+
+    Ok(())
+}
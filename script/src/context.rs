@@ -60,6 +60,10 @@ pub trait Context {
     /// Maximum script length in bytes
     const MAX_SCRIPT_SIZE: usize;
 
+    /// Maximum number of non-push operations per script, public keys of executed
+    /// OP_CHECKMULTISIG operations count towards the limit as well
+    const MAX_OPS_PER_SCRIPT: usize = 201;
+
     /// Public key type.
     type Public;
 
@@ -50,6 +50,8 @@ pub enum Error {
     StackSize,
     #[error("Maximum script size exceeded.")]
     ScriptSize,
+    #[error("Maximum number of operations per script exceeded.")]
+    OpCount,
     #[error("Incorrect number of public keys for multisig")]
     PubkeyCount,
     #[error("Incorrect number of signatures for multisig")]
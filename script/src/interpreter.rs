@@ -225,6 +225,7 @@ pub fn run_script<'a, Ctx: Context>(
     let mut subscript: &[u8] = instr_iter.subscript();
     let mut cur_instr_num = 0u32;
     let mut codesep_idx = u32::MAX;
+    let mut op_count = 0usize;
     let mut exec_stack = ExecStack::default();
     let mut alt_stack = Stack::<'a>::default();
 
@@ -232,6 +233,13 @@ pub fn run_script<'a, Ctx: Context>(
         let instr = instr?;
 
         let executing = exec_stack.executing();
+        if let Instruction::Op(opcode) = instr {
+            // Small integer pushes do not count as operations, unexecuted branches do
+            if opcode.into_u8() > opcodes::all::OP_PUSHNUM_16.into_u8() {
+                op_count += 1;
+                ensure!(op_count <= Ctx::MAX_OPS_PER_SCRIPT, Error::OpCount);
+            }
+        }
         match instr {
             Instruction::PushBytes(data) => {
                 ensure!(data.len() <= Ctx::MAX_SCRIPT_ELEMENT_SIZE, Error::PushSize);
@@ -276,6 +284,8 @@ pub fn run_script<'a, Ctx: Context>(
                             ensure!(nkey >= 0, Error::PubkeyCount);
                             let nkey = nkey as usize;
                             ensure!(nkey <= Ctx::MAX_PUBKEYS_PER_MULTISIG, Error::PubkeyCount);
+                            op_count += nkey;
+                            ensure!(op_count <= Ctx::MAX_OPS_PER_SCRIPT, Error::OpCount);
                             let keys = stack.top_slice(0..nkey)?.iter().map(AsRef::as_ref);
 
                             // Extract signatures
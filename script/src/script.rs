@@ -333,6 +333,40 @@ impl Script {
                 || opcodes::All::from(self.0[0]).classify() == opcodes::Class::IllegalOp)
     }
 
+    /// Count the signature operations in the script.
+    ///
+    /// OP_CHECKMULTISIG counts as the number of public keys if it is preceded by a small integer
+    /// push, or as `max_pubkeys_per_multisig` otherwise. The count is an upper bound of the
+    /// signature checks performed when running the script. Parsing stops at the first error.
+    pub fn sigop_count(&self, max_pubkeys_per_multisig: usize) -> usize {
+        let mut count = 0usize;
+        let mut last_opcode = None;
+        for instr in self.instructions() {
+            let opcode = match instr {
+                Ok(Instruction::Op(opcode)) => opcode,
+                Ok(Instruction::PushBytes(_)) => {
+                    last_opcode = None;
+                    continue;
+                }
+                Err(_) => break,
+            };
+            if let opcodes::Class::Signature(sig_opcode) = opcode.classify() {
+                count += match sig_opcode {
+                    opcodes::Signature::OP_CHECKSIG | opcodes::Signature::OP_CHECKSIGVERIFY => 1,
+                    opcodes::Signature::OP_CHECKMULTISIG
+                    | opcodes::Signature::OP_CHECKMULTISIGVERIFY => {
+                        match last_opcode.map(opcodes::All::classify) {
+                            Some(opcodes::Class::PushNum(n)) if n > 0 => n as usize,
+                            _ => max_pubkeys_per_multisig,
+                        }
+                    }
+                };
+            }
+            last_opcode = Some(opcode);
+        }
+        count
+    }
+
     /// Iterate over the script in the form of `Instruction`s, which are an enum covering
     /// opcodes, datapushes and errors. At most one error will be returned and then the
     /// iterator will end. To instead iterate over the script as sequence of bytes, treat
@@ -1064,6 +1098,37 @@ mod test {
         assert!(script_2 > script_1);
     }
 
+    #[test]
+    fn sigop_count() {
+        let checksig = Builder::new()
+            .push_slice(&[0u8; 33])
+            .push_opcode(opcodes::all::OP_CHECKSIGVERIFY)
+            .push_slice(&[1u8; 33])
+            .push_opcode(opcodes::all::OP_CHECKSIG)
+            .into_script();
+        assert_eq!(checksig.sigop_count(20), 2);
+
+        let multisig = Builder::new()
+            .push_int(2)
+            .push_slice(&[0u8; 33])
+            .push_slice(&[1u8; 33])
+            .push_slice(&[2u8; 33])
+            .push_int(3)
+            .push_opcode(opcodes::all::OP_CHECKMULTISIG)
+            .into_script();
+        assert_eq!(multisig.sigop_count(20), 3);
+
+        // The number of keys is not known without running the script
+        let unknown_multisig = Builder::new()
+            .push_int(3)
+            .push_opcode(opcodes::all::OP_DUP)
+            .push_opcode(opcodes::all::OP_CHECKMULTISIGVERIFY)
+            .into_script();
+        assert_eq!(unknown_multisig.sigop_count(20), 20);
+
+        assert_eq!(Script::new().sigop_count(20), 0);
+    }
+
     use proptest::prelude::*;
 
     proptest! {
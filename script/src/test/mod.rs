@@ -69,3 +69,41 @@ fn test_4opc_sequences() {
     // Let the test fail if we have at least one mismatch.
     assert_eq!(fails, 0, "{fails} tests failed");
 }
+
+#[test]
+fn test_op_count_limit() {
+    let max_ops = <TestContext as Context>::MAX_OPS_PER_SCRIPT;
+    let script_with_ops = |num_ops: usize| -> Script {
+        (0..num_ops)
+            .fold(Builder::new().push_int(1), |builder, _| {
+                builder.push_opcode(opcodes::all::OP_NOP)
+            })
+            .into_script()
+    };
+
+    let expected = Stack::from(vec![vec![1u8].into()]);
+    let script = script_with_ops(max_ops);
+    assert_eq!(
+        run_script(&TestContext::default(), &script, vec![].into()),
+        Ok(expected)
+    );
+
+    let script = script_with_ops(max_ops + 1);
+    assert_eq!(
+        run_script(&TestContext::default(), &script, vec![].into()),
+        Err(Error::OpCount)
+    );
+
+    // Operations in branches that are not executed count as well
+    let script = (0..max_ops)
+        .fold(
+            Builder::new().push_int(0).push_opcode(opcodes::all::OP_IF),
+            |builder, _| builder.push_opcode(opcodes::all::OP_NOP),
+        )
+        .push_opcode(opcodes::all::OP_ENDIF)
+        .into_script();
+    assert_eq!(
+        run_script(&TestContext::default(), &script, vec![].into()),
+        Err(Error::OpCount)
+    );
+}
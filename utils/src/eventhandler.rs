@@ -16,12 +16,16 @@
 use crate::blockuntilzero::BlockUntilZero;
 
 use crate::sync::atomic::AtomicI32;
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
 
 pub type EventHandler<E> = Arc<dyn Fn(E) + Send + Sync>;
 
+/// An event handler that is unsubscribed once all the strong references to it are dropped.
+pub type WeakEventHandler<E> = Weak<dyn Fn(E) + Send + Sync>;
+
 pub struct EventsController<E> {
     event_subscribers: Vec<EventHandler<E>>,
+    weak_event_subscribers: Vec<WeakEventHandler<E>>,
     events_broadcaster: slave_pool::ThreadPool,
     wait_for_events: BlockUntilZero<AtomicI32>,
 }
@@ -32,6 +36,7 @@ impl<E: Clone + Send + Sync + 'static> EventsController<E> {
         events_broadcaster.set_threads(1).expect("Event thread-pool starting failed");
         Self {
             event_subscribers: Vec::new(),
+            weak_event_subscribers: Vec::new(),
             events_broadcaster,
             wait_for_events: BlockUntilZero::new(),
         }
@@ -45,6 +50,19 @@ impl<E: Clone + Send + Sync + 'static> EventsController<E> {
         self.event_subscribers.push(handler)
     }
 
+    /// Subscribe a handler that stays subscribed only while its owner keeps it alive.
+    ///
+    /// Handlers that have been dropped are not called anymore and are removed on the next
+    /// subscription.
+    pub fn subscribe_to_events_weak(&mut self, handler: WeakEventHandler<E>) {
+        self.weak_event_subscribers.retain(|handler| handler.strong_count() > 0);
+        self.weak_event_subscribers.push(handler)
+    }
+
+    pub fn weak_subscribers_count(&self) -> usize {
+        self.weak_event_subscribers.len()
+    }
+
     pub fn wait_for_all_events(&self) {
         self.wait_for_events.wait_for_zero();
     }
@@ -59,10 +77,15 @@ impl<E: Clone + Send + Sync + 'static> EventsController<E> {
     }
 
     pub fn broadcast(&self, event: E) {
-        self.event_subscribers.iter().cloned().for_each(|handler| {
-            let event = event.clone();
-            self.broadcast_spawn_call(event, handler)
-        })
+        let weak_subscribers = self.weak_event_subscribers.iter().filter_map(Weak::upgrade);
+        self.event_subscribers
+            .iter()
+            .cloned()
+            .chain(weak_subscribers)
+            .for_each(|handler| {
+                let event = event.clone();
+                self.broadcast_spawn_call(event, handler)
+            })
     }
 }
 
@@ -72,4 +95,37 @@ impl<E: Clone + Send + Sync + 'static> Default for EventsController<E> {
     }
 }
 
-// TODO: add tests for events
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    #[test]
+    fn weak_handler_is_removed_after_drop() {
+        let mut controller = EventsController::<u32>::new();
+        let counter = Arc::new(AtomicUsize::new(0));
+
+        let handler: EventHandler<u32> = {
+            let counter = Arc::clone(&counter);
+            Arc::new(move |_| {
+                counter.fetch_add(1, Ordering::SeqCst);
+            })
+        };
+        controller.subscribe_to_events_weak(Arc::downgrade(&handler));
+
+        controller.broadcast(1);
+        controller.wait_for_all_events();
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+
+        drop(handler);
+        controller.broadcast(2);
+        controller.wait_for_all_events();
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+
+        // The dropped handler is cleaned up when a new one is subscribed
+        let other_handler: EventHandler<u32> = Arc::new(|_| {});
+        controller.subscribe_to_events_weak(Arc::downgrade(&other_handler));
+        assert_eq!(controller.weak_subscribers_count(), 1);
+    }
+}
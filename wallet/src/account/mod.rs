@@ -22,42 +22,62 @@ use common::chain::block::timestamp::BlockTimestamp;
 use common::primitives::id::WithId;
 use common::Uint256;
 use crypto::key::hdkd::child_number::ChildNumber;
-use mempool::FeeRate;
+use mempool::{FeeRate, INCREMENTAL_RELAY_FEE_RATE};
 pub use utxo_selector::UtxoSelectorError;
 
 use crate::account::utxo_selector::{select_coins, OutputGroup};
 use crate::key_chain::{make_path_to_vrf_key, AccountKeyChain, KeyChainError};
-use crate::send_request::{make_address_output, make_address_output_token, make_stake_output};
+use crate::musig2_session::MuSig2InputSession;
+use crate::send_request::{
+    make_address_output, make_address_output_token, make_create_delegation_output,
+    make_stake_output,
+};
 use crate::wallet_events::{WalletEvents, WalletEventsNoOp};
 use crate::{SendRequest, WalletError, WalletResult};
 use common::address::Address;
+use common::chain::classic_multisig::ClassicMultisigChallenge;
+use common::chain::signature::inputsig::classical_multisig::authorize_classical_multisig::{
+    sign_classical_multisig_spending, AuthorizedClassicalMultisigSpend,
+};
 use common::chain::signature::inputsig::standard_signature::StandardInputSignature;
 use common::chain::signature::inputsig::InputWitness;
 use common::chain::signature::sighash::sighashtype::SigHashType;
+use common::chain::signature::sighash::signature_hash;
 use common::chain::signature::TransactionSigError;
+use common::chain::timelock::OutputTimeLock;
 use common::chain::tokens::{OutputValue, TokenData, TokenId, TokenTransfer};
 use common::chain::{
-    Block, ChainConfig, Destination, GenBlock, PoolId, SignedTransaction, Transaction, TxInput,
-    TxOutput, UtxoOutPoint,
+    AccountNonce, AccountOutPoint, AccountSpending, Block, ChainConfig, DelegationId, Destination,
+    GenBlock, OutPointSourceId, PoolId, SignedTransaction, Transaction, TxInput, TxOutput,
+    UtxoOutPoint,
 };
 use common::primitives::per_thousand::PerThousand;
-use common::primitives::{Amount, BlockHeight, Id};
+use common::primitives::{Amount, BlockHeight, Id, H256};
 use consensus::PoSGenerateBlockInputData;
+use crypto::key::extended::ExtendedPublicKey;
 use crypto::key::hdkd::u31::U31;
-use crypto::key::PublicKey;
+use crypto::key::musig2::{PartialSignature, PublicNonce};
+use crypto::key::{PrivateKey, PublicKey};
 use crypto::vrf::{VRFPrivateKey, VRFPublicKey};
 use itertools::Itertools;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::ops::Add;
 use std::sync::Arc;
+use utils::ensure;
 use wallet_storage::{
     StoreTxRo, StoreTxRw, WalletStorageReadLocked, WalletStorageReadUnlocked,
     WalletStorageWriteLocked, WalletStorageWriteUnlocked,
 };
-use wallet_types::utxo_types::{get_utxo_type, UtxoState, UtxoStates, UtxoType, UtxoTypes};
+use wallet_types::partially_signed_transaction::PartiallySignedTransaction;
+use wallet_types::utxo_types::{
+    get_utxo_type, UtxoMark, UtxoState, UtxoStates, UtxoType, UtxoTypes,
+};
 use wallet_types::wallet_tx::{BlockData, TxData, TxState};
-use wallet_types::{AccountId, AccountInfo, AccountWalletTxId, BlockInfo, KeyPurpose, WalletTx};
+use wallet_types::{
+    AccountId, AccountInfo, AccountUtxoId, AccountWalletTxId, BlockInfo, KeyPurpose, WalletTx,
+};
 
+pub use self::output_cache::DelegationData;
 use self::output_cache::OutputCache;
 use self::transaction_list::{get_transaction_list, TransactionList};
 use self::utxo_selector::PayFee;
@@ -67,6 +87,8 @@ pub struct Account {
     key_chain: AccountKeyChain,
     output_cache: OutputCache,
     account_info: AccountInfo,
+    /// The frozen flags and labels of the UTXOs, see `set_utxo_mark`
+    utxo_marks: BTreeMap<UtxoOutPoint, UtxoMark>,
 }
 
 impl Account {
@@ -85,11 +107,18 @@ impl Account {
         let txs = db_tx.get_transactions(&key_chain.get_account_id())?;
         let output_cache = OutputCache::new(txs);
 
+        let utxo_marks = db_tx
+            .get_utxo_marks(&key_chain.get_account_id())?
+            .into_iter()
+            .map(|(id, mark)| (id.into_item_id(), mark))
+            .collect();
+
         Ok(Account {
             chain_config,
             key_chain,
             output_cache,
             account_info,
+            utxo_marks,
         })
     }
 
@@ -119,6 +148,7 @@ impl Account {
             key_chain,
             output_cache,
             account_info,
+            utxo_marks: BTreeMap::new(),
         };
 
         account.scan_genesis(db_tx, &mut WalletEventsNoOp)?;
@@ -127,8 +157,27 @@ impl Account {
     }
 
     fn select_inputs_for_send_request(
+        &mut self,
+        request: SendRequest,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        median_time: BlockTimestamp,
+        current_fee_rate: FeeRate,
+        consolidate_fee_rate: FeeRate,
+    ) -> WalletResult<SendRequest> {
+        self.select_inputs_for_send_request_from(
+            request,
+            InputsSource::OwnKeys,
+            db_tx,
+            median_time,
+            current_fee_rate,
+            consolidate_fee_rate,
+        )
+    }
+
+    fn select_inputs_for_send_request_from(
         &mut self,
         mut request: SendRequest,
+        inputs_source: InputsSource,
         db_tx: &mut impl WalletStorageWriteLocked,
         median_time: BlockTimestamp,
         current_fee_rate: FeeRate,
@@ -161,7 +210,12 @@ impl Account {
                 median_time,
                 UtxoState::Confirmed | UtxoState::InMempool | UtxoState::Inactive,
             )
-            .into_iter(),
+            .into_iter()
+            .filter(|(outpoint, (tx_output, _))| {
+                !self.is_utxo_frozen(outpoint)
+                    && Self::get_tx_output_destination(tx_output)
+                        .map_or(false, |destination| inputs_source.contains(destination))
+            }),
             |(_, (tx_output, _))| tx_output,
             |grouped: &mut Vec<(UtxoOutPoint, TxOutput)>, element, _| -> WalletResult<()> {
                 grouped.push((element.0.clone(), element.1 .0.clone()));
@@ -176,35 +230,9 @@ impl Account {
 
         let mut total_fees_not_paied = network_fee;
 
-        let utxo_to_output_group =
-            |(outpoint, txo): &(UtxoOutPoint, TxOutput)| -> WalletResult<OutputGroup> {
-                let tx_input: TxInput = outpoint.clone().into();
-                let input_size = serialization::Encode::encoded_size(&tx_input);
-
-                let destination = Self::get_tx_output_destination(txo).ok_or_else(|| {
-                    WalletError::UnsupportedTransactionOutput(Box::new(txo.clone()))
-                })?;
-
-                let inp_sig_size = input_signature_size(destination)?;
-
-                let fee = current_fee_rate
-                    .compute_fee(input_size + inp_sig_size)
-                    .map_err(|_| UtxoSelectorError::AmountArithmeticError)?;
-                let consolidate_fee = consolidate_fee_rate
-                    .compute_fee(input_size + inp_sig_size)
-                    .map_err(|_| UtxoSelectorError::AmountArithmeticError)?;
-
-                // TODO: calculate weight from the size of the input
-                let weight = 0;
-                let out_group = OutputGroup::new(
-                    (tx_input, txo.clone()),
-                    fee.into(),
-                    consolidate_fee.into(),
-                    weight,
-                )?;
-
-                Ok(out_group)
-            };
+        let utxo_to_output_group = |(outpoint, txo): &(UtxoOutPoint, TxOutput)| {
+            self.make_output_group(outpoint, txo, current_fee_rate, consolidate_fee_rate)
+        };
 
         let mut selected_inputs: BTreeMap<_, _> = output_currency_amounts
             .iter()
@@ -286,7 +314,14 @@ impl Account {
                 selected_inputs.get(currency).map_or(Amount::ZERO, |result| result.get_change());
 
             if change_amount > Amount::ZERO {
-                let (_, change_address) = self.get_new_address(db_tx, KeyPurpose::Change)?;
+                let change_address = match &inputs_source {
+                    InputsSource::OwnKeys => self.get_new_address(db_tx, KeyPurpose::Change)?.1,
+                    // The change stays locked to the multisig
+                    InputsSource::Multisig(challenge_hash) => Address::new_from_destination(
+                        self.chain_config.as_ref(),
+                        &Destination::ClassicMultisig(*challenge_hash),
+                    )?,
+                };
                 let change_output = match currency {
                     Currency::Coin => make_address_output(
                         self.chain_config.as_ref(),
@@ -308,6 +343,45 @@ impl Account {
         Ok(request.with_inputs(selected_inputs))
     }
 
+    fn make_output_group(
+        &self,
+        outpoint: &UtxoOutPoint,
+        txo: &TxOutput,
+        current_fee_rate: FeeRate,
+        consolidate_fee_rate: FeeRate,
+    ) -> WalletResult<OutputGroup> {
+        let tx_input: TxInput = outpoint.clone().into();
+        let input_size = self.input_size_with_signature(&tx_input, Some(txo))?;
+
+        let fee = current_fee_rate
+            .compute_fee(input_size)
+            .map_err(|_| UtxoSelectorError::AmountArithmeticError)?;
+        let consolidate_fee = consolidate_fee_rate
+            .compute_fee(input_size)
+            .map_err(|_| UtxoSelectorError::AmountArithmeticError)?;
+
+        // TODO: calculate weight from the size of the input
+        let weight = 0;
+        let out_group = OutputGroup::new(
+            (tx_input, txo.clone()),
+            fee.into(),
+            consolidate_fee.into(),
+            weight,
+        )?;
+
+        Ok(out_group)
+    }
+
+    /// Return the encoded size of the input together with its signature
+    fn input_size_with_signature(
+        &self,
+        input: &TxInput,
+        utxo: Option<&TxOutput>,
+    ) -> WalletResult<usize> {
+        let destination = self.get_input_destination(input, utxo)?;
+        Ok(serialization::Encode::encoded_size(input) + self.input_signature_size(destination)?)
+    }
+
     pub fn process_send_request(
         &mut self,
         db_tx: &mut impl WalletStorageWriteUnlocked,
@@ -329,6 +403,163 @@ impl Account {
         Ok(tx)
     }
 
+    /// Create a transaction that spends exactly the provided UTXOs instead of selecting the
+    /// inputs automatically. What is left after the outputs and the fee are paid is sent to
+    /// a new change address.
+    ///
+    /// The UTXOs must be spendable with the keys of this account and frozen UTXOs are rejected,
+    /// they have to be unfrozen first.
+    pub fn process_send_request_from_utxos(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteUnlocked,
+        request: SendRequest,
+        utxos: BTreeSet<UtxoOutPoint>,
+        median_time: BlockTimestamp,
+        current_fee_rate: FeeRate,
+    ) -> WalletResult<SignedTransaction> {
+        ensure!(!utxos.is_empty(), WalletError::NoUtxos);
+
+        let spendable_utxos = self.get_utxos(
+            UtxoType::Transfer | UtxoType::LockThenTransfer,
+            median_time,
+            UtxoState::Confirmed | UtxoState::InMempool | UtxoState::Inactive,
+        );
+        let inputs = utxos
+            .into_iter()
+            .map(|outpoint| -> WalletResult<_> {
+                ensure!(
+                    !self.is_utxo_frozen(&outpoint),
+                    WalletError::UtxoFrozen(outpoint)
+                );
+                let (txo, token_id) = spendable_utxos
+                    .get(&outpoint)
+                    .filter(|(txo, _)| {
+                        Self::get_tx_output_destination(txo).map_or(false, |destination| {
+                            InputsSource::OwnKeys.contains(destination)
+                        })
+                    })
+                    .ok_or_else(|| WalletError::UtxoNotSpendable(outpoint.clone()))?;
+                Ok((outpoint, (*txo).clone(), *token_id))
+            })
+            .collect::<WalletResult<Vec<_>>>()?;
+
+        let mut input_amounts = group_utxos_for_input(
+            inputs.iter(),
+            |(_, txo, _)| txo,
+            |total: &mut Amount, _, amount| -> WalletResult<()> {
+                *total = (*total + amount).ok_or(WalletError::OutputAmountOverflow)?;
+                Ok(())
+            },
+            |(_, _, token_id)| token_id.ok_or(WalletError::MissingTokenId),
+            Amount::ZERO,
+        )?;
+        let output_amounts = group_outputs(
+            request.outputs().iter(),
+            |&output| output,
+            |total: &mut Amount, _, amount| -> WalletResult<()> {
+                *total = (*total + amount).ok_or(WalletError::OutputAmountOverflow)?;
+                Ok(())
+            },
+            Amount::ZERO,
+        )?;
+
+        let mut change_amounts = output_amounts
+            .into_iter()
+            .map(|(currency, output_amount)| -> WalletResult<_> {
+                let input_amount = input_amounts.remove(&currency).unwrap_or(Amount::ZERO);
+                let change_amount = (input_amount - output_amount)
+                    .ok_or(WalletError::NotEnoughUtxo(input_amount, output_amount))?;
+                Ok((currency, change_amount))
+            })
+            .collect::<WalletResult<BTreeMap<_, _>>>()?;
+        change_amounts.extend(input_amounts);
+        // The fee is paid from the coins left after the outputs
+        let coins_left = change_amounts.remove(&Currency::Coin).unwrap_or(Amount::ZERO);
+
+        let (_, change_address) = self.get_new_address(db_tx, KeyPurpose::Change)?;
+        let token_change_outputs = change_amounts
+            .into_iter()
+            .filter(|(_, change_amount)| *change_amount > Amount::ZERO)
+            .map(|(currency, change_amount)| match currency {
+                Currency::Coin => make_address_output(
+                    self.chain_config.as_ref(),
+                    change_address.clone(),
+                    change_amount,
+                ),
+                Currency::Token(token_id) => make_address_output_token(
+                    self.chain_config.as_ref(),
+                    change_address.clone(),
+                    change_amount,
+                    token_id,
+                ),
+            })
+            .collect::<WalletResult<Vec<_>>>()?;
+
+        let request = request
+            .with_inputs(inputs.into_iter().map(|(outpoint, txo, _)| (outpoint.into(), txo)))
+            .with_outputs(token_change_outputs);
+
+        let fee_without_change = self.estimate_fee(&request, current_fee_rate)?;
+        let (coin_change_fee, _) = coin_and_token_output_change_fees(current_fee_rate)?;
+        let fee_with_change =
+            (fee_without_change + coin_change_fee).ok_or(WalletError::OutputAmountOverflow)?;
+
+        let request = if coins_left > fee_with_change {
+            let change_amount = (coins_left - fee_with_change).expect("checked above");
+            request.with_outputs([make_address_output(
+                self.chain_config.as_ref(),
+                change_address,
+                change_amount,
+            )?])
+        } else {
+            // The change is too small to be worth an output, it all goes to the fee
+            ensure!(
+                coins_left >= fee_without_change,
+                WalletError::NotEnoughAmountToPayFee(coins_left, fee_without_change)
+            );
+            request
+        };
+
+        self.sign_transaction(request, db_tx)
+    }
+
+    /// Select the inputs for the request like `process_send_request` does, but return the
+    /// transaction without signing it, so it can be signed later or by other wallets
+    pub fn process_send_request_unsigned(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        request: SendRequest,
+        median_time: BlockTimestamp,
+        current_fee_rate: FeeRate,
+        consolidate_fee_rate: FeeRate,
+    ) -> WalletResult<PartiallySignedTransaction> {
+        let request = self.select_inputs_for_send_request(
+            request,
+            db_tx,
+            median_time,
+            current_fee_rate,
+            consolidate_fee_rate,
+        )?;
+
+        let (tx, input_utxos) = request.into_transaction_and_utxos()?;
+        let destinations = tx
+            .inputs()
+            .iter()
+            .zip(input_utxos.iter())
+            .map(|(input, utxo)| {
+                self.get_input_destination(input, utxo.as_ref()).map(|d| Some(d.clone()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let witnesses = vec![None; tx.inputs().len()];
+
+        Ok(PartiallySignedTransaction::new(
+            tx,
+            witnesses,
+            input_utxos,
+            destinations,
+        )?)
+    }
+
     fn get_vrf_key(
         &self,
         db_tx: &impl WalletStorageReadUnlocked,
@@ -422,6 +653,321 @@ impl Account {
         Ok(tx)
     }
 
+    pub fn get_delegations(&self) -> impl Iterator<Item = (&DelegationId, &DelegationData)> {
+        self.output_cache
+            .delegation_ids()
+            .filter(|(_, data)| self.is_destination_mine_or_watched(&data.destination))
+    }
+
+    pub fn create_delegation(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteUnlocked,
+        address: Address,
+        pool_id: PoolId,
+        median_time: BlockTimestamp,
+        current_fee_rate: FeeRate,
+        consolidate_fee_rate: FeeRate,
+    ) -> WalletResult<(DelegationId, SignedTransaction)> {
+        let output = make_create_delegation_output(self.chain_config.as_ref(), address, pool_id)?;
+        let request = SendRequest::new().with_outputs([output]);
+        let request = self.select_inputs_for_send_request(
+            request,
+            db_tx,
+            median_time,
+            current_fee_rate,
+            consolidate_fee_rate,
+        )?;
+
+        // the delegation id is calculated from the first UTXO input, same as in the tx verifier
+        let delegation_id = request
+            .inputs()
+            .iter()
+            .find_map(|input| input.utxo_outpoint())
+            .map(pos_accounting::make_delegation_id)
+            .ok_or(WalletError::NoUtxos)?;
+
+        let tx = self.sign_transaction(request, db_tx)?;
+        Ok((delegation_id, tx))
+    }
+
+    /// Create a transaction that withdraws `amount` from the delegation to the `address`.
+    /// The fee is paid from the withdrawn amount.
+    ///
+    /// `confirmed_nonce` is the last nonce used to spend from the delegation according to the
+    /// node, the next nonce is calculated from it and the nonces of the transactions known to
+    /// this account, so transactions that are not confirmed yet are also taken into account.
+    pub fn spend_from_delegation(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteUnlocked,
+        address: Address,
+        amount: Amount,
+        delegation_id: DelegationId,
+        confirmed_nonce: Option<AccountNonce>,
+        current_fee_rate: FeeRate,
+    ) -> WalletResult<SignedTransaction> {
+        let delegation_data = self
+            .output_cache
+            .delegation_data(&delegation_id)
+            .ok_or(WalletError::DelegationNotFound(delegation_id))?;
+        let input_sig_size = self.input_signature_size(&delegation_data.destination)?;
+
+        let nonce = std::cmp::max(
+            self.output_cache.last_delegation_nonce(&delegation_id),
+            confirmed_nonce,
+        )
+        .map_or(Some(AccountNonce::new(0)), |nonce| nonce.increment())
+        .ok_or(WalletError::DelegationNonceOverflow(delegation_id))?;
+
+        // the transaction is included in the next block at the earliest
+        let next_block_height = self.account_info.best_block_height().next_height();
+        let maturity_distance: i64 =
+            self.chain_config.spend_share_maturity_distance(next_block_height).into();
+        let timelock = OutputTimeLock::ForBlockCount(
+            maturity_distance.try_into().expect("maturity distance is not negative"),
+        );
+        let destination = address.destination(self.chain_config.as_ref())?;
+
+        let input = AccountOutPoint::new(nonce, AccountSpending::Delegation(delegation_id, amount));
+        let input_size = serialization::Encode::encoded_size(&TxInput::Account(input.clone()));
+        let output_size = tx_size_with_outputs(&[TxOutput::LockThenTransfer(
+            OutputValue::Coin(amount),
+            destination.clone(),
+            timelock.clone(),
+        )]);
+        let fee: Amount = current_fee_rate
+            .compute_fee(output_size + input_size + input_sig_size)
+            .map_err(|_| UtxoSelectorError::AmountArithmeticError)?
+            .into();
+        let amount_after_fee =
+            (amount - fee).ok_or(WalletError::NotEnoughAmountToPayFee(amount, fee))?;
+
+        let output =
+            TxOutput::LockThenTransfer(OutputValue::Coin(amount_after_fee), destination, timelock);
+        let request = SendRequest::new().with_account_inputs([input]).with_outputs([output]);
+
+        let tx = self.sign_transaction(request, db_tx)?;
+        Ok(tx)
+    }
+
+    /// Create a transaction that decommissions the pool. The pool pledge `pool_balance`, minus
+    /// the fee, is sent to a new address of this account and is locked for the decommission
+    /// maturity period.
+    ///
+    /// The transaction is signed only if the decommission key belongs to this account.
+    /// Otherwise it has to be signed by the wallet holding the decommission key, see
+    /// `sign_raw_transaction`.
+    pub fn decommission_stake_pool(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteUnlocked,
+        pool_id: PoolId,
+        pool_balance: Amount,
+        median_time: BlockTimestamp,
+        current_fee_rate: FeeRate,
+    ) -> WalletResult<PartiallySignedTransaction> {
+        let (_, address) = self.key_chain.issue_address(db_tx, KeyPurpose::ReceiveFunds)?;
+        let destination = address.destination(self.chain_config.as_ref())?;
+
+        let (pool_outpoint, pool_utxo) = self
+            .get_utxos(
+                UtxoType::CreateStakePool | UtxoType::ProduceBlockFromStake,
+                median_time,
+                UtxoState::Confirmed.into(),
+            )
+            .into_iter()
+            .find_map(|(outpoint, (utxo, _token_id))| {
+                let utxo_pool_id = match utxo {
+                    TxOutput::CreateStakePool(pool_id, _)
+                    | TxOutput::ProduceBlockFromStake(_, pool_id) => Some(pool_id),
+                    TxOutput::Transfer(_, _)
+                    | TxOutput::LockThenTransfer(_, _, _)
+                    | TxOutput::Burn(_)
+                    | TxOutput::CreateDelegationId(_, _)
+                    | TxOutput::DelegateStaking(_, _) => None,
+                };
+                (utxo_pool_id == Some(&pool_id)).then(|| (outpoint, utxo.clone()))
+            })
+            .ok_or(WalletError::UnknownPoolId(pool_id))?;
+
+        // Spending a pool output in a transaction requires the decommission key
+        // and not the staker key
+        let decommission_key = self
+            .output_cache
+            .pool_decommission_key(&pool_id)
+            .ok_or(WalletError::UnknownPoolId(pool_id))?
+            .clone();
+        let input_sig_size = self.input_signature_size(&decommission_key)?;
+
+        // the transaction is included in the next block at the earliest
+        let next_block_height = self.account_info.best_block_height().next_height();
+        let maturity_distance: i64 =
+            self.chain_config.decommission_pool_maturity_distance(next_block_height).into();
+        let timelock = OutputTimeLock::ForBlockCount(
+            maturity_distance.try_into().expect("maturity distance is not negative"),
+        );
+
+        let input = TxInput::Utxo(pool_outpoint);
+        let input_size = serialization::Encode::encoded_size(&input);
+        let output_size = tx_size_with_outputs(&[TxOutput::LockThenTransfer(
+            OutputValue::Coin(pool_balance),
+            destination.clone(),
+            timelock.clone(),
+        )]);
+        let fee: Amount = current_fee_rate
+            .compute_fee(output_size + input_size + input_sig_size)
+            .map_err(|_| UtxoSelectorError::AmountArithmeticError)?
+            .into();
+        let amount_after_fee =
+            (pool_balance - fee).ok_or(WalletError::NotEnoughAmountToPayFee(pool_balance, fee))?;
+
+        let output =
+            TxOutput::LockThenTransfer(OutputValue::Coin(amount_after_fee), destination, timelock);
+        let request = SendRequest::new().with_inputs([(input, pool_utxo)]).with_outputs([output]);
+
+        let (tx, input_utxos) = request.into_transaction_and_utxos()?;
+        let ptx = PartiallySignedTransaction::new(
+            tx,
+            vec![None],
+            input_utxos,
+            vec![Some(decommission_key)],
+        )?;
+
+        self.sign_raw_transaction(ptx, db_tx)
+    }
+
+    /// Sign the inputs of the transaction that are not signed yet and that can be signed
+    /// with the keys of this account. The other inputs are left as they are.
+    pub fn sign_raw_transaction(
+        &self,
+        ptx: PartiallySignedTransaction,
+        db_tx: &impl WalletStorageReadUnlocked,
+    ) -> WalletResult<PartiallySignedTransaction> {
+        let input_utxos = ptx.input_utxos().iter().map(Option::as_ref).collect::<Vec<_>>();
+
+        let witnesses = ptx
+            .witnesses()
+            .iter()
+            .zip(ptx.destinations())
+            .enumerate()
+            .map(|(i, (witness, destination))| match (witness, destination) {
+                (Some(witness), Some(Destination::ClassicMultisig(_))) => {
+                    self.sign_partial_multisig_input(ptx.tx(), witness, &input_utxos, i, db_tx)
+                }
+                (Some(witness), _) => Ok(Some(witness.clone())),
+                (None, Some(destination)) => {
+                    self.sign_input(ptx.tx(), destination, &input_utxos, i, db_tx)
+                }
+                (None, None) => Ok(None),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(ptx.with_witnesses(witnesses)?)
+    }
+
+    /// Add a classic multisig challenge made of keys of this account and foreign keys,
+    /// so the outputs sent to it are tracked by this account. Returns the multisig address.
+    ///
+    /// Only outputs in the blocks scanned after the challenge is added are detected.
+    pub fn add_multisig_challenge(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        challenge: ClassicMultisigChallenge,
+    ) -> WalletResult<Address> {
+        let challenge_hash = self.key_chain.add_multisig_challenge(db_tx, challenge)?;
+        Ok(Address::new_from_destination(
+            self.chain_config.as_ref(),
+            &Destination::ClassicMultisig(challenge_hash),
+        )?)
+    }
+
+    pub fn get_multisig_challenges(
+        &self,
+    ) -> WalletResult<Vec<(Address, ClassicMultisigChallenge)>> {
+        self.key_chain
+            .get_multisig_challenges()
+            .map(|(challenge_hash, challenge)| {
+                let address = Address::new_from_destination(
+                    self.chain_config.as_ref(),
+                    &Destination::ClassicMultisig(*challenge_hash),
+                )?;
+                Ok((address, challenge.clone()))
+            })
+            .collect()
+    }
+
+    /// Create a transaction that spends UTXOs locked to the multisig challenge with the
+    /// provided hash, the change is sent back to the multisig.
+    ///
+    /// The transaction is signed with the keys of this account that are part of the challenge,
+    /// the remaining signatures have to be added by the other parties, see `sign_raw_transaction`.
+    pub fn create_transaction_from_multisig(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteUnlocked,
+        challenge_hash: PublicKeyHash,
+        request: SendRequest,
+        median_time: BlockTimestamp,
+        current_fee_rate: FeeRate,
+        consolidate_fee_rate: FeeRate,
+    ) -> WalletResult<PartiallySignedTransaction> {
+        if self.key_chain.get_multisig_challenge(&challenge_hash).is_none() {
+            return Err(WalletError::UnknownMultisigChallenge(challenge_hash));
+        }
+
+        let request = self.select_inputs_for_send_request_from(
+            request,
+            InputsSource::Multisig(challenge_hash),
+            db_tx,
+            median_time,
+            current_fee_rate,
+            consolidate_fee_rate,
+        )?;
+
+        let (tx, input_utxos) = request.into_transaction_and_utxos()?;
+        let destinations =
+            vec![Some(Destination::ClassicMultisig(challenge_hash)); tx.inputs().len()];
+        let witnesses = vec![None; tx.inputs().len()];
+        let ptx = PartiallySignedTransaction::new(tx, witnesses, input_utxos, destinations)?;
+
+        self.sign_raw_transaction(ptx, db_tx)
+    }
+
+    /// Start signing the input locked to the MuSig2 aggregation of the public keys
+    /// with the first of the keys that belongs to this account
+    pub fn start_musig2_input_session(
+        &self,
+        ptx: &PartiallySignedTransaction,
+        input_index: usize,
+        public_keys: &[PublicKey],
+        db_tx: &impl WalletStorageReadUnlocked,
+    ) -> WalletResult<MuSig2InputSession> {
+        MuSig2InputSession::new(ptx, input_index, public_keys, |public_key| {
+            self.get_private_key_for_public_key(public_key, db_tx)
+        })
+    }
+
+    pub fn musig2_partial_sign(
+        &self,
+        session: &mut MuSig2InputSession,
+        public_nonces: Vec<PublicNonce>,
+        db_tx: &impl WalletStorageReadUnlocked,
+    ) -> WalletResult<PartialSignature> {
+        let private_key = self
+            .get_private_key_for_public_key(session.signer_public_key(), db_tx)?
+            .ok_or(WalletError::KeyChainError(KeyChainError::NoPrivateKeyFound))?;
+        session.sign(public_nonces, &private_key)
+    }
+
+    fn get_private_key_for_public_key(
+        &self,
+        public_key: &PublicKey,
+        db_tx: &impl WalletStorageReadUnlocked,
+    ) -> WalletResult<Option<PrivateKey>> {
+        let private_key = self
+            .key_chain
+            .get_private_key_for_destination(&Destination::PublicKey(public_key.clone()), db_tx)?
+            .map(|private_key| private_key.private_key());
+        Ok(private_key)
+    }
+
     pub fn get_pos_gen_block_data(
         &self,
         db_tx: &impl WalletStorageReadUnlocked,
@@ -476,45 +1022,22 @@ impl Account {
     ) -> WalletResult<SignedTransaction> {
         let (tx, utxos) = req.into_transaction_and_utxos()?;
         let inputs = tx.inputs();
-        let input_utxos = utxos.iter().map(Some).collect::<Vec<_>>();
+        let input_utxos = utxos.iter().map(Option::as_ref).collect::<Vec<_>>();
         if utxos.len() != inputs.len() {
             return Err(
                 TransactionSigError::InvalidUtxoCountVsInputs(utxos.len(), inputs.len()).into(),
             );
         }
 
-        let witnesses = utxos
+        let witnesses = input_utxos
             .iter()
+            .zip(inputs)
             .enumerate()
-            .map(|(i, utxo)| {
-                // Get the destination from this utxo
-                let destination = Self::get_tx_output_destination(utxo).ok_or_else(|| {
-                    WalletError::UnsupportedTransactionOutput(Box::new(utxo.clone()))
-                })?;
-
-                if *destination == Destination::AnyoneCanSpend {
-                    Ok(InputWitness::NoSignature(None))
-                } else {
-                    let private_key = self
-                        .key_chain
-                        .get_private_key_for_destination(destination, db_tx)?
-                        .ok_or(WalletError::KeyChainError(KeyChainError::NoPrivateKeyFound))?
-                        .private_key();
-
-                    let sighash_type =
-                        SigHashType::try_from(SigHashType::ALL).expect("Should not fail");
-
-                    StandardInputSignature::produce_uniparty_signature_for_input(
-                        &private_key,
-                        sighash_type,
-                        destination.clone(),
-                        &tx,
-                        &input_utxos,
-                        i,
-                    )
-                    .map(InputWitness::Standard)
-                    .map_err(WalletError::TransactionSig)
-                }
+            .map(|(i, (utxo, input))| {
+                let destination = self.get_input_destination(input, *utxo)?;
+
+                self.sign_input(&tx, destination, &input_utxos, i, db_tx)?
+                    .ok_or(WalletError::KeyChainError(KeyChainError::NoPrivateKeyFound))
             })
             .collect::<Result<Vec<InputWitness>, _>>()?;
 
@@ -523,10 +1046,150 @@ impl Account {
         Ok(tx)
     }
 
+    /// Add the signatures of this account to a partial classic multisig witness.
+    /// Witnesses of other kinds are returned unchanged.
+    fn sign_partial_multisig_input(
+        &self,
+        tx: &Transaction,
+        witness: &InputWitness,
+        input_utxos: &[Option<&TxOutput>],
+        input_index: usize,
+        db_tx: &impl WalletStorageReadUnlocked,
+    ) -> WalletResult<Option<InputWitness>> {
+        let signature = match witness {
+            InputWitness::Standard(signature) => signature,
+            InputWitness::NoSignature(_) => return Ok(Some(witness.clone())),
+        };
+        let spend = match AuthorizedClassicalMultisigSpend::from_data(signature.raw_signature()) {
+            Ok(spend) => spend,
+            Err(_) => return Ok(Some(witness.clone())),
+        };
+
+        let sighash = signature_hash(signature.sighash_type(), tx, input_utxos, input_index)?;
+        let spend = self.sign_multisig_spend(spend, &sighash, db_tx)?;
+
+        Ok(Some(InputWitness::Standard(StandardInputSignature::new(
+            signature.sighash_type(),
+            serialization::Encode::encode(&spend),
+        ))))
+    }
+
+    /// Sign the challenge with all the keys of this account that are part of it,
+    /// until enough signatures are collected
+    fn sign_multisig_spend(
+        &self,
+        mut spend: AuthorizedClassicalMultisigSpend,
+        sighash: &H256,
+        db_tx: &impl WalletStorageReadUnlocked,
+    ) -> WalletResult<AuthorizedClassicalMultisigSpend> {
+        let challenge = spend.challenge().clone();
+        for (key_index, public_key) in challenge.public_keys().iter().enumerate() {
+            if spend.available_signatures_count() >= challenge.min_required_signatures() as usize {
+                break;
+            }
+
+            let key_index = key_index as u8;
+            if spend.signatures().contains_key(&key_index) {
+                continue;
+            }
+
+            let private_key = match self.key_chain.get_private_key_for_destination(
+                &Destination::PublicKey(public_key.clone()),
+                db_tx,
+            )? {
+                Some(private_key) => private_key.private_key(),
+                None => continue,
+            };
+
+            spend = sign_classical_multisig_spending(
+                &self.chain_config,
+                key_index,
+                &private_key,
+                &challenge,
+                sighash,
+                spend,
+            )?
+            .take();
+        }
+
+        Ok(spend)
+    }
+
+    /// Start a classic multisig witness for the input with the signatures of this account.
+    /// Returns None if the challenge was not added to this account.
+    fn sign_multisig_input(
+        &self,
+        tx: &Transaction,
+        challenge_hash: &PublicKeyHash,
+        input_utxos: &[Option<&TxOutput>],
+        input_index: usize,
+        db_tx: &impl WalletStorageReadUnlocked,
+    ) -> WalletResult<Option<InputWitness>> {
+        let challenge = match self.key_chain.get_multisig_challenge(challenge_hash) {
+            Some(challenge) => challenge.clone(),
+            None => return Ok(None),
+        };
+
+        let sighash_type = SigHashType::try_from(SigHashType::ALL).expect("Should not fail");
+        let sighash = signature_hash(sighash_type, tx, input_utxos, input_index)?;
+        let spend = self.sign_multisig_spend(
+            AuthorizedClassicalMultisigSpend::new_empty(challenge),
+            &sighash,
+            db_tx,
+        )?;
+
+        Ok(Some(InputWitness::Standard(StandardInputSignature::new(
+            sighash_type,
+            serialization::Encode::encode(&spend),
+        ))))
+    }
+
+    /// Sign the input with the key of the destination.
+    /// Returns None if the key does not belong to this account.
+    fn sign_input(
+        &self,
+        tx: &Transaction,
+        destination: &Destination,
+        input_utxos: &[Option<&TxOutput>],
+        input_index: usize,
+        db_tx: &impl WalletStorageReadUnlocked,
+    ) -> WalletResult<Option<InputWitness>> {
+        if *destination == Destination::AnyoneCanSpend {
+            return Ok(Some(InputWitness::NoSignature(None)));
+        }
+
+        if let Destination::ClassicMultisig(challenge_hash) = destination {
+            return self.sign_multisig_input(tx, challenge_hash, input_utxos, input_index, db_tx);
+        }
+
+        let private_key =
+            match self.key_chain.get_private_key_for_destination(destination, db_tx)? {
+                Some(private_key) => private_key.private_key(),
+                None => return Ok(None),
+            };
+
+        let sighash_type = SigHashType::try_from(SigHashType::ALL).expect("Should not fail");
+
+        StandardInputSignature::produce_uniparty_signature_for_input(
+            &private_key,
+            sighash_type,
+            destination.clone(),
+            tx,
+            input_utxos,
+            input_index,
+        )
+        .map(|signature| Some(InputWitness::Standard(signature)))
+        .map_err(WalletError::TransactionSig)
+    }
+
     pub fn account_index(&self) -> U31 {
         self.key_chain.account_index()
     }
 
+    pub fn account_public_key(&self) -> &ExtendedPublicKey {
+        self.key_chain.account_public_key()
+    }
+
     /// Get the id of this account
     pub fn get_account_id(&self) -> AccountId {
         self.key_chain.get_account_id()
@@ -554,6 +1217,27 @@ impl Account {
         self.key_chain.get_all_issued_addresses()
     }
 
+    /// Get the destination that must sign the input, either from the spent UTXO or,
+    /// for inputs spending from an account, from the account data
+    fn get_input_destination<'a>(
+        &'a self,
+        input: &TxInput,
+        utxo: Option<&'a TxOutput>,
+    ) -> WalletResult<&'a Destination> {
+        match (input, utxo) {
+            (_, Some(utxo)) => Self::get_tx_output_destination(utxo)
+                .ok_or_else(|| WalletError::UnsupportedTransactionOutput(Box::new(utxo.clone()))),
+            (TxInput::Account(outpoint), None) => match outpoint.account() {
+                AccountSpending::Delegation(delegation_id, _) => self
+                    .output_cache
+                    .delegation_data(delegation_id)
+                    .map(|data| &data.destination)
+                    .ok_or(WalletError::DelegationNotFound(*delegation_id)),
+            },
+            (TxInput::Utxo(outpoint), None) => Err(WalletError::MissingUtxo(outpoint.clone())),
+        }
+    }
+
     fn get_tx_output_destination(txo: &TxOutput) -> Option<&Destination> {
         // TODO: Reuse code from TxVerifier
         match txo {
@@ -569,13 +1253,39 @@ impl Account {
     /// Return true if this transaction output is can be spent by this account or if it is being
     /// watched.
     fn is_mine_or_watched(&self, txo: &TxOutput) -> bool {
+        Self::get_tx_output_destination(txo)
+            .map_or(false, |d| self.is_destination_mine_or_watched(d))
+    }
+
+    fn is_destination_mine_or_watched(&self, destination: &Destination) -> bool {
         // TODO: Should we really report `AnyoneCanSpend` as own?
-        Self::get_tx_output_destination(txo).map_or(false, |d| match d {
+        match destination {
             Destination::Address(pkh) => self.key_chain.is_public_key_hash_mine(pkh),
             Destination::PublicKey(pk) => self.key_chain.is_public_key_mine(pk),
             Destination::AnyoneCanSpend => true,
-            Destination::ScriptHash(_) | Destination::ClassicMultisig(_) => false,
-        })
+            Destination::ClassicMultisig(challenge_hash) => {
+                self.key_chain.get_multisig_challenge(challenge_hash).is_some()
+            }
+            Destination::ScriptHash(_) => false,
+        }
+    }
+
+    /// Return the encoded size of an input signature
+    fn input_signature_size(&self, destination: &Destination) -> WalletResult<usize> {
+        // Sizes calculated upfront
+        match destination {
+            Destination::Address(_) => Ok(103),
+            Destination::PublicKey(_) => Ok(69),
+            Destination::AnyoneCanSpend => Ok(2),
+            Destination::ClassicMultisig(challenge_hash) => self
+                .key_chain
+                .get_multisig_challenge(challenge_hash)
+                .map(multisig_input_signature_size)
+                .ok_or_else(|| WalletError::UnsupportedInputDestination(destination.clone())),
+            Destination::ScriptHash(_) => Err(WalletError::UnsupportedInputDestination(
+                destination.clone(),
+            )),
+        }
     }
 
     fn mark_outputs_as_seen(
@@ -596,7 +1306,18 @@ impl Account {
         db_tx: &mut impl WalletStorageWriteLocked,
         output: &TxOutput,
     ) -> WalletResult<bool> {
-        if let Some(d) = Self::get_tx_output_destination(output) {
+        let destination = match output {
+            // The delegation owner is not spending the output itself, but the delegation still
+            // needs to be tracked by the wallet
+            TxOutput::CreateDelegationId(d, _) => Some(d),
+            TxOutput::Transfer(_, _)
+            | TxOutput::LockThenTransfer(_, _, _)
+            | TxOutput::Burn(_)
+            | TxOutput::CreateStakePool(_, _)
+            | TxOutput::ProduceBlockFromStake(_, _)
+            | TxOutput::DelegateStaking(_, _) => Self::get_tx_output_destination(output),
+        };
+        if let Some(d) = destination {
             match d {
                 Destination::Address(pkh) => {
                     let found = self.key_chain.mark_public_key_hash_as_used(db_tx, pkh)?;
@@ -611,7 +1332,12 @@ impl Account {
                     }
                 }
                 Destination::AnyoneCanSpend => return Ok(true),
-                Destination::ClassicMultisig(_) | Destination::ScriptHash(_) => {}
+                Destination::ClassicMultisig(challenge_hash) => {
+                    if self.key_chain.get_multisig_challenge(challenge_hash).is_some() {
+                        return Ok(true);
+                    }
+                }
+                Destination::ScriptHash(_) => {}
             }
         }
         Ok(false)
@@ -654,6 +1380,71 @@ impl Account {
         all_outputs
     }
 
+    /// Set the frozen flag and the label of a UTXO of this account. Frozen UTXOs are skipped
+    /// by the automatic selection of inputs, so they are only spent when they are unfrozen.
+    pub fn set_utxo_mark(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        outpoint: UtxoOutPoint,
+        mark: UtxoMark,
+    ) -> WalletResult<()> {
+        ensure!(
+            self.output_cache.get_txo(&outpoint).is_some(),
+            WalletError::MissingUtxo(outpoint)
+        );
+
+        let id = AccountUtxoId::new(self.get_account_id(), outpoint.clone());
+        if mark.is_empty() {
+            db_tx.del_utxo_mark(&id)?;
+            self.utxo_marks.remove(&outpoint);
+        } else {
+            db_tx.set_utxo_mark(&id, &mark)?;
+            self.utxo_marks.insert(outpoint, mark);
+        }
+        Ok(())
+    }
+
+    pub fn get_utxo_mark(&self, outpoint: &UtxoOutPoint) -> UtxoMark {
+        self.utxo_marks.get(outpoint).cloned().unwrap_or_default()
+    }
+
+    pub fn get_utxo_marks(&self) -> &BTreeMap<UtxoOutPoint, UtxoMark> {
+        &self.utxo_marks
+    }
+
+    fn is_utxo_frozen(&self, outpoint: &UtxoOutPoint) -> bool {
+        self.utxo_marks.get(outpoint).map_or(false, |mark| mark.frozen)
+    }
+
+    /// Delete the marks of the outputs that are consumed, they can't be spent anymore
+    fn del_utxo_marks(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        outpoints: impl IntoIterator<Item = UtxoOutPoint>,
+    ) -> WalletResult<()> {
+        for outpoint in outpoints {
+            if self.utxo_marks.remove(&outpoint).is_some() {
+                db_tx.del_utxo_mark(&AccountUtxoId::new(self.get_account_id(), outpoint))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Delete the marks of the outputs of the abandoned transactions
+    fn del_abandoned_utxo_marks(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        abandoned_txs: &[WalletTx],
+    ) -> WalletResult<()> {
+        let outpoints = abandoned_txs
+            .iter()
+            .flat_map(|tx| {
+                (0..tx.outputs().len()).map(|index| UtxoOutPoint::new(tx.id(), index as u32))
+            })
+            .collect::<Vec<_>>();
+        self.del_utxo_marks(db_tx, outpoints)
+    }
+
     pub fn get_transaction_list(&self, skip: usize, count: usize) -> WalletResult<TransactionList> {
         get_transaction_list(&self.key_chain, &self.output_cache, skip, count)
     }
@@ -705,10 +1496,29 @@ impl Account {
                 .output_cache
                 .get_txo(outpoint)
                 .map_or(false, |txo| self.is_mine_or_watched(txo)),
-            TxInput::Account(_) => false,
+            TxInput::Account(outpoint) => match outpoint.account() {
+                AccountSpending::Delegation(delegation_id, _) => {
+                    self.output_cache.delegation_data(delegation_id).map_or(false, |data| {
+                        self.is_destination_mine_or_watched(&data.destination)
+                    })
+                }
+            },
         });
         let relevant_outputs = self.mark_outputs_as_seen(db_tx, tx.outputs())?;
         if relevant_inputs || relevant_outputs {
+            // The marks of the outputs spent by an unconfirmed transaction are kept,
+            // because the outputs become spendable again if the transaction is abandoned
+            let spent_outpoints: Vec<UtxoOutPoint> = match tx.state() {
+                TxState::Confirmed(_, _) => {
+                    tx.inputs().iter().filter_map(|input| input.utxo_outpoint()).cloned().collect()
+                }
+                TxState::Inactive
+                | TxState::Conflicted(_)
+                | TxState::InMempool
+                | TxState::Abandoned => Vec::new(),
+            };
+            self.del_utxo_marks(db_tx, spent_outpoints)?;
+
             let id = AccountWalletTxId::new(self.get_account_id(), tx.id());
             db_tx.set_transaction(&id, &tx)?;
             wallet_events.set_transaction(&id, &tx);
@@ -842,8 +1652,331 @@ impl Account {
         self.output_cache.pending_transactions()
     }
 
-    pub fn abandon_transaction(&mut self, tx_id: Id<Transaction>) -> WalletResult<()> {
-        self.output_cache.abandon_transaction(tx_id)
+    pub fn abandon_transaction(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        tx_id: Id<Transaction>,
+    ) -> WalletResult<()> {
+        let abandoned_txs = self.output_cache.abandon_transaction(tx_id)?;
+        self.del_abandoned_utxo_marks(db_tx, &abandoned_txs)
+    }
+
+    /// Create a transaction that replaces the pending transaction with the same inputs and
+    /// outputs, but pays the fee of the new fee rate. The fee is taken from the change output
+    /// and if the change is not enough more inputs are selected.
+    ///
+    /// The replaced transaction must signal that it is replaceable and its inputs must belong
+    /// to this account. It is abandoned, together with its descendants, by
+    /// `abandon_replaced_transaction` once the replacement is accepted by the node.
+    pub fn create_replacement_transaction(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteUnlocked,
+        tx_id: Id<Transaction>,
+        median_time: BlockTimestamp,
+        new_fee_rate: FeeRate,
+        consolidate_fee_rate: FeeRate,
+    ) -> WalletResult<SignedTransaction> {
+        let original_tx = self.output_cache.get_pending_transaction(tx_id)?.clone();
+        ensure!(
+            original_tx.is_replaceable(),
+            WalletError::TransactionNotReplaceable(tx_id)
+        );
+
+        let inputs = original_tx
+            .inputs()
+            .iter()
+            .map(|input| match input {
+                TxInput::Utxo(outpoint) => self
+                    .output_cache
+                    .get_txo(outpoint)
+                    .map(|utxo| (input.clone(), utxo.clone()))
+                    .ok_or_else(|| WalletError::MissingUtxo(outpoint.clone())),
+                TxInput::Account(_) => Err(WalletError::NotImplemented(
+                    "Replacing a transaction that spends from an account",
+                )),
+            })
+            .collect::<WalletResult<Vec<_>>>()?;
+        let original_fee = coin_fee(
+            inputs.iter().map(|(_, utxo)| utxo),
+            original_tx.outputs().iter(),
+        )?;
+
+        // The change output is created again with what is left after paying the new fee
+        let mut outputs = original_tx.outputs().to_vec();
+        let change_output = outputs
+            .iter()
+            .position(|output| self.is_coin_change_output(output))
+            .map(|index| outputs.remove(index));
+        let change_destination =
+            change_output.as_ref().and_then(Self::get_tx_output_destination).cloned();
+        let available = (original_fee
+            + change_output.as_ref().and_then(coin_output_amount).unwrap_or(Amount::ZERO))
+        .ok_or(WalletError::OutputAmountOverflow)?;
+
+        let request = SendRequest::new().replaceable().with_inputs(inputs).with_outputs(outputs);
+        let fee_without_change = self.estimate_fee(&request, new_fee_rate)?;
+        let (coin_change_fee, _) = coin_and_token_output_change_fees(new_fee_rate)?;
+        let fee_with_change =
+            (fee_without_change + coin_change_fee).ok_or(WalletError::OutputAmountOverflow)?;
+
+        let (request, new_fee, change_amount) = if available > fee_with_change {
+            let change_amount = (available - fee_with_change).expect("checked above");
+            (request, fee_with_change, change_amount)
+        } else if available >= fee_without_change {
+            // The change is too small to be worth an output, it all goes to the fee
+            (request, available, Amount::ZERO)
+        } else {
+            let missing_fee = (fee_without_change - available).expect("checked above");
+            let utxos = self
+                .get_utxos(
+                    UtxoType::Transfer | UtxoType::LockThenTransfer,
+                    median_time,
+                    UtxoState::Confirmed.into(),
+                )
+                .into_iter()
+                .filter(|(outpoint, (txo, _))| {
+                    !self.is_utxo_frozen(outpoint)
+                        && coin_output_amount(txo).is_some()
+                        && Self::get_tx_output_destination(txo).map_or(false, |destination| {
+                            InputsSource::OwnKeys.contains(destination)
+                        })
+                })
+                .map(|(outpoint, (txo, _))| {
+                    self.make_output_group(&outpoint, txo, new_fee_rate, consolidate_fee_rate)
+                })
+                .filter(|group| group.as_ref().map_or(true, |group| group.value > group.fee))
+                .try_collect()?;
+            let selection_result = select_coins(
+                utxos,
+                missing_fee,
+                PayFee::PayFeeWithThisCurrency,
+                coin_change_fee,
+            )?;
+
+            let change_amount = selection_result.get_change();
+            let mut new_fee = (fee_without_change + selection_result.get_total_fees())
+                .ok_or(WalletError::OutputAmountOverflow)?;
+            if change_amount > Amount::ZERO {
+                new_fee = (new_fee + coin_change_fee).ok_or(WalletError::OutputAmountOverflow)?;
+            }
+            (
+                request.with_inputs(selection_result.into_output_pairs()),
+                new_fee,
+                change_amount,
+            )
+        };
+
+        let request = if change_amount > Amount::ZERO {
+            let change_destination = match change_destination {
+                Some(destination) => destination,
+                None => self
+                    .get_new_address(db_tx, KeyPurpose::Change)?
+                    .1
+                    .destination(self.chain_config.as_ref())?,
+            };
+            request.with_outputs([TxOutput::Transfer(
+                OutputValue::Coin(change_amount),
+                change_destination,
+            )])
+        } else {
+            request
+        };
+
+        let tx = self.sign_transaction(request, db_tx)?;
+
+        // The replacement must also pay for its own relay, otherwise the mempool rejects it
+        let relay_fee: Amount = INCREMENTAL_RELAY_FEE_RATE
+            .compute_fee(serialization::Encode::encoded_size(&tx))
+            .map_err(|_| UtxoSelectorError::AmountArithmeticError)?
+            .into();
+        let min_fee = (original_fee + relay_fee).ok_or(WalletError::OutputAmountOverflow)?;
+        ensure!(
+            new_fee >= min_fee,
+            WalletError::ReplacementFeeTooLow(min_fee, new_fee)
+        );
+
+        Ok(tx)
+    }
+
+    /// Mark the transaction replaced by a transaction from `create_replacement_transaction`
+    /// and its descendants as abandoned
+    pub fn abandon_replaced_transaction(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        wallet_events: &mut impl WalletEvents,
+        tx_id: Id<Transaction>,
+    ) -> WalletResult<()> {
+        let abandoned_txs = self.output_cache.abandon_replaced_transaction(tx_id)?;
+        for tx in &abandoned_txs {
+            let id = AccountWalletTxId::new(self.get_account_id(), tx.id());
+            db_tx.set_transaction(&id, tx)?;
+            wallet_events.set_transaction(&id, tx);
+        }
+        self.del_abandoned_utxo_marks(db_tx, &abandoned_txs)
+    }
+
+    /// Create a transaction that spends the coins sent to this account by the pending
+    /// transaction (the parent) to a new change address, with a fee that is high enough for
+    /// both transactions together to pay the fee rate. Block producers select transactions
+    /// together with their ancestors, so this speeds up the confirmation of a parent that can't
+    /// be replaced.
+    pub fn create_child_pays_for_parent_transaction(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteUnlocked,
+        parent_tx_id: Id<Transaction>,
+        median_time: BlockTimestamp,
+        fee_rate: FeeRate,
+    ) -> WalletResult<SignedTransaction> {
+        let parent_tx = self.output_cache.get_pending_transaction(parent_tx_id)?.clone();
+        let parent_source_id = OutPointSourceId::from(parent_tx_id);
+
+        let inputs: Vec<(TxInput, TxOutput)> = self
+            .get_utxos(
+                UtxoType::Transfer | UtxoType::LockThenTransfer,
+                median_time,
+                UtxoState::Confirmed | UtxoState::InMempool | UtxoState::Inactive,
+            )
+            .into_iter()
+            .filter(|(outpoint, (txo, _))| {
+                outpoint.tx_id() == parent_source_id
+                    && !self.is_utxo_frozen(outpoint)
+                    && coin_output_amount(txo).is_some()
+                    && Self::get_tx_output_destination(txo).map_or(false, |destination| {
+                        InputsSource::OwnKeys.contains(destination)
+                    })
+            })
+            .map(|(outpoint, (txo, _))| (outpoint.into(), txo.clone()))
+            .collect();
+        ensure!(
+            !inputs.is_empty(),
+            WalletError::NoOutputsToSpend(parent_tx_id)
+        );
+        let total_amount = inputs
+            .iter()
+            .filter_map(|(_, txo)| coin_output_amount(txo))
+            .sum::<Option<Amount>>()
+            .ok_or(WalletError::OutputAmountOverflow)?;
+
+        // The fee of the parent is known only if all its inputs spend outputs known to the
+        // wallet, otherwise the child pays for the whole parent
+        let parent_utxos = parent_tx
+            .inputs()
+            .iter()
+            .map(|input| {
+                input.utxo_outpoint().and_then(|outpoint| self.output_cache.get_txo(outpoint))
+            })
+            .collect::<Vec<_>>();
+        let parent_fee = parent_utxos
+            .iter()
+            .copied()
+            .collect::<Option<Vec<_>>>()
+            .map(|utxos| coin_fee(utxos.into_iter(), parent_tx.outputs().iter()))
+            .transpose()?
+            .unwrap_or(Amount::ZERO);
+        let parent_size = self.estimate_signed_tx_size(&parent_tx, &parent_utxos);
+
+        let (_, change_address) = self.get_new_address(db_tx, KeyPurpose::Change)?;
+        let mut request =
+            SendRequest::new().with_inputs(inputs).with_outputs([make_address_output(
+                self.chain_config.as_ref(),
+                change_address.clone(),
+                total_amount,
+            )?]);
+        let child_fee = self.estimate_fee(&request, fee_rate)?;
+        let package_fee: Amount = fee_rate
+            .compute_fee(parent_size + self.estimate_size(&request)?)
+            .map_err(|_| UtxoSelectorError::AmountArithmeticError)?
+            .into();
+        let child_fee = std::cmp::max(
+            child_fee,
+            (package_fee - parent_fee).unwrap_or(Amount::ZERO),
+        );
+
+        let amount_after_fee = (total_amount - child_fee).ok_or(
+            WalletError::NotEnoughAmountToPayFee(total_amount, child_fee),
+        )?;
+        *request.get_outputs_mut() = vec![make_address_output(
+            self.chain_config.as_ref(),
+            change_address,
+            amount_after_fee,
+        )?];
+
+        self.sign_transaction(request, db_tx)
+    }
+
+    /// Return true if the output sends coins to a change address of this account
+    fn is_coin_change_output(&self, output: &TxOutput) -> bool {
+        coin_output_amount(output).is_some()
+            && Self::get_tx_output_destination(output).map_or(false, |destination| {
+                self.key_chain
+                    .get_leaf_key_chain(KeyPurpose::Change)
+                    .get_child_num_from_destination(destination)
+                    .is_some()
+            })
+    }
+
+    /// Estimate the size of the transaction of the request once all its inputs are signed
+    fn estimate_size(&self, request: &SendRequest) -> WalletResult<usize> {
+        request.inputs().iter().zip(request.utxos()).try_fold(
+            tx_size_with_outputs(request.outputs()),
+            |size, (input, utxo)| -> WalletResult<usize> {
+                Ok(size + self.input_size_with_signature(input, utxo.as_ref())?)
+            },
+        )
+    }
+
+    fn estimate_fee(&self, request: &SendRequest, fee_rate: FeeRate) -> WalletResult<Amount> {
+        let fee = fee_rate
+            .compute_fee(self.estimate_size(request)?)
+            .map_err(|_| UtxoSelectorError::AmountArithmeticError)?;
+        Ok(fee.into())
+    }
+
+    /// Estimate the size of a transaction that may also have inputs from other wallets,
+    /// those inputs are assumed to be signed with the key of an address
+    fn estimate_signed_tx_size(
+        &self,
+        tx: &Transaction,
+        input_utxos: &[Option<&TxOutput>],
+    ) -> usize {
+        let foreign_input_destination = Destination::Address(PublicKeyHash::from_low_u64_ne(0));
+        tx.inputs().iter().zip(input_utxos).fold(
+            tx_size_with_outputs(tx.outputs()),
+            |size, (input, utxo)| {
+                let destination =
+                    self.get_input_destination(input, *utxo).unwrap_or(&foreign_input_destination);
+                let signature_size = self
+                    .input_signature_size(destination)
+                    .or_else(|_| self.input_signature_size(&foreign_input_destination))
+                    .expect("an address signature size is known");
+                size + serialization::Encode::encoded_size(input) + signature_size
+            },
+        )
+    }
+}
+
+/// The UTXOs of the account that can be selected as inputs of a transaction
+enum InputsSource {
+    /// UTXOs that can be spent with the keys of this account alone
+    OwnKeys,
+    /// UTXOs locked to the classic multisig challenge with this hash
+    Multisig(PublicKeyHash),
+}
+
+impl InputsSource {
+    fn contains(&self, destination: &Destination) -> bool {
+        match self {
+            InputsSource::OwnKeys => match destination {
+                Destination::Address(_)
+                | Destination::PublicKey(_)
+                | Destination::ScriptHash(_)
+                | Destination::AnyoneCanSpend => true,
+                Destination::ClassicMultisig(_) => false,
+            },
+            InputsSource::Multisig(challenge_hash) => {
+                *destination == Destination::ClassicMultisig(*challenge_hash)
+            }
+        }
     }
 }
 
@@ -870,9 +2003,9 @@ fn group_outputs<T, Grouped: Clone>(
                 v.clone()
             }
             TxOutput::CreateStakePool(_, stake) => OutputValue::Coin(stake.value()),
-            TxOutput::ProduceBlockFromStake(_, _)
-            | TxOutput::CreateDelegationId(_, _)
-            | TxOutput::DelegateStaking(_, _) => {
+            TxOutput::DelegateStaking(amount, _) => OutputValue::Coin(*amount),
+            TxOutput::CreateDelegationId(_, _) => OutputValue::Coin(Amount::ZERO),
+            TxOutput::ProduceBlockFromStake(_, _) => {
                 return Err(WalletError::UnsupportedTransactionOutput(Box::new(
                     get_tx_output(&output).clone(),
                 )))
@@ -893,7 +2026,23 @@ fn group_outputs<T, Grouped: Clone>(
 
                         combiner(total_token_amount, &output, token_transfer.amount)?;
                     }
-                    TokenData::TokenIssuance(_) | TokenData::NftIssuance(_) => {}
+                    TokenData::TokenReissueV1 {
+                        token_id,
+                        amount_to_issue,
+                    } => {
+                        let total_token_amount = tokens_grouped
+                            .entry(Currency::Token(*token_id))
+                            .or_insert_with(|| init.clone());
+
+                        combiner(total_token_amount, &output, *amount_to_issue)?;
+                    }
+                    TokenData::TokenIssuance(_)
+                    | TokenData::NftIssuance(_)
+                    | TokenData::TokenIssuanceV1(_)
+                    | TokenData::LockTokenSupplyV1 { .. }
+                    | TokenData::ChangeTokenAuthorityV1 { .. }
+                    | TokenData::FreezeTokenV1 { .. }
+                    | TokenData::UnfreezeTokenV1 { .. } => {}
                 }
             }
         }
@@ -951,6 +2100,28 @@ fn group_utxos_for_input<T, Grouped: Clone>(
 
                         combiner(total_token_amount, &output, token_issuance.amount_to_issue)?;
                     }
+                    TokenData::TokenIssuanceV1(token_issuance) => {
+                        let token_id = get_token_id(&output)?;
+                        let total_token_amount = tokens_grouped
+                            .entry(Currency::Token(token_id))
+                            .or_insert_with(|| init.clone());
+
+                        combiner(total_token_amount, &output, token_issuance.amount_to_issue)?;
+                    }
+                    TokenData::TokenReissueV1 {
+                        token_id,
+                        amount_to_issue,
+                    } => {
+                        let total_token_amount = tokens_grouped
+                            .entry(Currency::Token(*token_id))
+                            .or_insert_with(|| init.clone());
+
+                        combiner(total_token_amount, &output, *amount_to_issue)?;
+                    }
+                    TokenData::LockTokenSupplyV1 { .. }
+                    | TokenData::ChangeTokenAuthorityV1 { .. }
+                    | TokenData::FreezeTokenV1 { .. }
+                    | TokenData::UnfreezeTokenV1 { .. } => {}
                     TokenData::NftIssuance(_) => {
                         let token_id = get_token_id(&output)?;
                         let total_token_amount = tokens_grouped
@@ -968,6 +2139,52 @@ fn group_utxos_for_input<T, Grouped: Clone>(
     Ok(tokens_grouped)
 }
 
+/// Return the coins held by the output if it can be spent as a coin input
+fn coin_output_amount(output: &TxOutput) -> Option<Amount> {
+    match output {
+        TxOutput::Transfer(value, _) | TxOutput::LockThenTransfer(value, _, _) => {
+            value.coin_amount()
+        }
+        TxOutput::Burn(_)
+        | TxOutput::CreateStakePool(_, _)
+        | TxOutput::ProduceBlockFromStake(_, _)
+        | TxOutput::CreateDelegationId(_, _)
+        | TxOutput::DelegateStaking(_, _) => None,
+    }
+}
+
+/// Return the fee paid in coins by a transaction spending the UTXOs
+fn coin_fee<'a>(
+    input_utxos: impl Iterator<Item = &'a TxOutput>,
+    outputs: impl Iterator<Item = &'a TxOutput>,
+) -> WalletResult<Amount> {
+    let sum_coins = |grouped: WalletResult<BTreeMap<Currency, Amount>>| {
+        grouped.map(|mut amounts| amounts.remove(&Currency::Coin).unwrap_or(Amount::ZERO))
+    };
+    let add_amount = |total: &mut Amount, _: &&TxOutput, amount: Amount| -> WalletResult<()> {
+        *total = (*total + amount).ok_or(WalletError::OutputAmountOverflow)?;
+        Ok(())
+    };
+
+    let inputs_amount = sum_coins(group_utxos_for_input(
+        input_utxos,
+        |&output| output,
+        add_amount,
+        // Only the coin amount is needed
+        |_| Ok(TokenId::zero()),
+        Amount::ZERO,
+    ))?;
+    let outputs_amount = sum_coins(group_outputs(
+        outputs,
+        |&output| output,
+        add_amount,
+        Amount::ZERO,
+    ))?;
+
+    (inputs_amount - outputs_amount)
+        .ok_or(WalletError::NotEnoughUtxo(inputs_amount, outputs_amount))
+}
+
 /// Return the encoded size for a SignedTransaction with specified outputs and empty inputs and
 /// signatures
 pub fn tx_size_with_outputs(outputs: &[TxOutput]) -> usize {
@@ -979,17 +2196,16 @@ pub fn tx_size_with_outputs(outputs: &[TxOutput]) -> usize {
     serialization::Encode::encoded_size(&tx)
 }
 
-/// Return the encoded size of an input signature
-fn input_signature_size(destination: &Destination) -> WalletResult<usize> {
-    // Sizes calculated upfront
-    match destination {
-        Destination::Address(_) => Ok(103),
-        Destination::PublicKey(_) => Ok(69),
-        Destination::AnyoneCanSpend => Ok(2),
-        Destination::ScriptHash(_) | Destination::ClassicMultisig(_) => Err(
-            WalletError::UnsupportedInputDestination(destination.clone()),
-        ),
-    }
+/// Estimate the size of a classic multisig witness with all the required signatures
+fn multisig_input_signature_size(challenge: &ClassicMultisigChallenge) -> usize {
+    // A signature together with the index of its public key in the challenge
+    const SIGNATURE_WITH_INDEX_SIZE: usize = 66;
+    // The witness and sighash type tags and the compact length prefixes
+    const OVERHEAD_SIZE: usize = 8;
+
+    serialization::Encode::encoded_size(challenge)
+        + challenge.min_required_signatures() as usize * SIGNATURE_WITH_INDEX_SIZE
+        + OVERHEAD_SIZE
 }
 
 /// Calculate the amount of fee that needs to be paid to add a change output
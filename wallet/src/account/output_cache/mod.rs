@@ -18,6 +18,7 @@ use std::collections::{btree_map::Entry, BTreeMap, BTreeSet};
 use common::{
     chain::{
         tokens::{token_id, TokenId},
+        AccountNonce, AccountOutPoint, AccountSpending, DelegationId, Destination,
         OutPointSourceId, PoolId, Transaction, TxInput, TxOutput, UtxoOutPoint,
     },
     primitives::{id::WithId, Id},
@@ -30,6 +31,13 @@ use wallet_types::{
 
 use crate::{WalletError, WalletResult};
 
+/// A delegation created by a confirmed transaction known to the wallet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DelegationData {
+    pub pool_id: PoolId,
+    pub destination: Destination,
+}
+
 /// A helper structure for the UTXO search.
 ///
 /// All transactions and blocks from the DB are cached here. If a transaction
@@ -46,6 +54,10 @@ pub struct OutputCache {
     consumed: BTreeMap<UtxoOutPoint, TxState>,
     unconfirmed_descendants: BTreeMap<OutPointSourceId, BTreeSet<OutPointSourceId>>,
     pools: BTreeMap<PoolId, (UtxoOutPoint, BlockInfo)>,
+    delegations: BTreeMap<DelegationId, DelegationData>,
+    // Tracked separately from `delegations` because the transactions loaded from the DB are not
+    // ordered, so a spending from a delegation can be seen before the delegation creation
+    delegation_nonces: BTreeMap<DelegationId, AccountNonce>,
 }
 
 impl OutputCache {
@@ -55,6 +67,8 @@ impl OutputCache {
             consumed: BTreeMap::new(),
             unconfirmed_descendants: BTreeMap::new(),
             pools: BTreeMap::new(),
+            delegations: BTreeMap::new(),
+            delegation_nonces: BTreeMap::new(),
         }
     }
 
@@ -85,6 +99,34 @@ impl OutputCache {
             .collect()
     }
 
+    /// Returns the decommission key of the pool from the known `CreateStakePool` output
+    pub fn pool_decommission_key(&self, pool_id: &PoolId) -> Option<&Destination> {
+        self.txs.values().flat_map(|tx| tx.outputs()).find_map(|output| match output {
+            TxOutput::CreateStakePool(id, data) if id == pool_id => Some(data.decommission_key()),
+            TxOutput::CreateStakePool(_, _)
+            | TxOutput::Transfer(_, _)
+            | TxOutput::LockThenTransfer(_, _, _)
+            | TxOutput::Burn(_)
+            | TxOutput::ProduceBlockFromStake(_, _)
+            | TxOutput::CreateDelegationId(_, _)
+            | TxOutput::DelegateStaking(_, _) => None,
+        })
+    }
+
+    pub fn delegation_ids(&self) -> impl Iterator<Item = (&DelegationId, &DelegationData)> {
+        self.delegations.iter()
+    }
+
+    pub fn delegation_data(&self, delegation_id: &DelegationId) -> Option<&DelegationData> {
+        self.delegations.get(delegation_id)
+    }
+
+    /// Returns the last nonce used to spend from the delegation by any confirmed or
+    /// not yet abandoned transaction known to the wallet
+    pub fn last_delegation_nonce(&self, delegation_id: &DelegationId) -> Option<AccountNonce> {
+        self.delegation_nonces.get(delegation_id).copied()
+    }
+
     pub fn add_tx(&mut self, tx_id: OutPointSourceId, tx: WalletTx) {
         let is_unconfirmed = match tx.state() {
             TxState::Inactive
@@ -108,9 +150,12 @@ impl OutputCache {
                             .map(|descendants| descendants.insert(tx_id.clone()));
                     }
                 }
-                TxInput::Account(_) => {
-                    // TODO: Add accounts support
-                }
+                TxInput::Account(outpoint) => match tx.state() {
+                    TxState::Confirmed(_, _) | TxState::InMempool | TxState::Inactive => {
+                        update_delegation_nonce(&mut self.delegation_nonces, outpoint);
+                    }
+                    TxState::Conflicted(_) | TxState::Abandoned => {}
+                },
             }
         }
 
@@ -122,6 +167,7 @@ impl OutputCache {
             | TxState::Abandoned => None,
         };
         if let Some(block_info) = tx_block_info {
+            let input0_outpoint = tx.inputs().iter().find_map(|input| input.utxo_outpoint());
             for (idx, output) in tx.outputs().iter().enumerate() {
                 match output {
                     TxOutput::ProduceBlockFromStake(_, pool_id)
@@ -133,11 +179,21 @@ impl OutputCache {
                                 (UtxoOutPoint::new(tx.id(), idx as u32), block_info)
                             });
                     }
+                    TxOutput::CreateDelegationId(destination, pool_id) => {
+                        if let Some(input0_outpoint) = input0_outpoint {
+                            self.delegations.insert(
+                                pos_accounting::make_delegation_id(input0_outpoint),
+                                DelegationData {
+                                    pool_id: *pool_id,
+                                    destination: destination.clone(),
+                                },
+                            );
+                        }
+                    }
                     TxOutput::Burn(_)
                     | TxOutput::Transfer(_, _)
                     | TxOutput::LockThenTransfer(_, _, _)
-                    | TxOutput::DelegateStaking(_, _)
-                    | TxOutput::CreateDelegationId(_, _) => {}
+                    | TxOutput::DelegateStaking(_, _) => {}
                 };
             }
         }
@@ -153,11 +209,29 @@ impl OutputCache {
                         self.consumed.remove(outpoint);
                         self.unconfirmed_descendants.remove(tx_id);
                     }
-                    TxInput::Account(_) => {
-                        // TODO: Add accounts support
+                    TxInput::Account(outpoint) => {
+                        rollback_delegation_nonce(&mut self.delegation_nonces, outpoint);
                     }
                 }
             }
+
+            let input0_outpoint = tx.inputs().iter().find_map(|input| input.utxo_outpoint());
+            for output in tx.outputs() {
+                match output {
+                    TxOutput::CreateDelegationId(_, _) => {
+                        if let Some(input0_outpoint) = input0_outpoint {
+                            self.delegations
+                                .remove(&pos_accounting::make_delegation_id(input0_outpoint));
+                        }
+                    }
+                    TxOutput::Burn(_)
+                    | TxOutput::Transfer(_, _)
+                    | TxOutput::LockThenTransfer(_, _, _)
+                    | TxOutput::CreateStakePool(_, _)
+                    | TxOutput::ProduceBlockFromStake(_, _)
+                    | TxOutput::DelegateStaking(_, _) => {}
+                }
+            }
         }
     }
 
@@ -239,9 +313,55 @@ impl OutputCache {
             .collect()
     }
 
-    pub fn abandon_transaction(&mut self, tx_id: Id<Transaction>) -> WalletResult<()> {
+    /// Returns the transaction if it is not confirmed yet and it can still be confirmed
+    pub fn get_pending_transaction(
+        &self,
+        tx_id: Id<Transaction>,
+    ) -> WalletResult<&WithId<Transaction>> {
+        match self.txs.get(&tx_id.into()) {
+            Some(WalletTx::Tx(tx)) => match tx.state() {
+                TxState::Inactive | TxState::InMempool => Ok(tx.get_transaction_with_id()),
+                TxState::Confirmed(_, _) | TxState::Conflicted(_) | TxState::Abandoned => {
+                    Err(WalletError::TransactionNotPending(*tx.state()))
+                }
+            },
+            Some(WalletTx::Block(_)) | None => Err(WalletError::CannotFindTransactionWithId(tx_id)),
+        }
+    }
+
+    /// Mark the inactive transaction and its descendants as abandoned.
+    /// Returns the transactions with the updated state.
+    pub fn abandon_transaction(&mut self, tx_id: Id<Transaction>) -> WalletResult<Vec<WalletTx>> {
+        self.abandon_with_descendants(tx_id, |state| match state {
+            TxState::Inactive => true,
+            TxState::Confirmed(_, _)
+            | TxState::Conflicted(_)
+            | TxState::InMempool
+            | TxState::Abandoned => false,
+        })
+    }
+
+    /// Mark the transaction that was replaced and its descendants as abandoned, they can't be
+    /// confirmed anymore because the replacement spends the same outputs.
+    /// Returns the transactions with the updated state.
+    pub fn abandon_replaced_transaction(
+        &mut self,
+        tx_id: Id<Transaction>,
+    ) -> WalletResult<Vec<WalletTx>> {
+        self.abandon_with_descendants(tx_id, |state| match state {
+            TxState::Inactive | TxState::InMempool => true,
+            TxState::Confirmed(_, _) | TxState::Conflicted(_) | TxState::Abandoned => false,
+        })
+    }
+
+    fn abandon_with_descendants(
+        &mut self,
+        tx_id: Id<Transaction>,
+        can_abandon: impl Fn(&TxState) -> bool,
+    ) -> WalletResult<Vec<WalletTx>> {
         let mut to_abandon = BTreeSet::new();
         to_abandon.insert(OutPointSourceId::from(tx_id));
+        let mut abandoned = Vec::new();
 
         while let Some(outpoint_source_id) = to_abandon.pop_first() {
             if let Some(descendants) = self.unconfirmed_descendants.remove(&outpoint_source_id) {
@@ -251,29 +371,69 @@ impl OutputCache {
             match self.txs.entry(outpoint_source_id) {
                 Entry::Occupied(mut entry) => match entry.get_mut() {
                     WalletTx::Block(_) => Err(WalletError::CannotFindTransactionWithId(tx_id)),
-                    WalletTx::Tx(tx) => match tx.state() {
-                        TxState::Inactive => {
+                    WalletTx::Tx(tx) => {
+                        if can_abandon(tx.state()) {
                             tx.set_state(TxState::Abandoned);
                             for input in tx.get_transaction().inputs() {
                                 match input {
                                     TxInput::Utxo(outpoint) => {
                                         self.consumed.insert(outpoint.clone(), *tx.state());
                                     }
-                                    TxInput::Account(_) => {
-                                        // TODO: Add accounts support
+                                    TxInput::Account(outpoint) => {
+                                        rollback_delegation_nonce(
+                                            &mut self.delegation_nonces,
+                                            outpoint,
+                                        );
                                     }
                                 }
                             }
+                            abandoned.push(WalletTx::Tx(tx.clone()));
                             Ok(())
+                        } else {
+                            Err(WalletError::CannotAbandonTransaction(*tx.state()))
                         }
-                        state => Err(WalletError::CannotAbandonTransaction(*state)),
-                    },
+                    }
                 },
                 Entry::Vacant(_) => Err(WalletError::CannotFindTransactionWithId(tx_id)),
             }?;
         }
 
-        Ok(())
+        Ok(abandoned)
+    }
+}
+
+fn update_delegation_nonce(
+    delegation_nonces: &mut BTreeMap<DelegationId, AccountNonce>,
+    outpoint: &AccountOutPoint,
+) {
+    match outpoint.account() {
+        AccountSpending::Delegation(delegation_id, _) => {
+            delegation_nonces
+                .entry(*delegation_id)
+                .and_modify(|nonce| *nonce = std::cmp::max(*nonce, outpoint.nonce()))
+                .or_insert(outpoint.nonce());
+        }
+    }
+}
+
+fn rollback_delegation_nonce(
+    delegation_nonces: &mut BTreeMap<DelegationId, AccountNonce>,
+    outpoint: &AccountOutPoint,
+) {
+    match outpoint.account() {
+        AccountSpending::Delegation(delegation_id, _) => {
+            // Nonces are sequential, so every nonce starting from the removed one is free again
+            match outpoint.nonce().decrement() {
+                Some(prev_nonce) => {
+                    if let Some(nonce) = delegation_nonces.get_mut(delegation_id) {
+                        *nonce = std::cmp::min(*nonce, prev_nonce);
+                    }
+                }
+                None => {
+                    delegation_nonces.remove(delegation_id);
+                }
+            }
+        }
     }
 }
 
@@ -77,6 +77,15 @@ impl OutputGroup {
                     TokenData::TokenTransfer(token_transfer) => token_transfer.amount,
                     TokenData::TokenIssuance(token_issuance) => token_issuance.amount_to_issue,
                     TokenData::NftIssuance(_) => Amount::from_atoms(1),
+                    TokenData::TokenIssuanceV1(token_issuance) => token_issuance.amount_to_issue,
+                    TokenData::TokenReissueV1 {
+                        token_id: _,
+                        amount_to_issue,
+                    } => *amount_to_issue,
+                    TokenData::LockTokenSupplyV1 { .. }
+                    | TokenData::ChangeTokenAuthorityV1 { .. }
+                    | TokenData::FreezeTokenV1 { .. }
+                    | TokenData::UnfreezeTokenV1 { .. } => Amount::ZERO,
                 }
             }
         };
@@ -15,9 +15,10 @@
 
 use crate::key_chain::leaf_key_chain::LeafKeySoftChain;
 use crate::key_chain::with_purpose::WithPurpose;
-use crate::key_chain::{make_account_path, KeyChainError, KeyChainResult};
+use crate::key_chain::{get_account_index, make_account_path, KeyChainError, KeyChainResult};
 use common::address::pubkeyhash::PublicKeyHash;
 use common::address::Address;
+use common::chain::classic_multisig::ClassicMultisigChallenge;
 use common::chain::{ChainConfig, Destination};
 use crypto::key::extended::{ExtendedPrivateKey, ExtendedPublicKey};
 use crypto::key::hdkd::child_number::ChildNumber;
@@ -31,7 +32,7 @@ use std::sync::Arc;
 use utils::const_value::ConstValue;
 use wallet_storage::{StoreTxRo, WalletStorageReadUnlocked, WalletStorageWriteLocked};
 use wallet_types::keys::KeyPurpose;
-use wallet_types::{AccountId, AccountInfo};
+use wallet_types::{AccountId, AccountInfo, AccountMultisigChallengeId};
 
 use super::MasterKeyChain;
 
@@ -47,6 +48,9 @@ pub struct AccountKeyChain {
     /// Key chains for receiving and change funds
     sub_chains: WithPurpose<LeafKeySoftChain>,
 
+    /// Classic multisig challenges that include keys of this account, by their public key hash
+    multisig_challenges: BTreeMap<PublicKeyHash, ClassicMultisigChallenge>,
+
     /// The number of unused addresses that need to be checked after the last used address
     lookahead_size: ConstValue<u32>,
 }
@@ -65,6 +69,42 @@ impl AccountKeyChain {
 
         let account_pubkey = account_privkey.to_public_key();
 
+        Self::new_from_account_public_key(
+            chain_config,
+            db_tx,
+            account_pubkey,
+            account_index,
+            lookahead_size,
+        )
+    }
+
+    /// Create a key chain from the exported public key of an account. Such a key chain can issue
+    /// addresses and recognize the outputs of the account, but it has no access to the private
+    /// keys, so it is only usable in watch-only wallets.
+    pub fn new_watch_only(
+        chain_config: Arc<ChainConfig>,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        account_pubkey: ExtendedPublicKey,
+        lookahead_size: u32,
+    ) -> KeyChainResult<AccountKeyChain> {
+        let account_index = get_account_index(&chain_config, account_pubkey.get_derivation_path())?;
+
+        Self::new_from_account_public_key(
+            chain_config,
+            db_tx,
+            account_pubkey,
+            account_index,
+            lookahead_size,
+        )
+    }
+
+    fn new_from_account_public_key(
+        chain_config: Arc<ChainConfig>,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        account_pubkey: ExtendedPublicKey,
+        account_index: U31,
+        lookahead_size: u32,
+    ) -> KeyChainResult<AccountKeyChain> {
         let account_id = AccountId::new_from_xpub(&account_pubkey);
 
         let receiving_key_chain = LeafKeySoftChain::new_empty(
@@ -94,6 +134,7 @@ impl AccountKeyChain {
             account_index,
             account_public_key: account_pubkey.into(),
             sub_chains,
+            multisig_challenges: BTreeMap::new(),
             lookahead_size: lookahead_size.into(),
         };
 
@@ -135,11 +176,18 @@ impl AccountKeyChain {
         let sub_chains =
             LeafKeySoftChain::load_leaf_keys(chain_config.clone(), account_info, db_tx, id)?;
 
+        let multisig_challenges = db_tx
+            .get_multisig_challenges(id)?
+            .into_iter()
+            .map(|(challenge_id, challenge)| (challenge_id.into_item_id(), challenge))
+            .collect();
+
         Ok(AccountKeyChain {
             chain_config,
             account_index: account_info.account_index(),
             account_public_key: pubkey_id,
             sub_chains,
+            multisig_challenges,
             lookahead_size: account_info.lookahead_size().into(),
         })
     }
@@ -317,6 +365,45 @@ impl AccountKeyChain {
     pub fn get_all_issued_addresses(&self) -> BTreeMap<ChildNumber, Address> {
         self.get_leaf_key_chain(KeyPurpose::ReceiveFunds).get_all_issued_addresses()
     }
+
+    /// Add a classic multisig challenge to the key chain, so outputs sent to it are recognized
+    /// and can be signed with the keys of this account. At least one of the public keys of the
+    /// challenge must belong to this key chain.
+    pub fn add_multisig_challenge(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        challenge: ClassicMultisigChallenge,
+    ) -> KeyChainResult<PublicKeyHash> {
+        challenge.is_valid(&self.chain_config)?;
+        if !challenge
+            .public_keys()
+            .iter()
+            .any(|public_key| self.is_public_key_mine(public_key))
+        {
+            return Err(KeyChainError::NoOwnKeyInMultisigChallenge);
+        }
+
+        let challenge_hash = PublicKeyHash::from(&challenge);
+        let id = AccountMultisigChallengeId::new(self.get_account_id(), challenge_hash);
+        db_tx.set_multisig_challenge(&id, &challenge)?;
+        self.multisig_challenges.insert(challenge_hash, challenge);
+
+        Ok(challenge_hash)
+    }
+
+    /// Get the classic multisig challenge with the provided hash, if it was added to this key chain
+    pub fn get_multisig_challenge(
+        &self,
+        challenge_hash: &PublicKeyHash,
+    ) -> Option<&ClassicMultisigChallenge> {
+        self.multisig_challenges.get(challenge_hash)
+    }
+
+    pub fn get_multisig_challenges(
+        &self,
+    ) -> impl Iterator<Item = (&PublicKeyHash, &ClassicMultisigChallenge)> {
+        self.multisig_challenges.iter()
+    }
 }
 
 #[cfg(test)]
@@ -38,6 +38,7 @@ pub use master_key_chain::MasterKeyChain;
 
 use common::address::pubkeyhash::PublicKeyHashError;
 use common::address::AddressError;
+use common::chain::classic_multisig::ClassicMultisigChallengeError;
 use common::chain::config::BIP44_PATH;
 use common::chain::ChainConfig;
 use crypto::key::extended::ExtendedKeyKind;
@@ -96,6 +97,10 @@ pub enum KeyChainError {
     KeyNotRoot,
     #[error("No private key found")]
     NoPrivateKeyFound,
+    #[error("Invalid classic multisig challenge: {0}")]
+    InvalidMultisigChallenge(#[from] ClassicMultisigChallengeError),
+    #[error("None of the public keys of the multisig challenge belong to this account")]
+    NoOwnKeyInMultisigChallenge,
 }
 
 /// Result type used for the key chain
@@ -130,6 +135,24 @@ pub fn make_path_to_vrf_key(chain_config: &ChainConfig, account_index: U31) -> D
     path.try_into().expect("Path creation should not fail")
 }
 
+/// Get the account index from the derivation path of an account key made with `make_account_path`
+pub fn get_account_index(
+    chain_config: &ChainConfig,
+    account_path: &DerivationPath,
+) -> KeyChainResult<U31> {
+    let account_index = account_path
+        .as_slice()
+        .last()
+        .map(ChildNumber::get_index)
+        .ok_or_else(|| KeyChainError::InvalidBip44DerivationPath(account_path.clone()))?;
+    if make_account_path(chain_config, account_index) != *account_path {
+        return Err(KeyChainError::InvalidBip44DerivationPath(
+            account_path.clone(),
+        ));
+    }
+    Ok(account_index)
+}
+
 fn get_purpose_and_index(
     derivation_path: &DerivationPath,
 ) -> KeyChainResult<(KeyPurpose, ChildNumber)> {
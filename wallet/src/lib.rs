@@ -15,6 +15,7 @@
 
 pub mod account;
 mod key_chain;
+pub mod musig2_session;
 pub mod send_request;
 pub mod wallet;
 pub mod wallet_events;
@@ -0,0 +1,210 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Multi-party signing of a transaction input that is locked to a MuSig2 aggregated key.
+//!
+//! Every party creates a session for the input with the keys of all the parties in the same
+//! order and shares the public nonce of the session. Once all the public nonces are collected,
+//! every party produces a partial signature. Any party can then combine the partial signatures
+//! into the witness of the input, which looks like the witness of a single key spend.
+
+use common::address::pubkeyhash::PublicKeyHash;
+use common::chain::signature::inputsig::authorize_pubkey_spend::AuthorizedPublicKeySpend;
+use common::chain::signature::inputsig::authorize_pubkeyhash_spend::AuthorizedPublicKeyHashSpend;
+use common::chain::signature::inputsig::standard_signature::StandardInputSignature;
+use common::chain::signature::inputsig::InputWitness;
+use common::chain::signature::sighash::sighashtype::SigHashType;
+use common::chain::signature::sighash::signature_hash;
+use common::chain::Destination;
+use common::primitives::H256;
+use crypto::key::musig2::{
+    generate_nonce, KeyAggContext, PartialSignature, PublicNonce, SecretNonce, SigningSession,
+};
+use crypto::key::{PrivateKey, PublicKey};
+use crypto::random::make_true_rng;
+use serialization::Encode;
+use wallet_types::partially_signed_transaction::PartiallySignedTransaction;
+
+use crate::{WalletError, WalletResult};
+
+/// Whether the outputs with the destination are spent with a signature of the key
+fn is_locked_to_key(destination: &Destination, public_key: &PublicKey) -> bool {
+    match destination {
+        Destination::PublicKey(destination_key) => destination_key == public_key,
+        Destination::Address(public_key_hash) => {
+            *public_key_hash == PublicKeyHash::from(public_key)
+        }
+        Destination::AnyoneCanSpend
+        | Destination::ScriptHash(_)
+        | Destination::ClassicMultisig(_) => false,
+    }
+}
+
+/// The state of one of the parties signing an input locked to a MuSig2 aggregated key
+pub struct MuSig2InputSession {
+    input_index: usize,
+    destination: Destination,
+    sighash_type: SigHashType,
+    sighash: H256,
+    key_agg: KeyAggContext,
+    /// The key of this party, one of the aggregated keys
+    signer_public_key: PublicKey,
+    public_nonce: PublicNonce,
+    /// Taken by the partial signature so that the nonce is never used twice
+    secret_nonce: Option<SecretNonce>,
+    /// Available once the public nonces of all the parties are known
+    signing_session: Option<SigningSession>,
+}
+
+impl MuSig2InputSession {
+    /// Start the session for the input of the transaction, the first of the aggregated keys
+    /// that the private key is provided for by `find_private_key` is used to sign
+    pub(crate) fn new(
+        ptx: &PartiallySignedTransaction,
+        input_index: usize,
+        public_keys: &[PublicKey],
+        find_private_key: impl Fn(&PublicKey) -> WalletResult<Option<PrivateKey>>,
+    ) -> WalletResult<Self> {
+        let destination = ptx
+            .destinations()
+            .get(input_index)
+            .ok_or(WalletError::InputIndexOutOfRange(input_index))?
+            .clone()
+            .ok_or(WalletError::MuSig2DestinationMismatch(input_index))?;
+
+        let key_agg = KeyAggContext::new(public_keys)?;
+        if !is_locked_to_key(&destination, &key_agg.aggregate_public_key()) {
+            return Err(WalletError::MuSig2DestinationMismatch(input_index));
+        }
+
+        let (signer_public_key, private_key) = public_keys
+            .iter()
+            .map(|public_key| {
+                find_private_key(public_key)
+                    .map(|private_key| private_key.map(|key| (public_key.clone(), key)))
+            })
+            .find_map(Result::transpose)
+            .transpose()?
+            .ok_or(WalletError::MuSig2NoOwnKey)?;
+
+        let input_utxos = ptx.input_utxos().iter().map(Option::as_ref).collect::<Vec<_>>();
+        let sighash_type = SigHashType::try_from(SigHashType::ALL).expect("Should not fail");
+        let sighash = signature_hash(sighash_type, ptx.tx(), &input_utxos, input_index)?;
+
+        let (secret_nonce, public_nonce) = generate_nonce(
+            &mut make_true_rng(),
+            &key_agg,
+            &private_key,
+            Some(sighash.encode().as_slice()),
+        )?;
+
+        Ok(Self {
+            input_index,
+            destination,
+            sighash_type,
+            sighash,
+            key_agg,
+            signer_public_key,
+            public_nonce,
+            secret_nonce: Some(secret_nonce),
+            signing_session: None,
+        })
+    }
+
+    pub fn input_index(&self) -> usize {
+        self.input_index
+    }
+
+    pub fn signer_public_key(&self) -> &PublicKey {
+        &self.signer_public_key
+    }
+
+    pub fn aggregate_public_key(&self) -> PublicKey {
+        self.key_agg.aggregate_public_key()
+    }
+
+    /// The nonce to share with the other parties in the first round
+    pub fn public_nonce(&self) -> &PublicNonce {
+        &self.public_nonce
+    }
+
+    /// Produce the partial signature of this party from the public nonces of all the parties,
+    /// given in the order of the aggregated keys
+    pub(crate) fn sign(
+        &mut self,
+        public_nonces: Vec<PublicNonce>,
+        private_key: &PrivateKey,
+    ) -> WalletResult<PartialSignature> {
+        let signing_session =
+            SigningSession::new(self.key_agg.clone(), public_nonces, &self.sighash.encode())?;
+        let secret_nonce = self.secret_nonce.take().ok_or(WalletError::MuSig2NonceAlreadyUsed)?;
+        let partial_signature = signing_session.sign(secret_nonce, private_key)?;
+        self.signing_session = Some(signing_session);
+        Ok(partial_signature)
+    }
+
+    /// Check the partial signature of the party with the given index in the aggregated keys
+    pub fn verify_partial_signature(
+        &self,
+        signer_index: usize,
+        partial_signature: &PartialSignature,
+    ) -> WalletResult<()> {
+        let signing_session =
+            self.signing_session.as_ref().ok_or(WalletError::MuSig2NoncesNotCollected)?;
+        Ok(signing_session.verify_partial_signature(signer_index, partial_signature)?)
+    }
+
+    /// Combine the partial signatures of all the parties, in the order of the aggregated keys,
+    /// into the witness of the input
+    pub fn finalize(
+        &self,
+        ptx: PartiallySignedTransaction,
+        partial_signatures: &[PartialSignature],
+    ) -> WalletResult<PartiallySignedTransaction> {
+        let signing_session =
+            self.signing_session.as_ref().ok_or(WalletError::MuSig2NoncesNotCollected)?;
+
+        let input_utxos = ptx.input_utxos().iter().map(Option::as_ref).collect::<Vec<_>>();
+        let sighash = signature_hash(self.sighash_type, ptx.tx(), &input_utxos, self.input_index)?;
+        if sighash != self.sighash {
+            return Err(WalletError::MuSig2TransactionMismatch);
+        }
+
+        let signature = signing_session.aggregate_signatures(partial_signatures)?;
+
+        let raw_signature = match &self.destination {
+            Destination::PublicKey(_) => AuthorizedPublicKeySpend::new(signature).encode(),
+            Destination::Address(_) => {
+                AuthorizedPublicKeyHashSpend::new(self.aggregate_public_key(), signature).encode()
+            }
+            Destination::AnyoneCanSpend
+            | Destination::ScriptHash(_)
+            | Destination::ClassicMultisig(_) => {
+                return Err(WalletError::MuSig2DestinationMismatch(self.input_index))
+            }
+        };
+
+        let mut witnesses = ptx.witnesses().to_vec();
+        let witness = witnesses
+            .get_mut(self.input_index)
+            .ok_or(WalletError::InputIndexOutOfRange(self.input_index))?;
+        *witness = Some(InputWitness::Standard(StandardInputSignature::new(
+            self.sighash_type,
+            raw_signature,
+        )));
+
+        Ok(ptx.with_witnesses(witnesses)?)
+    }
+}
@@ -19,7 +19,8 @@ use common::chain::tokens::{
     Metadata, NftIssuance, OutputValue, TokenData, TokenId, TokenIssuance, TokenTransfer,
 };
 use common::chain::{
-    ChainConfig, Destination, PoolId, Transaction, TransactionCreationError, TxInput, TxOutput,
+    AccountOutPoint, ChainConfig, DelegationId, Destination, PoolId, Transaction,
+    TransactionCreationError, TxInput, TxOutput,
 };
 use common::primitives::per_thousand::PerThousand;
 use common::primitives::Amount;
@@ -28,14 +29,19 @@ use crypto::vrf::VRFPublicKey;
 
 use crate::WalletResult;
 
+/// The transaction flags that signal that the transaction can be replaced with one paying
+/// a higher fee while it is not confirmed
+const REPLACEABLE_TX_FLAGS: u128 = 1;
+
 /// The `SendRequest` struct provides the necessary information to the wallet
 /// on the precise method of sending funds to a designated destination.
 #[derive(Debug, Clone)]
 pub struct SendRequest {
     flags: u128,
 
-    /// The UTXOs for each input, this can be empty
-    utxos: Vec<TxOutput>,
+    /// The UTXOs for each input, this can be empty.
+    /// Inputs that spend from an account don't have a UTXO and are represented with None.
+    utxos: Vec<Option<TxOutput>>,
 
     inputs: Vec<TxInput>,
 
@@ -140,6 +146,20 @@ pub fn make_stake_output(
     Ok(TxOutput::CreateStakePool(pool_id, stake_data.into()))
 }
 
+pub fn make_create_delegation_output(
+    chain_config: &ChainConfig,
+    address: Address,
+    pool_id: PoolId,
+) -> WalletResult<TxOutput> {
+    let destination = address.destination(chain_config)?;
+
+    Ok(TxOutput::CreateDelegationId(destination, pool_id))
+}
+
+pub fn make_delegate_staking_output(amount: Amount, delegation_id: DelegationId) -> TxOutput {
+    TxOutput::DelegateStaking(amount, delegation_id)
+}
+
 impl SendRequest {
     pub fn new() -> Self {
         Self {
@@ -153,12 +173,18 @@ impl SendRequest {
     pub fn from_transaction(transaction: Transaction, utxos: Vec<TxOutput>) -> Self {
         Self {
             flags: transaction.flags(),
-            utxos,
+            utxos: utxos.into_iter().map(Some).collect(),
             inputs: transaction.inputs().to_vec(),
             outputs: transaction.outputs().to_vec(),
         }
     }
 
+    /// Signal that the transaction can be replaced with one paying a higher fee
+    pub fn replaceable(mut self) -> Self {
+        self.flags |= REPLACEABLE_TX_FLAGS;
+        self
+    }
+
     pub fn inputs(&self) -> &[TxInput] {
         &self.inputs
     }
@@ -167,14 +193,25 @@ impl SendRequest {
         &self.outputs
     }
 
-    pub fn utxos(&self) -> &[TxOutput] {
+    pub fn utxos(&self) -> &[Option<TxOutput>] {
         &self.utxos
     }
 
     pub fn with_inputs(mut self, utxos: impl IntoIterator<Item = (TxInput, TxOutput)>) -> Self {
         for (outpoint, txo) in utxos {
             self.inputs.push(outpoint);
-            self.utxos.push(txo);
+            self.utxos.push(Some(txo));
+        }
+        self
+    }
+
+    pub fn with_account_inputs(
+        mut self,
+        account_outpoints: impl IntoIterator<Item = AccountOutPoint>,
+    ) -> Self {
+        for outpoint in account_outpoints {
+            self.inputs.push(TxInput::Account(outpoint));
+            self.utxos.push(None);
         }
         self
     }
@@ -190,7 +227,7 @@ impl SendRequest {
 
     pub fn into_transaction_and_utxos(
         self,
-    ) -> Result<(Transaction, Vec<TxOutput>), TransactionCreationError> {
+    ) -> Result<(Transaction, Vec<Option<TxOutput>>), TransactionCreationError> {
         let tx = Transaction::new(self.flags, self.inputs, self.outputs)?;
         Ok((tx, self.utxos))
     }
@@ -13,31 +13,39 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
+use std::num::NonZeroU8;
 use std::path::Path;
 use std::sync::Arc;
 
 use crate::account::transaction_list::TransactionList;
-use crate::account::{Currency, UtxoSelectorError};
-use crate::key_chain::{KeyChainError, MasterKeyChain};
-use crate::send_request::{make_issue_nft_outputs, make_issue_token_outputs};
+use crate::account::{Currency, DelegationData, UtxoSelectorError};
+use crate::key_chain::{AccountKeyChain, KeyChainError, MasterKeyChain, LOOKAHEAD_SIZE};
+use crate::musig2_session::MuSig2InputSession;
+use crate::send_request::{
+    make_delegate_staking_output, make_issue_nft_outputs, make_issue_token_outputs,
+};
 use crate::wallet_events::WalletEvents;
 use crate::{Account, SendRequest};
 pub use bip39::{Language, Mnemonic};
-use common::address::pubkeyhash::PublicKeyHashError;
+use common::address::pubkeyhash::{PublicKeyHash, PublicKeyHashError};
 use common::address::{Address, AddressError};
 use common::chain::block::timestamp::BlockTimestamp;
+use common::chain::classic_multisig::{ClassicMultisigChallenge, ClassicMultisigChallengeError};
+use common::chain::signature::inputsig::classical_multisig::authorize_classical_multisig::ClassicalMultisigSigningError;
 use common::chain::signature::TransactionSigError;
 use common::chain::tokens::{token_id, Metadata, TokenId, TokenIssuance};
 use common::chain::{
-    Block, ChainConfig, Destination, GenBlock, PoolId, SignedTransaction, Transaction,
-    TransactionCreationError, TxOutput, UtxoOutPoint,
+    AccountNonce, Block, ChainConfig, DelegationId, Destination, GenBlock, PoolId,
+    SignedTransaction, Transaction, TransactionCreationError, TxOutput, UtxoOutPoint,
 };
 use common::primitives::id::WithId;
 use common::primitives::{Amount, BlockHeight, Id};
 use consensus::PoSGenerateBlockInputData;
+use crypto::key::extended::ExtendedPublicKey;
 use crypto::key::hdkd::child_number::ChildNumber;
 use crypto::key::hdkd::u31::U31;
+use crypto::key::musig2::{MuSig2Error, PartialSignature, PublicNonce};
 use crypto::key::PublicKey;
 use crypto::vrf::VRFPublicKey;
 use itertools::Itertools;
@@ -49,7 +57,10 @@ use wallet_storage::{
     WalletStorageReadLocked, WalletStorageWriteLocked,
 };
 use wallet_storage::{StoreTxRwUnlocked, TransactionRwUnlocked};
-use wallet_types::utxo_types::{UtxoStates, UtxoTypes};
+use wallet_types::partially_signed_transaction::{
+    PartiallySignedTransaction, PartiallySignedTransactionCreationError,
+};
+use wallet_types::utxo_types::{UtxoMark, UtxoStates, UtxoTypes};
 use wallet_types::wallet_tx::TxState;
 use wallet_types::{AccountId, BlockInfo, KeyPurpose};
 
@@ -118,6 +129,58 @@ pub enum WalletError {
     CannotFindTransactionWithId(Id<Transaction>),
     #[error("Address error: {0}")]
     AddressError(#[from] AddressError),
+    #[error("Delegation with id {0} not found")]
+    DelegationNotFound(DelegationId),
+    #[error("Delegation nonce overflow for delegation with id {0}")]
+    DelegationNonceOverflow(DelegationId),
+    #[error("Amount {0:?} is not enough to pay the fee {1:?}")]
+    NotEnoughAmountToPayFee(Amount, Amount),
+    #[error("Missing UTXO for input {0:?}")]
+    MissingUtxo(UtxoOutPoint),
+    #[error("Unknown pool id {0}")]
+    UnknownPoolId(PoolId),
+    #[error("Partially signed transaction error: {0}")]
+    PartiallySignedTransaction(#[from] PartiallySignedTransactionCreationError),
+    #[error("The decommission key of the pool is not in this wallet, a decommission request must be used instead")]
+    DecommissionKeyNotInWallet,
+    #[error("Classic multisig signing error: {0}")]
+    ClassicalMultisigSigning(#[from] ClassicalMultisigSigningError),
+    #[error("Invalid classic multisig challenge: {0}")]
+    MultisigChallenge(#[from] ClassicMultisigChallengeError),
+    #[error("The classic multisig challenge {0} was not added to this account")]
+    UnknownMultisigChallenge(PublicKeyHash),
+    #[error("{0} is not a classic multisig address")]
+    NotAMultisigAddress(String),
+    #[error(
+        "The wallet is watch-only and has no private keys to sign with or derive new accounts"
+    )]
+    WatchOnlyWallet,
+    #[error("Input index {0} is out of range")]
+    InputIndexOutOfRange(usize),
+    #[error("MuSig2 error: {0}")]
+    MuSig2(#[from] MuSig2Error),
+    #[error("Input {0} is not locked to the aggregated MuSig2 key")]
+    MuSig2DestinationMismatch(usize),
+    #[error("None of the aggregated MuSig2 keys belongs to the account")]
+    MuSig2NoOwnKey,
+    #[error("The nonce of the MuSig2 session was already used")]
+    MuSig2NonceAlreadyUsed,
+    #[error("The public nonces of the MuSig2 session were not collected yet")]
+    MuSig2NoncesNotCollected,
+    #[error("The transaction differs from the one the MuSig2 session was started for")]
+    MuSig2TransactionMismatch,
+    #[error("Transaction in {0} state is not pending")]
+    TransactionNotPending(TxState),
+    #[error("Transaction {0} does not signal that it can be replaced")]
+    TransactionNotReplaceable(Id<Transaction>),
+    #[error("The replacement fee {1:?} must be at least {0:?}, the fee of the replaced transaction plus the incremental relay fee")]
+    ReplacementFeeTooLow(Amount, Amount),
+    #[error("Transaction {0} has no outputs that the account can spend")]
+    NoOutputsToSpend(Id<Transaction>),
+    #[error("UTXO {0:?} is frozen")]
+    UtxoFrozen(UtxoOutPoint),
+    #[error("UTXO {0:?} cannot be spent by the account")]
+    UtxoNotSpendable(UtxoOutPoint),
 }
 
 /// Result type used for the wallet
@@ -126,7 +189,8 @@ pub type WalletResult<T> = Result<T, WalletError>;
 pub struct Wallet<B: storage::Backend> {
     chain_config: Arc<ChainConfig>,
     db: Store<B>,
-    key_chain: MasterKeyChain,
+    /// The master key chain, None for watch-only wallets that have no root keys
+    key_chain: Option<MasterKeyChain>,
     accounts: BTreeMap<U31, Account>,
     latest_median_time: BlockTimestamp,
     unsynced_accounts: BTreeMap<U31, Account>,
@@ -166,7 +230,7 @@ impl<B: storage::Backend> Wallet<B> {
         let mut wallet = Wallet {
             chain_config,
             db,
-            key_chain,
+            key_chain: Some(key_chain),
             accounts: BTreeMap::new(),
             latest_median_time,
             unsynced_accounts: BTreeMap::new(),
@@ -177,6 +241,45 @@ impl<B: storage::Backend> Wallet<B> {
         Ok(wallet)
     }
 
+    /// Create a watch-only wallet from the exported extended public key of an account,
+    /// see `get_account_public_key`. The wallet tracks the balance and the transactions of the
+    /// account and can create unsigned transactions, but it can't sign anything.
+    pub fn new_watch_only_wallet(
+        chain_config: Arc<ChainConfig>,
+        db: Store<B>,
+        account_public_key: ExtendedPublicKey,
+    ) -> WalletResult<Self> {
+        let mut db_tx = db.transaction_rw(None)?;
+
+        let account_key_chain = AccountKeyChain::new_watch_only(
+            chain_config.clone(),
+            &mut db_tx,
+            account_public_key,
+            LOOKAHEAD_SIZE,
+        )?;
+        let account = Account::new(
+            Arc::clone(&chain_config),
+            &mut db_tx,
+            account_key_chain,
+            None,
+        )?;
+
+        db_tx.set_watch_only(true)?;
+        db_tx.set_storage_version(CURRENT_WALLET_VERSION)?;
+
+        db_tx.commit()?;
+
+        let latest_median_time = chain_config.genesis_block().timestamp();
+        Ok(Wallet {
+            chain_config,
+            db,
+            key_chain: None,
+            accounts: [(account.account_index(), account)].into(),
+            latest_median_time,
+            unsynced_accounts: BTreeMap::new(),
+        })
+    }
+
     pub fn load_wallet(chain_config: Arc<ChainConfig>, db: Store<B>) -> WalletResult<Self> {
         // Please continue to use read-only transaction here.
         // Some unit tests expect that loading the wallet does not change the DB.
@@ -187,7 +290,14 @@ impl<B: storage::Backend> Wallet<B> {
             return Err(WalletError::WalletNotInitialized);
         }
 
-        let key_chain = MasterKeyChain::new_from_existing_database(chain_config.clone(), &db_tx)?;
+        let key_chain = if db_tx.is_watch_only()? {
+            None
+        } else {
+            Some(MasterKeyChain::new_from_existing_database(
+                chain_config.clone(),
+                &db_tx,
+            )?)
+        };
 
         let accounts_info = db_tx.get_accounts_info()?;
 
@@ -225,6 +335,17 @@ impl<B: storage::Backend> Wallet<B> {
         })
     }
 
+    pub fn is_watch_only(&self) -> bool {
+        self.key_chain.is_none()
+    }
+
+    /// Operations that need the private keys fail early in watch-only wallets,
+    /// instead of failing later on the missing root keys
+    fn ensure_not_watch_only(&self) -> WalletResult<()> {
+        ensure!(!self.is_watch_only(), WalletError::WatchOnlyWallet);
+        Ok(())
+    }
+
     pub fn is_encrypted(&self) -> bool {
         self.db.is_encrypted()
     }
@@ -257,6 +378,8 @@ impl<B: storage::Backend> Wallet<B> {
     }
 
     pub fn create_account(&mut self, name: Option<String>) -> WalletResult<(U31, Option<String>)> {
+        let key_chain = self.key_chain.as_ref().ok_or(WalletError::WatchOnlyWallet)?;
+
         ensure!(
             self.unsynced_accounts.is_empty(),
             WalletError::LastAccountNotInSync
@@ -285,7 +408,7 @@ impl<B: storage::Backend> Wallet<B> {
         let mut db_tx = self.db.transaction_rw_unlocked(None)?;
 
         let account_key_chain =
-            self.key_chain.create_account_key_chain(&mut db_tx, next_account_index)?;
+            key_chain.create_account_key_chain(&mut db_tx, next_account_index)?;
 
         let account = Account::new(
             Arc::clone(&self.chain_config),
@@ -336,6 +459,7 @@ impl<B: storage::Backend> Wallet<B> {
         account_index: U31,
         f: impl FnOnce(&mut Account, &mut StoreTxRwUnlocked<B>) -> WalletResult<T>,
     ) -> WalletResult<T> {
+        self.ensure_not_watch_only()?;
         let mut db_tx = self.db.transaction_rw_unlocked(None)?;
         let account = Self::get_account_mut(
             &mut self.accounts,
@@ -408,12 +532,85 @@ impl<B: storage::Backend> Wallet<B> {
         account_index: U31,
         tx_id: Id<Transaction>,
     ) -> WalletResult<()> {
-        let account = Self::get_account_mut(
-            &mut self.accounts,
-            &mut self.unsynced_accounts,
-            account_index,
-        )?;
-        account.abandon_transaction(tx_id)
+        self.for_account_rw(account_index, |account, db_tx| {
+            account.abandon_transaction(db_tx, tx_id)
+        })
+    }
+
+    /// Create a transaction that replaces the pending transaction, paying the fee of the new
+    /// fee rate. The wallet is not changed until the replacement is accepted by the node and
+    /// added with `add_replacement_transaction`.
+    ///
+    /// The replacement is accepted only by nodes that allow replacing transactions in the
+    /// mempool, otherwise `create_child_pays_for_parent_transaction` can be used instead.
+    pub fn create_replacement_transaction(
+        &mut self,
+        account_index: U31,
+        tx_id: Id<Transaction>,
+        new_fee_rate: FeeRate,
+        consolidate_fee_rate: FeeRate,
+    ) -> WalletResult<SignedTransaction> {
+        let latest_median_time = self.latest_median_time;
+        self.for_account_rw_unlocked(account_index, |account, db_tx| {
+            account.create_replacement_transaction(
+                db_tx,
+                tx_id,
+                latest_median_time,
+                new_fee_rate,
+                consolidate_fee_rate,
+            )
+        })
+    }
+
+    /// Add the replacement transaction that was accepted by the node, the replaced transaction
+    /// and its descendants are marked as abandoned
+    pub fn add_replacement_transaction(
+        &mut self,
+        wallet_events: &mut impl WalletEvents,
+        account_index: U31,
+        replaced_tx_id: Id<Transaction>,
+        tx: SignedTransaction,
+    ) -> WalletResult<()> {
+        self.for_account_rw(account_index, |account, db_tx| {
+            account.abandon_replaced_transaction(db_tx, wallet_events, replaced_tx_id)?;
+            account.scan_new_unconfirmed_transactions(
+                &[tx],
+                TxState::Inactive,
+                db_tx,
+                wallet_events,
+            )
+        })
+    }
+
+    /// Create a transaction that spends the outputs of the pending transaction that belong to
+    /// the account, paying a fee high enough for both transactions together to pay the fee rate
+    pub fn create_child_pays_for_parent_transaction(
+        &mut self,
+        wallet_events: &mut impl WalletEvents,
+        account_index: U31,
+        parent_tx_id: Id<Transaction>,
+        fee_rate: FeeRate,
+    ) -> WalletResult<SignedTransaction> {
+        let latest_median_time = self.latest_median_time;
+        self.for_account_rw_unlocked(account_index, |account, db_tx| {
+            let tx = account.create_child_pays_for_parent_transaction(
+                db_tx,
+                parent_tx_id,
+                latest_median_time,
+                fee_rate,
+            )?;
+
+            let txs = [tx];
+            account.scan_new_unconfirmed_transactions(
+                &txs,
+                TxState::Inactive,
+                db_tx,
+                wallet_events,
+            )?;
+
+            let [tx] = txs;
+            Ok(tx)
+        })
     }
 
     pub fn get_pool_ids(&self, account_index: U31) -> WalletResult<Vec<(PoolId, BlockInfo)>> {
@@ -421,6 +618,14 @@ impl<B: storage::Backend> Wallet<B> {
         Ok(pool_ids)
     }
 
+    pub fn get_delegations(
+        &self,
+        account_index: U31,
+    ) -> WalletResult<impl Iterator<Item = (&DelegationId, &DelegationData)>> {
+        let delegations = self.get_account(account_index)?.get_delegations();
+        Ok(delegations)
+    }
+
     pub fn get_new_address(&mut self, account_index: U31) -> WalletResult<(ChildNumber, Address)> {
         self.for_account_rw(account_index, |account, db_tx| {
             account.get_new_address(db_tx, KeyPurpose::ReceiveFunds)
@@ -443,6 +648,11 @@ impl<B: storage::Backend> Wallet<B> {
         account.get_transaction_list(skip, count)
     }
 
+    /// Get the extended public key of the account, it can be used to create a watch-only wallet
+    pub fn get_account_public_key(&self, account_index: U31) -> WalletResult<&ExtendedPublicKey> {
+        Ok(self.get_account(account_index)?.account_public_key())
+    }
+
     pub fn get_all_issued_addresses(
         &self,
         account_index: U31,
@@ -451,7 +661,73 @@ impl<B: storage::Backend> Wallet<B> {
         Ok(account.get_all_issued_addresses())
     }
 
+    /// Add an M-of-N classic multisig challenge of the provided public keys to the account
+    /// and return its address. At least one of the keys must belong to the account.
+    pub fn add_multisig_challenge(
+        &mut self,
+        account_index: U31,
+        min_required_signatures: NonZeroU8,
+        public_keys: Vec<PublicKey>,
+    ) -> WalletResult<Address> {
+        let challenge = ClassicMultisigChallenge::new(
+            &self.chain_config,
+            min_required_signatures,
+            public_keys,
+        )?;
+        self.for_account_rw(account_index, |account, db_tx| {
+            account.add_multisig_challenge(db_tx, challenge)
+        })
+    }
+
+    pub fn get_multisig_challenges(
+        &self,
+        account_index: U31,
+    ) -> WalletResult<Vec<(Address, ClassicMultisigChallenge)>> {
+        self.get_account(account_index)?.get_multisig_challenges()
+    }
+
+    /// Freeze or unfreeze the UTXO, frozen UTXOs are never selected automatically
+    /// as inputs of new transactions
+    pub fn set_utxo_frozen(
+        &mut self,
+        account_index: U31,
+        outpoint: UtxoOutPoint,
+        frozen: bool,
+    ) -> WalletResult<()> {
+        self.for_account_rw(account_index, |account, db_tx| {
+            let mark = UtxoMark {
+                frozen,
+                ..account.get_utxo_mark(&outpoint)
+            };
+            account.set_utxo_mark(db_tx, outpoint, mark)
+        })
+    }
+
+    /// Set the label of the UTXO, or remove it if `label` is None
+    pub fn set_utxo_label(
+        &mut self,
+        account_index: U31,
+        outpoint: UtxoOutPoint,
+        label: Option<String>,
+    ) -> WalletResult<()> {
+        self.for_account_rw(account_index, |account, db_tx| {
+            let mark = UtxoMark {
+                label,
+                ..account.get_utxo_mark(&outpoint)
+            };
+            account.set_utxo_mark(db_tx, outpoint, mark)
+        })
+    }
+
+    pub fn get_utxo_marks(
+        &self,
+        account_index: U31,
+    ) -> WalletResult<BTreeMap<UtxoOutPoint, UtxoMark>> {
+        Ok(self.get_account(account_index)?.get_utxo_marks().clone())
+    }
+
     pub fn get_vrf_public_key(&mut self, account_index: U31) -> WalletResult<VRFPublicKey> {
+        self.ensure_not_watch_only()?;
         let db_tx = self.db.transaction_ro_unlocked()?;
         self.get_account(account_index)?.get_vrf_public_key(&db_tx)
     }
@@ -481,6 +757,43 @@ impl<B: storage::Backend> Wallet<B> {
         consolidate_fee_rate: FeeRate,
     ) -> WalletResult<SignedTransaction> {
         let request = SendRequest::new().with_outputs(outputs);
+        self.create_transaction_from_send_request(
+            wallet_events,
+            account_index,
+            request,
+            current_fee_rate,
+            consolidate_fee_rate,
+        )
+    }
+
+    /// Same as `create_transaction_to_addresses`, but the transaction signals that it can be
+    /// replaced with one paying a higher fee (see `create_replacement_transaction`)
+    pub fn create_replaceable_transaction_to_addresses(
+        &mut self,
+        wallet_events: &mut impl WalletEvents,
+        account_index: U31,
+        outputs: impl IntoIterator<Item = TxOutput>,
+        current_fee_rate: FeeRate,
+        consolidate_fee_rate: FeeRate,
+    ) -> WalletResult<SignedTransaction> {
+        let request = SendRequest::new().replaceable().with_outputs(outputs);
+        self.create_transaction_from_send_request(
+            wallet_events,
+            account_index,
+            request,
+            current_fee_rate,
+            consolidate_fee_rate,
+        )
+    }
+
+    fn create_transaction_from_send_request(
+        &mut self,
+        wallet_events: &mut impl WalletEvents,
+        account_index: U31,
+        request: SendRequest,
+        current_fee_rate: FeeRate,
+        consolidate_fee_rate: FeeRate,
+    ) -> WalletResult<SignedTransaction> {
         let latest_median_time = self.latest_median_time;
         self.for_account_rw_unlocked(account_index, |account, db_tx| {
             let tx = account.process_send_request(
@@ -503,6 +816,39 @@ impl<B: storage::Backend> Wallet<B> {
         })
     }
 
+    /// Creates a transaction to send funds to specified addresses that spends exactly the
+    /// provided UTXOs, the change is sent to a new change address of the account.
+    pub fn create_transaction_to_addresses_from_utxos(
+        &mut self,
+        wallet_events: &mut impl WalletEvents,
+        account_index: U31,
+        outputs: impl IntoIterator<Item = TxOutput>,
+        utxos: BTreeSet<UtxoOutPoint>,
+        current_fee_rate: FeeRate,
+    ) -> WalletResult<SignedTransaction> {
+        let request = SendRequest::new().with_outputs(outputs);
+        let latest_median_time = self.latest_median_time;
+        self.for_account_rw_unlocked(account_index, |account, db_tx| {
+            let tx = account.process_send_request_from_utxos(
+                db_tx,
+                request,
+                utxos,
+                latest_median_time,
+                current_fee_rate,
+            )?;
+            let txs = [tx];
+            account.scan_new_unconfirmed_transactions(
+                &txs,
+                TxState::Inactive,
+                db_tx,
+                wallet_events,
+            )?;
+
+            let [tx] = txs;
+            Ok(tx)
+        })
+    }
+
     pub fn issue_new_token(
         &mut self,
         wallet_events: &mut impl WalletEvents,
@@ -548,6 +894,68 @@ impl<B: storage::Backend> Wallet<B> {
         Ok((token_id, tx))
     }
 
+    /// Create a transaction that sends the outputs like `create_transaction_to_addresses`,
+    /// but without signing it. The result can be signed later with `sign_raw_transaction`,
+    /// also by other wallets, which makes it possible to sign transactions offline.
+    ///
+    /// The selected inputs are not marked as spent until the signed transaction is seen
+    /// by the wallet.
+    pub fn create_unsigned_transaction_to_addresses(
+        &mut self,
+        account_index: U31,
+        outputs: impl IntoIterator<Item = TxOutput>,
+        current_fee_rate: FeeRate,
+        consolidate_fee_rate: FeeRate,
+    ) -> WalletResult<PartiallySignedTransaction> {
+        let request = SendRequest::new().with_outputs(outputs);
+        let latest_median_time = self.latest_median_time;
+        self.for_account_rw(account_index, |account, db_tx| {
+            account.process_send_request_unsigned(
+                db_tx,
+                request,
+                latest_median_time,
+                current_fee_rate,
+                consolidate_fee_rate,
+            )
+        })
+    }
+
+    /// Create a transaction that sends coins from the multisig address to the outputs.
+    /// The returned transaction is signed only by the keys of this account, so it must be
+    /// passed to the other parties of the multisig to complete it.
+    pub fn create_transaction_from_multisig(
+        &mut self,
+        account_index: U31,
+        multisig_address: Address,
+        outputs: impl IntoIterator<Item = TxOutput>,
+        current_fee_rate: FeeRate,
+        consolidate_fee_rate: FeeRate,
+    ) -> WalletResult<PartiallySignedTransaction> {
+        let challenge_hash = match multisig_address.destination(&self.chain_config)? {
+            Destination::ClassicMultisig(challenge_hash) => challenge_hash,
+            Destination::Address(_)
+            | Destination::PublicKey(_)
+            | Destination::ScriptHash(_)
+            | Destination::AnyoneCanSpend => {
+                return Err(WalletError::NotAMultisigAddress(
+                    multisig_address.get().to_owned(),
+                ))
+            }
+        };
+        let request = SendRequest::new().with_outputs(outputs);
+        let latest_median_time = self.latest_median_time;
+        self.for_account_rw_unlocked(account_index, |account, db_tx| {
+            account.create_transaction_from_multisig(
+                db_tx,
+                challenge_hash,
+                request,
+                latest_median_time,
+                current_fee_rate,
+                consolidate_fee_rate,
+            )
+        })
+    }
+
     pub fn create_stake_pool_tx(
         &mut self,
         wallet_events: &mut impl WalletEvents,
@@ -581,10 +989,209 @@ impl<B: storage::Backend> Wallet<B> {
         })
     }
 
+    pub fn create_delegation(
+        &mut self,
+        wallet_events: &mut impl WalletEvents,
+        account_index: U31,
+        address: Address,
+        pool_id: PoolId,
+        current_fee_rate: FeeRate,
+        consolidate_fee_rate: FeeRate,
+    ) -> WalletResult<(DelegationId, SignedTransaction)> {
+        let latest_median_time = self.latest_median_time;
+        self.for_account_rw_unlocked(account_index, |account, db_tx| {
+            let (delegation_id, tx) = account.create_delegation(
+                db_tx,
+                address,
+                pool_id,
+                latest_median_time,
+                current_fee_rate,
+                consolidate_fee_rate,
+            )?;
+
+            let txs = [tx];
+            account.scan_new_unconfirmed_transactions(
+                &txs,
+                TxState::Inactive,
+                db_tx,
+                wallet_events,
+            )?;
+
+            let [tx] = txs;
+            Ok((delegation_id, tx))
+        })
+    }
+
+    pub fn delegate_staking(
+        &mut self,
+        wallet_events: &mut impl WalletEvents,
+        account_index: U31,
+        amount: Amount,
+        delegation_id: DelegationId,
+        current_fee_rate: FeeRate,
+        consolidate_fee_rate: FeeRate,
+    ) -> WalletResult<SignedTransaction> {
+        let output = make_delegate_staking_output(amount, delegation_id);
+        self.create_transaction_to_addresses(
+            wallet_events,
+            account_index,
+            [output],
+            current_fee_rate,
+            consolidate_fee_rate,
+        )
+    }
+
+    /// Creates a transaction that withdraws `amount` from a delegation owned by the account.
+    /// The withdrawn coins are locked for the delegation spend maturity period.
+    ///
+    /// `confirmed_nonce` is the last nonce used to spend from the delegation according to the
+    /// node, see `ChainstateInterface::get_account_nonce_count`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn withdraw_from_delegation(
+        &mut self,
+        wallet_events: &mut impl WalletEvents,
+        account_index: U31,
+        address: Address,
+        amount: Amount,
+        delegation_id: DelegationId,
+        confirmed_nonce: Option<AccountNonce>,
+        current_fee_rate: FeeRate,
+    ) -> WalletResult<SignedTransaction> {
+        self.for_account_rw_unlocked(account_index, |account, db_tx| {
+            let tx = account.spend_from_delegation(
+                db_tx,
+                address,
+                amount,
+                delegation_id,
+                confirmed_nonce,
+                current_fee_rate,
+            )?;
+
+            let txs = [tx];
+            account.scan_new_unconfirmed_transactions(
+                &txs,
+                TxState::Inactive,
+                db_tx,
+                wallet_events,
+            )?;
+
+            let [tx] = txs;
+            Ok(tx)
+        })
+    }
+
+    /// Create and sign a transaction that decommissions the pool.
+    /// Fails if the decommission key of the pool is not in this wallet.
+    pub fn decommission_stake_pool(
+        &mut self,
+        wallet_events: &mut impl WalletEvents,
+        account_index: U31,
+        pool_id: PoolId,
+        pool_balance: Amount,
+        current_fee_rate: FeeRate,
+    ) -> WalletResult<SignedTransaction> {
+        let latest_median_time = self.latest_median_time;
+        let chain_config = Arc::clone(&self.chain_config);
+        self.for_account_rw_unlocked(account_index, |account, db_tx| {
+            let ptx = account.decommission_stake_pool(
+                db_tx,
+                pool_id,
+                pool_balance,
+                latest_median_time,
+                current_fee_rate,
+            )?;
+            ensure!(
+                ptx.is_fully_signed(&chain_config),
+                WalletError::DecommissionKeyNotInWallet
+            );
+            let tx = ptx.into_signed_tx(&chain_config)?;
+
+            let txs = [tx];
+            account.scan_new_unconfirmed_transactions(
+                &txs,
+                TxState::Inactive,
+                db_tx,
+                wallet_events,
+            )?;
+
+            let [tx] = txs;
+            Ok(tx)
+        })
+    }
+
+    /// Create a transaction that decommissions the pool, without signing the pool input
+    /// if the decommission key is not in this wallet.
+    /// The result should be signed by the wallet holding the decommission key with
+    /// `sign_raw_transaction` and then submitted.
+    pub fn decommission_stake_pool_request(
+        &mut self,
+        account_index: U31,
+        pool_id: PoolId,
+        pool_balance: Amount,
+        current_fee_rate: FeeRate,
+    ) -> WalletResult<PartiallySignedTransaction> {
+        let latest_median_time = self.latest_median_time;
+        self.for_account_rw_unlocked(account_index, |account, db_tx| {
+            account.decommission_stake_pool(
+                db_tx,
+                pool_id,
+                pool_balance,
+                latest_median_time,
+                current_fee_rate,
+            )
+        })
+    }
+
+    /// Sign the inputs of a partially signed transaction that can be signed by the account
+    pub fn sign_raw_transaction(
+        &self,
+        account_index: U31,
+        ptx: PartiallySignedTransaction,
+    ) -> WalletResult<PartiallySignedTransaction> {
+        self.ensure_not_watch_only()?;
+        let db_tx = self.db.transaction_ro_unlocked()?;
+        self.get_account(account_index)?.sign_raw_transaction(ptx, &db_tx)
+    }
+
+    /// Start signing the input of the transaction that is locked to the MuSig2 aggregation
+    /// of the public keys, one of which must belong to the account.
+    /// The public nonce of the returned session has to be shared with the other parties.
+    pub fn start_musig2_input_session(
+        &self,
+        account_index: U31,
+        ptx: &PartiallySignedTransaction,
+        input_index: usize,
+        public_keys: &[PublicKey],
+    ) -> WalletResult<MuSig2InputSession> {
+        self.ensure_not_watch_only()?;
+        let db_tx = self.db.transaction_ro_unlocked()?;
+        self.get_account(account_index)?.start_musig2_input_session(
+            ptx,
+            input_index,
+            public_keys,
+            &db_tx,
+        )
+    }
+
+    /// Produce the partial signature of the account once the public nonces of all the parties
+    /// are known, in the order of the aggregated keys
+    pub fn musig2_partial_sign(
+        &self,
+        account_index: U31,
+        session: &mut MuSig2InputSession,
+        public_nonces: Vec<PublicNonce>,
+    ) -> WalletResult<PartialSignature> {
+        self.ensure_not_watch_only()?;
+        let db_tx = self.db.transaction_ro_unlocked()?;
+        self.get_account(account_index)?
+            .musig2_partial_sign(session, public_nonces, &db_tx)
+    }
+
     pub fn get_pos_gen_block_data(
         &mut self,
         account_index: U31,
     ) -> WalletResult<PoSGenerateBlockInputData> {
+        self.ensure_not_watch_only()?;
         let db_tx = self.db.transaction_ro_unlocked()?;
         self.get_account(account_index)?
             .get_pos_gen_block_data(&db_tx, self.latest_median_time)
@@ -32,19 +32,21 @@ use common::{
         tokens::{OutputValue, TokenData, TokenTransfer},
         Destination, Genesis, OutPointSourceId, TxInput,
     },
-    primitives::Idable,
+    primitives::{Idable, H256},
 };
 use crypto::{
     key::hdkd::{child_number::ChildNumber, derivable::Derivable, derivation_path::DerivationPath},
     random::{CryptoRng, Rng, SliceRandom},
 };
 use itertools::Itertools;
+use mempool::INCREMENTAL_RELAY_FEE_RATE;
 use rstest::rstest;
 use serialization::extras::non_empty_vec::DataOrNoVec;
 use test_utils::random::{make_seedable_rng, Seed};
 use wallet_storage::WalletStorageEncryptionRead;
 use wallet_types::{
     account_info::DEFAULT_ACCOUNT_INDEX,
+    partially_signed_transaction::InputSignatureStatus,
     utxo_types::{UtxoState, UtxoType},
 };
 
@@ -53,6 +55,9 @@ use wallet_types::{
 const MNEMONIC: &str =
     "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
 
+const MNEMONIC2: &str =
+    "legal winner thank year wave sausage worth useful legal winner thank yellow";
+
 const NETWORK_FEE: u128 = 10000;
 
 fn gen_random_password(rng: &mut (impl Rng + CryptoRng)) -> String {
@@ -2058,3 +2063,1265 @@ fn wallet_abandone_transactions(#[case] seed: Seed) {
         .unwrap_or(Amount::ZERO);
     assert_eq!(coin_balance, coins_after_abandon);
 }
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn create_delegation_delegate_and_withdraw(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_mainnet());
+
+    let db = create_wallet_in_memory().unwrap();
+    let mut wallet = Wallet::new_wallet(Arc::clone(&chain_config), db, MNEMONIC, None).unwrap();
+
+    // Generate a new block which sends reward to the wallet
+    let block1_amount = Amount::from_atoms(rng.gen_range(NETWORK_FEE + 100..NETWORK_FEE + 10000));
+    let address = get_address(
+        &chain_config,
+        MNEMONIC,
+        DEFAULT_ACCOUNT_INDEX,
+        KeyPurpose::ReceiveFunds,
+        0.try_into().unwrap(),
+    );
+    let block1 = Block::new(
+        vec![],
+        chain_config.genesis_block_id(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(vec![make_address_output(
+            chain_config.as_ref(),
+            address,
+            block1_amount,
+        )
+        .unwrap()]),
+    )
+    .unwrap();
+    let block1_id = block1.get_id();
+    let block1_timestamp = block1.timestamp();
+
+    wallet
+        .scan_new_blocks(BlockHeight::new(0), vec![block1], &mut WalletEventsNoOp)
+        .unwrap();
+
+    assert_eq!(
+        wallet.get_delegations(DEFAULT_ACCOUNT_INDEX).unwrap().count(),
+        0
+    );
+
+    let pool_id = PoolId::new(H256::random_using(&mut rng));
+    let (_, owner_address) = wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
+    let (delegation_id, delegation_tx) = wallet
+        .create_delegation(
+            &mut WalletEventsNoOp,
+            DEFAULT_ACCOUNT_INDEX,
+            owner_address,
+            pool_id,
+            FeeRate::new(Amount::ZERO),
+            FeeRate::new(Amount::ZERO),
+        )
+        .unwrap();
+
+    // The delegation is only known once the transaction is confirmed
+    assert_eq!(
+        wallet.get_delegations(DEFAULT_ACCOUNT_INDEX).unwrap().count(),
+        0
+    );
+
+    let delegation_amount = Amount::from_atoms(rng.gen_range(2..block1_amount.into_atoms()));
+    let delegate_tx = wallet
+        .delegate_staking(
+            &mut WalletEventsNoOp,
+            DEFAULT_ACCOUNT_INDEX,
+            delegation_amount,
+            delegation_id,
+            FeeRate::new(Amount::ZERO),
+            FeeRate::new(Amount::ZERO),
+        )
+        .unwrap();
+    assert!(delegate_tx
+        .transaction()
+        .outputs()
+        .contains(&TxOutput::DelegateStaking(delegation_amount, delegation_id)));
+
+    let block2 = Block::new(
+        vec![delegation_tx, delegate_tx],
+        block1_id.into(),
+        block1_timestamp,
+        ConsensusData::None,
+        BlockReward::new(vec![]),
+    )
+    .unwrap();
+    let block2_id = block2.get_id();
+
+    wallet
+        .scan_new_blocks(BlockHeight::new(1), vec![block2], &mut WalletEventsNoOp)
+        .unwrap();
+
+    let delegations = wallet.get_delegations(DEFAULT_ACCOUNT_INDEX).unwrap().collect_vec();
+    assert_eq!(delegations.len(), 1);
+    let (id, data) = delegations[0];
+    assert_eq!(*id, delegation_id);
+    assert_eq!(data.pool_id, pool_id);
+
+    let withdraw_amount = Amount::from_atoms(rng.gen_range(1..=delegation_amount.into_atoms()));
+    let withdraw_tx = wallet
+        .withdraw_from_delegation(
+            &mut WalletEventsNoOp,
+            DEFAULT_ACCOUNT_INDEX,
+            get_address(
+                &chain_config,
+                MNEMONIC,
+                DEFAULT_ACCOUNT_INDEX,
+                KeyPurpose::ReceiveFunds,
+                0.try_into().unwrap(),
+            ),
+            withdraw_amount,
+            delegation_id,
+            None,
+            FeeRate::new(Amount::ZERO),
+        )
+        .unwrap();
+
+    let nonce_of = |tx: &SignedTransaction| match tx.transaction().inputs() {
+        [TxInput::Account(outpoint)] => outpoint.nonce(),
+        inputs => panic!("unexpected inputs: {inputs:?}"),
+    };
+    assert_eq!(nonce_of(&withdraw_tx), AccountNonce::new(0));
+    match withdraw_tx.transaction().outputs() {
+        [TxOutput::LockThenTransfer(
+            OutputValue::Coin(amount),
+            _,
+            OutputTimeLock::ForBlockCount(_),
+        )] => {
+            assert_eq!(*amount, withdraw_amount)
+        }
+        outputs => panic!("unexpected outputs: {outputs:?}"),
+    }
+
+    let block3 = Block::new(
+        vec![withdraw_tx],
+        block2_id.into(),
+        block1_timestamp,
+        ConsensusData::None,
+        BlockReward::new(vec![]),
+    )
+    .unwrap();
+
+    wallet
+        .scan_new_blocks(BlockHeight::new(2), vec![block3], &mut WalletEventsNoOp)
+        .unwrap();
+
+    // The next withdrawal must use the next nonce
+    let withdraw_tx = wallet
+        .withdraw_from_delegation(
+            &mut WalletEventsNoOp,
+            DEFAULT_ACCOUNT_INDEX,
+            get_address(
+                &chain_config,
+                MNEMONIC,
+                DEFAULT_ACCOUNT_INDEX,
+                KeyPurpose::ReceiveFunds,
+                0.try_into().unwrap(),
+            ),
+            withdraw_amount,
+            delegation_id,
+            Some(AccountNonce::new(0)),
+            FeeRate::new(Amount::ZERO),
+        )
+        .unwrap();
+    assert_eq!(nonce_of(&withdraw_tx), AccountNonce::new(1));
+}
+
+fn create_stake_pool_in_wallet(
+    chain_config: &Arc<ChainConfig>,
+    rng: &mut impl Rng,
+    wallet: &mut DefaultWallet,
+    decommission_key: Option<PublicKey>,
+) -> (PoolId, Amount) {
+    let block1_amount = Amount::from_atoms(rng.gen_range(NETWORK_FEE + 100..NETWORK_FEE + 10000));
+    let address = get_address(
+        chain_config,
+        MNEMONIC,
+        DEFAULT_ACCOUNT_INDEX,
+        KeyPurpose::ReceiveFunds,
+        0.try_into().unwrap(),
+    );
+    let block1 = Block::new(
+        vec![],
+        chain_config.genesis_block_id(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(vec![make_address_output(
+            chain_config.as_ref(),
+            address,
+            block1_amount,
+        )
+        .unwrap()]),
+    )
+    .unwrap();
+    let block1_id = block1.get_id();
+    let block1_timestamp = block1.timestamp();
+
+    wallet
+        .scan_new_blocks(BlockHeight::new(0), vec![block1], &mut WalletEventsNoOp)
+        .unwrap();
+
+    let stake_pool_transaction = wallet
+        .create_stake_pool_tx(
+            &mut WalletEventsNoOp,
+            DEFAULT_ACCOUNT_INDEX,
+            block1_amount,
+            decommission_key,
+            FeeRate::new(Amount::ZERO),
+            FeeRate::new(Amount::ZERO),
+        )
+        .unwrap();
+    let block2 = Block::new(
+        vec![stake_pool_transaction],
+        block1_id.into(),
+        block1_timestamp,
+        ConsensusData::None,
+        BlockReward::new(vec![]),
+    )
+    .unwrap();
+
+    wallet
+        .scan_new_blocks(BlockHeight::new(1), vec![block2], &mut WalletEventsNoOp)
+        .unwrap();
+
+    let pool_ids = wallet.get_pool_ids(DEFAULT_ACCOUNT_INDEX).unwrap();
+    assert_eq!(pool_ids.len(), 1);
+
+    (pool_ids[0].0, block1_amount)
+}
+
+#[track_caller]
+fn check_decommission_tx(tx: &Transaction, pool_amount: Amount) {
+    match tx.outputs() {
+        [TxOutput::LockThenTransfer(
+            OutputValue::Coin(amount),
+            _,
+            OutputTimeLock::ForBlockCount(_),
+        )] => {
+            assert_eq!(*amount, pool_amount)
+        }
+        outputs => panic!("unexpected outputs: {outputs:?}"),
+    }
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn decommission_stake_pool(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_mainnet());
+
+    let db = create_wallet_in_memory().unwrap();
+    let mut wallet = Wallet::new_wallet(Arc::clone(&chain_config), db, MNEMONIC, None).unwrap();
+
+    let (pool_id, pool_amount) =
+        create_stake_pool_in_wallet(&chain_config, &mut rng, &mut wallet, None);
+
+    let decommission_tx = wallet
+        .decommission_stake_pool(
+            &mut WalletEventsNoOp,
+            DEFAULT_ACCOUNT_INDEX,
+            pool_id,
+            pool_amount,
+            FeeRate::new(Amount::ZERO),
+        )
+        .unwrap();
+    check_decommission_tx(decommission_tx.transaction(), pool_amount);
+
+    // The pool output is consumed by the decommission transaction
+    assert!(wallet.get_pool_ids(DEFAULT_ACCOUNT_INDEX).unwrap().is_empty());
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn decommission_stake_pool_with_key_in_another_wallet(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_mainnet());
+
+    let db = create_wallet_in_memory().unwrap();
+    let mut wallet = Wallet::new_wallet(Arc::clone(&chain_config), db, MNEMONIC, None).unwrap();
+
+    let db = create_wallet_in_memory().unwrap();
+    let mut decommission_wallet =
+        Wallet::new_wallet(Arc::clone(&chain_config), db, MNEMONIC2, None).unwrap();
+    let decommission_key = decommission_wallet.get_new_public_key(DEFAULT_ACCOUNT_INDEX).unwrap();
+
+    let (pool_id, pool_amount) =
+        create_stake_pool_in_wallet(&chain_config, &mut rng, &mut wallet, Some(decommission_key));
+
+    let err = wallet
+        .decommission_stake_pool(
+            &mut WalletEventsNoOp,
+            DEFAULT_ACCOUNT_INDEX,
+            pool_id,
+            pool_amount,
+            FeeRate::new(Amount::ZERO),
+        )
+        .unwrap_err();
+    assert_eq!(err, WalletError::DecommissionKeyNotInWallet);
+
+    let decommission_ptx = wallet
+        .decommission_stake_pool_request(
+            DEFAULT_ACCOUNT_INDEX,
+            pool_id,
+            pool_amount,
+            FeeRate::new(Amount::ZERO),
+        )
+        .unwrap();
+    assert!(!decommission_ptx.is_fully_signed(&chain_config));
+    check_decommission_tx(decommission_ptx.tx(), pool_amount);
+
+    // The staker wallet can't sign it
+    let decommission_ptx =
+        wallet.sign_raw_transaction(DEFAULT_ACCOUNT_INDEX, decommission_ptx).unwrap();
+    assert!(!decommission_ptx.is_fully_signed(&chain_config));
+
+    let decommission_ptx = decommission_wallet
+        .sign_raw_transaction(DEFAULT_ACCOUNT_INDEX, decommission_ptx)
+        .unwrap();
+    assert!(decommission_ptx.is_fully_signed(&chain_config));
+
+    let decommission_tx = decommission_ptx.into_signed_tx(&chain_config).unwrap();
+    check_decommission_tx(decommission_tx.transaction(), pool_amount);
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn create_sign_and_combine_raw_transaction(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_mainnet());
+
+    let db = create_wallet_in_memory().unwrap();
+    let mut wallet = Wallet::new_wallet(Arc::clone(&chain_config), db, MNEMONIC, None).unwrap();
+
+    let block1_amount = Amount::from_atoms(rng.gen_range(NETWORK_FEE + 1..NETWORK_FEE + 10000));
+    let address = get_address(
+        &chain_config,
+        MNEMONIC,
+        DEFAULT_ACCOUNT_INDEX,
+        KeyPurpose::ReceiveFunds,
+        0.try_into().unwrap(),
+    );
+    let block1 = Block::new(
+        vec![],
+        chain_config.genesis_block_id(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(vec![make_address_output(
+            chain_config.as_ref(),
+            address,
+            block1_amount,
+        )
+        .unwrap()]),
+    )
+    .unwrap();
+
+    wallet
+        .scan_new_blocks(BlockHeight::new(0), vec![block1], &mut WalletEventsNoOp)
+        .unwrap();
+
+    let password = gen_random_password(&mut rng);
+    wallet.encrypt_wallet(&Some(password.clone())).unwrap();
+    wallet.lock_wallet().unwrap();
+
+    let new_output = TxOutput::Transfer(
+        OutputValue::Coin(Amount::from_atoms(
+            rng.gen_range(1..=block1_amount.into_atoms() - NETWORK_FEE),
+        )),
+        Destination::AnyoneCanSpend,
+    );
+
+    // An unsigned transaction can be created without unlocking the wallet
+    let unsigned_ptx = wallet
+        .create_unsigned_transaction_to_addresses(
+            DEFAULT_ACCOUNT_INDEX,
+            vec![new_output.clone()],
+            FeeRate::new(Amount::ZERO),
+            FeeRate::new(Amount::ZERO),
+        )
+        .unwrap();
+    assert!(unsigned_ptx.tx().outputs().contains(&new_output));
+    assert!(!unsigned_ptx.is_fully_signed(&chain_config));
+    assert!(unsigned_ptx
+        .signature_statuses(&chain_config)
+        .iter()
+        .all(|status| *status == InputSignatureStatus::NotSigned));
+    assert_eq!(
+        unsigned_ptx.into_signed_tx(&chain_config).unwrap_err(),
+        PartiallySignedTransactionCreationError::NotFullySigned
+    );
+
+    // Signing requires the private keys
+    assert_eq!(
+        wallet.sign_raw_transaction(DEFAULT_ACCOUNT_INDEX, unsigned_ptx.clone()),
+        Err(WalletError::DatabaseError(
+            wallet_storage::Error::WalletLocked
+        ))
+    );
+
+    wallet.unlock_wallet(&password).unwrap();
+    let signed_ptx = wallet
+        .sign_raw_transaction(DEFAULT_ACCOUNT_INDEX, unsigned_ptx.clone())
+        .unwrap();
+    assert!(signed_ptx.is_fully_signed(&chain_config));
+    assert_eq!(signed_ptx.tx(), unsigned_ptx.tx());
+
+    // Combining with the unsigned version keeps the signatures in any order
+    let combined_ptx = if rng.gen::<bool>() {
+        unsigned_ptx.combine(signed_ptx.clone()).unwrap()
+    } else {
+        signed_ptx.clone().combine(unsigned_ptx).unwrap()
+    };
+    assert_eq!(combined_ptx, signed_ptx);
+
+    let signed_tx = combined_ptx.into_signed_tx(&chain_config).unwrap();
+    assert_eq!(signed_tx.transaction(), signed_ptx.tx());
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn classic_multisig_receive_and_spend(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_mainnet());
+
+    let db = create_wallet_in_memory().unwrap();
+    let mut wallet1 = Wallet::new_wallet(Arc::clone(&chain_config), db, MNEMONIC, None).unwrap();
+    let db = create_wallet_in_memory().unwrap();
+    let mut wallet2 = Wallet::new_wallet(Arc::clone(&chain_config), db, MNEMONIC2, None).unwrap();
+
+    let public_key1 = wallet1.get_new_public_key(DEFAULT_ACCOUNT_INDEX).unwrap();
+    let public_key2 = wallet2.get_new_public_key(DEFAULT_ACCOUNT_INDEX).unwrap();
+    let (_, foreign_public_key) =
+        crypto::key::PrivateKey::new_from_rng(&mut rng, crypto::key::KeyKind::Secp256k1Schnorr);
+
+    // A challenge must include at least one key of the account
+    let err = wallet1
+        .add_multisig_challenge(
+            DEFAULT_ACCOUNT_INDEX,
+            NonZeroU8::new(1).unwrap(),
+            vec![foreign_public_key.clone()],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        WalletError::KeyChainError(KeyChainError::NoOwnKeyInMultisigChallenge)
+    );
+
+    // 2 of 3, the third key belongs to a party that does not sign
+    let public_keys = vec![public_key1, public_key2, foreign_public_key];
+    let min_required_signatures = NonZeroU8::new(2).unwrap();
+    let multisig_address1 = wallet1
+        .add_multisig_challenge(
+            DEFAULT_ACCOUNT_INDEX,
+            min_required_signatures,
+            public_keys.clone(),
+        )
+        .unwrap();
+    let multisig_address2 = wallet2
+        .add_multisig_challenge(DEFAULT_ACCOUNT_INDEX, min_required_signatures, public_keys)
+        .unwrap();
+    assert_eq!(multisig_address1, multisig_address2);
+    assert_eq!(
+        wallet1.get_multisig_challenges(DEFAULT_ACCOUNT_INDEX).unwrap().len(),
+        1
+    );
+
+    let multisig_amount = Amount::from_atoms(rng.gen_range(NETWORK_FEE + 100..NETWORK_FEE + 10000));
+    let block1 = Block::new(
+        vec![],
+        chain_config.genesis_block_id(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(vec![make_address_output(
+            chain_config.as_ref(),
+            multisig_address1.clone(),
+            multisig_amount,
+        )
+        .unwrap()]),
+    )
+    .unwrap();
+
+    for wallet in [&mut wallet1, &mut wallet2] {
+        wallet
+            .scan_new_blocks(
+                BlockHeight::new(0),
+                vec![block1.clone()],
+                &mut WalletEventsNoOp,
+            )
+            .unwrap();
+        let utxos = wallet
+            .get_utxos(
+                DEFAULT_ACCOUNT_INDEX,
+                UtxoType::Transfer.into(),
+                UtxoState::Confirmed.into(),
+            )
+            .unwrap();
+        assert_eq!(utxos.len(), 1);
+    }
+
+    let send_amount = Amount::from_atoms(rng.gen_range(1..=multisig_amount.into_atoms()));
+    let new_output =
+        TxOutput::Transfer(OutputValue::Coin(send_amount), Destination::AnyoneCanSpend);
+
+    // The multisig UTXOs are not used for the regular transactions
+    wallet1
+        .create_transaction_to_addresses(
+            &mut WalletEventsNoOp,
+            DEFAULT_ACCOUNT_INDEX,
+            vec![new_output.clone()],
+            FeeRate::new(Amount::ZERO),
+            FeeRate::new(Amount::ZERO),
+        )
+        .unwrap_err();
+
+    let ptx = wallet1
+        .create_transaction_from_multisig(
+            DEFAULT_ACCOUNT_INDEX,
+            multisig_address1.clone(),
+            vec![new_output.clone()],
+            FeeRate::new(Amount::ZERO),
+            FeeRate::new(Amount::ZERO),
+        )
+        .unwrap();
+    assert!(ptx.tx().outputs().contains(&new_output));
+    assert_eq!(
+        ptx.signature_statuses(&chain_config),
+        vec![InputSignatureStatus::PartialMultisig {
+            required_signatures: 2,
+            num_signatures: 1
+        }]
+    );
+
+    // Signing again with the same wallet does not add anything
+    let ptx = wallet1.sign_raw_transaction(DEFAULT_ACCOUNT_INDEX, ptx).unwrap();
+    assert!(!ptx.is_fully_signed(&chain_config));
+
+    let ptx = wallet2.sign_raw_transaction(DEFAULT_ACCOUNT_INDEX, ptx).unwrap();
+    assert_eq!(
+        ptx.signature_statuses(&chain_config),
+        vec![InputSignatureStatus::FullySigned]
+    );
+
+    let signed_tx = ptx.into_signed_tx(&chain_config).unwrap();
+    let multisig_destination = multisig_address1.destination(&chain_config).unwrap();
+    assert!(
+        signed_tx.transaction().outputs().iter().all(|output| match output {
+            TxOutput::Transfer(_, destination) => {
+                *destination == Destination::AnyoneCanSpend || *destination == multisig_destination
+            }
+            _ => false,
+        })
+    );
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn watch_only_wallet(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_mainnet());
+
+    let db = create_wallet_in_memory().unwrap();
+    let mut wallet = Wallet::new_wallet(Arc::clone(&chain_config), db, MNEMONIC, None).unwrap();
+
+    let account_public_key = wallet.get_account_public_key(DEFAULT_ACCOUNT_INDEX).unwrap().clone();
+    let db = create_wallet_in_memory().unwrap();
+    let watch_only_wallet =
+        Wallet::new_watch_only_wallet(Arc::clone(&chain_config), db, account_public_key).unwrap();
+    assert!(watch_only_wallet.is_watch_only());
+    assert!(!wallet.is_watch_only());
+
+    // The watch-only flag is persisted
+    let mut watch_only_wallet =
+        Wallet::load_wallet(Arc::clone(&chain_config), watch_only_wallet.db).unwrap();
+    assert!(watch_only_wallet.is_watch_only());
+    assert_eq!(
+        watch_only_wallet.account_indexes().collect_vec(),
+        vec![&DEFAULT_ACCOUNT_INDEX]
+    );
+
+    // Both wallets derive the same addresses
+    let address = wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap().1;
+    let watch_only_address = watch_only_wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap().1;
+    assert_eq!(address, watch_only_address);
+
+    let block1_amount = Amount::from_atoms(rng.gen_range(NETWORK_FEE + 1..NETWORK_FEE + 10000));
+    let block1 = Block::new(
+        vec![],
+        chain_config.genesis_block_id(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(vec![make_address_output(
+            chain_config.as_ref(),
+            address,
+            block1_amount,
+        )
+        .unwrap()]),
+    )
+    .unwrap();
+    wallet
+        .scan_new_blocks(
+            BlockHeight::new(0),
+            vec![block1.clone()],
+            &mut WalletEventsNoOp,
+        )
+        .unwrap();
+    watch_only_wallet
+        .scan_new_blocks(BlockHeight::new(0), vec![block1], &mut WalletEventsNoOp)
+        .unwrap();
+
+    let coin_balance = watch_only_wallet
+        .get_balance(
+            DEFAULT_ACCOUNT_INDEX,
+            UtxoType::Transfer | UtxoType::LockThenTransfer,
+            UtxoState::Confirmed.into(),
+        )
+        .unwrap()
+        .get(&Currency::Coin)
+        .copied()
+        .unwrap_or(Amount::ZERO);
+    assert_eq!(coin_balance, block1_amount);
+
+    let new_output = TxOutput::Transfer(
+        OutputValue::Coin(Amount::from_atoms(
+            rng.gen_range(1..=block1_amount.into_atoms() - NETWORK_FEE),
+        )),
+        Destination::AnyoneCanSpend,
+    );
+
+    // Everything that requires the private keys fails
+    assert_eq!(
+        watch_only_wallet.create_account(None).unwrap_err(),
+        WalletError::WatchOnlyWallet
+    );
+    assert_eq!(
+        watch_only_wallet
+            .create_transaction_to_addresses(
+                &mut WalletEventsNoOp,
+                DEFAULT_ACCOUNT_INDEX,
+                vec![new_output.clone()],
+                FeeRate::new(Amount::ZERO),
+                FeeRate::new(Amount::ZERO),
+            )
+            .unwrap_err(),
+        WalletError::WatchOnlyWallet
+    );
+    assert_eq!(
+        watch_only_wallet.get_vrf_public_key(DEFAULT_ACCOUNT_INDEX).unwrap_err(),
+        WalletError::WatchOnlyWallet
+    );
+
+    // But unsigned transactions can be created and signed by the wallet holding the keys
+    let ptx = watch_only_wallet
+        .create_unsigned_transaction_to_addresses(
+            DEFAULT_ACCOUNT_INDEX,
+            vec![new_output],
+            FeeRate::new(Amount::ZERO),
+            FeeRate::new(Amount::ZERO),
+        )
+        .unwrap();
+    assert_eq!(
+        watch_only_wallet
+            .sign_raw_transaction(DEFAULT_ACCOUNT_INDEX, ptx.clone())
+            .unwrap_err(),
+        WalletError::WatchOnlyWallet
+    );
+
+    let ptx = wallet.sign_raw_transaction(DEFAULT_ACCOUNT_INDEX, ptx).unwrap();
+    assert!(ptx.is_fully_signed(&chain_config));
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn musig2_spend(#[case] seed: Seed) {
+    use common::chain::signature::sighash::{sighashtype::SigHashType, signature_hash};
+    use crypto::key::musig2::{self, KeyAggContext, SigningSession};
+    use serialization::Encode;
+
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_mainnet());
+
+    let mut wallet1 = Wallet::new_wallet(
+        Arc::clone(&chain_config),
+        create_wallet_in_memory().unwrap(),
+        MNEMONIC,
+        None,
+    )
+    .unwrap();
+    let mut wallet2 = Wallet::new_wallet(
+        Arc::clone(&chain_config),
+        create_wallet_in_memory().unwrap(),
+        MNEMONIC2,
+        None,
+    )
+    .unwrap();
+    let (foreign_private_key, foreign_public_key) =
+        crypto::key::PrivateKey::new_from_rng(&mut rng, crypto::key::KeyKind::Secp256k1Schnorr);
+
+    let public_keys = vec![
+        wallet1.get_new_public_key(DEFAULT_ACCOUNT_INDEX).unwrap(),
+        foreign_public_key,
+        wallet2.get_new_public_key(DEFAULT_ACCOUNT_INDEX).unwrap(),
+    ];
+    let aggregate_key = musig2::aggregate_public_keys(&public_keys).unwrap();
+    let destination = if rng.gen::<bool>() {
+        Destination::PublicKey(aggregate_key)
+    } else {
+        Destination::Address(PublicKeyHash::from(&aggregate_key))
+    };
+
+    let utxo = TxOutput::Transfer(
+        OutputValue::Coin(Amount::from_atoms(rng.gen_range(1..1000))),
+        destination.clone(),
+    );
+    let tx = Transaction::new(
+        0,
+        vec![TxInput::from_utxo(
+            OutPointSourceId::Transaction(Id::new(H256::random_using(&mut rng))),
+            0,
+        )],
+        vec![TxOutput::Transfer(
+            OutputValue::Coin(Amount::from_atoms(1)),
+            Destination::AnyoneCanSpend,
+        )],
+    )
+    .unwrap();
+    let ptx = PartiallySignedTransaction::new(
+        tx.clone(),
+        vec![None],
+        vec![Some(utxo.clone())],
+        vec![Some(destination)],
+    )
+    .unwrap();
+
+    // The session must be started with the keys the input is locked to
+    let mut other_keys = public_keys.clone();
+    other_keys[0] =
+        crypto::key::PrivateKey::new_from_rng(&mut rng, crypto::key::KeyKind::Secp256k1Schnorr).1;
+    assert_eq!(
+        wallet1
+            .start_musig2_input_session(DEFAULT_ACCOUNT_INDEX, &ptx, 0, &other_keys[..2])
+            .unwrap_err(),
+        WalletError::MuSig2DestinationMismatch(0)
+    );
+
+    // Round 1: every party shares the public nonce
+    let mut session1 = wallet1
+        .start_musig2_input_session(DEFAULT_ACCOUNT_INDEX, &ptx, 0, &public_keys)
+        .unwrap();
+    let mut session2 = wallet2
+        .start_musig2_input_session(DEFAULT_ACCOUNT_INDEX, &ptx, 0, &public_keys)
+        .unwrap();
+    assert_eq!(session1.signer_public_key(), &public_keys[0]);
+    assert_eq!(session2.signer_public_key(), &public_keys[2]);
+
+    let key_agg = KeyAggContext::new(&public_keys).unwrap();
+    let (foreign_secret_nonce, foreign_public_nonce) =
+        musig2::generate_nonce(&mut rng, &key_agg, &foreign_private_key, None).unwrap();
+    let public_nonces = vec![
+        session1.public_nonce().clone(),
+        foreign_public_nonce,
+        session2.public_nonce().clone(),
+    ];
+
+    // Round 2: every party produces the partial signature
+    let partial_signature1 = wallet1
+        .musig2_partial_sign(DEFAULT_ACCOUNT_INDEX, &mut session1, public_nonces.clone())
+        .unwrap();
+    let partial_signature2 = wallet2
+        .musig2_partial_sign(DEFAULT_ACCOUNT_INDEX, &mut session2, public_nonces.clone())
+        .unwrap();
+    let sighash_type = SigHashType::try_from(SigHashType::ALL).unwrap();
+    let sighash = signature_hash(sighash_type, &tx, &[Some(&utxo)], 0).unwrap();
+    let foreign_partial_signature =
+        SigningSession::new(key_agg, public_nonces.clone(), &sighash.encode())
+            .unwrap()
+            .sign(foreign_secret_nonce, &foreign_private_key)
+            .unwrap();
+
+    // The nonce of a session can't be used twice
+    assert_eq!(
+        wallet1
+            .musig2_partial_sign(DEFAULT_ACCOUNT_INDEX, &mut session1, public_nonces)
+            .unwrap_err(),
+        WalletError::MuSig2NonceAlreadyUsed
+    );
+
+    let partial_signatures = [partial_signature1, foreign_partial_signature, partial_signature2];
+    session2.verify_partial_signature(0, &partial_signatures[0]).unwrap();
+    assert!(session2.verify_partial_signature(0, &partial_signatures[1]).is_err());
+
+    assert!(!ptx.is_fully_signed(&chain_config));
+    let signed_ptx = session2.finalize(ptx, &partial_signatures).unwrap();
+    assert!(signed_ptx.is_fully_signed(&chain_config));
+    signed_ptx.into_signed_tx(&chain_config).unwrap();
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn bump_fee_and_child_pays_for_parent(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_mainnet());
+
+    let db = create_wallet_in_memory().unwrap();
+    let mut wallet = Wallet::new_wallet(Arc::clone(&chain_config), db, MNEMONIC, None).unwrap();
+
+    let block1_amount = Amount::from_atoms(rng.gen_range(100000..1000000));
+    let address = get_address(
+        &chain_config,
+        MNEMONIC,
+        DEFAULT_ACCOUNT_INDEX,
+        KeyPurpose::ReceiveFunds,
+        0.try_into().unwrap(),
+    );
+    let block1 = Block::new(
+        vec![],
+        chain_config.genesis_block_id(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(vec![make_address_output(
+            chain_config.as_ref(),
+            address,
+            block1_amount,
+        )
+        .unwrap()]),
+    )
+    .unwrap();
+    wallet
+        .scan_new_blocks(BlockHeight::new(0), vec![block1], &mut WalletEventsNoOp)
+        .unwrap();
+
+    let get_fee = |tx: &SignedTransaction, inputs_amount: Amount| {
+        let outputs_amount = tx
+            .outputs()
+            .iter()
+            .map(|output| match output {
+                TxOutput::Transfer(value, _) | TxOutput::LockThenTransfer(value, _, _) => {
+                    value.coin_amount().unwrap()
+                }
+                _ => panic!("unexpected output"),
+            })
+            .sum::<Option<Amount>>()
+            .unwrap();
+        (inputs_amount - outputs_amount).unwrap()
+    };
+    let get_coin_balance = |wallet: &DefaultWallet| {
+        wallet
+            .get_balance(
+                DEFAULT_ACCOUNT_INDEX,
+                UtxoType::Transfer | UtxoType::LockThenTransfer,
+                UtxoState::Confirmed | UtxoState::InMempool | UtxoState::Inactive,
+            )
+            .unwrap()
+            .get(&Currency::Coin)
+            .copied()
+            .unwrap_or(Amount::ZERO)
+    };
+
+    let amount_to_transfer = Amount::from_atoms(rng.gen_range(1..=block1_amount.into_atoms() / 2));
+    let output = gen_random_transfer(&mut rng, amount_to_transfer);
+    let fee_rate = FeeRate::new(Amount::from_atoms(1000));
+    let original_tx = wallet
+        .create_replaceable_transaction_to_addresses(
+            &mut WalletEventsNoOp,
+            DEFAULT_ACCOUNT_INDEX,
+            [output.clone()],
+            fee_rate,
+            fee_rate,
+        )
+        .unwrap();
+    assert!(original_tx.is_replaceable());
+    let original_fee = get_fee(&original_tx, block1_amount);
+
+    // The replacement doesn't change the wallet until it's accepted by the node
+    let new_fee_rate = FeeRate::new(Amount::from_atoms(rng.gen_range(2000..10000)));
+    let replacement_tx = wallet
+        .create_replacement_transaction(
+            DEFAULT_ACCOUNT_INDEX,
+            original_tx.transaction().get_id(),
+            new_fee_rate,
+            new_fee_rate,
+        )
+        .unwrap();
+    let pending_ids = wallet
+        .pending_transactions(DEFAULT_ACCOUNT_INDEX)
+        .unwrap()
+        .into_iter()
+        .map(|tx| tx.get_id())
+        .collect::<Vec<_>>();
+    assert_eq!(pending_ids, vec![original_tx.transaction().get_id()]);
+    wallet
+        .add_replacement_transaction(
+            &mut WalletEventsNoOp,
+            DEFAULT_ACCOUNT_INDEX,
+            original_tx.transaction().get_id(),
+            replacement_tx.clone(),
+        )
+        .unwrap();
+
+    assert!(replacement_tx.is_replaceable());
+    assert_eq!(replacement_tx.inputs(), original_tx.inputs());
+    assert!(replacement_tx.outputs().contains(&output));
+    let replacement_fee = get_fee(&replacement_tx, block1_amount);
+    let replacement_size = serialization::Encode::encoded_size(&replacement_tx);
+    assert!(
+        replacement_fee
+            >= (original_fee
+                + INCREMENTAL_RELAY_FEE_RATE.compute_fee(replacement_size).unwrap().into())
+            .unwrap()
+    );
+    assert!(replacement_fee >= new_fee_rate.compute_fee(replacement_size).unwrap().into());
+
+    // The replaced transaction is abandoned and only the replacement is pending
+    let pending_ids = wallet
+        .pending_transactions(DEFAULT_ACCOUNT_INDEX)
+        .unwrap()
+        .into_iter()
+        .map(|tx| tx.get_id())
+        .collect::<Vec<_>>();
+    assert_eq!(pending_ids, vec![replacement_tx.transaction().get_id()]);
+    assert_eq!(
+        get_coin_balance(&wallet),
+        ((block1_amount - amount_to_transfer).unwrap() - replacement_fee).unwrap()
+    );
+    assert_eq!(
+        wallet.create_replacement_transaction(
+            DEFAULT_ACCOUNT_INDEX,
+            original_tx.transaction().get_id(),
+            new_fee_rate,
+            new_fee_rate,
+        ),
+        Err(WalletError::TransactionNotPending(TxState::Abandoned))
+    );
+
+    // The child spends the change of the replacement
+    let change_amount = get_coin_balance(&wallet);
+    let cpfp_fee_rate = FeeRate::new(Amount::from_atoms(rng.gen_range(10000..20000)));
+    let child_tx = wallet
+        .create_child_pays_for_parent_transaction(
+            &mut WalletEventsNoOp,
+            DEFAULT_ACCOUNT_INDEX,
+            replacement_tx.transaction().get_id(),
+            cpfp_fee_rate,
+        )
+        .unwrap();
+    let parent_source_id = OutPointSourceId::from(replacement_tx.transaction().get_id());
+    assert!(child_tx
+        .inputs()
+        .iter()
+        .all(|input| input.utxo_outpoint().unwrap().tx_id() == parent_source_id));
+    let child_fee = get_fee(&child_tx, change_amount);
+    let package_size = serialization::Encode::encoded_size(&replacement_tx)
+        + serialization::Encode::encoded_size(&child_tx);
+    assert!(
+        (replacement_fee + child_fee).unwrap()
+            >= cpfp_fee_rate.compute_fee(package_size).unwrap().into()
+    );
+    assert_eq!(
+        get_coin_balance(&wallet),
+        (change_amount - child_fee).unwrap()
+    );
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn coin_control_and_frozen_utxos(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_mainnet());
+
+    let db = create_wallet_in_memory().unwrap();
+    let mut wallet = Wallet::new_wallet(Arc::clone(&chain_config), db, MNEMONIC, None).unwrap();
+
+    let utxo_amounts = (0..3)
+        .map(|_| Amount::from_atoms(rng.gen_range(100000..1000000)))
+        .collect::<Vec<_>>();
+    let address = get_address(
+        &chain_config,
+        MNEMONIC,
+        DEFAULT_ACCOUNT_INDEX,
+        KeyPurpose::ReceiveFunds,
+        0.try_into().unwrap(),
+    );
+    let block1 = Block::new(
+        vec![],
+        chain_config.genesis_block_id(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(
+            utxo_amounts
+                .iter()
+                .map(|amount| {
+                    make_address_output(chain_config.as_ref(), address.clone(), *amount).unwrap()
+                })
+                .collect(),
+        ),
+    )
+    .unwrap();
+    let utxos = (0..3)
+        .map(|index| UtxoOutPoint::new(block1.get_id().into(), index))
+        .collect::<Vec<_>>();
+    wallet
+        .scan_new_blocks(BlockHeight::new(0), vec![block1], &mut WalletEventsNoOp)
+        .unwrap();
+
+    wallet.set_utxo_frozen(DEFAULT_ACCOUNT_INDEX, utxos[0].clone(), true).unwrap();
+    wallet
+        .set_utxo_label(
+            DEFAULT_ACCOUNT_INDEX,
+            utxos[1].clone(),
+            Some("reserved".to_owned()),
+        )
+        .unwrap();
+    let expected_marks = BTreeMap::from([
+        (
+            utxos[0].clone(),
+            UtxoMark {
+                frozen: true,
+                label: None,
+            },
+        ),
+        (
+            utxos[1].clone(),
+            UtxoMark {
+                frozen: false,
+                label: Some("reserved".to_owned()),
+            },
+        ),
+    ]);
+    assert_eq!(
+        wallet.get_utxo_marks(DEFAULT_ACCOUNT_INDEX).unwrap(),
+        expected_marks
+    );
+
+    // The marks are persisted
+    let loaded_wallet = Wallet::load_wallet(Arc::clone(&chain_config), wallet.db.clone()).unwrap();
+    assert_eq!(
+        loaded_wallet.get_utxo_marks(DEFAULT_ACCOUNT_INDEX).unwrap(),
+        expected_marks
+    );
+
+    let fee_rate = FeeRate::new(Amount::from_atoms(1000));
+
+    // The frozen UTXO is not selected automatically
+    let unfrozen_amount = (utxo_amounts[1] + utxo_amounts[2]).unwrap();
+    assert!(wallet
+        .create_transaction_to_addresses(
+            &mut WalletEventsNoOp,
+            DEFAULT_ACCOUNT_INDEX,
+            [gen_random_transfer(
+                &mut rng,
+                (unfrozen_amount + Amount::from_atoms(1)).unwrap()
+            )],
+            fee_rate,
+            fee_rate,
+        )
+        .is_err());
+    let tx = wallet
+        .create_transaction_to_addresses(
+            &mut WalletEventsNoOp,
+            DEFAULT_ACCOUNT_INDEX,
+            [gen_random_transfer(
+                &mut rng,
+                Amount::from_atoms(rng.gen_range(1..=utxo_amounts[1].into_atoms() / 2)),
+            )],
+            fee_rate,
+            fee_rate,
+        )
+        .unwrap();
+    assert!(!tx.inputs().contains(&TxInput::Utxo(utxos[0].clone())));
+
+    // The frozen UTXO can't be spent explicitly either until it's unfrozen
+    let amount_to_transfer =
+        Amount::from_atoms(rng.gen_range(1..=utxo_amounts[0].into_atoms() / 2));
+    let output = gen_random_transfer(&mut rng, amount_to_transfer);
+    assert_eq!(
+        wallet.create_transaction_to_addresses_from_utxos(
+            &mut WalletEventsNoOp,
+            DEFAULT_ACCOUNT_INDEX,
+            [output.clone()],
+            BTreeSet::from([utxos[0].clone()]),
+            fee_rate,
+        ),
+        Err(WalletError::UtxoFrozen(utxos[0].clone()))
+    );
+
+    wallet.set_utxo_frozen(DEFAULT_ACCOUNT_INDEX, utxos[0].clone(), false).unwrap();
+    wallet.set_utxo_label(DEFAULT_ACCOUNT_INDEX, utxos[1].clone(), None).unwrap();
+    assert!(wallet.get_utxo_marks(DEFAULT_ACCOUNT_INDEX).unwrap().is_empty());
+
+    let tx = wallet
+        .create_transaction_to_addresses_from_utxos(
+            &mut WalletEventsNoOp,
+            DEFAULT_ACCOUNT_INDEX,
+            [output.clone()],
+            BTreeSet::from([utxos[0].clone()]),
+            fee_rate,
+        )
+        .unwrap();
+    assert_eq!(tx.inputs(), &[TxInput::Utxo(utxos[0].clone())]);
+    assert!(tx.outputs().contains(&output));
+    let outputs_amount = tx
+        .outputs()
+        .iter()
+        .map(|output| match output {
+            TxOutput::Transfer(value, _) | TxOutput::LockThenTransfer(value, _, _) => {
+                value.coin_amount().unwrap()
+            }
+            _ => panic!("unexpected output"),
+        })
+        .sum::<Option<Amount>>()
+        .unwrap();
+    let fee = (utxo_amounts[0] - outputs_amount).unwrap();
+    assert!(fee >= fee_rate.compute_fee(serialization::Encode::encoded_size(&tx)).unwrap().into());
+
+    // Only the UTXOs of the account that are not spent yet can be selected
+    assert_eq!(
+        wallet.create_transaction_to_addresses_from_utxos(
+            &mut WalletEventsNoOp,
+            DEFAULT_ACCOUNT_INDEX,
+            [output],
+            BTreeSet::from([utxos[0].clone()]),
+            fee_rate,
+        ),
+        Err(WalletError::UtxoNotSpendable(utxos[0].clone()))
+    );
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn utxo_marks_of_consumed_outputs(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_mainnet());
+
+    let db = create_wallet_in_memory().unwrap();
+    let mut wallet = Wallet::new_wallet(Arc::clone(&chain_config), db, MNEMONIC, None).unwrap();
+
+    let utxo_amounts = (0..2)
+        .map(|_| Amount::from_atoms(rng.gen_range(100000..1000000)))
+        .collect::<Vec<_>>();
+    let address = get_address(
+        &chain_config,
+        MNEMONIC,
+        DEFAULT_ACCOUNT_INDEX,
+        KeyPurpose::ReceiveFunds,
+        0.try_into().unwrap(),
+    );
+    let block1 = Block::new(
+        vec![],
+        chain_config.genesis_block_id(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(
+            utxo_amounts
+                .iter()
+                .map(|amount| {
+                    make_address_output(chain_config.as_ref(), address.clone(), *amount).unwrap()
+                })
+                .collect(),
+        ),
+    )
+    .unwrap();
+    let block1_id = block1.get_id();
+    let utxos = (0..2)
+        .map(|index| UtxoOutPoint::new(block1_id.into(), index))
+        .collect::<Vec<_>>();
+    wallet
+        .scan_new_blocks(BlockHeight::new(0), vec![block1], &mut WalletEventsNoOp)
+        .unwrap();
+
+    for utxo in &utxos {
+        wallet
+            .set_utxo_label(
+                DEFAULT_ACCOUNT_INDEX,
+                utxo.clone(),
+                Some("reserved".to_owned()),
+            )
+            .unwrap();
+    }
+    let marked_utxos = |wallet: &Wallet<_>| {
+        wallet
+            .get_utxo_marks(DEFAULT_ACCOUNT_INDEX)
+            .unwrap()
+            .into_keys()
+            .collect::<BTreeSet<_>>()
+    };
+
+    let fee_rate = FeeRate::new(Amount::from_atoms(1000));
+    let amount_to_transfer =
+        Amount::from_atoms(rng.gen_range(1..=utxo_amounts[0].into_atoms() / 2));
+    let output = gen_random_transfer(&mut rng, amount_to_transfer);
+    let tx = wallet
+        .create_transaction_to_addresses_from_utxos(
+            &mut WalletEventsNoOp,
+            DEFAULT_ACCOUNT_INDEX,
+            [output.clone()],
+            BTreeSet::from([utxos[0].clone()]),
+            fee_rate,
+        )
+        .unwrap();
+    let tx_id = tx.transaction().get_id();
+    let change_index = tx.outputs().iter().position(|txo| *txo != output).unwrap();
+    let change_utxo = UtxoOutPoint::new(tx_id.into(), change_index as u32);
+    wallet
+        .set_utxo_frozen(DEFAULT_ACCOUNT_INDEX, change_utxo.clone(), true)
+        .unwrap();
+    assert_eq!(
+        marked_utxos(&wallet),
+        BTreeSet::from([utxos[0].clone(), utxos[1].clone(), change_utxo])
+    );
+
+    // The outputs of the abandoned transaction are gone, while the output it spent
+    // can be spent again, so it keeps the mark
+    wallet.abandon_transaction(DEFAULT_ACCOUNT_INDEX, tx_id).unwrap();
+    assert_eq!(
+        marked_utxos(&wallet),
+        BTreeSet::from([utxos[0].clone(), utxos[1].clone()])
+    );
+
+    // The mark of the output that is spent in a block is deleted
+    let output = gen_random_transfer(&mut rng, amount_to_transfer);
+    let tx = wallet
+        .create_transaction_to_addresses_from_utxos(
+            &mut WalletEventsNoOp,
+            DEFAULT_ACCOUNT_INDEX,
+            [output],
+            BTreeSet::from([utxos[0].clone()]),
+            fee_rate,
+        )
+        .unwrap();
+    assert_eq!(
+        marked_utxos(&wallet),
+        BTreeSet::from([utxos[0].clone(), utxos[1].clone()])
+    );
+    let block2 = Block::new(
+        vec![tx],
+        block1_id.into(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(Vec::new()),
+    )
+    .unwrap();
+    wallet
+        .scan_new_blocks(BlockHeight::new(1), vec![block2], &mut WalletEventsNoOp)
+        .unwrap();
+    assert_eq!(marked_utxos(&wallet), BTreeSet::from([utxos[1].clone()]));
+
+    // The marks are deleted from the database too
+    let loaded_wallet = Wallet::load_wallet(Arc::clone(&chain_config), wallet.db.clone()).unwrap();
+    assert_eq!(
+        marked_utxos(&loaded_wallet),
+        BTreeSet::from([utxos[1].clone()])
+    );
+}
@@ -15,7 +15,10 @@
 
 use std::collections::BTreeMap;
 
-use common::{address::Address, chain::block::timestamp::BlockTimestamp};
+use common::{
+    address::Address,
+    chain::{block::timestamp::BlockTimestamp, classic_multisig::ClassicMultisigChallenge},
+};
 use crypto::key::extended::ExtendedPublicKey;
 
 use crate::{
@@ -30,8 +33,9 @@ use password::{challenge_to_sym_key, password_to_sym_key};
 mod store_tx;
 pub use store_tx::{StoreTxRo, StoreTxRoUnlocked, StoreTxRw, StoreTxRwUnlocked};
 use wallet_types::{
-    wallet_tx::WalletTx, AccountDerivationPathId, AccountId, AccountInfo, AccountKeyPurposeId,
-    AccountWalletTxId, KeychainUsageState,
+    utxo_types::UtxoMark, wallet_tx::WalletTx, AccountDerivationPathId, AccountId, AccountInfo,
+    AccountKeyPurposeId, AccountMultisigChallengeId, AccountUtxoId, AccountWalletTxId,
+    KeychainUsageState,
 };
 
 use self::store_tx::EncryptionState;
@@ -234,6 +238,9 @@ impl<B: storage::Backend> WalletStorageReadLocked for Store<B> {
         fn get_public_key(&self, id: &AccountDerivationPathId) -> crate::Result<Option<ExtendedPublicKey>>;
         fn get_public_keys(&self, account_id: &AccountId) -> crate::Result<BTreeMap<AccountDerivationPathId, ExtendedPublicKey>>;
         fn get_median_time(&self) -> crate::Result<Option<BlockTimestamp>>;
+        fn is_watch_only(&self) -> crate::Result<bool>;
+        fn get_multisig_challenges(&self, account_id: &AccountId) -> crate::Result<BTreeMap<AccountMultisigChallengeId, ClassicMultisigChallenge>>;
+        fn get_utxo_marks(&self, account_id: &AccountId) -> crate::Result<BTreeMap<AccountUtxoId, UtxoMark>>;
     }
 }
 
@@ -251,6 +258,10 @@ impl<B: storage::Backend> WalletStorageWriteLocked for Store<B> {
         fn set_public_key(&mut self, id: &AccountDerivationPathId, content: &ExtendedPublicKey) -> crate::Result<()>;
         fn det_public_key(&mut self, id: &AccountDerivationPathId) -> crate::Result<()>;
         fn set_median_time(&mut self, median_time: BlockTimestamp) -> crate::Result<()>;
+        fn set_watch_only(&mut self, watch_only: bool) -> crate::Result<()>;
+        fn set_multisig_challenge(&mut self, id: &AccountMultisigChallengeId, challenge: &ClassicMultisigChallenge) -> crate::Result<()>;
+        fn set_utxo_mark(&mut self, id: &AccountUtxoId, mark: &UtxoMark) -> crate::Result<()>;
+        fn del_utxo_mark(&mut self, id: &AccountUtxoId) -> crate::Result<()>;
     }
 }
 
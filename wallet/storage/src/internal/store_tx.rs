@@ -15,7 +15,10 @@
 
 use std::collections::BTreeMap;
 
-use common::{address::Address, chain::block::timestamp::BlockTimestamp};
+use common::{
+    address::Address,
+    chain::{block::timestamp::BlockTimestamp, classic_multisig::ClassicMultisigChallenge},
+};
 use crypto::{kdf::KdfChallenge, key::extended::ExtendedPublicKey, symkey::SymmetricKey};
 use serialization::{Codec, DecodeAll, Encode, EncodeLike};
 use storage::schema;
@@ -24,8 +27,9 @@ use utils::{
     maybe_encrypted::{MaybeEncrypted, MaybeEncryptedError},
 };
 use wallet_types::{
-    keys::RootKeyConstant, keys::RootKeys, AccountDerivationPathId, AccountId, AccountInfo,
-    AccountKeyPurposeId, AccountWalletTxId, KeychainUsageState, WalletTx,
+    keys::RootKeyConstant, keys::RootKeys, utxo_types::UtxoMark, AccountDerivationPathId,
+    AccountId, AccountInfo, AccountKeyPurposeId, AccountMultisigChallengeId, AccountUtxoId,
+    AccountWalletTxId, KeychainUsageState, WalletTx,
 };
 
 use crate::{
@@ -61,6 +65,7 @@ mod well_known {
     declare_entry!(StoreVersion: u32);
     declare_entry!(EncryptionKeyKdfChallenge: KdfChallenge);
     declare_entry!(MedianTime: BlockTimestamp);
+    declare_entry!(WatchOnly: bool);
 }
 
 #[derive(PartialEq, Clone)]
@@ -232,6 +237,32 @@ macro_rules! impl_read_ops {
             fn get_median_time(&self) -> crate::Result<Option<BlockTimestamp>> {
                 self.read_value::<well_known::MedianTime>()
             }
+
+            fn is_watch_only(&self) -> crate::Result<bool> {
+                self.read_value::<well_known::WatchOnly>().map(|v| v.unwrap_or_default())
+            }
+
+            fn get_multisig_challenges(
+                &self,
+                account_id: &AccountId,
+            ) -> crate::Result<BTreeMap<AccountMultisigChallengeId, ClassicMultisigChallenge>> {
+                self.storage
+                    .get::<db::DBMultisigChallenges, _>()
+                    .prefix_iter_decoded(account_id)
+                    .map_err(crate::Error::from)
+                    .map(Iterator::collect)
+            }
+
+            fn get_utxo_marks(
+                &self,
+                account_id: &AccountId,
+            ) -> crate::Result<BTreeMap<AccountUtxoId, UtxoMark>> {
+                self.storage
+                    .get::<db::DBUtxoMarks, _>()
+                    .prefix_iter_decoded(account_id)
+                    .map_err(crate::Error::from)
+                    .map(Iterator::collect)
+            }
         }
 
         impl<'st, B: storage::Backend> $TxType<'st, B> {
@@ -380,6 +411,26 @@ macro_rules! impl_write_ops {
             fn set_median_time(&mut self, median_time: BlockTimestamp) -> crate::Result<()> {
                 self.write_value::<well_known::MedianTime>(&median_time)
             }
+
+            fn set_watch_only(&mut self, watch_only: bool) -> crate::Result<()> {
+                self.write_value::<well_known::WatchOnly>(&watch_only)
+            }
+
+            fn set_multisig_challenge(
+                &mut self,
+                id: &AccountMultisigChallengeId,
+                challenge: &ClassicMultisigChallenge,
+            ) -> crate::Result<()> {
+                self.write::<db::DBMultisigChallenges, _, _, _>(id, challenge)
+            }
+
+            fn set_utxo_mark(&mut self, id: &AccountUtxoId, mark: &UtxoMark) -> crate::Result<()> {
+                self.write::<db::DBUtxoMarks, _, _, _>(id, mark)
+            }
+
+            fn del_utxo_mark(&mut self, id: &AccountUtxoId) -> crate::Result<()> {
+                self.storage.get_mut::<db::DBUtxoMarks, _>().del(id).map_err(Into::into)
+            }
         }
 
         impl<'st, B: storage::Backend> $TxType<'st, B> {
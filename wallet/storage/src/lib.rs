@@ -19,14 +19,18 @@ mod internal;
 mod is_transaction_seal;
 pub mod schema;
 
-use common::{address::Address, chain::block::timestamp::BlockTimestamp};
+use common::{
+    address::Address,
+    chain::{block::timestamp::BlockTimestamp, classic_multisig::ClassicMultisigChallenge},
+};
 use crypto::{kdf::KdfChallenge, key::extended::ExtendedPublicKey, symkey::SymmetricKey};
 pub use internal::{Store, StoreTxRo, StoreTxRoUnlocked, StoreTxRw, StoreTxRwUnlocked};
 use std::collections::BTreeMap;
 
 use wallet_types::{
-    keys::RootKeys, AccountDerivationPathId, AccountId, AccountInfo, AccountKeyPurposeId,
-    AccountWalletTxId, KeychainUsageState, WalletTx,
+    keys::RootKeys, utxo_types::UtxoMark, AccountDerivationPathId, AccountId, AccountInfo,
+    AccountKeyPurposeId, AccountMultisigChallengeId, AccountUtxoId, AccountWalletTxId,
+    KeychainUsageState, WalletTx,
 };
 
 /// Wallet Errors
@@ -81,6 +85,13 @@ pub trait WalletStorageReadLocked {
         account_id: &AccountId,
     ) -> Result<BTreeMap<AccountDerivationPathId, ExtendedPublicKey>>;
     fn get_median_time(&self) -> Result<Option<BlockTimestamp>>;
+    /// Watch-only wallets have no root keys and can't sign transactions
+    fn is_watch_only(&self) -> Result<bool>;
+    fn get_multisig_challenges(
+        &self,
+        account_id: &AccountId,
+    ) -> Result<BTreeMap<AccountMultisigChallengeId, ClassicMultisigChallenge>>;
+    fn get_utxo_marks(&self, account_id: &AccountId) -> Result<BTreeMap<AccountUtxoId, UtxoMark>>;
 }
 
 /// Queries on persistent wallet data with access to encrypted data
@@ -117,6 +128,14 @@ pub trait WalletStorageWriteLocked: WalletStorageReadLocked {
     ) -> Result<()>;
     fn det_public_key(&mut self, id: &AccountDerivationPathId) -> Result<()>;
     fn set_median_time(&mut self, median_time: BlockTimestamp) -> Result<()>;
+    fn set_watch_only(&mut self, watch_only: bool) -> Result<()>;
+    fn set_multisig_challenge(
+        &mut self,
+        id: &AccountMultisigChallengeId,
+        challenge: &ClassicMultisigChallenge,
+    ) -> Result<()>;
+    fn set_utxo_mark(&mut self, id: &AccountUtxoId, mark: &UtxoMark) -> Result<()>;
+    fn del_utxo_mark(&mut self, id: &AccountUtxoId) -> Result<()>;
 }
 
 /// Modifying operations on persistent wallet data with access to encrypted data
@@ -15,13 +15,14 @@
 
 //! Wallet database schema
 
-use common::address::Address;
+use common::{address::Address, chain::classic_multisig::ClassicMultisigChallenge};
 use crypto::key::extended::ExtendedPublicKey;
 use utils::maybe_encrypted::MaybeEncrypted;
 use wallet_types::{
     keys::{RootKeyConstant, RootKeys},
-    AccountDerivationPathId, AccountId, AccountInfo, AccountKeyPurposeId, AccountWalletTxId,
-    KeychainUsageState, WalletTx,
+    utxo_types::UtxoMark,
+    AccountDerivationPathId, AccountId, AccountInfo, AccountKeyPurposeId,
+    AccountMultisigChallengeId, AccountUtxoId, AccountWalletTxId, KeychainUsageState, WalletTx,
 };
 
 storage::decl_schema! {
@@ -41,5 +42,9 @@ storage::decl_schema! {
         pub DBAddresses: Map<AccountDerivationPathId, Address>,
         /// Store for block/transaction entries
         pub DBTxs: Map<AccountWalletTxId, WalletTx>,
+        /// Store for the classic multisig challenges watched by an account
+        pub DBMultisigChallenges: Map<AccountMultisigChallengeId, ClassicMultisigChallenge>,
+        /// Store for the frozen flags and labels the user put on UTXOs
+        pub DBUtxoMarks: Map<AccountUtxoId, UtxoMark>,
     }
 }
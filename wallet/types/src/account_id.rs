@@ -14,7 +14,10 @@
 // limitations under the License.
 
 use crate::keys::KeyPurpose;
-use common::{address::pubkeyhash::PublicKeyHash, chain::OutPointSourceId};
+use common::{
+    address::pubkeyhash::PublicKeyHash,
+    chain::{OutPointSourceId, UtxoOutPoint},
+};
 use crypto::key::extended::ExtendedPublicKey;
 use crypto::key::hdkd::derivation_path::DerivationPath;
 use serialization::{Decode, Encode};
@@ -66,3 +69,5 @@ impl<Id: Encode> HasPrefix<AccountId> for AccountPrefixedId<Id> {}
 pub type AccountWalletTxId = AccountPrefixedId<OutPointSourceId>;
 pub type AccountDerivationPathId = AccountPrefixedId<DerivationPath>;
 pub type AccountKeyPurposeId = AccountPrefixedId<KeyPurpose>;
+pub type AccountMultisigChallengeId = AccountPrefixedId<PublicKeyHash>;
+pub type AccountUtxoId = AccountPrefixedId<UtxoOutPoint>;
@@ -16,10 +16,14 @@
 pub mod account_id;
 pub mod account_info;
 pub mod keys;
+pub mod partially_signed_transaction;
 pub mod utxo_types;
 pub mod wallet_tx;
 
-pub use account_id::{AccountDerivationPathId, AccountId, AccountKeyPurposeId, AccountWalletTxId};
+pub use account_id::{
+    AccountDerivationPathId, AccountId, AccountKeyPurposeId, AccountMultisigChallengeId,
+    AccountUtxoId, AccountWalletTxId,
+};
 pub use account_info::AccountInfo;
 pub use keys::{KeyPurpose, KeychainUsageState, RootKeys};
 pub use wallet_tx::{BlockInfo, WalletTx};
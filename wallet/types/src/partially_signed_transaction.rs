@@ -0,0 +1,340 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common::{
+    address::pubkeyhash::PublicKeyHash,
+    chain::{
+        signature::{
+            inputsig::{
+                classical_multisig::{
+                    authorize_classical_multisig::AuthorizedClassicalMultisigSpend,
+                    multisig_partial_signature::{
+                        PartiallySignedMultisigChallenge, SigsVerifyResult,
+                    },
+                },
+                standard_signature::StandardInputSignature,
+                InputWitness,
+            },
+            sighash::signature_hash,
+        },
+        ChainConfig, Destination, SignedTransaction, Transaction, TransactionCreationError,
+        TxOutput,
+    },
+};
+use serialization::{Decode, Encode};
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum PartiallySignedTransactionCreationError {
+    #[error("The number of witnesses {0} does not match the number of inputs {1}")]
+    InvalidWitnessCount(usize, usize),
+    #[error("The number of input utxos {0} does not match the number of inputs {1}")]
+    InvalidInputUtxosCount(usize, usize),
+    #[error("The number of destinations {0} does not match the number of inputs {1}")]
+    InvalidDestinationsCount(usize, usize),
+    #[error("Transaction is not fully signed")]
+    NotFullySigned,
+    #[error("Cannot combine partially signed transactions of different transactions")]
+    DifferentTransactions,
+    #[error("Conflicting witnesses for input {0}")]
+    ConflictingWitnesses(usize),
+    #[error("Transaction creation error: {0}")]
+    TransactionCreation(#[from] TransactionCreationError),
+}
+
+/// The signing status of a single input of a partially signed transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputSignatureStatus {
+    /// There is no witness for the input yet
+    NotSigned,
+    /// Some of the signatures of a classic multisig are present, but more are required
+    PartialMultisig {
+        required_signatures: u8,
+        num_signatures: u8,
+    },
+    /// The input is signed and the signature is valid
+    FullySigned,
+    /// The destination of the input is unknown, so the witness can't be checked
+    UnknownSignature,
+    /// The witness is not valid for the input
+    InvalidSignature,
+}
+
+/// A transaction that is not signed yet or signed only partially, together with the information
+/// required to sign the remaining inputs by a different wallet.
+///
+/// For each input the container keeps the spent utxo (`None` for account inputs), the destination
+/// that has to sign the input (`None` if unknown) and the witness if the input is already signed.
+/// The witness of a classic multisig input can be partial, in which case it holds an incomplete
+/// `AuthorizedClassicalMultisigSpend` that the other parties add their signatures to.
+#[derive(Debug, Eq, PartialEq, Clone, Encode, Decode)]
+pub struct PartiallySignedTransaction {
+    tx: Transaction,
+    witnesses: Vec<Option<InputWitness>>,
+
+    input_utxos: Vec<Option<TxOutput>>,
+    destinations: Vec<Option<Destination>>,
+}
+
+impl PartiallySignedTransaction {
+    pub fn new(
+        tx: Transaction,
+        witnesses: Vec<Option<InputWitness>>,
+        input_utxos: Vec<Option<TxOutput>>,
+        destinations: Vec<Option<Destination>>,
+    ) -> Result<Self, PartiallySignedTransactionCreationError> {
+        let inputs_count = tx.inputs().len();
+        if witnesses.len() != inputs_count {
+            return Err(
+                PartiallySignedTransactionCreationError::InvalidWitnessCount(
+                    witnesses.len(),
+                    inputs_count,
+                ),
+            );
+        }
+        if input_utxos.len() != inputs_count {
+            return Err(
+                PartiallySignedTransactionCreationError::InvalidInputUtxosCount(
+                    input_utxos.len(),
+                    inputs_count,
+                ),
+            );
+        }
+        if destinations.len() != inputs_count {
+            return Err(
+                PartiallySignedTransactionCreationError::InvalidDestinationsCount(
+                    destinations.len(),
+                    inputs_count,
+                ),
+            );
+        }
+
+        Ok(Self {
+            tx,
+            witnesses,
+            input_utxos,
+            destinations,
+        })
+    }
+
+    pub fn tx(&self) -> &Transaction {
+        &self.tx
+    }
+
+    pub fn witnesses(&self) -> &[Option<InputWitness>] {
+        self.witnesses.as_ref()
+    }
+
+    pub fn input_utxos(&self) -> &[Option<TxOutput>] {
+        self.input_utxos.as_ref()
+    }
+
+    pub fn destinations(&self) -> &[Option<Destination>] {
+        self.destinations.as_ref()
+    }
+
+    pub fn count_inputs(&self) -> usize {
+        self.tx.inputs().len()
+    }
+
+    pub fn with_witnesses(
+        self,
+        witnesses: Vec<Option<InputWitness>>,
+    ) -> Result<Self, PartiallySignedTransactionCreationError> {
+        Self::new(self.tx, witnesses, self.input_utxos, self.destinations)
+    }
+
+    pub fn signature_statuses(&self, chain_config: &ChainConfig) -> Vec<InputSignatureStatus> {
+        let input_utxos = self.input_utxos.iter().map(Option::as_ref).collect::<Vec<_>>();
+
+        self.witnesses
+            .iter()
+            .zip(self.destinations.iter())
+            .enumerate()
+            .map(
+                |(input_index, (witness, destination))| match (witness, destination) {
+                    (None, _) => InputSignatureStatus::NotSigned,
+                    (Some(_), None) => InputSignatureStatus::UnknownSignature,
+                    (Some(witness), Some(destination)) => self.input_signature_status(
+                        chain_config,
+                        witness,
+                        destination,
+                        &input_utxos,
+                        input_index,
+                    ),
+                },
+            )
+            .collect()
+    }
+
+    fn input_signature_status(
+        &self,
+        chain_config: &ChainConfig,
+        witness: &InputWitness,
+        destination: &Destination,
+        input_utxos: &[Option<&TxOutput>],
+        input_index: usize,
+    ) -> InputSignatureStatus {
+        let signature = match witness {
+            InputWitness::NoSignature(_) => {
+                return match destination {
+                    Destination::AnyoneCanSpend => InputSignatureStatus::FullySigned,
+                    Destination::Address(_)
+                    | Destination::PublicKey(_)
+                    | Destination::ScriptHash(_)
+                    | Destination::ClassicMultisig(_) => InputSignatureStatus::InvalidSignature,
+                }
+            }
+            InputWitness::Standard(signature) => signature,
+        };
+
+        let sighash =
+            match signature_hash(signature.sighash_type(), &self.tx, input_utxos, input_index) {
+                Ok(sighash) => sighash,
+                Err(_) => return InputSignatureStatus::InvalidSignature,
+            };
+
+        if signature.verify_signature(chain_config, destination, &sighash, None).is_ok() {
+            return InputSignatureStatus::FullySigned;
+        }
+
+        let challenge_hash = match destination {
+            Destination::ClassicMultisig(challenge_hash) => challenge_hash,
+            Destination::Address(_)
+            | Destination::PublicKey(_)
+            | Destination::ScriptHash(_)
+            | Destination::AnyoneCanSpend => return InputSignatureStatus::InvalidSignature,
+        };
+        let spend = match AuthorizedClassicalMultisigSpend::from_data(signature.raw_signature()) {
+            Ok(spend) => spend,
+            Err(_) => return InputSignatureStatus::InvalidSignature,
+        };
+        if PublicKeyHash::from(spend.challenge()) != *challenge_hash {
+            return InputSignatureStatus::InvalidSignature;
+        }
+
+        let message = sighash.encode();
+        let verify_result =
+            PartiallySignedMultisigChallenge::from_partial(chain_config, &message, &spend)
+                .and_then(|challenge| challenge.verify_signatures(chain_config));
+        match verify_result {
+            Ok(SigsVerifyResult::Incomplete) => InputSignatureStatus::PartialMultisig {
+                required_signatures: spend.challenge().min_required_signatures(),
+                num_signatures: spend.available_signatures_count() as u8,
+            },
+            Ok(SigsVerifyResult::CompleteAndValid) => InputSignatureStatus::FullySigned,
+            Ok(SigsVerifyResult::Invalid) | Err(_) => InputSignatureStatus::InvalidSignature,
+        }
+    }
+
+    pub fn is_fully_signed(&self, chain_config: &ChainConfig) -> bool {
+        self.signature_statuses(chain_config)
+            .iter()
+            .all(|status| *status == InputSignatureStatus::FullySigned)
+    }
+
+    /// Combine the witnesses and the input information of two partially signed versions of the
+    /// same transaction, the signatures of partial classic multisig witnesses are merged
+    pub fn combine(self, other: Self) -> Result<Self, PartiallySignedTransactionCreationError> {
+        if self.tx != other.tx {
+            return Err(PartiallySignedTransactionCreationError::DifferentTransactions);
+        }
+
+        let witnesses = self
+            .witnesses
+            .into_iter()
+            .zip(other.witnesses)
+            .enumerate()
+            .map(|(input_index, (witness, other_witness))| {
+                combine_witnesses(input_index, witness, other_witness)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let input_utxos = self
+            .input_utxos
+            .into_iter()
+            .zip(other.input_utxos)
+            .map(|(utxo, other_utxo)| utxo.or(other_utxo))
+            .collect();
+        let destinations = self
+            .destinations
+            .into_iter()
+            .zip(other.destinations)
+            .map(|(destination, other_destination)| destination.or(other_destination))
+            .collect();
+
+        Self::new(self.tx, witnesses, input_utxos, destinations)
+    }
+
+    pub fn into_signed_tx(
+        self,
+        chain_config: &ChainConfig,
+    ) -> Result<SignedTransaction, PartiallySignedTransactionCreationError> {
+        if !self.is_fully_signed(chain_config) {
+            return Err(PartiallySignedTransactionCreationError::NotFullySigned);
+        }
+        let witnesses = self
+            .witnesses
+            .into_iter()
+            .collect::<Option<Vec<_>>>()
+            .ok_or(PartiallySignedTransactionCreationError::NotFullySigned)?;
+        Ok(SignedTransaction::new(self.tx, witnesses)?)
+    }
+}
+
+fn combine_witnesses(
+    input_index: usize,
+    witness: Option<InputWitness>,
+    other_witness: Option<InputWitness>,
+) -> Result<Option<InputWitness>, PartiallySignedTransactionCreationError> {
+    match (witness, other_witness) {
+        (None, witness) | (witness, None) => Ok(witness),
+        (Some(witness), Some(other_witness)) if witness == other_witness => Ok(Some(witness)),
+        (
+            Some(InputWitness::Standard(signature)),
+            Some(InputWitness::Standard(other_signature)),
+        ) => {
+            let spend = AuthorizedClassicalMultisigSpend::from_data(signature.raw_signature());
+            let other_spend =
+                AuthorizedClassicalMultisigSpend::from_data(other_signature.raw_signature());
+            match (spend, other_spend) {
+                (Ok(spend), Ok(other_spend))
+                    if spend.challenge() == other_spend.challenge()
+                        && signature.sighash_type() == other_signature.sighash_type() =>
+                {
+                    // Signatures above the required number would make the witness invalid
+                    let required_signatures = spend.challenge().min_required_signatures() as usize;
+                    let challenge = spend.challenge().clone();
+                    let mut signatures = spend.take();
+                    for (key_index, other_signature) in other_spend.take() {
+                        if signatures.len() >= required_signatures {
+                            break;
+                        }
+                        signatures.entry(key_index).or_insert(other_signature);
+                    }
+                    let spend = AuthorizedClassicalMultisigSpend::new(signatures, challenge);
+                    Ok(Some(InputWitness::Standard(StandardInputSignature::new(
+                        signature.sighash_type(),
+                        spend.encode(),
+                    ))))
+                }
+                _ => {
+                    Err(PartiallySignedTransactionCreationError::ConflictingWitnesses(input_index))
+                }
+            }
+        }
+        (Some(_), Some(_)) => {
+            Err(PartiallySignedTransactionCreationError::ConflictingWitnesses(input_index))
+        }
+    }
+}
@@ -14,6 +14,7 @@
 // limitations under the License.
 
 use common::chain::TxOutput;
+use serialization::{Decode, Encode};
 
 use crate::wallet_tx::TxState;
 
@@ -62,6 +63,22 @@ pub fn get_utxo_state(output: &TxState) -> UtxoState {
     }
 }
 
+/// The marks put by the user on a UTXO of the wallet
+#[derive(Debug, Clone, Default, PartialEq, Eq, Decode, Encode)]
+pub struct UtxoMark {
+    /// A frozen UTXO is never selected automatically as an input of a transaction
+    pub frozen: bool,
+    /// A note of the user, e.g. where the coins come from
+    pub label: Option<String>,
+}
+
+impl UtxoMark {
+    /// Return true if the mark holds nothing and doesn't have to be stored
+    pub fn is_empty(&self) -> bool {
+        !self.frozen && self.label.is_none()
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct UtxoTypes(UtxoTypeInt);
 
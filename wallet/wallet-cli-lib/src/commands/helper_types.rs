@@ -18,10 +18,18 @@ use std::fmt::Display;
 use clap::ValueEnum;
 use serialization::hex::HexEncode;
 use wallet_controller::{UtxoState, UtxoStates, UtxoType, UtxoTypes};
+use wallet_types::{
+    partially_signed_transaction::{InputSignatureStatus, PartiallySignedTransaction},
+    utxo_types::UtxoMark,
+};
 
 use common::{
-    chain::{block::timestamp::BlockTimestamp, PoolId},
-    primitives::{Amount, BlockHeight},
+    address::Address,
+    chain::{
+        block::timestamp::BlockTimestamp, classic_multisig::ClassicMultisigChallenge, ChainConfig,
+        DelegationId, PoolId, TxOutput, UtxoOutPoint,
+    },
+    primitives::{Amount, BlockHeight, Idable},
 };
 
 #[derive(Debug, Clone, Copy, ValueEnum)]
@@ -110,3 +118,85 @@ pub fn format_pool_info(
         block_timestamp
     )
 }
+
+pub fn format_delegation_info(
+    delegation_id: DelegationId,
+    pool_id: PoolId,
+    balance: Amount,
+) -> String {
+    format!(
+        "Delegation Id: {}, Pool Id: {}, Balance: {}",
+        HexEncode::hex_encode(&delegation_id),
+        HexEncode::hex_encode(&pool_id),
+        balance.into_atoms(),
+    )
+}
+
+pub fn format_multisig_info(address: &Address, challenge: &ClassicMultisigChallenge) -> String {
+    let public_keys = challenge
+        .public_keys()
+        .iter()
+        .map(HexEncode::hex_encode)
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        "Address: {}, Required signatures: {} of {}, Public keys: [{}]",
+        address,
+        challenge.min_required_signatures(),
+        challenge.public_keys().len(),
+        public_keys,
+    )
+}
+
+pub fn format_utxo_info(
+    outpoint: &UtxoOutPoint,
+    output: &TxOutput,
+    mark: Option<&UtxoMark>,
+) -> String {
+    format!(
+        "Utxo: {}, Output: {:?}, Frozen: {}, Label: {}",
+        HexEncode::hex_encode(outpoint),
+        output,
+        mark.map_or(false, |mark| mark.frozen),
+        mark.and_then(|mark| mark.label.as_deref()).unwrap_or(""),
+    )
+}
+
+fn format_signature_status(status: &InputSignatureStatus) -> String {
+    match status {
+        InputSignatureStatus::NotSigned => "not signed".to_owned(),
+        InputSignatureStatus::PartialMultisig {
+            required_signatures,
+            num_signatures,
+        } => format!(
+            "partially signed multisig, {num_signatures} of {required_signatures} signatures"
+        ),
+        InputSignatureStatus::FullySigned => "signed".to_owned(),
+        InputSignatureStatus::UnknownSignature => {
+            "signed, but the destination is unknown".to_owned()
+        }
+        InputSignatureStatus::InvalidSignature => "invalid signature".to_owned(),
+    }
+}
+
+pub fn format_partially_signed_transaction_info(
+    chain_config: &ChainConfig,
+    ptx: &PartiallySignedTransaction,
+) -> String {
+    let statuses = ptx.signature_statuses(chain_config);
+    let inputs = statuses
+        .iter()
+        .enumerate()
+        .map(|(index, status)| format!("Input {}: {}", index, format_signature_status(status)))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let fully_signed = statuses.iter().all(|status| *status == InputSignatureStatus::FullySigned);
+
+    format!(
+        "Transaction Id: {}\nOutputs count: {}\n{}\nFully signed: {}",
+        ptx.tx().get_id(),
+        ptx.tx().outputs().len(),
+        inputs,
+        if fully_signed { "yes" } else { "no" },
+    )
+}
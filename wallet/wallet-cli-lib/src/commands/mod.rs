@@ -15,25 +15,30 @@
 
 mod helper_types;
 
-use std::{path::PathBuf, str::FromStr, sync::Arc};
+use std::{num::NonZeroU8, path::PathBuf, str::FromStr, sync::Arc};
 
 use clap::Parser;
 use common::{
     address::Address,
     chain::{
         tokens::{Metadata, TokenCreator, TokenId},
-        Block, ChainConfig, PoolId, SignedTransaction, Transaction,
+        Block, ChainConfig, DelegationId, PoolId, SignedTransaction, Transaction, UtxoOutPoint,
     },
     primitives::{Amount, BlockHeight, Id, H256},
 };
-use crypto::key::{hdkd::u31::U31, PublicKey};
+use crypto::key::{extended::ExtendedPublicKey, hdkd::u31::U31, PublicKey};
+use mempool::FeeRate;
 use serialization::{hex::HexEncode, hex_encoded::HexEncoded};
 use wallet::{account::Currency, wallet_events::WalletEventsNoOp};
-use wallet_controller::{NodeInterface, NodeRpcClient, PeerId, DEFAULT_ACCOUNT_INDEX};
+use wallet_controller::{NodeInterface, NodeRpcClient, PeerId, UtxoType, DEFAULT_ACCOUNT_INDEX};
+use wallet_types::partially_signed_transaction::PartiallySignedTransaction;
 
 use crate::{errors::WalletCliError, CliController};
 
-use self::helper_types::{format_pool_info, CliUtxoState, CliUtxoTypes};
+use self::helper_types::{
+    format_delegation_info, format_multisig_info, format_partially_signed_transaction_info,
+    format_pool_info, format_utxo_info, CliUtxoState, CliUtxoTypes,
+};
 
 #[derive(Debug, Parser)]
 #[clap(rename_all = "lower")]
@@ -47,6 +52,17 @@ pub enum WalletCommand {
         mnemonic: Option<String>,
     },
 
+    /// Create a watch-only wallet from the extended public key of an account, exported with
+    /// `exportaccountpublickey`. The wallet tracks the balance and the transactions of the account
+    /// and creates unsigned transactions, but it can't sign them.
+    CreateWatchOnlyWallet {
+        /// File path
+        wallet_path: PathBuf,
+
+        /// Hex encoded extended public key of the account
+        account_public_key: HexEncoded<ExtendedPublicKey>,
+    },
+
     /// Open exiting wallet
     OpenWallet {
         /// File path
@@ -151,6 +167,22 @@ pub enum WalletCommand {
         transaction_id: HexEncoded<Id<Transaction>>,
     },
 
+    /// Replace a pending transaction with one paying a higher fee.
+    /// The fee rate is in coins per 1000 bytes, the node estimate is used if it's not provided.
+    /// The node must run with `--mempool-enable-rbf true`, otherwise use `ChildPaysForParent`.
+    BumpFee {
+        transaction_id: HexEncoded<Id<Transaction>>,
+        fee_rate: Option<String>,
+    },
+
+    /// Speed up a pending transaction by spending its outputs that belong to the selected
+    /// account with a fee that is high enough for both transactions.
+    /// The fee rate is in coins per 1000 bytes, the node estimate is used if it's not provided.
+    ChildPaysForParent {
+        transaction_id: HexEncoded<Id<Transaction>>,
+        fee_rate: Option<String>,
+    },
+
     /// Issue a new token
     IssueNewToken {
         token_ticker: String,
@@ -190,23 +222,86 @@ pub enum WalletCommand {
         utxo_states: Vec<CliUtxoState>,
     },
 
+    /// List the coin UTXOs of the selected account with their frozen flags and labels.
+    /// The printed hex encoded UTXOs are the ones to use with the coin control commands.
+    ListCoins {
+        #[arg(default_values_t = vec![CliUtxoState::Confirmed])]
+        utxo_states: Vec<CliUtxoState>,
+    },
+
+    /// Freeze a UTXO, so that it's never selected automatically as an input of new transactions
+    FreezeUtxo {
+        utxo: HexEncoded<UtxoOutPoint>,
+    },
+
+    /// Make a frozen UTXO available to the automatic selection of inputs again
+    UnfreezeUtxo {
+        utxo: HexEncoded<UtxoOutPoint>,
+    },
+
+    /// Set the label of a UTXO, the label is removed if none is provided
+    LabelUtxo {
+        utxo: HexEncoded<UtxoOutPoint>,
+        label: Option<String>,
+    },
+
     /// List the pending transactions that can be abandoned
     ListPendingTransactions,
 
     /// List available Pool Ids
     ListPoolIds,
 
+    /// List the delegations owned by the selected account with their pools and balances
+    ListDelegationIds,
+
     /// Generate a new unused address
     NewAddress,
 
     /// Generate a new unused public key
     NewPublicKey,
 
+    /// Print the extended public key of the selected account, it can be used to create
+    /// a watch-only wallet with `createwatchonlywallet`
+    ExportAccountPublicKey,
+
+    /// Add an M-of-N classic multisig address to the selected account and print it.
+    /// At least one of the public keys must belong to the account, the others can be
+    /// public keys of other wallets. Funds sent to the address are tracked from now on.
+    AddMultisigAddress {
+        /// The number of signatures required to spend from the address
+        min_required_signatures: NonZeroU8,
+        /// Hex encoded public keys of the participants
+        public_keys: Vec<HexEncoded<PublicKey>>,
+    },
+
+    /// List the classic multisig addresses of the selected account
+    ListMultisigAddresses,
+
+    /// Create a transaction that sends coins from a multisig address of the selected account
+    /// and sign it with the keys of the account. Prints the partially signed transaction
+    /// that must be signed by the other participants with `signrawtransaction`.
+    SendFromMultisigAddress {
+        multisig_address: String,
+        address: String,
+        amount: String,
+    },
+
     GetVrfPublicKey,
 
     SendToAddress {
         address: String,
         amount: String,
+        /// Signal that the transaction can be replaced with one paying a higher fee (see `BumpFee`)
+        #[arg(long)]
+        replaceable: bool,
+    },
+
+    /// Send coins to the address spending exactly the provided UTXOs of the selected account,
+    /// instead of selecting the inputs automatically. Frozen UTXOs can't be spent.
+    SendToAddressFromUtxos {
+        address: String,
+        amount: String,
+        utxos: Vec<HexEncoded<UtxoOutPoint>>,
     },
 
     SendTokensToAddress {
@@ -221,6 +316,76 @@ pub enum WalletCommand {
         decomission_key: Option<HexEncoded<PublicKey>>,
     },
 
+    /// Create a delegation to a stake pool, the owner address is allowed to withdraw from it
+    CreateDelegation {
+        owner_address: String,
+        pool_id: HexEncoded<PoolId>,
+    },
+
+    /// Delegate coins to an existing delegation
+    DelegateStaking {
+        amount: String,
+        delegation_id: HexEncoded<DelegationId>,
+    },
+
+    /// Withdraw coins from a delegation owned by the selected account.
+    /// The withdrawn coins are locked for the delegation spend maturity period.
+    WithdrawFromDelegation {
+        address: String,
+        amount: String,
+        delegation_id: HexEncoded<DelegationId>,
+    },
+
+    /// Decommission a stake pool of the selected account. The pool pledge is sent to a new address
+    /// of the account and is locked for the decommission maturity period.
+    /// The decommission key of the pool must be in this wallet.
+    DecommissionStakePool {
+        pool_id: HexEncoded<PoolId>,
+    },
+
+    /// Create a transaction that decommissions a stake pool of the selected account when the
+    /// decommission key of the pool is in a different wallet.
+    /// The printed partially signed transaction must be signed with `signrawtransaction`
+    /// in the wallet holding the decommission key.
+    DecommissionStakePoolRequest {
+        pool_id: HexEncoded<PoolId>,
+    },
+
+    /// Sign the inputs of a partially signed transaction that can be signed by the selected account.
+    /// Prints the signed transaction if all the inputs are signed now,
+    /// and the partially signed transaction otherwise.
+    SignRawTransaction {
+        /// Hex encoded partially signed transaction
+        transaction: HexEncoded<PartiallySignedTransaction>,
+    },
+
+    /// Create a transaction that sends coins from the selected account to the address, without
+    /// signing it. Prints the partially signed transaction to be signed with `signrawtransaction`,
+    /// for example by an offline wallet.
+    CreateRawTransaction {
+        address: String,
+        amount: String,
+    },
+
+    /// Combine the signatures of several partially signed versions of the same transaction,
+    /// including the partial signatures of classic multisig inputs
+    CombineRawTransactions {
+        /// Hex encoded partially signed transactions
+        transactions: Vec<HexEncoded<PartiallySignedTransaction>>,
+    },
+
+    /// Print the signing status of each input of a partially signed transaction
+    InspectRawTransaction {
+        /// Hex encoded partially signed transaction
+        transaction: HexEncoded<PartiallySignedTransaction>,
+    },
+
+    /// Convert a fully signed partially signed transaction into a transaction that can be submitted
+    FinalizeRawTransaction {
+        /// Hex encoded partially signed transaction
+        transaction: HexEncoded<PartiallySignedTransaction>,
+    },
+
     /// Node version
     NodeVersion,
 
@@ -352,6 +517,14 @@ impl CommandHandler {
         Ok(())
     }
 
+    /// Select the first account of the wallet, the only account of a watch-only wallet
+    /// is not necessarily the one with the default index
+    fn select_first_account(&mut self, account_index: Option<U31>) {
+        if let (Some(state), Some(account_index)) = (self.state.as_mut(), account_index) {
+            state.selected_account = account_index;
+        }
+    }
+
     fn selected_account(&self) -> Option<U31> {
         self.state.as_ref().map(|state| state.selected_account)
     }
@@ -374,14 +547,19 @@ impl CommandHandler {
         tx: SignedTransaction,
     ) -> Result<ConsoleCommand, WalletCliError> {
         let status = rpc_client.submit_transaction(tx).await.map_err(WalletCliError::RpcError)?;
-        let status_text = match status {
+        Ok(ConsoleCommand::Print(
+            Self::tx_status_text(status).to_owned(),
+        ))
+    }
+
+    fn tx_status_text(status: mempool::TxStatus) -> &'static str {
+        match status {
             mempool::TxStatus::InMempool => "The transaction was submitted successfully",
             mempool::TxStatus::InOrphanPool => {
                 // Mempool should reject the transaction and not return `InOrphanPool`
                 "The transaction has been added to the orphan pool"
             }
-        };
-        Ok(ConsoleCommand::Print(status_text.to_owned()))
+        }
     }
 
     pub async fn handle_wallet_command(
@@ -443,6 +621,39 @@ impl CommandHandler {
                 })
             }
 
+            WalletCommand::CreateWatchOnlyWallet {
+                wallet_path,
+                account_public_key,
+            } => {
+                utils::ensure!(
+                    controller_opt.is_none(),
+                    WalletCliError::WalletFileAlreadyOpen
+                );
+
+                let wallet = CliController::create_watch_only_wallet(
+                    Arc::clone(chain_config),
+                    wallet_path,
+                    account_public_key.take(),
+                )
+                .map_err(WalletCliError::Controller)?;
+
+                let account_names = wallet.account_names().cloned().collect();
+                let first_account = wallet.account_indexes().next().copied();
+                *controller_opt = Some(CliController::new(
+                    Arc::clone(chain_config),
+                    rpc_client.clone(),
+                    wallet,
+                    WalletEventsNoOp,
+                ));
+
+                self.set_accounts(account_names);
+                self.select_first_account(first_account);
+                Ok(ConsoleCommand::SetStatus {
+                    status: self.repl_status(),
+                    print_message: "New watch-only wallet created successfully".to_owned(),
+                })
+            }
+
             WalletCommand::OpenWallet { wallet_path } => {
                 utils::ensure!(
                     controller_opt.is_none(),
@@ -453,6 +664,7 @@ impl CommandHandler {
                     .map_err(WalletCliError::Controller)?;
 
                 let account_names = wallet.account_names().cloned().collect();
+                let first_account = wallet.account_indexes().next().copied();
                 *controller_opt = Some(CliController::new(
                     Arc::clone(chain_config),
                     rpc_client.clone(),
@@ -461,6 +673,7 @@ impl CommandHandler {
                 ));
 
                 self.set_accounts(account_names);
+                self.select_first_account(first_account);
                 Ok(ConsoleCommand::SetStatus {
                     status: self.repl_status(),
                     print_message: "Wallet loaded successfully".to_owned(),
@@ -683,6 +896,48 @@ impl CommandHandler {
                 ))
             }
 
+            WalletCommand::BumpFee {
+                transaction_id,
+                fee_rate,
+            } => {
+                let fee_rate = fee_rate
+                    .map(|fee_rate| parse_coin_amount(chain_config, &fee_rate).map(FeeRate::new))
+                    .transpose()?;
+                let status = controller_opt
+                    .as_mut()
+                    .ok_or(WalletCliError::NoWallet)?
+                    .bump_transaction_fee(
+                        selected_account.ok_or(WalletCliError::NoSelectedAccount)?,
+                        transaction_id.take(),
+                        fee_rate,
+                    )
+                    .await
+                    .map_err(WalletCliError::Controller)?;
+                Ok(ConsoleCommand::Print(
+                    Self::tx_status_text(status).to_owned(),
+                ))
+            }
+
+            WalletCommand::ChildPaysForParent {
+                transaction_id,
+                fee_rate,
+            } => {
+                let fee_rate = fee_rate
+                    .map(|fee_rate| parse_coin_amount(chain_config, &fee_rate).map(FeeRate::new))
+                    .transpose()?;
+                let tx = controller_opt
+                    .as_mut()
+                    .ok_or(WalletCliError::NoWallet)?
+                    .create_child_pays_for_parent_transaction(
+                        selected_account.ok_or(WalletCliError::NoSelectedAccount)?,
+                        transaction_id.take(),
+                        fee_rate,
+                    )
+                    .await
+                    .map_err(WalletCliError::Controller)?;
+                Self::broadcast_transaction(rpc_client, tx).await
+            }
+
             WalletCommand::IssueNewToken {
                 token_ticker,
                 amount_to_issue,
@@ -813,6 +1068,66 @@ impl CommandHandler {
                 Ok(ConsoleCommand::Print(format!("{utxos:#?}")))
             }
 
+            WalletCommand::ListCoins { utxo_states } => {
+                let account_index = selected_account.ok_or(WalletCliError::NoSelectedAccount)?;
+                let controller = controller_opt.as_mut().ok_or(WalletCliError::NoWallet)?;
+                let utxos = controller
+                    .get_utxos(
+                        account_index,
+                        UtxoType::Transfer | UtxoType::LockThenTransfer,
+                        CliUtxoState::to_wallet_states(utxo_states),
+                    )
+                    .map_err(WalletCliError::Controller)?;
+                let marks =
+                    controller.get_utxo_marks(account_index).map_err(WalletCliError::Controller)?;
+                let coins: Vec<_> = utxos
+                    .iter()
+                    .map(|(outpoint, output)| {
+                        format_utxo_info(outpoint, output, marks.get(outpoint))
+                    })
+                    .collect();
+                Ok(ConsoleCommand::Print(coins.join("\n")))
+            }
+
+            WalletCommand::FreezeUtxo { utxo } => {
+                controller_opt
+                    .as_mut()
+                    .ok_or(WalletCliError::NoWallet)?
+                    .set_utxo_frozen(
+                        selected_account.ok_or(WalletCliError::NoSelectedAccount)?,
+                        utxo.take(),
+                        true,
+                    )
+                    .map_err(WalletCliError::Controller)?;
+                Ok(ConsoleCommand::Print("Success".to_owned()))
+            }
+
+            WalletCommand::UnfreezeUtxo { utxo } => {
+                controller_opt
+                    .as_mut()
+                    .ok_or(WalletCliError::NoWallet)?
+                    .set_utxo_frozen(
+                        selected_account.ok_or(WalletCliError::NoSelectedAccount)?,
+                        utxo.take(),
+                        false,
+                    )
+                    .map_err(WalletCliError::Controller)?;
+                Ok(ConsoleCommand::Print("Success".to_owned()))
+            }
+
+            WalletCommand::LabelUtxo { utxo, label } => {
+                controller_opt
+                    .as_mut()
+                    .ok_or(WalletCliError::NoWallet)?
+                    .set_utxo_label(
+                        selected_account.ok_or(WalletCliError::NoSelectedAccount)?,
+                        utxo.take(),
+                        label,
+                    )
+                    .map_err(WalletCliError::Controller)?;
+                Ok(ConsoleCommand::Print("Success".to_owned()))
+            }
+
             WalletCommand::ListPendingTransactions => {
                 let utxos = controller_opt
                     .as_mut()
@@ -833,6 +1148,17 @@ impl CommandHandler {
                 Ok(ConsoleCommand::Print(address.1.get().to_owned()))
             }
 
+            WalletCommand::ExportAccountPublicKey => {
+                let public_key = controller_opt
+                    .as_mut()
+                    .ok_or(WalletCliError::NoWallet)?
+                    .get_account_public_key(
+                        selected_account.ok_or(WalletCliError::NoSelectedAccount)?,
+                    )
+                    .map_err(WalletCliError::Controller)?;
+                Ok(ConsoleCommand::Print(public_key.hex_encode()))
+            }
+
             WalletCommand::NewPublicKey => {
                 let public_key = controller_opt
                     .as_mut()
@@ -842,6 +1168,62 @@ impl CommandHandler {
                 Ok(ConsoleCommand::Print(public_key.hex_encode()))
             }
 
+            WalletCommand::AddMultisigAddress {
+                min_required_signatures,
+                public_keys,
+            } => {
+                let address = controller_opt
+                    .as_mut()
+                    .ok_or(WalletCliError::NoWallet)?
+                    .add_multisig_challenge(
+                        selected_account.ok_or(WalletCliError::NoSelectedAccount)?,
+                        min_required_signatures,
+                        public_keys.into_iter().map(HexEncoded::take).collect(),
+                    )
+                    .map_err(WalletCliError::Controller)?;
+                Ok(ConsoleCommand::Print(address.get().to_owned()))
+            }
+
+            WalletCommand::ListMultisigAddresses => {
+                let multisigs: Vec<_> = controller_opt
+                    .as_mut()
+                    .ok_or(WalletCliError::NoWallet)?
+                    .get_multisig_challenges(
+                        selected_account.ok_or(WalletCliError::NoSelectedAccount)?,
+                    )
+                    .map_err(WalletCliError::Controller)?
+                    .iter()
+                    .map(|(address, challenge)| format_multisig_info(address, challenge))
+                    .collect();
+                Ok(ConsoleCommand::Print(format!("[{}]", multisigs.join(", "))))
+            }
+
+            WalletCommand::SendFromMultisigAddress {
+                multisig_address,
+                address,
+                amount,
+            } => {
+                let amount = parse_coin_amount(chain_config, &amount)?;
+                let multisig_address = parse_address(chain_config, &multisig_address)?;
+                let address = parse_address(chain_config, &address)?;
+                let ptx = controller_opt
+                    .as_mut()
+                    .ok_or(WalletCliError::NoWallet)?
+                    .create_transaction_from_multisig(
+                        selected_account.ok_or(WalletCliError::NoSelectedAccount)?,
+                        multisig_address,
+                        address,
+                        amount,
+                    )
+                    .await
+                    .map_err(WalletCliError::Controller)?;
+                Ok(ConsoleCommand::Print(format!(
+                    "{}\n{}",
+                    format_partially_signed_transaction_info(chain_config, &ptx),
+                    ptx.hex_encode()
+                )))
+            }
+
             WalletCommand::GetVrfPublicKey => {
                 let vrf_public_key = controller_opt
                     .as_mut()
@@ -851,7 +1233,11 @@ impl CommandHandler {
                 Ok(ConsoleCommand::Print(vrf_public_key.hex_encode()))
             }
 
-            WalletCommand::SendToAddress { address, amount } => {
+            WalletCommand::SendToAddress {
+                address,
+                amount,
+                replaceable,
+            } => {
                 let amount = parse_coin_amount(chain_config, &amount)?;
                 let address = parse_address(chain_config, &address)?;
                 let tx = controller_opt
@@ -861,6 +1247,28 @@ impl CommandHandler {
                         selected_account.ok_or(WalletCliError::NoSelectedAccount)?,
                         address,
                         amount,
+                        replaceable,
+                    )
+                    .await
+                    .map_err(WalletCliError::Controller)?;
+                Self::broadcast_transaction(rpc_client, tx).await
+            }
+
+            WalletCommand::SendToAddressFromUtxos {
+                address,
+                amount,
+                utxos,
+            } => {
+                let amount = parse_coin_amount(chain_config, &amount)?;
+                let address = parse_address(chain_config, &address)?;
+                let tx = controller_opt
+                    .as_mut()
+                    .ok_or(WalletCliError::NoWallet)?
+                    .send_to_address_from_utxos(
+                        selected_account.ok_or(WalletCliError::NoSelectedAccount)?,
+                        address,
+                        amount,
+                        utxos.into_iter().map(HexEncoded::take).collect(),
                     )
                     .await
                     .map_err(WalletCliError::Controller)?;
@@ -907,6 +1315,172 @@ impl CommandHandler {
                 Self::broadcast_transaction(rpc_client, tx).await
             }
 
+            WalletCommand::CreateDelegation {
+                owner_address,
+                pool_id,
+            } => {
+                let owner_address = parse_address(chain_config, &owner_address)?;
+                let (delegation_id, tx) = controller_opt
+                    .as_mut()
+                    .ok_or(WalletCliError::NoWallet)?
+                    .create_delegation(
+                        selected_account.ok_or(WalletCliError::NoSelectedAccount)?,
+                        owner_address,
+                        pool_id.take(),
+                    )
+                    .await
+                    .map_err(WalletCliError::Controller)?;
+                Self::broadcast_transaction(rpc_client, tx).await?;
+                Ok(ConsoleCommand::Print(format!(
+                    "A new delegation has been created with ID: {}",
+                    HexEncode::hex_encode(&delegation_id),
+                )))
+            }
+
+            WalletCommand::DelegateStaking {
+                amount,
+                delegation_id,
+            } => {
+                let amount = parse_coin_amount(chain_config, &amount)?;
+                let tx = controller_opt
+                    .as_mut()
+                    .ok_or(WalletCliError::NoWallet)?
+                    .delegate_staking(
+                        selected_account.ok_or(WalletCliError::NoSelectedAccount)?,
+                        amount,
+                        delegation_id.take(),
+                    )
+                    .await
+                    .map_err(WalletCliError::Controller)?;
+                Self::broadcast_transaction(rpc_client, tx).await
+            }
+
+            WalletCommand::WithdrawFromDelegation {
+                address,
+                amount,
+                delegation_id,
+            } => {
+                let amount = parse_coin_amount(chain_config, &amount)?;
+                let address = parse_address(chain_config, &address)?;
+                let tx = controller_opt
+                    .as_mut()
+                    .ok_or(WalletCliError::NoWallet)?
+                    .withdraw_from_delegation(
+                        selected_account.ok_or(WalletCliError::NoSelectedAccount)?,
+                        address,
+                        amount,
+                        delegation_id.take(),
+                    )
+                    .await
+                    .map_err(WalletCliError::Controller)?;
+                Self::broadcast_transaction(rpc_client, tx).await
+            }
+
+            WalletCommand::DecommissionStakePool { pool_id } => {
+                let tx = controller_opt
+                    .as_mut()
+                    .ok_or(WalletCliError::NoWallet)?
+                    .decommission_stake_pool(
+                        selected_account.ok_or(WalletCliError::NoSelectedAccount)?,
+                        pool_id.take(),
+                    )
+                    .await
+                    .map_err(WalletCliError::Controller)?;
+                Self::broadcast_transaction(rpc_client, tx).await
+            }
+
+            WalletCommand::DecommissionStakePoolRequest { pool_id } => {
+                let ptx = controller_opt
+                    .as_mut()
+                    .ok_or(WalletCliError::NoWallet)?
+                    .decommission_stake_pool_request(
+                        selected_account.ok_or(WalletCliError::NoSelectedAccount)?,
+                        pool_id.take(),
+                    )
+                    .await
+                    .map_err(WalletCliError::Controller)?;
+                Ok(ConsoleCommand::Print(format!(
+                    "Partially signed transaction, sign it in the wallet with the decommission key:\n{}",
+                    ptx.hex_encode()
+                )))
+            }
+
+            WalletCommand::SignRawTransaction { transaction } => {
+                let ptx = controller_opt
+                    .as_mut()
+                    .ok_or(WalletCliError::NoWallet)?
+                    .sign_raw_transaction(
+                        selected_account.ok_or(WalletCliError::NoSelectedAccount)?,
+                        transaction.take(),
+                    )
+                    .map_err(WalletCliError::Controller)?;
+
+                let result = if ptx.is_fully_signed(chain_config) {
+                    let tx = ptx
+                        .into_signed_tx(chain_config)
+                        .map_err(|e| WalletCliError::InvalidInput(e.to_string()))?;
+                    format!(
+                        "The transaction is fully signed and can be submitted:\n{}",
+                        tx.hex_encode()
+                    )
+                } else {
+                    format!(
+                        "Not all inputs could be signed, the partially signed transaction:\n{}",
+                        ptx.hex_encode()
+                    )
+                };
+                Ok(ConsoleCommand::Print(result))
+            }
+
+            WalletCommand::CreateRawTransaction { address, amount } => {
+                let amount = parse_coin_amount(chain_config, &amount)?;
+                let address = parse_address(chain_config, &address)?;
+                let ptx = controller_opt
+                    .as_mut()
+                    .ok_or(WalletCliError::NoWallet)?
+                    .create_unsigned_transaction(
+                        selected_account.ok_or(WalletCliError::NoSelectedAccount)?,
+                        address,
+                        amount,
+                    )
+                    .await
+                    .map_err(WalletCliError::Controller)?;
+                Ok(ConsoleCommand::Print(format!(
+                    "Partially signed transaction, sign it with signrawtransaction:\n{}",
+                    ptx.hex_encode()
+                )))
+            }
+
+            WalletCommand::CombineRawTransactions { transactions } => {
+                let mut transactions = transactions.into_iter().map(HexEncoded::take);
+                let first = transactions.next().ok_or_else(|| {
+                    WalletCliError::InvalidInput("No transactions to combine".to_owned())
+                })?;
+                let ptx = transactions
+                    .try_fold(first, |ptx, other| ptx.combine(other))
+                    .map_err(|e| WalletCliError::InvalidInput(e.to_string()))?;
+                Ok(ConsoleCommand::Print(format!(
+                    "{}\n{}",
+                    format_partially_signed_transaction_info(chain_config, &ptx),
+                    ptx.hex_encode()
+                )))
+            }
+
+            WalletCommand::InspectRawTransaction { transaction } => {
+                let ptx = transaction.take();
+                Ok(ConsoleCommand::Print(
+                    format_partially_signed_transaction_info(chain_config, &ptx),
+                ))
+            }
+
+            WalletCommand::FinalizeRawTransaction { transaction } => {
+                let tx = transaction
+                    .take()
+                    .into_signed_tx(chain_config)
+                    .map_err(|e| WalletCliError::InvalidInput(e.to_string()))?;
+                Ok(ConsoleCommand::Print(tx.hex_encode()))
+            }
+
             WalletCommand::NodeVersion => {
                 let version = rpc_client.node_version().await.map_err(WalletCliError::RpcError)?;
                 Ok(ConsoleCommand::Print(version))
@@ -927,6 +1501,24 @@ impl CommandHandler {
                 Ok(ConsoleCommand::Print(format!("[{}]", pool_ids.join(", "))))
             }
 
+            WalletCommand::ListDelegationIds => {
+                let delegations: Vec<_> = controller_opt
+                    .as_mut()
+                    .ok_or(WalletCliError::NoWallet)?
+                    .get_delegations(selected_account.ok_or(WalletCliError::NoSelectedAccount)?)
+                    .await
+                    .map_err(WalletCliError::Controller)?
+                    .into_iter()
+                    .map(|(delegation_id, pool_id, balance)| {
+                        format_delegation_info(delegation_id, pool_id, balance)
+                    })
+                    .collect();
+                Ok(ConsoleCommand::Print(format!(
+                    "[{}]",
+                    delegations.join(", ")
+                )))
+            }
+
             WalletCommand::NodeShutdown => {
                 rpc_client.node_shutdown().await.map_err(WalletCliError::RpcError)?;
                 Ok(ConsoleCommand::Print("Success".to_owned()))
@@ -175,6 +175,7 @@ async fn start_node(chain_config: Arc<ChainConfig>) -> (subsystem::Manager, Sock
     let p2p_config = p2p::config::P2pConfig {
         bind_addresses: vec!["127.0.0.1:0".to_owned()],
         socks5_proxy: Default::default(),
+        tor_control_address: Default::default(),
         disable_noise: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: Default::default(),
@@ -221,8 +222,10 @@ async fn start_node(chain_config: Arc<ChainConfig>) -> (subsystem::Manager, Sock
 
     let mempool = mempool::make_mempool(
         Arc::clone(&chain_config),
+        Default::default(),
         chainstate.clone(),
         Default::default(),
+        None,
     );
     let mempool = manager.add_subsystem_with_custom_eventloop("wallet-cli-test-mempool", {
         move |call, shutdn| mempool.run(call, shutdn)
@@ -236,6 +239,7 @@ async fn start_node(chain_config: Arc<ChainConfig>) -> (subsystem::Manager, Sock
         mempool.clone(),
         Default::default(),
         peerdb_storage,
+        p2p::node_key::NodeKeypair::generate(),
     )
     .unwrap();
     let p2p = manager.add_subsystem_with_custom_eventloop("p2p", {
@@ -21,28 +21,38 @@ mod sync;
 const NORMAL_DELAY: Duration = Duration::from_secs(1);
 const ERROR_DELAY: Duration = Duration::from_secs(10);
 
+/// Number of blocks new transactions should get confirmed within
+const DEFAULT_CONFIRMATION_TARGET: usize = 6;
+
+/// How sure (in percent) the fee estimate should be that the confirmation target is met
+const FEE_ESTIMATE_CONFIDENCE: u8 = 85;
+
 use std::{
     collections::{BTreeMap, BTreeSet},
+    num::NonZeroU8,
     path::{Path, PathBuf},
     sync::Arc,
     time::Duration,
 };
 
+use mempool::FeeRate;
 use mempool_types::TxStatus;
 use utils::tap_error_log::LogError;
 
 use common::{
     address::Address,
     chain::{
+        classic_multisig::ClassicMultisigChallenge,
         tokens::{Metadata, TokenId, TokenIssuance},
-        Block, ChainConfig, GenBlock, PoolId, SignedTransaction, Transaction, TxOutput,
-        UtxoOutPoint,
+        AccountType, Block, ChainConfig, DelegationId, GenBlock, PoolId, SignedTransaction,
+        Transaction, TxOutput, UtxoOutPoint,
     },
     primitives::{id::WithId, Amount, BlockHeight, Id, Idable},
 };
 use consensus::GenerateBlockInputData;
 use crypto::{
     key::{
+        extended::ExtendedPublicKey,
         hdkd::{child_number::ChildNumber, u31::U31},
         PublicKey,
     },
@@ -62,11 +72,11 @@ use wallet::{
     wallet_events::WalletEvents,
     DefaultWallet,
 };
-use wallet_types::BlockInfo;
 pub use wallet_types::{
     account_info::DEFAULT_ACCOUNT_INDEX,
-    utxo_types::{UtxoState, UtxoStates, UtxoType, UtxoTypes},
+    utxo_types::{UtxoMark, UtxoState, UtxoStates, UtxoType, UtxoTypes},
 };
+use wallet_types::{partially_signed_transaction::PartiallySignedTransaction, BlockInfo};
 
 #[derive(thiserror::Error, Debug)]
 pub enum ControllerError<T: NodeInterface> {
@@ -80,6 +90,8 @@ pub enum ControllerError<T: NodeInterface> {
     WalletFileError(PathBuf, String),
     #[error("Wallet error: {0}")]
     WalletError(wallet::wallet::WalletError),
+    #[error("Delegation {0} balance {1:?} is less than the requested amount {2:?}")]
+    NotEnoughDelegationBalance(DelegationId, Amount, Amount),
 }
 
 pub struct Controller<T, W> {
@@ -146,6 +158,32 @@ impl<T: NodeInterface + Clone + Send + Sync + 'static, W: WalletEvents> Controll
         Ok(wallet)
     }
 
+    /// Create a watch-only wallet that tracks the account with the provided extended public key
+    pub fn create_watch_only_wallet(
+        chain_config: Arc<ChainConfig>,
+        file_path: impl AsRef<Path>,
+        account_public_key: ExtendedPublicKey,
+    ) -> Result<DefaultWallet, ControllerError<T>> {
+        utils::ensure!(
+            !file_path.as_ref().exists(),
+            ControllerError::WalletFileError(
+                file_path.as_ref().to_owned(),
+                "File already exists".to_owned()
+            )
+        );
+
+        let db = wallet::wallet::open_or_create_wallet_file(file_path)
+            .map_err(ControllerError::WalletError)?;
+        let wallet = wallet::Wallet::new_watch_only_wallet(
+            Arc::clone(&chain_config),
+            db,
+            account_public_key,
+        )
+        .map_err(ControllerError::WalletError)?;
+
+        Ok(wallet)
+    }
+
     pub fn open_wallet(
         chain_config: Arc<ChainConfig>,
         file_path: impl AsRef<Path>,
@@ -249,6 +287,90 @@ impl<T: NodeInterface + Clone + Send + Sync + 'static, W: WalletEvents> Controll
             .map_err(ControllerError::WalletError)
     }
 
+    /// Get the fee rate the node estimates for confirmation within `target_blocks` blocks.
+    /// Falls back to the fee rate of the top `in_top_x_mb` MB of the mempool if the node has
+    /// not seen enough confirmed transactions yet.
+    async fn fee_rate_for_target(
+        &self,
+        target_blocks: usize,
+        in_top_x_mb: usize,
+    ) -> Result<FeeRate, ControllerError<T>> {
+        let estimate = self
+            .rpc_client
+            .mempool_estimate_fee_rate(target_blocks, FEE_ESTIMATE_CONFIDENCE)
+            .await
+            .map_err(ControllerError::NodeCallError)?;
+
+        match estimate {
+            Some(fee_rate) => Ok(fee_rate),
+            None => self
+                .rpc_client
+                .mempool_get_fee_rate(in_top_x_mb)
+                .await
+                .map_err(ControllerError::NodeCallError),
+        }
+    }
+
+    /// Submit a transaction that replaces the pending transaction, paying the fee of the fee
+    /// rate or of the node fee rate estimate if no fee rate is provided.
+    /// The replaced transaction is abandoned only if the node accepts the replacement, which
+    /// requires the node mempool to have replace-by-fee enabled.
+    pub async fn bump_transaction_fee(
+        &mut self,
+        account_index: U31,
+        tx_id: Id<Transaction>,
+        fee_rate: Option<FeeRate>,
+    ) -> Result<TxStatus, ControllerError<T>> {
+        let new_fee_rate = match fee_rate {
+            Some(fee_rate) => fee_rate,
+            None => self.fee_rate_for_target(1, 1).await?,
+        };
+
+        let consolidate_fee_rate = new_fee_rate;
+
+        let tx = self
+            .wallet
+            .create_replacement_transaction(
+                account_index,
+                tx_id,
+                new_fee_rate,
+                consolidate_fee_rate,
+            )
+            .map_err(ControllerError::WalletError)?;
+        let status = self
+            .rpc_client
+            .submit_transaction(tx.clone())
+            .await
+            .map_err(ControllerError::NodeCallError)?;
+        self.wallet
+            .add_replacement_transaction(&mut self.wallet_events, account_index, tx_id, tx)
+            .map_err(ControllerError::WalletError)?;
+        Ok(status)
+    }
+
+    /// Create a child-pays-for-parent transaction for the pending transaction, paying the fee
+    /// of the fee rate or of the node fee rate estimate if no fee rate is provided
+    pub async fn create_child_pays_for_parent_transaction(
+        &mut self,
+        account_index: U31,
+        parent_tx_id: Id<Transaction>,
+        fee_rate: Option<FeeRate>,
+    ) -> Result<SignedTransaction, ControllerError<T>> {
+        let fee_rate = match fee_rate {
+            Some(fee_rate) => fee_rate,
+            None => self.fee_rate_for_target(1, 1).await?,
+        };
+
+        self.wallet
+            .create_child_pays_for_parent_transaction(
+                &mut self.wallet_events,
+                account_index,
+                parent_tx_id,
+                fee_rate,
+            )
+            .map_err(ControllerError::WalletError)
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub async fn issue_new_token(
         &mut self,
@@ -259,11 +381,7 @@ impl<T: NodeInterface + Clone + Send + Sync + 'static, W: WalletEvents> Controll
         number_of_decimals: u8,
         metadata_uri: Vec<u8>,
     ) -> Result<(TokenId, TxStatus), ControllerError<T>> {
-        let current_fee_rate = self
-            .rpc_client
-            .mempool_get_fee_rate(5)
-            .await
-            .map_err(ControllerError::NodeCallError)?;
+        let current_fee_rate = self.fee_rate_for_target(DEFAULT_CONFIRMATION_TARGET, 5).await?;
 
         let consolidate_fee_rate = current_fee_rate;
         let (token_id, tx) = self
@@ -295,11 +413,7 @@ impl<T: NodeInterface + Clone + Send + Sync + 'static, W: WalletEvents> Controll
         address: Address,
         metadata: Metadata,
     ) -> Result<(TokenId, TxStatus), ControllerError<T>> {
-        let current_fee_rate = self
-            .rpc_client
-            .mempool_get_fee_rate(5)
-            .await
-            .map_err(ControllerError::NodeCallError)?;
+        let current_fee_rate = self.fee_rate_for_target(DEFAULT_CONFIRMATION_TARGET, 5).await?;
 
         let consolidate_fee_rate = current_fee_rate;
         let (token_id, tx) = self
@@ -327,12 +441,72 @@ impl<T: NodeInterface + Clone + Send + Sync + 'static, W: WalletEvents> Controll
         self.wallet.get_new_address(account_index).map_err(ControllerError::WalletError)
     }
 
+    pub fn get_account_public_key(
+        &self,
+        account_index: U31,
+    ) -> Result<ExtendedPublicKey, ControllerError<T>> {
+        self.wallet
+            .get_account_public_key(account_index)
+            .cloned()
+            .map_err(ControllerError::WalletError)
+    }
+
     pub fn new_public_key(&mut self, account_index: U31) -> Result<PublicKey, ControllerError<T>> {
         self.wallet
             .get_new_public_key(account_index)
             .map_err(ControllerError::WalletError)
     }
 
+    pub fn add_multisig_challenge(
+        &mut self,
+        account_index: U31,
+        min_required_signatures: NonZeroU8,
+        public_keys: Vec<PublicKey>,
+    ) -> Result<Address, ControllerError<T>> {
+        self.wallet
+            .add_multisig_challenge(account_index, min_required_signatures, public_keys)
+            .map_err(ControllerError::WalletError)
+    }
+
+    pub fn get_multisig_challenges(
+        &self,
+        account_index: U31,
+    ) -> Result<Vec<(Address, ClassicMultisigChallenge)>, ControllerError<T>> {
+        self.wallet
+            .get_multisig_challenges(account_index)
+            .map_err(ControllerError::WalletError)
+    }
+
+    /// Freeze or unfreeze the UTXO, frozen UTXOs are not selected automatically as inputs
+    pub fn set_utxo_frozen(
+        &mut self,
+        account_index: U31,
+        outpoint: UtxoOutPoint,
+        frozen: bool,
+    ) -> Result<(), ControllerError<T>> {
+        self.wallet
+            .set_utxo_frozen(account_index, outpoint, frozen)
+            .map_err(ControllerError::WalletError)
+    }
+
+    pub fn set_utxo_label(
+        &mut self,
+        account_index: U31,
+        outpoint: UtxoOutPoint,
+        label: Option<String>,
+    ) -> Result<(), ControllerError<T>> {
+        self.wallet
+            .set_utxo_label(account_index, outpoint, label)
+            .map_err(ControllerError::WalletError)
+    }
+
+    pub fn get_utxo_marks(
+        &self,
+        account_index: U31,
+    ) -> Result<BTreeMap<UtxoOutPoint, UtxoMark>, ControllerError<T>> {
+        self.wallet.get_utxo_marks(account_index).map_err(ControllerError::WalletError)
+    }
+
     async fn get_pool_info(
         &self,
         pool_id: PoolId,
@@ -367,6 +541,51 @@ impl<T: NodeInterface + Clone + Send + Sync + 'static, W: WalletEvents> Controll
         tasks.try_collect().await
     }
 
+    async fn get_delegation_balance(
+        &self,
+        delegation_id: DelegationId,
+    ) -> Result<Amount, ControllerError<T>> {
+        self.rpc_client
+            .get_stake_delegation_balance(delegation_id)
+            .await
+            .map_err(ControllerError::NodeCallError)
+            .and_then(|balance| {
+                balance.ok_or(ControllerError::SyncError(format!(
+                    "Delegation id {} from wallet not found in node",
+                    delegation_id
+                )))
+            })
+            .log_err()
+    }
+
+    /// Returns the delegations owned by the account together with their pool and balance.
+    /// Delegations the node doesn't know about (for example, removed after the pool was
+    /// decommissioned and the delegation was fully withdrawn) are skipped.
+    pub async fn get_delegations(
+        &self,
+        account_index: U31,
+    ) -> Result<Vec<(DelegationId, PoolId, Amount)>, ControllerError<T>> {
+        let delegations: Vec<_> = self
+            .wallet
+            .get_delegations(account_index)
+            .map_err(ControllerError::WalletError)?
+            .map(|(delegation_id, data)| (*delegation_id, data.pool_id))
+            .collect();
+
+        let mut result = Vec::with_capacity(delegations.len());
+        for (delegation_id, pool_id) in delegations {
+            let balance_opt = self
+                .rpc_client
+                .get_stake_delegation_balance(delegation_id)
+                .await
+                .map_err(ControllerError::NodeCallError)?;
+            if let Some(balance) = balance_opt {
+                result.push((delegation_id, pool_id, balance));
+            }
+        }
+        Ok(result)
+    }
+
     pub fn get_vrf_public_key(
         &mut self,
         account_index: U31,
@@ -381,20 +600,97 @@ impl<T: NodeInterface + Clone + Send + Sync + 'static, W: WalletEvents> Controll
         account_index: U31,
         address: Address,
         amount: Amount,
+        replaceable: bool,
     ) -> Result<SignedTransaction, ControllerError<T>> {
         let output = make_address_output(self.chain_config.as_ref(), address, amount)
             .map_err(ControllerError::WalletError)?;
-        let current_fee_rate = self
-            .rpc_client
-            .mempool_get_fee_rate(5)
-            .await
-            .map_err(ControllerError::NodeCallError)?;
+        let current_fee_rate = self.fee_rate_for_target(DEFAULT_CONFIRMATION_TARGET, 5).await?;
 
         let consolidate_fee_rate = current_fee_rate;
 
+        if replaceable {
+            self.wallet.create_replaceable_transaction_to_addresses(
+                &mut self.wallet_events,
+                account_index,
+                [output],
+                current_fee_rate,
+                consolidate_fee_rate,
+            )
+        } else {
+            self.wallet.create_transaction_to_addresses(
+                &mut self.wallet_events,
+                account_index,
+                [output],
+                current_fee_rate,
+                consolidate_fee_rate,
+            )
+        }
+        .map_err(ControllerError::WalletError)
+    }
+
+    /// Send the amount to the address spending exactly the provided UTXOs,
+    /// the change is sent back to the account
+    pub async fn send_to_address_from_utxos(
+        &mut self,
+        account_index: U31,
+        address: Address,
+        amount: Amount,
+        utxos: BTreeSet<UtxoOutPoint>,
+    ) -> Result<SignedTransaction, ControllerError<T>> {
+        let output = make_address_output(self.chain_config.as_ref(), address, amount)
+            .map_err(ControllerError::WalletError)?;
+        let current_fee_rate = self.fee_rate_for_target(DEFAULT_CONFIRMATION_TARGET, 5).await?;
+
         self.wallet
-            .create_transaction_to_addresses(
+            .create_transaction_to_addresses_from_utxos(
                 &mut self.wallet_events,
+                account_index,
+                [output],
+                utxos,
+                current_fee_rate,
+            )
+            .map_err(ControllerError::WalletError)
+    }
+
+    /// Create a transaction that sends the amount to the address without signing it
+    pub async fn create_transaction_from_multisig(
+        &mut self,
+        account_index: U31,
+        multisig_address: Address,
+        address: Address,
+        amount: Amount,
+    ) -> Result<PartiallySignedTransaction, ControllerError<T>> {
+        let output = make_address_output(self.chain_config.as_ref(), address, amount)
+            .map_err(ControllerError::WalletError)?;
+        let current_fee_rate = self.fee_rate_for_target(DEFAULT_CONFIRMATION_TARGET, 5).await?;
+
+        let consolidate_fee_rate = current_fee_rate;
+
+        self.wallet
+            .create_transaction_from_multisig(
+                account_index,
+                multisig_address,
+                [output],
+                current_fee_rate,
+                consolidate_fee_rate,
+            )
+            .map_err(ControllerError::WalletError)
+    }
+
+    pub async fn create_unsigned_transaction(
+        &mut self,
+        account_index: U31,
+        address: Address,
+        amount: Amount,
+    ) -> Result<PartiallySignedTransaction, ControllerError<T>> {
+        let output = make_address_output(self.chain_config.as_ref(), address, amount)
+            .map_err(ControllerError::WalletError)?;
+        let current_fee_rate = self.fee_rate_for_target(DEFAULT_CONFIRMATION_TARGET, 5).await?;
+
+        let consolidate_fee_rate = current_fee_rate;
+
+        self.wallet
+            .create_unsigned_transaction_to_addresses(
                 account_index,
                 [output],
                 current_fee_rate,
@@ -410,11 +706,7 @@ impl<T: NodeInterface + Clone + Send + Sync + 'static, W: WalletEvents> Controll
         address: Address,
         amount: Amount,
     ) -> Result<SignedTransaction, ControllerError<T>> {
-        let current_fee_rate = self
-            .rpc_client
-            .mempool_get_fee_rate(5)
-            .await
-            .map_err(ControllerError::NodeCallError)?;
+        let current_fee_rate = self.fee_rate_for_target(DEFAULT_CONFIRMATION_TARGET, 5).await?;
 
         let consolidate_fee_rate = current_fee_rate;
         let output =
@@ -437,11 +729,7 @@ impl<T: NodeInterface + Clone + Send + Sync + 'static, W: WalletEvents> Controll
         amount: Amount,
         decomission_key: Option<PublicKey>,
     ) -> Result<SignedTransaction, ControllerError<T>> {
-        let current_fee_rate = self
-            .rpc_client
-            .mempool_get_fee_rate(5)
-            .await
-            .map_err(ControllerError::NodeCallError)?;
+        let current_fee_rate = self.fee_rate_for_target(DEFAULT_CONFIRMATION_TARGET, 5).await?;
 
         let consolidate_fee_rate = current_fee_rate;
 
@@ -457,6 +745,142 @@ impl<T: NodeInterface + Clone + Send + Sync + 'static, W: WalletEvents> Controll
             .map_err(ControllerError::WalletError)
     }
 
+    pub async fn create_delegation(
+        &mut self,
+        account_index: U31,
+        address: Address,
+        pool_id: PoolId,
+    ) -> Result<(DelegationId, SignedTransaction), ControllerError<T>> {
+        let current_fee_rate = self.fee_rate_for_target(DEFAULT_CONFIRMATION_TARGET, 5).await?;
+
+        let consolidate_fee_rate = current_fee_rate;
+
+        self.wallet
+            .create_delegation(
+                &mut self.wallet_events,
+                account_index,
+                address,
+                pool_id,
+                current_fee_rate,
+                consolidate_fee_rate,
+            )
+            .map_err(ControllerError::WalletError)
+    }
+
+    pub async fn delegate_staking(
+        &mut self,
+        account_index: U31,
+        amount: Amount,
+        delegation_id: DelegationId,
+    ) -> Result<SignedTransaction, ControllerError<T>> {
+        let current_fee_rate = self.fee_rate_for_target(DEFAULT_CONFIRMATION_TARGET, 5).await?;
+
+        let consolidate_fee_rate = current_fee_rate;
+
+        self.wallet
+            .delegate_staking(
+                &mut self.wallet_events,
+                account_index,
+                amount,
+                delegation_id,
+                current_fee_rate,
+                consolidate_fee_rate,
+            )
+            .map_err(ControllerError::WalletError)
+    }
+
+    pub async fn withdraw_from_delegation(
+        &mut self,
+        account_index: U31,
+        address: Address,
+        amount: Amount,
+        delegation_id: DelegationId,
+    ) -> Result<SignedTransaction, ControllerError<T>> {
+        let balance = self.get_delegation_balance(delegation_id).await?;
+        utils::ensure!(
+            amount <= balance,
+            ControllerError::NotEnoughDelegationBalance(delegation_id, balance, amount)
+        );
+
+        let confirmed_nonce = self
+            .rpc_client
+            .get_account_nonce_count(AccountType::Delegation(delegation_id))
+            .await
+            .map_err(ControllerError::NodeCallError)?;
+
+        let current_fee_rate = self.fee_rate_for_target(DEFAULT_CONFIRMATION_TARGET, 5).await?;
+
+        self.wallet
+            .withdraw_from_delegation(
+                &mut self.wallet_events,
+                account_index,
+                address,
+                amount,
+                delegation_id,
+                confirmed_nonce,
+                current_fee_rate,
+            )
+            .map_err(ControllerError::WalletError)
+    }
+
+    async fn get_pool_pledge(&self, pool_id: PoolId) -> Result<Amount, ControllerError<T>> {
+        self.rpc_client
+            .get_stake_pool_pledge(pool_id)
+            .await
+            .map_err(ControllerError::NodeCallError)
+            .and_then(|pledge| {
+                pledge.ok_or(ControllerError::SyncError(format!(
+                    "Pool id {} from wallet not found in node",
+                    pool_id
+                )))
+            })
+            .log_err()
+    }
+
+    pub async fn decommission_stake_pool(
+        &mut self,
+        account_index: U31,
+        pool_id: PoolId,
+    ) -> Result<SignedTransaction, ControllerError<T>> {
+        let pool_balance = self.get_pool_pledge(pool_id).await?;
+
+        let current_fee_rate = self.fee_rate_for_target(DEFAULT_CONFIRMATION_TARGET, 5).await?;
+
+        self.wallet
+            .decommission_stake_pool(
+                &mut self.wallet_events,
+                account_index,
+                pool_id,
+                pool_balance,
+                current_fee_rate,
+            )
+            .map_err(ControllerError::WalletError)
+    }
+
+    pub async fn decommission_stake_pool_request(
+        &mut self,
+        account_index: U31,
+        pool_id: PoolId,
+    ) -> Result<PartiallySignedTransaction, ControllerError<T>> {
+        let pool_balance = self.get_pool_pledge(pool_id).await?;
+
+        let current_fee_rate = self.fee_rate_for_target(DEFAULT_CONFIRMATION_TARGET, 5).await?;
+
+        self.wallet
+            .decommission_stake_pool_request(account_index, pool_id, pool_balance, current_fee_rate)
+            .map_err(ControllerError::WalletError)
+    }
+
+    pub fn sign_raw_transaction(
+        &mut self,
+        account_index: U31,
+        ptx: PartiallySignedTransaction,
+    ) -> Result<PartiallySignedTransaction, ControllerError<T>> {
+        self.wallet
+            .sign_raw_transaction(account_index, ptx)
+            .map_err(ControllerError::WalletError)
+    }
+
     pub async fn generate_block(
         &mut self,
         account_index: U31,
@@ -585,39 +1009,43 @@ impl<T: NodeInterface + Clone + Send + Sync + 'static, W: WalletEvents> Controll
         Ok(())
     }
 
-    /// Synchronize the wallet in the background from the node's blockchain.
-    /// Try staking new blocks if staking was started.
-    pub async fn run(&mut self) {
-        loop {
-            let sync_res = self.sync_once().await;
+    /// Synchronize the wallet once and try staking new blocks if staking was started.
+    /// Returns the delay to wait before the next call.
+    pub async fn run_once(&mut self) -> Duration {
+        let sync_res = self.sync_once().await;
 
-            if let Err(e) = sync_res {
-                log::error!("Wallet sync error: {e}");
-                tokio::time::sleep(ERROR_DELAY).await;
-                continue;
-            }
-
-            // TODO: Try to remove the `clone` call
-            for account_index in self.staking_started.clone().iter() {
-                let generate_res = self.generate_block(*account_index, None).await;
+        if let Err(e) = sync_res {
+            log::error!("Wallet sync error: {e}");
+            return ERROR_DELAY;
+        }
 
-                if let Ok(block) = generate_res {
-                    log::info!(
-                        "New block generated successfully, block id: {}",
-                        block.get_id()
-                    );
+        // TODO: Try to remove the `clone` call
+        for account_index in self.staking_started.clone().iter() {
+            let generate_res = self.generate_block(*account_index, None).await;
 
-                    let submit_res = self.rpc_client.submit_block(block).await;
-                    if let Err(e) = submit_res {
-                        log::error!("Block submit failed: {e}");
-                        tokio::time::sleep(ERROR_DELAY).await;
-                    }
+            if let Ok(block) = generate_res {
+                log::info!(
+                    "New block generated successfully, block id: {}",
+                    block.get_id()
+                );
 
-                    continue;
+                let submit_res = self.rpc_client.submit_block(block).await;
+                if let Err(e) = submit_res {
+                    log::error!("Block submit failed: {e}");
+                    return ERROR_DELAY;
                 }
             }
+        }
+
+        NORMAL_DELAY
+    }
 
-            tokio::time::sleep(NORMAL_DELAY).await;
+    /// Synchronize the wallet in the background from the node's blockchain.
+    /// Try staking new blocks if staking was started.
+    pub async fn run(&mut self) {
+        loop {
+            let delay = self.run_once().await;
+            tokio::time::sleep(delay).await;
         }
     }
 }
@@ -21,7 +21,10 @@ use std::{
 use chainstate::ChainInfo;
 use chainstate_test_framework::TestFramework;
 use common::{
-    chain::{PoolId, SignedTransaction},
+    chain::{
+        tokens::{RPCTokenInfo, TokenId},
+        AccountNonce, AccountType, DelegationId, PoolId, SignedTransaction,
+    },
     primitives::Amount,
 };
 use consensus::GenerateBlockInputData;
@@ -172,6 +175,27 @@ impl NodeInterface for MockNode {
     ) -> Result<Option<Amount>, Self::Error> {
         unreachable!()
     }
+    async fn get_stake_pool_pledge(&self, _pool_id: PoolId) -> Result<Option<Amount>, Self::Error> {
+        unreachable!()
+    }
+    async fn get_stake_delegation_balance(
+        &self,
+        _delegation_id: DelegationId,
+    ) -> Result<Option<Amount>, Self::Error> {
+        unreachable!()
+    }
+    async fn get_account_nonce_count(
+        &self,
+        _account: AccountType,
+    ) -> Result<Option<AccountNonce>, Self::Error> {
+        unreachable!()
+    }
+    async fn get_token_info(
+        &self,
+        _token_id: TokenId,
+    ) -> Result<Option<RPCTokenInfo>, Self::Error> {
+        unreachable!()
+    }
 
     async fn generate_block(
         &self,
@@ -216,6 +240,14 @@ impl NodeInterface for MockNode {
     async fn mempool_get_fee_rate(&self, _in_top_x_mb: usize) -> Result<FeeRate, Self::Error> {
         Ok(FeeRate::new(Amount::ZERO))
     }
+
+    async fn mempool_estimate_fee_rate(
+        &self,
+        _target_blocks: usize,
+        _confidence_percent: u8,
+    ) -> Result<Option<FeeRate>, Self::Error> {
+        Ok(None)
+    }
 }
 
 fn create_chain(node: &MockNode, rng: &mut (impl Rng + CryptoRng), parent: u64, count: usize) {
@@ -16,7 +16,10 @@
 use blockprod::{BlockProductionError, BlockProductionHandle};
 use chainstate::{BlockSource, ChainInfo, ChainstateError, ChainstateHandle};
 use common::{
-    chain::{Block, GenBlock, PoolId, SignedTransaction},
+    chain::{
+        tokens::{RPCTokenInfo, TokenId},
+        AccountNonce, AccountType, Block, DelegationId, GenBlock, PoolId, SignedTransaction,
+    },
     primitives::{Amount, BlockHeight, Id},
 };
 use consensus::GenerateBlockInputData;
@@ -134,6 +137,45 @@ impl NodeInterface for WalletHandlesClient {
         Ok(result)
     }
 
+    async fn get_stake_pool_pledge(&self, pool_id: PoolId) -> Result<Option<Amount>, Self::Error> {
+        let result = self
+            .chainstate
+            .call(move |this| this.get_stake_pool_data(pool_id))
+            .await??
+            .map(|pool_data| pool_data.pledge_amount());
+        Ok(result)
+    }
+
+    async fn get_stake_delegation_balance(
+        &self,
+        delegation_id: DelegationId,
+    ) -> Result<Option<Amount>, Self::Error> {
+        let result = self
+            .chainstate
+            .call(move |this| this.get_stake_delegation_balance(delegation_id))
+            .await??;
+        Ok(result)
+    }
+
+    async fn get_account_nonce_count(
+        &self,
+        account: AccountType,
+    ) -> Result<Option<AccountNonce>, Self::Error> {
+        let result = self
+            .chainstate
+            .call(move |this| this.get_account_nonce_count(account))
+            .await??;
+        Ok(result)
+    }
+
+    async fn get_token_info(&self, token_id: TokenId) -> Result<Option<RPCTokenInfo>, Self::Error> {
+        let result = self
+            .chainstate
+            .call(move |this| this.get_token_info_for_rpc(token_id))
+            .await??;
+        Ok(result)
+    }
+
     async fn generate_block(
         &self,
         input_data: GenerateBlockInputData,
@@ -199,4 +241,16 @@ impl NodeInterface for WalletHandlesClient {
         let res = self.mempool.call(move |this| this.get_fee_rate(in_top_x_mb)).await??;
         Ok(res)
     }
+
+    async fn mempool_estimate_fee_rate(
+        &self,
+        target_blocks: usize,
+        confidence_percent: u8,
+    ) -> Result<Option<FeeRate>, Self::Error> {
+        let res = self
+            .mempool
+            .call(move |this| this.estimate_fee_rate(target_blocks, confidence_percent))
+            .await??;
+        Ok(res)
+    }
 }
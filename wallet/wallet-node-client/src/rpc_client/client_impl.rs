@@ -16,7 +16,10 @@
 use blockprod::rpc::BlockProductionRpcClient;
 use chainstate::{rpc::ChainstateRpcClient, ChainInfo};
 use common::{
-    chain::{Block, GenBlock, PoolId, SignedTransaction},
+    chain::{
+        tokens::{RPCTokenInfo, TokenId},
+        AccountNonce, AccountType, Block, DelegationId, GenBlock, PoolId, SignedTransaction,
+    },
     primitives::{Amount, BlockHeight, Id},
 };
 use consensus::GenerateBlockInputData;
@@ -87,6 +90,36 @@ impl NodeInterface for NodeRpcClient {
             .map_err(NodeRpcError::ResponseError)
     }
 
+    async fn get_stake_pool_pledge(&self, pool_id: PoolId) -> Result<Option<Amount>, Self::Error> {
+        ChainstateRpcClient::stake_pool_pledge(&self.http_client, pool_id)
+            .await
+            .map_err(NodeRpcError::ResponseError)
+    }
+
+    async fn get_stake_delegation_balance(
+        &self,
+        delegation_id: DelegationId,
+    ) -> Result<Option<Amount>, Self::Error> {
+        ChainstateRpcClient::stake_delegation_balance(&self.http_client, delegation_id)
+            .await
+            .map_err(NodeRpcError::ResponseError)
+    }
+
+    async fn get_account_nonce_count(
+        &self,
+        account: AccountType,
+    ) -> Result<Option<AccountNonce>, Self::Error> {
+        ChainstateRpcClient::account_nonce_count(&self.http_client, account)
+            .await
+            .map_err(NodeRpcError::ResponseError)
+    }
+
+    async fn get_token_info(&self, token_id: TokenId) -> Result<Option<RPCTokenInfo>, Self::Error> {
+        ChainstateRpcClient::token_info(&self.http_client, token_id)
+            .await
+            .map_err(NodeRpcError::ResponseError)
+    }
+
     async fn generate_block(
         &self,
         input_data: GenerateBlockInputData,
@@ -158,4 +191,14 @@ impl NodeInterface for NodeRpcClient {
             .await
             .map_err(NodeRpcError::ResponseError)
     }
+
+    async fn mempool_estimate_fee_rate(
+        &self,
+        target_blocks: usize,
+        confidence_percent: u8,
+    ) -> Result<Option<FeeRate>, Self::Error> {
+        MempoolRpcClient::estimate_fee_rate(&self.http_client, target_blocks, confidence_percent)
+            .await
+            .map_err(NodeRpcError::ResponseError)
+    }
 }
@@ -52,6 +52,7 @@ pub async fn start_subsystems(
     let p2p_config = p2p::config::P2pConfig {
         bind_addresses: vec!["127.0.0.1:0".to_owned()],
         socks5_proxy: Default::default(),
+        tor_control_address: Default::default(),
         disable_noise: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: Default::default(),
@@ -88,8 +89,10 @@ pub async fn start_subsystems(
 
     let mempool = mempool::make_mempool(
         Arc::clone(&chain_config),
+        Default::default(),
         chainstate_handle.clone(),
         Default::default(),
+        None,
     );
     let mempool_handle = manager.add_subsystem_with_custom_eventloop("test-mempool", {
         move |call, shutdn| mempool.run(call, shutdn)
@@ -103,6 +106,7 @@ pub async fn start_subsystems(
         mempool_handle.clone(),
         Default::default(),
         peerdb_storage,
+        p2p::node_key::NodeKeypair::generate(),
     )
     .unwrap();
     let p2p_handle = manager.add_subsystem_with_custom_eventloop("test-p2p", {
@@ -0,0 +1,120 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! JSON-RPC server for the wallet, used to run the wallet without a terminal
+
+pub mod config;
+pub mod rpc;
+mod service;
+
+use std::{net::SocketAddr, str::FromStr, sync::Arc};
+
+use ::rpc::{rpc_creds::RpcCreds, RpcAuthData, RpcConfig};
+use common::chain::config::ChainType;
+use utils::{
+    cookie::COOKIE_FILENAME,
+    default_data_dir::{default_data_dir_for_chain, prepare_data_dir},
+};
+
+pub use self::rpc::{WalletRpcClient, WalletRpcServer};
+pub use config::{Network, WalletRpcDaemonArgs};
+pub use service::{WalletHandle, WalletRpcError, WalletService};
+
+// TODO: Use the constants with the node
+const DEFAULT_NODE_RPC_ADDR: &str = "127.0.0.1:3030";
+const DEFAULT_WALLET_RPC_ADDR: &str = "127.0.0.1:3034";
+const DEFAULT_WALLET_RPC_WS_ADDR: &str = "127.0.0.1:3035";
+
+const WALLET_DATA_DIR_NAME: &str = "wallet-rpc";
+
+pub async fn run(args: WalletRpcDaemonArgs) -> anyhow::Result<()> {
+    let WalletRpcDaemonArgs {
+        network,
+        wallet_file,
+        node_rpc_address,
+        node_rpc_cookie_file,
+        node_rpc_username,
+        node_rpc_password,
+        rpc_bind_address,
+        rpc_ws_bind_address,
+        rpc_cookie_file,
+        rpc_username,
+        rpc_password,
+    } = args;
+
+    let chain_type: ChainType = network.into();
+    let chain_config = Arc::new(common::chain::config::Builder::new(chain_type).build());
+
+    let node_rpc_address = node_rpc_address.unwrap_or_else(|| {
+        SocketAddr::from_str(DEFAULT_NODE_RPC_ADDR).expect("Address must be correct")
+    });
+    let node_rpc_auth = match (node_rpc_cookie_file, node_rpc_username, node_rpc_password) {
+        (None, None, None) => {
+            let cookie_file_path =
+                default_data_dir_for_chain(chain_type.name()).join(COOKIE_FILENAME);
+            RpcAuthData::Cookie { cookie_file_path }
+        }
+        (Some(cookie_file_path), None, None) => RpcAuthData::Cookie {
+            cookie_file_path: cookie_file_path.into(),
+        },
+        (None, Some(username), Some(password)) => RpcAuthData::Basic { username, password },
+        _ => anyhow::bail!("Invalid node RPC cookie/username/password combination"),
+    };
+    let node_rpc = wallet_controller::make_rpc_client(node_rpc_address, node_rpc_auth).await?;
+
+    let data_dir = prepare_data_dir(
+        || default_data_dir_for_chain(chain_type.name()).join(WALLET_DATA_DIR_NAME),
+        &None,
+    )?;
+
+    let mut wallet_service = WalletService::new(Arc::clone(&chain_config), node_rpc);
+    if let Some(wallet_file) = wallet_file {
+        wallet_service.open_wallet(wallet_file)?;
+    }
+
+    let mut manager = subsystem::Manager::new("wallet-rpc");
+    manager.install_signal_handlers();
+
+    let wallet: WalletHandle = manager
+        .add_subsystem_with_custom_eventloop("wallet", |call_rq, shutdown_rq| {
+            wallet_service.run(call_rq, shutdown_rq)
+        });
+
+    let rpc_config = RpcConfig {
+        http_bind_address: rpc_bind_address
+            .unwrap_or_else(|| {
+                SocketAddr::from_str(DEFAULT_WALLET_RPC_ADDR).expect("Address must be correct")
+            })
+            .into(),
+        http_enabled: true.into(),
+        ws_bind_address: rpc_ws_bind_address
+            .unwrap_or_else(|| {
+                SocketAddr::from_str(DEFAULT_WALLET_RPC_WS_ADDR).expect("Address must be correct")
+            })
+            .into(),
+        ws_enabled: true.into(),
+    };
+    let rpc_creds = RpcCreds::new(&data_dir, rpc_username, rpc_password, rpc_cookie_file)?;
+
+    let rpc = ::rpc::Builder::new(rpc_config, Some(rpc_creds))
+        .register(wallet.clone().into_rpc())
+        .build()
+        .await?;
+    let _rpc = manager.add_subsystem("rpc", rpc);
+
+    manager.main().await;
+
+    Ok(())
+}
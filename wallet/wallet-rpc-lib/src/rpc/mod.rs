@@ -0,0 +1,348 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Wallet RPC interface
+
+pub mod types;
+
+use std::{collections::BTreeMap, path::PathBuf};
+
+use common::{
+    address::Address,
+    chain::{ChainConfig, SignedTransaction},
+    primitives::{Amount, Idable},
+};
+use serialization::hex::HexEncode;
+use wallet::account::Currency;
+use wallet_controller::{UtxoState, UtxoType};
+
+use crate::service::{parse_account_index, WalletHandle, WalletRpcError};
+
+use rpc::Result as RpcResult;
+use types::{
+    coin_amount_to_string, AddressInfo, Balances, BlockInfo, CreatedWallet, NewToken,
+    NewTransaction, NftMetadata, TxList, UtxoInfo,
+};
+
+#[rpc::rpc(server, client, namespace = "wallet")]
+trait WalletRpc {
+    /// Create a new wallet file and open it. A new mnemonic is generated if none is provided.
+    #[method(name = "create_wallet")]
+    async fn create_wallet(
+        &self,
+        path: String,
+        mnemonic: Option<String>,
+    ) -> RpcResult<CreatedWallet>;
+
+    #[method(name = "open_wallet")]
+    async fn open_wallet(&self, path: String) -> RpcResult<()>;
+
+    #[method(name = "close_wallet")]
+    async fn close_wallet(&self) -> RpcResult<()>;
+
+    /// The block the wallet is synchronized to
+    #[method(name = "best_block")]
+    async fn best_block(&self) -> RpcResult<BlockInfo>;
+
+    /// Confirmed balance of the account
+    #[method(name = "get_balance")]
+    async fn get_balance(&self, account_index: u32) -> RpcResult<Balances>;
+
+    /// Confirmed transferable UTXOs of the account
+    #[method(name = "get_utxos")]
+    async fn get_utxos(&self, account_index: u32) -> RpcResult<Vec<UtxoInfo>>;
+
+    #[method(name = "new_address")]
+    async fn new_address(&self, account_index: u32) -> RpcResult<AddressInfo>;
+
+    /// Send coins to the address and submit the transaction to the node
+    #[method(name = "send_coins")]
+    async fn send_coins(
+        &self,
+        account_index: u32,
+        address: String,
+        amount: String,
+    ) -> RpcResult<NewTransaction>;
+
+    #[method(name = "issue_new_token")]
+    async fn issue_new_token(
+        &self,
+        account_index: u32,
+        destination_address: String,
+        token_ticker: String,
+        amount_to_issue: String,
+        number_of_decimals: u8,
+        metadata_uri: String,
+    ) -> RpcResult<NewToken>;
+
+    #[method(name = "issue_new_nft")]
+    async fn issue_new_nft(
+        &self,
+        account_index: u32,
+        destination_address: String,
+        metadata: NftMetadata,
+    ) -> RpcResult<NewToken>;
+
+    #[method(name = "start_staking")]
+    async fn start_staking(&self, account_index: u32) -> RpcResult<()>;
+
+    #[method(name = "stop_staking")]
+    async fn stop_staking(&self, account_index: u32) -> RpcResult<()>;
+
+    #[method(name = "list_transactions")]
+    async fn list_transactions(
+        &self,
+        account_index: u32,
+        skip: usize,
+        count: usize,
+    ) -> RpcResult<TxList>;
+}
+
+fn parse_address(chain_config: &ChainConfig, address: &str) -> Result<Address, WalletRpcError> {
+    Address::from_str(chain_config, address)
+        .map_err(|e| WalletRpcError::InvalidAddress(format!("{address}: {e}")))
+}
+
+fn parse_amount(value: &str, decimals: u8) -> Result<Amount, WalletRpcError> {
+    Amount::from_fixedpoint_str(value, decimals)
+        .ok_or_else(|| WalletRpcError::InvalidAmount(value.to_owned()))
+}
+
+#[async_trait::async_trait]
+impl WalletRpcServer for WalletHandle {
+    async fn create_wallet(
+        &self,
+        path: String,
+        mnemonic: Option<String>,
+    ) -> RpcResult<CreatedWallet> {
+        let res = self
+            .call_mut(move |this| this.create_wallet(PathBuf::from(path), mnemonic))
+            .await
+            .map(|res| res.map(|mnemonic| CreatedWallet { mnemonic }));
+        rpc::handle_result(res)
+    }
+
+    async fn open_wallet(&self, path: String) -> RpcResult<()> {
+        rpc::handle_result(self.call_mut(move |this| this.open_wallet(PathBuf::from(path))).await)
+    }
+
+    async fn close_wallet(&self) -> RpcResult<()> {
+        rpc::handle_result(self.call_mut(|this| this.close_wallet()).await)
+    }
+
+    async fn best_block(&self) -> RpcResult<BlockInfo> {
+        rpc::handle_result(
+            self.call_mut(|this| {
+                let (id, height) = this.controller()?.best_block();
+                Ok::<_, WalletRpcError>(BlockInfo { id, height })
+            })
+            .await,
+        )
+    }
+
+    async fn get_balance(&self, account_index: u32) -> RpcResult<Balances> {
+        rpc::handle_result(
+            self.call_async_mut(move |this| {
+                Box::pin(async move {
+                    let account_index = parse_account_index(account_index)?;
+                    let mut balances = this
+                        .controller()?
+                        .get_balance(account_index, UtxoState::Confirmed.into())?;
+
+                    let coins = balances.remove(&Currency::Coin).unwrap_or(Amount::ZERO);
+                    let mut tokens = BTreeMap::new();
+                    for (currency, amount) in balances {
+                        match currency {
+                            Currency::Token(token_id) => {
+                                let decimals = this.token_number_of_decimals(token_id).await?;
+                                tokens.insert(
+                                    token_id.hex_encode(),
+                                    amount.into_fixedpoint_str(decimals),
+                                );
+                            }
+                            Currency::Coin => {}
+                        }
+                    }
+
+                    Ok::<_, WalletRpcError>(Balances {
+                        coins: coin_amount_to_string(this.chain_config(), coins),
+                        tokens,
+                    })
+                })
+            })
+            .await,
+        )
+    }
+
+    async fn get_utxos(&self, account_index: u32) -> RpcResult<Vec<UtxoInfo>> {
+        rpc::handle_result(
+            self.call_mut(move |this| {
+                let account_index = parse_account_index(account_index)?;
+                let utxos = this.controller()?.get_utxos(
+                    account_index,
+                    UtxoType::Transfer | UtxoType::LockThenTransfer,
+                    UtxoState::Confirmed.into(),
+                )?;
+                Ok::<_, WalletRpcError>(
+                    utxos
+                        .into_iter()
+                        .map(|(outpoint, output)| UtxoInfo::new(outpoint, output))
+                        .collect(),
+                )
+            })
+            .await,
+        )
+    }
+
+    async fn new_address(&self, account_index: u32) -> RpcResult<AddressInfo> {
+        rpc::handle_result(
+            self.call_mut(move |this| {
+                let account_index = parse_account_index(account_index)?;
+                let (child_number, address) = this.controller()?.new_address(account_index)?;
+                Ok::<_, WalletRpcError>(AddressInfo::new(child_number, address.get().to_owned()))
+            })
+            .await,
+        )
+    }
+
+    async fn send_coins(
+        &self,
+        account_index: u32,
+        address: String,
+        amount: String,
+    ) -> RpcResult<NewTransaction> {
+        rpc::handle_result(
+            self.call_async_mut(move |this| {
+                Box::pin(async move {
+                    let account_index = parse_account_index(account_index)?;
+                    let address = parse_address(this.chain_config(), &address)?;
+                    let amount = parse_amount(&amount, this.chain_config().coin_decimals())?;
+
+                    let tx: SignedTransaction = this
+                        .controller()?
+                        .send_to_address(account_index, address, amount, false)
+                        .await?;
+                    let tx_id = tx.transaction().get_id();
+                    this.broadcast_transaction(tx).await?;
+
+                    Ok::<_, WalletRpcError>(NewTransaction { tx_id })
+                })
+            })
+            .await,
+        )
+    }
+
+    async fn issue_new_token(
+        &self,
+        account_index: u32,
+        destination_address: String,
+        token_ticker: String,
+        amount_to_issue: String,
+        number_of_decimals: u8,
+        metadata_uri: String,
+    ) -> RpcResult<NewToken> {
+        rpc::handle_result(
+            self.call_async_mut(move |this| {
+                Box::pin(async move {
+                    let account_index = parse_account_index(account_index)?;
+                    let address = parse_address(this.chain_config(), &destination_address)?;
+                    let amount_to_issue = parse_amount(&amount_to_issue, number_of_decimals)?;
+
+                    let (token_id, _tx_status) = this
+                        .controller()?
+                        .issue_new_token(
+                            account_index,
+                            address,
+                            token_ticker.into_bytes(),
+                            amount_to_issue,
+                            number_of_decimals,
+                            metadata_uri.into_bytes(),
+                        )
+                        .await?;
+
+                    Ok::<_, WalletRpcError>(NewToken {
+                        token_id: token_id.hex_encode(),
+                    })
+                })
+            })
+            .await,
+        )
+    }
+
+    async fn issue_new_nft(
+        &self,
+        account_index: u32,
+        destination_address: String,
+        metadata: NftMetadata,
+    ) -> RpcResult<NewToken> {
+        rpc::handle_result(
+            self.call_async_mut(move |this| {
+                Box::pin(async move {
+                    let account_index = parse_account_index(account_index)?;
+                    let address = parse_address(this.chain_config(), &destination_address)?;
+
+                    let (token_id, _tx_status) = this
+                        .controller()?
+                        .issue_new_nft(account_index, address, metadata.into_metadata())
+                        .await?;
+
+                    Ok::<_, WalletRpcError>(NewToken {
+                        token_id: token_id.hex_encode(),
+                    })
+                })
+            })
+            .await,
+        )
+    }
+
+    async fn start_staking(&self, account_index: u32) -> RpcResult<()> {
+        rpc::handle_result(
+            self.call_mut(move |this| {
+                let account_index = parse_account_index(account_index)?;
+                this.controller()?.start_staking(account_index)?;
+                Ok::<_, WalletRpcError>(())
+            })
+            .await,
+        )
+    }
+
+    async fn stop_staking(&self, account_index: u32) -> RpcResult<()> {
+        rpc::handle_result(
+            self.call_mut(move |this| {
+                let account_index = parse_account_index(account_index)?;
+                this.controller()?.stop_staking(account_index)?;
+                Ok::<_, WalletRpcError>(())
+            })
+            .await,
+        )
+    }
+
+    async fn list_transactions(
+        &self,
+        account_index: u32,
+        skip: usize,
+        count: usize,
+    ) -> RpcResult<TxList> {
+        rpc::handle_result(
+            self.call_mut(move |this| {
+                let account_index = parse_account_index(account_index)?;
+                let chain_config = std::sync::Arc::clone(this.chain_config());
+                let list = this.controller()?.get_transaction_list(account_index, skip, count)?;
+                Ok::<_, WalletRpcError>(TxList::new(&chain_config, list))
+            })
+            .await,
+        )
+    }
+}
@@ -0,0 +1,163 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Types used by the wallet RPC interface
+
+use std::collections::BTreeMap;
+
+use common::{
+    chain::{
+        block::timestamp::BlockTimestamp, ChainConfig, GenBlock, Transaction, TxOutput,
+        UtxoOutPoint,
+    },
+    primitives::{Amount, BlockHeight, Id},
+};
+use crypto::key::hdkd::child_number::ChildNumber;
+use serialization::hex_encoded::HexEncoded;
+use wallet::account::transaction_list::{TransactionInfo, TransactionList};
+
+/// Render the amount of coins as a decimal string
+pub fn coin_amount_to_string(chain_config: &ChainConfig, amount: Amount) -> String {
+    amount.into_fixedpoint_str(chain_config.coin_decimals())
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct CreatedWallet {
+    /// The newly generated mnemonic, `None` if the mnemonic was provided by the caller
+    pub mnemonic: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct BlockInfo {
+    pub id: Id<GenBlock>,
+    pub height: BlockHeight,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Balances {
+    pub coins: String,
+    /// Token balances keyed by the hex-encoded token id
+    pub tokens: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct UtxoInfo {
+    pub outpoint: HexEncoded<UtxoOutPoint>,
+    pub output: HexEncoded<TxOutput>,
+}
+
+impl UtxoInfo {
+    pub fn new(outpoint: UtxoOutPoint, output: TxOutput) -> Self {
+        Self {
+            outpoint: outpoint.into(),
+            output: output.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct AddressInfo {
+    pub address: String,
+    pub index: String,
+}
+
+impl AddressInfo {
+    pub fn new(child_number: ChildNumber, address: String) -> Self {
+        Self {
+            address,
+            index: child_number.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct NewTransaction {
+    pub tx_id: Id<Transaction>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct NewToken {
+    /// Hex-encoded id of the issued token
+    pub token_id: String,
+}
+
+/// NFT metadata as it is accepted over RPC, strings are stored as UTF-8 bytes
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct NftMetadata {
+    pub name: String,
+    pub description: String,
+    pub ticker: String,
+    pub media_hash: String,
+    pub creator: Option<HexEncoded<crypto::key::PublicKey>>,
+    pub icon_uri: Option<String>,
+    pub media_uri: Option<String>,
+    pub additional_metadata_uri: Option<String>,
+}
+
+impl NftMetadata {
+    pub fn into_metadata(self) -> common::chain::tokens::Metadata {
+        common::chain::tokens::Metadata {
+            creator: self.creator.map(|public_key| common::chain::tokens::TokenCreator {
+                public_key: public_key.take(),
+            }),
+            name: self.name.into_bytes(),
+            description: self.description.into_bytes(),
+            ticker: self.ticker.into_bytes(),
+            icon_uri: self.icon_uri.map(String::into_bytes).into(),
+            additional_metadata_uri: self.additional_metadata_uri.map(String::into_bytes).into(),
+            media_uri: self.media_uri.map(String::into_bytes).into(),
+            media_hash: self.media_hash.into_bytes(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TxInfo {
+    pub tx_id: Id<Transaction>,
+    pub tx_type: String,
+    /// Transferred coin amount, if known for the transaction type
+    pub amount: Option<String>,
+    pub timestamp: Option<BlockTimestamp>,
+    pub state: String,
+}
+
+impl TxInfo {
+    pub fn new(chain_config: &ChainConfig, info: TransactionInfo) -> Self {
+        Self {
+            tx_id: info.txid,
+            tx_type: info.tx_type.type_name().to_owned(),
+            amount: info.tx_type.amount().map(|amount| coin_amount_to_string(chain_config, amount)),
+            timestamp: info.timestamp,
+            state: info.state.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TxList {
+    pub skip: usize,
+    pub total: usize,
+    pub txs: Vec<TxInfo>,
+}
+
+impl TxList {
+    pub fn new(chain_config: &ChainConfig, list: TransactionList) -> Self {
+        Self {
+            skip: list.skip,
+            total: list.total,
+            txs: list.txs.into_iter().map(|info| TxInfo::new(chain_config, info)).collect(),
+        }
+    }
+}
@@ -0,0 +1,188 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The wallet subsystem that owns the wallet controller
+
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
+use common::chain::{
+    tokens::{RPCTokenInfo, TokenId},
+    ChainConfig, SignedTransaction,
+};
+use crypto::key::hdkd::u31::U31;
+use node_comm::rpc_client::NodeRpcError;
+use subsystem::{CallRequest, ShutdownRequest};
+use wallet::wallet_events::WalletEventsNoOp;
+use wallet_controller::{mnemonic, ControllerError, NodeInterface, NodeRpcClient};
+
+pub type WalletController = wallet_controller::RpcController<WalletEventsNoOp>;
+
+pub type WalletHandle = subsystem::Handle<WalletService>;
+
+#[derive(thiserror::Error, Debug)]
+pub enum WalletRpcError {
+    #[error("Controller error: {0}")]
+    Controller(#[from] ControllerError<NodeRpcClient>),
+    #[error("Node RPC error: {0}")]
+    NodeRpc(#[from] NodeRpcError),
+    #[error("Invalid mnemonic: {0}")]
+    InvalidMnemonic(mnemonic::Error),
+    #[error("Invalid address: {0}")]
+    InvalidAddress(String),
+    #[error("Invalid amount: {0}")]
+    InvalidAmount(String),
+    #[error("Invalid account index: {0}")]
+    InvalidAccountIndex(u32),
+    #[error("Wallet file already open")]
+    WalletFileAlreadyOpen,
+    #[error("Please open or create wallet file first")]
+    NoWallet,
+    #[error("Unknown token id: {0}")]
+    UnknownTokenId(TokenId),
+}
+
+/// Keeps the open wallet (if any) and synchronizes it with the node in the background.
+pub struct WalletService {
+    chain_config: Arc<ChainConfig>,
+    node_rpc: NodeRpcClient,
+    controller: Option<WalletController>,
+}
+
+impl WalletService {
+    pub fn new(chain_config: Arc<ChainConfig>, node_rpc: NodeRpcClient) -> Self {
+        Self {
+            chain_config,
+            node_rpc,
+            controller: None,
+        }
+    }
+
+    pub fn chain_config(&self) -> &Arc<ChainConfig> {
+        &self.chain_config
+    }
+
+    /// Creates a new wallet file from the mnemonic and opens it.
+    ///
+    /// A new mnemonic is generated if none is provided, it is returned in that case.
+    pub fn create_wallet(
+        &mut self,
+        wallet_path: PathBuf,
+        mnemonic: Option<String>,
+    ) -> Result<Option<String>, WalletRpcError> {
+        utils::ensure!(
+            self.controller.is_none(),
+            WalletRpcError::WalletFileAlreadyOpen
+        );
+
+        // TODO: Support other languages
+        let language = mnemonic::Language::English;
+        let (mnemonic, generated) = match mnemonic {
+            Some(mnemonic) => (
+                mnemonic::parse_mnemonic(language, &mnemonic)
+                    .map_err(WalletRpcError::InvalidMnemonic)?,
+                false,
+            ),
+            None => (mnemonic::generate_new_mnemonic(language), true),
+        };
+
+        let wallet = WalletController::create_wallet(
+            Arc::clone(&self.chain_config),
+            wallet_path,
+            mnemonic.clone(),
+            None,
+        )?;
+        self.set_wallet(wallet);
+
+        Ok(generated.then(|| mnemonic.to_string()))
+    }
+
+    pub fn open_wallet(&mut self, wallet_path: PathBuf) -> Result<(), WalletRpcError> {
+        utils::ensure!(
+            self.controller.is_none(),
+            WalletRpcError::WalletFileAlreadyOpen
+        );
+
+        let wallet = WalletController::open_wallet(Arc::clone(&self.chain_config), wallet_path)?;
+        self.set_wallet(wallet);
+
+        Ok(())
+    }
+
+    pub fn close_wallet(&mut self) -> Result<(), WalletRpcError> {
+        utils::ensure!(self.controller.is_some(), WalletRpcError::NoWallet);
+        self.controller = None;
+        Ok(())
+    }
+
+    pub fn controller(&mut self) -> Result<&mut WalletController, WalletRpcError> {
+        self.controller.as_mut().ok_or(WalletRpcError::NoWallet)
+    }
+
+    /// Submits the transaction to the node.
+    pub async fn broadcast_transaction(&self, tx: SignedTransaction) -> Result<(), WalletRpcError> {
+        self.node_rpc.submit_transaction(tx).await?;
+        Ok(())
+    }
+
+    /// Number of decimals of the token, NFTs are indivisible and have none.
+    pub async fn token_number_of_decimals(&self, token_id: TokenId) -> Result<u8, WalletRpcError> {
+        let token_info = self
+            .node_rpc
+            .get_token_info(token_id)
+            .await?
+            .ok_or(WalletRpcError::UnknownTokenId(token_id))?;
+        let decimals = match token_info {
+            RPCTokenInfo::FungibleToken(token_info) => token_info.number_of_decimals,
+            RPCTokenInfo::NonFungibleToken(_) => 0,
+        };
+        Ok(decimals)
+    }
+
+    fn set_wallet(&mut self, wallet: wallet::DefaultWallet) {
+        self.controller = Some(WalletController::new(
+            Arc::clone(&self.chain_config),
+            self.node_rpc.clone(),
+            wallet,
+            WalletEventsNoOp,
+        ));
+    }
+
+    /// Serves the subsystem calls and keeps the open wallet in sync in the meantime.
+    ///
+    /// A sync round is never interrupted by the incoming calls, the calls are served in between
+    /// the rounds and the delay until the next round is kept across them.
+    pub async fn run(mut self, mut call_rq: CallRequest<Self>, mut shutdown_rq: ShutdownRequest) {
+        let next_sync = tokio::time::sleep(Duration::ZERO);
+        tokio::pin!(next_sync);
+
+        loop {
+            tokio::select! {
+                () = shutdown_rq.recv() => break,
+                call = call_rq.recv() => call(&mut self).await,
+                () = &mut next_sync, if self.controller.is_some() => {
+                    let delay = match self.controller.as_mut() {
+                        Some(controller) => controller.run_once().await,
+                        None => Duration::ZERO,
+                    };
+                    next_sync.as_mut().reset(tokio::time::Instant::now() + delay);
+                }
+            }
+        }
+    }
+}
+
+pub fn parse_account_index(account_index: u32) -> Result<U31, WalletRpcError> {
+    U31::from_u32(account_index).ok_or(WalletRpcError::InvalidAccountIndex(account_index))
+}
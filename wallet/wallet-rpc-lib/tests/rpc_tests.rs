@@ -0,0 +1,106 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{net::SocketAddr, sync::Arc};
+
+use chainstate::{
+    make_chainstate, rpc::ChainstateRpcServer, ChainstateConfig,
+    DefaultTransactionVerificationStrategy,
+};
+use common::chain::config::create_unit_test_config;
+use rpc::{new_http_client, RpcAuthData, RpcConfig};
+use wallet_rpc_lib::{WalletHandle, WalletRpcClient, WalletRpcServer, WalletService};
+
+fn local_rpc_config() -> RpcConfig {
+    RpcConfig {
+        http_bind_address: "127.0.0.1:0".parse::<SocketAddr>().unwrap().into(),
+        http_enabled: true.into(),
+        ws_bind_address: "127.0.0.1:0".parse::<SocketAddr>().unwrap().into(),
+        ws_enabled: false.into(),
+    }
+}
+
+#[tokio::test]
+async fn wallet_rpc_round_trip() {
+    let chain_config = Arc::new(create_unit_test_config());
+    let mut manager = subsystem::Manager::new("wallet-rpc-test");
+    let shutdown_trigger = manager.make_shutdown_trigger();
+
+    // A minimal node the wallet can sync from
+    let chainstate = make_chainstate(
+        Arc::clone(&chain_config),
+        ChainstateConfig::new(),
+        chainstate_storage::inmemory::Store::new_empty().unwrap(),
+        DefaultTransactionVerificationStrategy::new(),
+        None,
+        Default::default(),
+    )
+    .unwrap();
+    let chainstate_handle = manager.add_subsystem("test-chainstate", chainstate);
+    let node_rpc = rpc::Builder::new(local_rpc_config(), None)
+        .register(chainstate_handle.clone().into_rpc())
+        .build()
+        .await
+        .unwrap();
+    let node_rpc_address = *node_rpc.http_address().unwrap();
+    let _node_rpc = manager.add_subsystem("test-node-rpc", node_rpc);
+
+    let node_rpc_client = wallet_controller::make_rpc_client(node_rpc_address, RpcAuthData::None)
+        .await
+        .unwrap();
+    let wallet_service = WalletService::new(Arc::clone(&chain_config), node_rpc_client);
+    let wallet: WalletHandle = manager
+        .add_subsystem_with_custom_eventloop("test-wallet", |call_rq, shutdown_rq| {
+            wallet_service.run(call_rq, shutdown_rq)
+        });
+    let wallet_rpc = rpc::Builder::new(local_rpc_config(), None)
+        .register(wallet.into_rpc())
+        .build()
+        .await
+        .unwrap();
+    let wallet_rpc_address = *wallet_rpc.http_address().unwrap();
+    let _wallet_rpc = manager.add_subsystem("test-wallet-rpc", wallet_rpc);
+
+    let manager_task = tokio::spawn(async move { manager.main().await });
+
+    let client =
+        new_http_client(format!("http://{wallet_rpc_address}"), RpcAuthData::None).unwrap();
+
+    // No wallet is open yet
+    assert!(client.get_balance(0).await.is_err());
+
+    let data_dir = tempfile::TempDir::new().unwrap();
+    let wallet_path = data_dir.path().join("wallet.sqlite");
+    let created = client
+        .create_wallet(wallet_path.to_string_lossy().into_owned(), None)
+        .await
+        .unwrap();
+    assert!(created.mnemonic.is_some());
+
+    let address = client.new_address(0).await.unwrap();
+    assert!(!address.address.is_empty());
+
+    let balances = client.get_balance(0).await.unwrap();
+    assert_eq!(balances.coins, "0");
+    assert!(balances.tokens.is_empty());
+
+    assert!(client.list_transactions(0, 0, 10).await.unwrap().txs.is_empty());
+
+    client.close_wallet().await.unwrap();
+    assert!(client.best_block().await.is_err());
+
+    shutdown_trigger.initiate();
+    manager_task.await.unwrap();
+}